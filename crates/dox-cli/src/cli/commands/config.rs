@@ -13,6 +13,10 @@ pub struct ConfigArgs {
     #[arg(long, conflicts_with_all = ["init", "get", "set", "unset", "validate", "show_path", "reset", "edit"])]
     pub list: bool,
 
+    /// --list에서 API 키 등 민감한 값을 가리지 않고 표시
+    #[arg(long, requires = "list")]
+    pub show_secrets: bool,
+
     /// 특정 설정 값 가져오기
     #[arg(long, value_name = "키", conflicts_with_all = ["init", "list", "set", "unset", "validate", "show_path", "reset", "edit"])]
     pub get: Option<String>,
@@ -21,6 +25,10 @@ pub struct ConfigArgs {
     #[arg(long, value_name = "키=값", conflicts_with_all = ["init", "list", "get", "unset", "validate", "show_path", "reset", "edit"])]
     pub set: Option<String>,
 
+    /// --set에서 알 수 없는 설정 키를 사용자 정의 값으로 저장하지 않고 거부
+    #[arg(long, requires = "set")]
+    pub strict: bool,
+
     /// 설정 값 제거
     #[arg(long, value_name = "키", conflicts_with_all = ["init", "list", "get", "set", "validate", "show_path", "reset", "edit"])]
     pub unset: Option<String>,
@@ -42,18 +50,55 @@ pub struct ConfigArgs {
     pub edit: bool,
 }
 
-/// Helper function to load config with optional custom path
-fn load_config_with_path(config_path: Option<&Path>) -> Result<dox_core::utils::config::Config> {
+/// Read the config file that `--set`/`--unset` modify, ignoring environment overrides
+fn read_config_with_path(config_path: Option<&Path>) -> Result<dox_core::utils::config::Config> {
     use dox_core::utils::config::Config;
 
-    if let Some(path) = config_path {
-        Config::load_from(path)
-    } else {
-        Config::load()
+    match config_path {
+        Some(path) => Config::read_from(path),
+        None => Config::read_from(&Config::default_path()?),
+    }
+}
+
+/// Helper function to load config with optional custom path and profile
+pub(crate) fn load_config_with_path(
+    config_path: Option<&Path>,
+    profile: Option<&str>,
+) -> Result<dox_core::utils::config::Config> {
+    use dox_core::utils::config::Config;
+
+    match config_path {
+        Some(path) => Config::load_profile_from(path, profile),
+        None => Config::load_profile_from(&Config::default_path()?, profile),
     }
 }
 
-pub async fn execute(args: ConfigArgs, config_path: Option<&Path>) -> Result<()> {
+/// Entries shown by `--list`, with secrets masked unless `show_secrets` is set
+pub(crate) fn list_entries(
+    config: &dox_core::utils::config::Config,
+    show_secrets: bool,
+) -> Vec<(String, String)> {
+    use dox_core::utils::config::Config;
+
+    config
+        .entries()
+        .into_iter()
+        .map(|(key, value)| {
+            if !show_secrets && Config::is_secret(&key) {
+                let masked = Config::mask_secret(&value);
+                (key, masked)
+            } else {
+                (key, value)
+            }
+        })
+        .collect()
+}
+
+pub async fn execute(
+    args: ConfigArgs,
+    config_path: Option<&Path>,
+    profile: Option<&str>,
+) -> Result<()> {
     use dox_core::utils::{config::Config, ui};
 
     if args.init {
@@ -61,21 +106,31 @@ pub async fn execute(args: ConfigArgs, config_path: Option<&Path>) -> Result<()>
         Config::init()?;
         ui::print_success("설정 파일이 성공적으로 초기화되었습니다");
     } else if args.list {
-        let config = load_config_with_path(config_path)?;
+        let config = load_config_with_path(config_path, profile)?;
         ui::print_header("현재 설정");
 
         // Use colored display if colors are enabled
-        if std::env::var("NO_COLOR").is_ok() || !colored::control::SHOULD_COLORIZE.should_colorize()
-        {
-            println!("{}", config.display());
-        } else {
-            println!("{}", config.display_colored());
+        let colorize = std::env::var("NO_COLOR").is_err()
+            && colored::control::SHOULD_COLORIZE.should_colorize();
+        for (key, value) in list_entries(&config, args.show_secrets) {
+            if colorize {
+                use colored::Colorize;
+                println!("{} = {}", key.green(), value.yellow());
+            } else {
+                println!("{} = {}", key, value);
+            }
         }
     } else if let Some(key) = args.get {
-        let config = load_config_with_path(config_path)?;
+        let config = load_config_with_path(config_path, profile)?;
         match config.get(&key) {
             Some(value) => println!("{}", value),
-            None => ui::print_error(&format!("'{}' 설정 키를 찾을 수 없습니다", key)),
+            None => match Config::suggest_key(&key) {
+                Some(known) => ui::print_error(&format!(
+                    "'{}' 설정 키를 찾을 수 없습니다. '{}'을(를) 의도하셨나요?",
+                    key, known
+                )),
+                None => ui::print_error(&format!("'{}' 설정 키를 찾을 수 없습니다", key)),
+            },
         }
     } else if let Some(key_value) = args.set {
         let parts: Vec<&str> = key_value.splitn(2, '=').collect();
@@ -84,17 +139,27 @@ pub async fn execute(args: ConfigArgs, config_path: Option<&Path>) -> Result<()>
             return Ok(());
         }
 
-        let mut config = load_config_with_path(config_path)?;
-        config.set(parts[0], parts[1])?;
+        let mut config = read_config_with_path(config_path)?;
+        if args.strict {
+            config.set_strict(parts[0], parts[1])?;
+        } else {
+            if let Some(known) = Config::suggest_key(parts[0]) {
+                ui::print_warning(&format!(
+                    "'{}'은(는) 알려진 설정 키가 아니어서 사용자 정의 값으로 저장합니다. '{}'을(를) 의도하셨나요?",
+                    parts[0], known
+                ));
+            }
+            config.set(parts[0], parts[1])?;
+        }
         config.save()?;
         ui::print_success(&format!("{} = {} 설정됨", parts[0], parts[1]));
     } else if let Some(key) = args.unset {
-        let mut config = load_config_with_path(config_path)?;
+        let mut config = read_config_with_path(config_path)?;
         config.unset(&key)?;
         config.save()?;
         ui::print_success(&format!("'{}' 설정 키가 제거되었습니다", key));
     } else if args.validate {
-        match load_config_with_path(config_path) {
+        match load_config_with_path(config_path, profile) {
             Ok(_) => ui::print_success("설정 파일이 유효합니다"),
             Err(err) => {
                 ui::print_error(&format!("설정 파일 유효성 검사 실패: {}", err));
@@ -172,3 +237,31 @@ pub async fn execute(args: ConfigArgs, config_path: Option<&Path>) -> Result<()>
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dox_core::utils::config::{Config, KNOWN_KEYS};
+
+    #[test]
+    fn test_list_entries_masks_secrets() {
+        let mut config = Config::default();
+        config.set("openai.api_key", "sk-1234567890abcdef").unwrap();
+
+        let value = |entries: &[(String, String)], key: &str| {
+            entries
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.clone())
+                .unwrap()
+        };
+
+        let masked = list_entries(&config, false);
+        assert_eq!(masked.len(), KNOWN_KEYS.len());
+        assert_eq!(value(&masked, "openai.api_key"), "sk-12345***");
+        assert_eq!(value(&masked, "global.lang"), "");
+
+        let revealed = list_entries(&config, true);
+        assert_eq!(value(&revealed, "openai.api_key"), "sk-1234567890abcdef");
+    }
+}
@@ -0,0 +1,174 @@
+use super::extract::ExtractFormat;
+use anyhow::Result;
+use clap::Args;
+use dox_document::DocumentType;
+use std::path::{Path, PathBuf};
+
+/// 문서를 다른 형식으로 변환
+///
+/// 입력 형식은 파일 내용으로 판별합니다.
+///
+/// 예시:
+///   # Word 문서를 마크다운으로
+///   dox convert report.docx --to markdown -o report.md
+///
+///   # Excel 시트를 CSV로 (출력 확장자로 형식 추정)
+///   dox convert sales.xlsx -o sales.csv --sheet 매출
+///
+///   # PDF를 텍스트로 표준출력에 출력
+///   dox convert paper.pdf --to text
+#[derive(Args, Debug)]
+pub struct ConvertArgs {
+    /// 변환할 문서 (.docx, .pptx, .xlsx, .pdf, .txt)
+    #[arg(value_name = "파일")]
+    pub input: PathBuf,
+
+    /// 출력 형식 (생략하면 출력 파일 확장자로 판단)
+    #[arg(long, value_enum)]
+    pub to: Option<ExtractFormat>,
+
+    /// 출력 파일 경로 (지정하지 않으면 표준출력)
+    #[arg(short, long, value_name = "파일")]
+    pub output: Option<PathBuf>,
+
+    /// Excel을 CSV로 변환할 시트 (생략하면 첫 번째 시트)
+    #[arg(long, value_name = "시트")]
+    pub sheet: Option<String>,
+}
+
+pub async fn execute(args: ConvertArgs) -> Result<()> {
+    use dox_core::utils::ui;
+
+    if !args.input.is_file() {
+        return Err(anyhow::anyhow!(
+            "입력 파일을 찾을 수 없습니다: {}",
+            args.input.display()
+        ));
+    }
+
+    let format = output_format(args.to, args.output.as_deref())?;
+    let doc_type = DocumentType::detect(&args.input).ok_or_else(|| {
+        anyhow::anyhow!(
+            "지원되지 않는 입력 형식입니다: {} (Markdown에서 문서를 만들려면 dox create를 사용하세요)",
+            args.input.display()
+        )
+    })?;
+    check_supported(&doc_type, format)?;
+
+    let output = convert(&args.input, &doc_type, format, args.sheet.as_deref()).await?;
+
+    match args.output {
+        Some(path) => {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&path, &output)?;
+            ui::print_success(&format!(
+                "변환됨: {} → {}",
+                args.input.display(),
+                path.display()
+            ));
+        }
+        None => print!("{}", output),
+    }
+
+    Ok(())
+}
+
+/// `--to`, or the format matching the output file's extension
+fn output_format(to: Option<ExtractFormat>, output: Option<&Path>) -> Result<ExtractFormat> {
+    if let Some(format) = to {
+        return Ok(format);
+    }
+
+    let extension = output
+        .and_then(|path| path.extension())
+        .and_then(|ext| ext.to_str())
+        .map(str::to_lowercase);
+    match extension.as_deref() {
+        Some("txt") => Ok(ExtractFormat::Text),
+        Some("json") => Ok(ExtractFormat::Json),
+        Some("md" | "markdown") => Ok(ExtractFormat::Markdown),
+        Some("html" | "htm") => Ok(ExtractFormat::Html),
+        Some("rtf") => Ok(ExtractFormat::Rtf),
+        Some("csv") => Ok(ExtractFormat::Csv),
+        _ => Err(anyhow::anyhow!(
+            "출력 형식을 알 수 없습니다. --to로 지정하세요 (text, json, markdown, html, rtf, csv)"
+        )),
+    }
+}
+
+/// Reject pairs that cannot produce meaningful output
+fn check_supported(doc_type: &DocumentType, format: ExtractFormat) -> Result<()> {
+    // Plain text has no tables, so there is nothing to put in a CSV
+    if *doc_type == DocumentType::Text && matches!(format, ExtractFormat::Csv) {
+        return Err(anyhow::anyhow!(
+            "텍스트 파일은 {} 형식으로 변환할 수 없습니다 (표가 없습니다)",
+            format.extension()
+        ));
+    }
+    Ok(())
+}
+
+/// Produce the converted document as a string
+#[cfg_attr(not(feature = "excel"), allow(unused_variables))]
+async fn convert(
+    input: &Path,
+    doc_type: &DocumentType,
+    format: ExtractFormat,
+    sheet: Option<&str>,
+) -> Result<String> {
+    use dox_document::extract::extractors::UniversalExtractor;
+    use dox_document::OutputFormatter;
+
+    // A sheet converts to plain CSV, without the per-table headers that the
+    // extract output uses
+    #[cfg(feature = "excel")]
+    if *doc_type == DocumentType::Excel && matches!(format, ExtractFormat::Csv) {
+        return sheet_to_csv(input, sheet).await;
+    }
+    if sheet.is_some() {
+        return Err(anyhow::anyhow!(
+            "--sheet는 Excel을 CSV로 변환할 때만 사용할 수 있습니다"
+        ));
+    }
+
+    let result = UniversalExtractor::extract_from_path(input)?;
+    if !result.success {
+        if let Some(ref error) = result.error {
+            return Err(anyhow::anyhow!("Extraction error: {}", error));
+        }
+    }
+
+    let output = OutputFormatter::format(&result, format.into())?;
+    if matches!(format, ExtractFormat::Csv) && output.is_empty() {
+        return Err(anyhow::anyhow!(
+            "{}에 표가 없어 CSV로 변환할 수 없습니다",
+            input.display()
+        ));
+    }
+    Ok(output)
+}
+
+#[cfg(feature = "excel")]
+async fn sheet_to_csv(input: &Path, sheet: Option<&str>) -> Result<String> {
+    use dox_core::{SheetId, SpreadsheetProvider};
+    use dox_excel::ExcelProvider;
+
+    let provider = ExcelProvider::new();
+    let sheet_id = SheetId(input.display().to_string());
+    let sheet = match sheet {
+        Some(sheet) => sheet.to_string(),
+        None => provider
+            .list_sheets(&sheet_id)
+            .await?
+            .into_iter()
+            .next()
+            .map(|sheet| sheet.name)
+            .ok_or_else(|| anyhow::anyhow!("시트가 없습니다: {}", input.display()))?,
+    };
+
+    let csv = tempfile::NamedTempFile::new()?;
+    provider.export_csv(&sheet_id, &sheet, csv.path()).await?;
+    Ok(std::fs::read_to_string(csv.path())?)
+}
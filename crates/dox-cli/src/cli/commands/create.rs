@@ -24,6 +24,27 @@ pub struct CreateArgs {
     /// Overwrite existing files without prompting
     #[arg(long)]
     pub force: bool,
+
+    /// Heading level that starts a new slide (PowerPoint only)
+    ///
+    /// 1 makes one slide per H1 with H2 sections as bullets; 2 makes one
+    /// slide per H2.
+    #[arg(long, value_name = "LEVEL", default_value_t = 1, value_parser = clap::value_parser!(u8).range(1..=6))]
+    pub slide_level: u8,
+
+    /// Download images given by http(s) URLs and embed them
+    ///
+    /// Without this, remote images are skipped with a warning. Local image
+    /// paths are resolved against the Markdown file's directory.
+    #[arg(long)]
+    pub fetch_images: bool,
+
+    /// ZIP compression of the document parts: stored or deflate,
+    /// optionally with a level such as deflate:9
+    ///
+    /// stored is fastest to write, deflate (the default) is smaller.
+    #[arg(long, value_name = "METHOD[:LEVEL]", default_value = "deflate")]
+    pub compression: dox_core::create::Compression,
 }
 
 #[derive(Debug, Clone, Copy, clap::ValueEnum)]
@@ -80,12 +101,18 @@ pub async fn execute(args: CreateArgs) -> Result<()> {
     );
 
     // Create parser with options
-    let mut create_options = CreateOptions::default();
-    create_options.title = args
-        .output
-        .file_stem()
-        .and_then(|s| s.to_str())
-        .map(|s| s.to_string());
+    let create_options = CreateOptions {
+        title: args
+            .output
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .map(|s| s.to_string()),
+        slide_level: args.slide_level,
+        image_base_dir: args.from.parent().map(|dir| dir.to_path_buf()),
+        fetch_remote_images: args.fetch_images,
+        compression: args.compression,
+        ..Default::default()
+    };
 
     if let Some(template) = &args.template {
         ui::print_info(&format!("📋 Using template: {}", template.display()));
@@ -0,0 +1,97 @@
+use anyhow::Result;
+use clap::Args;
+use dox_document::DocumentDiff;
+use std::path::{Path, PathBuf};
+
+/// 두 문서의 텍스트를 문단 단위로 비교
+///
+/// 두 문서를 추출한 뒤 문단을 맞춰 추가·삭제·변경된 문단을 보여줍니다.
+/// 형식이 다른 문서끼리도 비교할 수 있습니다.
+///
+/// 예시:
+///   # 두 버전의 Word 문서 비교
+///   dox diff report_v1.docx report_v2.docx
+///
+///   # 좌우 나란히 비교
+///   dox diff draft.docx final.pdf --format side-by-side
+///
+///   # 변경 내역을 JSON으로
+///   dox diff old.pptx new.pptx --format json
+#[derive(Args, Debug)]
+pub struct DiffArgs {
+    /// 이전 문서
+    #[arg(value_name = "이전")]
+    pub old: PathBuf,
+
+    /// 새 문서
+    #[arg(value_name = "새")]
+    pub new: PathBuf,
+
+    /// 출력 형식
+    #[arg(long, value_enum, default_value = "unified")]
+    pub format: DiffFormat,
+
+    /// unified 형식에서 변경 문단 주변에 보여줄 문단 수
+    #[arg(long, value_name = "수", default_value_t = 3)]
+    pub context: usize,
+
+    /// side-by-side 형식의 전체 너비 (열 단위)
+    #[arg(long, value_name = "열", default_value_t = 120)]
+    pub width: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum DiffFormat {
+    Unified,
+    SideBySide,
+    Json,
+}
+
+pub async fn execute(args: DiffArgs) -> Result<()> {
+    use dox_core::utils::ui;
+
+    for path in [&args.old, &args.new] {
+        check_input(path)?;
+    }
+
+    let (old, new) = (args.old.clone(), args.new.clone());
+    let diff = tokio::task::spawn_blocking(move || DocumentDiff::between_files(&old, &new))
+        .await?
+        .map_err(|e| anyhow::anyhow!("문서 비교 실패: {}", e))?;
+
+    if args.format == DiffFormat::Json || ui::json_output() {
+        ui::print_json(&diff)?;
+        return Ok(());
+    }
+
+    if !diff.has_changes() {
+        ui::print_success("두 문서의 텍스트가 같습니다");
+        return Ok(());
+    }
+
+    if args.format == DiffFormat::SideBySide {
+        print!("{}", diff.side_by_side(args.width));
+    } else {
+        println!("--- {}", args.old.display());
+        println!("+++ {}", args.new.display());
+        ui::print_diff(&diff.old_text(), &diff.new_text(), args.context);
+    }
+
+    let summary = diff.summary;
+    ui::print_info(&format!(
+        "추가 {}, 삭제 {}, 변경 {} (변경 없음 {})",
+        summary.added, summary.removed, summary.changed, summary.unchanged
+    ));
+
+    Ok(())
+}
+
+fn check_input(path: &Path) -> Result<()> {
+    if !path.is_file() {
+        return Err(anyhow::anyhow!(
+            "입력 파일을 찾을 수 없습니다: {}",
+            path.display()
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,332 @@
+use anyhow::Result;
+use clap::{Args, Subcommand};
+use dox_core::{index_to_column_letter, Cell, RangeRef, SheetId, SpreadsheetProvider};
+use dox_excel::{ExcelChartType, ExcelProvider};
+use std::path::PathBuf;
+
+/// Excel(.xlsx) 파일 읽기, 쓰기, 차트 생성
+///
+/// 예시:
+///   # 시트 목록 보기
+///   dox excel list-sheets -f sales.xlsx
+///
+///   # 범위 읽기
+///   dox excel read -f sales.xlsx --range "Sheet1!A1:C10"
+///
+///   # 데이터 쓰기 (JSON 2차원 배열)
+///   dox excel write -f out.xlsx --range "Data!A1" --data '[["이름","점수"],["홍길동",90]]'
+///
+///   # 첫 열을 항목, 나머지 열을 계열로 하는 차트 생성
+///   dox excel chart -f sales.xlsx --type line -o chart.xlsx
+#[derive(Args, Debug)]
+pub struct ExcelArgs {
+    #[command(subcommand)]
+    pub action: ExcelAction,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ExcelAction {
+    /// 셀 범위를 읽어 탭으로 구분해 출력
+    Read {
+        /// Excel 파일 경로
+        #[arg(short, long, value_name = "파일")]
+        file: PathBuf,
+
+        /// 읽을 범위 (예: A1:C10, Sheet1!A1:C10, 정의된 이름). 생략하면 시트 전체
+        #[arg(long, value_name = "범위")]
+        range: Option<String>,
+
+        /// 시트 이름 (범위에 시트가 없을 때 사용)
+        #[arg(long, value_name = "시트")]
+        sheet: Option<String>,
+
+        /// JSON 2차원 배열로 출력
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// JSON 2차원 배열 데이터를 시트에 쓰기
+    ///
+    /// 파일은 이 시트 하나만 담은 새 파일로 바뀝니다. 기존 시트는 남지 않습니다.
+    Write {
+        /// Excel 파일 경로
+        #[arg(short, long, value_name = "파일")]
+        file: PathBuf,
+
+        /// 쓸 시트와 위치 (예: A1, Data!A1). 데이터는 항상 A1부터 씁니다
+        #[arg(long, value_name = "범위", default_value = "A1")]
+        range: String,
+
+        /// 쓸 데이터 (예: '[["이름","점수"],["홍길동",90]]')
+        #[arg(long, value_name = "JSON")]
+        data: String,
+    },
+
+    /// 시트 목록과 크기 출력
+    ListSheets {
+        /// Excel 파일 경로
+        #[arg(short, long, value_name = "파일")]
+        file: PathBuf,
+    },
+
+    /// 시트 데이터로 차트가 포함된 보고서 파일 생성
+    ///
+    /// 첫 행은 계열 이름, 첫 열은 항목으로 사용합니다.
+    Chart {
+        /// 데이터를 읽을 Excel 파일 경로
+        #[arg(short, long, value_name = "파일")]
+        file: PathBuf,
+
+        /// 시트 이름 (생략하면 첫 번째 시트)
+        #[arg(long, value_name = "시트")]
+        sheet: Option<String>,
+
+        /// 차트 종류
+        #[arg(long = "type", value_enum, default_value = "column")]
+        chart_type: ChartKind,
+
+        /// 차트 제목
+        #[arg(long, value_name = "제목", default_value = "Chart")]
+        title: String,
+
+        /// 저장할 파일 경로
+        #[arg(short, long, value_name = "파일")]
+        output: PathBuf,
+    },
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+pub enum ChartKind {
+    Column,
+    Line,
+    Pie,
+}
+
+impl From<ChartKind> for ExcelChartType {
+    fn from(kind: ChartKind) -> Self {
+        match kind {
+            ChartKind::Column => ExcelChartType::Column,
+            ChartKind::Line => ExcelChartType::Line,
+            ChartKind::Pie => ExcelChartType::Pie,
+        }
+    }
+}
+
+pub async fn execute(args: ExcelArgs) -> Result<()> {
+    use dox_core::utils::ui;
+
+    let provider = ExcelProvider::new();
+
+    match args.action {
+        ExcelAction::Read {
+            file,
+            range,
+            sheet,
+            json,
+        } => {
+            let range = qualified_range(sheet.as_deref(), range.as_deref().unwrap_or(""));
+            let data = provider.read_range(&sheet_id(&file), &range, None).await?;
+
+            if json {
+                let values: Vec<Vec<&str>> = data
+                    .iter()
+                    .map(|row| row.iter().map(|cell| cell.value.as_str()).collect())
+                    .collect();
+                println!("{}", serde_json::to_string_pretty(&values)?);
+            } else {
+                for row in &data {
+                    let values: Vec<&str> = row.iter().map(|cell| cell.value.as_str()).collect();
+                    println!("{}", values.join("\t"));
+                }
+            }
+        }
+        ExcelAction::Write { file, range, data } => {
+            let range = write_range(&range)?;
+            let data = parse_data(&data)?;
+            let rows = data.len();
+            provider
+                .write_range(&sheet_id(&file), &range, data, None)
+                .await?;
+            ui::print_success(&format!(
+                "{}행을 {} ({})에 썼습니다",
+                rows,
+                file.display(),
+                range
+            ));
+        }
+        ExcelAction::ListSheets { file } => {
+            let sheets = provider.list_sheets(&sheet_id(&file)).await?;
+            ui::print_table(
+                &["이름", "행", "열"],
+                sheets
+                    .into_iter()
+                    .map(|sheet| {
+                        vec![
+                            sheet.name,
+                            sheet.row_count.to_string(),
+                            sheet.column_count.to_string(),
+                        ]
+                    })
+                    .collect(),
+            );
+        }
+        ExcelAction::Chart {
+            file,
+            sheet,
+            chart_type,
+            title,
+            output,
+        } => {
+            let range = qualified_range(sheet.as_deref(), "");
+            let data = provider.read_range(&sheet_id(&file), &range, None).await?;
+            let series = chart_series(&data)?;
+            let series_count = series.len();
+
+            provider
+                .create_excel_report(
+                    &sheet_id(&output),
+                    data,
+                    vec![(
+                        chart_type.into(),
+                        title,
+                        series
+                            .iter()
+                            .map(|(name, range)| (name.as_str(), range.clone()))
+                            .collect(),
+                    )],
+                )
+                .await?;
+            ui::print_success(&format!(
+                "계열 {}개로 차트를 만들어 {}에 저장했습니다",
+                series_count,
+                output.display()
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+fn sheet_id(file: &std::path::Path) -> SheetId {
+    SheetId(file.display().to_string())
+}
+
+/// Combine `--sheet` and `--range`; a sheet inside the range wins
+///
+/// An empty range after the sheet name reads the whole sheet.
+fn qualified_range(sheet: Option<&str>, range: &str) -> RangeRef {
+    match sheet {
+        Some(sheet) if !range.contains('!') => RangeRef::new(format!("{}!{}", sheet, range)),
+        _ => RangeRef::new(range),
+    }
+}
+
+/// Check the `--range` of `write`, which always writes from A1
+///
+/// The provider writes a new file holding just this sheet, so any other
+/// start cell would put the data somewhere the user did not ask for.
+fn write_range(range: &str) -> Result<RangeRef> {
+    let range = RangeRef::new(range);
+    range.validate()?;
+
+    let (_, cells) = range.parse();
+    if !cells.is_empty() {
+        let (first_row, first_col, _, _) = dox_excel::validation::parse_cell_range(&range)?;
+        if (first_row, first_col) != (0, 0) {
+            return Err(anyhow::anyhow!(
+                "write는 A1부터만 쓸 수 있습니다: {} (예: A1, Data!A1)",
+                range
+            ));
+        }
+    }
+    Ok(range)
+}
+
+/// Parse `--data` as a JSON array of rows
+fn parse_data(data: &str) -> Result<Vec<Vec<Cell>>> {
+    let rows: Vec<Vec<serde_json::Value>> = serde_json::from_str(data).map_err(|e| {
+        anyhow::anyhow!(
+            "--data는 JSON 2차원 배열이어야 합니다 (예: [[\"a\",1]]): {}",
+            e
+        )
+    })?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            row.into_iter()
+                .map(|value| match value {
+                    serde_json::Value::String(s) => Cell::new(s),
+                    serde_json::Value::Null => Cell::new(""),
+                    value => Cell::new(value.to_string()),
+                })
+                .collect()
+        })
+        .collect())
+}
+
+/// One series per column after the first, named after its header
+///
+/// Ranges point at the sheet written by [`ExcelProvider::create_excel_report`].
+fn chart_series(data: &[Vec<Cell>]) -> Result<Vec<(String, RangeRef)>> {
+    let header = data
+        .first()
+        .ok_or_else(|| anyhow::anyhow!("차트를 만들 데이터가 없습니다"))?;
+    if data.len() < 2 || header.len() < 2 {
+        return Err(anyhow::anyhow!(
+            "차트에는 머리글 행과 항목 열 외에 최소 한 행, 한 열의 데이터가 필요합니다"
+        ));
+    }
+
+    let last_row = data.len();
+    Ok(header
+        .iter()
+        .enumerate()
+        .skip(1)
+        .map(|(col, cell)| {
+            let column = index_to_column_letter(col as u32);
+            (
+                cell.value.clone(),
+                RangeRef::new(format!("Report!${0}$2:${0}${1}", column, last_row)),
+            )
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_qualified_range() {
+        assert_eq!(qualified_range(Some("Data"), "A1:B2").0, "Data!A1:B2");
+        assert_eq!(qualified_range(Some("Data"), "Other!A1").0, "Other!A1");
+        assert_eq!(qualified_range(None, "A1").0, "A1");
+        assert_eq!(qualified_range(Some("Data"), "").0, "Data!");
+    }
+
+    #[test]
+    fn test_write_range_starts_at_a1() {
+        assert_eq!(write_range("A1").unwrap().0, "A1");
+        assert_eq!(write_range("Data!$A$1:C3").unwrap().0, "Data!$A$1:C3");
+        assert_eq!(write_range("Data!").unwrap().0, "Data!");
+
+        let error = write_range("Data!B2").unwrap_err();
+        assert!(error.to_string().contains("A1"), "{}", error);
+        assert!(write_range("Data!B").is_err());
+    }
+
+    #[test]
+    fn test_chart_series_ranges() {
+        let data = parse_data(r#"[["월","매출","비용"],["1월",10,4],["2월",12,5]]"#).unwrap();
+        let series = chart_series(&data).unwrap();
+
+        assert_eq!(series.len(), 2);
+        assert_eq!(series[0].0, "매출");
+        assert_eq!(series[0].1 .0, "Report!$B$2:$B$3");
+        assert_eq!(series[1].1 .0, "Report!$C$2:$C$3");
+
+        assert!(chart_series(&data[..1]).is_err());
+        assert!(parse_data("{}").is_err());
+    }
+}
@@ -1,5 +1,6 @@
 use anyhow::Result;
 use clap::Args;
+use dox_document::{CancellationToken, DocumentType, ExtractResult, PageRange};
 use std::path::PathBuf;
 
 #[cfg(feature = "pdf")]
@@ -19,6 +20,9 @@ extern crate pdf_extract;
 ///   
 ///   # JSON 형식으로 메타데이터와 함께 추출
 ///   dox extract -i presentation.pptx --format json --with-metadata
+///
+///   # 여러 문서를 한 번에 추출 (파일마다 하나씩 출력)
+///   dox extract "docs/**/*.docx" --format json --output-dir out/ --exclude "~$*"
 #[cfg_attr(feature = "pdf", doc = "///   ")]
 #[cfg_attr(feature = "pdf", doc = "///   # PDF 문서에서 텍스트 추출")]
 #[cfg_attr(
@@ -27,7 +31,13 @@ extern crate pdf_extract;
 )]
 #[derive(Args, Debug)]
 pub struct ExtractArgs {
-    /// 입력 문서 파일 또는 디렉토리 경로
+    /// 입력 문서 파일, 디렉토리 또는 glob 패턴 (여러 개 지정 가능)
+    ///
+    /// 예: report.docx docs/ "docs/**/*.pdf"
+    #[arg(value_name = "경로", required_unless_present = "input")]
+    pub paths: Vec<String>,
+
+    /// 입력 문서 파일 또는 디렉토리 경로 (여러 번 지정 가능)
     ///
     #[cfg_attr(
         feature = "pdf",
@@ -38,9 +48,9 @@ pub struct ExtractArgs {
         doc = "/// 지원 형식: .docx (Word), .pptx (PowerPoint), .xlsx (Excel)"
     )]
     #[arg(short, long, value_name = "경로")]
-    pub input: PathBuf,
+    pub input: Vec<PathBuf>,
 
-    /// 출력 파일 경로 (지정하지 않으면 표준출력)
+    /// 출력 파일 경로 (입력이 파일 하나일 때만, 지정하지 않으면 표준출력)
     #[arg(short, long, value_name = "파일")]
     pub output: Option<PathBuf>,
 
@@ -50,11 +60,13 @@ pub struct ExtractArgs {
     /// • json: 구조화된 JSON (메타데이터 포함 가능)
     /// • markdown: 마크다운 형식 (제목, 목록 등 보존)
     /// • html: HTML 형식 (테이블, 레이아웃 보존)
+    /// • rtf: RTF 형식 (제목, 표 보존, 워드프로세서용)
+    /// • csv: CSV 형식 (표만 추출)
     #[arg(
         long,
         value_enum,
         default_value = "text",
-        help = "출력 형식\n  • text: 일반 텍스트 (서식 없음)\n  • json: 구조화된 JSON (메타데이터 포함 가능)\n  • markdown: 마크다운 형식 (제목, 목록 등 보존)\n  • html: HTML 형식 (테이블, 레이아웃 보존)"
+        help = "출력 형식\n  • text: 일반 텍스트 (서식 없음)\n  • json: 구조화된 JSON (메타데이터 포함 가능)\n  • markdown: 마크다운 형식 (제목, 목록 등 보존)\n  • html: HTML 형식 (테이블, 레이아웃 보존)\n  • rtf: RTF 형식 (제목, 표 보존, 워드프로세서용)\n  • csv: CSV 형식 (표만 추출)"
     )]
     pub format: ExtractFormat,
 
@@ -65,6 +77,12 @@ pub struct ExtractArgs {
     #[arg(long)]
     pub with_metadata: bool,
 
+    /// 추출할 페이지 또는 슬라이드 (PDF, PowerPoint)
+    ///
+    /// 예: "2-5,8". 문서에 없는 페이지는 경고 후 무시합니다.
+    #[arg(long, value_name = "범위")]
+    pub pages: Option<PageRange>,
+
     /// 하위 디렉토리까지 재귀적으로 처리
     #[arg(long, default_value = "true")]
     pub recursive: bool,
@@ -96,35 +114,20 @@ pub enum ExtractFormat {
     Json,
     Markdown,
     Html,
+    Rtf,
+    Csv,
 }
 
 pub async fn execute(args: ExtractArgs) -> Result<()> {
     use dox_core::utils::ui;
 
-    // Verify input path exists
-    if !args.input.exists() {
-        ui::print_error(&format!(
-            "입력 경로를 찾을 수 없습니다: {}",
-            args.input.display()
-        ));
-        return Err(anyhow::anyhow!("Path not found: {}", args.input.display()));
-    }
-
     // Find all document files
-    let files = if args.input.is_file() {
-        // Single file processing
-        if is_supported_document(&args.input) {
-            vec![args.input.clone()]
-        } else {
-            ui::print_error(&format!(
-                "지원되지 않는 파일 형식입니다: {}",
-                args.input.display()
-            ));
-            return Err(anyhow::anyhow!("Unsupported file format"));
+    let files = match collect_input_files(&args) {
+        Ok(files) => files,
+        Err(err) => {
+            ui::print_error(&err.to_string());
+            return Err(err);
         }
-    } else {
-        // Directory processing
-        find_document_files(&args.input, args.recursive, args.exclude.as_deref())?
     };
 
     if files.is_empty() {
@@ -132,13 +135,20 @@ pub async fn execute(args: ExtractArgs) -> Result<()> {
         return Ok(());
     }
 
+    if files.len() > 1 && args.output.is_some() {
+        return Err(anyhow::anyhow!(
+            "여러 문서를 추출할 때는 --output 대신 --output-dir을 사용하세요"
+        ));
+    }
+
     ui::print_header(&format!("{}개 문서 처리", files.len()));
 
     // Process files
+    let cancel = super::cancel_on_ctrl_c();
     let results = if args.concurrent && files.len() > 1 {
-        process_concurrent(files, &args).await?
+        process_concurrent(files, &args, &cancel).await?
     } else {
-        process_sequential(files, &args).await?
+        process_sequential(files, &args, &cancel).await?
     };
 
     // Print summary
@@ -147,12 +157,89 @@ pub async fn execute(args: ExtractArgs) -> Result<()> {
     Ok(())
 }
 
-/// Check if a file is a supported document type
-fn is_supported_document(path: &std::path::Path) -> bool {
-    match path.extension().and_then(|s| s.to_str()) {
-        Some("docx") | Some("pptx") | Some("pdf") | Some("xlsx") | Some("txt") => true,
-        _ => false,
+impl ExtractArgs {
+    /// Positional paths followed by `--input` values
+    fn all_inputs(&self) -> Vec<String> {
+        self.paths
+            .iter()
+            .cloned()
+            .chain(self.input.iter().map(|path| path.display().to_string()))
+            .collect()
+    }
+
+    /// Whether exactly one existing file was named, so output may go to stdout
+    fn is_single_file(&self) -> bool {
+        match self.all_inputs().as_slice() {
+            [input] => std::path::Path::new(input).is_file(),
+            _ => false,
+        }
+    }
+}
+
+/// Expand files, directories and glob patterns into a sorted list of documents
+///
+/// Files named explicitly must be supported documents; their type is
+/// detected from the content, so renamed files are accepted. Directory and
+/// glob matches are filtered by extension and silently skip unsupported
+/// files and those matching `--exclude`.
+fn collect_input_files(args: &ExtractArgs) -> Result<Vec<std::path::PathBuf>> {
+    let exclude = args
+        .exclude
+        .as_deref()
+        .map(glob::Pattern::new)
+        .transpose()?;
+    let excluded = |path: &std::path::Path| {
+        exclude.as_ref().is_some_and(|pattern| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| pattern.matches(name))
+        })
+    };
+
+    let mut files = Vec::new();
+    for input in args.all_inputs() {
+        let path = std::path::Path::new(&input);
+
+        if path.is_file() {
+            if DocumentType::detect(path).is_none() {
+                return Err(anyhow::anyhow!(
+                    "지원되지 않는 파일 형식입니다: {}",
+                    path.display()
+                ));
+            }
+            files.push(path.to_path_buf());
+        } else if path.is_dir() {
+            files.extend(find_document_files(
+                path,
+                args.recursive,
+                args.exclude.as_deref(),
+            )?);
+        } else if input.contains(['*', '?', '[']) {
+            for entry in glob::glob(&input)? {
+                let path = entry?;
+                if path.is_file() && is_supported_document(&path) && !excluded(&path) {
+                    files.push(path);
+                }
+            }
+        } else {
+            return Err(anyhow::anyhow!(
+                "입력 경로를 찾을 수 없습니다: {}",
+                path.display()
+            ));
+        }
     }
+
+    files.sort();
+    files.dedup();
+    Ok(files)
+}
+
+/// Check if a file has the extension of a supported document type, in any case
+fn is_supported_document(path: &std::path::Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .and_then(DocumentType::from_extension)
+        .is_some()
 }
 
 /// Find all document files in a directory
@@ -197,12 +284,14 @@ struct ProcessResults {
     files_succeeded: usize,
     files_failed: usize,
     total_size: u64,
+    cancelled: bool,
 }
 
 /// Process files sequentially
 async fn process_sequential(
     files: Vec<std::path::PathBuf>,
     args: &ExtractArgs,
+    cancel: &CancellationToken,
 ) -> Result<ProcessResults> {
     use dox_core::utils::ui;
 
@@ -210,6 +299,11 @@ async fn process_sequential(
     let progress = ui::create_progress_bar(files.len() as u64, "문서 추출 중");
 
     for (i, file) in files.iter().enumerate() {
+        if cancel.is_cancelled() {
+            results.cancelled = true;
+            break;
+        }
+
         progress.set_message(format!("처리 중: {}", file.display()));
 
         match process_single_file(file, args).await {
@@ -232,54 +326,57 @@ async fn process_sequential(
 }
 
 /// Process files concurrently
+///
+/// Extraction runs on a pool of `max_workers` blocking workers; the
+/// results are then written in input order.
 async fn process_concurrent(
     files: Vec<std::path::PathBuf>,
     args: &ExtractArgs,
+    cancel: &CancellationToken,
 ) -> Result<ProcessResults> {
     use dox_core::utils::ui;
-    use futures::stream::{self, StreamExt};
-    use std::sync::{
-        atomic::{AtomicUsize, Ordering},
-        Arc,
-    };
+    use dox_document::{BatchExtractor, ProgressCallback};
 
     let max_workers = args.max_workers.min(files.len());
     let progress = ui::create_progress_bar(
         files.len() as u64,
         &format!("병렬 추출 중 ({}개 작업자)", max_workers),
     );
-    let completed = Arc::new(AtomicUsize::new(0));
-
-    let results = stream::iter(files)
-        .map(|file| {
-            let args = args.clone();
-            let progress = progress.clone();
-            let completed = Arc::clone(&completed);
-            async move {
-                let result = process_single_file(&file, &args)
-                    .await
-                    .map(|size| (1, 1, 0, size))
-                    .unwrap_or_else(|_| (1, 0, 1, 0));
-
-                let current = completed.fetch_add(1, Ordering::SeqCst) + 1;
-                progress.set_position(current as u64);
-
-                result
-            }
-        })
-        .buffer_unordered(max_workers)
-        .fold(
-            ProcessResults::default(),
-            |mut acc, (processed, succeeded, failed, size)| async move {
-                acc.files_processed += processed;
-                acc.files_succeeded += succeeded;
-                acc.files_failed += failed;
-                acc.total_size += size;
-                acc
-            },
-        )
+
+    let bar = progress.clone();
+    let mut extractor = BatchExtractor::new(max_workers).with_cancel(cancel.clone());
+    if let Some(pages) = &args.pages {
+        extractor = extractor.with_page_range(pages.clone());
+    }
+    let batch = extractor
+        .with_progress(ProgressCallback::new(move |current, _| {
+            bar.set_position(current as u64)
+        }))
+        .extract(files)
         .await;
 
+    let mut results = ProcessResults {
+        files_processed: batch.files_processed(),
+        files_failed: batch.errors.len(),
+        cancelled: batch.cancelled,
+        ..Default::default()
+    };
+    for (file, error) in &batch.errors {
+        ui::print_error(&format!("처리 실패 {}: {}", file.display(), error));
+    }
+    for (file, extract_result) in &batch.results {
+        match write_result(file, extract_result, args) {
+            Ok(size) => {
+                results.files_succeeded += 1;
+                results.total_size += size;
+            }
+            Err(e) => {
+                ui::print_error(&format!("처리 실패 {}: {}", file.display(), e));
+                results.files_failed += 1;
+            }
+        }
+    }
+
     progress.finish_with_message("병렬 추출 완료");
     Ok(results)
 }
@@ -287,11 +384,21 @@ async fn process_concurrent(
 /// Process a single file
 async fn process_single_file(file: &std::path::Path, args: &ExtractArgs) -> Result<u64> {
     use dox_document::extract::extractors::UniversalExtractor;
-    use dox_document::OutputFormatter;
-    use std::fs;
 
     // Extract content from document
-    let extract_result = UniversalExtractor::extract_from_path(file)?;
+    let extract_result = UniversalExtractor::extract_pages(file, args.pages.as_ref())?;
+    write_result(file, &extract_result, args)
+}
+
+/// Print or save the extracted content of `file`, returning its formatted size
+fn write_result(
+    file: &std::path::Path,
+    extract_result: &ExtractResult,
+    args: &ExtractArgs,
+) -> Result<u64> {
+    use dox_core::utils::ui;
+    use dox_document::OutputFormatter;
+    use std::fs;
 
     if !extract_result.success {
         if let Some(ref error) = extract_result.error {
@@ -299,16 +406,12 @@ async fn process_single_file(file: &std::path::Path, args: &ExtractArgs) -> Resu
         }
     }
 
-    // Convert ExtractFormat enum from clap to our internal enum
-    let output_format = match args.format {
-        ExtractFormat::Text => dox_document::ExtractFormat::Text,
-        ExtractFormat::Json => dox_document::ExtractFormat::Json,
-        ExtractFormat::Markdown => dox_document::ExtractFormat::Markdown,
-        ExtractFormat::Html => dox_document::ExtractFormat::Html,
-    };
+    if ui::json_output() {
+        ui::print_json(extract_result)?;
+    }
 
     // Format the output
-    let formatted_output = OutputFormatter::format(&extract_result, output_format)?;
+    let formatted_output = OutputFormatter::format(extract_result, args.format.into())?;
 
     // Determine output path
     let output_path = determine_output_path(file, args)?;
@@ -324,10 +427,9 @@ async fn process_single_file(file: &std::path::Path, args: &ExtractArgs) -> Resu
 
         // Print individual file success (only in sequential mode)
         if !args.concurrent {
-            use dox_core::utils::ui;
             ui::print_success(&format!("저장됨: {} → {}", file.display(), path.display()));
         }
-    } else if args.input.is_file() {
+    } else if args.is_single_file() && !ui::json_output() {
         // Single file to stdout
         println!("{}", formatted_output);
     }
@@ -341,7 +443,7 @@ fn determine_output_path(
     args: &ExtractArgs,
 ) -> Result<Option<std::path::PathBuf>> {
     // If single file and no output specified, use stdout
-    if args.input.is_file() && args.output.is_none() && args.output_dir.is_none() {
+    if args.is_single_file() && args.output.is_none() && args.output_dir.is_none() {
         return Ok(None);
     }
 
@@ -370,13 +472,28 @@ fn determine_output_path(
     Ok(Some(output_dir.join(output_filename)))
 }
 
+impl From<ExtractFormat> for dox_document::ExtractFormat {
+    fn from(format: ExtractFormat) -> Self {
+        match format {
+            ExtractFormat::Text => dox_document::ExtractFormat::Text,
+            ExtractFormat::Json => dox_document::ExtractFormat::Json,
+            ExtractFormat::Markdown => dox_document::ExtractFormat::Markdown,
+            ExtractFormat::Html => dox_document::ExtractFormat::Html,
+            ExtractFormat::Rtf => dox_document::ExtractFormat::Rtf,
+            ExtractFormat::Csv => dox_document::ExtractFormat::Csv,
+        }
+    }
+}
+
 impl ExtractFormat {
-    fn extension(&self) -> &'static str {
+    pub(crate) fn extension(&self) -> &'static str {
         match self {
             ExtractFormat::Text => "txt",
             ExtractFormat::Json => "json",
             ExtractFormat::Markdown => "md",
             ExtractFormat::Html => "html",
+            ExtractFormat::Rtf => "rtf",
+            ExtractFormat::Csv => "csv",
         }
     }
 }
@@ -386,10 +503,12 @@ impl ExtractFormat {
 impl Clone for ExtractArgs {
     fn clone(&self) -> Self {
         Self {
+            paths: self.paths.clone(),
             input: self.input.clone(),
             output: self.output.clone(),
             format: self.format,
             with_metadata: self.with_metadata,
+            pages: self.pages.clone(),
             recursive: self.recursive,
             exclude: self.exclude.clone(),
             concurrent: self.concurrent,
@@ -422,4 +541,8 @@ fn print_summary(results: &ProcessResults, _args: &ExtractArgs) {
             results.files_failed
         ));
     }
+
+    if results.cancelled {
+        ui::print_warning("작업이 취소되어 나머지 파일은 추출하지 않았습니다");
+    }
 }
@@ -1,12 +1,18 @@
 use anyhow::Result;
 use clap::Args;
-use std::path::PathBuf;
+use dox_core::generate::local::LOCAL_MODEL_PREFIXES;
+use dox_core::generate::{pricing, ContentGenerator, GenerationRequest, GenerationResponse};
+use dox_core::utils::config::Config;
+use dox_core::DoxError;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 
-/// AI를 사용하여 콘텐츠 생성 (OpenAI 또는 Claude)
+/// AI를 사용하여 콘텐츠 생성 (OpenAI, Claude 또는 로컬 모델)
 ///
-/// AI 제공업체 설정:
-///   • OpenAI: OPENAI_API_KEY 환경변수 설정
-///   • Claude: ANTHROPIC_API_KEY 환경변수 설정
+/// AI 제공업체 설정 (우선순위: --api-key > 환경변수 > 설정 파일):
+///   • OpenAI: OPENAI_API_KEY 환경변수 또는 [openai] api_key 설정
+///   • Claude: ANTHROPIC_API_KEY 환경변수 또는 [claude] api_key 설정
+///   • 로컬: API 키 불필요, [local] base_url 설정 (기본값: Ollama http://localhost:11434/v1)
 ///
 /// 예시:
 ///   # 블로그 포스트 생성
@@ -17,6 +23,9 @@ use std::path::PathBuf;
 ///   
 ///   # Claude로 이메일 생성
 ///   dox generate -p "프로젝트 업데이트 공유" -t email --model claude-3-5-sonnet-20241022
+///   
+///   # 로컬 Ollama 모델로 요약 생성
+///   dox generate -p "회의록 요약" -t summary --model ollama/llama3
 #[derive(Args, Debug)]
 pub struct GenerateArgs {
     /// 생성 프롬프트
@@ -39,17 +48,17 @@ pub struct GenerateArgs {
     #[arg(short, long, value_name = "파일")]
     pub output: Option<PathBuf>,
 
-    /// 사용할 AI 모델
-    #[arg(long, default_value = "gpt-3.5-turbo")]
-    pub model: String,
+    /// 사용할 AI 모델 (기본값: 설정의 generate.model, 로컬 제공업체는 local.model)
+    #[arg(long)]
+    pub model: Option<String>,
 
-    /// 응답의 최대 토큰 수
-    #[arg(long, default_value = "2000")]
-    pub max_tokens: usize,
+    /// 응답의 최대 토큰 수 (기본값: 설정의 generate.max_tokens)
+    #[arg(long)]
+    pub max_tokens: Option<usize>,
 
-    /// 창의성 수준 (0.0-1.0)
-    #[arg(long, default_value = "0.7")]
-    pub temperature: f32,
+    /// 창의성 수준 (0.0-1.0, 기본값: 설정의 generate.temperature)
+    #[arg(long)]
+    pub temperature: Option<f32>,
 
     /// AI 제공업체 (모델에서 자동 감지)
     #[arg(long, value_enum)]
@@ -70,6 +79,29 @@ pub struct GenerateArgs {
     /// 글의 톤
     #[arg(long, default_value = "전문적")]
     pub tone: String,
+
+    /// 생성되는 내용을 실시간으로 출력 (--output을 지정하면 전체 내용도 파일에 저장)
+    #[arg(long)]
+    pub stream: bool,
+
+    /// 예상 프롬프트 비용이 이 금액(USD)을 넘으면 API를 호출하지 않고 중단
+    #[arg(long, value_name = "USD")]
+    pub max_cost: Option<f64>,
+
+    /// 시스템 프롬프트 템플릿 파일 ({{prompt}}, {{audience}}, {{tone}} 치환)
+    ///
+    /// 지정하지 않으면 프롬프트 디렉터리([generate] prompt_dir, 기본값
+    /// ~/.config/dox/prompts)의 <콘텐츠유형>.<언어>.txt, 없으면 내장 템플릿 사용
+    #[arg(long, value_name = "파일")]
+    pub template: Option<PathBuf>,
+
+    /// 문서 내용을 추출하여 컨텍스트로 사용 (PDF, Word, PowerPoint, Excel 등)
+    #[arg(long, value_name = "파일")]
+    pub from: Option<PathBuf>,
+
+    /// --from 문서에서 사용할 최대 문자 수 (초과분은 잘라냄)
+    #[arg(long, value_name = "N", default_value = "20000")]
+    pub max_context_chars: usize,
 }
 
 #[derive(Debug, Clone, Copy, clap::ValueEnum)]
@@ -87,47 +119,75 @@ pub enum ContentType {
 pub enum AIProvider {
     OpenAI,
     Claude,
+    Local,
 }
 
-pub async fn execute(args: GenerateArgs) -> Result<()> {
+pub async fn execute(
+    args: GenerateArgs,
+    config_path: Option<&Path>,
+    profile: Option<&str>,
+) -> Result<()> {
     use dox_core::generate::{
-        claude::ClaudeProvider, openai::OpenAIProvider, ContentGenerator, GenerationRequest,
+        claude::ClaudeProvider, local::LocalProvider, openai::OpenAIProvider, HttpClientConfig,
+        PromptLibrary,
     };
     use dox_core::utils::ui;
-    use std::fs;
+
+    let config = super::config::load_config_with_path(config_path, profile)?;
 
     ui::print_info(&format!(
         "{} 콘텐츠를 생성하는 중...",
         args.content_type.as_str_ko()
     ));
 
-    // Create generation request
-    let request = GenerationRequest {
-        prompt: args.prompt.clone(),
-        content_type: convert_content_type(args.content_type),
-        model: args.model.clone(),
-        max_tokens: args.max_tokens,
-        temperature: args.temperature,
-        language: args.language.clone(),
-        audience: args.audience.clone(),
-        tone: args.tone.clone(),
-        context: None,
-        stream: false, // TODO: Implement streaming
-        provider_params: std::collections::HashMap::new(),
+    let prompts = PromptLibrary {
+        template_file: args.template.clone(),
+        prompt_dir: config
+            .generate
+            .prompt_dir
+            .as_ref()
+            .map(PathBuf::from)
+            .or_else(PromptLibrary::default_dir),
     };
+    let template = prompts.resolve(convert_content_type(args.content_type), &args.language)?;
+    let provider_name = provider_name(&args, &config);
+    let model = resolve_model(&args, provider_name, &config);
+    let mut request = build_request(&args, model, &config).with_system_template(template);
+    if let Some(path) = &args.from {
+        request.context = Some(document_context(path, args.max_context_chars)?);
+    }
 
-    // Create AI provider
-    let provider = match detect_provider(&args.model) {
-        "openai" => {
-            // Try to get API key from args, environment, or config
-            let api_key = get_api_key("openai", args.api_key.as_deref())?;
-            Box::new(OpenAIProvider::new(api_key)) as Box<dyn ContentGenerator>
-        }
-        "claude" => {
-            // Try to get API key from args, environment, or config
-            let api_key = get_api_key("claude", args.api_key.as_deref())?;
-            Box::new(ClaudeProvider::new(api_key)) as Box<dyn ContentGenerator>
+    // Local models cost nothing and need no API key
+    if provider_name == "local" {
+        let mut provider = LocalProvider::configured(&HttpClientConfig {
+            base_url: config.local.base_url.clone(),
+            ..Default::default()
+        })?;
+        if let Some(api_key) = args.api_key.clone() {
+            provider = provider.with_api_key(api_key);
         }
+        return run(Box::new(provider), request, args).await;
+    }
+
+    check_budget(&request, args.max_cost)?;
+    let api_key = get_api_key(provider_name, args.api_key.as_deref(), &config)?;
+    let provider: Box<dyn ContentGenerator> = match provider_name {
+        "openai" => Box::new(OpenAIProvider::configured(
+            api_key,
+            &HttpClientConfig {
+                base_url: config.openai.base_url.clone(),
+                proxy: config.openai.proxy.clone(),
+                ca_cert: config.openai.ca_cert.as_ref().map(PathBuf::from),
+            },
+        )?),
+        "claude" => Box::new(ClaudeProvider::configured(
+            api_key,
+            &HttpClientConfig {
+                base_url: config.claude.base_url.clone(),
+                proxy: config.claude.proxy.clone(),
+                ca_cert: config.claude.ca_cert.as_ref().map(PathBuf::from),
+            },
+        )?),
         provider_name => {
             return Err(anyhow::anyhow!(
                 "지원되지 않는 AI 제공업체: {}",
@@ -136,6 +196,17 @@ pub async fn execute(args: GenerateArgs) -> Result<()> {
         }
     };
 
+    run(provider, request, args).await
+}
+
+/// Generate with the selected provider and write the result
+async fn run(
+    provider: Box<dyn ContentGenerator>,
+    request: GenerationRequest,
+    args: GenerateArgs,
+) -> Result<()> {
+    use dox_core::utils::ui;
+
     // Show generation info
     ui::print_info(&format!(
         "🤖 {} 모델로 {} 콘텐츠 생성 중...",
@@ -143,32 +214,38 @@ pub async fn execute(args: GenerateArgs) -> Result<()> {
         request.content_type.as_str()
     ));
 
-    // Generate content
-    let response = provider.generate(&request).await?;
+    // Streamed chunks go to stdout as they arrive; the complete text is
+    // still saved to --output below. JSON output needs the whole response.
+    let stream_to_stdout = request.stream && !ui::json_output();
+    let response = if stream_to_stdout {
+        println!();
+        let mut stdout = std::io::stdout();
+        let response = run_generation(provider.as_ref(), &request, &mut stdout)
+            .await
+            .map_err(provider_error)?;
+        println!();
+        response
+    } else {
+        provider.generate(&request).await.map_err(provider_error)?
+    };
 
-    // Show token usage if available
-    if let Some(usage) = &response.usage {
-        ui::print_info(&format!(
-            "📊 토큰 사용량: {} (프롬프트: {}, 완성: {})",
-            usage.total_tokens, usage.prompt_tokens, usage.completion_tokens
-        ));
+    print_generation_summary(&response);
+    if ui::json_output() {
+        if let Some(output_path) = args.output {
+            save_output(output_path, args.content_type, &response.content)?;
+        }
+        ui::print_json(&response)?;
+        return Ok(());
     }
 
     // Output content
-    if let Some(mut output_path) = args.output {
-        // Add appropriate extension if not present
-        if output_path.extension().is_none() {
-            let extension = get_file_extension(args.content_type);
-            output_path = output_path.with_extension(extension);
-        }
-
-        // Save to file
-        fs::write(&output_path, &response.content)?;
+    if let Some(output_path) = args.output {
+        let output_path = save_output(output_path, args.content_type, &response.content)?;
         ui::print_success(&format!(
             "✅ 콘텐츠가 생성되어 {}에 저장되었습니다",
             output_path.display()
         ));
-    } else {
+    } else if !stream_to_stdout {
         // Print to stdout
         println!("\n{}", response.content);
     }
@@ -176,6 +253,189 @@ pub async fn execute(args: GenerateArgs) -> Result<()> {
     Ok(())
 }
 
+/// Give transport failures that reach the command untyped a [`DoxError`],
+/// so the error report can suggest how to recover
+///
+/// Provider HTTP errors already arrive as [`DoxError`]s; connection drops
+/// while a response streams in come through as bare `reqwest` errors.
+fn provider_error(err: anyhow::Error) -> anyhow::Error {
+    match err.downcast::<reqwest::Error>() {
+        Ok(err) => DoxError::from(err).into(),
+        Err(err) => err,
+    }
+}
+
+/// AI provider named by `--provider`, or detected from `--model` or the
+/// configured `generate.model`
+fn provider_name(args: &GenerateArgs, config: &Config) -> &'static str {
+    match args.provider {
+        Some(AIProvider::OpenAI) => "openai",
+        Some(AIProvider::Claude) => "claude",
+        Some(AIProvider::Local) => "local",
+        None => detect_provider(args.model.as_deref().unwrap_or(&config.generate.model)),
+    }
+}
+
+/// Model to request: `--model`, then the `[local]` model for the local
+/// provider, then the configured `generate.model`
+fn resolve_model(args: &GenerateArgs, provider_name: &str, config: &Config) -> String {
+    args.model
+        .clone()
+        .or_else(|| {
+            (provider_name == "local")
+                .then(|| config.local.model.clone())
+                .flatten()
+        })
+        .unwrap_or_else(|| config.generate.model.clone())
+}
+
+/// Convert CLI arguments into a core generation request for `model`
+///
+/// `--max-tokens` and `--temperature` fall back to the `[generate]` values
+/// of `config`.
+fn build_request(args: &GenerateArgs, model: String, config: &Config) -> GenerationRequest {
+    GenerationRequest {
+        prompt: args.prompt.clone(),
+        content_type: convert_content_type(args.content_type),
+        model,
+        max_tokens: args.max_tokens.unwrap_or(config.generate.max_tokens),
+        temperature: args.temperature.unwrap_or(config.generate.temperature),
+        language: args.language.clone(),
+        audience: args.audience.clone(),
+        tone: args.tone.clone(),
+        context: None,
+        system_prompt: None,
+        examples: Vec::new(),
+        response_format: None,
+        stream: args.stream,
+        provider_params: std::collections::HashMap::new(),
+    }
+}
+
+/// Extract a document as Markdown for use as generation context
+///
+/// Markdown keeps headings and tables readable for the model. Text beyond
+/// `max_chars` characters is dropped.
+fn document_context(path: &Path, max_chars: usize) -> Result<String> {
+    use dox_core::utils::ui;
+    use dox_document::extract::extractors::UniversalExtractor;
+    use dox_document::{ExtractFormat, OutputFormatter};
+
+    let result = UniversalExtractor::extract_from_path(path)?;
+    if !result.success {
+        return Err(anyhow::anyhow!(
+            "{}에서 텍스트를 추출하지 못했습니다: {}",
+            path.display(),
+            result.error.as_deref().unwrap_or("알 수 없는 오류")
+        ));
+    }
+
+    let text = OutputFormatter::format(&result, ExtractFormat::Markdown)?;
+    let total = text.chars().count();
+    if total <= max_chars {
+        return Ok(text);
+    }
+
+    ui::print_warning(&format!(
+        "{} 내용이 {}자로 길어 앞의 {}자만 사용합니다",
+        path.display(),
+        total,
+        max_chars
+    ));
+    Ok(text.chars().take(max_chars).collect())
+}
+
+/// Abort before any API call if the estimated prompt cost exceeds `max_cost`
+fn check_budget(request: &GenerationRequest, max_cost: Option<f64>) -> Result<()> {
+    let Some(max_cost) = max_cost else {
+        return Ok(());
+    };
+    if max_cost.is_nan() || max_cost < 0.0 {
+        return Err(anyhow::anyhow!(
+            "--max-cost는 0 이상이어야 합니다: {}",
+            max_cost
+        ));
+    }
+
+    let estimate = pricing::estimate_cost(request).ok_or_else(|| {
+        anyhow::anyhow!(
+            "{} 모델의 가격 정보가 없어 비용을 추정할 수 없습니다",
+            request.model
+        )
+    })?;
+    if estimate > max_cost {
+        return Err(anyhow::anyhow!(
+            "예상 프롬프트 비용 ${:.4}가 예산 ${:.4}를 초과합니다 (약 {} 토큰)",
+            estimate,
+            max_cost,
+            pricing::estimate_prompt_tokens(request)
+        ));
+    }
+    Ok(())
+}
+
+/// Show token usage and its cost, when the provider reported usage
+fn print_generation_summary(response: &GenerationResponse) {
+    use dox_core::utils::ui;
+
+    let Some(usage) = &response.usage else {
+        return;
+    };
+    ui::print_info(&format!(
+        "📊 토큰 사용량: {} (프롬프트: {}, 완성: {})",
+        usage.total_tokens, usage.prompt_tokens, usage.completion_tokens
+    ));
+    if let Some(cost) = pricing::usage_cost(&response.model, usage) {
+        ui::print_info(&format!("💰 비용: ${:.4}", cost));
+    }
+}
+
+/// Stream generated content to `out` as it arrives
+///
+/// Providers without native streaming emit the whole text as one chunk. If
+/// the final response carries no content, the streamed chunks are used so
+/// the caller always gets the complete text.
+async fn run_generation(
+    provider: &dyn ContentGenerator,
+    request: &GenerationRequest,
+    out: &mut (dyn Write + Send),
+) -> Result<GenerationResponse> {
+    let mut write_error = None;
+    let mut streamed = String::new();
+    let mut response = provider
+        .generate_stream(request, &mut |chunk| {
+            streamed.push_str(chunk);
+            if write_error.is_none() {
+                if let Err(e) = out.write_all(chunk.as_bytes()).and_then(|_| out.flush()) {
+                    write_error = Some(e);
+                }
+            }
+        })
+        .await?;
+
+    if let Some(e) = write_error {
+        return Err(e.into());
+    }
+    if response.content.is_empty() {
+        response.content = streamed;
+    }
+    Ok(response)
+}
+
+/// Write the generated content, adding the content type's extension if the
+/// path has none, and return the path written
+fn save_output(
+    mut output_path: PathBuf,
+    content_type: ContentType,
+    content: &str,
+) -> Result<PathBuf> {
+    if output_path.extension().is_none() {
+        output_path = output_path.with_extension(get_file_extension(content_type));
+    }
+    std::fs::write(&output_path, content)?;
+    Ok(output_path)
+}
+
 /// Convert CLI content type to core content type
 fn convert_content_type(cli_type: ContentType) -> dox_core::generate::ContentType {
     match cli_type {
@@ -190,8 +450,13 @@ fn convert_content_type(cli_type: ContentType) -> dox_core::generate::ContentTyp
 }
 
 /// Detect AI provider from model name
-fn detect_provider(model: &str) -> &str {
-    if model.starts_with("gpt-") {
+fn detect_provider(model: &str) -> &'static str {
+    if LOCAL_MODEL_PREFIXES
+        .iter()
+        .any(|prefix| model.starts_with(prefix))
+    {
+        "local"
+    } else if model.starts_with("gpt-") {
         "openai"
     } else if model.starts_with("claude-") {
         "claude"
@@ -201,20 +466,43 @@ fn detect_provider(model: &str) -> &str {
 }
 
 /// Get API key from various sources
-fn get_api_key(provider: &str, cli_key: Option<&str>) -> Result<String> {
-    // Priority: CLI arg > environment variable > config file
-    if let Some(key) = cli_key {
-        return Ok(key.to_string());
-    }
+fn get_api_key(provider: &str, cli_key: Option<&str>, config: &Config) -> Result<String> {
+    api_key_with_env(provider, cli_key, config, |name| std::env::var(name).ok())
+}
 
-    let env_var = match provider {
-        "openai" => "OPENAI_API_KEY",
-        "claude" => "ANTHROPIC_API_KEY",
+/// [`get_api_key`] with the environment lookup passed in
+fn api_key_with_env(
+    provider: &str,
+    cli_key: Option<&str>,
+    config: &Config,
+    env: impl Fn(&str) -> Option<String>,
+) -> Result<String> {
+    let (env_var, config_key) = match provider {
+        "openai" => ("OPENAI_API_KEY", config.openai.api_key.as_deref()),
+        "claude" => ("ANTHROPIC_API_KEY", config.claude.api_key.as_deref()),
         _ => return Err(anyhow::anyhow!("알 수 없는 제공업체: {}", provider)),
     };
 
-    std::env::var(env_var)
-        .map_err(|_| anyhow::anyhow!("{} 환경변수 또는 --api-key 옵션이 필요합니다", env_var))
+    let env_key = env(env_var);
+    select_api_key(cli_key, env_key.as_deref(), config_key).ok_or_else(|| {
+        anyhow::anyhow!(
+            "{} 환경변수, --api-key 옵션 또는 설정 파일의 API 키가 필요합니다",
+            env_var
+        )
+    })
+}
+
+/// Pick the first non-empty key. Priority: CLI arg > environment variable > config file
+fn select_api_key(
+    cli_key: Option<&str>,
+    env_key: Option<&str>,
+    config_key: Option<&str>,
+) -> Option<String> {
+    [cli_key, env_key, config_key]
+        .into_iter()
+        .flatten()
+        .find(|key| !key.trim().is_empty())
+        .map(str::to_string)
 }
 
 /// Get appropriate file extension for content type
@@ -231,18 +519,6 @@ fn get_file_extension(content_type: ContentType) -> &'static str {
 }
 
 impl ContentType {
-    fn as_str(&self) -> &str {
-        match self {
-            ContentType::Blog => "blog",
-            ContentType::Documentation => "documentation",
-            ContentType::Report => "report",
-            ContentType::Summary => "summary",
-            ContentType::Email => "email",
-            ContentType::Proposal => "proposal",
-            ContentType::Custom => "custom",
-        }
-    }
-
     fn as_str_ko(&self) -> &str {
         match self {
             ContentType::Blog => "블로그",
@@ -255,3 +531,365 @@ impl ContentType {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Generator recording the request it receives and streaming fixed chunks
+    struct MockGenerator {
+        chunks: Vec<&'static str>,
+        received: Mutex<Option<GenerationRequest>>,
+    }
+
+    impl MockGenerator {
+        fn new(chunks: Vec<&'static str>) -> Self {
+            Self {
+                chunks,
+                received: Mutex::new(None),
+            }
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl ContentGenerator for MockGenerator {
+        async fn generate(&self, request: &GenerationRequest) -> Result<GenerationResponse> {
+            *self.received.lock().unwrap() = Some(request.clone());
+            Ok(GenerationResponse {
+                content: self.chunks.concat(),
+                model: request.model.clone(),
+                provider: "mock".to_string(),
+                usage: None,
+                metadata: Default::default(),
+            })
+        }
+
+        async fn generate_stream(
+            &self,
+            request: &GenerationRequest,
+            on_chunk: &mut (dyn for<'c> FnMut(&'c str) + Send),
+        ) -> Result<GenerationResponse> {
+            for chunk in &self.chunks {
+                on_chunk(chunk);
+            }
+            self.generate(request).await
+        }
+
+        fn provider_name(&self) -> &str {
+            "mock"
+        }
+
+        fn supported_models(&self) -> Vec<String> {
+            vec!["mock-model".to_string()]
+        }
+    }
+
+    fn args(extra: &[&str]) -> GenerateArgs {
+        use clap::Parser;
+
+        #[derive(Parser)]
+        struct TestCli {
+            #[command(flatten)]
+            args: GenerateArgs,
+        }
+
+        let mut argv = vec!["dox", "--prompt", "테스트"];
+        argv.extend_from_slice(extra);
+        TestCli::parse_from(argv).args
+    }
+
+    /// Request built from `args` without a configuration file
+    fn request(args: &GenerateArgs) -> GenerationRequest {
+        let config = Config::default();
+        let model = resolve_model(args, provider_name(args, &config), &config);
+        build_request(args, model, &config)
+    }
+
+    #[test]
+    fn test_local_model_from_config() {
+        let mut config = Config::default();
+        config.local.model = Some("llama3".to_string());
+
+        let local = args(&["--provider", "local"]);
+        let request = build_request(
+            &local,
+            resolve_model(&local, provider_name(&local, &config), &config),
+            &config,
+        );
+        assert_eq!(request.model, "llama3");
+
+        // --model wins, and other providers ignore the local model
+        let named = args(&["--provider", "local", "--model", "ollama/qwen2"]);
+        assert_eq!(
+            resolve_model(&named, provider_name(&named, &config), &config),
+            "ollama/qwen2"
+        );
+        let openai = args(&[]);
+        assert_eq!(provider_name(&openai, &config), "openai");
+        assert_eq!(
+            resolve_model(&openai, provider_name(&openai, &config), &config),
+            "gpt-3.5-turbo"
+        );
+    }
+
+    #[test]
+    fn test_generate_settings_from_config() {
+        let mut config = Config::default();
+        config.generate.model = "gpt-4".to_string();
+        config.generate.max_tokens = 512;
+        config.generate.temperature = 0.2;
+
+        // The configured model also selects the provider
+        let defaults = args(&[]);
+        let provider = provider_name(&defaults, &config);
+        assert_eq!(provider, "openai");
+        let request = build_request(
+            &defaults,
+            resolve_model(&defaults, provider, &config),
+            &config,
+        );
+        assert_eq!(request.model, "gpt-4");
+        assert_eq!(request.max_tokens, 512);
+        assert_eq!(request.temperature, 0.2);
+
+        // Flags win over the configuration
+        let flags = args(&[
+            "--model",
+            "claude-3-5-haiku-20241022",
+            "--max-tokens",
+            "100",
+            "--temperature",
+            "0.9",
+        ]);
+        let provider = provider_name(&flags, &config);
+        assert_eq!(provider, "claude");
+        let request = build_request(&flags, resolve_model(&flags, provider, &config), &config);
+        assert_eq!(request.model, "claude-3-5-haiku-20241022");
+        assert_eq!(request.max_tokens, 100);
+        assert_eq!(request.temperature, 0.9);
+    }
+
+    #[tokio::test]
+    async fn test_request_fields_passed_to_generator() {
+        let args = args(&[
+            "--model",
+            "claude-3-5-haiku-20241022",
+            "--temperature",
+            "0.2",
+            "--max-tokens",
+            "512",
+            "-t",
+            "report",
+        ]);
+        let generator = MockGenerator::new(vec!["done"]);
+
+        let response = generator.generate(&request(&args)).await.unwrap();
+        assert_eq!(response.content, "done");
+
+        let received = generator.received.lock().unwrap().take().unwrap();
+        assert_eq!(received.model, "claude-3-5-haiku-20241022");
+        assert_eq!(received.temperature, 0.2);
+        assert_eq!(received.max_tokens, 512);
+        assert_eq!(
+            received.content_type,
+            dox_core::generate::ContentType::Report
+        );
+        assert!(!received.stream);
+    }
+
+    #[tokio::test]
+    async fn test_streaming_writes_chunks_incrementally() {
+        let request = request(&args(&["--stream"]));
+        assert!(request.stream);
+
+        let generator = MockGenerator::new(vec!["첫 번째 ", "두 번째"]);
+        let mut out = Vec::new();
+        let response = run_generation(&generator, &request, &mut out)
+            .await
+            .unwrap();
+
+        assert_eq!(String::from_utf8(out).unwrap(), "첫 번째 두 번째");
+        assert_eq!(response.content, "첫 번째 두 번째");
+
+        let received = generator.received.lock().unwrap().take().unwrap();
+        assert_eq!(received.model, "gpt-3.5-turbo");
+        assert_eq!(received.max_tokens, 2000);
+        assert_eq!(received.temperature, 0.7);
+    }
+
+    /// Writer keeping each write separately to observe chunk boundaries
+    #[derive(Default)]
+    struct WriteLog(Vec<String>);
+
+    impl Write for WriteLog {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.push(String::from_utf8_lossy(buf).into_owned());
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_streaming_with_output_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let args = args(&[
+            "--stream",
+            "-t",
+            "blog",
+            "-o",
+            dir.path().join("draft").to_str().unwrap(),
+        ]);
+        let request = request(&args);
+
+        let generator = MockGenerator::new(vec!["# 제목\n", "본문 ", "끝"]);
+        let mut out = WriteLog::default();
+        let response = run_generation(&generator, &request, &mut out)
+            .await
+            .unwrap();
+        assert_eq!(out.0, ["# 제목\n", "본문 ", "끝"]);
+
+        let path = save_output(args.output.unwrap(), args.content_type, &response.content).unwrap();
+        assert_eq!(path.extension().unwrap(), "md");
+        assert_eq!(std::fs::read_to_string(path).unwrap(), "# 제목\n본문 끝");
+    }
+
+    #[tokio::test]
+    async fn test_streaming_falls_back_to_single_chunk() {
+        /// Generator relying on the default `generate_stream`
+        struct BlockingGenerator;
+
+        #[async_trait::async_trait]
+        impl ContentGenerator for BlockingGenerator {
+            async fn generate(&self, request: &GenerationRequest) -> Result<GenerationResponse> {
+                Ok(GenerationResponse {
+                    content: "전체 응답".to_string(),
+                    model: request.model.clone(),
+                    provider: "blocking".to_string(),
+                    usage: None,
+                    metadata: Default::default(),
+                })
+            }
+
+            fn provider_name(&self) -> &str {
+                "blocking"
+            }
+
+            fn supported_models(&self) -> Vec<String> {
+                vec![]
+            }
+        }
+
+        let request = request(&args(&["--stream"]));
+        let mut out = WriteLog::default();
+        let response = run_generation(&BlockingGenerator, &request, &mut out)
+            .await
+            .unwrap();
+
+        assert_eq!(out.0, ["전체 응답"]);
+        assert_eq!(response.content, "전체 응답");
+    }
+
+    #[test]
+    fn test_budget_guard() {
+        let mut request = request(&args(&["--model", "gpt-4"]));
+        request.prompt = "a".repeat(40_000);
+
+        // About 10,000 prompt tokens at $30 per million
+        let err = check_budget(&request, Some(0.01)).unwrap_err();
+        assert!(err.to_string().contains("예산"), "{}", err);
+        assert!(check_budget(&request, Some(1.0)).is_ok());
+        assert!(check_budget(&request, None).is_ok());
+        assert!(check_budget(&request, Some(-1.0)).is_err());
+
+        request.model = "unknown-model".to_string();
+        assert!(check_budget(&request, Some(1.0)).is_err());
+        assert!(check_budget(&request, None).is_ok());
+    }
+
+    #[test]
+    fn test_detect_provider() {
+        assert_eq!(detect_provider("gpt-4o"), "openai");
+        assert_eq!(detect_provider("claude-3-5-sonnet-20241022"), "claude");
+        assert_eq!(detect_provider("ollama/llama3"), "local");
+        assert_eq!(detect_provider("local/qwen2:7b"), "local");
+    }
+
+    #[test]
+    fn test_provider_error_types_transport_failures() {
+        let transport = reqwest::Client::new()
+            .get("http://[::1")
+            .build()
+            .unwrap_err();
+        let err = provider_error(anyhow::Error::from(transport));
+        assert!(matches!(
+            err.downcast_ref::<DoxError>(),
+            Some(DoxError::NetworkError { .. })
+        ));
+
+        // Errors that already carry a type are left alone
+        let err = provider_error(DoxError::from_http_status("openai", 429, "slow down").into());
+        assert!(matches!(
+            err.downcast_ref::<DoxError>(),
+            Some(DoxError::RateLimited { .. })
+        ));
+    }
+
+    #[test]
+    fn test_api_key_priority() {
+        assert_eq!(
+            select_api_key(Some("cli"), Some("env"), Some("config")).as_deref(),
+            Some("cli")
+        );
+        assert_eq!(
+            select_api_key(None, Some("env"), Some("config")).as_deref(),
+            Some("env")
+        );
+        assert_eq!(
+            select_api_key(None, Some(""), Some("config")).as_deref(),
+            Some("config")
+        );
+        assert_eq!(select_api_key(None, None, None), None);
+    }
+
+    #[test]
+    fn test_api_key_from_config_section() {
+        let mut config = Config::default();
+        config.claude.api_key = Some("sk-ant-config".to_string());
+
+        let no_env = |_: &str| None;
+        let key = api_key_with_env("claude", Some("sk-ant-cli"), &config, no_env).unwrap();
+        assert_eq!(key, "sk-ant-cli");
+
+        config.openai.api_key = Some("sk-openai-config".to_string());
+        assert_eq!(
+            api_key_with_env("openai", None, &config, no_env).unwrap(),
+            "sk-openai-config"
+        );
+
+        // The environment variable wins over the config file
+        let env = |name: &str| (name == "OPENAI_API_KEY").then(|| "sk-openai-env".to_string());
+        assert_eq!(
+            api_key_with_env("openai", None, &config, env).unwrap(),
+            "sk-openai-env"
+        );
+
+        config.openai.api_key = None;
+        assert!(api_key_with_env("openai", None, &config, no_env).is_err());
+    }
+
+    #[test]
+    fn test_cli_key_overrides_env_config() {
+        let mut config = Config::default();
+        config
+            .apply_env_overrides([("DOX_CLAUDE_API_KEY".to_string(), "sk-ant-env".to_string())])
+            .unwrap();
+        assert_eq!(config.claude.api_key.as_deref(), Some("sk-ant-env"));
+
+        let key = get_api_key("claude", Some("sk-ant-cli"), &config).unwrap();
+        assert_eq!(key, "sk-ant-cli");
+    }
+}
@@ -1,13 +1,38 @@
 pub mod config;
+pub mod convert;
 pub mod create;
+pub mod diff;
+#[cfg(feature = "excel")]
+pub mod excel;
 pub mod extract;
 pub mod generate;
 pub mod replace;
 pub mod template;
+mod watch;
 
 pub use config::ConfigArgs;
+pub use convert::ConvertArgs;
 pub use create::CreateArgs;
+pub use diff::DiffArgs;
+#[cfg(feature = "excel")]
+pub use excel::ExcelArgs;
 pub use extract::ExtractArgs;
 pub use generate::GenerateArgs;
 pub use replace::ReplaceArgs;
 pub use template::TemplateArgs;
+
+/// Token that is cancelled when the user presses Ctrl+C
+///
+/// Batch commands use it to stop after the file currently being processed
+/// instead of being killed halfway through writing a document.
+pub(crate) fn cancel_on_ctrl_c() -> dox_document::CancellationToken {
+    let token = dox_document::CancellationToken::new();
+    let handle = token.clone();
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            dox_core::utils::ui::print_warning("취소 요청됨: 현재 파일까지 처리 후 중단합니다");
+            handle.cancel();
+        }
+    });
+    token
+}
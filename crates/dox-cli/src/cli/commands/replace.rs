@@ -41,7 +41,7 @@ pub struct ReplaceArgs {
     #[arg(long)]
     pub dry_run: bool,
 
-    /// 수정 전 백업 파일 생성
+    /// 수정 전 원본을 <파일>.bak으로 백업
     #[arg(long)]
     pub backup: bool,
 
@@ -86,9 +86,28 @@ pub struct ReplaceArgs {
     /// 교체할 텍스트 (--find와 함께 사용)
     #[arg(short = 't', long, value_name = "교체텍스트")]
     pub to: Option<String>,
+
+    /// 규칙 파일이 바뀔 때마다 다시 실행 (Ctrl+C로 종료)
+    ///
+    /// 치환 결과로 문서가 바뀌므로 대상 문서는 감시하지 않습니다.
+    #[arg(long, requires = "rules")]
+    pub watch: bool,
+
+    /// 파일별 처리 결과와 SHA-256 해시를 JSON 매니페스트로 저장
+    #[arg(long, value_name = "파일")]
+    pub manifest: Option<PathBuf>,
 }
 
 pub async fn execute(args: ReplaceArgs) -> Result<()> {
+    if let (true, Some(rules)) = (args.watch, &args.rules) {
+        let rules = rules.clone();
+        let args = &args;
+        return super::watch::watch(&[rules], move || run(args)).await;
+    }
+    run(&args).await
+}
+
+async fn run(args: &ReplaceArgs) -> Result<()> {
     use dox_core::utils::ui;
     use dox_document::replace::{Replacer, Rule};
     use dox_document::validate_file_access;
@@ -173,13 +192,28 @@ pub async fn execute(args: ReplaceArgs) -> Result<()> {
         dry_run: args.dry_run,
         backup: args.backup,
         recursive: args.recursive,
-        exclude: args.exclude,
+        exclude: args.exclude.clone(),
         concurrent: args.concurrent,
         max_workers: args.max_workers,
         show_diff: args.show_diff,
+        progress: None,
+        cancel: Some(super::cancel_on_ctrl_c()),
+        checksums: args.manifest.is_some(),
     };
 
     let results = replacer.process_path(&args.path, options).await?;
+    if let Some(manifest) = &args.manifest {
+        let content =
+            serde_json::to_string_pretty(&serde_json::json!({ "files": &results.files }))?;
+        std::fs::write(manifest, content)?;
+        if !ui::json_output() {
+            ui::print_info(&format!("매니페스트 저장: {}", manifest.display()));
+        }
+    }
+    if ui::json_output() {
+        ui::print_json(&results)?;
+        return Ok(());
+    }
 
     // Display summary
     ui::print_header("요약");
@@ -188,6 +222,13 @@ pub async fn execute(args: ReplaceArgs) -> Result<()> {
         results.files_processed, results.total_replacements
     ));
 
+    if results.skipped > 0 {
+        ui::print_warning(&format!("{}개 파일을 건너뛰었습니다", results.skipped));
+        for (path, reason) in &results.skipped_files {
+            ui::print_info(&format!("  {}: {}", path.display(), reason));
+        }
+    }
+
     if results.errors > 0 {
         ui::print_error(&format!(
             "{}개 파일에서 오류가 발생했습니다",
@@ -195,6 +236,10 @@ pub async fn execute(args: ReplaceArgs) -> Result<()> {
         ));
     }
 
+    if results.cancelled {
+        ui::print_warning("작업이 취소되어 나머지 파일은 변경되지 않았습니다");
+    }
+
     Ok(())
 }
 
@@ -1,5 +1,6 @@
 use anyhow::Result;
 use clap::Args;
+use serde_json::Value;
 use std::path::PathBuf;
 
 /// Process document templates with placeholder replacement
@@ -24,18 +25,124 @@ pub struct TemplateArgs {
     /// Overwrite existing files without prompting
     #[arg(long)]
     pub force: bool,
+
+    /// Re-run whenever the template or values file changes, until Ctrl+C
+    #[arg(long)]
+    pub watch: bool,
 }
 
 pub async fn execute(args: TemplateArgs) -> Result<()> {
     use dox_core::utils::ui;
 
-    ui::print_info(&format!(
-        "Processing template '{}'...",
-        args.template.display()
-    ));
+    if !args.template.is_file() {
+        return Err(anyhow::anyhow!(
+            "Template file not found: {}",
+            args.template.display()
+        ));
+    }
+
+    // Only the first run asks; later runs in watch mode rewrite their own output
+    if args.output.exists() && !args.force {
+        ui::print_warning(&format!(
+            "Output file '{}' already exists. Use --force to overwrite.",
+            args.output.display()
+        ));
+        return Ok(());
+    }
+
+    if args.watch {
+        let mut files = vec![args.template.clone()];
+        files.extend(args.values.clone());
+        let args = &args;
+        return super::watch::watch(&files, move || async move { render(args) }).await;
+    }
+
+    render(&args)
+}
+
+/// Fill the template's `{{key}}` placeholders and save it as the output
+fn render(args: &TemplateArgs) -> Result<()> {
+    use dox_core::utils::ui;
+    use dox_document::{Document, TemplateProcessor};
+
+    let values = load_values(args)?;
+    let mut document = Document::open(&args.template)?;
+    let text = document.get_text()?;
 
-    // TODO: Implement template processing logic
-    ui::print_warning("Template command is not yet implemented in the Rust version");
+    // Fails listing every placeholder without a value
+    let processor = TemplateProcessor::new();
+    processor.process(&text, &values)?;
 
+    let pattern = regex::Regex::new(r"\{\{[^{}]*\}\}").expect("valid placeholder pattern");
+    let mut placeholders: Vec<&str> = pattern.find_iter(&text).map(|m| m.as_str()).collect();
+    placeholders.sort_unstable();
+    placeholders.dedup();
+
+    let mut replaced = 0;
+    for placeholder in placeholders {
+        let value = processor.process(placeholder, &values)?;
+        replaced += document.replace_text(placeholder, &value)?;
+    }
+
+    if let Some(parent) = args.output.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    document.save_as(&args.output)?;
+
+    ui::print_success(&format!(
+        "Created '{}' ({} placeholders filled)",
+        args.output.display(),
+        replaced
+    ));
     Ok(())
 }
+
+/// Values from `--values` with `--set` entries on top
+///
+/// A dotted `--set` key such as `user.name` sets a nested value.
+fn load_values(args: &TemplateArgs) -> Result<Value> {
+    let mut values = match &args.values {
+        Some(path) => {
+            let content = std::fs::read_to_string(path).map_err(|e| {
+                anyhow::anyhow!("Failed to read values file {}: {}", path.display(), e)
+            })?;
+            // YAML is a superset of JSON, so one parser reads both
+            serde_yaml::from_str::<Value>(&content)
+                .map_err(|e| anyhow::anyhow!("Invalid values file {}: {}", path.display(), e))?
+        }
+        None => Value::Object(Default::default()),
+    };
+    if !values.is_object() {
+        return Err(anyhow::anyhow!(
+            "The values file must contain a mapping of keys to values"
+        ));
+    }
+
+    for entry in &args.set {
+        let (key, value) = entry
+            .split_once('=')
+            .ok_or_else(|| anyhow::anyhow!("Invalid --set '{}', expected KEY=VALUE", entry))?;
+        set_path(&mut values, key.trim(), Value::String(value.to_string()));
+    }
+
+    Ok(values)
+}
+
+/// Set `value` at a dotted `key`, replacing non-object values on the way
+fn set_path(values: &mut Value, key: &str, value: Value) {
+    let mut current = values;
+    let mut segments = key.split('.').peekable();
+    while let Some(segment) = segments.next() {
+        if !current.is_object() {
+            *current = Value::Object(Default::default());
+        }
+        let map = current.as_object_mut().expect("just made an object");
+        if segments.peek().is_none() {
+            map.insert(segment.to_string(), value);
+            return;
+        }
+        current = map
+            .entry(segment)
+            .or_insert_with(|| Value::Object(Default::default()));
+    }
+}
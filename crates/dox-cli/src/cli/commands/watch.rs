@@ -0,0 +1,196 @@
+//! Re-running a command when its input files change
+
+use anyhow::Result;
+use dox_core::utils::ui;
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// Quiet period after the last change before re-running; editors often
+/// write a file several times per save
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Run `run` once, then again whenever one of `files` changes, until Ctrl+C
+///
+/// The parent directories are watched rather than the files, so a file
+/// that an editor replaces on save stays watched. A failed run is reported
+/// and watching goes on.
+pub(crate) async fn watch<F, Fut>(files: &[PathBuf], run: F) -> Result<()>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<()>>,
+{
+    let files: Vec<PathBuf> = files.iter().map(|file| absolute(file)).collect();
+    let (sender, events) = mpsc::unbounded_channel();
+    let mut watcher = notify::recommended_watcher(move |event| {
+        // The receiver is gone once watching has stopped
+        let _ = sender.send(event);
+    })?;
+
+    let mut directories: Vec<&Path> = files.iter().filter_map(|file| file.parent()).collect();
+    directories.sort();
+    directories.dedup();
+    for directory in directories {
+        watcher.watch(directory, RecursiveMode::NonRecursive)?;
+    }
+
+    let shutdown = async {
+        let _ = tokio::signal::ctrl_c().await;
+    };
+    let runs = watch_events(&files, events, shutdown, DEBOUNCE, run).await?;
+    ui::print_info(&format!("감시를 종료합니다 ({}회 실행)", runs));
+    Ok(())
+}
+
+/// The watch loop over a stream of file system events; returns the number
+/// of runs once `shutdown` completes or the events stop
+async fn watch_events<F, Fut>(
+    files: &[PathBuf],
+    mut events: mpsc::UnboundedReceiver<notify::Result<Event>>,
+    shutdown: impl Future<Output = ()>,
+    debounce: Duration,
+    mut run: F,
+) -> Result<usize>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<()>>,
+{
+    tokio::pin!(shutdown);
+    let mut runs = 0;
+
+    loop {
+        runs += 1;
+        if let Err(e) = run().await {
+            ui::print_error(&format!("{:#}", e));
+        }
+        ui::print_info("파일 변경을 감시하는 중입니다 (Ctrl+C로 종료)");
+
+        // Wait for a change to one of the files
+        let changed = loop {
+            tokio::select! {
+                _ = &mut shutdown => return Ok(runs),
+                event = events.recv() => match event {
+                    None => return Ok(runs),
+                    Some(event) => {
+                        if let Some(file) = changed_file(files, event) {
+                            break file;
+                        }
+                    }
+                },
+            }
+        };
+
+        // Let a burst of writes settle before running again
+        loop {
+            tokio::select! {
+                _ = &mut shutdown => return Ok(runs),
+                event = tokio::time::timeout(debounce, events.recv()) => match event {
+                    Err(_) | Ok(None) => break,
+                    Ok(Some(_)) => {}
+                },
+            }
+        }
+
+        ui::print_info(&format!(
+            "변경 감지: {} ({})",
+            changed.display(),
+            chrono::Local::now().format("%H:%M:%S")
+        ));
+    }
+}
+
+/// The watched file that `event` modifies, if any
+fn changed_file(files: &[PathBuf], event: notify::Result<Event>) -> Option<PathBuf> {
+    let event = match event {
+        Ok(event) => event,
+        Err(e) => {
+            ui::print_warning(&format!("파일 감시 오류: {}", e));
+            return None;
+        }
+    };
+    if !matches!(
+        event.kind,
+        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+    ) {
+        return None;
+    }
+    event
+        .paths
+        .iter()
+        .map(|path| absolute(path))
+        .find(|path| files.contains(path))
+}
+
+fn absolute(path: &Path) -> PathBuf {
+    std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{DataChange, ModifyKind};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn modified(path: &Path) -> notify::Result<Event> {
+        Ok(
+            Event::new(EventKind::Modify(ModifyKind::Data(DataChange::Content)))
+                .add_path(path.into()),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_watch_reruns_on_change() {
+        let dir = tempfile::tempdir().unwrap();
+        let rules = dir.path().join("rules.yaml");
+        let (sender, events) = mpsc::unbounded_channel();
+        let runs = AtomicUsize::new(0);
+
+        // Two quick saves of the watched file and a change to another file
+        sender.send(modified(&rules)).unwrap();
+        sender.send(modified(&rules)).unwrap();
+        sender
+            .send(modified(&dir.path().join("notes.txt")))
+            .unwrap();
+
+        let shutdown = tokio::time::sleep(Duration::from_millis(500));
+        let count = watch_events(
+            &[absolute(&rules)],
+            events,
+            shutdown,
+            Duration::from_millis(50),
+            || async {
+                runs.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            },
+        )
+        .await
+        .unwrap();
+
+        // The initial run, then one run for the debounced saves
+        assert_eq!(count, 2);
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_watch_keeps_going_after_failed_run() {
+        let dir = tempfile::tempdir().unwrap();
+        let template = dir.path().join("template.docx");
+        let (sender, events) = mpsc::unbounded_channel();
+        sender.send(modified(&template)).unwrap();
+        // Closing the channel ends the loop once the events are handled
+        drop(sender);
+
+        let count = watch_events(
+            &[absolute(&template)],
+            events,
+            std::future::pending(),
+            Duration::from_millis(10),
+            || async { Err(anyhow::anyhow!("bad template")) },
+        )
+        .await
+        .unwrap();
+        assert_eq!(count, 2);
+    }
+}
@@ -1,5 +1,7 @@
 use anyhow::Result;
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
+use dox_core::i18n::messages;
+use std::ffi::OsString;
 use std::path::PathBuf;
 
 pub mod commands;
@@ -20,6 +22,10 @@ pub struct Cli {
     #[arg(short, long, value_name = "파일", global = true)]
     pub config: Option<PathBuf>,
 
+    /// 사용할 설정 프로필 (기본 설정 위에 덮어씀, DOX_PROFILE 대신 사용)
+    #[arg(long, value_name = "이름", global = true)]
+    pub profile: Option<String>,
+
     /// 상세 출력 활성화
     #[arg(short, long, global = true, conflicts_with = "quiet")]
     pub verbose: bool,
@@ -36,10 +42,26 @@ pub struct Cli {
     #[arg(long, global = true, value_name = "언어")]
     pub lang: Option<String>,
 
+    /// 결과 출력 형식 (json: 결과와 오류를 한 줄짜리 JSON으로 표준출력에 출력)
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        default_value = "human",
+        value_name = "형식"
+    )]
+    pub output_format: OutputFormat,
+
     #[command(subcommand)]
     pub command: Commands,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    Human,
+    Json,
+}
+
 #[derive(Subcommand, Debug)]
 pub enum Commands {
     /// YAML 규칙 파일을 사용하여 문서의 텍스트 치환
@@ -57,25 +79,169 @@ pub enum Commands {
     /// 문서에서 텍스트 추출
     Extract(ExtractArgs),
 
+    /// 문서를 다른 형식으로 변환 (docx→markdown, xlsx→csv, pdf→text 등)
+    Convert(ConvertArgs),
+
+    /// 두 문서의 텍스트를 문단 단위로 비교
+    Diff(DiffArgs),
+
     /// 설정 관리
     Config(ConfigArgs),
+
+    /// Excel 파일 읽기, 쓰기, 차트 생성
+    #[cfg(feature = "excel")]
+    Excel(ExcelArgs),
 }
 
 impl Cli {
+    /// Parse the process arguments, with help text in the interface language
+    ///
+    /// The config file is not read yet, so help follows `--lang` and the
+    /// locale environment only.
+    pub fn parse_localized() -> Self {
+        let args: Vec<OsString> = std::env::args_os().collect();
+        let lang = dox_core::i18n::resolve_language(lang_arg(&args).as_deref(), None);
+
+        let matches = localized_command(lang).get_matches_from(args);
+        Self::from_arg_matches(&matches).unwrap_or_else(|e| e.exit())
+    }
+
     pub async fn execute(self) -> Result<()> {
         // Apply global settings
         if self.no_color || std::env::var("NO_COLOR").is_ok() {
             colored::control::set_override(false);
         }
+        dox_core::utils::ui::set_json_output(self.output_format == OutputFormat::Json);
 
         // Execute the command
         match self.command {
             Commands::Replace(args) => replace::execute(args).await,
             Commands::Create(args) => create::execute(args).await,
             Commands::Template(args) => template::execute(args).await,
-            Commands::Generate(args) => generate::execute(args).await,
+            Commands::Generate(args) => {
+                generate::execute(args, self.config.as_deref(), self.profile.as_deref()).await
+            }
             Commands::Extract(args) => extract::execute(args).await,
-            Commands::Config(args) => config::execute(args, self.config.as_deref()).await,
+            Commands::Convert(args) => convert::execute(args).await,
+            Commands::Diff(args) => diff::execute(args).await,
+            #[cfg(feature = "excel")]
+            Commands::Excel(args) => excel::execute(args).await,
+            Commands::Config(args) => {
+                config::execute(args, self.config.as_deref(), self.profile.as_deref()).await
+            }
+        }
+    }
+}
+
+/// Value of `--lang` before full parsing, ignoring anything after `--`
+fn lang_arg(args: &[OsString]) -> Option<String> {
+    let mut args = args.iter().skip(1).map(|arg| arg.to_string_lossy());
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            break;
         }
+        if arg == "--lang" {
+            return args.next().map(|value| value.into_owned());
+        }
+        if let Some(value) = arg.strip_prefix("--lang=") {
+            return Some(value.to_string());
+        }
+    }
+    None
+}
+
+/// The CLI definition with descriptions from the message table
+///
+/// Commands use `cmd.<name>`, global options `opt.<id>` and command options
+/// `opt.<command>.<id>`. A translation replaces the long description too;
+/// text without one keeps the Korean description from the source.
+fn localized_command(lang: &str) -> clap::Command {
+    let text = |key: &str| messages::get(lang, key);
+
+    let mut command = Cli::command();
+    if let Some(about) = text("app.description") {
+        command = command.about(about);
+    }
+
+    let global_ids: Vec<String> = command
+        .get_arguments()
+        .map(|arg| arg.get_id().to_string())
+        .collect();
+    for id in &global_ids {
+        if let Some(help) = text(&format!("opt.{}", id)) {
+            command = command.mut_arg(id, |arg| arg.help(help).long_help(None::<&str>));
+        }
+    }
+
+    let names: Vec<String> = command
+        .get_subcommands()
+        .map(|sub| sub.get_name().to_string())
+        .collect();
+    for name in names {
+        command = command.mut_subcommand(&name, |mut sub| {
+            if let Some(about) = text(&format!("cmd.{}", name)) {
+                sub = sub.about(about).long_about(None::<&str>);
+            }
+            let ids: Vec<String> = sub
+                .get_arguments()
+                .map(|arg| arg.get_id().to_string())
+                .collect();
+            for id in ids {
+                if let Some(help) = text(&format!("opt.{}.{}", name, id)) {
+                    sub = sub.mut_arg(&id, |arg| arg.help(help).long_help(None::<&str>));
+                }
+            }
+            sub
+        });
+    }
+
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn os_args(args: &[&str]) -> Vec<OsString> {
+        args.iter().map(OsString::from).collect()
+    }
+
+    #[test]
+    fn test_lang_arg() {
+        assert_eq!(
+            lang_arg(&os_args(&["dox", "--lang", "en", "extract"])).as_deref(),
+            Some("en")
+        );
+        assert_eq!(
+            lang_arg(&os_args(&["dox", "extract", "--lang=ko"])).as_deref(),
+            Some("ko")
+        );
+        assert_eq!(lang_arg(&os_args(&["dox", "--", "--lang", "en"])), None);
+    }
+
+    #[test]
+    fn test_localized_command() {
+        let command = localized_command("en");
+        assert_eq!(
+            command
+                .find_subcommand("extract")
+                .unwrap()
+                .get_about()
+                .unwrap()
+                .to_string(),
+            "Extract text from documents"
+        );
+        command.debug_assert();
+
+        let command = localized_command("ko");
+        assert_eq!(
+            command
+                .find_subcommand("extract")
+                .unwrap()
+                .get_about()
+                .unwrap()
+                .to_string(),
+            "문서에서 텍스트 추출"
+        );
     }
 }
@@ -1,16 +1,15 @@
 use anyhow::Result;
-use clap::Parser;
 use dox_core::{config::Config, DoxError, ErrorReporter, LogConfig, LogFormat};
 use tracing::{debug, error, info};
 
 mod cli;
 
-use cli::Cli;
+use cli::{Cli, OutputFormat};
 
 #[tokio::main]
 async fn main() -> Result<()> {
     // Parse CLI arguments first to get verbosity level
-    let cli = Cli::parse();
+    let cli = Cli::parse_localized();
 
     // Load configuration with priority: --config flag > default config file
     let config = load_config_with_priority(&cli)?;
@@ -18,7 +17,7 @@ async fn main() -> Result<()> {
     // Initialize logging based on CLI flags and config
     let log_config = get_log_config(&cli, &config);
     let is_verbose = is_verbose(&cli, &config);
-    dox_core::init_logging(log_config)?;
+    let log_guard = dox_core::init_logging(log_config)?;
 
     // Apply global settings from config
     apply_global_settings(&cli, &config);
@@ -27,12 +26,20 @@ async fn main() -> Result<()> {
     debug!("명령어: {:?}", std::env::args().collect::<Vec<_>>());
 
     // Execute command and handle errors properly
+    let json_output = cli.output_format == OutputFormat::Json;
     match cli.execute().await {
         Ok(()) => {
             info!("명령어가 성공적으로 완료되었습니다");
             Ok(())
         }
         Err(err) => {
+            if json_output {
+                error!("명령어 실행 실패: {}", err);
+                ErrorReporter::report_json(&err);
+                drop(log_guard);
+                std::process::exit(1);
+            }
+
             // Try to downcast to DoxError for better error reporting
             if let Some(dox_err) = err.downcast_ref::<DoxError>() {
                 error!("명령어 실행 실패: {}", dox_err);
@@ -41,6 +48,8 @@ async fn main() -> Result<()> {
                 error!("명령어 실행 실패: {}", err);
                 ErrorReporter::report_generic(&*err, "명령어 실행 실패");
             }
+            // Flush the log file before exiting
+            drop(log_guard);
             std::process::exit(1);
         }
     }
@@ -50,10 +59,10 @@ async fn main() -> Result<()> {
 fn load_config_with_priority(cli: &Cli) -> Result<Config> {
     if let Some(config_path) = &cli.config {
         debug!("사용자 지정 설정 파일 로딩: {:?}", config_path);
-        Config::load_from(config_path)
+        Config::load_profile_from(config_path, cli.profile.as_deref())
     } else {
         debug!("기본 설정 파일 로딩");
-        Config::load()
+        Config::load_profile_from(&Config::default_path()?, cli.profile.as_deref())
     }
 }
 
@@ -64,9 +73,24 @@ fn apply_global_settings(cli: &Cli, config: &Config) {
     if no_color {
         colored::control::set_override(false);
     }
+
+    // Interface language: --lang flag > config file > locale > Korean
+    dox_core::i18n::set_language(dox_core::i18n::resolve_language(
+        cli.lang.as_deref(),
+        config.global.lang.as_deref(),
+    ));
 }
 
 fn get_log_config(cli: &Cli, config: &Config) -> LogConfig {
+    let mut log_config = base_log_config(cli, config);
+    // DOX_LOG_FILE: 표준오류와 함께 파일에도 로그 기록
+    log_config.file = std::env::var_os("DOX_LOG_FILE")
+        .filter(|path| !path.is_empty())
+        .map(std::path::PathBuf::from);
+    log_config
+}
+
+fn base_log_config(cli: &Cli, config: &Config) -> LogConfig {
     // CLI 플래그 우선순위: CLI args > config file > env vars > defaults
     if cli.verbose {
         LogConfig::verbose()
@@ -86,6 +110,8 @@ fn get_log_config(cli: &Cli, config: &Config) -> LogConfig {
 
         let format = if std::env::var("DOX_LOG_JSON").is_ok() {
             LogFormat::Json
+        } else if std::env::var("DOX_LOG_COMPACT").is_ok() {
+            LogFormat::Compact
         } else {
             LogFormat::Pretty
         };
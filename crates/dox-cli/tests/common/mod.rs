@@ -0,0 +1,51 @@
+//! Helpers shared by the command tests
+
+// Each test binary uses only some of the helpers
+#![allow(dead_code)]
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+/// Run the dox binary without colors or progress output
+pub fn dox(args: &[&str]) -> Output {
+    dox_with_env(args, &[])
+}
+
+/// Like [`dox`], with extra environment variables for the child process
+pub fn dox_with_env(args: &[&str], vars: &[(&str, &str)]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_dox"))
+        .args(["--no-color", "--quiet"])
+        .args(args)
+        .env("NO_COLOR", "1")
+        .envs(vars.iter().copied())
+        .output()
+        .unwrap()
+}
+
+pub fn assert_success(output: &Output) {
+    assert!(
+        output.status.success(),
+        "dox failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+/// Build `<name>.docx` in `dir` from Markdown with `dox create`
+///
+/// The Markdown source is removed again, so `dir` only gains the document.
+pub fn docx_fixture(dir: &Path, name: &str, markdown: &str) -> PathBuf {
+    let source = dir.join(format!("{}.md", name));
+    std::fs::write(&source, markdown).unwrap();
+
+    let docx = dir.join(format!("{}.docx", name));
+    assert_success(&dox(&[
+        "create",
+        "--from",
+        source.to_str().unwrap(),
+        "-o",
+        docx.to_str().unwrap(),
+        "--force",
+    ]));
+    std::fs::remove_file(source).unwrap();
+    docx
+}
@@ -0,0 +1,48 @@
+use std::process::{Command, Output};
+
+/// Run `dox config` with its default config file inside `home`
+fn dox_config(home: &std::path::Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_dox"))
+        .arg("config")
+        .args(args)
+        .env("NO_COLOR", "1")
+        .env("HOME", home)
+        .env("XDG_CONFIG_HOME", home.join(".config"))
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn test_set_warns_about_misspelled_key() {
+    let home = tempfile::tempdir().unwrap();
+
+    let output = dox_config(home.path(), &["--set", "generate.temprature=0.5"]);
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("'generate.temperature'"), "{}", stderr);
+
+    let output = dox_config(home.path(), &["--set", "generate.temperature=0.5"]);
+    assert!(output.status.success());
+    assert!(!String::from_utf8_lossy(&output.stderr).contains("generate.temprature"));
+}
+
+#[test]
+fn test_strict_set_rejects_unknown_key() {
+    let home = tempfile::tempdir().unwrap();
+
+    let output = dox_config(
+        home.path(),
+        &["--set", "generate.temprature=0.5", "--strict"],
+    );
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("did you mean 'generate.temperature'?"),
+        "{}",
+        stderr
+    );
+
+    // Nothing was written
+    let output = dox_config(home.path(), &["--get", "generate.temprature"]);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("찾을 수 없습니다"));
+}
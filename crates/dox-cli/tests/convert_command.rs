@@ -0,0 +1,77 @@
+mod common;
+
+use common::{assert_success, docx_fixture, dox};
+
+const REPORT: &str = "# Quarterly Report\n\nRevenue grew this quarter.\n\n## Outlook\n\nSteady.\n";
+
+#[test]
+fn test_docx_to_markdown() {
+    let dir = tempfile::tempdir().unwrap();
+    let docx = docx_fixture(dir.path(), "report", REPORT);
+    let output = dir.path().join("out").join("report.md");
+
+    assert_success(&dox(&[
+        "convert",
+        docx.to_str().unwrap(),
+        "--to",
+        "markdown",
+        "-o",
+        output.to_str().unwrap(),
+    ]));
+
+    let markdown = std::fs::read_to_string(&output).unwrap();
+    assert!(markdown.contains("# Quarterly Report"), "{}", markdown);
+    assert!(markdown.contains("Revenue grew this quarter."));
+    assert!(markdown.contains("Outlook"));
+}
+
+#[test]
+fn test_unsupported_conversions() {
+    let dir = tempfile::tempdir().unwrap();
+    let text = dir.path().join("notes.txt");
+    std::fs::write(&text, "just text").unwrap();
+
+    let output = dox(&["convert", text.to_str().unwrap(), "--to", "csv"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("csv"));
+
+    // Without --to the format comes from the output extension
+    let output = dox(&["convert", text.to_str().unwrap(), "-o", "notes.xyz"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--to"));
+}
+
+#[cfg(feature = "excel")]
+#[test]
+fn test_xlsx_to_csv() {
+    let dir = tempfile::tempdir().unwrap();
+    let xlsx = dir.path().join("sales.xlsx");
+
+    let mut workbook = rust_xlsxwriter::Workbook::new();
+    workbook.add_worksheet().set_name("Summary").unwrap();
+    let sheet = workbook.add_worksheet().set_name("Sales").unwrap();
+    sheet.write_string(0, 0, "Month").unwrap();
+    sheet.write_string(0, 1, "Note").unwrap();
+    sheet.write_string(1, 0, "Jan").unwrap();
+    sheet.write_string(1, 1, "flat, for now").unwrap();
+    workbook.save(&xlsx).unwrap();
+
+    let csv = dir.path().join("sales.csv");
+    assert_success(&dox(&[
+        "convert",
+        xlsx.to_str().unwrap(),
+        "--sheet",
+        "Sales",
+        "-o",
+        csv.to_str().unwrap(),
+    ]));
+    assert_eq!(
+        std::fs::read_to_string(&csv).unwrap(),
+        "Month,Note\nJan,\"flat, for now\"\n"
+    );
+
+    // The first sheet is used by default and output goes to stdout
+    let output = dox(&["convert", xlsx.to_str().unwrap(), "--to", "csv"]);
+    assert_success(&output);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "");
+}
@@ -0,0 +1,96 @@
+mod common;
+
+use common::{assert_success, docx_fixture, dox};
+use std::path::{Path, PathBuf};
+
+/// Two revisions of a report that differ in one paragraph
+fn revisions(dir: &Path) -> (PathBuf, PathBuf) {
+    let old = docx_fixture(
+        dir,
+        "report_v1",
+        "# Report\n\nSales rose 5% this quarter.\n\nCosts were flat.\n",
+    );
+    let new = docx_fixture(
+        dir,
+        "report_v2",
+        "# Report\n\nSales rose 7% this quarter.\n\nCosts were flat.\n",
+    );
+    (old, new)
+}
+
+#[test]
+fn test_diff_reports_changed_paragraph() {
+    let dir = tempfile::tempdir().unwrap();
+    let (old, new) = revisions(dir.path());
+
+    let output = dox(&["diff", old.to_str().unwrap(), new.to_str().unwrap()]);
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("-Sales rose 5% this quarter."),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("+Sales rose 7% this quarter."),
+        "{}",
+        stdout
+    );
+    assert!(!stdout.contains("-Costs were flat."), "{}", stdout);
+
+    let output = dox(&[
+        "diff",
+        old.to_str().unwrap(),
+        new.to_str().unwrap(),
+        "--format",
+        "side-by-side",
+    ]);
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout
+        .lines()
+        .any(|line| line.contains("5%") && line.contains(" | ") && line.contains("7%")));
+}
+
+#[test]
+fn test_diff_json() {
+    let dir = tempfile::tempdir().unwrap();
+    let (old, new) = revisions(dir.path());
+
+    let output = dox(&[
+        "diff",
+        old.to_str().unwrap(),
+        new.to_str().unwrap(),
+        "--format",
+        "json",
+    ]);
+    assert_success(&output);
+    let diff: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(diff["summary"]["changed"], 1);
+    assert_eq!(diff["summary"]["added"], 0);
+    assert_eq!(diff["summary"]["removed"], 0);
+
+    let changes: Vec<&serde_json::Value> = diff["segments"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|segment| segment["kind"] != "unchanged")
+        .collect();
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0]["kind"], "changed");
+    assert_eq!(changes[0]["old_text"], "Sales rose 5% this quarter.");
+    assert_eq!(changes[0]["new_text"], "Sales rose 7% this quarter.");
+}
+
+#[test]
+fn test_diff_missing_input() {
+    let dir = tempfile::tempdir().unwrap();
+    let (old, _) = revisions(dir.path());
+
+    let output = dox(&[
+        "diff",
+        old.to_str().unwrap(),
+        dir.path().join("missing.docx").to_str().unwrap(),
+    ]);
+    assert!(!output.status.success());
+}
@@ -0,0 +1,143 @@
+#![cfg(feature = "excel")]
+
+mod common;
+
+use common::{assert_success, dox};
+use std::path::{Path, PathBuf};
+use std::process::Output;
+
+/// Workbook with a "Sales" sheet holding a header and two months of data
+fn sales_fixture(dir: &Path) -> PathBuf {
+    let path = dir.join("sales.xlsx");
+    let mut workbook = rust_xlsxwriter::Workbook::new();
+
+    let sheet = workbook.add_worksheet().set_name("Sales").unwrap();
+    sheet.write_string(0, 0, "Month").unwrap();
+    sheet.write_string(0, 1, "Revenue").unwrap();
+    sheet.write_string(0, 2, "Cost").unwrap();
+    for (row, (month, revenue, cost)) in [("Jan", 100.0, 40.0), ("Feb", 120.0, 55.0)]
+        .into_iter()
+        .enumerate()
+    {
+        let row = row as u32 + 1;
+        sheet.write_string(row, 0, month).unwrap();
+        sheet.write_number(row, 1, revenue).unwrap();
+        sheet.write_number(row, 2, cost).unwrap();
+    }
+    workbook.add_worksheet().set_name("Notes").unwrap();
+
+    workbook.save(&path).unwrap();
+    path
+}
+
+fn stdout(output: &Output) -> String {
+    assert_success(output);
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+#[test]
+fn test_read_range() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = sales_fixture(dir.path());
+    let file = file.to_str().unwrap();
+
+    let output = stdout(&dox(&[
+        "excel",
+        "read",
+        "-f",
+        file,
+        "--range",
+        "Sales!A2:B3",
+    ]));
+    assert_eq!(output, "Jan\t100\nFeb\t120\n");
+
+    let output = stdout(&dox(&[
+        "excel", "read", "-f", file, "--sheet", "Sales", "--json",
+    ]));
+    let values: Vec<Vec<String>> = serde_json::from_str(&output).unwrap();
+    assert_eq!(values.len(), 3);
+    assert_eq!(values[0], ["Month", "Revenue", "Cost"]);
+}
+
+#[test]
+fn test_list_sheets() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = sales_fixture(dir.path());
+
+    let output = stdout(&dox(&[
+        "--output-format",
+        "json",
+        "excel",
+        "list-sheets",
+        "-f",
+        file.to_str().unwrap(),
+    ]));
+    let sheets: Vec<serde_json::Value> = output
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(
+        sheets,
+        [
+            serde_json::json!({"이름": "Sales", "행": "3", "열": "3"}),
+            serde_json::json!({"이름": "Notes", "행": "0", "열": "0"}),
+        ]
+    );
+}
+
+#[test]
+fn test_write_then_read() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("out.xlsx");
+    let file = file.to_str().unwrap();
+
+    stdout(&dox(&[
+        "excel",
+        "write",
+        "-f",
+        file,
+        "--range",
+        "Data!A1",
+        "--data",
+        r#"[["Name","Score"],["Kim",90]]"#,
+    ]));
+
+    let output = stdout(&dox(&["excel", "read", "-f", file, "--range", "Data!"]));
+    assert_eq!(output, "Name\tScore\nKim\t90\n");
+
+    let output = dox(&["excel", "write", "-f", file, "--data", "not json"]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn test_chart() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = sales_fixture(dir.path());
+    let report = dir.path().join("chart.xlsx");
+
+    stdout(&dox(&[
+        "excel",
+        "chart",
+        "-f",
+        file.to_str().unwrap(),
+        "--sheet",
+        "Sales",
+        "--type",
+        "line",
+        "--title",
+        "Monthly",
+        "-o",
+        report.to_str().unwrap(),
+    ]));
+
+    let mut archive = zip::ZipArchive::new(std::fs::File::open(&report).unwrap()).unwrap();
+    let mut chart = String::new();
+    std::io::Read::read_to_string(
+        &mut archive.by_name("xl/charts/chart1.xml").unwrap(),
+        &mut chart,
+    )
+    .unwrap();
+    assert!(chart.contains("<c:lineChart>"));
+    assert!(chart.contains("Report!$B$2:$B$3"));
+    assert!(chart.contains("Report!$C$2:$C$3"));
+}
@@ -0,0 +1,132 @@
+mod common;
+
+use common::{assert_success, docx_fixture, dox};
+use std::path::Path;
+
+/// Directory with a Word document, a workbook, text files and a file that
+/// cannot be extracted, with one text file in a subdirectory
+fn mixed_documents(dir: &Path) {
+    docx_fixture(dir, "minutes", "# Minutes\n\nAll agreed.\n");
+
+    let mut workbook = rust_xlsxwriter::Workbook::new();
+    workbook
+        .add_worksheet()
+        .write_string(0, 0, "Budget")
+        .unwrap();
+    workbook.save(dir.join("budget.xlsx")).unwrap();
+
+    std::fs::write(dir.join("notes.txt"), "plain notes").unwrap();
+    std::fs::write(dir.join("draft.txt"), "draft notes").unwrap();
+    std::fs::write(dir.join("image.png"), [0x89, b'P', b'N', b'G']).unwrap();
+    std::fs::create_dir(dir.join("archive")).unwrap();
+    std::fs::write(dir.join("archive").join("old.txt"), "old notes").unwrap();
+}
+
+fn output_names(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = std::fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn test_extract_directory() {
+    let dir = tempfile::tempdir().unwrap();
+    let docs = dir.path().join("docs");
+    std::fs::create_dir(&docs).unwrap();
+    mixed_documents(&docs);
+    let out = dir.path().join("out");
+
+    assert_success(&dox(&[
+        "extract",
+        docs.to_str().unwrap(),
+        "--format",
+        "json",
+        "--output-dir",
+        out.to_str().unwrap(),
+        "--exclude",
+        "draft*",
+    ]));
+
+    assert_eq!(
+        output_names(&out),
+        ["budget.json", "minutes.json", "notes.json", "old.json"]
+    );
+    let notes = std::fs::read_to_string(out.join("notes.json")).unwrap();
+    assert!(notes.contains("plain notes"));
+}
+
+#[test]
+fn test_extract_glob_and_multiple_inputs() {
+    let dir = tempfile::tempdir().unwrap();
+    mixed_documents(dir.path());
+    let out = dir.path().join("out");
+
+    let pattern = dir.path().join("**").join("*.txt");
+    let output = dox(&[
+        "extract",
+        pattern.to_str().unwrap(),
+        dir.path().join("minutes.docx").to_str().unwrap(),
+        "--output-dir",
+        out.to_str().unwrap(),
+        "--exclude",
+        "old*",
+    ]);
+    assert_success(&output);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("성공: 3"));
+
+    assert_eq!(
+        output_names(&out),
+        ["draft.txt", "minutes.txt", "notes.txt"]
+    );
+
+    // A single output file cannot hold several documents
+    let output = dox(&["extract", pattern.to_str().unwrap(), "-o", "all.txt"]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn test_extract_invalid_page_range() {
+    let dir = tempfile::tempdir().unwrap();
+    let notes = dir.path().join("notes.txt");
+    std::fs::write(&notes, "plain notes").unwrap();
+
+    for pages in ["0", "5-2", "two"] {
+        let output = dox(&["extract", notes.to_str().unwrap(), "--pages", pages]);
+        assert!(!output.status.success(), "--pages {} was accepted", pages);
+        assert!(String::from_utf8_lossy(&output.stderr).contains("Invalid page range"));
+    }
+
+    // Formats without pages are extracted whole
+    let output = dox(&["extract", notes.to_str().unwrap(), "--pages", "2-3"]);
+    assert_success(&output);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("plain notes"));
+}
+
+#[test]
+fn test_extract_detects_type_from_content() {
+    let dir = tempfile::tempdir().unwrap();
+    let docx = docx_fixture(dir.path(), "minutes", "# Minutes\n\nAll agreed.\n");
+    let renamed = dir.path().join("renamed.dat");
+    std::fs::copy(&docx, &renamed).unwrap();
+    let upper = dir.path().join("REPORT.DOCX");
+    std::fs::rename(&docx, &upper).unwrap();
+
+    for path in [&renamed, &upper] {
+        let output = dox(&["extract", path.to_str().unwrap()]);
+        assert_success(&output);
+        assert!(String::from_utf8_lossy(&output.stdout).contains("All agreed."));
+    }
+
+    // Directory scans match extensions in any case
+    let out = dir.path().join("out");
+    assert_success(&dox(&[
+        "extract",
+        dir.path().to_str().unwrap(),
+        "--output-dir",
+        out.to_str().unwrap(),
+    ]));
+    assert_eq!(output_names(&out), ["REPORT.txt"]);
+}
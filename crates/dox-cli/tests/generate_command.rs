@@ -0,0 +1,132 @@
+mod common;
+
+use common::{assert_success, docx_fixture, dox, dox_with_env};
+use mockito::Matcher;
+use std::path::Path;
+
+/// Config file pointing the local provider at the mock server
+fn local_config(dir: &Path, server: &mockito::Server) -> String {
+    let config = dir.join("config.toml");
+    std::fs::write(
+        &config,
+        format!("[local]\nbase_url = \"{}/v1\"\n", server.url()),
+    )
+    .unwrap();
+    config.to_str().unwrap().to_string()
+}
+
+fn mock_completion(server: &mut mockito::Server, body: Matcher) -> mockito::Mock {
+    server
+        .mock("POST", "/v1/chat/completions")
+        .match_body(body)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"choices": [{"message": {"role": "assistant", "content": "요약 결과"}}]}"#)
+        .create()
+}
+
+#[test]
+fn test_generate_from_docx() {
+    let dir = tempfile::tempdir().unwrap();
+    let docx = docx_fixture(
+        dir.path(),
+        "report",
+        "# 분기 보고서\n\n매출이 12% 증가했습니다.\n",
+    );
+
+    let mut server = mockito::Server::new();
+    let mock = mock_completion(
+        &mut server,
+        Matcher::AllOf(vec![
+            Matcher::Regex("<context>".to_string()),
+            Matcher::Regex("분기 보고서".to_string()),
+            Matcher::Regex("매출이 12% 증가했습니다".to_string()),
+        ]),
+    );
+
+    let output = dox(&[
+        "--config",
+        &local_config(dir.path(), &server),
+        "generate",
+        "-p",
+        "핵심만 요약",
+        "-t",
+        "summary",
+        "--model",
+        "ollama/llama3",
+        "--from",
+        docx.to_str().unwrap(),
+    ]);
+    assert_success(&output);
+
+    mock.assert();
+    assert!(String::from_utf8_lossy(&output.stdout).contains("요약 결과"));
+}
+
+#[test]
+fn test_generate_from_truncates_context() {
+    let dir = tempfile::tempdir().unwrap();
+    let notes = dir.path().join("notes.txt");
+    std::fs::write(&notes, format!("START {} END", "x".repeat(500))).unwrap();
+
+    let mut server = mockito::Server::new();
+    let mock = mock_completion(
+        &mut server,
+        Matcher::AllOf(vec![
+            Matcher::Regex("START".to_string()),
+            Matcher::Regex("END".to_string()),
+        ]),
+    );
+    let truncated = mock_completion(&mut server, Matcher::Regex("START x{10}".to_string()));
+
+    let config = local_config(dir.path(), &server);
+    let output = dox(&[
+        "--config",
+        &config,
+        "generate",
+        "-p",
+        "요약",
+        "--provider",
+        "local",
+        "--from",
+        notes.to_str().unwrap(),
+        "--max-context-chars",
+        "100",
+    ]);
+    assert_success(&output);
+
+    mock.expect(0).assert();
+    truncated.assert();
+}
+
+#[test]
+fn test_generate_settings_from_environment() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut server = mockito::Server::new();
+    let mock = mock_completion(
+        &mut server,
+        Matcher::PartialJson(serde_json::json!({"model": "gpt-4", "max_tokens": 321})),
+    );
+    let config = dir.path().join("config.toml");
+    std::fs::write(
+        &config,
+        format!("[openai]\nbase_url = \"{}/v1\"\n", server.url()),
+    )
+    .unwrap();
+
+    let output = dox_with_env(
+        &[
+            "--config",
+            config.to_str().unwrap(),
+            "generate",
+            "-p",
+            "요약",
+        ],
+        &[
+            ("DOX_GENERATE_MODEL", "gpt-4"),
+            ("DOX_GENERATE_MAX_TOKENS", "321"),
+            ("DOX_OPENAI_API_KEY", "sk-test"),
+        ],
+    );
+    assert_success(&output);
+    mock.assert();
+}
@@ -0,0 +1,84 @@
+mod common;
+
+use common::docx_fixture;
+use std::process::{Command, Output};
+
+fn dox(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_dox"))
+        .args(["--no-color", "--quiet", "--output-format", "json"])
+        .args(args)
+        .env("NO_COLOR", "1")
+        .output()
+        .unwrap()
+}
+
+/// Parse each stdout line as a JSON value
+fn json_lines(output: &Output) -> Vec<serde_json::Value> {
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| {
+            serde_json::from_str(line).unwrap_or_else(|e| panic!("invalid JSON {:?}: {}", line, e))
+        })
+        .collect()
+}
+
+#[test]
+fn test_replace_json() {
+    let dir = tempfile::tempdir().unwrap();
+    let docx = docx_fixture(dir.path(), "notice", "# Notice\n\nVersion 1 is out.\n");
+
+    let output = dox(&[
+        "replace",
+        "--find",
+        "Version 1",
+        "--to",
+        "Version 2",
+        "--path",
+        docx.to_str().unwrap(),
+    ]);
+    assert!(output.status.success());
+
+    let lines = json_lines(&output);
+    assert_eq!(lines.len(), 1);
+    assert_eq!(lines[0]["files_processed"], 1);
+    assert_eq!(lines[0]["total_replacements"], 1);
+    assert_eq!(lines[0]["cancelled"], false);
+}
+
+#[test]
+fn test_extract_json() {
+    let dir = tempfile::tempdir().unwrap();
+    let first = docx_fixture(dir.path(), "first", "# First\n\nAlpha text.\n");
+    let second = docx_fixture(dir.path(), "second", "# Second\n\nBeta text.\n");
+
+    let output = dox(&["extract", first.to_str().unwrap()]);
+    assert!(output.status.success());
+    let lines = json_lines(&output);
+    assert_eq!(lines.len(), 1);
+    assert_eq!(lines[0]["success"], true);
+    assert!(lines[0]["pages"][0]["text"]
+        .as_str()
+        .unwrap()
+        .contains("Alpha text."));
+
+    // One line per document
+    let output = dox(&[
+        "extract",
+        first.to_str().unwrap(),
+        second.to_str().unwrap(),
+        "--output-dir",
+        dir.path().join("out").to_str().unwrap(),
+    ]);
+    assert!(output.status.success());
+    assert_eq!(json_lines(&output).len(), 2);
+}
+
+#[test]
+fn test_error_json() {
+    let output = dox(&["extract", "--input", "missing.docx"]);
+    assert!(!output.status.success());
+
+    let lines = json_lines(&output);
+    assert_eq!(lines.len(), 1);
+    assert!(lines[0]["error"]["message"].is_string());
+}
@@ -0,0 +1,50 @@
+use std::process::{Command, Output};
+
+/// Run dox with a clean locale so only the given settings apply
+fn dox(args: &[&str], locale: Option<&str>) -> Output {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_dox"));
+    command
+        .args(args)
+        .env("NO_COLOR", "1")
+        .env_remove("LC_ALL")
+        .env_remove("LC_MESSAGES")
+        .env_remove("LANG");
+    if let Some(locale) = locale {
+        command.env("LANG", locale);
+    }
+    command.output().unwrap()
+}
+
+fn help(args: &[&str], locale: Option<&str>) -> String {
+    let output = dox(args, locale);
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn test_lang_flag_sets_help_language() {
+    let english = help(&["--lang", "en", "--help"], None);
+    assert!(
+        english.contains("Extract text from documents"),
+        "{}",
+        english
+    );
+    assert!(english.contains("Set the interface language"));
+
+    let korean = help(&["--lang=ko", "--help"], Some("en_US.UTF-8"));
+    assert!(korean.contains("문서에서 텍스트 추출"), "{}", korean);
+
+    let subcommand = help(&["--lang", "en", "replace", "--help"], None);
+    assert!(
+        subcommand.contains("Glob pattern for files to exclude"),
+        "{}",
+        subcommand
+    );
+}
+
+#[test]
+fn test_locale_fallback() {
+    assert!(help(&["--help"], Some("en_US.UTF-8")).contains("Manage configuration"));
+    assert!(help(&["--help"], Some("C")).contains("설정 관리"));
+    assert!(help(&["--help"], None).contains("설정 관리"));
+}
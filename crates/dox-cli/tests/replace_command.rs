@@ -0,0 +1,49 @@
+mod common;
+
+use common::{assert_success, docx_fixture, dox};
+use std::path::Path;
+
+#[test]
+fn test_replace_manifest_lists_every_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let docs = dir.path().join("docs");
+    std::fs::create_dir(&docs).unwrap();
+    let changed = docx_fixture(&docs, "changed", "# Report\n\nVersion 1.0 is out.\n");
+    let untouched = docx_fixture(&docs, "untouched", "# Notes\n\nNothing to see here.\n");
+    let manifest = dir.path().join("manifest.json");
+
+    assert_success(&dox(&[
+        "replace",
+        "--find",
+        "Version 1.0",
+        "--to",
+        "Version 2.0",
+        "-p",
+        docs.to_str().unwrap(),
+        "--manifest",
+        manifest.to_str().unwrap(),
+    ]));
+
+    let manifest: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&manifest).unwrap()).unwrap();
+    let files = manifest["files"].as_array().unwrap();
+    assert_eq!(files.len(), 2);
+
+    let entry = |path: &Path| {
+        files
+            .iter()
+            .find(|file| file["path"] == path.to_str().unwrap())
+            .unwrap_or_else(|| panic!("no manifest entry for {}", path.display()))
+    };
+
+    let modified = entry(&changed);
+    assert_eq!(modified["status"], "modified");
+    assert_eq!(modified["replacements"], 1);
+    assert_ne!(modified["hash_before"], modified["hash_after"]);
+
+    let unchanged = entry(&untouched);
+    assert_eq!(unchanged["status"], "unchanged");
+    assert_eq!(unchanged["replacements"], 0);
+    assert!(unchanged["hash_before"].is_string());
+    assert_eq!(unchanged["hash_before"], unchanged["hash_after"]);
+}
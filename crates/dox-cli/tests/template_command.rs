@@ -0,0 +1,65 @@
+mod common;
+
+use common::{assert_success, docx_fixture, dox};
+use std::path::{Path, PathBuf};
+
+/// Word template with `{{name}}` and `{{event.date}}` placeholders
+fn template_fixture(dir: &Path) -> PathBuf {
+    docx_fixture(
+        dir,
+        "invite",
+        "# Invitation\n\nDear {{name}},\n\nSee you on {{event.date}}.\n",
+    )
+}
+
+fn extract_text(path: &Path) -> String {
+    let output = dox(&["extract", "-i", path.to_str().unwrap()]);
+    assert_success(&output);
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+#[test]
+fn test_template_fills_placeholders() {
+    let dir = tempfile::tempdir().unwrap();
+    let template = template_fixture(dir.path());
+    let values = dir.path().join("values.yaml");
+    std::fs::write(&values, "name: Kim\nevent:\n  date: Friday\n").unwrap();
+    let output = dir.path().join("out/invite_kim.docx");
+
+    assert_success(&dox(&[
+        "template",
+        "-t",
+        template.to_str().unwrap(),
+        "-o",
+        output.to_str().unwrap(),
+        "--values",
+        values.to_str().unwrap(),
+        "--set",
+        "name=Lee",
+    ]));
+
+    let text = extract_text(&output);
+    assert!(text.contains("Dear Lee,"), "{}", text);
+    assert!(text.contains("See you on Friday."), "{}", text);
+    assert!(!text.contains("{{"), "{}", text);
+}
+
+#[test]
+fn test_template_missing_value() {
+    let dir = tempfile::tempdir().unwrap();
+    let template = template_fixture(dir.path());
+    let output = dir.path().join("invite_out.docx");
+
+    let result = dox(&[
+        "template",
+        "-t",
+        template.to_str().unwrap(),
+        "-o",
+        output.to_str().unwrap(),
+        "--set",
+        "name=Lee",
+    ]);
+    assert!(!result.status.success());
+    assert!(String::from_utf8_lossy(&result.stderr).contains("event.date"));
+    assert!(!output.exists());
+}
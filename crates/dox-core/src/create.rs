@@ -3,13 +3,21 @@
 //! This module provides functionality to create Word and PowerPoint documents
 //! from Markdown content, with support for templates and advanced formatting.
 
+mod images;
+mod validate;
+
+use crate::utils::write_atomic;
 use anyhow::Result;
 use chrono::Utc;
+use images::EmbeddedImage;
 use serde::{Deserialize, Serialize};
+use std::cell::{Cell, RefCell};
 use std::fs::File;
 use std::io::Write;
-use std::path::Path;
-use zip::{write::SimpleFileOptions, ZipWriter};
+use std::path::{Path, PathBuf};
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipWriter};
+
+pub use validate::{validate_package, InvalidPackage, PackageProblem};
 
 /// Document creation request
 #[derive(Debug, Clone)]
@@ -80,6 +88,90 @@ pub struct CreateOptions {
     pub author: Option<String>,
     /// Language for document
     pub language: String,
+    /// Heading level that starts a new PowerPoint slide; text under deeper
+    /// headings becomes bullets of the current slide
+    pub slide_level: u8,
+    /// Directory that relative image paths are resolved against, usually
+    /// the one holding the Markdown file
+    pub image_base_dir: Option<PathBuf>,
+    /// Download images given by http(s) URLs; otherwise they are skipped
+    /// with a warning. Downloads over 20 MiB are skipped too.
+    pub fetch_remote_images: bool,
+    /// How the parts of the Office package are compressed
+    pub compression: Compression,
+}
+
+/// ZIP compression applied to every part of a created document
+///
+/// Only methods Word and PowerPoint can read are offered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    /// No compression; fastest to write
+    Stored,
+    /// Deflate at a level from 0 to 9, or the library default
+    Deflated { level: Option<i64> },
+}
+
+impl Default for Compression {
+    fn default() -> Self {
+        Compression::Deflated { level: None }
+    }
+}
+
+impl Compression {
+    fn file_options(self) -> SimpleFileOptions {
+        let (method, level) = match self {
+            Compression::Stored => (CompressionMethod::Stored, None),
+            Compression::Deflated { level } => (CompressionMethod::Deflated, level),
+        };
+        SimpleFileOptions::default()
+            .compression_method(method)
+            .compression_level(level)
+    }
+}
+
+impl std::str::FromStr for Compression {
+    type Err = String;
+
+    /// Parse `stored` or `deflate`, optionally followed by `:<level>` as in
+    /// `deflate:9`
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let (method, level) = match s.split_once(':') {
+            Some((method, level)) => {
+                let level = level
+                    .trim()
+                    .parse::<i64>()
+                    .map_err(|_| format!("Invalid compression level: {}", level))?;
+                (method, Some(level))
+            }
+            None => (s, None),
+        };
+
+        let in_range = |range: std::ops::RangeInclusive<i64>| match level {
+            Some(level) if !range.contains(&level) => Err(format!(
+                "Compression level {} is out of range {}-{}",
+                level,
+                range.start(),
+                range.end()
+            )),
+            _ => Ok(level),
+        };
+        match method.trim().to_lowercase().as_str() {
+            "stored" | "store" | "none" if level.is_none() => Ok(Compression::Stored),
+            "stored" | "store" | "none" => Err("Stored compression takes no level".to_string()),
+            "deflate" | "deflated" => Ok(Compression::Deflated {
+                level: in_range(0..=9)?,
+            }),
+            "zstd" => Err(
+                "zstd is not supported: Word and PowerPoint cannot open zstd-compressed documents"
+                    .to_string(),
+            ),
+            other => Err(format!(
+                "Unknown compression '{}'. Supported: stored, deflate",
+                other
+            )),
+        }
+    }
 }
 
 impl Default for CreateOptions {
@@ -93,6 +185,10 @@ impl Default for CreateOptions {
             title: None,
             author: None,
             language: "ko".to_string(),
+            slide_level: 1,
+            image_base_dir: None,
+            fetch_remote_images: false,
+            compression: Compression::default(),
         }
     }
 }
@@ -140,11 +236,19 @@ pub enum MarkdownElement {
     /// Heading with level and text
     Heading { level: u8, text: String },
     /// Paragraph with text content
-    Paragraph { text: String },
+    ///
+    /// `runs` holds the inline structure of `text` as `Text`, `Bold`,
+    /// `Italic`, `BoldItalic`, `Strikethrough`, `InlineCode`, `Link`, `Image`
+    /// and `LineBreak` elements. It is empty when only the plain text is known.
+    Paragraph {
+        text: String,
+        #[serde(default)]
+        runs: Vec<MarkdownElement>,
+    },
     /// Unordered list
-    UnorderedList { items: Vec<String> },
+    UnorderedList { items: Vec<ListItem> },
     /// Ordered list
-    OrderedList { items: Vec<String> },
+    OrderedList { items: Vec<ListItem> },
     /// Code block with language and content
     CodeBlock {
         language: Option<String>,
@@ -171,8 +275,56 @@ pub enum MarkdownElement {
     Bold { text: String },
     /// Italic text
     Italic { text: String },
+    /// Text that is both bold and italic
+    BoldItalic { text: String },
     /// Strikethrough text
     Strikethrough { text: String },
+    /// Unformatted inline text
+    Text { text: String },
+}
+
+/// Item of an ordered or unordered list
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ListItem {
+    /// Item text; separate paragraphs of the item are joined by newlines
+    pub text: String,
+    /// Inline structure of `text`, as for [`MarkdownElement::Paragraph`]
+    #[serde(default)]
+    pub runs: Vec<MarkdownElement>,
+    /// Lists nested inside the item
+    #[serde(default)]
+    pub children: Vec<MarkdownElement>,
+}
+
+/// A list being parsed, with the items read so far
+struct OpenList {
+    ordered: bool,
+    items: Vec<ListItem>,
+}
+
+/// The item currently being read in the innermost open list
+fn open_item(lists: &mut [OpenList]) -> Option<&mut ListItem> {
+    lists.last_mut().and_then(|list| list.items.last_mut())
+}
+
+impl OpenList {
+    fn into_element(self) -> MarkdownElement {
+        if self.ordered {
+            MarkdownElement::OrderedList { items: self.items }
+        } else {
+            MarkdownElement::UnorderedList { items: self.items }
+        }
+    }
+}
+
+/// Inline formatting open at the current position of a paragraph
+#[derive(Debug, Clone, PartialEq)]
+enum InlineStyle {
+    Bold,
+    Italic,
+    Strikethrough,
+    Link(String),
+    Image(String),
 }
 
 /// Markdown parser for document creation
@@ -186,6 +338,11 @@ impl MarkdownParser {
         MarkdownParser { options }
     }
 
+    /// Options this parser was created with
+    pub fn options(&self) -> &CreateOptions {
+        &self.options
+    }
+
     /// Parse Markdown content into structured document
     pub fn parse(&self, content: &str) -> Result<MarkdownDocument> {
         use pulldown_cmark::{Event, Parser, Tag, TagEnd};
@@ -204,7 +361,10 @@ impl MarkdownParser {
             document.title = document.metadata.title.clone();
         }
 
-        let parser = Parser::new(&content_without_frontmatter);
+        let mut parser_options = pulldown_cmark::Options::empty();
+        parser_options.insert(pulldown_cmark::Options::ENABLE_TABLES);
+        parser_options.insert(pulldown_cmark::Options::ENABLE_STRIKETHROUGH);
+        let parser = Parser::new_ext(&content_without_frontmatter, parser_options);
         let mut current_section = MarkdownSection {
             title: None,
             level: 1,
@@ -216,12 +376,20 @@ impl MarkdownParser {
         let mut heading_text = String::new();
         let mut in_paragraph = false;
         let mut paragraph_text = String::new();
+        let mut paragraph_runs: Vec<MarkdownElement> = Vec::new();
+        let mut inline_styles: Vec<InlineStyle> = Vec::new();
         let mut in_code_block = false;
         let mut code_language = None;
         let mut code_content = String::new();
-        let mut list_items = Vec::new();
-        let mut in_list = false;
-        let mut list_ordered = false;
+        // Lists currently open, innermost last
+        let mut lists: Vec<OpenList> = Vec::new();
+        let mut in_blockquote = false;
+        let mut blockquote_text = String::new();
+        let mut in_table = false;
+        let mut table_headers = Vec::new();
+        let mut table_rows: Vec<Vec<String>> = Vec::new();
+        let mut table_row = Vec::new();
+        let mut cell_text = String::new();
 
         for event in parser {
             match event {
@@ -229,13 +397,11 @@ impl MarkdownParser {
                     self.finish_current_element(
                         &mut current_section,
                         &mut in_paragraph,
-                        &mut paragraph_text,
+                        &paragraph_text,
+                        &paragraph_runs,
                         &mut in_code_block,
-                        &mut code_content,
+                        &code_content,
                         &mut code_language,
-                        &mut in_list,
-                        &mut list_items,
-                        list_ordered,
                     );
 
                     in_heading = true;
@@ -265,40 +431,99 @@ impl MarkdownParser {
                         text: heading_text.clone(),
                     });
                 }
+                Event::Start(Tag::Paragraph) if !lists.is_empty() => {
+                    // Further paragraphs of a loose list item continue its text
+                    if let Some(item) = open_item(&mut lists) {
+                        if !item.text.trim().is_empty() {
+                            item.text.push('\n');
+                            item.runs.push(MarkdownElement::LineBreak);
+                        }
+                    }
+                }
                 Event::Start(Tag::Paragraph) => {
                     self.finish_current_element(
                         &mut current_section,
                         &mut in_paragraph,
-                        &mut paragraph_text,
+                        &paragraph_text,
+                        &paragraph_runs,
                         &mut in_code_block,
-                        &mut code_content,
+                        &code_content,
                         &mut code_language,
-                        &mut in_list,
-                        &mut list_items,
-                        list_ordered,
                     );
                     in_paragraph = true;
                     paragraph_text.clear();
+                    paragraph_runs.clear();
+                    inline_styles.clear();
                 }
-                Event::End(TagEnd::Paragraph) => {
-                    if in_paragraph {
+                Event::End(TagEnd::Paragraph) if in_paragraph => {
+                    if in_blockquote {
+                        // Paragraphs of a blockquote become lines of its content
+                        if !blockquote_text.is_empty() {
+                            blockquote_text.push('\n');
+                        }
+                        blockquote_text.push_str(&paragraph_text);
+                    } else {
                         current_section.content.push(MarkdownElement::Paragraph {
                             text: paragraph_text.clone(),
+                            runs: paragraph_runs.clone(),
                         });
-                        in_paragraph = false;
                     }
+                    in_paragraph = false;
+                }
+                Event::Start(Tag::BlockQuote(_)) => {
+                    self.finish_current_element(
+                        &mut current_section,
+                        &mut in_paragraph,
+                        &paragraph_text,
+                        &paragraph_runs,
+                        &mut in_code_block,
+                        &code_content,
+                        &mut code_language,
+                    );
+                    in_blockquote = true;
+                    blockquote_text.clear();
+                }
+                Event::End(TagEnd::BlockQuote(_)) if in_blockquote => {
+                    current_section.content.push(MarkdownElement::Blockquote {
+                        content: blockquote_text.clone(),
+                    });
+                    in_blockquote = false;
+                }
+                Event::Start(Tag::Table(_)) => {
+                    self.finish_current_element(
+                        &mut current_section,
+                        &mut in_paragraph,
+                        &paragraph_text,
+                        &paragraph_runs,
+                        &mut in_code_block,
+                        &code_content,
+                        &mut code_language,
+                    );
+                    in_table = true;
+                    table_headers.clear();
+                    table_rows.clear();
+                }
+                Event::Start(Tag::TableHead) | Event::Start(Tag::TableRow) => table_row.clear(),
+                Event::Start(Tag::TableCell) => cell_text.clear(),
+                Event::End(TagEnd::TableCell) => table_row.push(cell_text.trim().to_string()),
+                Event::End(TagEnd::TableHead) => table_headers = std::mem::take(&mut table_row),
+                Event::End(TagEnd::TableRow) => table_rows.push(std::mem::take(&mut table_row)),
+                Event::End(TagEnd::Table) if in_table => {
+                    current_section.content.push(MarkdownElement::Table {
+                        headers: std::mem::take(&mut table_headers),
+                        rows: std::mem::take(&mut table_rows),
+                    });
+                    in_table = false;
                 }
                 Event::Start(Tag::CodeBlock(kind)) => {
                     self.finish_current_element(
                         &mut current_section,
                         &mut in_paragraph,
-                        &mut paragraph_text,
+                        &paragraph_text,
+                        &paragraph_runs,
                         &mut in_code_block,
-                        &mut code_content,
+                        &code_content,
                         &mut code_language,
-                        &mut in_list,
-                        &mut list_items,
-                        list_ordered,
                     );
 
                     in_code_block = true;
@@ -314,71 +539,125 @@ impl MarkdownParser {
                         pulldown_cmark::CodeBlockKind::Indented => None,
                     };
                 }
-                Event::End(TagEnd::CodeBlock) => {
-                    if in_code_block {
-                        current_section.content.push(MarkdownElement::CodeBlock {
-                            language: code_language.clone(),
-                            code: code_content.clone(),
-                        });
-                        in_code_block = false;
-                    }
+                Event::End(TagEnd::CodeBlock) if in_code_block => {
+                    current_section.content.push(MarkdownElement::CodeBlock {
+                        language: code_language.clone(),
+                        code: code_content.clone(),
+                    });
+                    in_code_block = false;
                 }
                 Event::Start(Tag::List(start)) => {
-                    self.finish_current_element(
-                        &mut current_section,
-                        &mut in_paragraph,
-                        &mut paragraph_text,
-                        &mut in_code_block,
-                        &mut code_content,
-                        &mut code_language,
-                        &mut in_list,
-                        &mut list_items,
-                        list_ordered,
-                    );
+                    if lists.is_empty() {
+                        self.finish_current_element(
+                            &mut current_section,
+                            &mut in_paragraph,
+                            &paragraph_text,
+                            &paragraph_runs,
+                            &mut in_code_block,
+                            &code_content,
+                            &mut code_language,
+                        );
+                    }
 
-                    in_list = true;
-                    list_ordered = start.is_some();
-                    list_items.clear();
+                    lists.push(OpenList {
+                        ordered: start.is_some(),
+                        items: Vec::new(),
+                    });
                 }
                 Event::End(TagEnd::List(_)) => {
-                    if in_list {
-                        if list_ordered {
-                            current_section.content.push(MarkdownElement::OrderedList {
-                                items: list_items.clone(),
-                            });
-                        } else {
-                            current_section
-                                .content
-                                .push(MarkdownElement::UnorderedList {
-                                    items: list_items.clone(),
-                                });
+                    if let Some(list) = lists.pop() {
+                        let element = list.into_element();
+                        // A nested list belongs to the item it appears in
+                        match open_item(&mut lists) {
+                            Some(parent) => parent.children.push(element),
+                            None => current_section.content.push(element),
                         }
-                        in_list = false;
                     }
                 }
                 Event::Start(Tag::Item) => {
-                    // Start collecting list item text
+                    if let Some(list) = lists.last_mut() {
+                        list.items.push(ListItem::default());
+                    }
                 }
                 Event::End(TagEnd::Item) => {
-                    // This gets handled in the text event
+                    if let Some(item) = open_item(&mut lists) {
+                        item.text = item.text.trim().to_string();
+                    }
                 }
                 Event::Text(text) => {
-                    if in_heading {
+                    if in_table {
+                        cell_text.push_str(&text);
+                    } else if in_heading {
                         heading_text.push_str(&text);
                     } else if in_paragraph {
                         paragraph_text.push_str(&text);
+                        push_inline_text(&mut paragraph_runs, &inline_styles, &text);
                     } else if in_code_block {
                         code_content.push_str(&text);
-                    } else if in_list {
-                        list_items.push(text.to_string());
+                    } else if let Some(item) = open_item(&mut lists) {
+                        item.text.push_str(&text);
+                        push_inline_text(&mut item.runs, &inline_styles, &text);
+                    }
+                }
+                Event::Start(Tag::Strong) => inline_styles.push(InlineStyle::Bold),
+                Event::Start(Tag::Emphasis) => inline_styles.push(InlineStyle::Italic),
+                Event::Start(Tag::Strikethrough) => inline_styles.push(InlineStyle::Strikethrough),
+                Event::Start(Tag::Link { dest_url, .. }) => {
+                    inline_styles.push(InlineStyle::Link(dest_url.to_string()))
+                }
+                Event::Start(Tag::Image { dest_url, .. }) => {
+                    let url = dest_url.to_string();
+                    // An image without alt text still needs a run
+                    let image = MarkdownElement::Image {
+                        alt: String::new(),
+                        url: url.clone(),
+                    };
+                    if in_paragraph {
+                        paragraph_runs.push(image);
+                    } else if let Some(item) = open_item(&mut lists) {
+                        item.runs.push(image);
+                    }
+                    inline_styles.push(InlineStyle::Image(url));
+                }
+                Event::End(TagEnd::Strong)
+                | Event::End(TagEnd::Emphasis)
+                | Event::End(TagEnd::Strikethrough)
+                | Event::End(TagEnd::Link)
+                | Event::End(TagEnd::Image) => {
+                    inline_styles.pop();
+                }
+                Event::SoftBreak if in_paragraph => {
+                    paragraph_text.push(' ');
+                    push_inline_text(&mut paragraph_runs, &inline_styles, " ");
+                }
+                Event::HardBreak if in_paragraph => {
+                    paragraph_text.push('\n');
+                    paragraph_runs.push(MarkdownElement::LineBreak);
+                }
+                Event::SoftBreak => {
+                    if let Some(item) = open_item(&mut lists) {
+                        item.text.push(' ');
+                        push_inline_text(&mut item.runs, &inline_styles, " ");
+                    }
+                }
+                Event::HardBreak => {
+                    if let Some(item) = open_item(&mut lists) {
+                        item.text.push('\n');
+                        item.runs.push(MarkdownElement::LineBreak);
                     }
                 }
                 Event::Code(code) => {
                     let code_element = MarkdownElement::InlineCode {
                         code: code.to_string(),
                     };
-                    if in_paragraph {
+                    if in_table {
+                        cell_text.push_str(&code);
+                    } else if in_paragraph {
                         paragraph_text.push_str(&format!("`{}`", code));
+                        paragraph_runs.push(code_element);
+                    } else if let Some(item) = open_item(&mut lists) {
+                        item.text.push_str(&format!("`{}`", code));
+                        item.runs.push(code_element);
                     } else {
                         current_section.content.push(code_element);
                     }
@@ -387,13 +666,11 @@ impl MarkdownParser {
                     self.finish_current_element(
                         &mut current_section,
                         &mut in_paragraph,
-                        &mut paragraph_text,
+                        &paragraph_text,
+                        &paragraph_runs,
                         &mut in_code_block,
-                        &mut code_content,
+                        &code_content,
                         &mut code_language,
-                        &mut in_list,
-                        &mut list_items,
-                        list_ordered,
                     );
                     current_section
                         .content
@@ -409,13 +686,11 @@ impl MarkdownParser {
         self.finish_current_element(
             &mut current_section,
             &mut in_paragraph,
-            &mut paragraph_text,
+            &paragraph_text,
+            &paragraph_runs,
             &mut in_code_block,
-            &mut code_content,
+            &code_content,
             &mut code_language,
-            &mut in_list,
-            &mut list_items,
-            list_ordered,
         );
 
         // Add the last section
@@ -430,6 +705,7 @@ impl MarkdownParser {
                 level: 1,
                 content: vec![MarkdownElement::Paragraph {
                     text: content_without_frontmatter.trim().to_string(),
+                    runs: Vec::new(),
                 }],
             });
         }
@@ -438,21 +714,21 @@ impl MarkdownParser {
     }
 
     /// Helper function to finish current parsing element
+    #[allow(clippy::too_many_arguments)]
     fn finish_current_element(
         &self,
         current_section: &mut MarkdownSection,
         in_paragraph: &mut bool,
-        paragraph_text: &mut String,
+        paragraph_text: &str,
+        paragraph_runs: &[MarkdownElement],
         in_code_block: &mut bool,
-        code_content: &mut String,
+        code_content: &str,
         code_language: &mut Option<String>,
-        in_list: &mut bool,
-        list_items: &mut Vec<String>,
-        list_ordered: bool,
     ) {
         if *in_paragraph && !paragraph_text.trim().is_empty() {
             current_section.content.push(MarkdownElement::Paragraph {
-                text: paragraph_text.clone(),
+                text: paragraph_text.to_string(),
+                runs: paragraph_runs.to_vec(),
             });
             *in_paragraph = false;
         }
@@ -460,56 +736,32 @@ impl MarkdownParser {
         if *in_code_block && !code_content.trim().is_empty() {
             current_section.content.push(MarkdownElement::CodeBlock {
                 language: code_language.clone(),
-                code: code_content.clone(),
+                code: code_content.to_string(),
             });
             *in_code_block = false;
         }
-
-        if *in_list && !list_items.is_empty() {
-            if list_ordered {
-                current_section.content.push(MarkdownElement::OrderedList {
-                    items: list_items.clone(),
-                });
-            } else {
-                current_section
-                    .content
-                    .push(MarkdownElement::UnorderedList {
-                        items: list_items.clone(),
-                    });
-            }
-            *in_list = false;
-            list_items.clear();
-        }
     }
 
     /// Extract YAML frontmatter from Markdown content
     fn extract_frontmatter(&self, content: &str) -> (String, MarkdownMetadata) {
         let mut metadata = MarkdownMetadata::default();
 
-        if content.starts_with("---\n") {
-            if let Some(end_pos) = content[4..].find("\n---\n") {
-                let frontmatter = &content[4..end_pos + 4];
-                let remaining_content = &content[end_pos + 8..];
-
-                // Parse YAML frontmatter
-                if let Ok(yaml_value) = serde_yaml::from_str::<serde_yaml::Value>(frontmatter) {
-                    if let Some(mapping) = yaml_value.as_mapping() {
-                        for (key, value) in mapping {
-                            if let (Some(key_str), Some(value_str)) = (key.as_str(), value.as_str())
-                            {
-                                match key_str {
-                                    "title" => metadata.title = Some(value_str.to_string()),
-                                    "author" => metadata.author = Some(value_str.to_string()),
-                                    "date" => metadata.date = Some(value_str.to_string()),
-                                    _ => {
-                                        metadata
-                                            .custom
-                                            .insert(key_str.to_string(), value_str.to_string());
-                                    }
-                                }
+        if let Some(body) = content.strip_prefix("---\n") {
+            if let Some(end_pos) = body.find("\n---\n") {
+                let frontmatter = &body[..end_pos];
+                let remaining_content = &body[end_pos + 5..];
+
+                // Malformed frontmatter is dropped rather than failing the document
+                match serde_yaml::from_str::<serde_yaml::Value>(frontmatter) {
+                    Ok(serde_yaml::Value::Mapping(mapping)) => {
+                        for (key, value) in &mapping {
+                            if let Some(key) = key.as_str() {
+                                Self::apply_frontmatter_field(&mut metadata, key, value);
                             }
                         }
                     }
+                    Ok(_) => tracing::warn!("Ignoring frontmatter that is not a mapping"),
+                    Err(e) => tracing::warn!("Ignoring malformed frontmatter: {}", e),
                 }
 
                 return (remaining_content.to_string(), metadata);
@@ -519,6 +771,49 @@ impl MarkdownParser {
         (content.to_string(), metadata)
     }
 
+    /// Store one frontmatter entry in the matching metadata field
+    ///
+    /// `tags` takes a list or a comma-separated string. Other unknown keys go
+    /// to `custom`; lists and mappings there are kept as JSON.
+    fn apply_frontmatter_field(
+        metadata: &mut MarkdownMetadata,
+        key: &str,
+        value: &serde_yaml::Value,
+    ) {
+        match key {
+            "title" => metadata.title = frontmatter_scalar(value),
+            "author" => metadata.author = frontmatter_scalar(value),
+            "date" => metadata.date = frontmatter_scalar(value),
+            "tags" => {
+                metadata.tags = match value {
+                    serde_yaml::Value::Sequence(items) => {
+                        items.iter().filter_map(frontmatter_scalar).collect()
+                    }
+                    _ => frontmatter_scalar(value)
+                        .map(|tags| {
+                            tags.split(',')
+                                .map(str::trim)
+                                .filter(|tag| !tag.is_empty())
+                                .map(str::to_string)
+                                .collect()
+                        })
+                        .unwrap_or_default(),
+                }
+            }
+            _ => {
+                let text = match value {
+                    serde_yaml::Value::Sequence(_) | serde_yaml::Value::Mapping(_) => {
+                        serde_json::to_string(value).ok()
+                    }
+                    _ => frontmatter_scalar(value),
+                };
+                if let Some(text) = text {
+                    metadata.custom.insert(key.to_string(), text);
+                }
+            }
+        }
+    }
+
     /// Parse Markdown file
     pub fn parse_file(&self, path: &Path) -> Result<MarkdownDocument> {
         let content = std::fs::read_to_string(path)?;
@@ -526,6 +821,91 @@ impl MarkdownParser {
     }
 }
 
+/// Text of a scalar frontmatter value; `None` for null, lists and mappings
+fn frontmatter_scalar(value: &serde_yaml::Value) -> Option<String> {
+    match value {
+        serde_yaml::Value::String(text) => Some(text.clone()),
+        serde_yaml::Value::Number(number) => Some(number.to_string()),
+        serde_yaml::Value::Bool(flag) => Some(flag.to_string()),
+        serde_yaml::Value::Tagged(tagged) => frontmatter_scalar(&tagged.value),
+        _ => None,
+    }
+}
+
+/// Append paragraph text as an inline run styled by the innermost open span
+///
+/// Text inside a link or image always belongs to it, whatever emphasis is
+/// also applied; bold inside italic or the other way round is both.
+/// Consecutive text with the same style extends the last run.
+fn push_inline_text(runs: &mut Vec<MarkdownElement>, styles: &[InlineStyle], text: &str) {
+    let style = styles
+        .iter()
+        .rev()
+        .find(|style| matches!(style, InlineStyle::Link(_) | InlineStyle::Image(_)))
+        .or_else(|| styles.last());
+    let bold_italic = matches!(style, Some(InlineStyle::Bold | InlineStyle::Italic))
+        && styles.contains(&InlineStyle::Bold)
+        && styles.contains(&InlineStyle::Italic);
+
+    if let Some(last) = runs.last_mut() {
+        let extended = match (last, style) {
+            (MarkdownElement::BoldItalic { text: run }, _) if bold_italic => Some(run),
+            (MarkdownElement::Text { text: run }, None)
+            | (MarkdownElement::Bold { text: run }, Some(InlineStyle::Bold))
+            | (MarkdownElement::Italic { text: run }, Some(InlineStyle::Italic))
+            | (MarkdownElement::Strikethrough { text: run }, Some(InlineStyle::Strikethrough))
+                if !bold_italic =>
+            {
+                Some(run)
+            }
+            (MarkdownElement::Link { text: run, url }, Some(InlineStyle::Link(open)))
+            | (MarkdownElement::Image { alt: run, url }, Some(InlineStyle::Image(open)))
+                if url == open =>
+            {
+                Some(run)
+            }
+            _ => None,
+        };
+        if let Some(run) = extended {
+            run.push_str(text);
+            return;
+        }
+    }
+
+    let text = text.to_string();
+    runs.push(match style {
+        _ if bold_italic => MarkdownElement::BoldItalic { text },
+        None => MarkdownElement::Text { text },
+        Some(InlineStyle::Bold) => MarkdownElement::Bold { text },
+        Some(InlineStyle::Italic) => MarkdownElement::Italic { text },
+        Some(InlineStyle::Strikethrough) => MarkdownElement::Strikethrough { text },
+        Some(InlineStyle::Link(url)) => MarkdownElement::Link {
+            text,
+            url: url.clone(),
+        },
+        Some(InlineStyle::Image(url)) => MarkdownElement::Image {
+            alt: text,
+            url: url.clone(),
+        },
+    });
+}
+
+/// Text of every list item, including nested ones, in document order
+fn list_texts(items: &[ListItem]) -> Vec<String> {
+    let mut texts = Vec::new();
+    for item in items {
+        texts.push(item.text.clone());
+        for child in &item.children {
+            if let MarkdownElement::UnorderedList { items }
+            | MarkdownElement::OrderedList { items } = child
+            {
+                texts.extend(list_texts(items));
+            }
+        }
+    }
+    texts
+}
+
 /// Document creator trait for different formats
 pub trait DocumentCreator {
     /// Create document from parsed Markdown
@@ -566,7 +946,10 @@ impl WordDocumentCreator {
 impl DocumentCreator for WordDocumentCreator {
     fn create_document(&self, markdown: &MarkdownDocument, request: &CreateRequest) -> Result<()> {
         // Create Word document structure
-        let word_generator = WordDocumentGenerator::new(request.clone());
+        let word_generator = WordDocumentGenerator::new(request.clone())
+            .with_images(images::load_images(markdown, &request.options))
+            .with_links(link_targets(markdown))
+            .with_numbering(has_lists(markdown));
         word_generator.generate(markdown)?;
 
         Ok(())
@@ -577,47 +960,300 @@ impl DocumentCreator for WordDocumentCreator {
     }
 }
 
+/// Whether any section has a bulleted or numbered list
+fn has_lists(markdown: &MarkdownDocument) -> bool {
+    markdown
+        .sections
+        .iter()
+        .flat_map(|section| &section.content)
+        .any(|element| {
+            matches!(
+                element,
+                MarkdownElement::UnorderedList { .. } | MarkdownElement::OrderedList { .. }
+            )
+        })
+}
+
+/// Targets of the links in `markdown` that point outside the document, in
+/// document order without repeats
+fn link_targets(markdown: &MarkdownDocument) -> Vec<String> {
+    fn collect(elements: &[MarkdownElement], targets: &mut Vec<String>) {
+        for element in elements {
+            match element {
+                // `#heading` links point inside the document
+                MarkdownElement::Link { url, .. }
+                    if !url.is_empty() && !url.starts_with('#') && !targets.contains(url) =>
+                {
+                    targets.push(url.clone())
+                }
+                MarkdownElement::Paragraph { runs, .. } => collect(runs, targets),
+                MarkdownElement::UnorderedList { items }
+                | MarkdownElement::OrderedList { items } => {
+                    for item in items {
+                        collect(&item.runs, targets);
+                        collect(&item.children, targets);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let mut targets = Vec::new();
+    for section in &markdown.sections {
+        collect(&section.content, &mut targets);
+    }
+    targets
+}
+
+/// Finish a generated package and check that Office will open it
+///
+/// Runs inside [`write_atomic`], so a package that fails validation never
+/// replaces the output file.
+fn finish_package(zip_writer: ZipWriter<File>) -> Result<()> {
+    let mut archive = zip::ZipArchive::new(zip_writer.finish()?)?;
+    validate::ensure_valid(&mut archive)
+}
+
+/// Relationship type of an embedded picture
+const IMAGE_RELATIONSHIP: &str =
+    "http://schemas.openxmlformats.org/officeDocument/2006/relationships/image";
+
+/// Relationship type of a hyperlink target
+const HYPERLINK_RELATIONSHIP: &str =
+    "http://schemas.openxmlformats.org/officeDocument/2006/relationships/hyperlink";
+
+/// `Default` content types for the image formats in `images`
+fn image_content_types(images: &[EmbeddedImage]) -> String {
+    let mut formats = Vec::new();
+    for image in images {
+        if !formats.contains(&image.format) {
+            formats.push(image.format);
+        }
+    }
+    formats
+        .into_iter()
+        .map(|format| {
+            format!(
+                r#"
+    <Default Extension="{}" ContentType="{}"/>"#,
+                format.extension(),
+                format.content_type()
+            )
+        })
+        .collect()
+}
+
 /// Word document generator using XML-based approach
 struct WordDocumentGenerator {
     request: CreateRequest,
+    /// Images stored under `word/media/`, related after the optional parts
+    images: Vec<EmbeddedImage>,
+    /// External link targets, related after the images
+    links: Vec<String>,
+    /// Drawings written so far; each needs its own `wp:docPr` id
+    drawings: Cell<usize>,
+    /// Whether the document has lists and so needs `word/numbering.xml`
+    numbering: bool,
+    /// Starting level of each numbered list written so far; list `i` uses
+    /// `w:numId` `DECIMAL_NUM_ID + i`
+    ordered_lists: RefCell<Vec<usize>>,
+}
+
+/// A part of the Word package that is only written for some options
+struct OptionalPart {
+    /// Path of the part relative to `word/`
+    name: &'static str,
+    content_type: &'static str,
+    relationship: &'static str,
+}
+
+const SETTINGS_PART: OptionalPart = OptionalPart {
+    name: "settings.xml",
+    content_type: "application/vnd.openxmlformats-officedocument.wordprocessingml.settings+xml",
+    relationship: "http://schemas.openxmlformats.org/officeDocument/2006/relationships/settings",
+};
+
+const HEADER_PART: OptionalPart = OptionalPart {
+    name: "header1.xml",
+    content_type: "application/vnd.openxmlformats-officedocument.wordprocessingml.header+xml",
+    relationship: "http://schemas.openxmlformats.org/officeDocument/2006/relationships/header",
+};
+
+const FOOTER_PART: OptionalPart = OptionalPart {
+    name: "footer1.xml",
+    content_type: "application/vnd.openxmlformats-officedocument.wordprocessingml.footer+xml",
+    relationship: "http://schemas.openxmlformats.org/officeDocument/2006/relationships/footer",
+};
+
+const NUMBERING_PART: OptionalPart = OptionalPart {
+    name: "numbering.xml",
+    content_type: "application/vnd.openxmlformats-officedocument.wordprocessingml.numbering+xml",
+    relationship: "http://schemas.openxmlformats.org/officeDocument/2006/relationships/numbering",
+};
+
+/// `w:numId` of bulleted and numbered list paragraphs, defined in
+/// `word/numbering.xml`; each numbered list after the first takes the next
+/// id so it starts again at 1
+const BULLET_NUM_ID: u32 = 1;
+const DECIMAL_NUM_ID: u32 = 2;
+
+/// List levels defined in `word/numbering.xml`; deeper nesting stays at
+/// the last level
+const LIST_LEVELS: usize = 9;
+
+/// One `w:lvl` of a list definition, indented half an inch per level with
+/// the marker hanging in front of the text
+fn numbering_level(level: usize, format: &str, text: &str) -> String {
+    format!(
+        r#"
+        <w:lvl w:ilvl="{}">
+            <w:start w:val="1"/>
+            <w:numFmt w:val="{}"/>
+            <w:lvlText w:val="{}"/>
+            <w:lvlJc w:val="left"/>
+            <w:pPr>
+                <w:ind w:left="{}" w:hanging="360"/>
+            </w:pPr>
+        </w:lvl>"#,
+        level,
+        format,
+        text,
+        720 * (level + 1)
+    )
 }
 
 impl WordDocumentGenerator {
     fn new(request: CreateRequest) -> Self {
-        WordDocumentGenerator { request }
+        WordDocumentGenerator {
+            request,
+            images: Vec::new(),
+            links: Vec::new(),
+            drawings: Cell::new(0),
+            numbering: false,
+            ordered_lists: RefCell::new(Vec::new()),
+        }
+    }
+
+    fn with_images(mut self, images: Vec<EmbeddedImage>) -> Self {
+        self.images = images;
+        self
+    }
+
+    fn with_links(mut self, links: Vec<String>) -> Self {
+        self.links = links;
+        self
+    }
+
+    fn with_numbering(mut self, numbering: bool) -> Self {
+        self.numbering = numbering;
+        self
+    }
+
+    fn file_options(&self) -> SimpleFileOptions {
+        self.request.options.compression.file_options()
     }
 
     fn generate(&self, markdown: &MarkdownDocument) -> Result<()> {
-        use std::fs::File;
+        // Written through a temporary file so a failure midway leaves no
+        // truncated document behind
+        write_atomic(Path::new(&self.request.output_path), |output_file| {
+            let mut zip_writer = ZipWriter::new(output_file);
+
+            // Generate Word document structure
+            self.write_content_types(&mut zip_writer)?;
+            self.write_app_properties(&mut zip_writer, markdown)?;
+            self.write_core_properties(&mut zip_writer, markdown)?;
+            self.write_document_relationships(&mut zip_writer)?;
+            self.write_main_document(&mut zip_writer, markdown)?;
+            self.write_styles(&mut zip_writer)?;
+            self.write_media(&mut zip_writer)?;
+            if self.request.options.include_toc {
+                self.write_settings(&mut zip_writer)?;
+            }
+            if let Some(header) = &self.request.options.header {
+                self.write_header(&mut zip_writer, header)?;
+            }
+            if self.has_footer() {
+                self.write_footer(&mut zip_writer)?;
+            }
+            if self.numbering {
+                self.write_numbering(&mut zip_writer)?;
+            }
 
-        // Create output file
-        let output_file = File::create(&self.request.output_path)?;
-        let mut zip_writer = ZipWriter::new(output_file);
+            finish_package(zip_writer)
+        })
+    }
 
-        // Generate Word document structure
-        self.write_content_types(&mut zip_writer)?;
-        self.write_app_properties(&mut zip_writer, markdown)?;
-        self.write_core_properties(&mut zip_writer, markdown)?;
-        self.write_document_relationships(&mut zip_writer)?;
-        self.write_main_document(&mut zip_writer, markdown)?;
-        self.write_styles(&mut zip_writer)?;
+    /// Optional parts in relationship order; the first one is `rId2`
+    fn optional_parts(&self) -> Vec<&'static OptionalPart> {
+        let options = &self.request.options;
+        let mut parts = Vec::new();
+        if options.include_toc {
+            parts.push(&SETTINGS_PART);
+        }
+        if options.header.is_some() {
+            parts.push(&HEADER_PART);
+        }
+        if self.has_footer() {
+            parts.push(&FOOTER_PART);
+        }
+        if self.numbering {
+            parts.push(&NUMBERING_PART);
+        }
+        parts
+    }
 
-        zip_writer.finish()?;
-        Ok(())
+    /// Relationship id of an optional part, if it is written
+    fn part_relationship_id(&self, part: &OptionalPart) -> Option<String> {
+        self.optional_parts()
+            .iter()
+            .position(|candidate| candidate.name == part.name)
+            .map(|index| format!("rId{}", index + 2))
+    }
+
+    /// Relationship id of the image at `index`, numbered after the optional parts
+    fn image_relationship_id(&self, index: usize) -> String {
+        format!("rId{}", self.optional_parts().len() + 2 + index)
+    }
+
+    /// Relationship id of the link target at `index`, numbered after the images
+    fn link_relationship_id(&self, index: usize) -> String {
+        self.image_relationship_id(self.images.len() + index)
+    }
+
+    /// The footer carries the footer text, the page number or both
+    fn has_footer(&self) -> bool {
+        self.request.options.footer.is_some() || self.request.options.page_numbers
     }
 
     fn write_content_types(&self, zip_writer: &mut ZipWriter<File>) -> Result<()> {
-        let content = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+        let overrides: String = self
+            .optional_parts()
+            .iter()
+            .map(|part| {
+                format!(
+                    r#"
+    <Override PartName="/word/{}" ContentType="{}"/>"#,
+                    part.name, part.content_type
+                )
+            })
+            .collect();
+        let content = format!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
 <Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">
     <Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>
-    <Default Extension="xml" ContentType="application/xml"/>
+    <Default Extension="xml" ContentType="application/xml"/>{}
     <Override PartName="/word/document.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml"/>
-    <Override PartName="/word/styles.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.styles+xml"/>
+    <Override PartName="/word/styles.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.styles+xml"/>{}
     <Override PartName="/docProps/app.xml" ContentType="application/vnd.openxmlformats-officedocument.extended-properties+xml"/>
     <Override PartName="/docProps/core.xml" ContentType="application/vnd.openxmlformats-package.core-properties+xml"/>
-</Types>"#;
+</Types>"#,
+            image_content_types(&self.images),
+            overrides
+        );
 
-        zip_writer.start_file("[Content_Types].xml", SimpleFileOptions::default())?;
+        zip_writer.start_file("[Content_Types].xml", self.file_options())?;
         zip_writer.write_all(content.as_bytes())?;
         Ok(())
     }
@@ -625,7 +1261,7 @@ impl WordDocumentGenerator {
     fn write_app_properties(
         &self,
         zip_writer: &mut ZipWriter<File>,
-        markdown: &MarkdownDocument,
+        _markdown: &MarkdownDocument,
     ) -> Result<()> {
         let content = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
 <Properties xmlns="http://schemas.openxmlformats.org/officeDocument/2006/extended-properties" xmlns:vt="http://schemas.openxmlformats.org/officeDocument/2006/docPropsVTypes">
@@ -639,7 +1275,7 @@ impl WordDocumentGenerator {
     <AppVersion>1.0</AppVersion>
 </Properties>"#.to_string();
 
-        zip_writer.start_file("docProps/app.xml", SimpleFileOptions::default())?;
+        zip_writer.start_file("docProps/app.xml", self.file_options())?;
         zip_writer.write_all(content.as_bytes())?;
         Ok(())
     }
@@ -666,7 +1302,7 @@ impl WordDocumentGenerator {
             title, author, author, created, created
         );
 
-        zip_writer.start_file("docProps/core.xml", SimpleFileOptions::default())?;
+        zip_writer.start_file("docProps/core.xml", self.file_options())?;
         zip_writer.write_all(content.as_bytes())?;
         Ok(())
     }
@@ -680,16 +1316,61 @@ impl WordDocumentGenerator {
     <Relationship Id="rId3" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/extended-properties" Target="docProps/app.xml"/>
 </Relationships>"#;
 
-        zip_writer.start_file("_rels/.rels", SimpleFileOptions::default())?;
+        zip_writer.start_file("_rels/.rels", self.file_options())?;
         zip_writer.write_all(content.as_bytes())?;
 
         // Document relationships
-        let doc_rels = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+        let optional_rels: String = self
+            .optional_parts()
+            .iter()
+            .enumerate()
+            .map(|(index, part)| {
+                format!(
+                    r#"
+    <Relationship Id="rId{}" Type="{}" Target="{}"/>"#,
+                    index + 2,
+                    part.relationship,
+                    part.name
+                )
+            })
+            .collect();
+        let image_rels: String = self
+            .images
+            .iter()
+            .enumerate()
+            .map(|(index, image)| {
+                format!(
+                    r#"
+    <Relationship Id="{}" Type="{}" Target="media/{}"/>"#,
+                    self.image_relationship_id(index),
+                    IMAGE_RELATIONSHIP,
+                    image.file_name(index)
+                )
+            })
+            .collect();
+        let link_rels: String = self
+            .links
+            .iter()
+            .enumerate()
+            .map(|(index, url)| {
+                format!(
+                    r#"
+    <Relationship Id="{}" Type="{}" Target="{}" TargetMode="External"/>"#,
+                    self.link_relationship_id(index),
+                    HYPERLINK_RELATIONSHIP,
+                    self.escape_xml(url)
+                )
+            })
+            .collect();
+        let doc_rels = format!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
 <Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
-    <Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles" Target="styles.xml"/>
-</Relationships>"#;
+    <Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles" Target="styles.xml"/>{}{}{}
+</Relationships>"#,
+            optional_rels, image_rels, link_rels
+        );
 
-        zip_writer.start_file("word/_rels/document.xml.rels", SimpleFileOptions::default())?;
+        zip_writer.start_file("word/_rels/document.xml.rels", self.file_options())?;
         zip_writer.write_all(doc_rels.as_bytes())?;
         Ok(())
     }
@@ -709,6 +1390,10 @@ impl WordDocumentGenerator {
             document_xml.push_str(&self.create_title_paragraph(title));
         }
 
+        if self.request.options.include_toc {
+            document_xml.push_str(&self.create_table_of_contents());
+        }
+
         // Convert sections to Word paragraphs
         for section in &markdown.sections {
             for element in &section.content {
@@ -717,8 +1402,25 @@ impl WordDocumentGenerator {
         }
 
         // Close document
+        document_xml.push_str(
+            r#"
+        <w:sectPr>"#,
+        );
+        if let Some(id) = self.part_relationship_id(&HEADER_PART) {
+            document_xml.push_str(&format!(
+                r#"
+            <w:headerReference w:type="default" r:id="{}"/>"#,
+                id
+            ));
+        }
+        if let Some(id) = self.part_relationship_id(&FOOTER_PART) {
+            document_xml.push_str(&format!(
+                r#"
+            <w:footerReference w:type="default" r:id="{}"/>"#,
+                id
+            ));
+        }
         document_xml.push_str(r#"
-        <w:sectPr>
             <w:pgSz w:w="11906" w:h="16838"/>
             <w:pgMar w:top="1440" w:right="1440" w:bottom="1440" w:left="1440" w:header="708" w:footer="708" w:gutter="0"/>
             <w:cols w:space="708"/>
@@ -727,124 +1429,266 @@ impl WordDocumentGenerator {
     </w:body>
 </w:document>"#);
 
-        zip_writer.start_file("word/document.xml", SimpleFileOptions::default())?;
+        zip_writer.start_file("word/document.xml", self.file_options())?;
         zip_writer.write_all(document_xml.as_bytes())?;
         Ok(())
     }
 
-    fn create_title_paragraph(&self, title: &str) -> String {
-        format!(
-            r#"
-        <w:p>
-            <w:pPr>
-                <w:pStyle w:val="Title"/>
-                <w:jc w:val="center"/>
-            </w:pPr>
-            <w:r>
-                <w:rPr>
-                    <w:b/>
-                    <w:sz w:val="32"/>
-                </w:rPr>
-                <w:t>{}</w:t>
-            </w:r>
-        </w:p>"#,
-            self.escape_xml(title)
-        )
+    fn write_media(&self, zip_writer: &mut ZipWriter<File>) -> Result<()> {
+        for (index, image) in self.images.iter().enumerate() {
+            zip_writer.start_file(
+                format!("word/media/{}", image.file_name(index)),
+                self.file_options(),
+            )?;
+            zip_writer.write_all(&image.data)?;
+        }
+        Ok(())
     }
 
-    fn convert_element_to_word(&self, element: &MarkdownElement) -> String {
-        match element {
-            MarkdownElement::Heading { level, text } => {
-                let style = format!("Heading{}", level);
+    /// Settings asking Word to refresh fields such as the TOC when opened
+    fn write_settings(&self, zip_writer: &mut ZipWriter<File>) -> Result<()> {
+        let content = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:settings xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">
+    <w:updateFields w:val="true"/>
+</w:settings>"#;
+
+        zip_writer.start_file("word/settings.xml", self.file_options())?;
+        zip_writer.write_all(content.as_bytes())?;
+        Ok(())
+    }
+
+    fn write_header(&self, zip_writer: &mut ZipWriter<File>, text: &str) -> Result<()> {
+        let content = format!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:hdr xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">
+    <w:p>
+        <w:pPr>
+            <w:jc w:val="right"/>
+        </w:pPr>
+        <w:r>
+            <w:t xml:space="preserve">{}</w:t>
+        </w:r>
+    </w:p>
+</w:hdr>"#,
+            self.escape_xml(text)
+        );
+
+        zip_writer.start_file("word/header1.xml", self.file_options())?;
+        zip_writer.write_all(content.as_bytes())?;
+        Ok(())
+    }
+
+    /// Bullet and decimal list definitions with all nine levels Word
+    /// supports, and a `w:num` per numbered list restarting it at 1
+    fn write_numbering(&self, zip_writer: &mut ZipWriter<File>) -> Result<()> {
+        let bullet_levels: String = (0..LIST_LEVELS)
+            .map(|level| {
+                // Cycle through filled, hollow and square bullets
+                let bullet = ["•", "◦", "▪"][level % 3];
+                numbering_level(level, "bullet", bullet)
+            })
+            .collect();
+        let decimal_levels: String = (0..LIST_LEVELS)
+            .map(|level| {
+                // 1. then a. then i. as lists nest
+                let format = ["decimal", "lowerLetter", "lowerRoman"][level % 3];
+                numbering_level(level, format, &format!("%{}.", level + 1))
+            })
+            .collect();
+        let decimal_nums: String = self
+            .ordered_lists
+            .borrow()
+            .iter()
+            .enumerate()
+            .map(|(index, level)| {
                 format!(
                     r#"
-        <w:p>
-            <w:pPr>
-                <w:pStyle w:val="{}"/>
-            </w:pPr>
-            <w:r>
-                <w:rPr>
-                    <w:b/>
-                </w:rPr>
-                <w:t>{}</w:t>
-            </w:r>
-        </w:p>"#,
-                    style,
-                    self.escape_xml(text)
+    <w:num w:numId="{}">
+        <w:abstractNumId w:val="1"/>
+        <w:lvlOverride w:ilvl="{}">
+            <w:startOverride w:val="1"/>
+        </w:lvlOverride>
+    </w:num>"#,
+                    DECIMAL_NUM_ID + index as u32,
+                    level
                 )
-            }
-            MarkdownElement::Paragraph { text } => {
-                format!(
-                    r#"
+            })
+            .collect();
+
+        let content = format!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:numbering xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">
+    <w:abstractNum w:abstractNumId="0">
+        <w:multiLevelType w:val="hybridMultilevel"/>{}
+    </w:abstractNum>
+    <w:abstractNum w:abstractNumId="1">
+        <w:multiLevelType w:val="hybridMultilevel"/>{}
+    </w:abstractNum>
+    <w:num w:numId="{}">
+        <w:abstractNumId w:val="0"/>
+    </w:num>{}
+</w:numbering>"#,
+            bullet_levels, decimal_levels, BULLET_NUM_ID, decimal_nums
+        );
+
+        zip_writer.start_file("word/numbering.xml", self.file_options())?;
+        zip_writer.write_all(content.as_bytes())?;
+        Ok(())
+    }
+
+    /// Footer with the footer text and, when enabled, a `PAGE` field
+    fn write_footer(&self, zip_writer: &mut ZipWriter<File>) -> Result<()> {
+        let options = &self.request.options;
+        let mut paragraphs = String::new();
+
+        if let Some(text) = &options.footer {
+            paragraphs.push_str(&format!(
+                r#"
+    <w:p>
+        <w:pPr>
+            <w:jc w:val="center"/>
+        </w:pPr>
+        <w:r>
+            <w:t xml:space="preserve">{}</w:t>
+        </w:r>
+    </w:p>"#,
+                self.escape_xml(text)
+            ));
+        }
+
+        if options.page_numbers {
+            paragraphs.push_str(
+                r#"
+    <w:p>
+        <w:pPr>
+            <w:jc w:val="center"/>
+        </w:pPr>
+        <w:fldSimple w:instr="PAGE \* MERGEFORMAT">
+            <w:r>
+                <w:t>1</w:t>
+            </w:r>
+        </w:fldSimple>
+    </w:p>"#,
+            );
+        }
+
+        let content = format!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:ftr xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">{}
+</w:ftr>"#,
+            paragraphs
+        );
+
+        zip_writer.start_file("word/footer1.xml", self.file_options())?;
+        zip_writer.write_all(content.as_bytes())?;
+        Ok(())
+    }
+
+    /// Table of contents heading and field over heading levels 1-3
+    ///
+    /// The heading is a bold run rather than a Heading style so it does not
+    /// list itself; Word fills the field in when fields are updated.
+    fn create_table_of_contents(&self) -> String {
+        let language = &self.request.options.language;
+        format!(
+            r#"
         <w:p>
             <w:r>
+                <w:rPr>
+                    <w:b/>
+                    <w:sz w:val="28"/>
+                </w:rPr>
                 <w:t>{}</w:t>
             </w:r>
+        </w:p>
+        <w:p>
+            <w:fldSimple w:instr="TOC \o &quot;1-3&quot; \h \z \u">
+                <w:r>
+                    <w:t>{}</w:t>
+                </w:r>
+            </w:fldSimple>
         </w:p>"#,
-                    self.escape_xml(text)
-                )
-            }
-            MarkdownElement::CodeBlock { language: _, code } => {
+            self.escape_xml(crate::i18n::translate(language, "create.toc_title")),
+            self.escape_xml(crate::i18n::translate(language, "create.toc_placeholder"))
+        )
+    }
+
+    fn create_title_paragraph(&self, title: &str) -> String {
+        format!(
+            r#"
+        <w:p>
+            <w:pPr>
+                <w:pStyle w:val="Title"/>
+                <w:jc w:val="center"/>
+            </w:pPr>
+            <w:r>
+                <w:rPr>
+                    <w:b/>
+                    <w:sz w:val="32"/>
+                </w:rPr>
+                <w:t>{}</w:t>
+            </w:r>
+        </w:p>"#,
+            self.escape_xml(title)
+        )
+    }
+
+    fn convert_element_to_word(&self, element: &MarkdownElement) -> String {
+        match element {
+            MarkdownElement::Heading { level, text } => {
+                let style = format!("Heading{}", level);
                 format!(
                     r#"
         <w:p>
             <w:pPr>
-                <w:pStyle w:val="Code"/>
+                <w:pStyle w:val="{}"/>
             </w:pPr>
             <w:r>
                 <w:rPr>
-                    <w:rFonts w:ascii="Courier New" w:hAnsi="Courier New"/>
-                    <w:sz w:val="20"/>
+                    <w:b/>
                 </w:rPr>
-                <w:t xml:space="preserve">{}</w:t>
+                <w:t>{}</w:t>
             </w:r>
         </w:p>"#,
-                    self.escape_xml(code)
+                    style,
+                    self.escape_xml(text)
                 )
             }
-            MarkdownElement::UnorderedList { items } => {
-                let mut list_xml = String::new();
-                for item in items {
-                    list_xml.push_str(&format!(
-                        r#"
+            MarkdownElement::Paragraph { runs, .. } if !runs.is_empty() => {
+                self.create_inline_paragraph(runs)
+            }
+            MarkdownElement::Paragraph { text, .. } => {
+                format!(
+                    r#"
         <w:p>
-            <w:pPr>
-                <w:pStyle w:val="ListParagraph"/>
-                <w:numPr>
-                    <w:ilvl w:val="0"/>
-                    <w:numId w:val="1"/>
-                </w:numPr>
-            </w:pPr>
             <w:r>
                 <w:t>{}</w:t>
             </w:r>
         </w:p>"#,
-                        self.escape_xml(item)
-                    ));
-                }
-                list_xml
+                    self.escape_xml(text)
+                )
             }
-            MarkdownElement::OrderedList { items } => {
-                let mut list_xml = String::new();
-                for item in items {
-                    list_xml.push_str(&format!(
-                        r#"
+            MarkdownElement::CodeBlock { language: _, code } => {
+                format!(
+                    r#"
         <w:p>
             <w:pPr>
-                <w:pStyle w:val="ListParagraph"/>
-                <w:numPr>
-                    <w:ilvl w:val="0"/>
-                    <w:numId w:val="2"/>
-                </w:numPr>
+                <w:pStyle w:val="Code"/>
             </w:pPr>
             <w:r>
-                <w:t>{}</w:t>
+                <w:rPr>
+                    <w:rFonts w:ascii="Courier New" w:hAnsi="Courier New"/>
+                    <w:sz w:val="20"/>
+                </w:rPr>
+                <w:t xml:space="preserve">{}</w:t>
             </w:r>
         </w:p>"#,
-                        self.escape_xml(item)
-                    ));
-                }
-                list_xml
+                    self.escape_xml(code)
+                )
+            }
+            MarkdownElement::UnorderedList { items } => self.create_list(items, BULLET_NUM_ID, 0),
+            MarkdownElement::OrderedList { items } => {
+                self.create_list(items, self.ordered_num_id(0), 0)
             }
             MarkdownElement::InlineCode { code } => {
                 format!(
@@ -869,13 +1713,328 @@ impl WordDocumentGenerator {
             </w:pPr>
         </w:p>"#
                 .to_string(),
-            _ => {
-                // Handle other elements or skip
-                String::new()
+            MarkdownElement::Table { headers, rows } => self.create_table(headers, rows),
+            MarkdownElement::Blockquote { content } => content
+                .lines()
+                .map(|line| {
+                    format!(
+                        r#"
+        <w:p>
+            <w:pPr>
+                <w:pStyle w:val="Quote"/>
+            </w:pPr>
+            <w:r>
+                <w:t xml:space="preserve">{}</w:t>
+            </w:r>
+        </w:p>"#,
+                        self.escape_xml(line)
+                    )
+                })
+                .collect(),
+            MarkdownElement::Bold { .. }
+            | MarkdownElement::Italic { .. }
+            | MarkdownElement::BoldItalic { .. }
+            | MarkdownElement::Strikethrough { .. }
+            | MarkdownElement::Link { .. }
+            | MarkdownElement::Image { .. }
+            | MarkdownElement::Text { .. } => {
+                self.create_inline_paragraph(std::slice::from_ref(element))
             }
+            MarkdownElement::LineBreak => String::new(),
         }
     }
 
+    /// `w:numId` for a new numbered list starting at `level`
+    fn ordered_num_id(&self, level: usize) -> u32 {
+        let mut lists = self.ordered_lists.borrow_mut();
+        lists.push(level.min(LIST_LEVELS - 1));
+        DECIMAL_NUM_ID + lists.len() as u32 - 1
+    }
+
+    /// List paragraphs at the given nesting level, followed by nested lists
+    fn create_list(&self, items: &[ListItem], num_id: u32, level: usize) -> String {
+        let mut list_xml = String::new();
+        for item in items {
+            list_xml.push_str(&format!(
+                r#"
+        <w:p>
+            <w:pPr>
+                <w:pStyle w:val="ListParagraph"/>
+                <w:numPr>
+                    <w:ilvl w:val="{}"/>
+                    <w:numId w:val="{}"/>
+                </w:numPr>
+            </w:pPr>{}
+        </w:p>"#,
+                level.min(LIST_LEVELS - 1),
+                num_id,
+                if item.runs.is_empty() {
+                    self.create_run(&MarkdownElement::Text {
+                        text: item.text.clone(),
+                    })
+                } else {
+                    item.runs.iter().map(|run| self.create_run(run)).collect()
+                }
+            ));
+
+            for child in &item.children {
+                list_xml.push_str(&match child {
+                    MarkdownElement::UnorderedList { items } => {
+                        self.create_list(items, BULLET_NUM_ID, level + 1)
+                    }
+                    MarkdownElement::OrderedList { items } => {
+                        self.create_list(items, self.ordered_num_id(level + 1), level + 1)
+                    }
+                    other => self.convert_element_to_word(other),
+                });
+            }
+        }
+        list_xml
+    }
+
+    /// Paragraph made of inline runs, skipped when none of them has content
+    fn create_inline_paragraph(&self, runs: &[MarkdownElement]) -> String {
+        let runs: String = runs.iter().map(|run| self.create_run(run)).collect();
+        if runs.is_empty() {
+            return String::new();
+        }
+
+        format!(
+            r#"
+        <w:p>{}
+        </w:p>"#,
+            runs
+        )
+    }
+
+    /// Word run for an inline element, with matching run properties
+    fn create_run(&self, element: &MarkdownElement) -> String {
+        let (text, run_properties) = match element {
+            MarkdownElement::Text { text } => (text.clone(), ""),
+            MarkdownElement::Bold { text } => (text.clone(), "<w:b/>"),
+            MarkdownElement::Italic { text } => (text.clone(), "<w:i/>"),
+            MarkdownElement::BoldItalic { text } => (text.clone(), "<w:b/><w:i/>"),
+            MarkdownElement::Strikethrough { text } => (text.clone(), "<w:strike/>"),
+            MarkdownElement::InlineCode { code } => (
+                code.clone(),
+                r#"<w:rFonts w:ascii="Courier New" w:hAnsi="Courier New"/>"#,
+            ),
+            MarkdownElement::Link { text, url } => (
+                if text.is_empty() { url } else { text }.clone(),
+                r#"<w:color w:val="0563C1"/><w:u w:val="single"/>"#,
+            ),
+            MarkdownElement::Image { alt, url } => {
+                if let Some(index) = self.images.iter().position(|image| &image.url == url) {
+                    return self.create_drawing_run(index, alt);
+                }
+                if alt.is_empty() {
+                    return String::new();
+                }
+                (format!("[{}]", alt), "<w:i/>")
+            }
+            MarkdownElement::LineBreak => {
+                return r#"
+            <w:r>
+                <w:br/>
+            </w:r>"#
+                    .to_string()
+            }
+            _ => return String::new(),
+        };
+
+        if text.is_empty() {
+            return String::new();
+        }
+
+        let run_properties = if run_properties.is_empty() {
+            String::new()
+        } else {
+            format!(
+                r#"
+                <w:rPr>
+                    {}
+                </w:rPr>"#,
+                run_properties
+            )
+        };
+
+        let run = format!(
+            r#"
+            <w:r>{}
+                <w:t xml:space="preserve">{}</w:t>
+            </w:r>"#,
+            run_properties,
+            self.escape_xml(&text)
+        );
+
+        // Links to outside the document open their target when clicked
+        let link = match element {
+            MarkdownElement::Link { url, .. } => self.links.iter().position(|link| link == url),
+            _ => None,
+        };
+        match link {
+            Some(index) => format!(
+                r#"
+            <w:hyperlink r:id="{}" w:history="1">{}
+            </w:hyperlink>"#,
+                self.link_relationship_id(index),
+                run
+            ),
+            None => run,
+        }
+    }
+
+    /// Run holding an inline picture, scaled down to the text width
+    fn create_drawing_run(&self, index: usize, alt: &str) -> String {
+        let image = &self.images[index];
+        // A4 width less both margins, in EMUs
+        let (cx, cy) = image.extent((11906 - 2 * 1440) * 635, u64::MAX);
+        let id = self.drawings.get() + 1;
+        self.drawings.set(id);
+
+        format!(
+            r#"
+            <w:r>
+                <w:drawing>
+                    <wp:inline distT="0" distB="0" distL="0" distR="0">
+                        <wp:extent cx="{cx}" cy="{cy}"/>
+                        <wp:docPr id="{id}" name="Picture {id}" descr="{alt}"/>
+                        <a:graphic xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main">
+                            <a:graphicData uri="http://schemas.openxmlformats.org/drawingml/2006/picture">
+                                <pic:pic xmlns:pic="http://schemas.openxmlformats.org/drawingml/2006/picture">
+                                    <pic:nvPicPr>
+                                        <pic:cNvPr id="{id}" name="{name}"/>
+                                        <pic:cNvPicPr/>
+                                    </pic:nvPicPr>
+                                    <pic:blipFill>
+                                        <a:blip r:embed="{rel}"/>
+                                        <a:stretch>
+                                            <a:fillRect/>
+                                        </a:stretch>
+                                    </pic:blipFill>
+                                    <pic:spPr>
+                                        <a:xfrm>
+                                            <a:off x="0" y="0"/>
+                                            <a:ext cx="{cx}" cy="{cy}"/>
+                                        </a:xfrm>
+                                        <a:prstGeom prst="rect">
+                                            <a:avLst/>
+                                        </a:prstGeom>
+                                    </pic:spPr>
+                                </pic:pic>
+                            </a:graphicData>
+                        </a:graphic>
+                    </wp:inline>
+                </w:drawing>
+            </w:r>"#,
+            cx = cx,
+            cy = cy,
+            id = id,
+            alt = self.escape_xml(alt),
+            name = image.file_name(index),
+            rel = self.image_relationship_id(index),
+        )
+    }
+
+    /// Bordered table with a repeating bold header row
+    fn create_table(&self, headers: &[String], rows: &[Vec<String>]) -> String {
+        let columns = rows
+            .iter()
+            .map(Vec::len)
+            .chain(std::iter::once(headers.len()))
+            .max()
+            .unwrap_or(0);
+        if columns == 0 {
+            return String::new();
+        }
+
+        // Page width minus margins, in twentieths of a point
+        let column_width = (11906 - 2 * 1440) / columns;
+        let border = r#"w:val="single" w:sz="4" w:space="0" w:color="auto""#;
+
+        let mut table_xml = format!(
+            r#"
+        <w:tbl>
+            <w:tblPr>
+                <w:tblW w:w="0" w:type="auto"/>
+                <w:tblBorders>
+                    <w:top {border}/>
+                    <w:left {border}/>
+                    <w:bottom {border}/>
+                    <w:right {border}/>
+                    <w:insideH {border}/>
+                    <w:insideV {border}/>
+                </w:tblBorders>
+                <w:tblLook w:val="04A0" w:firstRow="1" w:lastRow="0" w:firstColumn="0" w:lastColumn="0" w:noHBand="0" w:noVBand="1"/>
+            </w:tblPr>
+            <w:tblGrid>{}
+            </w:tblGrid>"#,
+            format!(
+                r#"
+                <w:gridCol w:w="{}"/>"#,
+                column_width
+            )
+            .repeat(columns)
+        );
+
+        let header_row = (!headers.is_empty()).then_some((headers, true));
+        for (cells, is_header) in header_row
+            .into_iter()
+            .chain(rows.iter().map(|row| (row.as_slice(), false)))
+        {
+            table_xml.push_str(
+                r#"
+            <w:tr>"#,
+            );
+            if is_header {
+                table_xml.push_str(
+                    r#"
+                <w:trPr>
+                    <w:tblHeader/>
+                </w:trPr>"#,
+                );
+            }
+
+            for column in 0..columns {
+                let text = cells.get(column).map(String::as_str).unwrap_or("");
+                table_xml.push_str(&format!(
+                    r#"
+                <w:tc>
+                    <w:tcPr>
+                        <w:tcW w:w="{}" w:type="dxa"/>
+                    </w:tcPr>
+                    <w:p>
+                        <w:r>{}
+                            <w:t xml:space="preserve">{}</w:t>
+                        </w:r>
+                    </w:p>
+                </w:tc>"#,
+                    column_width,
+                    if is_header {
+                        r#"
+                            <w:rPr>
+                                <w:b/>
+                            </w:rPr>"#
+                    } else {
+                        ""
+                    },
+                    self.escape_xml(text)
+                ));
+            }
+
+            table_xml.push_str(
+                r#"
+            </w:tr>"#,
+            );
+        }
+
+        table_xml.push_str(
+            r#"
+        </w:tbl>"#,
+        );
+        table_xml
+    }
+
     fn write_styles(&self, zip_writer: &mut ZipWriter<File>) -> Result<()> {
         let styles_xml = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
 <w:styles xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">
@@ -962,6 +2121,22 @@ impl WordDocumentGenerator {
         </w:rPr>
     </w:style>
     
+    <w:style w:type="paragraph" w:styleId="Quote">
+        <w:name w:val="Quote"/>
+        <w:basedOn w:val="Normal"/>
+        <w:qFormat/>
+        <w:pPr>
+            <w:pBdr>
+                <w:left w:val="single" w:sz="18" w:space="8" w:color="BFBFBF"/>
+            </w:pBdr>
+            <w:ind w:left="720" w:right="720"/>
+        </w:pPr>
+        <w:rPr>
+            <w:i/>
+            <w:color w:val="595959"/>
+        </w:rPr>
+    </w:style>
+    
     <w:style w:type="paragraph" w:styleId="ListParagraph">
         <w:name w:val="List Paragraph"/>
         <w:basedOn w:val="Normal"/>
@@ -972,7 +2147,7 @@ impl WordDocumentGenerator {
     </w:style>
 </w:styles>"#;
 
-        zip_writer.start_file("word/styles.xml", SimpleFileOptions::default())?;
+        zip_writer.start_file("word/styles.xml", self.file_options())?;
         zip_writer.write_all(styles_xml.as_bytes())?;
         Ok(())
     }
@@ -1003,7 +2178,8 @@ impl PowerPointDocumentCreator {
 
 impl DocumentCreator for PowerPointDocumentCreator {
     fn create_document(&self, markdown: &MarkdownDocument, request: &CreateRequest) -> Result<()> {
-        let generator = PowerPointDocumentGenerator::new(request.clone());
+        let generator = PowerPointDocumentGenerator::new(request.clone())
+            .with_images(images::load_images(markdown, &request.options));
         generator.generate(markdown)
     }
 
@@ -1012,57 +2188,114 @@ impl DocumentCreator for PowerPointDocumentCreator {
     }
 }
 
+/// Position and size of the content slide body, in EMUs
+const BODY_X: u64 = 457200;
+const BODY_Y: u64 = 1600200;
+const BODY_CX: u64 = 8229600;
+const BODY_CY: u64 = 4525963;
+
 /// PowerPoint document generator
 struct PowerPointDocumentGenerator {
     request: CreateRequest,
+    /// Images stored under `ppt/media/`
+    images: Vec<EmbeddedImage>,
+}
+
+/// Title, bullet text and pictures of a content slide
+struct SlideContent {
+    title: String,
+    bullets: Vec<String>,
+    /// Indexes into the generator's images
+    pictures: Vec<usize>,
 }
 
 impl PowerPointDocumentGenerator {
     fn new(request: CreateRequest) -> Self {
-        Self { request }
+        Self {
+            request,
+            images: Vec::new(),
+        }
+    }
+
+    fn with_images(mut self, images: Vec<EmbeddedImage>) -> Self {
+        self.images = images;
+        self
+    }
+
+    fn image_index(&self, url: &str) -> Option<usize> {
+        self.images.iter().position(|image| image.url == url)
+    }
+
+    fn file_options(&self) -> SimpleFileOptions {
+        self.request.options.compression.file_options()
     }
 }
 
 impl PowerPointDocumentGenerator {
     fn generate(&self, markdown: &MarkdownDocument) -> Result<()> {
-        use std::fs::File;
-
-        // Create output file
-        let output_file = File::create(&self.request.output_path)?;
-        let mut zip_writer = ZipWriter::new(output_file);
-
-        // Write all required PowerPoint files
-        self.write_content_types(&mut zip_writer)?;
-        self.write_app_properties(&mut zip_writer, markdown)?;
-        self.write_core_properties(&mut zip_writer, markdown)?;
-        self.write_presentation_relationships(&mut zip_writer)?;
-        self.write_main_presentation(&mut zip_writer, markdown)?;
-        self.write_slide_master(&mut zip_writer)?;
-        self.write_slide_layout(&mut zip_writer)?;
-        self.write_theme(&mut zip_writer)?;
-
-        // Generate slides from markdown sections
-        self.write_slides(&mut zip_writer, markdown)?;
-
-        zip_writer.finish()?;
-        Ok(())
+        write_atomic(Path::new(&self.request.output_path), |output_file| {
+            let mut zip_writer = ZipWriter::new(output_file);
+
+            // The title slide comes first, followed by one slide per section
+            let content_slides = self.content_slides(markdown);
+            let slide_count = content_slides.len() + 1;
+
+            // Write all required PowerPoint files
+            self.write_content_types(&mut zip_writer, slide_count)?;
+            self.write_app_properties(&mut zip_writer, slide_count)?;
+            self.write_core_properties(&mut zip_writer, markdown)?;
+            self.write_presentation_relationships(&mut zip_writer, slide_count)?;
+            self.write_main_presentation(&mut zip_writer, slide_count)?;
+            self.write_slide_master(&mut zip_writer)?;
+            self.write_slide_layout(&mut zip_writer)?;
+            self.write_theme(&mut zip_writer)?;
+            for (index, image) in self.images.iter().enumerate() {
+                zip_writer.start_file(
+                    format!("ppt/media/{}", image.file_name(index)),
+                    self.file_options(),
+                )?;
+                zip_writer.write_all(&image.data)?;
+            }
+
+            // Generate slides from markdown sections
+            self.write_slides(&mut zip_writer, markdown, &content_slides)?;
+
+            finish_package(zip_writer)
+        })
     }
 
-    fn write_content_types(&self, zip_writer: &mut ZipWriter<File>) -> Result<()> {
-        let content = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+    fn write_content_types(
+        &self,
+        zip_writer: &mut ZipWriter<File>,
+        slide_count: usize,
+    ) -> Result<()> {
+        let slide_overrides: String = (1..=slide_count)
+            .map(|n| {
+                format!(
+                    r#"
+    <Override PartName="/ppt/slides/slide{}.xml" ContentType="application/vnd.openxmlformats-presentationml.slide+xml"/>"#,
+                    n
+                )
+            })
+            .collect();
+
+        let content = format!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
 <Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">
     <Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>
-    <Default Extension="xml" ContentType="application/xml"/>
+    <Default Extension="xml" ContentType="application/xml"/>{}
     <Override PartName="/ppt/presentation.xml" ContentType="application/vnd.openxmlformats-presentationml.presentation.main+xml"/>
     <Override PartName="/ppt/slideMasters/slideMaster1.xml" ContentType="application/vnd.openxmlformats-presentationml.slideMaster+xml"/>
     <Override PartName="/ppt/slideLayouts/slideLayout1.xml" ContentType="application/vnd.openxmlformats-presentationml.slideLayout+xml"/>
-    <Override PartName="/ppt/theme/theme1.xml" ContentType="application/vnd.openxmlformats-officedocument.theme+xml"/>
-    <Override PartName="/ppt/slides/slide1.xml" ContentType="application/vnd.openxmlformats-presentationml.slide+xml"/>
+    <Override PartName="/ppt/theme/theme1.xml" ContentType="application/vnd.openxmlformats-officedocument.theme+xml"/>{}
     <Override PartName="/docProps/core.xml" ContentType="application/vnd.openxmlformats-package.core-properties+xml"/>
     <Override PartName="/docProps/app.xml" ContentType="application/vnd.openxmlformats-officedocument.extended-properties+xml"/>
-</Types>"#;
+</Types>"#,
+            image_content_types(&self.images),
+            slide_overrides
+        );
 
-        zip_writer.start_file("[Content_Types].xml", SimpleFileOptions::default())?;
+        zip_writer.start_file("[Content_Types].xml", self.file_options())?;
         zip_writer.write_all(content.as_bytes())?;
         Ok(())
     }
@@ -1070,9 +2303,10 @@ impl PowerPointDocumentGenerator {
     fn write_app_properties(
         &self,
         zip_writer: &mut ZipWriter<File>,
-        _markdown: &MarkdownDocument,
+        slide_count: usize,
     ) -> Result<()> {
-        let content = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+        let content = format!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
 <Properties xmlns="http://schemas.openxmlformats.org/officeDocument/2006/extended-properties" xmlns:vt="http://schemas.openxmlformats.org/officeDocument/2006/docPropsVTypes">
     <Application>dox</Application>
     <ScaleCrop>false</ScaleCrop>
@@ -1080,13 +2314,15 @@ impl PowerPointDocumentGenerator {
     <SharedDoc>false</SharedDoc>
     <HyperlinksChanged>false</HyperlinksChanged>
     <AppVersion>16.0000</AppVersion>
-    <Slides>1</Slides>
+    <Slides>{}</Slides>
     <HiddenSlides>0</HiddenSlides>
     <MMClips>0</MMClips>
     <Notes>0</Notes>
-</Properties>"#;
+</Properties>"#,
+            slide_count
+        );
 
-        zip_writer.start_file("docProps/app.xml", SimpleFileOptions::default())?;
+        zip_writer.start_file("docProps/app.xml", self.file_options())?;
         zip_writer.write_all(content.as_bytes())?;
         Ok(())
     }
@@ -1112,12 +2348,16 @@ impl PowerPointDocumentGenerator {
             title, creator, creator, now, now
         );
 
-        zip_writer.start_file("docProps/core.xml", SimpleFileOptions::default())?;
+        zip_writer.start_file("docProps/core.xml", self.file_options())?;
         zip_writer.write_all(content.as_bytes())?;
         Ok(())
     }
 
-    fn write_presentation_relationships(&self, zip_writer: &mut ZipWriter<File>) -> Result<()> {
+    fn write_presentation_relationships(
+        &self,
+        zip_writer: &mut ZipWriter<File>,
+        slide_count: usize,
+    ) -> Result<()> {
         // Main relationships file
         let main_rels_content = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
 <Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
@@ -1126,40 +2366,64 @@ impl PowerPointDocumentGenerator {
     <Relationship Id="rId3" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/extended-properties" Target="docProps/app.xml"/>
 </Relationships>"#;
 
-        zip_writer.start_file("_rels/.rels", SimpleFileOptions::default())?;
+        zip_writer.start_file("_rels/.rels", self.file_options())?;
         zip_writer.write_all(main_rels_content.as_bytes())?;
 
-        // Presentation relationships file
-        let ppt_rels_content = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+        // Presentation relationships file; slides use rId3 onwards
+        let slide_rels: String = (1..=slide_count)
+            .map(|n| {
+                format!(
+                    r#"
+    <Relationship Id="{}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slide" Target="slides/slide{}.xml"/>"#,
+                    Self::slide_relationship_id(n),
+                    n
+                )
+            })
+            .collect();
+
+        let ppt_rels_content = format!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
 <Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
     <Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slideMaster" Target="slideMasters/slideMaster1.xml"/>
-    <Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slide" Target="slides/slide1.xml"/>
-    <Relationship Id="rId3" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/theme" Target="theme/theme1.xml"/>
-</Relationships>"#;
+    <Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/theme" Target="theme/theme1.xml"/>{}
+</Relationships>"#,
+            slide_rels
+        );
 
-        zip_writer.start_file(
-            "ppt/_rels/presentation.xml.rels",
-            SimpleFileOptions::default(),
-        )?;
+        zip_writer.start_file("ppt/_rels/presentation.xml.rels", self.file_options())?;
         zip_writer.write_all(ppt_rels_content.as_bytes())?;
 
         Ok(())
     }
 
+    /// Relationship id of slide `n` (1-based) in `presentation.xml.rels`
+    fn slide_relationship_id(n: usize) -> String {
+        format!("rId{}", n + 2)
+    }
+
     fn write_main_presentation(
         &self,
         zip_writer: &mut ZipWriter<File>,
-        markdown: &MarkdownDocument,
+        slide_count: usize,
     ) -> Result<()> {
-        let title = markdown.title.as_deref().unwrap_or("Untitled");
+        let slide_ids: String = (1..=slide_count)
+            .map(|n| {
+                format!(
+                    r#"
+        <p:sldId id="{}" r:id="{}"/>"#,
+                    255 + n,
+                    Self::slide_relationship_id(n)
+                )
+            })
+            .collect();
 
-        let content = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+        let content = format!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
 <p:presentation xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main">
     <p:sldMasterIdLst>
         <p:sldMasterId id="2147483648" r:id="rId1"/>
     </p:sldMasterIdLst>
-    <p:sldIdLst>
-        <p:sldId id="256" r:id="rId2"/>
+    <p:sldIdLst>{}
     </p:sldIdLst>
     <p:sldSz cx="9144000" cy="6858000" type="screen4x3"/>
     <p:notesSz cx="6858000" cy="9144000"/>
@@ -1178,17 +2442,140 @@ impl PowerPointDocumentGenerator {
             </a:defRPr>
         </a:lvl1pPr>
     </p:defaultTextStyle>
-</p:presentation>"#.to_string();
+</p:presentation>"#,
+            slide_ids
+        );
+
+        zip_writer.start_file("ppt/presentation.xml", self.file_options())?;
+        zip_writer.write_all(content.as_bytes())?;
+        Ok(())
+    }
+
+    /// Build the content slides, starting a new slide at every heading of
+    /// `slide_level` or above
+    ///
+    /// Deeper headings and body text become bullets of the current slide;
+    /// text before the first such heading goes on a slide named after its
+    /// section. A slide holding nothing but the document title is already
+    /// covered by the title slide and is skipped.
+    ///
+    /// Embedded images go on the slide as pictures; a paragraph made of
+    /// nothing else adds no bullet.
+    fn content_slides(&self, markdown: &MarkdownDocument) -> Vec<SlideContent> {
+        let slide_level = self.request.options.slide_level.max(1);
+        let mut slides = Vec::new();
+        // Title of the slide being filled; bullets and pictures go in `slide`
+        let mut current: Option<(Option<String>, SlideContent)> = None;
+
+        let mut finish = |current: Option<(Option<String>, SlideContent)>| {
+            let Some((title, mut slide)) = current else {
+                return;
+            };
+            if slide.bullets.is_empty()
+                && slide.pictures.is_empty()
+                && (title.is_none() || title == markdown.title)
+            {
+                return;
+            }
+            slide.title = title.unwrap_or_default();
+            slides.push(slide);
+        };
+        let start = |title: Option<String>| {
+            (
+                title,
+                SlideContent {
+                    title: String::new(),
+                    bullets: Vec::new(),
+                    pictures: Vec::new(),
+                },
+            )
+        };
+
+        for section in &markdown.sections {
+            for element in &section.content {
+                if let MarkdownElement::Heading { level, text } = element {
+                    if *level <= slide_level {
+                        finish(current.replace(start(Some(text.clone()))));
+                        continue;
+                    }
+                }
 
-        zip_writer.start_file("ppt/presentation.xml", SimpleFileOptions::default())?;
-        zip_writer.write_all(content.as_bytes())?;
-        Ok(())
+                let mut urls = Vec::new();
+                images::collect_urls(std::slice::from_ref(element), &mut urls);
+                let is_embedded = |run: &MarkdownElement| match run {
+                    MarkdownElement::Image { url, .. } => self.image_index(url).is_some(),
+                    MarkdownElement::Text { text } => text.trim().is_empty(),
+                    _ => false,
+                };
+
+                let texts = match element {
+                    MarkdownElement::Paragraph { runs, .. }
+                        if !runs.is_empty() && runs.iter().all(is_embedded) =>
+                    {
+                        vec![]
+                    }
+                    MarkdownElement::Heading { text, .. }
+                    | MarkdownElement::Paragraph { text, .. }
+                    | MarkdownElement::Blockquote { content: text } => vec![text.clone()],
+                    MarkdownElement::UnorderedList { items }
+                    | MarkdownElement::OrderedList { items } => list_texts(items),
+                    _ => vec![],
+                };
+                let slide = &mut current
+                    .get_or_insert_with(|| start(section.title.clone()))
+                    .1;
+                slide
+                    .bullets
+                    .extend(texts.into_iter().filter(|text| !text.trim().is_empty()));
+                for index in urls.iter().filter_map(|url| self.image_index(url)) {
+                    if !slide.pictures.contains(&index) {
+                        slide.pictures.push(index);
+                    }
+                }
+            }
+        }
+        finish(current);
+
+        // The agenda lists the section slides right after the title slide
+        if self.request.options.include_toc && !slides.is_empty() {
+            let agenda = SlideContent {
+                title: crate::i18n::translate(
+                    &self.request.options.language,
+                    "create.agenda_title",
+                )
+                .to_string(),
+                bullets: slides
+                    .iter()
+                    .map(|slide| slide.title.clone())
+                    .filter(|title| !title.is_empty())
+                    .collect(),
+                pictures: Vec::new(),
+            };
+            slides.insert(0, agenda);
+        }
+
+        slides
     }
 
     fn write_slides(
         &self,
         zip_writer: &mut ZipWriter<File>,
         markdown: &MarkdownDocument,
+        content_slides: &[SlideContent],
+    ) -> Result<()> {
+        self.write_title_slide(zip_writer, markdown)?;
+
+        for (index, slide) in content_slides.iter().enumerate() {
+            self.write_content_slide(zip_writer, index + 2, slide)?;
+        }
+
+        Ok(())
+    }
+
+    fn write_title_slide(
+        &self,
+        zip_writer: &mut ZipWriter<File>,
+        markdown: &MarkdownDocument,
     ) -> Result<()> {
         // Generate title slide
         let title = markdown.title.as_deref().unwrap_or("Untitled");
@@ -1280,8 +2667,237 @@ impl PowerPointDocumentGenerator {
             self.escape_xml(&subtitle)
         );
 
-        zip_writer.start_file("ppt/slides/slide1.xml", SimpleFileOptions::default())?;
+        self.write_slide_part(zip_writer, 1, &slide_content, &[])
+    }
+
+    fn write_content_slide(
+        &self,
+        zip_writer: &mut ZipWriter<File>,
+        number: usize,
+        slide: &SlideContent,
+    ) -> Result<()> {
+        // Pictures share the body area with the bullets, taking its right half
+        let (body_cx, picture_x, picture_cx) =
+            match (slide.bullets.is_empty(), slide.pictures.is_empty()) {
+                (false, false) => (BODY_CX / 2, BODY_X + BODY_CX / 2, BODY_CX / 2),
+                _ => (BODY_CX, BODY_X, BODY_CX),
+            };
+
+        let paragraphs: String = slide
+            .bullets
+            .iter()
+            .map(|bullet| {
+                format!(
+                    r#"
+                    <a:p>
+                        <a:pPr marL="342900" indent="-342900">
+                            <a:buFont typeface="Arial"/>
+                            <a:buChar char="•"/>
+                        </a:pPr>
+                        <a:r>
+                            <a:rPr lang="ko-KR" sz="2000">
+                                <a:latin typeface="맑은 고딕"/>
+                                <a:ea typeface="맑은 고딕"/>
+                            </a:rPr>
+                            <a:t>{}</a:t>
+                        </a:r>
+                    </a:p>"#,
+                    self.escape_xml(bullet)
+                )
+            })
+            .collect();
+
+        let slide_content = format!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<p:sld xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main">
+    <p:cSld>
+        <p:spTree>
+            <p:nvGrpSpPr>
+                <p:cNvPr id="1" name=""/>
+                <p:cNvGrpSpPr/>
+                <p:nvPr/>
+            </p:nvGrpSpPr>
+            <p:grpSpPr>
+                <a:xfrm>
+                    <a:off x="0" y="0"/>
+                    <a:ext cx="0" cy="0"/>
+                    <a:chOff x="0" y="0"/>
+                    <a:chExt cx="0" cy="0"/>
+                </a:xfrm>
+            </p:grpSpPr>
+            <!-- Title placeholder -->
+            <p:sp>
+                <p:nvSpPr>
+                    <p:cNvPr id="2" name="Title"/>
+                    <p:cNvSpPr>
+                        <a:spLocks noGrp="1"/>
+                    </p:cNvSpPr>
+                    <p:nvPr>
+                        <p:ph type="title"/>
+                    </p:nvPr>
+                </p:nvSpPr>
+                <p:spPr>
+                    <a:xfrm>
+                        <a:off x="457200" y="274638"/>
+                        <a:ext cx="8229600" cy="1143000"/>
+                    </a:xfrm>
+                </p:spPr>
+                <p:txBody>
+                    <a:bodyPr/>
+                    <a:lstStyle/>
+                    <a:p>
+                        <a:r>
+                            <a:rPr lang="ko-KR" sz="3200" b="1">
+                                <a:latin typeface="맑은 고딕"/>
+                                <a:ea typeface="맑은 고딕"/>
+                            </a:rPr>
+                            <a:t>{}</a:t>
+                        </a:r>
+                    </a:p>
+                </p:txBody>
+            </p:sp>
+            <!-- Body placeholder -->
+            <p:sp>
+                <p:nvSpPr>
+                    <p:cNvPr id="3" name="Content"/>
+                    <p:cNvSpPr>
+                        <a:spLocks noGrp="1"/>
+                    </p:cNvSpPr>
+                    <p:nvPr>
+                        <p:ph idx="1"/>
+                    </p:nvPr>
+                </p:nvSpPr>
+                <p:spPr>
+                    <a:xfrm>
+                        <a:off x="457200" y="1600200"/>
+                        <a:ext cx="{}" cy="{}"/>
+                    </a:xfrm>
+                </p:spPr>
+                <p:txBody>
+                    <a:bodyPr>
+                        <a:normAutofit/>
+                    </a:bodyPr>
+                    <a:lstStyle/>{}
+                </p:txBody>
+            </p:sp>{}
+        </p:spTree>
+    </p:cSld>
+    <p:clrMapOvr>
+        <a:masterClrMapping/>
+    </p:clrMapOvr>
+</p:sld>"#,
+            self.escape_xml(&slide.title),
+            body_cx,
+            BODY_CY,
+            // A text body needs at least one paragraph
+            if paragraphs.is_empty() {
+                "\n                    <a:p/>".to_string()
+            } else {
+                paragraphs
+            },
+            self.create_pictures(&slide.pictures, picture_x, picture_cx)
+        );
+
+        self.write_slide_part(zip_writer, number, &slide_content, &slide.pictures)
+    }
+
+    /// Picture shapes stacked in the column from `x`, `cx` wide, each
+    /// centred in an equal share of the body height
+    ///
+    /// Shape ids follow the title and body placeholders. A picture's
+    /// relationship is `rId2` onwards in the order of `pictures`.
+    fn create_pictures(&self, pictures: &[usize], x: u64, cx: u64) -> String {
+        let Some(cell_cy) = BODY_CY.checked_div(pictures.len() as u64) else {
+            return String::new();
+        };
+
+        pictures
+            .iter()
+            .enumerate()
+            .map(|(position, &index)| {
+                let image = &self.images[index];
+                let (image_cx, image_cy) = image.extent(cx, cell_cy);
+                let off_x = x + (cx - image_cx) / 2;
+                let off_y = BODY_Y + position as u64 * cell_cy + (cell_cy - image_cy) / 2;
+                format!(
+                    r#"
+            <p:pic>
+                <p:nvPicPr>
+                    <p:cNvPr id="{}" name="{}"/>
+                    <p:cNvPicPr>
+                        <a:picLocks noChangeAspect="1"/>
+                    </p:cNvPicPr>
+                    <p:nvPr/>
+                </p:nvPicPr>
+                <p:blipFill>
+                    <a:blip r:embed="rId{}"/>
+                    <a:stretch>
+                        <a:fillRect/>
+                    </a:stretch>
+                </p:blipFill>
+                <p:spPr>
+                    <a:xfrm>
+                        <a:off x="{}" y="{}"/>
+                        <a:ext cx="{}" cy="{}"/>
+                    </a:xfrm>
+                    <a:prstGeom prst="rect">
+                        <a:avLst/>
+                    </a:prstGeom>
+                </p:spPr>
+            </p:pic>"#,
+                    position + 4,
+                    image.file_name(index),
+                    position + 2,
+                    off_x,
+                    off_y,
+                    image_cx,
+                    image_cy
+                )
+            })
+            .collect()
+    }
+
+    /// Write `slideN.xml` together with its relationships to the slide
+    /// layout and the images of `pictures`
+    fn write_slide_part(
+        &self,
+        zip_writer: &mut ZipWriter<File>,
+        number: usize,
+        slide_content: &str,
+        pictures: &[usize],
+    ) -> Result<()> {
+        zip_writer.start_file(
+            format!("ppt/slides/slide{}.xml", number),
+            self.file_options(),
+        )?;
         zip_writer.write_all(slide_content.as_bytes())?;
+
+        let image_rels: String = pictures
+            .iter()
+            .enumerate()
+            .map(|(position, &index)| {
+                format!(
+                    r#"
+    <Relationship Id="rId{}" Type="{}" Target="../media/{}"/>"#,
+                    position + 2,
+                    IMAGE_RELATIONSHIP,
+                    self.images[index].file_name(index)
+                )
+            })
+            .collect();
+        let rels_content = format!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+    <Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slideLayout" Target="../slideLayouts/slideLayout1.xml"/>{}
+</Relationships>"#,
+            image_rels
+        );
+
+        zip_writer.start_file(
+            format!("ppt/slides/_rels/slide{}.xml.rels", number),
+            self.file_options(),
+        )?;
+        zip_writer.write_all(rels_content.as_bytes())?;
         Ok(())
     }
 
@@ -1346,10 +2962,7 @@ impl PowerPointDocumentGenerator {
     </p:txStyles>
 </p:sldMaster>"#;
 
-        zip_writer.start_file(
-            "ppt/slideMasters/slideMaster1.xml",
-            SimpleFileOptions::default(),
-        )?;
+        zip_writer.start_file("ppt/slideMasters/slideMaster1.xml", self.file_options())?;
         zip_writer.write_all(content.as_bytes())?;
         Ok(())
     }
@@ -1379,10 +2992,7 @@ impl PowerPointDocumentGenerator {
     </p:clrMapOvr>
 </p:sldLayout>"#;
 
-        zip_writer.start_file(
-            "ppt/slideLayouts/slideLayout1.xml",
-            SimpleFileOptions::default(),
-        )?;
+        zip_writer.start_file("ppt/slideLayouts/slideLayout1.xml", self.file_options())?;
         zip_writer.write_all(content.as_bytes())?;
         Ok(())
     }
@@ -1588,7 +3198,7 @@ impl PowerPointDocumentGenerator {
     </a:extLst>
 </a:theme>"#;
 
-        zip_writer.start_file("ppt/theme/theme1.xml", SimpleFileOptions::default())?;
+        zip_writer.start_file("ppt/theme/theme1.xml", self.file_options())?;
         zip_writer.write_all(content.as_bytes())?;
         Ok(())
     }
@@ -1601,3 +3211,810 @@ impl PowerPointDocumentGenerator {
             .replace("\"", "&quot;")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    fn read_part(archive: &mut zip::ZipArchive<File>, name: &str) -> String {
+        let mut content = String::new();
+        archive
+            .by_name(name)
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+        content
+    }
+
+    #[test]
+    fn test_powerpoint_slide_per_section() {
+        let markdown = "# Quarterly Report\n\n\
+            ## Overview\n\nRevenue grew this quarter.\n\n\
+            ## Highlights\n\n- New customers\n- Lower costs\n\n\
+            ## Next Steps\n\n1. Hire\n2. Expand\n";
+
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("report.pptx");
+        let request = CreateRequest {
+            content: markdown.to_string(),
+            format: OutputFormat::PowerPoint,
+            template_path: None,
+            output_path: output.to_string_lossy().to_string(),
+            options: CreateOptions {
+                slide_level: 2,
+                ..CreateOptions::default()
+            },
+        };
+
+        let document = MarkdownParser::new(CreateOptions::default())
+            .parse(markdown)
+            .unwrap();
+        PowerPointDocumentCreator::new()
+            .create_document(&document, &request)
+            .unwrap();
+
+        let mut archive = zip::ZipArchive::new(File::open(&output).unwrap()).unwrap();
+        assert!(archive.by_name("ppt/slides/slide5.xml").is_err());
+
+        let presentation = read_part(&mut archive, "ppt/presentation.xml");
+        assert_eq!(presentation.matches("<p:sldId ").count(), 4);
+        let content_types = read_part(&mut archive, "[Content_Types].xml");
+        assert_eq!(content_types.matches("/ppt/slides/slide").count(), 4);
+        let rels = read_part(&mut archive, "ppt/_rels/presentation.xml.rels");
+        assert!(rels.contains("slides/slide4.xml"));
+
+        assert!(read_part(&mut archive, "ppt/slides/slide1.xml").contains("Quarterly Report"));
+        let expected = [
+            (2, "Overview", "Revenue grew this quarter."),
+            (3, "Highlights", "Lower costs"),
+            (4, "Next Steps", "Expand"),
+        ];
+        for (number, title, body) in expected {
+            let slide = read_part(&mut archive, &format!("ppt/slides/slide{}.xml", number));
+            assert!(slide.contains(&format!("<a:t>{}</a:t>", title)));
+            assert!(slide.contains(&format!("<a:t>{}</a:t>", body)));
+            read_part(
+                &mut archive,
+                &format!("ppt/slides/_rels/slide{}.xml.rels", number),
+            );
+        }
+    }
+
+    #[test]
+    fn test_powerpoint_slide_level() {
+        let markdown = "# Handbook\n\nTeam guide.\n\n\
+            ## Setup\n\nInstall the tools.\n\n### Windows\n\nUse the installer.\n\n\
+            ## Usage\n\nRun the command.\n\n\
+            # Appendix\n\n## Glossary\n\nTerms.\n";
+
+        let slides = |slide_level| {
+            let options = CreateOptions {
+                slide_level,
+                ..CreateOptions::default()
+            };
+            let request = CreateRequest {
+                content: markdown.to_string(),
+                format: OutputFormat::PowerPoint,
+                template_path: None,
+                output_path: String::new(),
+                options: options.clone(),
+            };
+            let document = MarkdownParser::new(options).parse(markdown).unwrap();
+            PowerPointDocumentGenerator::new(request).content_slides(&document)
+        };
+
+        // One slide per H1, with the H2 and H3 sections as bullets
+        let by_chapter = slides(1);
+        let titles: Vec<&str> = by_chapter.iter().map(|s| s.title.as_str()).collect();
+        assert_eq!(titles, ["Handbook", "Appendix"]);
+        assert_eq!(
+            by_chapter[0].bullets,
+            [
+                "Team guide.",
+                "Setup",
+                "Install the tools.",
+                "Windows",
+                "Use the installer.",
+                "Usage",
+                "Run the command."
+            ]
+        );
+        assert_eq!(by_chapter[1].bullets, ["Glossary", "Terms."]);
+
+        // One slide per H2; an H1 without text of its own is a divider slide
+        let by_section = slides(2);
+        let titles: Vec<&str> = by_section.iter().map(|s| s.title.as_str()).collect();
+        assert_eq!(
+            titles,
+            ["Handbook", "Setup", "Usage", "Appendix", "Glossary"]
+        );
+        assert_eq!(
+            by_section[1].bullets,
+            ["Install the tools.", "Windows", "Use the installer."]
+        );
+        assert_eq!(slides(3).len(), 6);
+    }
+
+    fn create_with_toc(
+        format: OutputFormat,
+        file_name: &str,
+        include_toc: bool,
+    ) -> (tempfile::TempDir, zip::ZipArchive<File>) {
+        let options = CreateOptions {
+            include_toc,
+            language: "en".to_string(),
+            slide_level: 2,
+            ..CreateOptions::default()
+        };
+        create_with_options(format, file_name, options)
+    }
+
+    fn create_with_options(
+        format: OutputFormat,
+        file_name: &str,
+        options: CreateOptions,
+    ) -> (tempfile::TempDir, zip::ZipArchive<File>) {
+        let markdown =
+            "# Handbook\n\n## Setup\n\nInstall the tools.\n\n## Usage\n\nRun the command.\n";
+        create_from_markdown(format, file_name, markdown, options)
+    }
+
+    fn create_from_markdown(
+        format: OutputFormat,
+        file_name: &str,
+        markdown: &str,
+        options: CreateOptions,
+    ) -> (tempfile::TempDir, zip::ZipArchive<File>) {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join(file_name);
+        let request = CreateRequest {
+            content: markdown.to_string(),
+            format,
+            template_path: None,
+            output_path: output.to_string_lossy().to_string(),
+            options: options.clone(),
+        };
+
+        let document = MarkdownParser::new(options).parse(markdown).unwrap();
+        DocumentCreatorFactory::create_creator(format)
+            .unwrap()
+            .create_document(&document, &request)
+            .unwrap();
+
+        let archive = zip::ZipArchive::new(File::open(&output).unwrap()).unwrap();
+        (dir, archive)
+    }
+
+    #[test]
+    fn test_word_table_of_contents() {
+        let (_dir, mut archive) = create_with_toc(OutputFormat::Word, "handbook.docx", true);
+
+        let document = read_part(&mut archive, "word/document.xml");
+        assert!(document.contains(r#"<w:fldSimple w:instr="TOC \o &quot;1-3&quot; \h \z \u">"#));
+        assert!(document.contains("<w:t>Contents</w:t>"));
+        assert!(document.find("Contents").unwrap() < document.find("Setup").unwrap());
+        assert!(read_part(&mut archive, "word/settings.xml").contains("w:updateFields"));
+        assert!(read_part(&mut archive, "word/_rels/document.xml.rels").contains("settings.xml"));
+    }
+
+    #[test]
+    fn test_word_without_table_of_contents() {
+        let (_dir, mut archive) = create_with_toc(OutputFormat::Word, "handbook.docx", false);
+
+        let document = read_part(&mut archive, "word/document.xml");
+        assert!(!document.contains("fldSimple"));
+        assert!(!document.contains("TOC"));
+        assert!(archive.by_name("word/settings.xml").is_err());
+    }
+
+    #[test]
+    fn test_powerpoint_agenda_slide() {
+        let (_dir, mut archive) = create_with_toc(OutputFormat::PowerPoint, "handbook.pptx", true);
+
+        let agenda = read_part(&mut archive, "ppt/slides/slide2.xml");
+        assert!(agenda.contains("<a:t>Agenda</a:t>"));
+        assert!(agenda.contains("<a:t>Setup</a:t>"));
+        assert!(agenda.contains("<a:t>Usage</a:t>"));
+        assert!(read_part(&mut archive, "ppt/slides/slide3.xml").contains("<a:t>Setup</a:t>"));
+
+        let (_dir, mut archive) = create_with_toc(OutputFormat::PowerPoint, "handbook.pptx", false);
+        assert!(!read_part(&mut archive, "ppt/slides/slide2.xml").contains("Agenda"));
+    }
+
+    #[test]
+    fn test_word_header_footer_and_page_numbers() {
+        let options = CreateOptions {
+            header: Some("Internal & Confidential".to_string()),
+            footer: Some("ACME Corp".to_string()),
+            page_numbers: true,
+            ..CreateOptions::default()
+        };
+        let (_dir, mut archive) = create_with_options(OutputFormat::Word, "report.docx", options);
+
+        let header = read_part(&mut archive, "word/header1.xml");
+        assert!(header.contains("Internal &amp; Confidential"));
+        let footer = read_part(&mut archive, "word/footer1.xml");
+        assert!(footer.contains("ACME Corp"));
+        assert!(footer.contains(r#"w:instr="PAGE \* MERGEFORMAT""#));
+
+        let content_types = read_part(&mut archive, "[Content_Types].xml");
+        assert!(content_types.contains(r#"PartName="/word/header1.xml""#));
+        assert!(content_types.contains(r#"PartName="/word/footer1.xml""#));
+        let rels = read_part(&mut archive, "word/_rels/document.xml.rels");
+        assert!(rels.contains(r#"Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/header" Target="header1.xml""#));
+        assert!(rels.contains(r#"Id="rId3" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/footer" Target="footer1.xml""#));
+        let document = read_part(&mut archive, "word/document.xml");
+        assert!(document.contains(r#"<w:headerReference w:type="default" r:id="rId2"/>"#));
+        assert!(document.contains(r#"<w:footerReference w:type="default" r:id="rId3"/>"#));
+    }
+
+    #[test]
+    fn test_word_page_numbers_without_footer_text() {
+        let (_dir, mut archive) =
+            create_with_options(OutputFormat::Word, "report.docx", CreateOptions::default());
+
+        assert!(archive.by_name("word/header1.xml").is_err());
+        let footer = read_part(&mut archive, "word/footer1.xml");
+        assert!(footer.contains("PAGE"));
+    }
+
+    #[test]
+    fn test_word_without_header_or_footer() {
+        let options = CreateOptions {
+            page_numbers: false,
+            ..CreateOptions::default()
+        };
+        let (_dir, mut archive) = create_with_options(OutputFormat::Word, "report.docx", options);
+
+        assert!(archive.by_name("word/header1.xml").is_err());
+        assert!(archive.by_name("word/footer1.xml").is_err());
+        assert!(!read_part(&mut archive, "[Content_Types].xml").contains("header"));
+        assert!(!read_part(&mut archive, "word/_rels/document.xml.rels").contains("footer"));
+        let document = read_part(&mut archive, "word/document.xml");
+        assert!(!document.contains("headerReference"));
+        assert!(!document.contains("footerReference"));
+    }
+
+    /// Create a document from `markdown` next to a 2x1 PNG named `chart.png`
+    fn create_with_image(
+        format: OutputFormat,
+        file_name: &str,
+        markdown: &str,
+    ) -> (tempfile::TempDir, zip::ZipArchive<File>) {
+        let dir = tempfile::tempdir().unwrap();
+        let mut png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec();
+        png.extend_from_slice(&2u32.to_be_bytes());
+        png.extend_from_slice(&1u32.to_be_bytes());
+        png.extend_from_slice(&[8, 6, 0, 0, 0]);
+        std::fs::write(dir.path().join("chart.png"), &png).unwrap();
+
+        let options = CreateOptions {
+            image_base_dir: Some(dir.path().to_path_buf()),
+            ..CreateOptions::default()
+        };
+        let output = dir.path().join(file_name);
+        let request = CreateRequest {
+            content: markdown.to_string(),
+            format,
+            template_path: None,
+            output_path: output.to_string_lossy().to_string(),
+            options: options.clone(),
+        };
+        let document = MarkdownParser::new(options).parse(markdown).unwrap();
+        DocumentCreatorFactory::create_creator(format)
+            .unwrap()
+            .create_document(&document, &request)
+            .unwrap();
+
+        let archive = zip::ZipArchive::new(File::open(&output).unwrap()).unwrap();
+        (dir, archive)
+    }
+
+    #[test]
+    fn test_word_embeds_local_image() {
+        let markdown = "# Sales\n\n![Monthly chart](chart.png)\n\n\
+            ![Logo](https://example.com/logo.png)\n";
+        let (_dir, mut archive) = create_with_image(OutputFormat::Word, "sales.docx", markdown);
+
+        assert!(archive.by_name("word/media/image1.png").is_ok());
+        assert!(read_part(&mut archive, "[Content_Types].xml")
+            .contains(r#"<Default Extension="png" ContentType="image/png"/>"#));
+        // The footer for page numbers is rId2, so the image follows it
+        let rels = read_part(&mut archive, "word/_rels/document.xml.rels");
+        assert!(rels.contains(r#"Id="rId3" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/image" Target="media/image1.png""#));
+
+        let document = read_part(&mut archive, "word/document.xml");
+        assert!(document.contains(r#"<a:blip r:embed="rId3"/>"#));
+        assert!(document.contains(r#"descr="Monthly chart""#));
+        assert!(document.contains(r#"<wp:extent cx="19050" cy="9525"/>"#));
+        // Remote images are only fetched on request; the alt text stays
+        assert!(document.contains("[Logo]"));
+        assert_eq!(document.matches("<w:drawing>").count(), 1);
+    }
+
+    #[test]
+    fn test_word_links_and_combined_emphasis() {
+        let markdown = "See [the guide](https://example.com/guide?a=1&b=2), \
+            [the guide](https://example.com/guide?a=1&b=2) again and [notes](#notes).\n\n\
+            ***Important***\n";
+        let (_dir, mut archive) = create_from_markdown(
+            OutputFormat::Word,
+            "links.docx",
+            markdown,
+            CreateOptions::default(),
+        );
+
+        // One external relationship per distinct target
+        let rels = read_part(&mut archive, "word/_rels/document.xml.rels");
+        let link = r#"Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/hyperlink" Target="https://example.com/guide?a=1&amp;b=2" TargetMode="External""#;
+        assert_eq!(rels.matches(link).count(), 1);
+        let id = rels[..rels.find(link).unwrap()]
+            .rsplit(r#"Id=""#)
+            .next()
+            .and_then(|rest| rest.split('"').next())
+            .unwrap();
+
+        let document = read_part(&mut archive, "word/document.xml");
+        assert_eq!(
+            document
+                .matches(&format!(r#"<w:hyperlink r:id="{}""#, id))
+                .count(),
+            2
+        );
+        // Links inside the document stay styled text
+        assert_eq!(document.matches("<w:hyperlink ").count(), 2);
+        assert!(document.contains(">notes<"));
+
+        let important = &document[..document.find(">Important<").unwrap()];
+        let important = &important[important.rfind("<w:r>").unwrap()..];
+        assert!(important.contains("<w:b/><w:i/>"));
+    }
+
+    #[test]
+    fn test_powerpoint_embeds_local_image() {
+        let markdown = "# Sales\n\n## Trend\n\nRevenue grew.\n\n![Monthly chart](chart.png)\n";
+        let (_dir, mut archive) =
+            create_with_image(OutputFormat::PowerPoint, "sales.pptx", markdown);
+
+        assert!(archive.by_name("ppt/media/image1.png").is_ok());
+        assert!(read_part(&mut archive, "[Content_Types].xml")
+            .contains(r#"<Default Extension="png" ContentType="image/png"/>"#));
+        let rels = read_part(&mut archive, "ppt/slides/_rels/slide2.xml.rels");
+        assert!(rels.contains(r#"Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/image" Target="../media/image1.png""#));
+
+        let slide = read_part(&mut archive, "ppt/slides/slide2.xml");
+        assert!(slide.contains(r#"<a:blip r:embed="rId2"/>"#));
+        assert!(slide.contains("<a:t>Revenue grew.</a:t>"));
+        // A paragraph holding only the picture adds no bullet
+        assert!(!slide.contains("Monthly chart"));
+    }
+
+    fn paragraph_runs(markdown: &str) -> (String, Vec<MarkdownElement>) {
+        let document = MarkdownParser::new(CreateOptions::default())
+            .parse(markdown)
+            .unwrap();
+        match &document.sections[0].content[0] {
+            MarkdownElement::Paragraph { text, runs } => (text.clone(), runs.clone()),
+            other => panic!("expected a paragraph, got {:?}", other),
+        }
+    }
+
+    fn describe(runs: &[MarkdownElement]) -> Vec<String> {
+        runs.iter()
+            .map(|run| match run {
+                MarkdownElement::Text { text } => format!("text:{}", text),
+                MarkdownElement::Bold { text } => format!("bold:{}", text),
+                MarkdownElement::Italic { text } => format!("italic:{}", text),
+                MarkdownElement::BoldItalic { text } => format!("bold-italic:{}", text),
+                MarkdownElement::Strikethrough { text } => format!("strike:{}", text),
+                MarkdownElement::InlineCode { code } => format!("code:{}", code),
+                MarkdownElement::Link { text, url } => format!("link:{}->{}", text, url),
+                MarkdownElement::Image { alt, url } => format!("image:{}->{}", alt, url),
+                MarkdownElement::LineBreak => "break".to_string(),
+                other => panic!("unexpected inline element {:?}", other),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_frontmatter_fields() {
+        let parser = MarkdownParser::new(CreateOptions::default());
+        let document = parser
+            .parse(
+                "---\ntitle: Report\ndate: 2024-03-15\ntags: [a, b]\nversion: 2\n\
+                 owner:\n  name: Kim\n  team: docs\nreviewers:\n  - Lee\n  - Park\n---\n\
+                 # Body\n",
+            )
+            .unwrap();
+
+        let metadata = &document.metadata;
+        assert_eq!(metadata.title.as_deref(), Some("Report"));
+        assert_eq!(metadata.date.as_deref(), Some("2024-03-15"));
+        assert_eq!(metadata.tags, vec!["a", "b"]);
+        assert_eq!(metadata.custom["version"], "2");
+        assert_eq!(metadata.custom["owner"], r#"{"name":"Kim","team":"docs"}"#);
+        assert_eq!(metadata.custom["reviewers"], r#"["Lee","Park"]"#);
+        assert!(!metadata.custom.contains_key("tags"));
+
+        let document = parser
+            .parse("---\ntags: draft, internal\n---\nText\n")
+            .unwrap();
+        assert_eq!(document.metadata.tags, vec!["draft", "internal"]);
+
+        // Malformed frontmatter is skipped without losing the body
+        let document = parser
+            .parse("---\ntitle: [unclosed\n---\nStill here\n")
+            .unwrap();
+        assert!(document.metadata.title.is_none());
+        assert_eq!(document.sections.len(), 1);
+    }
+
+    #[test]
+    fn test_paragraph_inline_runs() {
+        let (text, runs) = paragraph_runs(
+            "Plain **bold** and *italic* with [a *styled* link](https://example.com), \
+             ~~old~~ `code`\nand ![logo](logo.png).",
+        );
+
+        assert_eq!(
+            text,
+            "Plain bold and italic with a styled link, old `code` and logo."
+        );
+        assert_eq!(
+            describe(&runs),
+            vec![
+                "text:Plain ",
+                "bold:bold",
+                "text: and ",
+                "italic:italic",
+                "text: with ",
+                "link:a styled link->https://example.com",
+                "text:, ",
+                "strike:old",
+                "text: ",
+                "code:code",
+                "text: and ",
+                "image:logo->logo.png",
+                "text:.",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_nested_emphasis_combines_bold_and_italic() {
+        let (_, runs) = paragraph_runs("**bold *both* bold** and ***all***");
+
+        assert_eq!(
+            describe(&runs),
+            vec![
+                "bold:bold ",
+                "bold-italic:both",
+                "bold: bold",
+                "text: and ",
+                "bold-italic:all",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_nested_list_hierarchy() {
+        let markdown =
+            "- Fruit with `code`\n  - Apple *red*\n  - Pear\n- Vegetables\n  1. Carrot\n";
+        let document = MarkdownParser::new(CreateOptions::default())
+            .parse(markdown)
+            .unwrap();
+
+        let content = &document.sections[0].content;
+        assert_eq!(content.len(), 1);
+        let MarkdownElement::UnorderedList { items } = &content[0] else {
+            panic!("expected an unordered list, got {:?}", content[0]);
+        };
+
+        let texts: Vec<&str> = items.iter().map(|item| item.text.as_str()).collect();
+        assert_eq!(texts, vec!["Fruit with `code`", "Vegetables"]);
+
+        let MarkdownElement::UnorderedList { items: fruit } = &items[0].children[0] else {
+            panic!("expected a nested unordered list");
+        };
+        let fruit: Vec<&str> = fruit.iter().map(|item| item.text.as_str()).collect();
+        assert_eq!(fruit, vec!["Apple red", "Pear"]);
+
+        let MarkdownElement::OrderedList { items: vegetables } = &items[1].children[0] else {
+            panic!("expected a nested ordered list");
+        };
+        assert_eq!(vegetables.len(), 1);
+        assert_eq!(vegetables[0].text, "Carrot");
+        assert!(vegetables[0].children.is_empty());
+    }
+
+    #[test]
+    fn test_loose_list_item_keeps_paragraphs_together() {
+        let markdown = "- First paragraph\n\n  Second paragraph\n\n- Next item\n";
+        let document = MarkdownParser::new(CreateOptions::default())
+            .parse(markdown)
+            .unwrap();
+
+        let content = &document.sections[0].content;
+        assert_eq!(content.len(), 1);
+        let MarkdownElement::UnorderedList { items } = &content[0] else {
+            panic!("expected an unordered list, got {:?}", content[0]);
+        };
+        let texts: Vec<&str> = items.iter().map(|item| item.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            vec!["First paragraph\nSecond paragraph", "Next item"]
+        );
+    }
+
+    #[test]
+    fn test_list_items_keep_inline_code_and_links() {
+        let markdown = "- run `dox extract` first\n- see [the guide](https://example.com/guide) for **details**\n";
+        let document = MarkdownParser::new(CreateOptions::default())
+            .parse(markdown)
+            .unwrap();
+
+        let MarkdownElement::UnorderedList { items } = &document.sections[0].content[0] else {
+            panic!("expected an unordered list");
+        };
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].text, "run `dox extract` first");
+        assert_eq!(items[1].text, "see the guide for details");
+
+        assert_eq!(
+            describe(&items[0].runs),
+            vec!["text:run ", "code:dox extract", "text: first"]
+        );
+        assert_eq!(
+            describe(&items[1].runs),
+            vec![
+                "text:see ",
+                "link:the guide->https://example.com/guide",
+                "text: for ",
+                "bold:details",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_compression_setting() {
+        for (format, file_name, part) in [
+            (OutputFormat::Word, "handbook.docx", "word/document.xml"),
+            (
+                OutputFormat::PowerPoint,
+                "handbook.pptx",
+                "ppt/slides/slide2.xml",
+            ),
+        ] {
+            let create = |compression| {
+                let options = CreateOptions {
+                    compression,
+                    ..CreateOptions::default()
+                };
+                create_with_options(format, file_name, options)
+            };
+            let (stored_dir, mut stored) = create(Compression::Stored);
+            let (deflated_dir, mut deflated) = create(Compression::Deflated { level: Some(9) });
+
+            let size = |dir: &tempfile::TempDir| {
+                std::fs::metadata(dir.path().join(file_name)).unwrap().len()
+            };
+            assert!(size(&stored_dir) > size(&deflated_dir));
+
+            assert_eq!(
+                stored.by_name(part).unwrap().compression(),
+                CompressionMethod::Stored
+            );
+            assert_eq!(
+                deflated.by_name(part).unwrap().compression(),
+                CompressionMethod::Deflated
+            );
+            // Both packages read back to the same parts
+            assert_eq!(read_part(&mut stored, part), read_part(&mut deflated, part));
+            assert!(read_part(&mut stored, part).contains("Setup"));
+        }
+    }
+
+    #[test]
+    fn test_parse_compression() {
+        assert_eq!("stored".parse(), Ok(Compression::Stored));
+        assert_eq!("deflate".parse(), Ok(Compression::Deflated { level: None }));
+        assert!("zstd:19".parse::<Compression>().is_err());
+        assert!("deflate:12".parse::<Compression>().is_err());
+        assert!("stored:1".parse::<Compression>().is_err());
+        assert!("gzip".parse::<Compression>().is_err());
+    }
+
+    #[test]
+    fn test_generated_documents_pass_validation() {
+        let options = CreateOptions {
+            include_toc: true,
+            header: Some("Internal".to_string()),
+            footer: Some("Draft".to_string()),
+            ..CreateOptions::default()
+        };
+        for (format, file_name) in [
+            (OutputFormat::Word, "handbook.docx"),
+            (OutputFormat::PowerPoint, "handbook.pptx"),
+        ] {
+            let (dir, mut archive) = create_with_options(format, file_name, options.clone());
+            assert_eq!(validate::package_problems(&mut archive).unwrap(), vec![]);
+            validate_package(&dir.path().join(file_name)).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_broken_package_is_rejected() {
+        let (dir, mut archive) = create_with_options(
+            OutputFormat::Word,
+            "handbook.docx",
+            CreateOptions::default(),
+        );
+
+        // Generate a package that forgets the styles part, adds a part of
+        // unknown type and cuts the settings off mid-element
+        let broken = dir.path().join("broken.docx");
+        let error = write_atomic(&broken, |file| {
+            let mut writer = ZipWriter::new(file);
+            for index in 0..archive.len() {
+                let mut part = archive.by_index(index).unwrap();
+                let name = part.name().to_string();
+                if name == "word/styles.xml" {
+                    continue;
+                }
+                let mut content = Vec::new();
+                part.read_to_end(&mut content).unwrap();
+                writer.start_file(name, SimpleFileOptions::default())?;
+                writer.write_all(&content)?;
+            }
+            writer.start_file("word/media/notes.bin", SimpleFileOptions::default())?;
+            writer.write_all(b"notes")?;
+            writer.start_file("word/settings.xml", SimpleFileOptions::default())?;
+            writer.write_all(b"<w:settings><w:zoom")?;
+            finish_package(writer)
+        })
+        .unwrap_err();
+
+        let invalid = error.downcast_ref::<InvalidPackage>().unwrap();
+        assert!(invalid.problems.contains(&PackageProblem::MissingTarget {
+            relationships: "word/_rels/document.xml.rels".to_string(),
+            id: "rId1".to_string(),
+            target: "word/styles.xml".to_string(),
+        }));
+        assert!(invalid
+            .problems
+            .contains(&PackageProblem::MissingContentType {
+                part: "word/media/notes.bin".to_string(),
+            }));
+        assert!(invalid.problems.iter().any(|problem| matches!(
+            problem,
+            PackageProblem::MalformedXml { part, .. } if part == "word/settings.xml"
+        )));
+        assert!(error.to_string().contains("word/styles.xml"));
+        // The corrupt package is never written out
+        assert!(!broken.exists());
+    }
+
+    #[test]
+    fn test_word_list_numbering() {
+        let markdown = "# Steps\n\n- Milk\n- Eggs\n\n1. Mix\n2. Bake\n";
+        let (_dir, mut archive) = create_from_markdown(
+            OutputFormat::Word,
+            "steps.docx",
+            markdown,
+            CreateOptions::default(),
+        );
+
+        let document = read_part(&mut archive, "word/document.xml");
+        assert_eq!(document.matches(r#"<w:numId w:val="1"/>"#).count(), 2);
+        assert_eq!(document.matches(r#"<w:numId w:val="2"/>"#).count(), 2);
+
+        let numbering = read_part(&mut archive, "word/numbering.xml");
+        for (num_id, format) in [("1", "bullet"), ("2", "decimal")] {
+            let start = numbering
+                .find(&format!(r#"<w:num w:numId="{}">"#, num_id))
+                .unwrap();
+            let abstract_id = numbering[start..]
+                .split(r#"<w:abstractNumId w:val=""#)
+                .nth(1)
+                .and_then(|rest| rest.split('"').next())
+                .unwrap();
+            let definition = numbering
+                .split(&format!(
+                    r#"<w:abstractNum w:abstractNumId="{}">"#,
+                    abstract_id
+                ))
+                .nth(1)
+                .unwrap();
+            let definition = &definition[..definition.find("</w:abstractNum>").unwrap()];
+            assert!(definition.contains(&format!(r#"<w:numFmt w:val="{}"/>"#, format)));
+        }
+
+        assert!(read_part(&mut archive, "word/_rels/document.xml.rels").contains(
+            r#"Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/numbering" Target="numbering.xml""#
+        ));
+        assert!(read_part(&mut archive, "[Content_Types].xml")
+            .contains(r#"<Override PartName="/word/numbering.xml""#));
+
+        // Documents without lists leave the part out
+        let (_dir, mut archive) = create_with_options(
+            OutputFormat::Word,
+            "handbook.docx",
+            CreateOptions::default(),
+        );
+        assert!(archive.by_name("word/numbering.xml").is_err());
+    }
+
+    #[test]
+    fn test_word_ordered_lists_restart_numbering() {
+        let markdown = "1. Mix\n2. Bake\n\nThen serve.\n\n1. Slice\n2. Plate\n";
+        let (_dir, mut archive) = create_from_markdown(
+            OutputFormat::Word,
+            "recipe.docx",
+            markdown,
+            CreateOptions::default(),
+        );
+
+        let document = read_part(&mut archive, "word/document.xml");
+        let num_id = |text: &str| {
+            let paragraph = &document[..document.find(&format!(">{}<", text)).unwrap()];
+            let paragraph = &paragraph[paragraph.rfind("<w:p>").unwrap()..];
+            let start = paragraph.find(r#"<w:numId w:val=""#).unwrap();
+            paragraph[start..].split('"').nth(1).unwrap().to_string()
+        };
+        assert_eq!(num_id("Mix"), num_id("Bake"));
+        assert_eq!(num_id("Slice"), num_id("Plate"));
+        assert_ne!(num_id("Mix"), num_id("Slice"));
+
+        // Both lists share the decimal definition and start again at 1
+        let numbering = read_part(&mut archive, "word/numbering.xml");
+        for text in ["Mix", "Slice"] {
+            let start = numbering
+                .find(&format!(r#"<w:num w:numId="{}">"#, num_id(text)))
+                .unwrap();
+            let num = &numbering[start..start + numbering[start..].find("</w:num>").unwrap()];
+            assert!(num.contains(r#"<w:abstractNumId w:val="1"/>"#));
+            assert!(num.contains(r#"<w:lvlOverride w:ilvl="0">"#));
+            assert!(num.contains(r#"<w:startOverride w:val="1"/>"#));
+        }
+    }
+
+    #[test]
+    fn test_word_nested_list_levels() {
+        let markdown = "- Fruit\n  - Apple\n    - Fuji\n- Vegetables\n  1. Carrot\n";
+        let (_dir, mut archive) = create_from_markdown(
+            OutputFormat::Word,
+            "groceries.docx",
+            markdown,
+            CreateOptions::default(),
+        );
+
+        let document = read_part(&mut archive, "word/document.xml");
+        let levels: Vec<(&str, &str)> = ["Fruit", "Apple", "Fuji", "Vegetables", "Carrot"]
+            .iter()
+            .map(|text| {
+                let paragraph = &document[..document.find(&format!(">{}<", text)).unwrap()];
+                let paragraph = &paragraph[paragraph.rfind("<w:p>").unwrap()..];
+                let value = |tag: &str| {
+                    let start = paragraph.find(&format!(r#"<w:{} w:val=""#, tag)).unwrap();
+                    paragraph[start..].split('"').nth(1).unwrap()
+                };
+                (value("ilvl"), value("numId"))
+            })
+            .collect();
+        assert_eq!(
+            levels,
+            vec![("0", "1"), ("1", "1"), ("2", "1"), ("0", "1"), ("1", "2")]
+        );
+
+        // Each level is defined and indents further than the one above
+        let numbering = read_part(&mut archive, "word/numbering.xml");
+        let bullets = &numbering[..numbering.find("</w:abstractNum>").unwrap()];
+        for level in 0..LIST_LEVELS {
+            assert!(bullets.contains(&format!(r#"<w:lvl w:ilvl="{}">"#, level)));
+            assert!(bullets.contains(&format!(r#"<w:ind w:left="{}""#, 720 * (level + 1))));
+        }
+        assert!(numbering.contains(r#"<w:numFmt w:val="lowerLetter"/>"#));
+        assert!(numbering.contains(r#"<w:lvlText w:val="%2."/>"#));
+    }
+}
@@ -0,0 +1,328 @@
+//! Images referenced from Markdown and embedded into created documents
+
+use super::{CreateOptions, ListItem, MarkdownDocument, MarkdownElement};
+use anyhow::{anyhow, bail, Result};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// EMUs per pixel at 96 DPI
+const EMU_PER_PIXEL: u64 = 9525;
+
+/// Time allowed to connect to an image host
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Time allowed for a whole image download
+const FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Largest image download accepted, in bytes
+const MAX_IMAGE_BYTES: usize = 20 * 1024 * 1024;
+
+/// Image file formats that Word and PowerPoint display
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ImageFormat {
+    Png,
+    Jpeg,
+    Gif,
+}
+
+impl ImageFormat {
+    pub(crate) fn extension(self) -> &'static str {
+        match self {
+            ImageFormat::Png => "png",
+            ImageFormat::Jpeg => "jpeg",
+            ImageFormat::Gif => "gif",
+        }
+    }
+
+    pub(crate) fn content_type(self) -> &'static str {
+        match self {
+            ImageFormat::Png => "image/png",
+            ImageFormat::Jpeg => "image/jpeg",
+            ImageFormat::Gif => "image/gif",
+        }
+    }
+}
+
+/// An image loaded for embedding, keyed by the URL written in the Markdown
+#[derive(Debug, Clone)]
+pub(crate) struct EmbeddedImage {
+    pub url: String,
+    pub data: Vec<u8>,
+    pub format: ImageFormat,
+    /// Size in pixels
+    pub width: u32,
+    pub height: u32,
+}
+
+impl EmbeddedImage {
+    /// Media file name, numbered from 1 in load order
+    pub(crate) fn file_name(&self, index: usize) -> String {
+        format!("image{}.{}", index + 1, self.format.extension())
+    }
+
+    /// Size in EMUs at 96 DPI, scaled down to fit `max_cx` by `max_cy`
+    pub(crate) fn extent(&self, max_cx: u64, max_cy: u64) -> (u64, u64) {
+        let cx = u64::from(self.width) * EMU_PER_PIXEL;
+        let cy = u64::from(self.height) * EMU_PER_PIXEL;
+        let scale = (max_cx as f64 / cx as f64)
+            .min(max_cy as f64 / cy as f64)
+            .min(1.0);
+        (
+            ((cx as f64 * scale) as u64).max(1),
+            ((cy as f64 * scale) as u64).max(1),
+        )
+    }
+}
+
+/// Load every distinct image referenced in `markdown`
+///
+/// Images that cannot be loaded are left out with a warning, and the
+/// generators fall back to their alt text.
+pub(crate) fn load_images(
+    markdown: &MarkdownDocument,
+    options: &CreateOptions,
+) -> Vec<EmbeddedImage> {
+    let mut urls = Vec::new();
+    for section in &markdown.sections {
+        collect_urls(&section.content, &mut urls);
+    }
+
+    urls.into_iter()
+        .filter_map(|url| match load_image(&url, options) {
+            Ok(Some(image)) => Some(image),
+            Ok(None) => None,
+            Err(e) => {
+                tracing::warn!("Skipping image {}: {}", url, e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// URLs of the images among `elements`, in document order without repeats
+pub(crate) fn collect_urls(elements: &[MarkdownElement], urls: &mut Vec<String>) {
+    for element in elements {
+        match element {
+            MarkdownElement::Image { url, .. } if !urls.contains(url) => urls.push(url.clone()),
+            MarkdownElement::Paragraph { runs, .. } => collect_urls(runs, urls),
+            MarkdownElement::UnorderedList { items } | MarkdownElement::OrderedList { items } => {
+                collect_item_urls(items, urls)
+            }
+            _ => {}
+        }
+    }
+}
+
+fn collect_item_urls(items: &[ListItem], urls: &mut Vec<String>) {
+    for item in items {
+        collect_urls(&item.runs, urls);
+        collect_urls(&item.children, urls);
+    }
+}
+
+/// Read an image from a local path or, when allowed, an http(s) URL
+///
+/// `Ok(None)` means the image was skipped on purpose.
+fn load_image(url: &str, options: &CreateOptions) -> Result<Option<EmbeddedImage>> {
+    let data = if url.starts_with("http://") || url.starts_with("https://") {
+        if !options.fetch_remote_images {
+            tracing::warn!(
+                "Skipping remote image {}; enable remote image fetching to embed it",
+                url
+            );
+            return Ok(None);
+        }
+        fetch_remote(url)?
+    } else if url.contains("://") || url.starts_with("data:") {
+        bail!("unsupported image URL");
+    } else {
+        let path = local_path(url, options.image_base_dir.as_deref());
+        std::fs::read(&path).map_err(|e| anyhow!("cannot read {}: {}", path.display(), e))?
+    };
+
+    let (format, width, height) = sniff(&data)
+        .ok_or_else(|| anyhow!("not a PNG, JPEG or GIF image, or its size is unreadable"))?;
+    Ok(Some(EmbeddedImage {
+        url: url.to_string(),
+        data,
+        format,
+        width,
+        height,
+    }))
+}
+
+/// Local path of an image URL, relative paths resolved against `base_dir`
+fn local_path(url: &str, base_dir: Option<&Path>) -> PathBuf {
+    let path = Path::new(url.strip_prefix("file://").unwrap_or(url));
+    match base_dir {
+        Some(base_dir) if path.is_relative() => base_dir.join(path),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// Download a remote image
+fn fetch_remote(url: &str) -> Result<Vec<u8>> {
+    fetch_with_limits(url, FETCH_TIMEOUT, MAX_IMAGE_BYTES)
+}
+
+/// Download `url`, giving up after `timeout` or once the body passes
+/// `max_bytes`
+///
+/// Document creation is synchronous and may run inside the CLI's runtime,
+/// so the request runs on its own thread with a private runtime.
+fn fetch_with_limits(url: &str, timeout: Duration, max_bytes: usize) -> Result<Vec<u8>> {
+    let url = url.to_string();
+    std::thread::spawn(move || -> Result<Vec<u8>> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        runtime.block_on(async {
+            let client = reqwest::Client::builder()
+                .connect_timeout(CONNECT_TIMEOUT.min(timeout))
+                .timeout(timeout)
+                .build()?;
+            let mut response = client.get(&url).send().await?.error_for_status()?;
+            let too_large = || anyhow!("image is larger than {} bytes", max_bytes);
+            if response
+                .content_length()
+                .is_some_and(|length| length > max_bytes as u64)
+            {
+                return Err(too_large());
+            }
+
+            // The declared length may be missing or wrong, so the body is
+            // checked as it arrives
+            let mut data = Vec::new();
+            while let Some(chunk) = response.chunk().await? {
+                if data.len() + chunk.len() > max_bytes {
+                    return Err(too_large());
+                }
+                data.extend_from_slice(&chunk);
+            }
+            Ok(data)
+        })
+    })
+    .join()
+    .map_err(|_| anyhow!("image download thread panicked"))?
+}
+
+/// Format and pixel size read from the image header
+fn sniff(data: &[u8]) -> Option<(ImageFormat, u32, u32)> {
+    let be16 = |at: usize| Some(u16::from_be_bytes(data.get(at..at + 2)?.try_into().ok()?));
+    let le16 = |at: usize| Some(u16::from_le_bytes(data.get(at..at + 2)?.try_into().ok()?));
+    let be32 = |at: usize| Some(u32::from_be_bytes(data.get(at..at + 4)?.try_into().ok()?));
+
+    let (format, width, height) = if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+        (ImageFormat::Png, be32(16)?, be32(20)?)
+    } else if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") {
+        (ImageFormat::Gif, u32::from(le16(6)?), u32::from(le16(8)?))
+    } else if data.starts_with(&[0xFF, 0xD8]) {
+        // Walk the segments up to the start-of-frame marker holding the size
+        let mut at = 2;
+        loop {
+            if *data.get(at)? != 0xFF {
+                return None;
+            }
+            let marker = *data.get(at + 1)?;
+            let is_frame = matches!(marker, 0xC0..=0xCF) && !matches!(marker, 0xC4 | 0xC8 | 0xCC);
+            if is_frame {
+                break (
+                    ImageFormat::Jpeg,
+                    u32::from(be16(at + 7)?),
+                    u32::from(be16(at + 5)?),
+                );
+            }
+            at += 2 + usize::from(be16(at + 2)?);
+        }
+    } else {
+        return None;
+    };
+
+    (width > 0 && height > 0).then_some((format, width, height))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sniff_image_size() {
+        let mut png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec();
+        png.extend_from_slice(&640u32.to_be_bytes());
+        png.extend_from_slice(&480u32.to_be_bytes());
+        assert_eq!(sniff(&png), Some((ImageFormat::Png, 640, 480)));
+
+        let gif = b"GIF89a\x20\x00\x10\x00";
+        assert_eq!(sniff(gif), Some((ImageFormat::Gif, 32, 16)));
+
+        // APP0 segment followed by a baseline frame header
+        let jpeg = [
+            0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x04, 0x00, 0x00, 0xFF, 0xC0, 0x00, 0x11, 0x08, 0x00,
+            0x64, 0x00, 0xC8,
+        ];
+        assert_eq!(sniff(&jpeg), Some((ImageFormat::Jpeg, 200, 100)));
+
+        assert_eq!(sniff(b"not an image"), None);
+    }
+
+    #[test]
+    fn test_image_extent_fits_bounds() {
+        let image = EmbeddedImage {
+            url: "chart.png".to_string(),
+            data: Vec::new(),
+            format: ImageFormat::Png,
+            width: 2000,
+            height: 1000,
+        };
+        assert_eq!(image.extent(u64::MAX, u64::MAX), (19_050_000, 9_525_000));
+        assert_eq!(image.extent(1_000_000, u64::MAX), (1_000_000, 500_000));
+        assert_eq!(image.file_name(0), "image1.png");
+    }
+
+    #[test]
+    fn test_fetch_gives_up_on_silent_server() {
+        // Accepts the connection but never answers
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/chart.png", listener.local_addr().unwrap());
+
+        let start = std::time::Instant::now();
+        assert!(fetch_with_limits(&url, Duration::from_millis(200), MAX_IMAGE_BYTES).is_err());
+        assert!(start.elapsed() < Duration::from_secs(5));
+        drop(listener);
+    }
+
+    /// Serve one request with `headers` followed by a body of `body_len` bytes
+    fn serve_once(headers: &'static str, body_len: usize) -> String {
+        use std::io::{Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/chart.png", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 1024];
+            let _ = stream.read(&mut request);
+            let _ = stream.write_all(headers.as_bytes());
+            let _ = stream.write_all(&vec![b'x'; body_len]);
+        });
+        url
+    }
+
+    #[test]
+    fn test_fetch_rejects_oversized_image() {
+        let timeout = Duration::from_secs(5);
+
+        // Refused from the declared length
+        let url = serve_once("HTTP/1.1 200 OK\r\nContent-Length: 100\r\n\r\n", 100);
+        let error = fetch_with_limits(&url, timeout, 10).unwrap_err();
+        assert!(error.to_string().contains("larger than 10 bytes"));
+
+        // Cut off while reading a body of unknown length
+        let close = "HTTP/1.1 200 OK\r\nConnection: close\r\n\r\n";
+        let url = serve_once(close, 100);
+        let error = fetch_with_limits(&url, timeout, 10).unwrap_err();
+        assert!(error.to_string().contains("larger than 10 bytes"));
+
+        let url = serve_once(close, 10);
+        assert_eq!(fetch_with_limits(&url, timeout, 10).unwrap().len(), 10);
+    }
+}
@@ -0,0 +1,426 @@
+//! Structural checks of a written Office package
+//!
+//! Word and PowerPoint refuse a package whose relationships point at
+//! missing parts, whose parts have no content type or whose XML is not
+//! well-formed, usually with nothing more than "the file is corrupt".
+
+use anyhow::Result;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs::File;
+use std::io::{Read, Seek};
+use std::path::Path;
+use zip::ZipArchive;
+
+const CONTENT_TYPES_PART: &str = "[Content_Types].xml";
+
+/// One structural defect of a package
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PackageProblem {
+    /// The package has no `[Content_Types].xml`
+    MissingContentTypes,
+    /// A part is neither listed as an override nor covered by a default
+    MissingContentType { part: String },
+    /// A relationship targets a part that is not in the package
+    MissingTarget {
+        relationships: String,
+        id: String,
+        target: String,
+    },
+    /// An XML part does not parse
+    MalformedXml { part: String, message: String },
+}
+
+impl fmt::Display for PackageProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PackageProblem::MissingContentTypes => write!(f, "{} is missing", CONTENT_TYPES_PART),
+            PackageProblem::MissingContentType { part } => {
+                write!(f, "{} has no content type", part)
+            }
+            PackageProblem::MissingTarget {
+                relationships,
+                id,
+                target,
+            } => write!(
+                f,
+                "relationship {} in {} targets missing part {}",
+                id, relationships, target
+            ),
+            PackageProblem::MalformedXml { part, message } => {
+                write!(f, "{} is not well-formed XML: {}", part, message)
+            }
+        }
+    }
+}
+
+/// A generated package that Office would reject
+#[derive(Debug, thiserror::Error)]
+#[error("Generated document is invalid:{}", list(.problems))]
+pub struct InvalidPackage {
+    pub problems: Vec<PackageProblem>,
+}
+
+fn list(problems: &[PackageProblem]) -> String {
+    problems
+        .iter()
+        .map(|problem| format!("\n  - {}", problem))
+        .collect()
+}
+
+/// Check the package at `path`
+///
+/// Fails with [`InvalidPackage`] listing every problem found.
+pub fn validate_package(path: &Path) -> Result<()> {
+    ensure_valid(&mut ZipArchive::new(File::open(path)?)?)
+}
+
+/// Fail with [`InvalidPackage`] unless the opened package is sound
+pub(crate) fn ensure_valid<R: Read + Seek>(archive: &mut ZipArchive<R>) -> Result<()> {
+    let problems = package_problems(archive)?;
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(InvalidPackage { problems }.into())
+    }
+}
+
+/// Every structural problem of an opened package, in part order
+pub fn package_problems<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+) -> Result<Vec<PackageProblem>> {
+    let mut parts = Vec::new();
+    let mut xml = HashMap::new();
+    for index in 0..archive.len() {
+        let mut file = archive.by_index(index)?;
+        if file.is_dir() {
+            continue;
+        }
+        let name = file.name().to_string();
+        if is_xml(&name) {
+            let mut content = Vec::new();
+            file.read_to_end(&mut content)?;
+            xml.insert(name.clone(), content);
+        }
+        parts.push(name);
+    }
+
+    let mut problems = Vec::new();
+    let mut wellformed = HashSet::new();
+    for name in &parts {
+        if let Some(content) = xml.get(name) {
+            match check_wellformed(content) {
+                Ok(()) => {
+                    wellformed.insert(name.as_str());
+                }
+                Err(message) => problems.push(PackageProblem::MalformedXml {
+                    part: name.clone(),
+                    message,
+                }),
+            }
+        }
+    }
+
+    match xml.get(CONTENT_TYPES_PART) {
+        None => problems.push(PackageProblem::MissingContentTypes),
+        Some(content) if wellformed.contains(CONTENT_TYPES_PART) => {
+            let types = ContentTypes::parse(content);
+            for name in parts.iter().filter(|name| *name != CONTENT_TYPES_PART) {
+                if !types.covers(name) {
+                    problems.push(PackageProblem::MissingContentType { part: name.clone() });
+                }
+            }
+        }
+        // Already reported as malformed
+        Some(_) => {}
+    }
+
+    let existing: HashSet<&str> = parts.iter().map(String::as_str).collect();
+    for name in parts.iter().filter(|name| name.ends_with(".rels")) {
+        if !wellformed.contains(name.as_str()) {
+            continue;
+        }
+        let source_dir = relationships_source_dir(name);
+        for (id, target) in internal_relationships(&xml[name]) {
+            let resolved = resolve_target(&source_dir, &target);
+            if !existing.contains(resolved.as_str()) {
+                problems.push(PackageProblem::MissingTarget {
+                    relationships: name.clone(),
+                    id,
+                    target: resolved,
+                });
+            }
+        }
+    }
+
+    Ok(problems)
+}
+
+fn is_xml(name: &str) -> bool {
+    let name = name.to_lowercase();
+    name.ends_with(".xml") || name.ends_with(".rels")
+}
+
+fn check_wellformed(content: &[u8]) -> std::result::Result<(), String> {
+    let mut reader = Reader::from_reader(content);
+    let mut buf = Vec::new();
+    let mut depth = 0usize;
+    let mut root_seen = false;
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(_)) => {
+                if depth == 0 && root_seen {
+                    return Err("more than one root element".to_string());
+                }
+                depth += 1;
+                root_seen = true;
+            }
+            Ok(Event::Empty(_)) => {
+                if depth == 0 && root_seen {
+                    return Err("more than one root element".to_string());
+                }
+                root_seen = true;
+            }
+            Ok(Event::End(_)) => match depth.checked_sub(1) {
+                Some(outer) => depth = outer,
+                None => return Err("end tag without a start tag".to_string()),
+            },
+            Ok(Event::Eof) => break,
+            Ok(_) => {}
+            Err(e) => {
+                return Err(format!("{} at byte {}", e, reader.error_position()));
+            }
+        }
+        buf.clear();
+    }
+    if !root_seen {
+        return Err("no root element".to_string());
+    }
+    if depth > 0 {
+        return Err(format!("{} unclosed element(s)", depth));
+    }
+    Ok(())
+}
+
+/// `Default` and `Override` entries of `[Content_Types].xml`
+#[derive(Default)]
+struct ContentTypes {
+    /// Lowercase extensions
+    defaults: HashSet<String>,
+    /// Part names without the leading `/`
+    overrides: HashSet<String>,
+}
+
+impl ContentTypes {
+    fn parse(content: &[u8]) -> Self {
+        let mut types = ContentTypes::default();
+        for (name, attributes) in elements(content) {
+            match name.as_str() {
+                "Default" => {
+                    if let Some(extension) = attributes.get("Extension") {
+                        types.defaults.insert(extension.to_lowercase());
+                    }
+                }
+                "Override" => {
+                    if let Some(part) = attributes.get("PartName") {
+                        types
+                            .overrides
+                            .insert(part.trim_start_matches('/').to_string());
+                    }
+                }
+                _ => {}
+            }
+        }
+        types
+    }
+
+    fn covers(&self, part: &str) -> bool {
+        if self.overrides.contains(part) {
+            return true;
+        }
+        let file_name = part.rsplit('/').next().unwrap_or(part);
+        file_name
+            .rsplit_once('.')
+            .is_some_and(|(_, extension)| self.defaults.contains(&extension.to_lowercase()))
+    }
+}
+
+/// Id and target of the relationships that point inside the package
+fn internal_relationships(content: &[u8]) -> Vec<(String, String)> {
+    elements(content)
+        .into_iter()
+        .filter(|(name, _)| name == "Relationship")
+        .filter(|(_, attributes)| {
+            attributes
+                .get("TargetMode")
+                .is_none_or(|mode| mode != "External")
+        })
+        .filter_map(|(_, mut attributes)| {
+            Some((
+                attributes.remove("Id").unwrap_or_default(),
+                attributes.remove("Target")?,
+            ))
+        })
+        .collect()
+}
+
+/// Local name and attributes of every element in a well-formed part
+fn elements(content: &[u8]) -> Vec<(String, HashMap<String, String>)> {
+    let mut reader = Reader::from_reader(content);
+    let mut buf = Vec::new();
+    let mut elements = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(element)) | Ok(Event::Empty(element)) => {
+                let name = String::from_utf8_lossy(element.local_name().as_ref()).into_owned();
+                let attributes = element
+                    .attributes()
+                    .flatten()
+                    .filter_map(|attribute| {
+                        let key = String::from_utf8_lossy(attribute.key.local_name().as_ref())
+                            .into_owned();
+                        let value = attribute.unescape_value().ok()?.into_owned();
+                        Some((key, value))
+                    })
+                    .collect();
+                elements.push((name, attributes));
+            }
+            Ok(Event::Eof) | Err(_) => break,
+            Ok(_) => {}
+        }
+        buf.clear();
+    }
+    elements
+}
+
+/// Directory that the targets of a relationships part are relative to
+///
+/// `word/_rels/document.xml.rels` describes `word/document.xml`, so its
+/// targets resolve against `word/`; `_rels/.rels` describes the package.
+fn relationships_source_dir(rels: &str) -> String {
+    let dir = rels.rsplit_once('/').map_or("", |(dir, _)| dir);
+    let source_dir = dir.strip_suffix("_rels").unwrap_or(dir);
+    source_dir.trim_end_matches('/').to_string()
+}
+
+/// Part name of `target` seen from `source_dir`, with `.` and `..` removed
+fn resolve_target(source_dir: &str, target: &str) -> String {
+    let target = target.split(['#', '?']).next().unwrap_or(target);
+    let mut segments: Vec<&str> = if target.starts_with('/') {
+        Vec::new()
+    } else {
+        source_dir.split('/').filter(|s| !s.is_empty()).collect()
+    };
+    for segment in target.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop();
+            }
+            segment => segments.push(segment),
+        }
+    }
+    segments.join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Cursor, Write};
+    use zip::write::SimpleFileOptions;
+    use zip::ZipWriter;
+
+    fn package(parts: &[(&str, &str)]) -> ZipArchive<Cursor<Vec<u8>>> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in parts {
+            writer
+                .start_file(*name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        ZipArchive::new(writer.finish().unwrap()).unwrap()
+    }
+
+    const CONTENT_TYPES: &str = r#"<?xml version="1.0"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">
+    <Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>
+    <Override PartName="/word/document.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml"/>
+</Types>"#;
+
+    const ROOT_RELS: &str = r#"<?xml version="1.0"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+    <Relationship Id="rId1" Type="officeDocument" Target="word/document.xml"/>
+</Relationships>"#;
+
+    #[test]
+    fn test_valid_package() {
+        let mut archive = package(&[
+            (CONTENT_TYPES_PART, CONTENT_TYPES),
+            ("_rels/.rels", ROOT_RELS),
+            ("word/document.xml", "<w:document/>"),
+        ]);
+        assert_eq!(package_problems(&mut archive).unwrap(), vec![]);
+    }
+
+    #[test]
+    fn test_reports_every_problem() {
+        let document_rels = r#"<Relationships>
+    <Relationship Id="rId1" Type="styles" Target="styles.xml"/>
+    <Relationship Id="rId2" Type="image" Target="../media/missing.png"/>
+    <Relationship Id="rId3" Type="hyperlink" Target="https://example.com" TargetMode="External"/>
+</Relationships>"#;
+        let mut archive = package(&[
+            (CONTENT_TYPES_PART, CONTENT_TYPES),
+            ("_rels/.rels", ROOT_RELS),
+            ("word/_rels/document.xml.rels", document_rels),
+            ("word/document.xml", "<w:document><w:body></w:document>"),
+            ("word/styles.xml", "<w:styles/>"),
+        ]);
+
+        let problems = package_problems(&mut archive).unwrap();
+        assert!(matches!(
+            &problems[0],
+            PackageProblem::MalformedXml { part, .. } if part == "word/document.xml"
+        ));
+        assert_eq!(
+            problems[1..],
+            [
+                PackageProblem::MissingContentType {
+                    part: "word/styles.xml".to_string()
+                },
+                PackageProblem::MissingTarget {
+                    relationships: "word/_rels/document.xml.rels".to_string(),
+                    id: "rId2".to_string(),
+                    target: "media/missing.png".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_missing_content_types() {
+        let mut archive = package(&[("_rels/.rels", ROOT_RELS)]);
+        let problems = package_problems(&mut archive).unwrap();
+        assert_eq!(problems[0], PackageProblem::MissingContentTypes);
+    }
+
+    #[test]
+    fn test_resolve_target() {
+        assert_eq!(relationships_source_dir("_rels/.rels"), "");
+        assert_eq!(
+            relationships_source_dir("ppt/slides/_rels/slide1.xml.rels"),
+            "ppt/slides"
+        );
+        assert_eq!(
+            resolve_target("ppt/slides", "../slideLayouts/slideLayout1.xml"),
+            "ppt/slideLayouts/slideLayout1.xml"
+        );
+        assert_eq!(
+            resolve_target("word", "/word/styles.xml"),
+            "word/styles.xml"
+        );
+        assert_eq!(resolve_target("", "word/document.xml"), "word/document.xml");
+    }
+}
@@ -39,6 +39,24 @@ mod tests {
         assert!(!non_recoverable.is_recoverable());
     }
 
+    #[test]
+    fn test_http_status_classification() {
+        assert!(DoxError::from_http_status("openai", 429, "rate limited").is_recoverable());
+        assert!(DoxError::from_http_status("openai", 503, "unavailable").is_recoverable());
+
+        let auth = DoxError::from_http_status("claude", 401, "invalid x-api-key");
+        assert_eq!(auth.code(), ErrorCode::AuthenticationError);
+        assert!(!auth.is_recoverable());
+
+        assert!(!DoxError::from_http_status("claude", 400, "bad request").is_recoverable());
+
+        let limited = DoxError::from_http_status("openai", 429, "slow down");
+        assert_eq!(limited.code(), ErrorCode::RateLimited);
+        let timeout = DoxError::from_http_status("openai", 504, "gateway timeout");
+        assert_eq!(timeout.code(), ErrorCode::Timeout);
+        assert!(timeout.is_recoverable());
+    }
+
     #[test]
     fn test_user_friendly_messages() {
         let err = DoxError::MissingApiKey {
@@ -48,7 +66,7 @@ mod tests {
 
         assert!(message.contains("Missing API key for OpenAI"));
         assert!(message.contains("Suggestion:"));
-        assert!(message.contains("dox config api-key"));
+        assert!(message.contains("dox config --set openai.api_key=<KEY>"));
     }
 
     #[test]
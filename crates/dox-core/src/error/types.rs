@@ -29,6 +29,12 @@ pub enum DoxError {
     #[error("API error from {provider}: {message}")]
     ApiError { provider: String, message: String },
 
+    #[error("Authentication failed for {provider}: {message}")]
+    AuthenticationError { provider: String, message: String },
+
+    #[error("Rate limit exceeded for {provider}: {message}")]
+    RateLimited { provider: String, message: String },
+
     #[error("Validation error for {field}: {message}")]
     ValidationError { field: String, message: String },
 
@@ -44,6 +50,9 @@ pub enum DoxError {
     #[error("Network error: {message}")]
     NetworkError { message: String },
 
+    #[error("Request timed out: {message}")]
+    Timeout { message: String },
+
     #[error("Concurrent processing error: {message}")]
     ConcurrentError { message: String },
 }
@@ -99,6 +108,33 @@ impl DoxError {
         }
     }
 
+    /// Create an API error from an HTTP error status
+    ///
+    /// Rate limiting (429), timeouts (408/504) and other server errors (5xx)
+    /// become recoverable [`DoxError::RateLimited`], [`DoxError::Timeout`]
+    /// and [`DoxError::ApiError`]s; rejected credentials (401/403) and other
+    /// client errors are not retried.
+    pub fn from_http_status(
+        provider: impl Into<String>,
+        status: u16,
+        message: impl Into<String>,
+    ) -> Self {
+        let provider = provider.into();
+        let message = format!("HTTP {}: {}", status, message.into());
+        match status {
+            401 | 403 => DoxError::AuthenticationError { provider, message },
+            429 => DoxError::RateLimited { provider, message },
+            408 | 504 => DoxError::Timeout {
+                message: format!("{} ({})", message, provider),
+            },
+            500..=599 => DoxError::ApiError { provider, message },
+            _ => DoxError::ValidationError {
+                field: format!("{} request", provider),
+                message,
+            },
+        }
+    }
+
     /// Create a validation error
     pub fn validation(field: impl Into<String>, message: impl Into<String>) -> Self {
         DoxError::ValidationError {
@@ -122,11 +158,14 @@ pub enum ErrorCode {
     ConfigError = 2001,
     MissingApiKey = 3001,
     ApiError = 3002,
+    AuthenticationError = 3003,
+    RateLimited = 3004,
     ValidationError = 4001,
     TemplateError = 4002,
     ParseError = 4003,
     IoError = 5001,
     NetworkError = 5002,
+    Timeout = 5003,
     ConcurrentError = 6001,
 }
 
@@ -142,11 +181,14 @@ impl DoxError {
             DoxError::ConfigError { .. } => ErrorCode::ConfigError,
             DoxError::MissingApiKey { .. } => ErrorCode::MissingApiKey,
             DoxError::ApiError { .. } => ErrorCode::ApiError,
+            DoxError::AuthenticationError { .. } => ErrorCode::AuthenticationError,
+            DoxError::RateLimited { .. } => ErrorCode::RateLimited,
             DoxError::ValidationError { .. } => ErrorCode::ValidationError,
             DoxError::TemplateError { .. } => ErrorCode::TemplateError,
             DoxError::ParseError { .. } => ErrorCode::ParseError,
             DoxError::IoError { .. } => ErrorCode::IoError,
             DoxError::NetworkError { .. } => ErrorCode::NetworkError,
+            DoxError::Timeout { .. } => ErrorCode::Timeout,
             DoxError::ConcurrentError { .. } => ErrorCode::ConcurrentError,
         }
     }
@@ -156,7 +198,9 @@ impl DoxError {
         matches!(
             self,
             DoxError::NetworkError { .. }
+                | DoxError::Timeout { .. }
                 | DoxError::ApiError { .. }
+                | DoxError::RateLimited { .. }
                 | DoxError::ConcurrentError { .. }
         )
     }
@@ -180,8 +224,14 @@ impl From<std::io::Error> for DoxError {
 
 impl From<reqwest::Error> for DoxError {
     fn from(err: reqwest::Error) -> Self {
-        DoxError::NetworkError {
-            message: err.to_string(),
+        if err.is_timeout() {
+            DoxError::Timeout {
+                message: err.to_string(),
+            }
+        } else {
+            DoxError::NetworkError {
+                message: err.to_string(),
+            }
         }
     }
 }
@@ -259,6 +309,9 @@ impl DoxError {
             DoxError::NetworkError { message } => DoxError::NetworkError {
                 message: format!("{}: {}", context, message),
             },
+            DoxError::Timeout { message } => DoxError::Timeout {
+                message: format!("{}: {}", context, message),
+            },
             _ => self,
         }
     }
@@ -306,8 +359,9 @@ impl DoxError {
             }
             DoxError::MissingApiKey { provider } => {
                 format!(
-                    "Missing API key for {}\n\nSuggestion: Set up your API key using 'dox config api-key {}'",
-                    provider, provider
+                    "Missing API key for {}\n\nSuggestion: Set up your API key using 'dox config --set {}.api_key=<KEY>'",
+                    provider,
+                    provider.to_lowercase()
                 )
             }
             DoxError::ApiError { provider, message } => {
@@ -316,6 +370,20 @@ impl DoxError {
                     provider, message
                 )
             }
+            DoxError::AuthenticationError { provider, message } => {
+                format!(
+                    "Authentication failed for {}: {}\n\nSuggestion: Check that the API key has access to the requested model, or set a new one with 'dox config --set {}.api_key=<KEY>'.",
+                    provider,
+                    message,
+                    provider.to_lowercase()
+                )
+            }
+            DoxError::RateLimited { provider, message } => {
+                format!(
+                    "Rate limit exceeded for {}: {}\n\nSuggestion: Wait a minute and retry later, or check the usage limits of your {} account.",
+                    provider, message, provider
+                )
+            }
             DoxError::ValidationError { field, message } => {
                 format!(
                     "Validation error for '{}': {}\n\nSuggestion: Review the input requirements for this field.",
@@ -346,6 +414,12 @@ impl DoxError {
                     message
                 )
             }
+            DoxError::Timeout { message } => {
+                format!(
+                    "Request timed out: {}\n\nSuggestion: Retry later, or check your connection and proxy settings if it keeps timing out.",
+                    message
+                )
+            }
             DoxError::ConcurrentError { message } => {
                 format!(
                     "Concurrent processing error: {}\n\nSuggestion: Reduce the number of concurrent operations or try again.",
@@ -1,6 +1,10 @@
 //! Claude (Anthropic) API integration for content generation
 
-use super::{ContentGenerator, GenerationRequest, GenerationResponse, Usage};
+use super::{
+    send_with_retry, ContentGenerator, GenerationRequest, GenerationResponse, HttpClientConfig,
+    SseDecoder, Usage, STRUCTURED_OUTPUT_NAME,
+};
+use crate::error::{DoxError, DoxResult};
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use reqwest::Client;
@@ -34,6 +38,25 @@ impl ClaudeProvider {
         }
     }
 
+    /// Create Claude provider from HTTP transport settings
+    ///
+    /// Falls back to the public endpoint when no base URL is configured.
+    pub fn configured(api_key: String, http: &HttpClientConfig) -> Result<Self> {
+        let provider = match &http.base_url {
+            Some(base_url) => {
+                Self::with_base_url(api_key, base_url.trim_end_matches('/').to_string())
+            }
+            None => Self::new(api_key),
+        };
+        Ok(provider.with_client(http.build_client()?))
+    }
+
+    /// Use a pre-built HTTP client for all requests
+    pub fn with_client(mut self, client: Client) -> Self {
+        self.client = client;
+        self
+    }
+
     /// Get API key from environment variable
     pub fn from_env() -> Result<Self> {
         let api_key = std::env::var("ANTHROPIC_API_KEY")
@@ -44,7 +67,6 @@ impl ClaudeProvider {
     /// Convert internal request to Claude API format
     fn build_claude_request(&self, request: &GenerationRequest) -> ClaudeMessageRequest {
         let system_message = self.build_system_message(request);
-        let user_message = request.prompt.clone();
 
         ClaudeMessageRequest {
             model: request.model.clone(),
@@ -59,22 +81,41 @@ impl ClaudeProvider {
             } else {
                 None
             },
-            messages: vec![ClaudeMessage {
-                role: "user".to_string(),
-                content: user_message,
-            }],
-            stream: None, // Disable streaming for now
+            messages: request
+                .chat_turns()
+                .into_iter()
+                .map(|(role, content)| ClaudeMessage {
+                    role: role.to_string(),
+                    content,
+                })
+                .collect(),
+            stream: None, // Enabled by generate_stream
             stop_sequences: None,
             top_p: None,
+            // Structured output is a forced call to a tool taking the schema
+            tools: request.response_format.as_ref().map(|format| {
+                vec![serde_json::json!({
+                    "name": STRUCTURED_OUTPUT_NAME,
+                    "description": "Return the response as structured data",
+                    "input_schema": format.schema(),
+                })]
+            }),
+            tool_choice: request
+                .response_format
+                .as_ref()
+                .map(|_| serde_json::json!({ "type": "tool", "name": STRUCTURED_OUTPUT_NAME })),
         }
     }
 
     /// Build system message based on content type and context
     fn build_system_message(&self, request: &GenerationRequest) -> String {
-        use super::BuiltinTemplates;
         use super::TemplateEngine;
 
-        let template = BuiltinTemplates::get_template(request.content_type, &request.language);
+        if let Some(system_prompt) = &request.system_prompt {
+            return system_prompt.clone();
+        }
+
+        let template = request.system_template();
 
         let mut variables = HashMap::new();
         variables.insert("prompt".to_string(), request.prompt.clone());
@@ -90,14 +131,11 @@ impl ClaudeProvider {
         engine.render(&template).unwrap_or(template)
     }
 
-    /// Send non-streaming request to Claude
-    async fn send_request(
-        &self,
-        claude_request: &ClaudeMessageRequest,
-    ) -> Result<ClaudeMessageResponse> {
+    /// Post a messages request, turning HTTP errors into `Err`
+    async fn post(&self, claude_request: &ClaudeMessageRequest) -> DoxResult<reqwest::Response> {
         debug!(
-            "Sending Claude API request: model={}, max_tokens={}",
-            claude_request.model, claude_request.max_tokens
+            "Sending Claude API request: model={}, max_tokens={}, stream={:?}",
+            claude_request.model, claude_request.max_tokens, claude_request.stream
         );
 
         let response = self
@@ -117,10 +155,26 @@ impl ClaudeProvider {
                 .await
                 .unwrap_or_else(|_| "Unknown error".to_string());
             error!("Claude API error: {} - {}", status, error_text);
-            return Err(anyhow!("Claude API error {}: {}", status, error_text));
+            return Err(DoxError::from_http_status(
+                "claude",
+                status.as_u16(),
+                error_text,
+            ));
         }
 
-        let claude_response: ClaudeMessageResponse = response.json().await?;
+        Ok(response)
+    }
+
+    /// Send non-streaming request to Claude
+    async fn send_request(
+        &self,
+        claude_request: &ClaudeMessageRequest,
+    ) -> DoxResult<ClaudeMessageResponse> {
+        let response = self.post(claude_request).await?;
+        let claude_response: ClaudeMessageResponse =
+            response.json().await.map_err(|e| DoxError::ParseError {
+                message: format!("Invalid Claude response: {}", e),
+            })?;
         debug!(
             "Received Claude response: {} content blocks, usage={:?}",
             claude_response.content.len(),
@@ -139,12 +193,11 @@ impl ClaudeProvider {
         let content = claude_response
             .content
             .iter()
-            .filter_map(|content| {
-                if content.content_type == "text" {
-                    Some(content.text.as_deref().unwrap_or_default())
-                } else {
-                    None
-                }
+            .filter_map(|content| match content.content_type.as_str() {
+                "text" => content.text.clone(),
+                // Structured output arrives as the forced tool call's input
+                "tool_use" => content.input.as_ref().map(|input| input.to_string()),
+                _ => None,
             })
             .collect::<Vec<_>>()
             .join("");
@@ -180,13 +233,10 @@ impl ContentGenerator for ClaudeProvider {
 
         let claude_request = self.build_claude_request(request);
 
-        // For now, only support non-streaming requests
-        // TODO: Implement streaming support
-        if request.stream {
-            warn!("Streaming not yet implemented, falling back to non-streaming");
-        }
-
-        let claude_response = self.send_request(&claude_request).await?;
+        let claude_response = send_with_retry(request, "Claude request", || {
+            self.send_request(&claude_request)
+        })
+        .await?;
         let response = self.convert_response(claude_response, request.model.clone());
 
         info!(
@@ -198,6 +248,77 @@ impl ContentGenerator for ClaudeProvider {
         Ok(response)
     }
 
+    async fn generate_stream(
+        &self,
+        request: &GenerationRequest,
+        on_chunk: &mut (dyn for<'c> FnMut(&'c str) + Send),
+    ) -> Result<GenerationResponse> {
+        info!("Streaming content using Claude model: {}", request.model);
+
+        let mut claude_request = self.build_claude_request(request);
+        claude_request.stream = Some(true);
+
+        let mut response = send_with_retry(request, "Claude stream request", || {
+            self.post(&claude_request)
+        })
+        .await?;
+        let mut decoder = SseDecoder::default();
+        let mut content = String::new();
+        let mut input_tokens = 0;
+        let mut output_tokens = 0;
+
+        while let Some(bytes) = response.chunk().await? {
+            for payload in decoder.feed(&bytes) {
+                let event: ClaudeStreamEvent = serde_json::from_str(&payload)
+                    .map_err(|e| anyhow!("Invalid Claude stream event: {}", e))?;
+
+                match event {
+                    ClaudeStreamEvent::MessageStart { message } => {
+                        if let Some(usage) = message.usage {
+                            input_tokens = usage.input_tokens;
+                            output_tokens = usage.output_tokens;
+                        }
+                    }
+                    ClaudeStreamEvent::ContentBlockDelta { delta } => {
+                        if let Some(text) = delta.text.as_deref().or(delta.partial_json.as_deref())
+                        {
+                            on_chunk(text);
+                            content.push_str(text);
+                        }
+                    }
+                    ClaudeStreamEvent::MessageDelta { usage } => {
+                        if let Some(usage) = usage {
+                            output_tokens = usage.output_tokens;
+                        }
+                    }
+                    ClaudeStreamEvent::Error { error } => {
+                        error!("Claude stream error: {}", error.message);
+                        return Err(error.into_dox_error().into());
+                    }
+                    ClaudeStreamEvent::Other => {}
+                }
+            }
+        }
+
+        info!(
+            "Streaming completed. Generated {} characters, used {} tokens",
+            content.len(),
+            input_tokens + output_tokens
+        );
+
+        Ok(GenerationResponse {
+            content,
+            model: request.model.clone(),
+            provider: "claude".to_string(),
+            usage: Some(Usage {
+                prompt_tokens: input_tokens,
+                completion_tokens: output_tokens,
+                total_tokens: input_tokens + output_tokens,
+            }),
+            metadata: HashMap::new(),
+        })
+    }
+
     fn provider_name(&self) -> &str {
         "claude"
     }
@@ -230,6 +351,10 @@ struct ClaudeMessageRequest {
     stop_sequences: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tools: Option<Vec<serde_json::Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_choice: Option<serde_json::Value>,
 }
 
 /// Claude message structure
@@ -242,14 +367,7 @@ struct ClaudeMessage {
 /// Claude Messages API response structure
 #[derive(Debug, Deserialize)]
 struct ClaudeMessageResponse {
-    id: String,
-    #[serde(rename = "type")]
-    response_type: String,
-    role: String,
     content: Vec<ClaudeContent>,
-    model: String,
-    stop_reason: Option<String>,
-    stop_sequence: Option<String>,
     usage: Option<ClaudeUsage>,
 }
 
@@ -259,6 +377,7 @@ struct ClaudeContent {
     #[serde(rename = "type")]
     content_type: String,
     text: Option<String>,
+    input: Option<serde_json::Value>,
 }
 
 /// Claude usage structure
@@ -268,10 +387,73 @@ struct ClaudeUsage {
     output_tokens: usize,
 }
 
+/// Claude streaming event (only the fields used for text output)
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ClaudeStreamEvent {
+    MessageStart {
+        message: ClaudeStreamMessage,
+    },
+    ContentBlockDelta {
+        delta: ClaudeStreamDelta,
+    },
+    MessageDelta {
+        usage: Option<ClaudeDeltaUsage>,
+    },
+    Error {
+        error: ClaudeStreamError,
+    },
+    #[serde(other)]
+    Other,
+}
+
+/// Message envelope sent with `message_start`
+#[derive(Debug, Deserialize)]
+struct ClaudeStreamMessage {
+    usage: Option<ClaudeUsage>,
+}
+
+/// Text, or tool input JSON for structured output, added by a
+/// `content_block_delta` event
+#[derive(Debug, Deserialize)]
+struct ClaudeStreamDelta {
+    text: Option<String>,
+    partial_json: Option<String>,
+}
+
+/// Cumulative output token count sent with `message_delta`
+#[derive(Debug, Deserialize)]
+struct ClaudeDeltaUsage {
+    output_tokens: usize,
+}
+
+/// Error reported inside the event stream
+#[derive(Debug, Deserialize)]
+struct ClaudeStreamError {
+    #[serde(rename = "type", default)]
+    error_type: String,
+    message: String,
+}
+
+impl ClaudeStreamError {
+    /// Classify an error reported mid-stream by its `type`
+    fn into_dox_error(self) -> DoxError {
+        let provider = "claude".to_string();
+        let message = self.message;
+        match self.error_type.as_str() {
+            "authentication_error" | "permission_error" => {
+                DoxError::AuthenticationError { provider, message }
+            }
+            "rate_limit_error" => DoxError::RateLimited { provider, message },
+            _ => DoxError::ApiError { provider, message },
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::generate::ContentType;
+    use crate::generate::{ContentType, ResponseFormat};
 
     #[test]
     fn test_provider_creation() {
@@ -295,6 +477,9 @@ mod tests {
             audience: "개발자".to_string(),
             tone: "친근한".to_string(),
             context: None,
+            system_prompt: None,
+            examples: Vec::new(),
+            response_format: None,
             stream: false,
             provider_params: HashMap::new(),
         };
@@ -304,4 +489,78 @@ mod tests {
         assert!(system_message.contains("한국어"));
         assert!(system_message.contains("개발자"));
     }
+
+    #[test]
+    fn test_system_prompt_and_examples_roles() {
+        let provider = ClaudeProvider::new("test-key".to_string());
+        let request = GenerationRequest {
+            prompt: "배포 일정 공유".to_string(),
+            content_type: ContentType::Email,
+            model: "claude-3-5-sonnet-20241022".to_string(),
+            max_tokens: 500,
+            temperature: 0.7,
+            language: "ko".to_string(),
+            audience: "팀원".to_string(),
+            tone: "친근한".to_string(),
+            context: None,
+            system_prompt: Some("You write short emails.".to_string()),
+            examples: vec![(
+                "회의 취소".to_string(),
+                "오늘 회의는 취소되었습니다.".to_string(),
+            )],
+            response_format: None,
+            stream: false,
+            provider_params: HashMap::new(),
+        };
+
+        let payload = serde_json::to_value(provider.build_claude_request(&request)).unwrap();
+        assert_eq!(payload["system"], "You write short emails.");
+        assert_eq!(
+            payload["messages"],
+            serde_json::json!([
+                {"role": "user", "content": "회의 취소"},
+                {"role": "assistant", "content": "오늘 회의는 취소되었습니다."},
+                {"role": "user", "content": "배포 일정 공유"},
+            ])
+        );
+    }
+
+    #[test]
+    fn test_structured_output_uses_forced_tool() {
+        let provider = ClaudeProvider::new("test-key".to_string());
+        let mut request = GenerationRequest {
+            prompt: "요약".to_string(),
+            content_type: ContentType::Summary,
+            model: "claude-3-5-sonnet-20241022".to_string(),
+            max_tokens: 500,
+            temperature: 0.0,
+            language: "ko".to_string(),
+            audience: "일반".to_string(),
+            tone: "전문적".to_string(),
+            context: None,
+            system_prompt: None,
+            examples: Vec::new(),
+            response_format: None,
+            stream: false,
+            provider_params: HashMap::new(),
+        };
+
+        let payload = serde_json::to_value(provider.build_claude_request(&request)).unwrap();
+        assert!(payload.get("tools").is_none());
+
+        request.response_format = Some(ResponseFormat::Json { schema: None });
+        let payload = serde_json::to_value(provider.build_claude_request(&request)).unwrap();
+        assert_eq!(payload["tools"][0]["input_schema"]["type"], "object");
+        assert_eq!(payload["tool_choice"]["name"], payload["tools"][0]["name"]);
+
+        let response: ClaudeMessageResponse = serde_json::from_value(serde_json::json!({
+            "id": "msg_1", "type": "message", "role": "assistant", "model": "claude",
+            "content": [{"type": "tool_use", "id": "tu_1", "name": "structured_response",
+                         "input": {"points": ["a", "b"]}}],
+            "stop_reason": "tool_use", "stop_sequence": null, "usage": null,
+        }))
+        .unwrap();
+        let response = provider.convert_response(response, request.model.clone());
+        assert_eq!(response.content, r#"{"points":["a","b"]}"#);
+    }
 }
@@ -0,0 +1,449 @@
+//! Local model integration (Ollama and other OpenAI-compatible servers)
+
+use super::{
+    send_with_retry, ContentGenerator, GenerationRequest, GenerationResponse, HttpClientConfig,
+    SseDecoder, Usage,
+};
+use crate::error::{DoxError, DoxResult};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tracing::{debug, error, info};
+
+/// Default endpoint of Ollama's OpenAI-compatible API
+pub const DEFAULT_LOCAL_URL: &str = "http://localhost:11434/v1";
+
+/// Model name prefixes used to route a model to the local provider
+pub const LOCAL_MODEL_PREFIXES: &[&str] = &["local/", "ollama/"];
+
+/// Client for a self-hosted `/chat/completions` endpoint
+///
+/// No API key is required; one is only sent when the server sits behind an
+/// authenticating gateway.
+pub struct LocalProvider {
+    client: Client,
+    api_key: Option<String>,
+    base_url: String,
+}
+
+impl LocalProvider {
+    /// Create a provider for the default Ollama endpoint
+    pub fn new() -> Self {
+        Self::with_base_url(DEFAULT_LOCAL_URL.to_string())
+    }
+
+    /// Create a provider for a custom endpoint
+    pub fn with_base_url(base_url: String) -> Self {
+        Self {
+            client: Client::new(),
+            api_key: None,
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// Create a provider from HTTP transport settings
+    pub fn configured(http: &HttpClientConfig) -> Result<Self> {
+        let provider = match &http.base_url {
+            Some(base_url) => Self::with_base_url(base_url.clone()),
+            None => Self::new(),
+        };
+        Ok(provider.with_client(http.build_client()?))
+    }
+
+    /// Use a pre-built HTTP client for all requests
+    pub fn with_client(mut self, client: Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Send a bearer token with every request
+    pub fn with_api_key(mut self, api_key: String) -> Self {
+        self.api_key = Some(api_key);
+        self
+    }
+
+    /// Strip the routing prefix so `ollama/llama3` is sent as `llama3`
+    pub fn map_model(model: &str) -> &str {
+        LOCAL_MODEL_PREFIXES
+            .iter()
+            .find_map(|prefix| model.strip_prefix(prefix))
+            .unwrap_or(model)
+    }
+
+    /// Convert internal request to the chat completions format
+    fn build_chat_request(&self, request: &GenerationRequest, stream: bool) -> LocalChatRequest {
+        use super::TemplateEngine;
+
+        let template = request.system_template();
+
+        let mut variables = HashMap::new();
+        variables.insert("prompt".to_string(), request.prompt.clone());
+        variables.insert("language".to_string(), request.language.clone());
+        variables.insert("audience".to_string(), request.audience.clone());
+        variables.insert("tone".to_string(), request.tone.clone());
+        if let Some(context) = &request.context {
+            variables.insert("context".to_string(), context.clone());
+        }
+        let system_message = match &request.system_prompt {
+            Some(system_prompt) => system_prompt.clone(),
+            None => TemplateEngine::with_variables(variables)
+                .render(&template)
+                .unwrap_or(template),
+        };
+        let messages = std::iter::once(("system", system_message))
+            .chain(request.chat_turns())
+            .map(|(role, content)| Message {
+                role: role.to_string(),
+                content,
+            })
+            .collect();
+
+        LocalChatRequest {
+            model: Self::map_model(&request.model).to_string(),
+            messages,
+            max_tokens: Some(request.max_tokens),
+            temperature: Some(request.temperature),
+            stream,
+            stream_options: stream.then_some(StreamOptions {
+                include_usage: true,
+            }),
+            response_format: request
+                .response_format
+                .as_ref()
+                .map(|format| format.chat_completions_format()),
+        }
+    }
+
+    /// Post a chat completion request, turning HTTP errors into `Err`
+    async fn post(&self, chat_request: &LocalChatRequest) -> DoxResult<reqwest::Response> {
+        debug!(
+            "Sending local API request: url={}, model={}, stream={}",
+            self.base_url, chat_request.model, chat_request.stream
+        );
+
+        let mut builder = self
+            .client
+            .post(format!("{}/chat/completions", self.base_url))
+            .header("Content-Type", "application/json")
+            .json(chat_request);
+        if let Some(api_key) = &self.api_key {
+            builder = builder.header("Authorization", format!("Bearer {}", api_key));
+        }
+
+        let response = builder.send().await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            error!("Local API error: {} - {}", status, error_text);
+            return Err(DoxError::from_http_status(
+                "local",
+                status.as_u16(),
+                error_text,
+            ));
+        }
+
+        Ok(response)
+    }
+
+    /// Send non-streaming request to the local server
+    async fn send_request(&self, chat_request: &LocalChatRequest) -> DoxResult<LocalChatResponse> {
+        let response = self.post(chat_request).await?;
+        response.json().await.map_err(|e| DoxError::ParseError {
+            message: format!("Invalid local model response: {}", e),
+        })
+    }
+
+    fn build_response(
+        content: String,
+        model: String,
+        usage: Option<LocalUsage>,
+    ) -> GenerationResponse {
+        GenerationResponse {
+            content,
+            model,
+            provider: "local".to_string(),
+            usage: usage.map(|u| Usage {
+                prompt_tokens: u.prompt_tokens,
+                completion_tokens: u.completion_tokens,
+                total_tokens: u.total_tokens,
+            }),
+            metadata: HashMap::new(),
+        }
+    }
+}
+
+impl Default for LocalProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl ContentGenerator for LocalProvider {
+    async fn generate(&self, request: &GenerationRequest) -> Result<GenerationResponse> {
+        info!("Generating content using local model: {}", request.model);
+
+        let chat_request = self.build_chat_request(request, false);
+        let chat_response = send_with_retry(request, "Local model request", || {
+            self.send_request(&chat_request)
+        })
+        .await?;
+
+        let content = chat_response
+            .choices
+            .into_iter()
+            .next()
+            .map(|choice| choice.message.content)
+            .unwrap_or_default();
+
+        Ok(Self::build_response(
+            content,
+            request.model.clone(),
+            chat_response.usage,
+        ))
+    }
+
+    async fn generate_stream(
+        &self,
+        request: &GenerationRequest,
+        on_chunk: &mut (dyn for<'c> FnMut(&'c str) + Send),
+    ) -> Result<GenerationResponse> {
+        info!("Streaming content using local model: {}", request.model);
+
+        let chat_request = self.build_chat_request(request, true);
+        let mut response = send_with_retry(request, "Local model stream request", || {
+            self.post(&chat_request)
+        })
+        .await?;
+        let mut decoder = SseDecoder::default();
+        let mut content = String::new();
+        let mut usage = None;
+
+        'stream: while let Some(bytes) = response.chunk().await? {
+            for payload in decoder.feed(&bytes) {
+                if payload == "[DONE]" {
+                    break 'stream;
+                }
+
+                let chunk: LocalStreamChunk = serde_json::from_str(&payload)
+                    .map_err(|e| anyhow!("Invalid local model stream chunk: {}", e))?;
+                if let Some(text) = chunk
+                    .choices
+                    .first()
+                    .and_then(|choice| choice.delta.content.as_deref())
+                {
+                    on_chunk(text);
+                    content.push_str(text);
+                }
+                // Sent in a final chunk when the server honors `include_usage`
+                if chunk.usage.is_some() {
+                    usage = chunk.usage;
+                }
+            }
+        }
+
+        Ok(Self::build_response(content, request.model.clone(), usage))
+    }
+
+    fn provider_name(&self) -> &str {
+        "local"
+    }
+
+    fn supported_models(&self) -> Vec<String> {
+        // Whatever the server has pulled; there is no fixed list
+        Vec::new()
+    }
+
+    fn supports_model(&self, _model: &str) -> bool {
+        true
+    }
+}
+
+/// Chat completions request structure
+#[derive(Debug, Serialize)]
+struct LocalChatRequest {
+    model: String,
+    messages: Vec<Message>,
+    max_tokens: Option<usize>,
+    temperature: Option<f32>,
+    stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream_options: Option<StreamOptions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_format: Option<serde_json::Value>,
+}
+
+/// Asks the server to report token usage at the end of a stream
+#[derive(Debug, Serialize)]
+struct StreamOptions {
+    include_usage: bool,
+}
+
+/// Chat message structure
+#[derive(Debug, Serialize, Deserialize)]
+struct Message {
+    role: String,
+    content: String,
+}
+
+/// Chat completions response structure
+#[derive(Debug, Deserialize)]
+struct LocalChatResponse {
+    choices: Vec<Choice>,
+    usage: Option<LocalUsage>,
+}
+
+/// Completion choice structure
+#[derive(Debug, Deserialize)]
+struct Choice {
+    message: Message,
+}
+
+/// Streaming chunk structure
+#[derive(Debug, Deserialize)]
+struct LocalStreamChunk {
+    #[serde(default)]
+    choices: Vec<StreamChoice>,
+    usage: Option<LocalUsage>,
+}
+
+/// Streaming choice structure
+#[derive(Debug, Deserialize)]
+struct StreamChoice {
+    delta: Delta,
+}
+
+/// Incremental message content in a streaming choice
+#[derive(Debug, Deserialize)]
+struct Delta {
+    content: Option<String>,
+}
+
+/// Token usage structure
+#[derive(Debug, Deserialize)]
+struct LocalUsage {
+    prompt_tokens: usize,
+    completion_tokens: usize,
+    total_tokens: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate::{ContentType, PARAM_RETRY_MAX_ATTEMPTS};
+    use mockito::Matcher;
+
+    fn request(model: &str) -> GenerationRequest {
+        GenerationRequest {
+            prompt: "Rust 소개".to_string(),
+            content_type: ContentType::Blog,
+            model: model.to_string(),
+            max_tokens: 256,
+            temperature: 0.5,
+            language: "ko".to_string(),
+            audience: "개발자".to_string(),
+            tone: "친근한".to_string(),
+            context: None,
+            system_prompt: None,
+            examples: Vec::new(),
+            response_format: None,
+            stream: false,
+            provider_params: HashMap::from([(
+                PARAM_RETRY_MAX_ATTEMPTS.to_string(),
+                serde_json::json!(1),
+            )]),
+        }
+    }
+
+    #[test]
+    fn test_map_model() {
+        assert_eq!(LocalProvider::map_model("ollama/llama3"), "llama3");
+        assert_eq!(LocalProvider::map_model("local/qwen2:7b"), "qwen2:7b");
+        assert_eq!(LocalProvider::map_model("mistral"), "mistral");
+    }
+
+    #[tokio::test]
+    async fn test_generate_against_local_server() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/v1/chat/completions")
+            .match_header("authorization", Matcher::Missing)
+            .match_body(Matcher::PartialJson(serde_json::json!({
+                "model": "llama3",
+                "max_tokens": 256,
+                "stream": false,
+                "messages": [{"role": "system"}, {"role": "user", "content": "Rust 소개"}],
+            })))
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"choices": [{"message": {"role": "assistant", "content": "Rust는 시스템 언어입니다"}}],
+                    "usage": {"prompt_tokens": 12, "completion_tokens": 7, "total_tokens": 19}}"#,
+            )
+            .create_async()
+            .await;
+
+        let provider = LocalProvider::with_base_url(format!("{}/v1/", server.url()));
+        let response = provider.generate(&request("ollama/llama3")).await.unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(response.content, "Rust는 시스템 언어입니다");
+        assert_eq!(response.provider, "local");
+        assert_eq!(response.model, "ollama/llama3");
+        assert_eq!(response.usage.unwrap().total_tokens, 19);
+    }
+
+    #[tokio::test]
+    async fn test_generate_without_usage() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/chat/completions")
+            .with_body(r#"{"choices": [{"message": {"role": "assistant", "content": "ok"}}]}"#)
+            .create_async()
+            .await;
+
+        let provider = LocalProvider::with_base_url(server.url());
+        let response = provider.generate(&request("mistral")).await.unwrap();
+
+        assert_eq!(response.content, "ok");
+        assert!(response.usage.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_generate_stream() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/chat/completions")
+            .match_body(Matcher::PartialJson(serde_json::json!({
+                "stream": true,
+                "stream_options": {"include_usage": true},
+            })))
+            .with_header("content-type", "text/event-stream")
+            .with_body(concat!(
+                "data: {\"choices\":[{\"delta\":{\"content\":\"안녕\"}}]}\n\n",
+                "data: {\"choices\":[{\"delta\":{\"content\":\"하세요\"}}]}\n\n",
+                "data: {\"choices\":[],\"usage\":{\"prompt_tokens\":3,\"completion_tokens\":2,\"total_tokens\":5}}\n\n",
+                "data: [DONE]\n\n",
+            ))
+            .create_async()
+            .await;
+
+        let provider = LocalProvider::with_base_url(server.url());
+        let mut chunks = Vec::new();
+        let response = provider
+            .generate_stream(&request("llama3"), &mut |text| {
+                chunks.push(text.to_string())
+            })
+            .await
+            .unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(chunks, vec!["안녕", "하세요"]);
+        assert_eq!(response.content, "안녕하세요");
+        assert_eq!(response.usage.unwrap().completion_tokens, 2);
+    }
+}
@@ -4,11 +4,18 @@
 //! content generation that will be used by various AI providers.
 
 pub mod claude;
+pub mod local;
 pub mod openai;
+pub mod pricing;
 
+use crate::error::{retry_async, DoxError, DoxResult, RetryPolicy};
 use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::future::Future;
+use std::path::PathBuf;
+use std::time::Duration;
+use tracing::warn;
 
 /// Trait for AI content generation providers
 #[async_trait::async_trait]
@@ -16,6 +23,20 @@ pub trait ContentGenerator: Send + Sync {
     /// Generate content based on the provided request
     async fn generate(&self, request: &GenerationRequest) -> Result<GenerationResponse>;
 
+    /// Generate content, passing each piece of text to `on_chunk` as it arrives
+    ///
+    /// The returned response holds the complete content. Providers without
+    /// streaming support deliver the whole response as a single chunk.
+    async fn generate_stream(
+        &self,
+        request: &GenerationRequest,
+        on_chunk: &mut (dyn for<'c> FnMut(&'c str) + Send),
+    ) -> Result<GenerationResponse> {
+        let response = self.generate(request).await?;
+        on_chunk(&response.content);
+        Ok(response)
+    }
+
     /// Get the provider name
     fn provider_name(&self) -> &str;
 
@@ -28,6 +49,32 @@ pub trait ContentGenerator: Send + Sync {
     }
 }
 
+/// Incremental decoder for server-sent event streams
+///
+/// Bytes are buffered until a full line is available, so multi-byte
+/// characters split across network chunks decode correctly.
+#[derive(Debug, Default)]
+pub(crate) struct SseDecoder {
+    buffer: Vec<u8>,
+}
+
+impl SseDecoder {
+    /// Feed a chunk of the response body and return the complete `data:` payloads
+    pub(crate) fn feed(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.buffer.extend_from_slice(chunk);
+
+        let mut payloads = Vec::new();
+        while let Some(pos) = self.buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=pos).collect();
+            let line = String::from_utf8_lossy(&line);
+            if let Some(data) = line.trim_end().strip_prefix("data:") {
+                payloads.push(data.trim_start().to_string());
+            }
+        }
+        payloads
+    }
+}
+
 /// Request for content generation
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GenerationRequest {
@@ -58,6 +105,18 @@ pub struct GenerationRequest {
     /// Additional context to include
     pub context: Option<String>,
 
+    /// System prompt used verbatim instead of the content type template
+    #[serde(default)]
+    pub system_prompt: Option<String>,
+
+    /// Few-shot examples as (user, assistant) pairs sent ahead of the prompt
+    #[serde(default)]
+    pub examples: Vec<(String, String)>,
+
+    /// Structured output the model must return instead of prose
+    #[serde(default)]
+    pub response_format: Option<ResponseFormat>,
+
     /// Whether to stream the response
     pub stream: bool,
 
@@ -65,6 +124,288 @@ pub struct GenerationRequest {
     pub provider_params: HashMap<String, serde_json::Value>,
 }
 
+/// Output format a provider is asked to enforce
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ResponseFormat {
+    /// A JSON document, optionally conforming to a JSON schema
+    ///
+    /// OpenAI-compatible APIs use their JSON mode; Claude is forced to call
+    /// a tool whose input schema is `schema`.
+    Json {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        schema: Option<serde_json::Value>,
+    },
+}
+
+/// Name given to the schema (OpenAI) or tool (Claude) for structured output
+pub(crate) const STRUCTURED_OUTPUT_NAME: &str = "structured_response";
+
+impl ResponseFormat {
+    /// `response_format` value for chat completions APIs
+    pub(crate) fn chat_completions_format(&self) -> serde_json::Value {
+        match self {
+            ResponseFormat::Json { schema: None } => serde_json::json!({ "type": "json_object" }),
+            ResponseFormat::Json {
+                schema: Some(schema),
+            } => serde_json::json!({
+                "type": "json_schema",
+                "json_schema": { "name": STRUCTURED_OUTPUT_NAME, "schema": schema },
+            }),
+        }
+    }
+
+    /// JSON schema the output must follow, an arbitrary object if none was given
+    pub(crate) fn schema(&self) -> serde_json::Value {
+        match self {
+            ResponseFormat::Json { schema } => schema
+                .clone()
+                .unwrap_or_else(|| serde_json::json!({ "type": "object" })),
+        }
+    }
+}
+
+/// `provider_params` keys controlling how provider calls are retried
+pub const PARAM_RETRY_MAX_ATTEMPTS: &str = "retry_max_attempts";
+pub const PARAM_RETRY_INITIAL_DELAY_MS: &str = "retry_initial_delay_ms";
+pub const PARAM_RETRY_MAX_DELAY_MS: &str = "retry_max_delay_ms";
+/// `provider_params` key for the per-attempt timeout in milliseconds
+pub const PARAM_TIMEOUT_MS: &str = "timeout_ms";
+/// `provider_params` key for a system prompt template replacing the built-in one
+pub const PARAM_SYSTEM_TEMPLATE: &str = "system_template";
+
+/// Default per-attempt timeout for provider calls
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+
+impl GenerationRequest {
+    /// Retry policy for provider calls
+    ///
+    /// Starts from [`RetryPolicy::default`] with a one second initial delay
+    /// and applies any `retry_*` overrides from `provider_params`.
+    pub fn retry_policy(&self) -> RetryPolicy {
+        let mut policy = RetryPolicy {
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(30),
+            ..RetryPolicy::default()
+        };
+
+        if let Some(attempts) = self.param_u64(PARAM_RETRY_MAX_ATTEMPTS) {
+            policy.max_attempts = (attempts as u32).max(1);
+        }
+        if let Some(ms) = self.param_u64(PARAM_RETRY_INITIAL_DELAY_MS) {
+            policy.initial_delay = Duration::from_millis(ms);
+        }
+        if let Some(ms) = self.param_u64(PARAM_RETRY_MAX_DELAY_MS) {
+            policy.max_delay = Duration::from_millis(ms);
+        }
+        policy
+    }
+
+    /// Per-attempt timeout for provider calls
+    pub fn request_timeout(&self) -> Duration {
+        self.param_u64(PARAM_TIMEOUT_MS)
+            .map(Duration::from_millis)
+            .unwrap_or(DEFAULT_REQUEST_TIMEOUT)
+    }
+
+    /// Store a retry policy in `provider_params`
+    pub fn with_retry_policy(mut self, policy: &RetryPolicy) -> Self {
+        self.provider_params.insert(
+            PARAM_RETRY_MAX_ATTEMPTS.to_string(),
+            policy.max_attempts.into(),
+        );
+        self.provider_params.insert(
+            PARAM_RETRY_INITIAL_DELAY_MS.to_string(),
+            (policy.initial_delay.as_millis() as u64).into(),
+        );
+        self.provider_params.insert(
+            PARAM_RETRY_MAX_DELAY_MS.to_string(),
+            (policy.max_delay.as_millis() as u64).into(),
+        );
+        self
+    }
+
+    /// Store a per-attempt timeout in `provider_params`
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.provider_params.insert(
+            PARAM_TIMEOUT_MS.to_string(),
+            (timeout.as_millis() as u64).into(),
+        );
+        self
+    }
+
+    /// System prompt template, before variable substitution
+    ///
+    /// Uses the template stored by [`Self::with_system_template`] if any,
+    /// otherwise the built-in one for the content type and language.
+    pub fn system_template(&self) -> String {
+        self.provider_params
+            .get(PARAM_SYSTEM_TEMPLATE)
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+            .unwrap_or_else(|| BuiltinTemplates::get_template(self.content_type, &self.language))
+    }
+
+    /// Store a system prompt template in `provider_params`
+    pub fn with_system_template(mut self, template: impl Into<String>) -> Self {
+        self.provider_params
+            .insert(PARAM_SYSTEM_TEMPLATE.to_string(), template.into().into());
+        self
+    }
+
+    /// Chat turns following the system prompt as (role, content) pairs
+    ///
+    /// Each example becomes a user/assistant exchange so the model sees
+    /// them as earlier conversation, followed by the user message.
+    pub fn chat_turns(&self) -> Vec<(&'static str, String)> {
+        self.examples
+            .iter()
+            .flat_map(|(user, assistant)| {
+                [("user", user.clone()), ("assistant", assistant.clone())]
+            })
+            .chain(std::iter::once(("user", self.user_message())))
+            .collect()
+    }
+
+    /// The prompt, followed by the context as reference material if set
+    pub fn user_message(&self) -> String {
+        match self.context.as_deref().map(str::trim) {
+            Some(context) if !context.is_empty() => {
+                format!("{}\n\n<context>\n{}\n</context>", self.prompt, context)
+            }
+            _ => self.prompt.clone(),
+        }
+    }
+
+    fn param_u64(&self, key: &str) -> Option<u64> {
+        self.provider_params.get(key).and_then(|v| v.as_u64())
+    }
+}
+
+/// Run a provider call with the request's retry policy and timeout
+///
+/// Each attempt is bounded by [`GenerationRequest::request_timeout`]; timeouts
+/// and recoverable errors are retried with backoff, anything else fails on
+/// the first attempt.
+pub async fn send_with_retry<F, Fut, T>(
+    request: &GenerationRequest,
+    operation_name: &str,
+    mut send: F,
+) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = DoxResult<T>>,
+{
+    let policy = request.retry_policy();
+    let timeout = request.request_timeout();
+
+    retry_async(&policy, operation_name, || {
+        let attempt = send();
+        async move {
+            tokio::time::timeout(timeout, attempt)
+                .await
+                .unwrap_or_else(|_| {
+                    Err(DoxError::Timeout {
+                        message: format!("{} timed out after {:?}", operation_name, timeout),
+                    })
+                })
+        }
+    })
+    .await
+    .map_err(anyhow::Error::from)
+}
+
+/// Generate content, checking it against the request's response format
+///
+/// With [`ResponseFormat::Json`] the content must parse as JSON; if it does
+/// not, the request is sent once more before failing. The returned content
+/// is the bare JSON text, without any Markdown code fence around it.
+pub async fn generate_validated(
+    generator: &dyn ContentGenerator,
+    request: &GenerationRequest,
+) -> Result<GenerationResponse> {
+    if !matches!(request.response_format, Some(ResponseFormat::Json { .. })) {
+        return generator.generate(request).await;
+    }
+
+    let mut last_error = None;
+    for attempt in 1..=2 {
+        let mut response = generator.generate(request).await?;
+        match extract_json(&response.content) {
+            Ok(json) => {
+                response.content = json;
+                return Ok(response);
+            }
+            Err(e) => {
+                warn!("Attempt {} returned invalid JSON: {}", attempt, e);
+                last_error = Some(e);
+            }
+        }
+    }
+
+    let e = last_error.expect("at least one attempt was made");
+    Err(DoxError::ParseError {
+        message: format!("Model did not return valid JSON: {}", e),
+    }
+    .into())
+}
+
+/// Strip an optional Markdown code fence and check the rest is JSON
+fn extract_json(content: &str) -> std::result::Result<String, serde_json::Error> {
+    let trimmed = content.trim();
+    let json = trimmed
+        .strip_prefix("```json")
+        .or_else(|| trimmed.strip_prefix("```"))
+        .and_then(|rest| rest.strip_suffix("```"))
+        .map(str::trim)
+        .unwrap_or(trimmed);
+
+    serde_json::from_str::<serde_json::Value>(json)?;
+    Ok(json.to_string())
+}
+
+/// HTTP transport settings shared by the API providers
+///
+/// Lets callers point a provider at a compatible endpoint, route traffic
+/// through a corporate proxy, or trust an internal CA without touching the
+/// provider code.
+#[derive(Debug, Clone, Default)]
+pub struct HttpClientConfig {
+    /// Replaces the provider's public API endpoint
+    pub base_url: Option<String>,
+
+    /// Proxy URL applied to all requests
+    pub proxy: Option<String>,
+
+    /// PEM file with additional root certificates to trust
+    pub ca_cert: Option<PathBuf>,
+}
+
+impl HttpClientConfig {
+    /// Build a reqwest client honoring the proxy and CA settings
+    pub fn build_client(&self) -> Result<reqwest::Client> {
+        let mut builder = reqwest::Client::builder();
+
+        if let Some(proxy) = &self.proxy {
+            let proxy = reqwest::Proxy::all(proxy)
+                .map_err(|e| anyhow!("Invalid proxy URL '{}': {}", proxy, e))?;
+            builder = builder.proxy(proxy);
+        }
+
+        if let Some(path) = &self.ca_cert {
+            let pem = std::fs::read(path)
+                .map_err(|e| anyhow!("Failed to read CA certificate {}: {}", path.display(), e))?;
+            let cert = reqwest::Certificate::from_pem(&pem)
+                .map_err(|e| anyhow!("Invalid CA certificate {}: {}", path.display(), e))?;
+            builder = builder.add_root_certificate(cert);
+        }
+
+        builder
+            .build()
+            .map_err(|e| anyhow!("Failed to build HTTP client: {}", e))
+    }
+}
+
 /// Response from content generation
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GenerationResponse {
@@ -166,48 +507,96 @@ impl ContentType {
     }
 }
 
+/// What [`TemplateEngine::render`] does with placeholders that have no variable
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MissingPolicy {
+    /// Fail with a list of the unresolved placeholders
+    #[default]
+    Error,
+    /// Log a warning and leave the placeholders in the output
+    Warn,
+    /// Leave the placeholders in the output silently
+    Leave,
+    /// Remove the placeholders from the output
+    Empty,
+}
+
 /// Template engine for prompt customization
 pub struct TemplateEngine {
     variables: HashMap<String, String>,
+    missing: MissingPolicy,
 }
 
 impl TemplateEngine {
     pub fn new() -> Self {
+        Self::with_variables(HashMap::new())
+    }
+
+    pub fn with_variables(variables: HashMap<String, String>) -> Self {
         Self {
-            variables: HashMap::new(),
+            variables,
+            missing: MissingPolicy::default(),
         }
     }
 
-    pub fn with_variables(variables: HashMap<String, String>) -> Self {
-        Self { variables }
+    /// Set how placeholders without a variable are handled
+    pub fn with_missing_policy(mut self, policy: MissingPolicy) -> Self {
+        self.missing = policy;
+        self
     }
 
     pub fn add_variable(&mut self, key: String, value: String) {
         self.variables.insert(key, value);
     }
 
+    /// Replace `{{key}}` placeholders with their variables
+    ///
+    /// `{{key|default}}` falls back to `default` (which may be empty) when
+    /// the variable is not set, and never counts as unresolved. Substituted
+    /// values are not scanned again, so braces inside them are kept as they
+    /// are.
     pub fn render(&self, template: &str) -> Result<String> {
-        let mut result = template.to_string();
+        let mut result = String::with_capacity(template.len());
+        let mut unresolved = Vec::new();
+        let mut rest = template;
 
-        // Replace variables in {{key}} format
-        for (key, value) in &self.variables {
-            let placeholder = format!("{{{{{}}}}}", key);
-            result = result.replace(&placeholder, value);
-        }
+        while let Some(start) = rest.find("{{") {
+            let Some(len) = rest[start + 2..].find("}}") else {
+                break;
+            };
+            let end = start + 2 + len + 2;
+            let (key, default) = match rest[start + 2..end - 2].split_once('|') {
+                Some((key, default)) => (key, Some(default)),
+                None => (&rest[start + 2..end - 2], None),
+            };
 
-        // Check for unresolved placeholders
-        if result.contains("{{") && result.contains("}}") {
-            let unresolved: Vec<&str> = result
-                .split("{{")
-                .skip(1)
-                .filter_map(|s| s.split("}}").next())
-                .collect();
+            result.push_str(&rest[..start]);
+            match self.variables.get(key).map(String::as_str).or(default) {
+                Some(value) => result.push_str(value),
+                None => {
+                    unresolved.push(key);
+                    if self.missing != MissingPolicy::Empty {
+                        result.push_str(&rest[start..end]);
+                    }
+                }
+            }
+            rest = &rest[end..];
+        }
+        result.push_str(rest);
 
-            if !unresolved.is_empty() {
-                return Err(anyhow!(
+        if !unresolved.is_empty() {
+            match self.missing {
+                MissingPolicy::Error => {
+                    return Err(anyhow!(
+                        "Template contains unresolved placeholders: {}",
+                        unresolved.join(", ")
+                    ));
+                }
+                MissingPolicy::Warn => tracing::warn!(
                     "Template contains unresolved placeholders: {}",
                     unresolved.join(", ")
-                ));
+                ),
+                MissingPolicy::Leave | MissingPolicy::Empty => {}
             }
         }
 
@@ -221,6 +610,53 @@ impl Default for TemplateEngine {
     }
 }
 
+/// Prompt templates from user files, falling back to the built-in ones
+///
+/// Resolution order: the explicit template file, then
+/// `<prompt_dir>/<content_type>.<lang>.txt`, then [`BuiltinTemplates`].
+#[derive(Debug, Clone, Default)]
+pub struct PromptLibrary {
+    /// Template file given explicitly, e.g. via `--template`
+    pub template_file: Option<PathBuf>,
+
+    /// Directory of per content type and language overrides
+    pub prompt_dir: Option<PathBuf>,
+}
+
+impl PromptLibrary {
+    /// Default user prompt directory (`~/.config/dox/prompts`)
+    pub fn default_dir() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("dox").join("prompts"))
+    }
+
+    /// File in the prompt directory that would override the built-in template
+    pub fn user_template_path(&self, content_type: ContentType, language: &str) -> Option<PathBuf> {
+        self.prompt_dir
+            .as_ref()
+            .map(|dir| dir.join(format!("{}.{}.txt", content_type.as_str(), language)))
+    }
+
+    /// Find the template for a content type and language
+    ///
+    /// A missing explicit file is an error; a missing file in the prompt
+    /// directory just falls through to the built-in template.
+    pub fn resolve(&self, content_type: ContentType, language: &str) -> Result<String> {
+        if let Some(path) = &self.template_file {
+            return std::fs::read_to_string(path)
+                .map_err(|e| anyhow!("Failed to read template {}: {}", path.display(), e));
+        }
+
+        if let Some(path) = self.user_template_path(content_type, language) {
+            if path.is_file() {
+                return std::fs::read_to_string(&path)
+                    .map_err(|e| anyhow!("Failed to read template {}: {}", path.display(), e));
+            }
+        }
+
+        Ok(BuiltinTemplates::get_template(content_type, language))
+    }
+}
+
 /// Built-in templates for different content types
 pub struct BuiltinTemplates;
 
@@ -382,3 +818,352 @@ impl BuiltinTemplates {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn request() -> GenerationRequest {
+        GenerationRequest {
+            prompt: "prompt".to_string(),
+            content_type: ContentType::Custom,
+            model: "gpt-4o-mini".to_string(),
+            max_tokens: 100,
+            temperature: 0.5,
+            language: "ko".to_string(),
+            audience: "일반".to_string(),
+            tone: "전문적".to_string(),
+            context: None,
+            system_prompt: None,
+            examples: Vec::new(),
+            response_format: None,
+            stream: false,
+            provider_params: HashMap::new(),
+        }
+        .with_retry_policy(&RetryPolicy {
+            max_attempts: 3,
+            initial_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(5),
+            backoff_multiplier: 2.0,
+            jitter: false,
+        })
+    }
+
+    #[test]
+    fn test_retry_settings_from_provider_params() {
+        let request = request().with_timeout(Duration::from_millis(250));
+        let policy = request.retry_policy();
+
+        assert_eq!(policy.max_attempts, 3);
+        assert_eq!(policy.initial_delay, Duration::from_millis(1));
+        assert_eq!(request.request_timeout(), Duration::from_millis(250));
+
+        let mut defaults = request.clone();
+        defaults.provider_params.clear();
+        assert_eq!(defaults.request_timeout(), DEFAULT_REQUEST_TIMEOUT);
+    }
+
+    /// Generator replaying canned contents, one per call
+    struct ScriptedGenerator {
+        contents: Vec<&'static str>,
+        calls: AtomicU32,
+    }
+
+    impl ScriptedGenerator {
+        fn new(contents: Vec<&'static str>) -> Self {
+            Self {
+                contents,
+                calls: AtomicU32::new(0),
+            }
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl ContentGenerator for ScriptedGenerator {
+        async fn generate(&self, request: &GenerationRequest) -> Result<GenerationResponse> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst) as usize;
+            Ok(GenerationResponse {
+                content: self.contents[call].to_string(),
+                model: request.model.clone(),
+                provider: "scripted".to_string(),
+                usage: None,
+                metadata: HashMap::new(),
+            })
+        }
+
+        fn provider_name(&self) -> &str {
+            "scripted"
+        }
+
+        fn supported_models(&self) -> Vec<String> {
+            Vec::new()
+        }
+    }
+
+    fn json_request() -> GenerationRequest {
+        GenerationRequest {
+            response_format: Some(ResponseFormat::Json { schema: None }),
+            ..request()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_structured_output_valid_json() {
+        let generator = ScriptedGenerator::new(vec!["```json\n{\"title\": \"Rust\"}\n```"]);
+
+        let response = generate_validated(&generator, &json_request())
+            .await
+            .unwrap();
+        assert_eq!(response.content, r#"{"title": "Rust"}"#);
+        assert_eq!(generator.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_structured_output_retries_once() {
+        let generator = ScriptedGenerator::new(vec!["Sure! Here it is:", r#"{"ok": true}"#]);
+        let response = generate_validated(&generator, &json_request())
+            .await
+            .unwrap();
+        assert_eq!(response.content, r#"{"ok": true}"#);
+        assert_eq!(generator.calls.load(Ordering::SeqCst), 2);
+
+        let generator = ScriptedGenerator::new(vec!["not json", "{broken", "never sent"]);
+        let err = generate_validated(&generator, &json_request())
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<DoxError>(),
+            Some(DoxError::ParseError { .. })
+        ));
+        assert_eq!(generator.calls.load(Ordering::SeqCst), 2);
+
+        // Prose requests are passed through untouched
+        let generator = ScriptedGenerator::new(vec!["plain text"]);
+        let response = generate_validated(&generator, &request()).await.unwrap();
+        assert_eq!(response.content, "plain text");
+    }
+
+    #[test]
+    fn test_context_in_user_message() {
+        let mut request = request();
+        assert_eq!(request.user_message(), "prompt");
+
+        request.context = Some("# 보고서\n\n매출 증가\n".to_string());
+        let turns = request.chat_turns();
+        assert_eq!(
+            turns.last().unwrap(),
+            &(
+                "user",
+                "prompt\n\n<context>\n# 보고서\n\n매출 증가\n</context>".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_response_format_payloads() {
+        let schema = serde_json::json!({
+            "type": "object",
+            "properties": { "title": { "type": "string" } },
+        });
+        let format = ResponseFormat::Json {
+            schema: Some(schema.clone()),
+        };
+
+        assert_eq!(
+            ResponseFormat::Json { schema: None }.chat_completions_format(),
+            serde_json::json!({ "type": "json_object" })
+        );
+        assert_eq!(
+            format.chat_completions_format()["json_schema"]["schema"],
+            schema
+        );
+        assert_eq!(format.schema(), schema);
+    }
+
+    #[tokio::test]
+    async fn test_retries_transient_failures() {
+        let attempts = AtomicU32::new(0);
+
+        // Stub transport: rate limited, then a server error, then success
+        let result = send_with_retry(&request(), "stub", || async {
+            match attempts.fetch_add(1, Ordering::SeqCst) {
+                0 => Err(DoxError::from_http_status("stub", 429, "slow down")),
+                1 => Err(DoxError::from_http_status("stub", 502, "bad gateway")),
+                _ => Ok("generated"),
+            }
+        })
+        .await;
+
+        assert_eq!(result.unwrap(), "generated");
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_auth_errors_fail_fast() {
+        let attempts = AtomicU32::new(0);
+
+        let result: Result<()> = send_with_retry(&request(), "stub", || async {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            Err(DoxError::from_http_status("stub", 401, "invalid api key"))
+        })
+        .await;
+
+        let err = result.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<DoxError>(),
+            Some(DoxError::AuthenticationError { .. })
+        ));
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_timeout_is_retried() {
+        let attempts = AtomicU32::new(0);
+        let request = request().with_timeout(Duration::from_millis(20));
+
+        let result = send_with_retry(&request, "stub", || async {
+            if attempts.fetch_add(1, Ordering::SeqCst) == 0 {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+            }
+            Ok(())
+        })
+        .await;
+
+        assert!(result.is_ok());
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_sse_decoder_handles_split_chunks() {
+        let mut decoder = SseDecoder::default();
+        let body = "event: delta\ndata: {\"text\":\"안녕\"}\n\ndata: [DONE]\n".as_bytes();
+
+        // Split inside the multi-byte character to mimic network chunking
+        let (first, second) = body.split_at(26);
+        assert!(decoder.feed(first).is_empty());
+        assert_eq!(
+            decoder.feed(second),
+            vec!["{\"text\":\"안녕\"}".to_string(), "[DONE]".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_template_missing_policies() {
+        let template = "Hello {{name}}, see {{link}}.";
+        let engine = || {
+            TemplateEngine::with_variables(HashMap::from([(
+                "name".to_string(),
+                "Kim {{x}}".to_string(),
+            )]))
+        };
+
+        let err = engine().render(template).unwrap_err();
+        assert!(err.to_string().contains("link"));
+        assert!(!err.to_string().contains("name"));
+
+        for (policy, expected) in [
+            (MissingPolicy::Warn, "Hello Kim {{x}}, see {{link}}."),
+            (MissingPolicy::Leave, "Hello Kim {{x}}, see {{link}}."),
+            (MissingPolicy::Empty, "Hello Kim {{x}}, see ."),
+        ] {
+            let rendered = engine()
+                .with_missing_policy(policy)
+                .render(template)
+                .unwrap();
+            assert_eq!(rendered, expected, "{:?}", policy);
+        }
+    }
+
+    #[test]
+    fn test_template_default_values() {
+        let engine = TemplateEngine::with_variables(HashMap::from([(
+            "company".to_string(),
+            "Pyhub".to_string(),
+        )]));
+
+        // A set variable wins over its default
+        assert_eq!(engine.render("{{company|Acme Corp}}").unwrap(), "Pyhub");
+        // Missing variables use the default, which may be empty
+        assert_eq!(
+            engine.render("{{team|Sales}} / [{{note|}}]").unwrap(),
+            "Sales / []"
+        );
+        // Without a default the missing policy still applies
+        assert!(engine.render("{{team|Sales}} {{team}}").is_err());
+        assert_eq!(
+            engine
+                .with_missing_policy(MissingPolicy::Empty)
+                .render("{{team|Sales}}{{team}}")
+                .unwrap(),
+            "Sales"
+        );
+    }
+
+    #[test]
+    fn test_user_template_shadows_builtin() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("blog.ko.txt"),
+            "사내 블로그 규칙을 따르세요: {{prompt}} ({{audience}})",
+        )
+        .unwrap();
+        let library = PromptLibrary {
+            template_file: None,
+            prompt_dir: Some(dir.path().to_path_buf()),
+        };
+
+        let template = library.resolve(ContentType::Blog, "ko").unwrap();
+        let mut request = request().with_system_template(template);
+        request.content_type = ContentType::Blog;
+        request.prompt = "Rust".to_string();
+        let rendered = TemplateEngine::with_variables(HashMap::from([
+            ("prompt".to_string(), request.prompt.clone()),
+            ("audience".to_string(), request.audience.clone()),
+        ]))
+        .render(&request.system_template())
+        .unwrap();
+        assert_eq!(rendered, "사내 블로그 규칙을 따르세요: Rust (일반)");
+
+        // An explicit template file wins over the prompt directory
+        let explicit = dir.path().join("custom.txt");
+        std::fs::write(&explicit, "explicit {{prompt}}").unwrap();
+        let library = PromptLibrary {
+            template_file: Some(explicit),
+            ..library
+        };
+        assert_eq!(
+            library.resolve(ContentType::Blog, "ko").unwrap(),
+            "explicit {{prompt}}"
+        );
+    }
+
+    #[test]
+    fn test_missing_user_template_falls_back() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("blog.ko.txt"), "user blog").unwrap();
+        let library = PromptLibrary {
+            template_file: None,
+            prompt_dir: Some(dir.path().to_path_buf()),
+        };
+
+        assert_eq!(
+            library.resolve(ContentType::Report, "ko").unwrap(),
+            BuiltinTemplates::get_template(ContentType::Report, "ko")
+        );
+        assert_eq!(
+            library.resolve(ContentType::Blog, "en").unwrap(),
+            BuiltinTemplates::get_template(ContentType::Blog, "en")
+        );
+        assert_eq!(
+            request().system_template(),
+            BuiltinTemplates::get_template(ContentType::Custom, "ko")
+        );
+
+        let library = PromptLibrary {
+            template_file: Some(dir.path().join("missing.txt")),
+            prompt_dir: None,
+        };
+        assert!(library.resolve(ContentType::Blog, "ko").is_err());
+    }
+}
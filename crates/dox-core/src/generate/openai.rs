@@ -1,6 +1,10 @@
 //! OpenAI API integration for content generation
 
-use super::{ContentGenerator, GenerationRequest, GenerationResponse, Usage};
+use super::{
+    send_with_retry, ContentGenerator, GenerationRequest, GenerationResponse, HttpClientConfig,
+    SseDecoder, Usage,
+};
+use crate::error::{DoxError, DoxResult};
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use reqwest::Client;
@@ -34,6 +38,25 @@ impl OpenAIProvider {
         }
     }
 
+    /// Create OpenAI provider from HTTP transport settings
+    ///
+    /// Falls back to the public endpoint when no base URL is configured.
+    pub fn configured(api_key: String, http: &HttpClientConfig) -> Result<Self> {
+        let provider = match &http.base_url {
+            Some(base_url) => {
+                Self::with_base_url(api_key, base_url.trim_end_matches('/').to_string())
+            }
+            None => Self::new(api_key),
+        };
+        Ok(provider.with_client(http.build_client()?))
+    }
+
+    /// Use a pre-built HTTP client for all requests
+    pub fn with_client(mut self, client: Client) -> Self {
+        self.client = client;
+        self
+    }
+
     /// Get API key from environment variable
     pub fn from_env() -> Result<Self> {
         let api_key = std::env::var("OPENAI_API_KEY")
@@ -44,20 +67,17 @@ impl OpenAIProvider {
     /// Convert internal request to OpenAI API format
     fn build_openai_request(&self, request: &GenerationRequest) -> OpenAIChatRequest {
         let system_message = self.build_system_message(request);
-        let user_message = request.prompt.clone();
+        let messages = std::iter::once(("system", system_message))
+            .chain(request.chat_turns())
+            .map(|(role, content)| Message {
+                role: role.to_string(),
+                content,
+            })
+            .collect();
 
         OpenAIChatRequest {
             model: request.model.clone(),
-            messages: vec![
-                Message {
-                    role: "system".to_string(),
-                    content: system_message,
-                },
-                Message {
-                    role: "user".to_string(),
-                    content: user_message,
-                },
-            ],
+            messages,
             max_tokens: Some(request.max_tokens),
             temperature: Some(request.temperature),
             stream: Some(request.stream),
@@ -65,15 +85,22 @@ impl OpenAIProvider {
             stop: None,
             presence_penalty: None,
             frequency_penalty: None,
+            response_format: request
+                .response_format
+                .as_ref()
+                .map(|format| format.chat_completions_format()),
         }
     }
 
     /// Build system message based on content type and context
     fn build_system_message(&self, request: &GenerationRequest) -> String {
-        use super::BuiltinTemplates;
         use super::TemplateEngine;
 
-        let template = BuiltinTemplates::get_template(request.content_type, &request.language);
+        if let Some(system_prompt) = &request.system_prompt {
+            return system_prompt.clone();
+        }
+
+        let template = request.system_template();
 
         let mut variables = HashMap::new();
         variables.insert("prompt".to_string(), request.prompt.clone());
@@ -89,11 +116,11 @@ impl OpenAIProvider {
         engine.render(&template).unwrap_or(template)
     }
 
-    /// Send non-streaming request to OpenAI
-    async fn send_request(&self, openai_request: &OpenAIChatRequest) -> Result<OpenAIChatResponse> {
+    /// Post a chat completion request, turning HTTP errors into `Err`
+    async fn post(&self, openai_request: &OpenAIChatRequest) -> DoxResult<reqwest::Response> {
         debug!(
-            "Sending OpenAI API request: model={}, max_tokens={:?}",
-            openai_request.model, openai_request.max_tokens
+            "Sending OpenAI API request: model={}, max_tokens={:?}, stream={:?}",
+            openai_request.model, openai_request.max_tokens, openai_request.stream
         );
 
         let response = self
@@ -112,10 +139,26 @@ impl OpenAIProvider {
                 .await
                 .unwrap_or_else(|_| "Unknown error".to_string());
             error!("OpenAI API error: {} - {}", status, error_text);
-            return Err(anyhow!("OpenAI API error {}: {}", status, error_text));
+            return Err(DoxError::from_http_status(
+                "openai",
+                status.as_u16(),
+                error_text,
+            ));
         }
 
-        let openai_response: OpenAIChatResponse = response.json().await?;
+        Ok(response)
+    }
+
+    /// Send non-streaming request to OpenAI
+    async fn send_request(
+        &self,
+        openai_request: &OpenAIChatRequest,
+    ) -> DoxResult<OpenAIChatResponse> {
+        let response = self.post(openai_request).await?;
+        let openai_response: OpenAIChatResponse =
+            response.json().await.map_err(|e| DoxError::ParseError {
+                message: format!("Invalid OpenAI response: {}", e),
+            })?;
         debug!(
             "Received OpenAI response: {} choices, usage={:?}",
             openai_response.choices.len(),
@@ -166,15 +209,14 @@ impl ContentGenerator for OpenAIProvider {
             );
         }
 
-        let openai_request = self.build_openai_request(request);
+        let mut openai_request = self.build_openai_request(request);
+        // Streaming output goes through generate_stream
+        openai_request.stream = Some(false);
 
-        // For now, only support non-streaming requests
-        // TODO: Implement streaming support
-        if request.stream {
-            warn!("Streaming not yet implemented, falling back to non-streaming");
-        }
-
-        let openai_response = self.send_request(&openai_request).await?;
+        let openai_response = send_with_retry(request, "OpenAI request", || {
+            self.send_request(&openai_request)
+        })
+        .await?;
         let response = self.convert_response(openai_response, request.model.clone());
 
         info!(
@@ -186,6 +228,56 @@ impl ContentGenerator for OpenAIProvider {
         Ok(response)
     }
 
+    async fn generate_stream(
+        &self,
+        request: &GenerationRequest,
+        on_chunk: &mut (dyn for<'c> FnMut(&'c str) + Send),
+    ) -> Result<GenerationResponse> {
+        info!("Streaming content using OpenAI model: {}", request.model);
+
+        let mut openai_request = self.build_openai_request(request);
+        openai_request.stream = Some(true);
+
+        let mut response = send_with_retry(request, "OpenAI stream request", || {
+            self.post(&openai_request)
+        })
+        .await?;
+        let mut decoder = SseDecoder::default();
+        let mut content = String::new();
+
+        'stream: while let Some(bytes) = response.chunk().await? {
+            for payload in decoder.feed(&bytes) {
+                if payload == "[DONE]" {
+                    break 'stream;
+                }
+
+                let chunk: OpenAIStreamChunk = serde_json::from_str(&payload)
+                    .map_err(|e| anyhow!("Invalid OpenAI stream chunk: {}", e))?;
+                if let Some(text) = chunk
+                    .choices
+                    .first()
+                    .and_then(|choice| choice.delta.content.as_deref())
+                {
+                    on_chunk(text);
+                    content.push_str(text);
+                }
+            }
+        }
+
+        info!(
+            "Streaming completed. Generated {} characters",
+            content.len()
+        );
+
+        Ok(GenerationResponse {
+            content,
+            model: request.model.clone(),
+            provider: "openai".to_string(),
+            usage: None,
+            metadata: HashMap::new(),
+        })
+    }
+
     fn provider_name(&self) -> &str {
         "openai"
     }
@@ -214,6 +306,8 @@ struct OpenAIChatRequest {
     stop: Option<Vec<String>>,
     presence_penalty: Option<f32>,
     frequency_penalty: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_format: Option<serde_json::Value>,
 }
 
 /// OpenAI message structure
@@ -226,10 +320,6 @@ struct Message {
 /// OpenAI Chat Completions API response structure
 #[derive(Debug, Deserialize)]
 struct OpenAIChatResponse {
-    id: String,
-    object: String,
-    created: u64,
-    model: String,
     choices: Vec<Choice>,
     usage: Option<OpenAIUsage>,
 }
@@ -237,9 +327,25 @@ struct OpenAIChatResponse {
 /// OpenAI choice structure
 #[derive(Debug, Deserialize)]
 struct Choice {
-    index: i32,
     message: Message,
-    finish_reason: Option<String>,
+}
+
+/// OpenAI streaming chunk (`chat.completion.chunk`)
+#[derive(Debug, Deserialize)]
+struct OpenAIStreamChunk {
+    choices: Vec<StreamChoice>,
+}
+
+/// OpenAI streaming choice structure
+#[derive(Debug, Deserialize)]
+struct StreamChoice {
+    delta: Delta,
+}
+
+/// Incremental message content in a streaming choice
+#[derive(Debug, Deserialize)]
+struct Delta {
+    content: Option<String>,
 }
 
 /// OpenAI usage structure
@@ -253,7 +359,7 @@ struct OpenAIUsage {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::generate::ContentType;
+    use crate::generate::{ContentType, HttpClientConfig};
 
     #[test]
     fn test_provider_creation() {
@@ -264,6 +370,89 @@ mod tests {
         assert!(!provider.supports_model("invalid-model"));
     }
 
+    fn chat_request() -> GenerationRequest {
+        GenerationRequest {
+            prompt: "Test prompt".to_string(),
+            content_type: ContentType::Blog,
+            model: "gpt-4".to_string(),
+            max_tokens: 100,
+            temperature: 0.7,
+            language: "ko".to_string(),
+            audience: "개발자".to_string(),
+            tone: "친근한".to_string(),
+            context: None,
+            system_prompt: None,
+            examples: Vec::new(),
+            response_format: None,
+            stream: false,
+            provider_params: HashMap::from([(
+                crate::generate::PARAM_RETRY_MAX_ATTEMPTS.to_string(),
+                serde_json::json!(1),
+            )]),
+        }
+    }
+
+    const CHAT_RESPONSE: &str = r#"{
+        "id": "chatcmpl-1", "object": "chat.completion", "created": 0, "model": "gpt-4",
+        "choices": [{"index": 0, "message": {"role": "assistant", "content": "안녕하세요"}, "finish_reason": "stop"}],
+        "usage": {"prompt_tokens": 5, "completion_tokens": 3, "total_tokens": 8}
+    }"#;
+
+    #[tokio::test]
+    async fn test_configured_base_url_is_used() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/v1/chat/completions")
+            .match_header("authorization", "Bearer test-key")
+            .with_header("content-type", "application/json")
+            .with_body(CHAT_RESPONSE)
+            .create_async()
+            .await;
+
+        let http = HttpClientConfig {
+            base_url: Some(format!("{}/v1/", server.url())),
+            ..Default::default()
+        };
+        let provider = OpenAIProvider::configured("test-key".to_string(), &http).unwrap();
+        let response = provider.generate(&chat_request()).await.unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(response.content, "안녕하세요");
+    }
+
+    #[tokio::test]
+    async fn test_configured_proxy_is_honored() {
+        // The mock server stands in for the proxy: an absolute-form request
+        // for the unreachable host can only arrive through it.
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/v1/chat/completions")
+            .match_header("host", "api.example.invalid")
+            .with_header("content-type", "application/json")
+            .with_body(CHAT_RESPONSE)
+            .create_async()
+            .await;
+
+        let http = HttpClientConfig {
+            base_url: Some("http://api.example.invalid/v1".to_string()),
+            proxy: Some(server.url()),
+            ca_cert: None,
+        };
+        let provider = OpenAIProvider::configured("test-key".to_string(), &http).unwrap();
+        provider.generate(&chat_request()).await.unwrap();
+
+        mock.assert_async().await;
+    }
+
+    #[test]
+    fn test_configured_rejects_missing_ca_cert() {
+        let http = HttpClientConfig {
+            ca_cert: Some("/nonexistent/ca.pem".into()),
+            ..Default::default()
+        };
+        assert!(OpenAIProvider::configured("test-key".to_string(), &http).is_err());
+    }
+
     #[test]
     fn test_system_message_building() {
         let provider = OpenAIProvider::new("test-key".to_string());
@@ -277,6 +466,9 @@ mod tests {
             audience: "개발자".to_string(),
             tone: "친근한".to_string(),
             context: None,
+            system_prompt: None,
+            examples: Vec::new(),
+            response_format: None,
             stream: false,
             provider_params: HashMap::new(),
         };
@@ -286,4 +478,36 @@ mod tests {
         assert!(system_message.contains("한국어"));
         assert!(system_message.contains("개발자"));
     }
+
+    #[test]
+    fn test_system_prompt_and_examples_roles() {
+        let provider = OpenAIProvider::new("test-key".to_string());
+        let mut request = chat_request();
+        request.examples = vec![
+            ("Q1".to_string(), "A1".to_string()),
+            ("Q2".to_string(), "A2".to_string()),
+        ];
+
+        // Without a system prompt the content type template is used
+        let payload = serde_json::to_value(provider.build_openai_request(&request)).unwrap();
+        assert_eq!(payload["messages"][0]["role"], "system");
+        assert!(payload["messages"][0]["content"]
+            .as_str()
+            .unwrap()
+            .contains("Test prompt"));
+
+        request.system_prompt = Some("Answer briefly.".to_string());
+        let payload = serde_json::to_value(provider.build_openai_request(&request)).unwrap();
+        assert_eq!(
+            payload["messages"],
+            serde_json::json!([
+                {"role": "system", "content": "Answer briefly."},
+                {"role": "user", "content": "Q1"},
+                {"role": "assistant", "content": "A1"},
+                {"role": "user", "content": "Q2"},
+                {"role": "assistant", "content": "A2"},
+                {"role": "user", "content": "Test prompt"},
+            ])
+        );
+    }
 }
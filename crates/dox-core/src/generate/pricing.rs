@@ -0,0 +1,163 @@
+//! Per-model pricing and cost estimation for generation requests
+//!
+//! Prices are list prices in USD per million tokens. Costs computed here are
+//! estimates for budgeting, not billing figures.
+
+use super::{GenerationRequest, Usage};
+
+/// Price of a model in USD per million tokens
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ModelPricing {
+    pub input_per_million: f64,
+    pub output_per_million: f64,
+}
+
+impl ModelPricing {
+    const fn new(input_per_million: f64, output_per_million: f64) -> Self {
+        Self {
+            input_per_million,
+            output_per_million,
+        }
+    }
+
+    /// Cost in USD for the given token counts
+    pub fn cost(&self, prompt_tokens: usize, completion_tokens: usize) -> f64 {
+        (prompt_tokens as f64 * self.input_per_million
+            + completion_tokens as f64 * self.output_per_million)
+            / 1_000_000.0
+    }
+}
+
+/// Model name prefixes and their prices
+///
+/// More specific prefixes come first so that `gpt-4o-mini` is not priced as
+/// `gpt-4o`, and `gpt-4o` not as `gpt-4`.
+const PRICING: &[(&str, ModelPricing)] = &[
+    // OpenAI
+    ("gpt-4o-mini", ModelPricing::new(0.15, 0.60)),
+    ("gpt-4o", ModelPricing::new(2.50, 10.00)),
+    ("gpt-4-turbo", ModelPricing::new(10.00, 30.00)),
+    ("gpt-4", ModelPricing::new(30.00, 60.00)),
+    ("gpt-3.5-turbo-16k", ModelPricing::new(3.00, 4.00)),
+    ("gpt-3.5-turbo", ModelPricing::new(0.50, 1.50)),
+    // Claude
+    ("claude-3-5-sonnet", ModelPricing::new(3.00, 15.00)),
+    ("claude-3-5-haiku", ModelPricing::new(0.80, 4.00)),
+    ("claude-3-opus", ModelPricing::new(15.00, 75.00)),
+    ("claude-3-sonnet", ModelPricing::new(3.00, 15.00)),
+    ("claude-3-haiku", ModelPricing::new(0.25, 1.25)),
+];
+
+/// Pricing for a model, or `None` if the model is not in the table
+pub fn pricing_for(model: &str) -> Option<ModelPricing> {
+    PRICING
+        .iter()
+        .find(|(prefix, _)| model.starts_with(prefix))
+        .map(|(_, pricing)| *pricing)
+}
+
+/// Rough token count for `text`
+///
+/// Counts about four ASCII characters per token and one token per other
+/// character, which keeps estimates for Korean text on the high side.
+pub fn estimate_tokens(text: &str) -> usize {
+    let (ascii, other) = text.chars().fold((0usize, 0usize), |(ascii, other), c| {
+        if c.is_ascii() {
+            (ascii + 1, other)
+        } else {
+            (ascii, other + 1)
+        }
+    });
+    ascii.div_ceil(4) + other
+}
+
+/// Estimated prompt tokens for a request, including the system prompt (or
+/// the content type's instructions), few-shot examples and extra context
+pub fn estimate_prompt_tokens(request: &GenerationRequest) -> usize {
+    let instructions = request
+        .system_prompt
+        .as_deref()
+        .unwrap_or_else(|| request.content_type.default_instructions());
+    let examples: usize = request
+        .examples
+        .iter()
+        .map(|(user, assistant)| estimate_tokens(user) + estimate_tokens(assistant))
+        .sum();
+
+    estimate_tokens(&request.prompt)
+        + estimate_tokens(instructions)
+        + examples
+        + request.context.as_deref().map_or(0, estimate_tokens)
+}
+
+/// Estimated cost in USD of sending the request's prompt
+///
+/// Returns `None` if the model has no known pricing.
+pub fn estimate_cost(request: &GenerationRequest) -> Option<f64> {
+    pricing_for(&request.model).map(|pricing| pricing.cost(estimate_prompt_tokens(request), 0))
+}
+
+/// Actual cost in USD of a completed generation
+pub fn usage_cost(model: &str, usage: &Usage) -> Option<f64> {
+    pricing_for(model).map(|pricing| pricing.cost(usage.prompt_tokens, usage.completion_tokens))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate::ContentType;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_pricing_lookup() {
+        assert_eq!(
+            pricing_for("gpt-4o-mini"),
+            Some(ModelPricing::new(0.15, 0.60))
+        );
+        assert_eq!(
+            pricing_for("gpt-4o-2024-08-06"),
+            Some(ModelPricing::new(2.50, 10.00))
+        );
+        assert_eq!(pricing_for("gpt-4").unwrap().input_per_million, 30.00);
+        assert_eq!(
+            pricing_for("claude-3-5-haiku-20241022"),
+            Some(ModelPricing::new(0.80, 4.00))
+        );
+        assert_eq!(pricing_for("llama-3"), None);
+    }
+
+    #[test]
+    fn test_costs() {
+        let usage = Usage {
+            prompt_tokens: 1_000,
+            completion_tokens: 2_000,
+            total_tokens: 3_000,
+        };
+        let cost = usage_cost("claude-3-opus-20240229", &usage).unwrap();
+        assert!((cost - 0.165).abs() < 1e-9);
+
+        assert_eq!(estimate_tokens("abcdefgh"), 2);
+        assert_eq!(estimate_tokens("안녕하세요 abc"), 6);
+
+        let request = GenerationRequest {
+            prompt: "a".repeat(4_000),
+            content_type: ContentType::Custom,
+            model: "gpt-4".to_string(),
+            max_tokens: 100,
+            temperature: 0.5,
+            language: "en".to_string(),
+            audience: "general".to_string(),
+            tone: "neutral".to_string(),
+            context: None,
+            system_prompt: None,
+            examples: Vec::new(),
+            response_format: None,
+            stream: false,
+            provider_params: HashMap::new(),
+        };
+        let tokens = estimate_prompt_tokens(&request);
+        assert!(tokens > 1_000);
+        let cost = estimate_cost(&request).unwrap();
+        assert!((cost - tokens as f64 * 30.0 / 1_000_000.0).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,310 @@
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+
+/// English message map
+pub(super) static MESSAGES: Lazy<HashMap<&'static str, &'static str>> = Lazy::new(|| {
+    let mut m = HashMap::new();
+
+    // === Common ===
+    m.insert("app.name", "dox");
+    m.insert(
+        "app.description",
+        "Document automation and AI-powered content generation CLI",
+    );
+    m.insert("app.version", "Version");
+
+    // === Commands ===
+    m.insert(
+        "cmd.replace",
+        "Replace text in documents using a YAML rules file",
+    );
+    m.insert("cmd.create", "Create documents from Markdown files");
+    m.insert(
+        "cmd.template",
+        "Process document templates containing placeholders",
+    );
+    m.insert("cmd.generate", "Generate content using AI");
+    m.insert("cmd.extract", "Extract text from documents");
+    m.insert("cmd.config", "Manage configuration");
+    m.insert(
+        "cmd.convert",
+        "Convert documents to other formats (docx→markdown, xlsx→csv, pdf→text, ...)",
+    );
+    m.insert(
+        "cmd.diff",
+        "Compare the text of two documents paragraph by paragraph",
+    );
+    m.insert("cmd.excel", "Read, write and chart Excel files");
+
+    // === Common options ===
+    m.insert("opt.config", "Path to the configuration file");
+    m.insert("opt.verbose", "Enable verbose output");
+    m.insert("opt.quiet", "Suppress all output except errors");
+    m.insert("opt.no_color", "Disable colored output");
+    m.insert("opt.lang", "Set the interface language (en, ko)");
+    m.insert(
+        "opt.profile",
+        "Configuration profile to apply over the base settings (instead of DOX_PROFILE)",
+    );
+    m.insert(
+        "opt.output_format",
+        "Result output format (json: print results and errors as single-line JSON on stdout)",
+    );
+    m.insert("opt.help", "Show help");
+
+    // === Replace options ===
+    m.insert(
+        "opt.replace.rules",
+        "YAML file containing replacement rules",
+    );
+    m.insert("opt.replace.path", "Target file or directory path");
+    m.insert(
+        "opt.replace.dry_run",
+        "Preview changes without modifying files",
+    );
+    m.insert("opt.replace.backup", "Create backup files before modifying");
+    m.insert(
+        "opt.replace.recursive",
+        "Process subdirectories recursively",
+    );
+    m.insert("opt.replace.exclude", "Glob pattern for files to exclude");
+    m.insert("opt.replace.concurrent", "Enable parallel processing");
+    m.insert(
+        "opt.replace.max_workers",
+        "Maximum number of parallel workers",
+    );
+    m.insert("opt.replace.show_diff", "Show a diff for each change");
+    m.insert(
+        "opt.replace.watch",
+        "Re-run whenever the rules file changes (Ctrl+C to stop)",
+    );
+    m.insert(
+        "opt.replace.manifest",
+        "Write a JSON manifest with per-file results and SHA-256 hashes",
+    );
+
+    // === Extract options ===
+    m.insert("opt.extract.input", "Input document path");
+    m.insert(
+        "opt.extract.output",
+        "Output file path (defaults to standard output)",
+    );
+    m.insert("opt.extract.format", "Output format");
+    m.insert(
+        "opt.extract.with_metadata",
+        "Include metadata in the output",
+    );
+    m.insert(
+        "opt.extract.pages",
+        "Pages or slides to extract, e.g. 2-5,8 (PDF, PowerPoint)",
+    );
+
+    // === Diff options ===
+    m.insert("opt.diff.old", "Old document");
+    m.insert("opt.diff.new", "New document");
+    m.insert(
+        "opt.diff.format",
+        "Output format (unified, side-by-side, json)",
+    );
+    m.insert(
+        "opt.diff.context",
+        "Paragraphs of context around each change (unified)",
+    );
+    m.insert("opt.diff.width", "Total output width (side-by-side)");
+
+    // === Create options ===
+    m.insert("opt.create.from", "Input Markdown file");
+    m.insert("opt.create.output", "Output document path");
+    m.insert("opt.create.template", "Template document used for styling");
+    m.insert(
+        "opt.create.format",
+        "Output format (detected from the extension)",
+    );
+    m.insert(
+        "opt.create.force",
+        "Overwrite existing files without asking",
+    );
+    m.insert(
+        "opt.create.slide_level",
+        "Heading level that starts a new slide (PowerPoint)",
+    );
+    m.insert(
+        "opt.create.fetch_images",
+        "Download and embed images given by http(s) URLs",
+    );
+    m.insert(
+        "opt.create.compression",
+        "ZIP compression: stored or deflate, with an optional :LEVEL",
+    );
+
+    // === Template options ===
+    m.insert("opt.template.template", "Template file path");
+    m.insert("opt.template.output", "Output file path");
+    m.insert("opt.template.values", "YAML/JSON file containing values");
+    m.insert("opt.template.set", "Set an individual value (key=value)");
+    m.insert(
+        "opt.template.force",
+        "Overwrite existing files without asking",
+    );
+    m.insert(
+        "opt.template.watch",
+        "Re-run whenever the template or values file changes (Ctrl+C to stop)",
+    );
+
+    // === Generate options ===
+    m.insert("opt.generate.prompt", "Generation prompt");
+    m.insert("opt.generate.content_type", "Type of content to generate");
+    m.insert(
+        "opt.generate.output",
+        "Output file path (defaults to standard output)",
+    );
+    m.insert("opt.generate.model", "AI model to use");
+    m.insert(
+        "opt.generate.max_tokens",
+        "Maximum number of tokens in the response",
+    );
+    m.insert("opt.generate.temperature", "Creativity level (0.0-1.0)");
+    m.insert(
+        "opt.generate.provider",
+        "AI provider (detected from the model)",
+    );
+    m.insert(
+        "opt.generate.api_key",
+        "API key (environment variables are also supported)",
+    );
+
+    // === Config options ===
+    m.insert("opt.config.init", "Initialize the configuration file");
+    m.insert("opt.config.list", "List all configuration values");
+    m.insert("opt.config.get", "Get a configuration value");
+    m.insert("opt.config.set", "Set a configuration value");
+    m.insert(
+        "opt.config.strict",
+        "With --set, reject unknown keys instead of storing them as custom values",
+    );
+    m.insert("opt.config.unset", "Remove a configuration value");
+
+    // === UI ===
+    m.insert("ui.header", "Title");
+    m.insert("ui.info", "Info");
+    m.insert("ui.success", "Success");
+    m.insert("ui.warning", "Warning");
+    m.insert("ui.error", "Error");
+    m.insert("ui.step", "Step");
+    m.insert("ui.progress", "In progress");
+    m.insert("ui.processing", "Processing");
+    m.insert("ui.done", "Done");
+    m.insert("ui.failed", "Failed");
+    m.insert("ui.cancelled", "Cancelled");
+
+    // === Status ===
+    m.insert("status.starting", "Starting...");
+    m.insert("status.loading", "Loading...");
+    m.insert("status.saving", "Saving...");
+    m.insert("status.extracting", "Extracting...");
+    m.insert("status.generating", "Generating...");
+    m.insert("status.replacing", "Replacing...");
+    m.insert("status.creating", "Creating...");
+    m.insert("status.processing_template", "Processing template...");
+    m.insert("status.completed", "Completed");
+    m.insert("status.failed", "Failed");
+    m.insert("status.files_processed", "{count} files processed");
+
+    // === Prompts ===
+    m.insert("prompt.confirm", "Do you want to continue?");
+    m.insert("prompt.overwrite", "The file already exists. Overwrite it?");
+    m.insert("prompt.select", "Please select");
+    m.insert("prompt.enter_value", "Enter a value");
+    m.insert("prompt.yes", "Yes");
+    m.insert("prompt.no", "No");
+
+    // === Errors ===
+    m.insert("error.file_not_found", "File not found");
+    m.insert("error.invalid_format", "Invalid format");
+    m.insert("error.permission_denied", "Permission denied");
+    m.insert("error.io_error", "An I/O error occurred");
+    m.insert("error.parse_error", "A parse error occurred");
+    m.insert("error.api_error", "An API error occurred");
+    m.insert("error.not_implemented", "Not implemented yet");
+    m.insert("error.command_failed", "Command failed");
+
+    // === Success ===
+    m.insert("success.file_created", "File created");
+    m.insert("success.file_saved", "File saved");
+    m.insert("success.text_replaced", "Text replaced");
+    m.insert("success.content_generated", "Content generated");
+    m.insert("success.text_extracted", "Text extracted");
+    m.insert("success.config_saved", "Configuration saved");
+
+    // === Help ===
+    m.insert("help.usage", "Usage");
+    m.insert("help.commands", "Commands");
+    m.insert("help.options", "Options");
+    m.insert("help.examples", "Examples");
+    m.insert("help.supported_formats", "Supported formats");
+    m.insert("help.default_value", "Default");
+    m.insert("help.possible_values", "Possible values");
+
+    // === Extract help ===
+    m.insert("help.extract.formats", "• text: plain text (no formatting)\n• json: structured JSON (optionally with metadata)\n• markdown: Markdown (keeps headings, lists, etc.)");
+    #[cfg(feature = "pdf")]
+    m.insert(
+        "help.extract.supported_files",
+        "Supported files: .docx (Word), .pptx (PowerPoint), .pdf, .xlsx (Excel)",
+    );
+    #[cfg(not(feature = "pdf"))]
+    m.insert(
+        "help.extract.supported_files",
+        "Supported files: .docx (Word), .pptx (PowerPoint), .xlsx (Excel)",
+    );
+    m.insert(
+        "help.extract.example1",
+        "# Extract text from a Word document\ndox extract -i report.docx",
+    );
+    m.insert("help.extract.example2", "# Extract as JSON including metadata\ndox extract -i presentation.pptx --format json --with-metadata");
+
+    // === Replace help ===
+    m.insert("help.replace.yaml_format", "YAML rules file format:\nreplacements:\n  - old: \"old text\"\n    new: \"new text\"\n  - old: \"{{date}}\"\n    new: \"2025-09-02\"");
+    m.insert(
+        "help.replace.example1",
+        "# Replace in a single file\ndox replace -r rules.yaml -p document.docx",
+    );
+    m.insert("help.replace.example2", "# Replace recursively in a directory (preview)\ndox replace -r rules.yaml -p ./docs --recursive --dry-run");
+    m.insert(
+        "help.replace.exclude_pattern",
+        "Exclude pattern example: --exclude \"*.tmp\" --exclude \"backup/*\"",
+    );
+
+    // === Create help ===
+    m.insert("help.create.markdown_features", "Supported Markdown features:\n• Headings (# ## ###)\n• Lists (-, *, 1.)\n• Tables\n• Code blocks\n• Images\n• Links");
+    m.insert(
+        "help.create.example1",
+        "# Create a Word document from Markdown\ndox create -f README.md -o output.docx",
+    );
+    m.insert(
+        "help.create.example2",
+        "# Create using a template\ndox create -f content.md -o report.docx -t template.docx",
+    );
+
+    // === Document creation ===
+    m.insert("create.toc_title", "Contents");
+    m.insert(
+        "create.toc_placeholder",
+        "Press F9 to update the table of contents",
+    );
+    m.insert("create.agenda_title", "Agenda");
+
+    // === Generate help ===
+    m.insert("help.generate.providers", "AI provider setup:\n• OpenAI: set the OPENAI_API_KEY environment variable\n• Claude: set the ANTHROPIC_API_KEY environment variable");
+    m.insert("help.generate.content_types", "Content types:\n• blog: blog post\n• report: report\n• summary: summary\n• email: email\n• proposal: proposal\n• custom: custom");
+    m.insert(
+        "help.generate.example1",
+        "# Generate a blog post\ndox generate -p \"Introduction to Rust programming\" -t blog",
+    );
+    m.insert(
+        "help.generate.example2",
+        "# Generate a report with GPT-4\ndox generate -p \"2025 market analysis\" -t report --model gpt-4",
+    );
+
+    m
+});
@@ -2,7 +2,7 @@ use once_cell::sync::Lazy;
 use std::collections::HashMap;
 
 /// 한글 메시지 맵
-static MESSAGES: Lazy<HashMap<&'static str, &'static str>> = Lazy::new(|| {
+pub(super) static MESSAGES: Lazy<HashMap<&'static str, &'static str>> = Lazy::new(|| {
     let mut m = HashMap::new();
 
     // === 공통 메시지 ===
@@ -20,6 +20,12 @@ static MESSAGES: Lazy<HashMap<&'static str, &'static str>> = Lazy::new(|| {
     m.insert("cmd.generate", "AI를 사용하여 콘텐츠 생성");
     m.insert("cmd.extract", "문서에서 텍스트 추출");
     m.insert("cmd.config", "설정 관리");
+    m.insert(
+        "cmd.convert",
+        "문서를 다른 형식으로 변환 (docx→markdown, xlsx→csv, pdf→text 등)",
+    );
+    m.insert("cmd.diff", "두 문서의 텍스트를 문단 단위로 비교");
+    m.insert("cmd.excel", "Excel 파일 읽기, 쓰기, 차트 생성");
 
     // === 공통 옵션 ===
     m.insert("opt.config", "설정 파일 경로");
@@ -27,6 +33,14 @@ static MESSAGES: Lazy<HashMap<&'static str, &'static str>> = Lazy::new(|| {
     m.insert("opt.quiet", "오류 외 출력 억제");
     m.insert("opt.no_color", "색상 출력 비활성화");
     m.insert("opt.lang", "인터페이스 언어 설정 (en, ko)");
+    m.insert(
+        "opt.profile",
+        "사용할 설정 프로필 (기본 설정 위에 덮어씀, DOX_PROFILE 대신 사용)",
+    );
+    m.insert(
+        "opt.output_format",
+        "결과 출력 형식 (json: 결과와 오류를 한 줄짜리 JSON으로 표준출력에 출력)",
+    );
     m.insert("opt.help", "도움말 표시");
 
     // === Replace 명령어 옵션 ===
@@ -39,6 +53,14 @@ static MESSAGES: Lazy<HashMap<&'static str, &'static str>> = Lazy::new(|| {
     m.insert("opt.replace.concurrent", "병렬 처리 활성화");
     m.insert("opt.replace.max_workers", "최대 병렬 작업자 수");
     m.insert("opt.replace.show_diff", "각 변경사항의 차이점 표시");
+    m.insert(
+        "opt.replace.watch",
+        "규칙 파일이 바뀔 때마다 다시 실행 (Ctrl+C로 종료)",
+    );
+    m.insert(
+        "opt.replace.manifest",
+        "파일별 처리 결과와 SHA-256 해시를 JSON 매니페스트로 저장",
+    );
 
     // === Extract 명령어 옵션 ===
     m.insert("opt.extract.input", "입력 문서 경로");
@@ -48,6 +70,20 @@ static MESSAGES: Lazy<HashMap<&'static str, &'static str>> = Lazy::new(|| {
     );
     m.insert("opt.extract.format", "출력 형식");
     m.insert("opt.extract.with_metadata", "출력에 메타데이터 포함");
+    m.insert(
+        "opt.extract.pages",
+        "추출할 페이지 또는 슬라이드 (예: 2-5,8, PDF와 PowerPoint)",
+    );
+
+    // === Diff 명령어 옵션 ===
+    m.insert("opt.diff.old", "이전 문서");
+    m.insert("opt.diff.new", "새 문서");
+    m.insert("opt.diff.format", "출력 형식 (unified, side-by-side, json)");
+    m.insert(
+        "opt.diff.context",
+        "변경 문단 주변에 보여줄 문단 수 (unified)",
+    );
+    m.insert("opt.diff.width", "전체 출력 너비 (side-by-side)");
 
     // === Create 명령어 옵션 ===
     m.insert("opt.create.from", "입력 Markdown 파일");
@@ -55,6 +91,18 @@ static MESSAGES: Lazy<HashMap<&'static str, &'static str>> = Lazy::new(|| {
     m.insert("opt.create.template", "스타일링용 템플릿 문서");
     m.insert("opt.create.format", "출력 형식 (확장자에서 자동 감지)");
     m.insert("opt.create.force", "기존 파일을 묻지 않고 덮어쓰기");
+    m.insert(
+        "opt.create.slide_level",
+        "새 슬라이드를 시작할 제목 수준 (PowerPoint)",
+    );
+    m.insert(
+        "opt.create.fetch_images",
+        "http(s) URL 이미지를 내려받아 문서에 포함",
+    );
+    m.insert(
+        "opt.create.compression",
+        "ZIP 압축 방식: stored, deflate (:수준 지정 가능)",
+    );
 
     // === Template 명령어 옵션 ===
     m.insert("opt.template.template", "템플릿 파일 경로");
@@ -62,6 +110,10 @@ static MESSAGES: Lazy<HashMap<&'static str, &'static str>> = Lazy::new(|| {
     m.insert("opt.template.values", "값이 포함된 YAML/JSON 파일");
     m.insert("opt.template.set", "개별 값 설정 (key=value)");
     m.insert("opt.template.force", "기존 파일을 묻지 않고 덮어쓰기");
+    m.insert(
+        "opt.template.watch",
+        "템플릿이나 값 파일이 바뀔 때마다 다시 실행 (Ctrl+C로 종료)",
+    );
 
     // === Generate 명령어 옵션 ===
     m.insert("opt.generate.prompt", "생성 프롬프트");
@@ -81,6 +133,10 @@ static MESSAGES: Lazy<HashMap<&'static str, &'static str>> = Lazy::new(|| {
     m.insert("opt.config.list", "모든 설정 값 나열");
     m.insert("opt.config.get", "특정 설정 값 가져오기");
     m.insert("opt.config.set", "설정 값 지정");
+    m.insert(
+        "opt.config.strict",
+        "--set에서 알 수 없는 설정 키를 사용자 정의 값으로 저장하지 않고 거부",
+    );
     m.insert("opt.config.unset", "설정 값 제거");
 
     // === UI 메시지 ===
@@ -107,6 +163,7 @@ static MESSAGES: Lazy<HashMap<&'static str, &'static str>> = Lazy::new(|| {
     m.insert("status.processing_template", "템플릿 처리 중...");
     m.insert("status.completed", "완료되었습니다");
     m.insert("status.failed", "실패했습니다");
+    m.insert("status.files_processed", "{count}개 파일 처리됨");
 
     // === 프롬프트 메시지 ===
     m.insert("prompt.confirm", "계속하시겠습니까?");
@@ -187,6 +244,14 @@ static MESSAGES: Lazy<HashMap<&'static str, &'static str>> = Lazy::new(|| {
         "# 템플릿 사용하여 생성\ndox create -f content.md -o report.docx -t template.docx",
     );
 
+    // === 문서 생성 ===
+    m.insert("create.toc_title", "목차");
+    m.insert(
+        "create.toc_placeholder",
+        "목차를 업데이트하려면 F9 키를 누르세요",
+    );
+    m.insert("create.agenda_title", "목차");
+
     // === Generate 명령어 상세 도움말 ===
     m.insert("help.generate.providers", "AI 제공업체 설정:\n• OpenAI: OPENAI_API_KEY 환경변수 설정\n• Claude: ANTHROPIC_API_KEY 환경변수 설정");
     m.insert("help.generate.content_types", "콘텐츠 유형:\n• blog: 블로그 포스트\n• report: 보고서\n• summary: 요약\n• email: 이메일\n• proposal: 제안서\n• custom: 사용자 정의");
@@ -201,11 +266,3 @@ static MESSAGES: Lazy<HashMap<&'static str, &'static str>> = Lazy::new(|| {
 
     m
 });
-
-/// 메시지 가져오기
-pub fn get(key: &str) -> &'static str {
-    MESSAGES
-        .get(key)
-        .copied()
-        .unwrap_or("[translation missing]")
-}
@@ -0,0 +1,31 @@
+mod en;
+mod ko;
+
+/// 기본 언어 (번역이 없을 때 사용)
+pub const DEFAULT_LANGUAGE: &str = "ko";
+
+/// 지원하는 언어 목록
+pub const SUPPORTED_LANGUAGES: &[&str] = &["ko", "en"];
+
+/// 언어 코드를 지원 언어로 정규화 (예: "en-US" → "en")
+pub fn normalize(lang: &str) -> Option<&'static str> {
+    let primary = lang
+        .split(['-', '_', '.'])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+
+    SUPPORTED_LANGUAGES
+        .iter()
+        .copied()
+        .find(|supported| *supported == primary)
+}
+
+/// 지정한 언어의 메시지 가져오기 (번역이 없으면 `None`)
+pub fn get(lang: &str, key: &str) -> Option<&'static str> {
+    let table = match normalize(lang)? {
+        "en" => &en::MESSAGES,
+        _ => &ko::MESSAGES,
+    };
+    table.get(key).copied()
+}
@@ -21,7 +21,165 @@ pub fn get_language() -> String {
         .unwrap_or_else(|_| "ko".to_string())
 }
 
-/// 메시지 가져오기 (현재는 한글만 지원)
-pub fn t(key: &str) -> &'static str {
-    messages::get(key)
+/// 실행 시 사용할 언어 결정
+///
+/// 우선순위: CLI `--lang` > 설정 파일 > 로케일 환경변수(`LC_ALL`, `LC_MESSAGES`, `LANG`) > 한국어.
+/// 지원하지 않는 값은 건너뜁니다.
+pub fn resolve_language(cli: Option<&str>, config: Option<&str>) -> &'static str {
+    pick_language(cli, config, locale_from_env().as_deref())
+}
+
+/// Locale environment variable that applies, following POSIX precedence
+fn locale_from_env() -> Option<String> {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .into_iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.is_empty())
+}
+
+fn pick_language(cli: Option<&str>, config: Option<&str>, locale: Option<&str>) -> &'static str {
+    [cli, config, locale]
+        .into_iter()
+        .flatten()
+        .find_map(messages::normalize)
+        .unwrap_or(messages::DEFAULT_LANGUAGE)
+}
+
+/// 현재 언어의 메시지 가져오기
+///
+/// 현재 언어에 번역이 없으면 한국어 메시지를, 한국어에도 없으면 키를 그대로 반환합니다.
+pub fn t(key: &str) -> &str {
+    translate(&get_language(), key)
+}
+
+/// 지정한 언어의 메시지 가져오기 (`t`와 같은 대체 규칙 적용)
+pub fn translate<'a>(lang: &str, key: &'a str) -> &'a str {
+    messages::get(lang, key)
+        .or_else(|| messages::get(messages::DEFAULT_LANGUAGE, key))
+        .unwrap_or(key)
+}
+
+/// 현재 언어의 메시지에 인자를 채워 가져오기
+///
+/// 메시지의 `{name}` 자리표시자를 `args`의 값으로 바꿉니다. 인자가 없는
+/// 자리표시자는 그대로 남고, 메시지에 없는 인자는 무시됩니다.
+pub fn t_args(key: &str, args: &[(&str, &str)]) -> String {
+    format_message(t(key), args)
+}
+
+/// `{name}` 자리표시자 치환
+fn format_message(template: &str, args: &[(&str, &str)]) -> String {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+
+        let value = after.find('}').and_then(|end| {
+            let name = &after[..end];
+            args.iter()
+                .find(|(arg, _)| *arg == name)
+                .map(|(_, value)| (*value, end))
+        });
+
+        match value {
+            Some((value, end)) => {
+                output.push_str(value);
+                rest = &after[end + 1..];
+            }
+            None => {
+                output.push('{');
+                rest = after;
+            }
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_translate_per_language() {
+        let ko = translate("ko", "cmd.extract");
+        let en = translate("en", "cmd.extract");
+
+        assert_eq!(ko, "문서에서 텍스트 추출");
+        assert_eq!(en, "Extract text from documents");
+        assert_eq!(translate("en-US", "cmd.extract"), en);
+        assert_eq!(translate("ko_KR.UTF-8", "cmd.extract"), ko);
+    }
+
+    #[test]
+    fn test_translate_fallback() {
+        // Unsupported languages fall back to Korean
+        assert_eq!(translate("fr", "ui.done"), "완료");
+        // Unknown keys are returned unchanged
+        assert_eq!(translate("en", "no.such.key"), "no.such.key");
+    }
+
+    #[test]
+    fn test_t_follows_current_language() {
+        // The language is process-wide, so read it rather than change it
+        let lang = get_language();
+        assert_eq!(t("status.completed"), translate(&lang, "status.completed"));
+
+        assert_eq!(translate("en", "status.completed"), "Completed");
+        assert_eq!(translate("ko", "status.completed"), "완료되었습니다");
+    }
+
+    #[test]
+    fn test_language_priority() {
+        assert_eq!(
+            pick_language(Some("en"), Some("ko"), Some("ko_KR.UTF-8")),
+            "en"
+        );
+        assert_eq!(pick_language(None, Some("en"), Some("ko_KR.UTF-8")), "en");
+        assert_eq!(pick_language(None, None, Some("en_US.UTF-8")), "en");
+        assert_eq!(pick_language(None, None, None), "ko");
+
+        // Unsupported values and the C locale fall through
+        assert_eq!(pick_language(Some("fr"), None, Some("en_GB")), "en");
+        assert_eq!(pick_language(None, None, Some("C")), "ko");
+    }
+
+    #[test]
+    fn test_format_message_substitution() {
+        assert_eq!(
+            format_message(translate("ko", "status.files_processed"), &[("count", "3")]),
+            "3개 파일 처리됨"
+        );
+        assert_eq!(
+            format_message(translate("en", "status.files_processed"), &[("count", "3")]),
+            "3 files processed"
+        );
+        assert_eq!(
+            format_message("{path} → {path}", &[("path", "a.docx")]),
+            "a.docx → a.docx"
+        );
+    }
+
+    #[test]
+    fn test_format_message_missing_and_extra_args() {
+        // Placeholders without a value are left untouched
+        assert_eq!(
+            format_message("{count}개 중 {done}개 완료", &[("count", "5")]),
+            "5개 중 {done}개 완료"
+        );
+        // Unused arguments are ignored
+        assert_eq!(
+            format_message("{count}개", &[("count", "2"), ("unused", "x")]),
+            "2개"
+        );
+        // Unbalanced braces pass through
+        assert_eq!(format_message("{ {count", &[("count", "1")]), "{ {count");
+    }
+
+    #[test]
+    fn test_t_args_unknown_key() {
+        assert_eq!(t_args("no.such.key", &[("count", "1")]), "no.such.key");
+    }
 }
@@ -1,7 +1,13 @@
 use colored::Colorize;
+use std::path::PathBuf;
+use tracing::Dispatch;
+use tracing_appender::non_blocking::WorkerGuard;
 use tracing_subscriber::{
-    fmt::{self, format::FmtSpan},
-    EnvFilter,
+    fmt::{self, format::FmtSpan, MakeWriter},
+    layer::SubscriberExt,
+    registry::LookupSpan,
+    util::SubscriberInitExt,
+    EnvFilter, Layer,
 };
 
 /// Logging configuration
@@ -9,7 +15,7 @@ use tracing_subscriber::{
 pub struct LogConfig {
     /// Log level filter (trace, debug, info, warn, error)
     pub level: String,
-    /// Output format (plain, json, pretty)
+    /// Output format (plain, pretty, compact, json)
     pub format: LogFormat,
     /// Include file/line information
     pub include_location: bool,
@@ -19,6 +25,8 @@ pub struct LogConfig {
     pub include_timestamp: bool,
     /// Include span events
     pub span_events: FmtSpan,
+    /// Also append logs to this file, without colors
+    pub file: Option<PathBuf>,
 }
 
 impl Default for LogConfig {
@@ -30,6 +38,7 @@ impl Default for LogConfig {
             include_thread: false,
             include_timestamp: true,
             span_events: FmtSpan::CLOSE,
+            file: None,
         }
     }
 }
@@ -38,6 +47,8 @@ impl Default for LogConfig {
 pub enum LogFormat {
     Plain,
     Pretty,
+    /// Single-line human readable output
+    Compact,
     Json,
 }
 
@@ -51,6 +62,7 @@ impl LogConfig {
             include_thread: true,
             include_timestamp: true,
             span_events: FmtSpan::NEW | FmtSpan::CLOSE,
+            file: None,
         }
     }
 
@@ -63,21 +75,55 @@ impl LogConfig {
             include_thread: false,
             include_timestamp: false,
             span_events: FmtSpan::NONE,
+            file: None,
         }
     }
 }
 
+/// Keeps the file log writer running
+///
+/// Logs written to [`LogConfig::file`] go through a background thread; hold
+/// this until the program exits, and drop it before `std::process::exit` so
+/// buffered lines are flushed.
+#[must_use = "dropping the guard stops file logging"]
+pub struct LogGuard {
+    _file: Option<WorkerGuard>,
+}
+
 /// Initialize the logging system with the given configuration
-pub fn init_logging(config: LogConfig) -> anyhow::Result<()> {
+///
+/// Logs are written to stderr, keeping stdout for command output, and to
+/// [`LogConfig::file`] when set.
+pub fn init_logging(config: LogConfig) -> anyhow::Result<LogGuard> {
+    let (dispatch, guard) = build_dispatch(&config)?;
+    dispatch
+        .try_init()
+        .map_err(|e| anyhow::anyhow!("Failed to initialize logging: {}", e))?;
+    Ok(guard)
+}
+
+/// Build the subscriber for `config` without installing it
+fn build_dispatch(config: &LogConfig) -> anyhow::Result<(Dispatch, LogGuard)> {
     let env_filter = build_env_filter(&config.level)?;
 
-    match config.format {
-        LogFormat::Plain => init_plain_logging(config, env_filter),
-        LogFormat::Pretty => init_pretty_logging(config, env_filter),
-        LogFormat::Json => init_json_logging(config, env_filter),
+    let mut layers = vec![fmt_layer(config, std::io::stderr, true)];
+    let mut file_guard = None;
+    if let Some(path) = &config.file {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| anyhow::anyhow!("Failed to open log file {}: {}", path.display(), e))?;
+        let (writer, guard) = tracing_appender::non_blocking(file);
+        layers.push(fmt_layer(config, writer, false));
+        file_guard = Some(guard);
     }
 
-    Ok(())
+    let subscriber = tracing_subscriber::registry().with(layers).with(env_filter);
+    Ok((Dispatch::new(subscriber), LogGuard { _file: file_guard }))
 }
 
 /// Build environment filter from string
@@ -88,53 +134,32 @@ fn build_env_filter(level: &str) -> anyhow::Result<EnvFilter> {
         .map_err(|e| anyhow::anyhow!("Invalid log level: {}", e))
 }
 
-/// Initialize plain text logging
-fn init_plain_logging(config: LogConfig, filter: EnvFilter) {
-    tracing_subscriber::fmt()
-        .with_env_filter(filter)
-        .with_target(false)
+/// Format layer writing to `writer` in the configured format
+///
+/// Colors are only used for Pretty and Compact output, and never when `ansi`
+/// is false.
+fn fmt_layer<S, W>(config: &LogConfig, writer: W, ansi: bool) -> Box<dyn Layer<S> + Send + Sync>
+where
+    S: tracing::Subscriber + for<'a> LookupSpan<'a>,
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
+    let layer = fmt::layer()
+        .with_writer(writer)
         .with_thread_ids(config.include_thread)
         .with_thread_names(config.include_thread)
         .with_file(config.include_location)
         .with_line_number(config.include_location)
         .with_level(true)
-        .with_ansi(false)
-        .with_span_events(config.span_events)
-        .init();
-}
+        .with_span_events(config.span_events.clone());
 
-/// Initialize pretty colored logging
-fn init_pretty_logging(config: LogConfig, filter: EnvFilter) {
-    let format = fmt::format()
-        .with_level(true)
-        .with_target(false)
-        .with_thread_ids(config.include_thread)
-        .with_thread_names(config.include_thread)
-        .with_file(config.include_location)
-        .with_line_number(config.include_location);
-
-    tracing_subscriber::fmt()
-        .event_format(format)
-        .with_env_filter(filter)
-        .with_span_events(config.span_events)
-        .with_ansi(true)
-        .init();
-}
-
-/// Initialize JSON logging
-fn init_json_logging(config: LogConfig, filter: EnvFilter) {
-    // JSON formatting requires the json feature flag
-    // For now, use plain format with structured fields
-    tracing_subscriber::fmt()
-        .with_env_filter(filter)
-        .with_target(true)
-        .with_thread_ids(config.include_thread)
-        .with_thread_names(config.include_thread)
-        .with_file(config.include_location)
-        .with_line_number(config.include_location)
-        .with_level(true)
-        .with_span_events(config.span_events)
-        .init();
+    match config.format {
+        LogFormat::Plain => layer.with_target(false).with_ansi(false).boxed(),
+        LogFormat::Pretty => layer.with_target(false).with_ansi(ansi).boxed(),
+        LogFormat::Compact => layer.compact().with_target(false).with_ansi(ansi).boxed(),
+        // JSON formatting requires the json feature flag
+        // For now, use plain format with structured fields
+        LogFormat::Json => layer.with_target(true).with_ansi(false).boxed(),
+    }
 }
 
 /// Custom error reporter that formats errors with context
@@ -143,12 +168,29 @@ pub struct ErrorReporter;
 impl ErrorReporter {
     /// Report an error to the user with formatted output
     pub fn report(error: &crate::error::DoxError, verbose: bool) {
-        eprintln!();
-        eprintln!("{}", "Error:".red().bold());
+        eprint!("{}", Self::render(error, verbose));
+    }
+
+    /// The report printed by [`Self::report`]
+    ///
+    /// The suggestion from [`crate::error::DoxError::user_message`] is shown
+    /// in both modes, so verbose output still says how to fix the problem.
+    pub(crate) fn render(error: &crate::error::DoxError, verbose: bool) -> String {
+        use std::fmt::Write;
+
+        let mut out = String::new();
+        let user_msg = error.user_message();
+        let suggestion = |out: &mut String, line: &str| {
+            let _ = writeln!(out, "\n  {} {}", "💡".cyan(), line.cyan());
+        };
+
+        let _ = writeln!(out);
+        let _ = writeln!(out, "{}", "Error:".red().bold());
 
         // Show error code if available
         let code = error.code();
-        eprintln!(
+        let _ = writeln!(
+            out,
             "  {} {}",
             "Code:".yellow(),
             format!("{:?}", code).bright_black()
@@ -157,43 +199,72 @@ impl ErrorReporter {
         // Show main error message
         if verbose {
             // Show full error chain in verbose mode
-            eprintln!("  {} {}", "Message:".yellow(), error);
+            let _ = writeln!(out, "  {} {}", "Message:".yellow(), error);
+            for line in user_msg.lines().filter(|l| l.starts_with("Suggestion:")) {
+                suggestion(&mut out, line);
+            }
 
             // Show file/line if available through backtrace
             if std::env::var("RUST_BACKTRACE").is_ok() {
-                eprintln!("\n{}", "Stack trace:".yellow());
-                eprintln!("{:?}", error);
+                let _ = writeln!(out, "\n{}", "Stack trace:".yellow());
+                let _ = writeln!(out, "{:?}", error);
             }
         } else {
             // Show user-friendly message
-            let user_msg = error.user_message();
             for line in user_msg.lines() {
                 if line.starts_with("Suggestion:") {
-                    eprintln!("\n  {} {}", "💡".cyan(), line.cyan());
+                    suggestion(&mut out, line);
                 } else if !line.is_empty() {
-                    eprintln!("  {}", line);
+                    let _ = writeln!(out, "  {}", line);
                 }
             }
         }
 
         // Show recovery hint if error is recoverable
         if error.is_recoverable() {
-            eprintln!(
+            let _ = writeln!(
+                out,
                 "\n  {} This error may be temporary. Please try again.",
                 "↻".green()
             );
         }
 
-        eprintln!();
+        let _ = writeln!(out);
 
         // Show help text
         if !verbose {
-            eprintln!(
+            let _ = writeln!(
+                out,
                 "For more details, run with {} or set {}",
                 "--verbose".bright_blue(),
                 "RUST_LOG=debug".bright_blue()
             );
         }
+
+        out
+    }
+
+    /// Report an error as a single-line JSON object on stdout
+    ///
+    /// Used in JSON output mode so scripts can parse failures the same way
+    /// as results. `code` is set when the error is a [`crate::error::DoxError`].
+    pub fn report_json(error: &anyhow::Error) {
+        println!("{}", Self::error_json(error));
+    }
+
+    pub(crate) fn error_json(error: &anyhow::Error) -> serde_json::Value {
+        let code = error
+            .downcast_ref::<crate::error::DoxError>()
+            .map(|e| format!("{:?}", e.code()));
+        let causes: Vec<String> = error.chain().skip(1).map(|e| e.to_string()).collect();
+
+        serde_json::json!({
+            "error": {
+                "message": error.to_string(),
+                "code": code,
+                "causes": causes,
+            }
+        })
     }
 
     /// Report a generic error (non-DoxError)
@@ -302,4 +373,121 @@ mod tests {
         assert!(!config.include_location);
         assert!(!config.include_thread);
     }
+
+    #[test]
+    fn test_build_each_format() {
+        for format in [
+            LogFormat::Plain,
+            LogFormat::Pretty,
+            LogFormat::Compact,
+            LogFormat::Json,
+        ] {
+            let config = LogConfig {
+                format,
+                ..Default::default()
+            };
+            assert!(build_dispatch(&config).is_ok(), "{:?}", format);
+        }
+        assert!(build_dispatch(&LogConfig::verbose()).is_ok());
+        assert!(build_dispatch(&LogConfig::quiet()).is_ok());
+    }
+
+    #[test]
+    fn test_file_target_writes_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("logs").join("dox.log");
+        let config = LogConfig {
+            level: "info".to_string(),
+            format: LogFormat::Compact,
+            file: Some(path.clone()),
+            ..Default::default()
+        };
+
+        let (dispatch, guard) = build_dispatch(&config).unwrap();
+        tracing::dispatcher::with_default(&dispatch, || {
+            tracing::info!("first line");
+            tracing::debug!("filtered out");
+            tracing::warn!("second line");
+        });
+        drop(guard);
+
+        let log = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(log.lines().count(), 2, "{}", log);
+        assert!(log.contains("first line"));
+        assert!(log.contains("second line"));
+        // No color codes in the file
+        assert!(!log.contains('\u{1b}'));
+    }
+
+    #[test]
+    fn test_init_logging_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("dox.log");
+        let guard = init_logging(LogConfig {
+            file: Some(path.clone()),
+            ..LogConfig::quiet()
+        })
+        .unwrap();
+
+        tracing::error!("global subscriber");
+        assert!(init_logging(LogConfig::default()).is_err());
+        drop(guard);
+
+        assert!(std::fs::read_to_string(&path)
+            .unwrap()
+            .contains("global subscriber"));
+    }
+
+    #[test]
+    fn test_render_provider_error_hints() {
+        use crate::error::DoxError;
+
+        let cases = [
+            (
+                DoxError::from_http_status("openai", 401, "invalid api key"),
+                "dox config --set openai.api_key=<KEY>",
+            ),
+            (
+                DoxError::from_http_status("claude", 429, "rate_limit_error"),
+                "retry later",
+            ),
+            (
+                DoxError::Timeout {
+                    message: "Claude request timed out after 60s".to_string(),
+                },
+                "Retry later",
+            ),
+        ];
+
+        for (error, hint) in cases {
+            for verbose in [false, true] {
+                let report = ErrorReporter::render(&error, verbose);
+                assert!(report.contains(hint), "{:?} (verbose: {})", report, verbose);
+                assert!(report.contains(&format!("{:?}", error.code())));
+            }
+        }
+
+        // Only transient failures promise that retrying may help
+        let auth = DoxError::from_http_status("openai", 401, "invalid api key");
+        assert!(!ErrorReporter::render(&auth, false).contains("may be temporary"));
+        let limited = DoxError::from_http_status("openai", 429, "slow down");
+        assert!(ErrorReporter::render(&limited, true).contains("may be temporary"));
+    }
+
+    #[test]
+    fn test_error_json() {
+        let error = anyhow::Error::new(crate::error::DoxError::ValidationError {
+            field: "input".to_string(),
+            message: "bad input".to_string(),
+        })
+        .context("while loading");
+
+        let json = ErrorReporter::error_json(&error);
+        assert_eq!(json["error"]["message"], "while loading");
+        assert_eq!(json["error"]["code"], "ValidationError");
+        assert_eq!(json["error"]["causes"][0], error.root_cause().to_string());
+
+        let json = ErrorReporter::error_json(&anyhow::anyhow!("plain"));
+        assert!(json["error"]["code"].is_null());
+    }
 }
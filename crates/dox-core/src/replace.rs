@@ -54,7 +54,10 @@ impl SmartReplacer {
             language: "ko".to_string(),
             audience: "전문가".to_string(),
             tone: "정확한".to_string(),
-            context: self.context.clone(),
+            context: None, // Already part of the prompt
+            system_prompt: None,
+            examples: Vec::new(),
+            response_format: None,
             stream: false,
             provider_params: HashMap::new(),
         };
@@ -114,6 +117,9 @@ impl SmartReplacer {
             audience: "전문가".to_string(),
             tone: "분석적".to_string(),
             context: None,
+            system_prompt: None,
+            examples: Vec::new(),
+            response_format: None,
             stream: false,
             provider_params: HashMap::new(),
         };
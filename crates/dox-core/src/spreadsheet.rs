@@ -1,6 +1,8 @@
 //! Spreadsheet provider traits and common types.
 
+use crate::error::DoxError;
 use anyhow::Result;
+use chrono::{NaiveDate, NaiveDateTime};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::future::Future;
@@ -14,14 +16,57 @@ pub struct SheetId(pub String);
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct RangeRef(pub String);
 
+/// Number of columns in a worksheet; the last one is `XFD`
+pub const MAX_COLUMNS: u32 = 16_384;
+/// Number of rows in a worksheet
+pub const MAX_ROWS: u32 = 1_048_576;
+
 impl RangeRef {
     /// Creates a new RangeRef from a string
     pub fn new(range: impl Into<String>) -> Self {
         Self(range.into())
     }
 
+    /// A single cell from its 1-based column and row
+    ///
+    /// `RangeRef::cell(Some("Data"), 2, 3)` is `Data!B3`. Sheet names that
+    /// need it are quoted, as in `'Q1 Sales'!A1`. Fails for a column or row
+    /// of 0.
+    pub fn cell(sheet: Option<&str>, col: u32, row: u32) -> std::result::Result<Self, DoxError> {
+        Ok(Self::with_sheet(sheet, a1_cell(col, row)?))
+    }
+
+    /// A rectangular range from its 1-based `(column, row)` corners
+    ///
+    /// `RangeRef::range(Some("Data"), (1, 1), (4, 100))` is `Data!A1:D100`.
+    /// Fails for a column or row of 0.
+    pub fn range(
+        sheet: Option<&str>,
+        start: (u32, u32),
+        end: (u32, u32),
+    ) -> std::result::Result<Self, DoxError> {
+        let cells = format!("{}:{}", a1_cell(start.0, start.1)?, a1_cell(end.0, end.1)?);
+        Ok(Self::with_sheet(sheet, cells))
+    }
+
+    fn with_sheet(sheet: Option<&str>, cells: String) -> Self {
+        match sheet {
+            Some(sheet) => Self(format!("{}!{}", quote_sheet_name(sheet), cells)),
+            None => Self(cells),
+        }
+    }
+
     /// Parses the range into sheet name and cell range components
+    ///
+    /// Quoted sheet names are returned without their quotes.
     pub fn parse(&self) -> (Option<String>, String) {
+        if let Some(quoted) = self.0.strip_prefix('\'') {
+            if let Some(idx) = quoted.find("'!") {
+                let sheet = quoted[..idx].replace("''", "'");
+                return (Some(sheet), quoted[idx + 2..].to_string());
+            }
+        }
+
         if let Some(idx) = self.0.find('!') {
             let sheet = self.0[..idx].to_string();
             let range = self.0[idx + 1..].to_string();
@@ -30,6 +75,185 @@ impl RangeRef {
             (None, self.0.clone())
         }
     }
+
+    /// Check that the reference is well-formed A1 notation
+    ///
+    /// Accepts cells (`B3`), ranges (`A1:D100`), whole columns (`A:C`) and
+    /// rows (`1:5`), with or without `$` markers, and a sheet with no cells
+    /// (`Data!`) for the whole sheet. Defined names are not accepted.
+    pub fn validate(&self) -> std::result::Result<(), DoxError> {
+        let invalid = |message: String| Err(DoxError::validation("range", message));
+
+        if self.0.trim().is_empty() {
+            return invalid("Range reference is empty".to_string());
+        }
+
+        let (sheet, cells) = self.parse();
+        if let Some(sheet) = &sheet {
+            if sheet.is_empty() {
+                return invalid(format!("Sheet name is missing in '{}'", self.0));
+            }
+            if let Some(c) = sheet.chars().find(|c| INVALID_SHEET_CHARS.contains(c)) {
+                return invalid(format!("Sheet name '{}' cannot contain '{}'", sheet, c));
+            }
+            if sheet.chars().count() > MAX_SHEET_NAME_CHARS {
+                return invalid(format!(
+                    "Sheet name '{}' is longer than {} characters",
+                    sheet, MAX_SHEET_NAME_CHARS
+                ));
+            }
+            if cells.is_empty() {
+                return Ok(());
+            }
+        }
+
+        let (first, last) = cells.split_once(':').unwrap_or((&cells, &cells));
+        let first =
+            parse_a1_part(first).map_err(|message| DoxError::validation("range", message))?;
+        let last = parse_a1_part(last).map_err(|message| DoxError::validation("range", message))?;
+
+        match (first, last) {
+            ((Some(c1), r1), (Some(c2), r2)) if r1.is_some() == r2.is_some() => {
+                if c1 > c2 || r1 > r2 {
+                    let (start, end) = cells.split_once(':').unwrap_or_default();
+                    return invalid(format!(
+                        "Range {} is inverted; write it as {}:{}",
+                        cells, end, start
+                    ));
+                }
+                Ok(())
+            }
+            ((None, Some(r1)), (None, Some(r2))) => {
+                if r1 > r2 {
+                    return invalid(format!(
+                        "Row range {} is inverted; write it as {}:{}",
+                        cells, r2, r1
+                    ));
+                }
+                Ok(())
+            }
+            _ => invalid(format!(
+                "'{}' mixes cells, columns and rows; use A1, A1:D10, A:C or 1:5",
+                cells
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for RangeRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Characters Excel does not allow in sheet names
+pub const INVALID_SHEET_CHARS: &[char] = &['[', ']', ':', '*', '?', '/', '\\'];
+
+/// Longest sheet name Excel accepts, in characters
+pub const MAX_SHEET_NAME_CHARS: usize = 31;
+
+/// Column letters for a zero-based column index (0 is `A`, 27 is `AB`)
+///
+/// Indexes past the last column (`XFD`) still get letters; check against
+/// [`MAX_COLUMNS`] where that matters.
+pub fn index_to_column_letter(index: u32) -> String {
+    let mut letters = Vec::new();
+    let mut col = index as u64 + 1;
+    while col > 0 {
+        col -= 1;
+        letters.push(b'A' + (col % 26) as u8);
+        col /= 26;
+    }
+    letters.reverse();
+    String::from_utf8(letters).unwrap_or_default()
+}
+
+/// Zero-based column index for column letters such as `A`, `aa` or `XFD`
+///
+/// Returns `None` for anything that is not one to three letters within
+/// the sheet's columns.
+pub fn column_letter_to_index(letters: &str) -> Option<u32> {
+    if letters.is_empty() || letters.len() > 3 || !letters.chars().all(|c| c.is_ascii_alphabetic())
+    {
+        return None;
+    }
+
+    let col = letters.bytes().fold(0, |acc, c| {
+        acc * 26 + (c.to_ascii_uppercase() - b'A' + 1) as u32
+    });
+    (col <= MAX_COLUMNS).then(|| col - 1)
+}
+
+/// A1 notation for a 1-based column and row
+fn a1_cell(col: u32, row: u32) -> std::result::Result<String, DoxError> {
+    let (Some(index), true) = (col.checked_sub(1), row > 0) else {
+        return Err(DoxError::validation(
+            "range",
+            format!(
+                "Column {} and row {} must both be 1 or more; cells are numbered from 1",
+                col, row
+            ),
+        ));
+    };
+    Ok(format!("{}{}", index_to_column_letter(index), row))
+}
+
+/// Quote a sheet name unless it is made only of letters, digits and `_`
+fn quote_sheet_name(sheet: &str) -> String {
+    if !sheet.is_empty() && sheet.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        sheet.to_string()
+    } else {
+        format!("'{}'", sheet.replace('\'', "''"))
+    }
+}
+
+/// Parse one side of an A1 range into its 1-based column and row, either
+/// of which may be missing (`C` or `5`)
+fn parse_a1_part(part: &str) -> std::result::Result<(Option<u32>, Option<u32>), String> {
+    let malformed = || format!("'{}' is not an A1 cell reference", part);
+
+    let cleaned = part.trim().replace('$', "");
+    let split = cleaned
+        .find(|c: char| !c.is_ascii_alphabetic())
+        .unwrap_or(cleaned.len());
+    let (letters, digits) = cleaned.split_at(split);
+    if (letters.is_empty() && digits.is_empty()) || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return Err(malformed());
+    }
+
+    let col = if letters.is_empty() {
+        None
+    } else {
+        let letters = letters.to_ascii_uppercase();
+        // A long run of letters alone is more likely a name than a column
+        if letters.len() > 3 && digits.is_empty() {
+            return Err(malformed());
+        }
+        let Some(index) = column_letter_to_index(&letters) else {
+            return Err(format!(
+                "Column {} is beyond the last column {}",
+                letters,
+                index_to_column_letter(MAX_COLUMNS - 1)
+            ));
+        };
+        let col = index + 1;
+        Some(col)
+    };
+
+    let row = if digits.is_empty() {
+        None
+    } else {
+        let row = digits.parse::<u64>().map_err(|_| malformed())?;
+        if row == 0 || row > MAX_ROWS as u64 {
+            return Err(format!(
+                "Row {} is outside 1-{} in '{}'",
+                row, MAX_ROWS, part
+            ));
+        }
+        Some(row as u32)
+    };
+
+    Ok((col, row))
 }
 
 /// Represents a single cell in a spreadsheet
@@ -57,6 +281,70 @@ impl Cell {
             format: Some(format),
         }
     }
+
+    /// The cell's value with its type detected
+    pub fn typed_value(&self) -> CellValue {
+        CellValue::parse(&self.value)
+    }
+}
+
+impl From<CellValue> for Cell {
+    fn from(value: CellValue) -> Self {
+        Cell::new(value.to_string())
+    }
+}
+
+/// Typed cell value
+///
+/// Cells hold strings; this is how providers interpret them. Dates use ISO
+/// 8601 (`2024-03-15`, `2024-03-15T09:30:00`) in both directions so they
+/// survive a read and write round trip.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CellValue {
+    Empty,
+    Number(f64),
+    Bool(bool),
+    Date(NaiveDate),
+    DateTime(NaiveDateTime),
+    /// Formula including the leading `=`
+    Formula(String),
+    String(String),
+}
+
+impl CellValue {
+    /// Detect the type of a cell's string value
+    pub fn parse(value: &str) -> Self {
+        if value.is_empty() {
+            CellValue::Empty
+        } else if value.starts_with('=') {
+            CellValue::Formula(value.to_string())
+        } else if let Ok(number) = value.parse::<f64>() {
+            CellValue::Number(number)
+        } else if let Ok(bool_val) = value.parse::<bool>() {
+            CellValue::Bool(bool_val)
+        } else if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+            CellValue::Date(date)
+        } else if let Ok(datetime) = NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f")
+            .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f"))
+        {
+            CellValue::DateTime(datetime)
+        } else {
+            CellValue::String(value.to_string())
+        }
+    }
+}
+
+impl std::fmt::Display for CellValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CellValue::Empty => Ok(()),
+            CellValue::Number(number) => write!(f, "{}", number),
+            CellValue::Bool(bool_val) => write!(f, "{}", bool_val),
+            CellValue::Date(date) => write!(f, "{}", date.format("%Y-%m-%d")),
+            CellValue::DateTime(datetime) => write!(f, "{}", datetime.format("%Y-%m-%dT%H:%M:%S")),
+            CellValue::Formula(text) | CellValue::String(text) => f.write_str(text),
+        }
+    }
 }
 
 /// Cell formatting information
@@ -76,6 +364,15 @@ pub struct Sheet {
     pub id: Option<String>,
     pub row_count: usize,
     pub column_count: usize,
+    /// Whether the sheet is hidden from the tab bar
+    #[serde(default)]
+    pub hidden: bool,
+    /// Zero-based indexes of hidden rows, in ascending order
+    #[serde(default)]
+    pub hidden_rows: Vec<u32>,
+    /// Zero-based indexes of hidden columns, in ascending order
+    #[serde(default)]
+    pub hidden_columns: Vec<u32>,
 }
 
 /// Represents a rule to apply to spreadsheet data
@@ -107,6 +404,275 @@ pub struct ReadOptions {
     pub max_rows: Option<usize>,
     /// Maximum number of columns to read
     pub max_columns: Option<usize>,
+    /// Whether to copy each merged region's top-left value into the rest of
+    /// the region (otherwise those cells are empty)
+    #[serde(default)]
+    pub fill_merged: bool,
+    /// Whether to leave hidden rows and columns out of the result
+    #[serde(default)]
+    pub skip_hidden: bool,
+    /// Whether cells holding a formula come back as the formula, e.g.
+    /// `=SUM(A1:A3)`, instead of its result, so that writing the data back
+    /// keeps the formulas. Takes precedence over `evaluate_formulas`
+    #[serde(default)]
+    pub preserve_formulas: bool,
+}
+
+/// A comment or note attached to a cell
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CellComment {
+    /// Name of the sheet holding the cell
+    pub sheet: String,
+    /// Cell in A1 notation, e.g. `B3`
+    pub cell: String,
+    pub author: Option<String>,
+    pub text: String,
+    /// Whether this is a reply in a comment thread rather than its start
+    #[serde(default)]
+    pub reply: bool,
+}
+
+/// A rectangle of merged cells, in zero-based sheet coordinates
+///
+/// The top-left cell is the anchor that holds the region's value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MergedRange {
+    pub first_row: u32,
+    pub first_col: u32,
+    pub last_row: u32,
+    pub last_col: u32,
+}
+
+impl MergedRange {
+    /// Whether the cell at `(row, col)` lies inside the region
+    pub fn contains(&self, row: u32, col: u32) -> bool {
+        (self.first_row..=self.last_row).contains(&row)
+            && (self.first_col..=self.last_col).contains(&col)
+    }
+
+    /// Whether the region shares at least one cell with the zero-based
+    /// `(first_row, first_col, last_row, last_col)` rectangle
+    pub fn intersects(
+        &self,
+        (first_row, first_col, last_row, last_col): (u32, u32, u32, u32),
+    ) -> bool {
+        self.first_row <= last_row
+            && first_row <= self.last_row
+            && self.first_col <= last_col
+            && first_col <= self.last_col
+    }
+}
+
+/// Basic validation types supported
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum SimpleValidationType {
+    /// List of allowed values
+    List(Vec<String>),
+    /// Any integer number
+    WholeNumber,
+    /// Any decimal number
+    Decimal,
+    /// Integer between the two bounds, inclusive
+    WholeNumberBetween(i32, i32),
+    /// Decimal number between the two bounds, inclusive
+    DecimalBetween(f64, f64),
+    /// Custom formula (written to the file but not checked on write)
+    Custom(String),
+}
+
+impl SimpleValidationType {
+    /// Whether a non-blank cell value satisfies this rule
+    pub fn accepts(&self, value: &str) -> bool {
+        let value = value.trim();
+        match self {
+            SimpleValidationType::List(values) => values.iter().any(|v| v == value),
+            SimpleValidationType::WholeNumber => value.parse::<i64>().is_ok(),
+            SimpleValidationType::Decimal => value.parse::<f64>().is_ok(),
+            SimpleValidationType::WholeNumberBetween(min, max) => value
+                .parse::<i32>()
+                .is_ok_and(|n| (*min..=*max).contains(&n)),
+            SimpleValidationType::DecimalBetween(min, max) => value
+                .parse::<f64>()
+                .is_ok_and(|n| (*min..=*max).contains(&n)),
+            SimpleValidationType::Custom(_) => true,
+        }
+    }
+}
+
+/// Simplified validation configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimpleValidationConfig {
+    /// Target range for validation
+    pub range: RangeRef,
+    /// Type of validation
+    pub validation_type: SimpleValidationType,
+    /// Input message for user
+    pub input_message: Option<String>,
+    /// Allow blank cells
+    pub allow_blank: bool,
+}
+
+impl SimpleValidationConfig {
+    /// Whether a cell value satisfies this validation
+    pub fn accepts(&self, value: &str) -> bool {
+        if value.trim().is_empty() {
+            return self.allow_blank;
+        }
+        self.validation_type.accepts(value)
+    }
+}
+
+/// Basic format options that work with current rust_xlsxwriter
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BasicCellFormat {
+    /// Bold text
+    pub bold: Option<bool>,
+    /// Italic text
+    pub italic: Option<bool>,
+    /// Font size
+    pub font_size: Option<f64>,
+    /// Font name
+    pub font_name: Option<String>,
+    /// Font color as a hex string such as `#FFFFFF`
+    #[serde(default)]
+    pub font_color: Option<String>,
+    /// Fill color as a hex string such as `#1F4E78`
+    #[serde(default)]
+    pub background_color: Option<String>,
+    /// Excel number format such as `#,##0.00`
+    #[serde(default)]
+    pub num_format: Option<String>,
+}
+
+impl BasicCellFormat {
+    /// Combine with `other`, whose set options take precedence
+    pub fn merged(&self, other: &BasicCellFormat) -> BasicCellFormat {
+        BasicCellFormat {
+            bold: other.bold.or(self.bold),
+            italic: other.italic.or(self.italic),
+            font_size: other.font_size.or(self.font_size),
+            font_name: other.font_name.clone().or_else(|| self.font_name.clone()),
+            font_color: other.font_color.clone().or_else(|| self.font_color.clone()),
+            background_color: other
+                .background_color
+                .clone()
+                .or_else(|| self.background_color.clone()),
+            num_format: other.num_format.clone().or_else(|| self.num_format.clone()),
+        }
+    }
+}
+
+/// Format template for reusable styling
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FormatTemplate {
+    pub name: String,
+    pub description: String,
+    pub format: BasicCellFormat,
+}
+
+/// Predefined format templates
+impl FormatTemplate {
+    /// Header format template
+    pub fn header() -> Self {
+        Self {
+            name: "Header".to_string(),
+            description: "Bold header formatting".to_string(),
+            format: BasicCellFormat {
+                bold: Some(true),
+                italic: None,
+                font_size: Some(12.0),
+                font_name: Some("Arial".to_string()),
+                font_color: Some("#FFFFFF".to_string()),
+                background_color: Some("#1F4E78".to_string()),
+                num_format: None,
+            },
+        }
+    }
+
+    /// Normal data format template
+    pub fn data() -> Self {
+        Self {
+            name: "Data".to_string(),
+            description: "Standard data cell formatting".to_string(),
+            format: BasicCellFormat {
+                bold: Some(false),
+                italic: None,
+                font_size: Some(10.0),
+                font_name: Some("Arial".to_string()),
+                font_color: Some("#000000".to_string()),
+                background_color: None,
+                num_format: None,
+            },
+        }
+    }
+
+    /// Emphasis format template
+    pub fn emphasis() -> Self {
+        Self {
+            name: "Emphasis".to_string(),
+            description: "Italic emphasis formatting".to_string(),
+            format: BasicCellFormat {
+                bold: Some(false),
+                italic: Some(true),
+                font_size: Some(10.0),
+                font_name: Some("Arial".to_string()),
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Currency format template with two decimal places
+    pub fn currency() -> Self {
+        Self {
+            name: "Currency".to_string(),
+            description: "Currency amounts with thousands separators".to_string(),
+            format: BasicCellFormat {
+                num_format: Some("$#,##0.00".to_string()),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+/// Style theme containing multiple format templates
+///
+/// When used for a write, the `Header` template styles the first row and the
+/// `Data` template the rows below it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StyleTheme {
+    pub name: String,
+    pub description: String,
+    pub templates: Vec<FormatTemplate>,
+}
+
+impl StyleTheme {
+    /// Professional business theme
+    pub fn professional() -> Self {
+        Self {
+            name: "Professional".to_string(),
+            description: "Clean professional formatting".to_string(),
+            templates: vec![
+                FormatTemplate::header(),
+                FormatTemplate::data(),
+                FormatTemplate::emphasis(),
+            ],
+        }
+    }
+
+    /// Get a template by name
+    pub fn get_template(&self, name: &str) -> Option<&FormatTemplate> {
+        self.templates.iter().find(|t| t.name == name)
+    }
+}
+
+/// What a write does with values that break a validation rule
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ValidationMode {
+    /// Fail the write without touching the file
+    #[default]
+    Reject,
+    /// Log a warning and write the value anyway
+    Warn,
 }
 
 /// Options for writing to a spreadsheet
@@ -118,11 +684,201 @@ pub struct WriteOptions {
     pub auto_expand: bool,
     /// Whether to preserve existing formatting
     pub preserve_format: bool,
+    /// Data validation rules checked against the written cells and stored
+    /// in the file where the format supports it
+    #[serde(default)]
+    pub validations: Vec<SimpleValidationConfig>,
+    /// How values that break a rule in `validations` are handled
+    #[serde(default)]
+    pub validation_mode: ValidationMode,
+    /// Theme for the written table; see [`StyleTheme`]
+    #[serde(default)]
+    pub theme: Option<StyleTheme>,
+    /// Templates for data cells of individual columns (zero-based), layered
+    /// over the theme's `Data` template
+    #[serde(default)]
+    pub column_formats: HashMap<usize, FormatTemplate>,
+    /// Size each column to its longest value, header included
+    #[serde(default)]
+    pub auto_fit_columns: bool,
+    /// Freeze the rows above and columns left of this zero-based
+    /// `(row, column)` split; `(1, 0)` keeps the header row in view
+    #[serde(default)]
+    pub freeze_panes: Option<(u32, u16)>,
+    /// Protect the written sheet
+    #[serde(default)]
+    pub protect: Option<SheetProtection>,
+    /// Conditional formats added to the written sheet
+    #[serde(default)]
+    pub conditional_formats: Vec<ConditionalFormat>,
+    /// Sparklines drawn in cells of the written sheet
+    #[serde(default)]
+    pub sparklines: Vec<Sparkline>,
+}
+
+/// Comparison of a cell's value against fixed numbers
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum CellValueRule {
+    EqualTo(f64),
+    NotEqualTo(f64),
+    GreaterThan(f64),
+    GreaterThanOrEqualTo(f64),
+    LessThan(f64),
+    LessThanOrEqualTo(f64),
+    /// Between the two values, inclusive
+    Between(f64, f64),
+    NotBetween(f64, f64),
+}
+
+/// When a conditional format applies and how it is drawn
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ConditionalRule {
+    /// Format cells whose value matches the comparison
+    CellValue(CellValueRule),
+    /// Shade cells from `min_color` at the lowest value to `max_color` at
+    /// the highest; colors are hex strings such as `#F8696B`
+    ColorScale {
+        min_color: String,
+        max_color: String,
+    },
+    /// Draw a bar proportional to the value, in the given fill color or
+    /// Excel's default blue
+    DataBar { color: Option<String> },
+    /// Format the `n` highest values
+    Top(u16),
+    /// Format the `n` lowest values
+    Bottom(u16),
+}
+
+/// Conditional format for a range of cells
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConditionalFormat {
+    pub range: RangeRef,
+    pub rule: ConditionalRule,
+    /// Format for matching cells; color scales and data bars ignore it
+    #[serde(default)]
+    pub format: Option<BasicCellFormat>,
+}
+
+impl ConditionalFormat {
+    pub fn new(range: impl Into<String>, rule: ConditionalRule) -> Self {
+        Self {
+            range: RangeRef::new(range),
+            rule,
+            format: None,
+        }
+    }
+
+    /// Set the format used for matching cells
+    pub fn with_format(mut self, format: BasicCellFormat) -> Self {
+        self.format = Some(format);
+        self
+    }
+}
+
+/// How a sparkline draws its values
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SparklineType {
+    #[default]
+    Line,
+    Column,
+    /// Equal-height bars above or below the axis for positive and negative
+    /// values
+    WinLoss,
+}
+
+/// A small chart of a row or column of values, drawn inside one cell
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Sparkline {
+    /// Values to plot, one row or one column such as `B2:B13`; without a
+    /// sheet name they are read from the sheet the sparkline is on
+    pub data_range: RangeRef,
+    /// Cell the sparkline is drawn in, such as `C2`
+    pub location: RangeRef,
+    #[serde(default)]
+    pub sparkline_type: SparklineType,
+}
+
+impl Sparkline {
+    /// Line sparkline in `location` plotting `data_range`
+    pub fn new(data_range: impl Into<String>, location: impl Into<String>) -> Self {
+        Self {
+            data_range: RangeRef::new(data_range),
+            location: RangeRef::new(location),
+            sparkline_type: SparklineType::default(),
+        }
+    }
+
+    /// Set how the values are drawn
+    pub fn with_type(mut self, sparkline_type: SparklineType) -> Self {
+        self.sparkline_type = sparkline_type;
+        self
+    }
+}
+
+/// Actions that can stay available on a protected sheet
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProtectedAction {
+    SelectLockedCells,
+    SelectUnlockedCells,
+    FormatCells,
+    FormatColumns,
+    FormatRows,
+    InsertColumns,
+    InsertRows,
+    InsertLinks,
+    DeleteColumns,
+    DeleteRows,
+    Sort,
+    UseAutofilter,
+    UsePivotTables,
+    EditScenarios,
+    EditObjects,
+}
+
+/// Sheet protection applied when writing
+///
+/// The default matches Excel: no password, and users may only select cells.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SheetProtection {
+    /// Password required to unprotect the sheet
+    pub password: Option<String>,
+    /// Actions users can still perform while the sheet is protected
+    pub allowed: Vec<ProtectedAction>,
+}
+
+impl SheetProtection {
+    /// Protection with the default allowed actions and a password
+    pub fn with_password(password: impl Into<String>) -> Self {
+        Self {
+            password: Some(password.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Whether `action` remains available
+    pub fn allows(&self, action: ProtectedAction) -> bool {
+        self.allowed.contains(&action)
+    }
+}
+
+impl Default for SheetProtection {
+    fn default() -> Self {
+        Self {
+            password: None,
+            allowed: vec![
+                ProtectedAction::SelectLockedCells,
+                ProtectedAction::SelectUnlockedCells,
+            ],
+        }
+    }
 }
 
 /// Async trait for spreadsheet providers
 pub trait SpreadsheetProvider: Send + Sync {
     /// Reads data from a specified range
+    #[allow(clippy::type_complexity)]
     fn read_range(
         &self,
         sheet_id: &SheetId,
@@ -139,12 +895,59 @@ pub trait SpreadsheetProvider: Send + Sync {
         options: Option<WriteOptions>,
     ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + '_>>;
 
+    /// Writes several sheets at once, each from its first cell
+    ///
+    /// `writes` pairs a sheet name with its rows. Providers that rewrite the
+    /// whole file must save every sheet together, since separate
+    /// [`write_range`](Self::write_range) calls would each replace the last.
+    /// `options` apply to every sheet; validation and conditional format
+    /// ranges with a sheet name only apply to that sheet.
+    fn write_ranges(
+        &self,
+        sheet_id: &SheetId,
+        writes: Vec<(String, Vec<Vec<Cell>>)>,
+        options: Option<WriteOptions>,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + '_>>;
+
     /// Lists all sheets in a spreadsheet
     fn list_sheets(
         &self,
         sheet_id: &SheetId,
     ) -> Pin<Box<dyn Future<Output = Result<Vec<Sheet>>> + Send + '_>>;
 
+    /// Reads every sheet, keyed by sheet name
+    ///
+    /// The default lists the sheets and reads each one with
+    /// [`read_range`](Self::read_range); providers that can load the whole
+    /// spreadsheet in one go should override it.
+    #[allow(clippy::type_complexity)]
+    fn read_workbook(
+        &self,
+        sheet_id: &SheetId,
+        options: Option<ReadOptions>,
+    ) -> Pin<Box<dyn Future<Output = Result<HashMap<String, Vec<Vec<Cell>>>>> + Send + '_>> {
+        let sheet_id = sheet_id.clone();
+        Box::pin(async move {
+            let mut workbook = HashMap::new();
+            for sheet in self.list_sheets(&sheet_id).await? {
+                let range = RangeRef::new(format!("{}!", sheet.name));
+                let data = self.read_range(&sheet_id, &range, options.clone()).await?;
+                workbook.insert(sheet.name, data);
+            }
+            Ok(workbook)
+        })
+    }
+
+    /// Reads the comments and notes on cells of every sheet, in sheet order
+    ///
+    /// Providers that cannot read comments return none.
+    fn read_comments(
+        &self,
+        _sheet_id: &SheetId,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<CellComment>>> + Send + '_>> {
+        Box::pin(async { Ok(Vec::new()) })
+    }
+
     /// Applies a ruleset to a spreadsheet
     fn apply_rules(
         &self,
@@ -194,3 +997,123 @@ pub struct SpreadsheetConfig {
     /// Provider-specific configuration
     pub provider_config: HashMap<String, serde_json::Value>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_column_letters() {
+        let boundaries = [
+            (0, "A"),
+            (1, "B"),
+            (25, "Z"),
+            (26, "AA"),
+            (27, "AB"),
+            (51, "AZ"),
+            (52, "BA"),
+            (701, "ZZ"),
+            (702, "AAA"),
+            (16_383, "XFD"),
+        ];
+        for (index, letters) in boundaries {
+            assert_eq!(index_to_column_letter(index), letters);
+            assert_eq!(column_letter_to_index(letters), Some(index), "{}", letters);
+        }
+
+        // Every column survives the round trip
+        for index in 0..MAX_COLUMNS {
+            let letters = index_to_column_letter(index);
+            assert_eq!(column_letter_to_index(&letters), Some(index));
+        }
+
+        assert_eq!(column_letter_to_index("xfd"), Some(16_383));
+        assert_eq!(index_to_column_letter(MAX_COLUMNS), "XFE");
+        for invalid in ["", "XFE", "ZZZ", "AAAA", "A1", "$A", "Ä", " A"] {
+            assert_eq!(column_letter_to_index(invalid), None, "{:?}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_range_ref_constructors() {
+        assert_eq!(RangeRef::cell(None, 1, 1).unwrap().to_string(), "A1");
+        assert_eq!(
+            RangeRef::cell(Some("Data"), 28, 3).unwrap().to_string(),
+            "Data!AB3"
+        );
+        assert_eq!(
+            RangeRef::range(Some("Data"), (1, 1), (4, 100))
+                .unwrap()
+                .to_string(),
+            "Data!A1:D100"
+        );
+        assert_eq!(
+            RangeRef::cell(Some("Q1 Sales"), MAX_COLUMNS, MAX_ROWS)
+                .unwrap()
+                .to_string(),
+            "'Q1 Sales'!XFD1048576"
+        );
+        assert_eq!(
+            RangeRef::cell(Some("Bob's"), 2, 2).unwrap().to_string(),
+            "'Bob''s'!B2"
+        );
+
+        assert!(RangeRef::cell(None, 0, 1).is_err());
+        assert!(RangeRef::cell(Some("Data"), 1, 0).is_err());
+        assert!(RangeRef::range(None, (0, 1), (2, 2)).is_err());
+        assert!(RangeRef::range(None, (1, 1), (2, 0)).is_err());
+    }
+
+    #[test]
+    fn test_range_ref_round_trip() {
+        for range in [
+            RangeRef::cell(Some("매출"), 3, 7).unwrap(),
+            RangeRef::range(None, (2, 2), (26, 10)).unwrap(),
+            RangeRef::range(Some("Bob's data!"), (1, 1), (702, 5)).unwrap(),
+        ] {
+            let parsed = RangeRef::new(range.to_string());
+            assert_eq!(parsed, range);
+            assert!(parsed.validate().is_ok(), "{}", parsed);
+        }
+
+        assert_eq!(
+            RangeRef::range(Some("Bob's data!"), (1, 1), (702, 5))
+                .unwrap()
+                .parse(),
+            (Some("Bob's data!".to_string()), "A1:ZZ5".to_string())
+        );
+    }
+
+    #[test]
+    fn test_range_ref_validate_accepts() {
+        for range in [
+            "A1",
+            "$B$2:AA10",
+            "Data!A1:D100",
+            "Data!",
+            "A:C",
+            "2:5",
+            "XFD1048576",
+        ] {
+            assert!(RangeRef::new(range).validate().is_ok(), "{}", range);
+        }
+    }
+
+    #[test]
+    fn test_range_ref_validate_rejects() {
+        let message = |range: &str| RangeRef::new(range).validate().unwrap_err().to_string();
+
+        assert!(message("XFE1").contains("beyond the last column XFD"));
+        assert!(message("AAAA1").contains("beyond the last column XFD"));
+        assert!(message("A0").contains("Row 0 is outside"));
+        assert!(message("A1048577").contains("Row 1048577 is outside"));
+        assert!(message("D10:A1").contains("inverted; write it as A1:D10"));
+        assert!(message("5:2").contains("inverted; write it as 2:5"));
+        assert!(message("A1:C").contains("mixes cells"));
+        assert!(message("Sales").contains("not an A1 cell reference"));
+        assert!(message("A1B2").contains("not an A1 cell reference"));
+        assert!(message("").contains("empty"));
+        assert!(message("!A1").contains("Sheet name is missing"));
+        assert!(message("Q1/Q2!A1").contains("cannot contain '/'"));
+    }
+}
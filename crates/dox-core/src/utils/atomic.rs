@@ -0,0 +1,105 @@
+//! Replacing output files without leaving partial writes behind
+
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+/// Write `path` through a temporary file in the same directory
+///
+/// `write` gets the temporary file; once it succeeds the file is flushed to
+/// disk and renamed over `path` in one step. If `write` fails, `path` keeps
+/// its previous content and the temporary file is removed, so readers see
+/// either the old file or the complete new one.
+pub fn write_atomic<T, E>(path: &Path, write: impl FnOnce(File) -> Result<T, E>) -> Result<T, E>
+where
+    E: From<io::Error>,
+{
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+
+    let mut builder = tempfile::Builder::new();
+    builder.prefix(".dox-").suffix(".tmp");
+    // Temporary files are private by default; the output should get the
+    // permissions a plain create would have given it
+    let permissions = std::fs::metadata(path)
+        .map(|metadata| metadata.permissions())
+        .ok()
+        .or_else(default_permissions);
+    if let Some(permissions) = permissions {
+        builder.permissions(permissions);
+    }
+    let temp = builder.tempfile_in(dir)?;
+
+    let value = write(temp.as_file().try_clone()?)?;
+    temp.as_file().sync_all()?;
+    temp.persist(path).map_err(|e| e.error)?;
+    Ok(value)
+}
+
+#[cfg(unix)]
+fn default_permissions() -> Option<std::fs::Permissions> {
+    use std::os::unix::fs::PermissionsExt;
+    // Narrowed by the umask like any newly created file
+    Some(std::fs::Permissions::from_mode(0o666))
+}
+
+#[cfg(not(unix))]
+fn default_permissions() -> Option<std::fs::Permissions> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn entries(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = std::fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_write_atomic_replaces_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("report.docx");
+        std::fs::write(&path, "old").unwrap();
+
+        let written = write_atomic(&path, |mut file| -> io::Result<usize> {
+            file.write_all(b"new content")?;
+            Ok(11)
+        })
+        .unwrap();
+
+        assert_eq!(written, 11);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "new content");
+        assert_eq!(entries(dir.path()), vec!["report.docx"]);
+    }
+
+    #[test]
+    fn test_failed_write_leaves_destination_untouched() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("report.docx");
+        std::fs::write(&path, "old").unwrap();
+
+        // The write gets halfway before failing
+        let result = write_atomic(&path, |mut file| -> anyhow::Result<()> {
+            file.write_all(b"PK\x03\x04 partial")?;
+            anyhow::bail!("disk full")
+        });
+
+        assert_eq!(result.unwrap_err().to_string(), "disk full");
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "old");
+        assert_eq!(entries(dir.path()), vec!["report.docx"]);
+
+        // A failed first write creates nothing
+        let missing = dir.path().join("missing.docx");
+        assert!(write_atomic(&missing, |_| Err::<(), _>(io::Error::other("fail"))).is_err());
+        assert!(!missing.exists());
+    }
+}
@@ -3,6 +3,7 @@ use dirs;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
+use std::ops::RangeInclusive;
 use std::path::{Path, PathBuf};
 
 /// Application configuration
@@ -23,29 +24,27 @@ pub struct Config {
     #[serde(default)]
     pub claude: ClaudeConfig,
 
+    #[serde(default)]
+    pub local: LocalConfig,
+
+    /// Named overlays such as `[profiles.work]`, keyed like the base config
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub profiles: HashMap<String, serde_json::Value>,
+
     #[serde(flatten)]
     pub custom: HashMap<String, serde_json::Value>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct GlobalConfig {
     pub verbose: bool,
     pub quiet: bool,
-    pub lang: String,
+    /// Interface language; when unset the `LC_ALL`/`LANG` locale decides
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lang: Option<String>,
     pub no_color: bool,
 }
 
-impl Default for GlobalConfig {
-    fn default() -> Self {
-        GlobalConfig {
-            verbose: false,
-            quiet: false,
-            lang: "ko".to_string(), // 기본 언어를 한글로 설정
-            no_color: false,
-        }
-    }
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReplaceConfig {
     pub backup: bool,
@@ -71,6 +70,10 @@ pub struct GenerateConfig {
     pub max_tokens: usize,
     pub temperature: f32,
     pub content_type: String,
+    /// Directory of `<content_type>.<lang>.txt` prompt templates overriding
+    /// the built-in ones; defaults to `~/.config/dox/prompts`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt_dir: Option<String>,
 }
 
 impl Default for GenerateConfig {
@@ -80,6 +83,7 @@ impl Default for GenerateConfig {
             max_tokens: 2000,
             temperature: 0.7,
             content_type: "blog".to_string(),
+            prompt_dir: None,
         }
     }
 }
@@ -90,6 +94,12 @@ pub struct OpenAIConfig {
     pub model: Option<String>,
     pub max_tokens: Option<usize>,
     pub temperature: Option<f32>,
+    /// API endpoint replacing the public one, e.g. a compatible local server
+    pub base_url: Option<String>,
+    /// HTTP proxy for API requests
+    pub proxy: Option<String>,
+    /// PEM file with additional CA certificates to trust
+    pub ca_cert: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -98,6 +108,84 @@ pub struct ClaudeConfig {
     pub model: Option<String>,
     pub max_tokens: Option<usize>,
     pub temperature: Option<f32>,
+    /// API endpoint replacing the public one, e.g. a compatible local server
+    pub base_url: Option<String>,
+    /// HTTP proxy for API requests
+    pub proxy: Option<String>,
+    /// PEM file with additional CA certificates to trust
+    pub ca_cert: Option<String>,
+}
+
+/// Self-hosted model server (Ollama or another OpenAI-compatible API)
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct LocalConfig {
+    /// Chat completions endpoint, defaults to Ollama on localhost
+    pub base_url: Option<String>,
+    pub model: Option<String>,
+}
+
+/// Environment variable selecting a profile when `--profile` is not given
+pub const PROFILE_ENV_VAR: &str = "DOX_PROFILE";
+
+/// Allowed values for `max_tokens` in the generate, openai and claude sections
+pub const MAX_TOKENS_RANGE: RangeInclusive<usize> = 1..=100_000;
+
+/// Allowed values for `temperature` in the generate, openai and claude sections
+pub const TEMPERATURE_RANGE: RangeInclusive<f32> = 0.0..=2.0;
+
+/// Keys understood by [`Config::get`] and [`Config::set`], in display order
+pub const KNOWN_KEYS: &[&str] = &[
+    "global.verbose",
+    "global.quiet",
+    "global.lang",
+    "global.no_color",
+    "replace.backup",
+    "replace.recursive",
+    "replace.concurrent",
+    "replace.max_workers",
+    "generate.model",
+    "generate.max_tokens",
+    "generate.temperature",
+    "generate.content_type",
+    "generate.prompt_dir",
+    "openai.api_key",
+    "openai.model",
+    "openai.max_tokens",
+    "openai.temperature",
+    "openai.base_url",
+    "openai.proxy",
+    "openai.ca_cert",
+    "claude.api_key",
+    "claude.model",
+    "claude.max_tokens",
+    "claude.temperature",
+    "claude.base_url",
+    "claude.proxy",
+    "claude.ca_cert",
+    "local.base_url",
+    "local.model",
+];
+
+/// Largest edit distance at which an unknown key is taken for a typo of a
+/// known one
+const MAX_KEY_TYPO_DISTANCE: usize = 3;
+
+/// Number of single-character insertions, deletions and substitutions that
+/// turn `a` into `b`
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
 }
 
 impl Config {
@@ -125,13 +213,106 @@ impl Config {
     }
 
     /// Load configuration from file
+    ///
+    /// See [`Config::load_from`] for how environment variables are applied.
     pub fn load() -> Result<Self> {
         let path = Self::default_path()?;
         Self::load_from(&path)
     }
 
-    /// Load configuration from a specific path
+    /// Load configuration from a specific path, then apply environment overrides
+    ///
+    /// Values are resolved in this order, later ones winning: defaults, the
+    /// config file, the profile named by `DOX_PROFILE` if set, then
+    /// `DOX_<SECTION>_<KEY>` environment variables (see
+    /// [`Config::env_var_name`]). Command-line flags are applied by the CLI on
+    /// top of the loaded config and so win over all of these.
     pub fn load_from(path: &Path) -> Result<Self> {
+        Self::load_profile_from(path, None)
+    }
+
+    /// Like [`Config::load_from`], with `profile` taking precedence over `DOX_PROFILE`
+    pub fn load_profile_from(path: &Path, profile: Option<&str>) -> Result<Self> {
+        Self::load_with_env(path, profile, std::env::vars().collect())
+    }
+
+    /// Load from `path` with `vars` standing in for the process environment
+    fn load_with_env(
+        path: &Path,
+        profile: Option<&str>,
+        vars: Vec<(String, String)>,
+    ) -> Result<Self> {
+        let mut config = Self::read_from(path)?;
+
+        let env_profile = vars
+            .iter()
+            .find(|(key, _)| key == PROFILE_ENV_VAR)
+            .map(|(_, value)| value.as_str());
+        if let Some(name) = profile.or(env_profile).filter(|n| !n.is_empty()) {
+            config.apply_profile(name)?;
+        }
+
+        config.apply_env_overrides(vars)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Merge the named profile over the current values
+    ///
+    /// Profile values are validated like [`Config::set`]. Keys the profile
+    /// does not mention keep their base value.
+    pub fn apply_profile(&mut self, name: &str) -> Result<()> {
+        let profile = match self.profiles.get(name) {
+            Some(profile) => profile.clone(),
+            None => {
+                let mut available: Vec<&str> = self.profiles.keys().map(String::as_str).collect();
+                available.sort();
+                return Err(if available.is_empty() {
+                    anyhow::anyhow!("Unknown profile '{}': no profiles are defined", name)
+                } else {
+                    anyhow::anyhow!(
+                        "Unknown profile '{}', available profiles: {}",
+                        name,
+                        available.join(", ")
+                    )
+                });
+            }
+        };
+
+        let mut values = Vec::new();
+        Self::flatten_profile("", &profile, &mut values);
+        for (key, value) in values {
+            self.set(&key, &value)
+                .map_err(|e| anyhow::anyhow!("Invalid value in profile '{}': {}", name, e))?;
+        }
+
+        Ok(())
+    }
+
+    /// Collect `section.key` pairs from a profile table
+    fn flatten_profile(prefix: &str, value: &serde_json::Value, out: &mut Vec<(String, String)>) {
+        match value {
+            serde_json::Value::Object(map) => {
+                for (key, value) in map {
+                    let key = if prefix.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{}.{}", prefix, key)
+                    };
+                    Self::flatten_profile(&key, value, out);
+                }
+            }
+            serde_json::Value::String(s) => out.push((prefix.to_string(), s.clone())),
+            serde_json::Value::Null => {}
+            value => out.push((prefix.to_string(), value.to_string())),
+        }
+    }
+
+    /// Read configuration from a specific path without environment overrides
+    ///
+    /// Use this when the config is going to be saved back, so that values
+    /// coming from the environment are not written to the file.
+    pub fn read_from(path: &Path) -> Result<Self> {
         if !path.exists() {
             return Ok(Config::default());
         }
@@ -155,6 +336,35 @@ impl Config {
         Ok(config)
     }
 
+    /// Environment variable overriding `key`, e.g. `DOX_OPENAI_API_KEY` for `openai.api_key`
+    pub fn env_var_name(key: &str) -> String {
+        format!("DOX_{}", key.replace('.', "_").to_uppercase())
+    }
+
+    /// Override known keys from `DOX_<SECTION>_<KEY>` variables in `vars`
+    ///
+    /// Values are validated like [`Config::set`]; an invalid value is reported
+    /// with the name of the variable it came from.
+    pub fn apply_env_overrides(
+        &mut self,
+        vars: impl IntoIterator<Item = (String, String)>,
+    ) -> Result<()> {
+        let vars: HashMap<String, String> = vars
+            .into_iter()
+            .filter(|(name, _)| name.starts_with("DOX_"))
+            .collect();
+
+        for key in KNOWN_KEYS {
+            let name = Self::env_var_name(key);
+            if let Some(value) = vars.get(&name) {
+                self.set(key, value)
+                    .map_err(|e| anyhow::anyhow!("Invalid value in {}: {}", name, e))?;
+            }
+        }
+
+        Ok(())
+    }
+
     /// Save configuration to file
     pub fn save(&self) -> Result<()> {
         let path = Self::default_path()?;
@@ -191,7 +401,7 @@ impl Config {
         match parts.as_slice() {
             ["global", "verbose"] => Some(self.global.verbose.to_string()),
             ["global", "quiet"] => Some(self.global.quiet.to_string()),
-            ["global", "lang"] => Some(self.global.lang.clone()),
+            ["global", "lang"] => self.global.lang.clone(),
             ["global", "no_color"] => Some(self.global.no_color.to_string()),
 
             ["replace", "backup"] => Some(self.replace.backup.to_string()),
@@ -203,12 +413,26 @@ impl Config {
             ["generate", "max_tokens"] => Some(self.generate.max_tokens.to_string()),
             ["generate", "temperature"] => Some(self.generate.temperature.to_string()),
             ["generate", "content_type"] => Some(self.generate.content_type.clone()),
+            ["generate", "prompt_dir"] => self.generate.prompt_dir.clone(),
 
             ["openai", "api_key"] => self.openai.api_key.clone(),
             ["openai", "model"] => self.openai.model.clone(),
+            ["openai", "max_tokens"] => self.openai.max_tokens.map(|v| v.to_string()),
+            ["openai", "temperature"] => self.openai.temperature.map(|v| v.to_string()),
+            ["openai", "base_url"] => self.openai.base_url.clone(),
+            ["openai", "proxy"] => self.openai.proxy.clone(),
+            ["openai", "ca_cert"] => self.openai.ca_cert.clone(),
 
             ["claude", "api_key"] => self.claude.api_key.clone(),
             ["claude", "model"] => self.claude.model.clone(),
+            ["claude", "max_tokens"] => self.claude.max_tokens.map(|v| v.to_string()),
+            ["claude", "temperature"] => self.claude.temperature.map(|v| v.to_string()),
+            ["claude", "base_url"] => self.claude.base_url.clone(),
+            ["claude", "proxy"] => self.claude.proxy.clone(),
+            ["claude", "ca_cert"] => self.claude.ca_cert.clone(),
+
+            ["local", "base_url"] => self.local.base_url.clone(),
+            ["local", "model"] => self.local.model.clone(),
 
             _ => {
                 // Check custom values
@@ -220,7 +444,88 @@ impl Config {
         }
     }
 
+    /// Every known key with its current value, followed by custom entries
+    ///
+    /// Unset optional values are returned as empty strings. Secrets are
+    /// returned as-is; use [`Config::is_secret`] and [`Config::mask_secret`]
+    /// before showing them.
+    pub fn entries(&self) -> Vec<(String, String)> {
+        let mut entries: Vec<(String, String)> = KNOWN_KEYS
+            .iter()
+            .map(|key| (key.to_string(), self.get(key).unwrap_or_default()))
+            .collect();
+
+        let mut custom: Vec<&String> = self.custom.keys().collect();
+        custom.sort();
+        entries.extend(
+            custom
+                .into_iter()
+                .map(|key| (key.clone(), self.get(key).unwrap_or_default())),
+        );
+
+        entries
+    }
+
+    /// Whether the value stored under `key` should be masked when displayed
+    pub fn is_secret(key: &str) -> bool {
+        key.rsplit('.').next().is_some_and(|name| {
+            let name = name.to_lowercase();
+            name.contains("api_key")
+                || name.contains("secret")
+                || (name.contains("token") && name != "max_tokens")
+        })
+    }
+
+    /// Mask a secret, keeping only a short prefix of long values
+    pub fn mask_secret(value: &str) -> String {
+        match value.char_indices().nth(8) {
+            Some((end, _)) => format!("{}***", &value[..end]),
+            None if value.is_empty() => String::new(),
+            None => "***".to_string(),
+        }
+    }
+
+    /// The known key closest to `key` when `key` looks like a misspelling of it
+    ///
+    /// Returns `None` for known keys and for keys unlike any known one,
+    /// which are taken to be intentional custom entries.
+    pub fn suggest_key(key: &str) -> Option<&'static str> {
+        if KNOWN_KEYS.contains(&key) {
+            return None;
+        }
+
+        KNOWN_KEYS
+            .iter()
+            .map(|known| (edit_distance(key, known), *known))
+            .filter(|(distance, _)| *distance <= MAX_KEY_TYPO_DISTANCE)
+            .min_by_key(|(distance, _)| *distance)
+            .map(|(_, known)| known)
+    }
+
+    /// Set a known configuration value, rejecting custom keys
+    ///
+    /// Unlike [`Config::set`], a key outside [`KNOWN_KEYS`] is an error,
+    /// naming the closest known key when there is one.
+    pub fn set_strict(&mut self, key: &str, value: &str) -> Result<()> {
+        if !KNOWN_KEYS.contains(&key) {
+            return Err(match Self::suggest_key(key) {
+                Some(known) => anyhow::anyhow!(
+                    "Unknown configuration key '{}'; did you mean '{}'?",
+                    key,
+                    known
+                ),
+                None => anyhow::anyhow!("Unknown configuration key '{}'", key),
+            });
+        }
+
+        self.set(key, value)
+    }
+
     /// Set a configuration value by key
+    ///
+    /// Keys outside [`KNOWN_KEYS`] are stored as custom values; see
+    /// [`Config::suggest_key`] to catch typos and [`Config::set_strict`] to
+    /// refuse them.
     pub fn set(&mut self, key: &str, value: &str) -> Result<()> {
         // Validate the field before setting
         self.validate_field(key, value)?;
@@ -230,7 +535,7 @@ impl Config {
         match parts.as_slice() {
             ["global", "verbose"] => self.global.verbose = value.parse()?,
             ["global", "quiet"] => self.global.quiet = value.parse()?,
-            ["global", "lang"] => self.global.lang = value.to_string(),
+            ["global", "lang"] => self.global.lang = Some(value.to_string()),
             ["global", "no_color"] => self.global.no_color = value.parse()?,
 
             ["replace", "backup"] => self.replace.backup = value.parse()?,
@@ -242,12 +547,26 @@ impl Config {
             ["generate", "max_tokens"] => self.generate.max_tokens = value.parse()?,
             ["generate", "temperature"] => self.generate.temperature = value.parse()?,
             ["generate", "content_type"] => self.generate.content_type = value.to_string(),
+            ["generate", "prompt_dir"] => self.generate.prompt_dir = Some(value.to_string()),
 
             ["openai", "api_key"] => self.openai.api_key = Some(value.to_string()),
             ["openai", "model"] => self.openai.model = Some(value.to_string()),
+            ["openai", "max_tokens"] => self.openai.max_tokens = Some(value.parse()?),
+            ["openai", "temperature"] => self.openai.temperature = Some(value.parse()?),
+            ["openai", "base_url"] => self.openai.base_url = Some(value.to_string()),
+            ["openai", "proxy"] => self.openai.proxy = Some(value.to_string()),
+            ["openai", "ca_cert"] => self.openai.ca_cert = Some(value.to_string()),
 
             ["claude", "api_key"] => self.claude.api_key = Some(value.to_string()),
             ["claude", "model"] => self.claude.model = Some(value.to_string()),
+            ["claude", "max_tokens"] => self.claude.max_tokens = Some(value.parse()?),
+            ["claude", "temperature"] => self.claude.temperature = Some(value.parse()?),
+            ["claude", "base_url"] => self.claude.base_url = Some(value.to_string()),
+            ["claude", "proxy"] => self.claude.proxy = Some(value.to_string()),
+            ["claude", "ca_cert"] => self.claude.ca_cert = Some(value.to_string()),
+
+            ["local", "base_url"] => self.local.base_url = Some(value.to_string()),
+            ["local", "model"] => self.local.model = Some(value.to_string()),
 
             _ => {
                 // Set as custom value
@@ -266,11 +585,27 @@ impl Config {
         let parts: Vec<&str> = key.split('.').collect();
 
         match parts.as_slice() {
+            ["global", "lang"] => self.global.lang = None,
+            ["generate", "prompt_dir"] => self.generate.prompt_dir = None,
+
             ["openai", "api_key"] => self.openai.api_key = None,
             ["openai", "model"] => self.openai.model = None,
+            ["openai", "max_tokens"] => self.openai.max_tokens = None,
+            ["openai", "temperature"] => self.openai.temperature = None,
+            ["openai", "base_url"] => self.openai.base_url = None,
+            ["openai", "proxy"] => self.openai.proxy = None,
+            ["openai", "ca_cert"] => self.openai.ca_cert = None,
 
             ["claude", "api_key"] => self.claude.api_key = None,
             ["claude", "model"] => self.claude.model = None,
+            ["claude", "max_tokens"] => self.claude.max_tokens = None,
+            ["claude", "temperature"] => self.claude.temperature = None,
+            ["claude", "base_url"] => self.claude.base_url = None,
+            ["claude", "proxy"] => self.claude.proxy = None,
+            ["claude", "ca_cert"] => self.claude.ca_cert = None,
+
+            ["local", "base_url"] => self.local.base_url = None,
+            ["local", "model"] => self.local.model = None,
 
             _ => {
                 self.custom.remove(key);
@@ -287,6 +622,7 @@ impl Config {
         self.validate_generate()?;
         self.validate_openai()?;
         self.validate_claude()?;
+        self.validate_local()?;
         Ok(())
     }
 
@@ -326,29 +662,17 @@ impl Config {
                     ));
                 }
             }
-            ["generate", "max_tokens"] => {
-                let tokens: u32 = value
-                    .parse()
-                    .map_err(|_| anyhow::anyhow!("'{}' must be a number, got '{}'", key, value))?;
-                if !(1..=10000).contains(&tokens) {
-                    return Err(anyhow::anyhow!(
-                        "'{}' must be between 1 and 10000, got {}",
-                        key,
-                        tokens
-                    ));
-                }
+            ["generate" | "openai" | "claude", "max_tokens"] => {
+                let tokens: usize = value.parse().map_err(|_| {
+                    anyhow::anyhow!("'{}' must be a whole number, got '{}'", key, value)
+                })?;
+                Self::check_max_tokens(key, tokens)?;
             }
-            ["generate", "temperature"] => {
+            ["generate" | "openai" | "claude", "temperature"] => {
                 let temp: f32 = value
                     .parse()
                     .map_err(|_| anyhow::anyhow!("'{}' must be a number, got '{}'", key, value))?;
-                if !(0.0..=2.0).contains(&temp) {
-                    return Err(anyhow::anyhow!(
-                        "'{}' must be between 0.0 and 2.0, got {}",
-                        key,
-                        temp
-                    ));
-                }
+                Self::check_temperature(key, temp)?;
             }
             ["generate", "model"] => {
                 let valid_models = ["gpt-3.5-turbo", "gpt-4", "gpt-4-turbo"];
@@ -372,7 +696,15 @@ impl Config {
                     ));
                 }
             }
-            ["openai", "model"] | ["claude", "model"] => {
+            ["openai" | "claude", "base_url" | "proxy"] | ["local", "base_url"] => {
+                Self::check_url(key, value)?
+            }
+            ["openai" | "claude", "ca_cert"] | ["generate", "prompt_dir"] => {
+                if value.is_empty() {
+                    return Err(anyhow::anyhow!("'{}' cannot be empty", key));
+                }
+            }
+            ["openai" | "claude" | "local", "model"] => {
                 if value.is_empty() {
                     return Err(anyhow::anyhow!("'{}' cannot be empty", key));
                 }
@@ -392,11 +724,10 @@ impl Config {
         if self.global.verbose && self.global.quiet {
             return Err(anyhow::anyhow!("verbose and quiet cannot both be true"));
         }
-        if !["ko", "en"].contains(&self.global.lang.as_str()) {
-            return Err(anyhow::anyhow!(
-                "lang must be 'ko' or 'en', got '{}'",
-                self.global.lang
-            ));
+        if let Some(lang) = &self.global.lang {
+            if !["ko", "en"].contains(&lang.as_str()) {
+                return Err(anyhow::anyhow!("lang must be 'ko' or 'en', got '{}'", lang));
+            }
         }
         Ok(())
     }
@@ -412,18 +743,8 @@ impl Config {
     }
 
     fn validate_generate(&self) -> Result<()> {
-        if !(1..=10000).contains(&self.generate.max_tokens) {
-            return Err(anyhow::anyhow!(
-                "max_tokens must be between 1 and 10000, got {}",
-                self.generate.max_tokens
-            ));
-        }
-        if !(0.0..=2.0).contains(&self.generate.temperature) {
-            return Err(anyhow::anyhow!(
-                "temperature must be between 0.0 and 2.0, got {}",
-                self.generate.temperature
-            ));
-        }
+        Self::check_max_tokens("generate.max_tokens", self.generate.max_tokens)?;
+        Self::check_temperature("generate.temperature", self.generate.temperature)?;
         let valid_models = ["gpt-3.5-turbo", "gpt-4", "gpt-4-turbo"];
         if !valid_models.contains(&self.generate.model.as_str()) {
             return Err(anyhow::anyhow!(
@@ -449,6 +770,18 @@ impl Config {
                 return Err(anyhow::anyhow!("openai.model cannot be empty"));
             }
         }
+        if let Some(max_tokens) = self.openai.max_tokens {
+            Self::check_max_tokens("openai.max_tokens", max_tokens)?;
+        }
+        if let Some(temperature) = self.openai.temperature {
+            Self::check_temperature("openai.temperature", temperature)?;
+        }
+        if let Some(base_url) = &self.openai.base_url {
+            Self::check_url("openai.base_url", base_url)?;
+        }
+        if let Some(proxy) = &self.openai.proxy {
+            Self::check_url("openai.proxy", proxy)?;
+        }
         Ok(())
     }
 
@@ -458,192 +791,320 @@ impl Config {
                 return Err(anyhow::anyhow!("claude.model cannot be empty"));
             }
         }
+        if let Some(max_tokens) = self.claude.max_tokens {
+            Self::check_max_tokens("claude.max_tokens", max_tokens)?;
+        }
+        if let Some(temperature) = self.claude.temperature {
+            Self::check_temperature("claude.temperature", temperature)?;
+        }
+        if let Some(base_url) = &self.claude.base_url {
+            Self::check_url("claude.base_url", base_url)?;
+        }
+        if let Some(proxy) = &self.claude.proxy {
+            Self::check_url("claude.proxy", proxy)?;
+        }
         Ok(())
     }
 
-    /// Display the configuration in a readable format
-    pub fn display(&self) -> String {
-        toml::to_string_pretty(self).unwrap_or_else(|_| "Failed to display config".to_string())
-    }
-
-    /// Display the configuration with colors and better formatting
-    pub fn display_colored(&self) -> String {
-        use colored::*;
-
-        let mut output = String::new();
-
-        // Global settings
-        output.push_str(&format!("{}\n", "[global]".blue().bold()));
-        output.push_str(&format!(
-            "  {} = {}\n",
-            "verbose".green(),
-            format!("{}", self.global.verbose).yellow()
-        ));
-        output.push_str(&format!(
-            "  {} = {}\n",
-            "quiet".green(),
-            format!("{}", self.global.quiet).yellow()
-        ));
-        output.push_str(&format!(
-            "  {} = {}\n",
-            "lang".green(),
-            format!("\"{}\"", self.global.lang).yellow()
-        ));
-        output.push_str(&format!(
-            "  {} = {}\n",
-            "no_color".green(),
-            format!("{}", self.global.no_color).yellow()
-        ));
-        output.push('\n');
-
-        // Replace settings
-        output.push_str(&format!("{}\n", "[replace]".blue().bold()));
-        output.push_str(&format!(
-            "  {} = {}\n",
-            "backup".green(),
-            format!("{}", self.replace.backup).yellow()
-        ));
-        output.push_str(&format!(
-            "  {} = {}\n",
-            "recursive".green(),
-            format!("{}", self.replace.recursive).yellow()
-        ));
-        output.push_str(&format!(
-            "  {} = {}\n",
-            "concurrent".green(),
-            format!("{}", self.replace.concurrent).yellow()
-        ));
-        output.push_str(&format!(
-            "  {} = {}\n",
-            "max_workers".green(),
-            format!("{}", self.replace.max_workers).yellow()
-        ));
-        output.push('\n');
-
-        // Generate settings
-        output.push_str(&format!("{}\n", "[generate]".blue().bold()));
-        output.push_str(&format!(
-            "  {} = {}\n",
-            "model".green(),
-            format!("\"{}\"", self.generate.model).yellow()
-        ));
-        output.push_str(&format!(
-            "  {} = {}\n",
-            "max_tokens".green(),
-            format!("{}", self.generate.max_tokens).yellow()
-        ));
-        output.push_str(&format!(
-            "  {} = {}\n",
-            "temperature".green(),
-            format!("{}", self.generate.temperature).yellow()
-        ));
-        output.push_str(&format!(
-            "  {} = {}\n",
-            "content_type".green(),
-            format!("\"{}\"", self.generate.content_type).yellow()
-        ));
-        output.push('\n');
-
-        // OpenAI settings
-        output.push_str(&format!("{}\n", "[openai]".blue().bold()));
-        if let Some(ref api_key) = self.openai.api_key {
-            let masked_key = if api_key.len() > 8 {
-                format!("{}***", &api_key[..8])
-            } else {
-                "***".to_string()
-            };
-            output.push_str(&format!(
-                "  {} = {}\n",
-                "api_key".green(),
-                format!("\"{}\"", masked_key).yellow()
-            ));
-        } else {
-            output.push_str(&format!("  {} = {}\n", "api_key".green(), "null".red()));
+    fn validate_local(&self) -> Result<()> {
+        if let Some(model) = &self.local.model {
+            if model.is_empty() {
+                return Err(anyhow::anyhow!("local.model cannot be empty"));
+            }
         }
-
-        if let Some(ref model) = self.openai.model {
-            output.push_str(&format!(
-                "  {} = {}\n",
-                "model".green(),
-                format!("\"{}\"", model).yellow()
-            ));
-        } else {
-            output.push_str(&format!("  {} = {}\n", "model".green(), "null".red()));
+        if let Some(base_url) = &self.local.base_url {
+            Self::check_url("local.base_url", base_url)?;
         }
+        Ok(())
+    }
 
-        if let Some(max_tokens) = self.openai.max_tokens {
-            output.push_str(&format!(
-                "  {} = {}\n",
-                "max_tokens".green(),
-                format!("{}", max_tokens).yellow()
+    fn check_max_tokens(key: &str, tokens: usize) -> Result<()> {
+        if !MAX_TOKENS_RANGE.contains(&tokens) {
+            return Err(anyhow::anyhow!(
+                "'{}' must be between {} and {}, got {}",
+                key,
+                MAX_TOKENS_RANGE.start(),
+                MAX_TOKENS_RANGE.end(),
+                tokens
             ));
         }
+        Ok(())
+    }
 
-        if let Some(temperature) = self.openai.temperature {
-            output.push_str(&format!(
-                "  {} = {}\n",
-                "temperature".green(),
-                format!("{}", temperature).yellow()
-            ));
-        }
-        output.push('\n');
-
-        // Claude settings
-        output.push_str(&format!("{}\n", "[claude]".blue().bold()));
-        if let Some(ref api_key) = self.claude.api_key {
-            let masked_key = if api_key.len() > 8 {
-                format!("{}***", &api_key[..8])
-            } else {
-                "***".to_string()
-            };
-            output.push_str(&format!(
-                "  {} = {}\n",
-                "api_key".green(),
-                format!("\"{}\"", masked_key).yellow()
+    fn check_url(key: &str, url: &str) -> Result<()> {
+        if !(url.starts_with("http://") || url.starts_with("https://")) {
+            return Err(anyhow::anyhow!(
+                "'{}' must be an http:// or https:// URL, got '{}'",
+                key,
+                url
             ));
-        } else {
-            output.push_str(&format!("  {} = {}\n", "api_key".green(), "null".red()));
         }
+        Ok(())
+    }
 
-        if let Some(ref model) = self.claude.model {
-            output.push_str(&format!(
-                "  {} = {}\n",
-                "model".green(),
-                format!("\"{}\"", model).yellow()
+    fn check_temperature(key: &str, temperature: f32) -> Result<()> {
+        if !TEMPERATURE_RANGE.contains(&temperature) {
+            return Err(anyhow::anyhow!(
+                "'{}' must be between {:.1} and {:.1}, got {}",
+                key,
+                TEMPERATURE_RANGE.start(),
+                TEMPERATURE_RANGE.end(),
+                temperature
             ));
-        } else {
-            output.push_str(&format!("  {} = {}\n", "model".green(), "null".red()));
         }
+        Ok(())
+    }
+}
 
-        if let Some(max_tokens) = self.claude.max_tokens {
-            output.push_str(&format!(
-                "  {} = {}\n",
-                "max_tokens".green(),
-                format!("{}", max_tokens).yellow()
-            ));
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        if let Some(temperature) = self.claude.temperature {
-            output.push_str(&format!(
-                "  {} = {}\n",
-                "temperature".green(),
-                format!("{}", temperature).yellow()
-            ));
+    #[test]
+    fn test_entries_include_all_known_keys() {
+        let mut config = Config::default();
+        config.set("openai.api_key", "sk-1234567890abcdef").unwrap();
+        config.set("team.name", "docs").unwrap();
+
+        let entries = config.entries();
+        let keys: Vec<&str> = entries.iter().map(|(key, _)| key.as_str()).collect();
+
+        for key in KNOWN_KEYS {
+            assert!(keys.contains(key), "missing {}", key);
         }
+        assert_eq!(keys.last(), Some(&"team.name"));
 
-        // Custom settings
-        if !self.custom.is_empty() {
-            output.push('\n');
-            output.push_str(&format!("{}\n", "[custom]".blue().bold()));
-            for (key, value) in &self.custom {
-                let value_str = match value {
-                    serde_json::Value::String(s) => format!("\"{}\"", s),
-                    v => v.to_string(),
-                };
-                output.push_str(&format!("  {} = {}\n", key.green(), value_str.yellow()));
-            }
+        let value = |key: &str| {
+            entries
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.as_str())
+        };
+        // Unset, so the locale environment decides
+        assert_eq!(value("global.lang"), Some(""));
+        assert_eq!(value("replace.max_workers"), Some("4"));
+        assert_eq!(value("claude.api_key"), Some(""));
+        assert_eq!(value("openai.api_key"), Some("sk-1234567890abcdef"));
+        assert_eq!(value("team.name"), Some("docs"));
+    }
+
+    #[test]
+    fn test_env_overrides() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        let mut file_config = Config::default();
+        file_config.set("generate.model", "gpt-4").unwrap();
+        file_config.set("replace.max_workers", "8").unwrap();
+        file_config.save_to(&path).unwrap();
+
+        let mut config = Config::read_from(&path).unwrap();
+        config
+            .apply_env_overrides([
+                ("DOX_GENERATE_MODEL".to_string(), "gpt-4-turbo".to_string()),
+                ("DOX_OPENAI_API_KEY".to_string(), "sk-env".to_string()),
+                ("OPENAI_API_KEY".to_string(), "ignored".to_string()),
+            ])
+            .unwrap();
+
+        assert_eq!(
+            Config::env_var_name("global.no_color"),
+            "DOX_GLOBAL_NO_COLOR"
+        );
+        assert_eq!(config.generate.model, "gpt-4-turbo");
+        assert_eq!(config.openai.api_key.as_deref(), Some("sk-env"));
+        // Keys without a variable keep their file value
+        assert_eq!(config.replace.max_workers, 8);
+
+        let err = config
+            .apply_env_overrides([("DOX_REPLACE_MAX_WORKERS".to_string(), "100".to_string())])
+            .unwrap_err();
+        assert!(err.to_string().contains("DOX_REPLACE_MAX_WORKERS"));
+    }
+
+    #[test]
+    fn test_load_from_reads_environment() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        Config::default().save_to(&path).unwrap();
+
+        let vars = vec![(
+            "DOX_GENERATE_CONTENT_TYPE".to_string(),
+            "report".to_string(),
+        )];
+        let loaded = Config::load_with_env(&path, None, vars).unwrap();
+
+        assert_eq!(loaded.generate.content_type, "report");
+        assert_eq!(
+            Config::read_from(&path).unwrap().generate.content_type,
+            "blog"
+        );
+    }
+
+    #[test]
+    fn test_unknown_key_suggestions() {
+        assert_eq!(
+            Config::suggest_key("generate.temprature"),
+            Some("generate.temperature")
+        );
+        assert_eq!(Config::suggest_key("openai.apikey"), Some("openai.api_key"));
+        assert_eq!(Config::suggest_key("generate.temperature"), None);
+        assert_eq!(Config::suggest_key("team.name"), None);
+
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("같음", "같음"), 0);
+    }
+
+    #[test]
+    fn test_set_strict_rejects_unknown_keys() {
+        let mut config = Config::default();
+
+        let err = config.set_strict("generate.temprature", "0.5").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Unknown configuration key 'generate.temprature'; did you mean 'generate.temperature'?"
+        );
+        let err = config.set_strict("team.name", "docs").unwrap_err();
+        assert_eq!(err.to_string(), "Unknown configuration key 'team.name'");
+        assert!(config.custom.is_empty());
+
+        config.set_strict("generate.temperature", "0.5").unwrap();
+        assert_eq!(config.generate.temperature, 0.5);
+
+        // The lenient setter keeps the typo as a custom value
+        config.set("generate.temprature", "0.9").unwrap();
+        assert_eq!(config.get("generate.temprature").as_deref(), Some("0.9"));
+    }
+
+    #[test]
+    fn test_generation_ranges() {
+        let mut config = Config::default();
+
+        for key in ["generate", "openai", "claude"] {
+            let tokens_key = format!("{}.max_tokens", key);
+            let temperature_key = format!("{}.temperature", key);
+
+            config.set(&tokens_key, "100000").unwrap();
+            config.set(&temperature_key, "1.5").unwrap();
+            assert_eq!(config.get(&tokens_key).as_deref(), Some("100000"));
+            assert_eq!(config.get(&temperature_key).as_deref(), Some("1.5"));
+
+            let err = config.set(&tokens_key, "100001").unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                format!("'{}' must be between 1 and 100000, got 100001", tokens_key)
+            );
+            let err = config.set(&tokens_key, "0").unwrap_err();
+            assert!(err.to_string().contains("between 1 and 100000"));
+
+            let err = config.set(&temperature_key, "2.5").unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                format!("'{}' must be between 0.0 and 2.0, got 2.5", temperature_key)
+            );
+            let err = config.set(&temperature_key, "warm").unwrap_err();
+            assert!(err.to_string().contains("must be a number"));
         }
+        assert!(config.custom.is_empty());
+        config.validate().unwrap();
+
+        // Values that bypass `set`, e.g. from a hand-edited file, are caught too
+        config.claude.temperature = Some(-0.1);
+        let err = config.validate().unwrap_err();
+        assert!(err.to_string().contains("'claude.temperature'"));
+    }
+
+    #[test]
+    fn test_profile_merge() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(
+            &path,
+            r#"
+[generate]
+model = "gpt-4"
+max_tokens = 2000
+temperature = 0.7
+content_type = "report"
+
+[openai]
+api_key = "sk-base"
+
+[profiles.work.openai]
+api_key = "sk-work"
+
+[profiles.work.generate]
+max_tokens = 4000
+
+[profiles.personal.openai]
+api_key = "sk-personal"
+"#,
+        )
+        .unwrap();
+
+        let base = Config::read_from(&path).unwrap();
+        assert_eq!(base.openai.api_key.as_deref(), Some("sk-base"));
+
+        let mut work = base.clone();
+        work.apply_profile("work").unwrap();
+        assert_eq!(work.openai.api_key.as_deref(), Some("sk-work"));
+        assert_eq!(work.generate.max_tokens, 4000);
+        // Untouched keys keep their base value
+        assert_eq!(work.generate.model, "gpt-4");
+        assert_eq!(work.generate.content_type, "report");
+
+        // Environment overrides still win over the profile
+        work.apply_env_overrides([("DOX_OPENAI_API_KEY".to_string(), "sk-env".to_string())])
+            .unwrap();
+        assert_eq!(work.openai.api_key.as_deref(), Some("sk-env"));
+
+        let personal = Config::load_profile_from(&path, Some("personal")).unwrap();
+        assert_eq!(personal.generate.max_tokens, 2000);
+
+        // Profiles survive a save and reload
+        let saved = dir.path().join("saved.toml");
+        base.save_to(&saved).unwrap();
+        assert_eq!(Config::read_from(&saved).unwrap().profiles.len(), 2);
+    }
+
+    #[test]
+    fn test_unknown_profile() {
+        let mut config = Config::default();
+        let err = config.apply_profile("work").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Unknown profile 'work': no profiles are defined"
+        );
+
+        config
+            .profiles
+            .insert("personal".to_string(), serde_json::json!({}));
+        config
+            .profiles
+            .insert("home".to_string(), serde_json::json!({}));
+        let err = config.apply_profile("work").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Unknown profile 'work', available profiles: home, personal"
+        );
+
+        config.profiles.insert(
+            "broken".to_string(),
+            serde_json::json!({ "generate": { "temperature": 5.0 } }),
+        );
+        let err = config.apply_profile("broken").unwrap_err();
+        assert!(err.to_string().contains("profile 'broken'"));
+    }
+
+    #[test]
+    fn test_secret_masking() {
+        assert!(Config::is_secret("openai.api_key"));
+        assert!(Config::is_secret("service.token"));
+        assert!(!Config::is_secret("generate.max_tokens"));
+        assert!(!Config::is_secret("openai.model"));
 
-        output
+        assert_eq!(Config::mask_secret("sk-1234567890abcdef"), "sk-12345***");
+        assert_eq!(Config::mask_secret("short"), "***");
+        assert_eq!(Config::mask_secret(""), "");
     }
 }
@@ -1,2 +1,5 @@
+pub mod atomic;
 pub mod config;
 pub mod ui;
+
+pub use atomic::write_atomic;
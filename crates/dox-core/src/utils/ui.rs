@@ -1,20 +1,53 @@
 use colored::*;
 use indicatif::{ProgressBar, ProgressStyle};
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
 
+static JSON_OUTPUT: AtomicBool = AtomicBool::new(false);
+
+/// JSON 출력 모드 설정
+///
+/// While enabled, informational messages are suppressed so that stdout only
+/// carries the JSON written by [`print_json`]. Warnings and errors still go
+/// to stderr.
+pub fn set_json_output(enabled: bool) {
+    JSON_OUTPUT.store(enabled, Ordering::Relaxed);
+}
+
+/// JSON 출력 모드 여부
+pub fn json_output() -> bool {
+    JSON_OUTPUT.load(Ordering::Relaxed)
+}
+
+/// 값을 한 줄의 JSON으로 출력 (NDJSON)
+pub fn print_json<T: Serialize + ?Sized>(value: &T) -> serde_json::Result<()> {
+    println!("{}", serde_json::to_string(value)?);
+    Ok(())
+}
+
 /// 헤더 메시지 출력
 pub fn print_header(message: &str) {
+    if json_output() {
+        return;
+    }
     println!("\n{}", message.bold().blue());
     println!("{}", "=".repeat(message.chars().count()).blue());
 }
 
 /// 정보 메시지 출력
 pub fn print_info(message: &str) {
+    if json_output() {
+        return;
+    }
     println!("{} {}", "ℹ".blue(), message);
 }
 
 /// 성공 메시지 출력
 pub fn print_success(message: &str) {
+    if json_output() {
+        return;
+    }
     println!("{} {}", "✓".green(), message.green());
 }
 
@@ -30,6 +63,9 @@ pub fn print_error(message: &str) {
 
 /// 프로세스 단계 출력
 pub fn print_step(current: usize, total: usize, message: &str) {
+    if json_output() {
+        return;
+    }
     println!("{} [{}/{}] {}", "→".cyan(), current, total, message);
 }
 
@@ -61,22 +97,35 @@ pub fn create_spinner(message: &str) -> ProgressBar {
     spinner
 }
 
-/// Display a diff between old and new text
-pub fn print_diff(old: &str, new: &str, _context_lines: usize) {
-    use similar::{ChangeTag, TextDiff};
-
-    let diff = TextDiff::from_lines(old, new);
-
-    for change in diff.iter_all_changes() {
-        let sign = match change.tag() {
-            ChangeTag::Delete => "-".red(),
-            ChangeTag::Insert => "+".green(),
-            ChangeTag::Equal => " ".normal(),
+/// Display a colored unified diff between old and new text
+pub fn print_diff(old: &str, new: &str, context_lines: usize) {
+    if json_output() {
+        return;
+    }
+    for line in unified_diff(old, new, context_lines).lines() {
+        let line = if line.starts_with("@@") {
+            line.cyan()
+        } else if line.starts_with('-') {
+            line.red()
+        } else if line.starts_with('+') {
+            line.green()
+        } else {
+            line.normal()
         };
-        print!("{} {}", sign, change);
+        println!("{}", line);
     }
 }
 
+/// unified diff 문자열 생성 (색상 없음, 변경된 줄 주변 `context_lines`줄 포함)
+pub fn unified_diff(old: &str, new: &str, context_lines: usize) -> String {
+    use similar::TextDiff;
+
+    TextDiff::from_lines(old, new)
+        .unified_diff()
+        .context_radius(context_lines)
+        .to_string()
+}
+
 /// 사용자 확인 프롬프트
 pub fn confirm(message: &str, default: bool) -> bool {
     use dialoguer::Confirm;
@@ -132,9 +181,23 @@ pub fn select<T: ToString>(message: &str, items: &[T], default: usize) -> usize
 }
 
 /// Display a table of data
+///
+/// In JSON output mode each row is printed as an object keyed by header.
 pub fn print_table(headers: &[&str], rows: Vec<Vec<String>>) {
     use prettytable::{Cell, Row, Table};
 
+    if json_output() {
+        for row in rows {
+            let object: serde_json::Map<String, serde_json::Value> = headers
+                .iter()
+                .map(|h| h.to_string())
+                .zip(row.into_iter().map(serde_json::Value::String))
+                .collect();
+            println!("{}", serde_json::Value::Object(object));
+        }
+        return;
+    }
+
     let mut table = Table::new();
 
     // Add header
@@ -26,6 +26,15 @@ impl Document {
         }
     }
 
+    /// Replace regex matches in the document
+    pub fn replace_pattern(&mut self, pattern: &regex::Regex, replacement: &str) -> Result<usize> {
+        match self {
+            Document::Provider(provider) => provider
+                .replace_pattern(pattern, replacement)
+                .map_err(|e| anyhow::anyhow!("Replace pattern failed: {}", e)),
+        }
+    }
+
     /// Save the document
     pub fn save(&self) -> Result<()> {
         match self {
@@ -0,0 +1,353 @@
+//! Paragraph-level comparison of two documents
+
+use crate::extract::extractors::UniversalExtractor;
+use crate::extract::ExtractResult;
+use crate::provider::{DocumentError, DocumentType};
+use crate::word::WordProvider;
+use serde::{Deserialize, Serialize};
+use similar::{capture_diff_slices, Algorithm, DiffOp, TextDiff};
+use std::path::Path;
+use unicode_width::UnicodeWidthChar;
+
+/// Character similarity above which a replaced paragraph counts as changed
+/// rather than removed and added
+const CHANGED_RATIO: f32 = 0.5;
+
+/// How a paragraph differs between the two documents
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Unchanged,
+    Added,
+    Removed,
+    Changed,
+}
+
+/// One aligned paragraph pair; the side a paragraph is missing from is `None`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiffSegment {
+    pub kind: ChangeKind,
+    /// Paragraph number in the old document (1-based)
+    pub old_paragraph: Option<usize>,
+    /// Paragraph number in the new document (1-based)
+    pub new_paragraph: Option<usize>,
+    pub old_text: Option<String>,
+    pub new_text: Option<String>,
+}
+
+/// Number of paragraphs of each kind
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiffSummary {
+    pub unchanged: usize,
+    pub added: usize,
+    pub removed: usize,
+    pub changed: usize,
+}
+
+/// Two documents aligned paragraph by paragraph
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentDiff {
+    /// Name of the old document
+    pub old: String,
+    /// Name of the new document
+    pub new: String,
+    pub summary: DiffSummary,
+    /// Every paragraph of both documents in reading order
+    pub segments: Vec<DiffSegment>,
+}
+
+impl DocumentDiff {
+    /// Extract both files and compare their paragraphs
+    pub fn between_files(old: &Path, new: &Path) -> Result<Self, DocumentError> {
+        Ok(Self::compare(
+            &file_name(old),
+            &file_paragraphs(old)?,
+            &file_name(new),
+            &file_paragraphs(new)?,
+        ))
+    }
+
+    /// Compare the paragraphs of two extraction results
+    pub fn between(old: &ExtractResult, new: &ExtractResult) -> Self {
+        Self::compare(
+            &old.filename,
+            &paragraphs(old),
+            &new.filename,
+            &paragraphs(new),
+        )
+    }
+
+    /// Align two paragraph lists
+    ///
+    /// Runs of replaced paragraphs are paired in order; a pair that shares
+    /// too little text is reported as a removal and an addition instead.
+    pub fn compare(old_name: &str, old: &[String], new_name: &str, new: &[String]) -> Self {
+        let mut segments = Vec::new();
+        let removed = |index: usize| DiffSegment {
+            kind: ChangeKind::Removed,
+            old_paragraph: Some(index + 1),
+            new_paragraph: None,
+            old_text: Some(old[index].clone()),
+            new_text: None,
+        };
+        let added = |index: usize| DiffSegment {
+            kind: ChangeKind::Added,
+            old_paragraph: None,
+            new_paragraph: Some(index + 1),
+            old_text: None,
+            new_text: Some(new[index].clone()),
+        };
+        let paired = |kind, old_index: usize, new_index: usize| DiffSegment {
+            kind,
+            old_paragraph: Some(old_index + 1),
+            new_paragraph: Some(new_index + 1),
+            old_text: Some(old[old_index].clone()),
+            new_text: Some(new[new_index].clone()),
+        };
+
+        for op in capture_diff_slices(Algorithm::Myers, old, new) {
+            match op {
+                DiffOp::Equal {
+                    old_index,
+                    new_index,
+                    len,
+                } => segments.extend(
+                    (0..len).map(|i| paired(ChangeKind::Unchanged, old_index + i, new_index + i)),
+                ),
+                DiffOp::Delete {
+                    old_index, old_len, ..
+                } => segments.extend((old_index..old_index + old_len).map(removed)),
+                DiffOp::Insert {
+                    new_index, new_len, ..
+                } => segments.extend((new_index..new_index + new_len).map(added)),
+                DiffOp::Replace {
+                    old_index,
+                    old_len,
+                    new_index,
+                    new_len,
+                } => {
+                    for i in 0..old_len.max(new_len) {
+                        let (o, n) = (old_index + i, new_index + i);
+                        if i < old_len && i < new_len {
+                            if TextDiff::from_chars(&old[o], &new[n]).ratio() >= CHANGED_RATIO {
+                                segments.push(paired(ChangeKind::Changed, o, n));
+                            } else {
+                                segments.push(removed(o));
+                                segments.push(added(n));
+                            }
+                        } else if i < old_len {
+                            segments.push(removed(o));
+                        } else {
+                            segments.push(added(n));
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut summary = DiffSummary::default();
+        for segment in &segments {
+            match segment.kind {
+                ChangeKind::Unchanged => summary.unchanged += 1,
+                ChangeKind::Added => summary.added += 1,
+                ChangeKind::Removed => summary.removed += 1,
+                ChangeKind::Changed => summary.changed += 1,
+            }
+        }
+
+        Self {
+            old: old_name.to_string(),
+            new: new_name.to_string(),
+            summary,
+            segments,
+        }
+    }
+
+    /// Whether any paragraph was added, removed or changed
+    pub fn has_changes(&self) -> bool {
+        self.summary.unchanged != self.segments.len()
+    }
+
+    /// Old document, one paragraph per line
+    pub fn old_text(&self) -> String {
+        lines(self.segments.iter().filter_map(|s| s.old_text.as_deref()))
+    }
+
+    /// New document, one paragraph per line
+    pub fn new_text(&self) -> String {
+        lines(self.segments.iter().filter_map(|s| s.new_text.as_deref()))
+    }
+
+    /// Two-column listing `width` display columns wide
+    ///
+    /// The gutter marks each row like `sdiff`: `|` changed, `<` removed,
+    /// `>` added. Long paragraphs wrap within their column.
+    pub fn side_by_side(&self, width: usize) -> String {
+        let column = (width.saturating_sub(3) / 2).max(10);
+        let mut output = String::new();
+
+        for segment in &self.segments {
+            let marker = match segment.kind {
+                ChangeKind::Unchanged => ' ',
+                ChangeKind::Added => '>',
+                ChangeKind::Removed => '<',
+                ChangeKind::Changed => '|',
+            };
+            let left = wrap(segment.old_text.as_deref().unwrap_or(""), column);
+            let right = wrap(segment.new_text.as_deref().unwrap_or(""), column);
+
+            for row in 0..left.len().max(right.len()) {
+                let left = left.get(row).map(String::as_str).unwrap_or("");
+                let right = right.get(row).map(String::as_str).unwrap_or("");
+                let padding = column - display_width(left);
+                // The marker only goes on the first row of a paragraph
+                let marker = if row == 0 { marker } else { ' ' };
+                let line = format!("{}{} {} {}", left, " ".repeat(padding), marker, right);
+                output.push_str(line.trim_end());
+                output.push('\n');
+            }
+        }
+
+        output
+    }
+}
+
+/// Non-empty lines of an extraction result, trimmed, in page order
+pub fn paragraphs(result: &ExtractResult) -> Vec<String> {
+    result
+        .pages
+        .iter()
+        .flat_map(|page| page.text.lines())
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Paragraphs of a document file
+///
+/// Word extraction runs the whole body together, so Word paragraphs are
+/// read from the document structure instead.
+fn file_paragraphs(path: &Path) -> Result<Vec<String>, DocumentError> {
+    if DocumentType::detect(path) == Some(DocumentType::Word) {
+        return Ok(WordProvider::read_structure(path)?
+            .paragraphs
+            .iter()
+            .map(|paragraph| paragraph.text().trim().to_string())
+            .filter(|text| !text.is_empty())
+            .collect());
+    }
+    Ok(paragraphs(&UniversalExtractor::extract_from_path(path)?))
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+fn lines<'a>(paragraphs: impl Iterator<Item = &'a str>) -> String {
+    paragraphs
+        .map(|paragraph| format!("{}\n", paragraph))
+        .collect()
+}
+
+fn display_width(text: &str) -> usize {
+    text.chars().filter_map(UnicodeWidthChar::width).sum()
+}
+
+/// Split `text` into rows of at most `width` display columns
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut rows = vec![String::new()];
+    let mut row_width = 0;
+    for c in text.chars() {
+        let char_width = c.width().unwrap_or(0);
+        if row_width + char_width > width {
+            rows.push(String::new());
+            row_width = 0;
+        }
+        rows.last_mut().unwrap().push(c);
+        row_width += char_width;
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(texts: &[&str]) -> Vec<String> {
+        texts.iter().map(|text| text.to_string()).collect()
+    }
+
+    #[test]
+    fn test_compare_paragraphs() {
+        let old = texts(&["Title", "Revenue grew 5%.", "Old note", "Closing"]);
+        let new = texts(&["Title", "Revenue grew 7%.", "Closing", "Appendix"]);
+        let diff = DocumentDiff::compare("old.docx", &old, "new.docx", &new);
+
+        let kinds: Vec<ChangeKind> = diff.segments.iter().map(|s| s.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ChangeKind::Unchanged,
+                ChangeKind::Changed,
+                ChangeKind::Removed,
+                ChangeKind::Unchanged,
+                ChangeKind::Added,
+            ]
+        );
+        assert_eq!(
+            diff.summary,
+            DiffSummary {
+                unchanged: 2,
+                added: 1,
+                removed: 1,
+                changed: 1,
+            }
+        );
+        assert_eq!(diff.segments[1].old_paragraph, Some(2));
+        assert_eq!(diff.segments[4].new_paragraph, Some(4));
+        assert_eq!(
+            diff.old_text(),
+            "Title\nRevenue grew 5%.\nOld note\nClosing\n"
+        );
+        assert!(diff.has_changes());
+    }
+
+    #[test]
+    fn test_unrelated_replacement_is_removed_and_added() {
+        let diff = DocumentDiff::compare(
+            "old",
+            &texts(&["Quarterly summary"]),
+            "new",
+            &texts(&["번역된 문단"]),
+        );
+        let kinds: Vec<ChangeKind> = diff.segments.iter().map(|s| s.kind).collect();
+        assert_eq!(kinds, vec![ChangeKind::Removed, ChangeKind::Added]);
+
+        let same = DocumentDiff::compare("a", &texts(&["x"]), "b", &texts(&["x"]));
+        assert!(!same.has_changes());
+    }
+
+    #[test]
+    fn test_side_by_side_columns() {
+        let diff = DocumentDiff::compare(
+            "old",
+            &texts(&["Same", "매출 5%"]),
+            "new",
+            &texts(&["Same", "매출 7%"]),
+        );
+        assert_eq!(
+            diff.side_by_side(23),
+            "Same         Same\n매출 5%    | 매출 7%\n"
+        );
+
+        // Long paragraphs wrap inside their column
+        let diff = DocumentDiff::compare("old", &[], "new", &texts(&["abcdefghijklmno"]));
+        assert_eq!(
+            diff.side_by_side(23),
+            "           > abcdefghij\n             klmno\n"
+        );
+    }
+}
@@ -1,8 +1,19 @@
 //! Excel document provider using calamine for text extraction
+//!
+//! Text replacement edits the shared string table and inline cell strings
+//! directly, so formulas, numbers and styles are left untouched.
 
 use crate::provider::{DocumentError, DocumentProvider, DocumentType};
+use crate::utils::{
+    copy_zip_with_replacements, extract_zip, read_zip_file, replace_pattern_in_xml,
+    replace_text_in_xml,
+};
 use anyhow::Result;
-use calamine::{open_workbook, Reader, Xlsx};
+use calamine::{Reader, Xlsx};
+use dox_core::utils::write_atomic;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::io::Cursor;
 use std::path::Path;
 use tracing::debug;
 
@@ -23,6 +34,9 @@ pub struct ExcelMetadata {
 #[derive(Debug)]
 pub struct ExcelProvider {
     path: std::path::PathBuf,
+    archive_data: Vec<u8>,
+    /// Shared strings and worksheet parts, which hold all cell text
+    text_parts: Vec<(String, Vec<u8>)>,
     modified: bool,
 }
 
@@ -37,18 +51,107 @@ impl ExcelProvider {
             });
         }
 
+        let archive_data = std::fs::read(path)?;
+        Self::load(path.to_path_buf(), archive_data)
+    }
+
+    /// Open an Excel document held in memory
+    ///
+    /// The document has no path, so changes are written with `save_as`.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, DocumentError> {
+        debug!("Opening Excel document from {} bytes", data.len());
+        Self::load(std::path::PathBuf::new(), data.to_vec())
+    }
+
+    fn load(path: std::path::PathBuf, archive_data: Vec<u8>) -> Result<Self, DocumentError> {
+        // Damaged and encrypted files are reported before calamine sees them
+        let mut archive = extract_zip(&archive_data)?;
+
         // Test if we can open the file
-        let _workbook: Xlsx<_> = open_workbook(path).map_err(|e| DocumentError::FileReadError {
-            path: path.display().to_string(),
-            source: anyhow::anyhow!("Failed to open Excel file: {}", e),
+        Xlsx::new(Cursor::new(archive_data.as_slice())).map_err(|e| {
+            DocumentError::FileReadError {
+                path: path.display().to_string(),
+                source: anyhow::anyhow!("Failed to open Excel file: {}", e),
+            }
         })?;
 
+        let part_names: Vec<String> = archive
+            .file_names()
+            .filter(|name| Self::is_text_part(name))
+            .map(str::to_string)
+            .collect();
+
+        let mut text_parts = Vec::with_capacity(part_names.len());
+        for name in part_names {
+            let content = read_zip_file(&mut archive, &name)?;
+            text_parts.push((name, content));
+        }
+
         Ok(ExcelProvider {
-            path: path.to_path_buf(),
+            path,
+            archive_data,
+            text_parts,
             modified: false,
         })
     }
 
+    /// Whether an archive entry can contain cell text
+    fn is_text_part(name: &str) -> bool {
+        name == "xl/sharedStrings.xml"
+            || (name.starts_with("xl/worksheets/sheet") && name.ends_with(".xml"))
+    }
+
+    /// Text tags in shared strings (`<si><t>`) and inline strings (`<is><t>`)
+    fn text_tags() -> &'static [&'static str] {
+        &["t"]
+    }
+
+    /// Current archive bytes, including unsaved replacements
+    fn current_archive(&self) -> Result<Cow<'_, [u8]>, DocumentError> {
+        if !self.modified {
+            return Ok(Cow::Borrowed(&self.archive_data));
+        }
+
+        let mut output = Cursor::new(Vec::new());
+        copy_zip_with_replacements(&self.archive_data, &mut output, &self.replaced_parts())?;
+        Ok(Cow::Owned(output.into_inner()))
+    }
+
+    fn replaced_parts(&self) -> HashMap<String, Vec<u8>> {
+        self.text_parts.iter().cloned().collect()
+    }
+
+    /// Open the workbook from the current archive bytes
+    fn workbook(&self) -> Result<Xlsx<Cursor<Cow<'_, [u8]>>>, DocumentError> {
+        Xlsx::new(Cursor::new(self.current_archive()?)).map_err(|e| DocumentError::FileReadError {
+            path: self.path.display().to_string(),
+            source: anyhow::anyhow!("Failed to open Excel file: {}", e),
+        })
+    }
+
+    /// Apply a replacement to every text part, returning the total count
+    fn rewrite_text_parts(
+        &mut self,
+        mut rewrite: impl FnMut(&[u8]) -> Result<(Vec<u8>, usize), DocumentError>,
+    ) -> Result<usize, DocumentError> {
+        let mut total_count = 0;
+
+        for (part_name, content) in &mut self.text_parts {
+            let (new_content, count) = rewrite(content)?;
+            if count > 0 {
+                *content = new_content;
+                total_count += count;
+                debug!("Replaced {} occurrences in {}", count, part_name);
+            }
+        }
+
+        if total_count > 0 {
+            self.modified = true;
+        }
+
+        Ok(total_count)
+    }
+
     /// Extract text from all sheets in the Excel workbook
     fn extract_text_from_workbook(&self) -> Result<String, DocumentError> {
         debug!(
@@ -56,11 +159,7 @@ impl ExcelProvider {
             self.path.display()
         );
 
-        let mut workbook: Xlsx<_> =
-            open_workbook(&self.path).map_err(|e| DocumentError::FileReadError {
-                path: self.path.display().to_string(),
-                source: anyhow::anyhow!("Failed to open Excel file: {}", e),
-            })?;
+        let mut workbook = self.workbook()?;
 
         let mut full_text = String::new();
 
@@ -123,17 +222,29 @@ impl ExcelProvider {
             self.path.display()
         );
 
-        let workbook: Xlsx<_> =
-            open_workbook(&self.path).map_err(|e| DocumentError::FileReadError {
-                path: self.path.display().to_string(),
-                source: anyhow::anyhow!("Failed to open Excel file: {}", e),
-            })?;
+        let workbook = self.workbook()?;
+        Ok(Self::metadata_for(&self.path, workbook.sheet_names()))
+    }
 
-        let sheet_names = workbook.sheet_names();
+    /// Read the metadata of a workbook on disk without reading any cells
+    pub fn read_metadata(path: &Path) -> Result<ExcelMetadata, DocumentError> {
+        if !path.exists() {
+            return Err(DocumentError::DocumentNotFound {
+                path: path.display().to_string(),
+            });
+        }
+
+        let file = std::io::BufReader::new(std::fs::File::open(path)?);
+        let workbook = Xlsx::new(file).map_err(|e| DocumentError::FileReadError {
+            path: path.display().to_string(),
+            source: anyhow::anyhow!("Failed to open Excel file: {}", e),
+        })?;
+        Ok(Self::metadata_for(path, workbook.sheet_names()))
+    }
 
-        let metadata = ExcelMetadata {
-            title: self
-                .path
+    fn metadata_for(path: &Path, sheet_names: Vec<String>) -> ExcelMetadata {
+        ExcelMetadata {
+            title: path
                 .file_stem()
                 .and_then(|s| s.to_str())
                 .map(|s| s.to_string()),
@@ -141,12 +252,10 @@ impl ExcelProvider {
             subject: None,
             creator: None, // TODO: Extract application name
             total_sheets: sheet_names.len(),
-            sheet_names: sheet_names.clone(),
+            sheet_names,
             created: None,  // TODO: Extract from file system or document properties
             modified: None, // TODO: Extract from file system or document properties
-        };
-
-        Ok(metadata)
+        }
     }
 
     /// Get text from a specific sheet
@@ -156,11 +265,7 @@ impl ExcelProvider {
             sheet_name
         );
 
-        let mut workbook: Xlsx<_> =
-            open_workbook(&self.path).map_err(|e| DocumentError::FileReadError {
-                path: self.path.display().to_string(),
-                source: anyhow::anyhow!("Failed to open Excel file: {}", e),
-            })?;
+        let mut workbook = self.workbook()?;
 
         let mut sheet_text = String::new();
 
@@ -205,11 +310,7 @@ impl ExcelProvider {
 
     /// Get the sheet names
     pub fn get_sheet_names(&self) -> Result<Vec<String>, DocumentError> {
-        let workbook: Xlsx<_> =
-            open_workbook(&self.path).map_err(|e| DocumentError::FileReadError {
-                path: self.path.display().to_string(),
-                source: anyhow::anyhow!("Failed to open Excel file: {}", e),
-            })?;
+        let workbook = self.workbook()?;
 
         Ok(workbook.sheet_names())
     }
@@ -217,33 +318,44 @@ impl ExcelProvider {
 
 impl DocumentProvider for ExcelProvider {
     fn replace_text(&mut self, old: &str, new: &str) -> Result<usize, DocumentError> {
-        // Excel text replacement is not implemented for now
-        // This would require reading, modifying, and writing back the Excel file
+        debug!("Replacing text '{}' with '{}' in Excel document", old, new);
+        self.rewrite_text_parts(|content| replace_text_in_xml(content, Self::text_tags(), old, new))
+    }
+
+    fn replace_pattern(
+        &mut self,
+        pattern: &regex::Regex,
+        replacement: &str,
+    ) -> Result<usize, DocumentError> {
         debug!(
-            "Excel text replacement not implemented: '{}' -> '{}'",
-            old, new
+            "Replacing pattern '{}' with '{}' in Excel document",
+            pattern, replacement
         );
-        Ok(0)
+        self.rewrite_text_parts(|content| {
+            replace_pattern_in_xml(content, Self::text_tags(), pattern, replacement)
+        })
     }
 
     fn save(&self) -> Result<(), DocumentError> {
         if !self.modified {
+            debug!("No changes to save in Excel document");
             return Ok(());
         }
 
-        // Excel saving is not implemented for now
-        debug!("Excel save not implemented");
-        Err(DocumentError::OperationFailed {
-            reason: "Excel save operation not implemented".to_string(),
-        })
+        debug!("Saving Excel document to: {}", self.path.display());
+        self.save_as(&self.path)
     }
 
-    fn save_as(&self, _path: &Path) -> Result<(), DocumentError> {
-        // Excel save_as is not implemented for now
-        debug!("Excel save_as not implemented");
-        Err(DocumentError::OperationFailed {
-            reason: "Excel save_as operation not implemented".to_string(),
-        })
+    fn save_as(&self, path: &Path) -> Result<(), DocumentError> {
+        debug!("Saving Excel document as: {}", path.display());
+
+        let replacements = self.replaced_parts();
+        write_atomic(path, |file| {
+            copy_zip_with_replacements(&self.archive_data, file, &replacements)
+        })?;
+
+        debug!("Excel document saved successfully");
+        Ok(())
     }
 
     fn get_text(&self) -> Result<String, DocumentError> {
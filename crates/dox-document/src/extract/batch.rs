@@ -0,0 +1,180 @@
+//! Concurrent extraction of many documents
+
+use super::extractors::UniversalExtractor;
+use super::ExtractResult;
+use crate::page_range::PageRange;
+use crate::progress::{CancellationToken, ProgressCallback};
+use crate::provider::DocumentError;
+use std::path::PathBuf;
+use tracing::{debug, error, info, warn};
+
+/// Extracts a list of files on a bounded pool of blocking workers
+///
+/// A file that fails to extract is recorded in
+/// [`BatchExtraction::errors`] and the remaining files carry on.
+#[derive(Debug, Clone)]
+pub struct BatchExtractor {
+    max_workers: usize,
+    page_range: Option<PageRange>,
+    progress: Option<ProgressCallback>,
+    cancel: Option<CancellationToken>,
+}
+
+/// Outcome of a [`BatchExtractor`] run
+///
+/// Both lists keep the order in which the files were given, whatever order
+/// the workers finished in.
+#[derive(Debug, Default)]
+pub struct BatchExtraction {
+    pub results: Vec<(PathBuf, ExtractResult)>,
+    pub errors: Vec<(PathBuf, DocumentError)>,
+    /// The run was stopped through the cancellation token before every
+    /// file was extracted
+    pub cancelled: bool,
+}
+
+impl BatchExtraction {
+    /// Number of files that were extracted or failed
+    pub fn files_processed(&self) -> usize {
+        self.results.len() + self.errors.len()
+    }
+
+    fn record(&mut self, file: PathBuf, result: Result<ExtractResult, DocumentError>) {
+        match result {
+            Ok(result) => {
+                debug!("Extracted {}", file.display());
+                self.results.push((file, result));
+            }
+            Err(e) => {
+                error!("Error extracting {}: {}", file.display(), e);
+                self.errors.push((file, e));
+            }
+        }
+    }
+}
+
+impl BatchExtractor {
+    pub fn new(max_workers: usize) -> Self {
+        Self {
+            max_workers: max_workers.max(1),
+            page_range: None,
+            progress: None,
+            cancel: None,
+        }
+    }
+
+    /// Only extract the pages or slides in `page_range` from each file
+    pub fn with_page_range(mut self, page_range: PageRange) -> Self {
+        self.page_range = Some(page_range);
+        self
+    }
+
+    /// Call `progress` after each file with the number of files done and the total
+    pub fn with_progress(mut self, progress: ProgressCallback) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+
+    /// Stop picking up new files once `cancel` is cancelled
+    pub fn with_cancel(mut self, cancel: CancellationToken) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancel
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled)
+    }
+
+    /// Extract every file in `files`
+    ///
+    /// Files are extracted on tokio's blocking pool with at most
+    /// `max_workers` in flight. Without a runtime they are extracted one
+    /// after another on the calling thread.
+    pub async fn extract(&self, files: Vec<PathBuf>) -> BatchExtraction {
+        use futures::stream::{self, StreamExt};
+
+        if tokio::runtime::Handle::try_current().is_err() {
+            warn!("No async runtime available, extracting files sequentially");
+            return self.extract_sequential(files);
+        }
+
+        let total = files.len();
+        let max_workers = self.max_workers.min(total).max(1);
+        info!("Extracting {} files with {} workers", total, max_workers);
+
+        let mut outcomes: Vec<_> = stream::iter(files.into_iter().enumerate())
+            .map(|(index, file)| {
+                let batch = self.clone();
+                let path = file.clone();
+                let task = tokio::task::spawn_blocking(move || {
+                    // Checked when the file is picked up, so queued files are skipped
+                    if batch.is_cancelled() {
+                        return None;
+                    }
+                    Some(UniversalExtractor::extract_pages(
+                        &file,
+                        batch.page_range.as_ref(),
+                    ))
+                });
+                async move {
+                    let outcome = task.await.unwrap_or_else(|e| {
+                        Some(Err(DocumentError::OperationFailed {
+                            reason: format!("Extraction worker failed: {}", e),
+                        }))
+                    });
+                    (index, path, outcome)
+                }
+            })
+            .buffer_unordered(max_workers)
+            .enumerate()
+            .map(|(done, outcome)| {
+                self.report_progress(done + 1, total);
+                outcome
+            })
+            .collect()
+            .await;
+
+        outcomes.sort_by_key(|(index, _, _)| *index);
+
+        let mut batch = BatchExtraction::default();
+        for (_, file, outcome) in outcomes {
+            match outcome {
+                Some(result) => batch.record(file, result),
+                None => batch.cancelled = true,
+            }
+        }
+        if batch.cancelled {
+            warn!(
+                "Cancelled with {} file(s) left",
+                total - batch.files_processed()
+            );
+        }
+        batch
+    }
+
+    fn extract_sequential(&self, files: Vec<PathBuf>) -> BatchExtraction {
+        let total = files.len();
+        let mut batch = BatchExtraction::default();
+
+        for (i, file) in files.into_iter().enumerate() {
+            if self.is_cancelled() {
+                warn!("Cancelled with {} file(s) left", total - i);
+                batch.cancelled = true;
+                break;
+            }
+            let result = UniversalExtractor::extract_pages(&file, self.page_range.as_ref());
+            batch.record(file, result);
+            self.report_progress(i + 1, total);
+        }
+
+        batch
+    }
+
+    fn report_progress(&self, current: usize, total: usize) {
+        if let Some(progress) = &self.progress {
+            progress.report(current, total);
+        }
+    }
+}
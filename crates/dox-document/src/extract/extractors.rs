@@ -1,10 +1,16 @@
 //! Document-specific extractor implementations
 
-use super::{DocumentExtractor, ExtractMetadata, ExtractResult, ExtractedElement, ExtractedPage};
+use super::{
+    DocumentExtractor, ExtractMetadata, ExtractResult, ExtractStats, ExtractedElement,
+    ExtractedPage,
+};
+use crate::language;
+use crate::page_range::PageRange;
+use crate::pdf::PdfExtractConfig;
 use crate::provider::{DocumentError, DocumentProvider, DocumentType};
 use crate::{ExcelProvider, PdfProvider, PowerPointProvider, TextProvider, WordProvider};
 use std::path::Path;
-use tracing::debug;
+use tracing::{debug, warn};
 
 /// Word document extractor
 pub struct WordExtractor;
@@ -13,15 +19,23 @@ impl WordExtractor {
     pub fn new() -> Self {
         Self
     }
-}
-
-impl DocumentExtractor for WordExtractor {
-    fn extract(&self, path: &Path) -> Result<ExtractResult, DocumentError> {
-        debug!("Extracting text from Word document: {}", path.display());
 
-        let provider = WordProvider::open(path)?;
+    /// Build the extraction result for an opened document
+    fn extract_document(provider: &WordProvider) -> Result<ExtractResult, DocumentError> {
         let text = provider.get_text()?;
 
+        // Extract metadata from Word document
+        let properties = provider.get_metadata().unwrap_or_default();
+        let language = language::resolve(
+            properties.language.as_deref(),
+            properties.east_asian_language.as_deref(),
+            &text,
+        );
+        let metadata = ExtractMetadata {
+            language,
+            ..word_metadata(properties)
+        };
+
         // For Word documents, we treat the entire document as one page
         let page = ExtractedPage {
             number: 1,
@@ -33,33 +47,50 @@ impl DocumentExtractor for WordExtractor {
                 marker: None,
             }],
             tables: vec![], // TODO: Implement table extraction for Word
+            links: provider.hyperlinks()?,
         };
 
-        // Extract metadata from Word document
-        let word_metadata = provider.get_metadata().unwrap_or_default();
-        let metadata = ExtractMetadata {
-            title: word_metadata.title,
-            author: word_metadata.author,
-            subject: word_metadata.subject,
-            creator: word_metadata.creator,
-            total_pages: word_metadata.total_pages.max(1), // At least 1 page
-            created: word_metadata.created,
-            modified: word_metadata.modified,
+        let pages = vec![page];
+        // The page holds the body as one block, so paragraphs and tables are
+        // counted from the document structure
+        let (paragraph_count, table_count) = provider.block_counts()?;
+        let stats = ExtractStats {
+            paragraph_count,
+            table_count,
+            ..ExtractStats::from_pages(&pages)
         };
 
         Ok(ExtractResult {
-            filename: path
+            filename: provider
+                .get_path()
                 .file_name()
                 .unwrap_or_default()
                 .to_string_lossy()
                 .to_string(),
             format: "Word Document (.docx)".to_string(),
-            pages: vec![page],
+            pages,
             metadata,
+            stats,
             success: true,
             error: None,
         })
     }
+}
+
+impl DocumentExtractor for WordExtractor {
+    fn extract(&self, path: &Path) -> Result<ExtractResult, DocumentError> {
+        debug!("Extracting text from Word document: {}", path.display());
+        Self::extract_document(&WordProvider::open(path)?)
+    }
+
+    fn extract_bytes(&self, data: &[u8]) -> Result<ExtractResult, DocumentError> {
+        debug!("Extracting text from in-memory Word document");
+        Self::extract_document(&WordProvider::from_bytes(data)?)
+    }
+
+    fn extract_metadata_only(&self, path: &Path) -> Result<ExtractMetadata, DocumentError> {
+        WordProvider::read_metadata(path).map(word_metadata)
+    }
 
     fn supported_types(&self) -> &[DocumentType] {
         &[DocumentType::Word]
@@ -67,70 +98,103 @@ impl DocumentExtractor for WordExtractor {
 }
 
 /// PowerPoint document extractor
-pub struct PowerPointExtractor;
+pub struct PowerPointExtractor {
+    page_range: Option<PageRange>,
+}
 
 impl PowerPointExtractor {
     pub fn new() -> Self {
-        Self
+        Self { page_range: None }
     }
-}
 
-impl DocumentExtractor for PowerPointExtractor {
-    fn extract(&self, path: &Path) -> Result<ExtractResult, DocumentError> {
-        debug!(
-            "Extracting text from PowerPoint document: {}",
-            path.display()
-        );
-
-        let provider = PowerPointProvider::open(path)?;
+    /// Only extract the slides in `page_range`
+    pub fn with_page_range(mut self, page_range: PageRange) -> Self {
+        self.page_range = Some(page_range);
+        self
+    }
 
-        // Extract slides individually
-        let slide_count = provider.slide_count();
+    /// Build the extraction result for an opened presentation
+    fn extract_document(
+        &self,
+        provider: &PowerPointProvider,
+    ) -> Result<ExtractResult, DocumentError> {
+        // One page per slide, empty slides included, so page numbers match slide numbers
         let mut pages = Vec::new();
 
-        for slide_index in 0..slide_count {
-            let slide_text = provider.get_slide_text(slide_index)?;
+        let slide_count = provider.slide_count();
+        let slides: Vec<usize> = match &self.page_range {
+            Some(range) => {
+                if let Some(warning) = range.out_of_range(slide_count) {
+                    warn!("{}", warning);
+                }
+                range
+                    .select(slide_count)
+                    .into_iter()
+                    .map(|n| n - 1)
+                    .collect()
+            }
+            None => (0..slide_count).collect(),
+        };
 
-            if !slide_text.trim().is_empty() {
-                let page = ExtractedPage {
-                    number: slide_index + 1,
-                    text: slide_text.clone(),
-                    elements: vec![ExtractedElement {
-                        element_type: "slide".to_string(),
-                        content: slide_text,
-                        level: None,
-                        marker: None,
-                    }],
-                    tables: vec![], // TODO: Implement table extraction for PowerPoint
-                };
-                pages.push(page);
+        for slide_index in slides {
+            let slide = provider.get_slide_structure(slide_index)?;
+
+            let mut elements = Vec::new();
+            let mut push = |element_type: &str, content: &String, level: Option<u8>| {
+                elements.push(ExtractedElement {
+                    element_type: element_type.to_string(),
+                    content: content.clone(),
+                    level,
+                    marker: None,
+                });
+            };
+            for title in &slide.title {
+                push("heading", title, Some(1));
+            }
+            for paragraph in &slide.body {
+                push("paragraph", paragraph, None);
             }
+            for note in &slide.notes {
+                push("speaker_note", note, None);
+            }
+
+            pages.push(ExtractedPage {
+                number: slide_index + 1,
+                text: slide
+                    .title
+                    .iter()
+                    .chain(&slide.body)
+                    .cloned()
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                elements,
+                tables: vec![], // TODO: Implement table extraction for PowerPoint
+                links: slide.links,
+            });
         }
 
-        // If no slides had content, create a single empty page
-        if pages.is_empty() {
+        // A presentation without slides still yields a single empty page
+        if slide_count == 0 {
             pages.push(ExtractedPage {
                 number: 1,
                 text: String::new(),
                 elements: vec![],
                 tables: vec![],
+                links: vec![],
             });
         }
 
         // Extract metadata from PowerPoint document
-        let ppt_metadata = provider.get_metadata().unwrap_or_default();
         let metadata = ExtractMetadata {
-            title: ppt_metadata.title,
-            author: ppt_metadata.author,
-            subject: ppt_metadata.subject,
-            creator: ppt_metadata.creator,
-            total_pages: ppt_metadata.total_slides.max(1), // At least 1 page
-            created: ppt_metadata.created,
-            modified: ppt_metadata.modified,
+            language: language::detect(&pages_text(&pages)),
+            ..powerpoint_metadata(provider.get_metadata().unwrap_or_default())
         };
 
+        let stats = ExtractStats::from_pages(&pages);
+
         Ok(ExtractResult {
-            filename: path
+            filename: provider
+                .get_path()
                 .file_name()
                 .unwrap_or_default()
                 .to_string_lossy()
@@ -138,10 +202,30 @@ impl DocumentExtractor for PowerPointExtractor {
             format: "PowerPoint Presentation (.pptx)".to_string(),
             pages,
             metadata,
+            stats,
             success: true,
             error: None,
         })
     }
+}
+
+impl DocumentExtractor for PowerPointExtractor {
+    fn extract(&self, path: &Path) -> Result<ExtractResult, DocumentError> {
+        debug!(
+            "Extracting text from PowerPoint document: {}",
+            path.display()
+        );
+        self.extract_document(&PowerPointProvider::open(path)?)
+    }
+
+    fn extract_bytes(&self, data: &[u8]) -> Result<ExtractResult, DocumentError> {
+        debug!("Extracting text from in-memory PowerPoint document");
+        self.extract_document(&PowerPointProvider::from_bytes(data)?)
+    }
+
+    fn extract_metadata_only(&self, path: &Path) -> Result<ExtractMetadata, DocumentError> {
+        PowerPointProvider::read_metadata(path).map(powerpoint_metadata)
+    }
 
     fn supported_types(&self) -> &[DocumentType] {
         &[DocumentType::PowerPoint]
@@ -155,14 +239,9 @@ impl ExcelExtractor {
     pub fn new() -> Self {
         Self
     }
-}
-
-impl DocumentExtractor for ExcelExtractor {
-    fn extract(&self, path: &Path) -> Result<ExtractResult, DocumentError> {
-        debug!("Extracting text from Excel document: {}", path.display());
-
-        let provider = ExcelProvider::open(path)?;
 
+    /// Build the extraction result for an opened workbook
+    fn extract_document(provider: &ExcelProvider) -> Result<ExtractResult, DocumentError> {
         // Extract sheets individually
         let sheet_names = provider.get_sheet_names()?;
         let mut pages = Vec::new();
@@ -181,6 +260,7 @@ impl DocumentExtractor for ExcelExtractor {
                         marker: None,
                     }],
                     tables: vec![], // TODO: Implement table extraction for Excel
+                    links: vec![],
                 };
                 pages.push(page);
             }
@@ -193,23 +273,21 @@ impl DocumentExtractor for ExcelExtractor {
                 text: String::new(),
                 elements: vec![],
                 tables: vec![],
+                links: vec![],
             });
         }
 
         // Extract metadata from Excel document
-        let excel_metadata = provider.get_metadata().unwrap_or_default();
         let metadata = ExtractMetadata {
-            title: excel_metadata.title,
-            author: excel_metadata.author,
-            subject: excel_metadata.subject,
-            creator: excel_metadata.creator,
-            total_pages: excel_metadata.total_sheets.max(1), // At least 1 page
-            created: excel_metadata.created,
-            modified: excel_metadata.modified,
+            language: language::detect(&pages_text(&pages)),
+            ..excel_metadata(provider.get_metadata().unwrap_or_default())
         };
 
+        let stats = ExtractStats::from_pages(&pages);
+
         Ok(ExtractResult {
-            filename: path
+            filename: provider
+                .get_path()
                 .file_name()
                 .unwrap_or_default()
                 .to_string_lossy()
@@ -217,10 +295,27 @@ impl DocumentExtractor for ExcelExtractor {
             format: "Excel Spreadsheet (.xlsx)".to_string(),
             pages,
             metadata,
+            stats,
             success: true,
             error: None,
         })
     }
+}
+
+impl DocumentExtractor for ExcelExtractor {
+    fn extract(&self, path: &Path) -> Result<ExtractResult, DocumentError> {
+        debug!("Extracting text from Excel document: {}", path.display());
+        Self::extract_document(&ExcelProvider::open(path)?)
+    }
+
+    fn extract_bytes(&self, data: &[u8]) -> Result<ExtractResult, DocumentError> {
+        debug!("Extracting text from in-memory Excel document");
+        Self::extract_document(&ExcelProvider::from_bytes(data)?)
+    }
+
+    fn extract_metadata_only(&self, path: &Path) -> Result<ExtractMetadata, DocumentError> {
+        ExcelProvider::read_metadata(path).map(excel_metadata)
+    }
 
     fn supported_types(&self) -> &[DocumentType] {
         &[DocumentType::Excel]
@@ -228,11 +323,20 @@ impl DocumentExtractor for ExcelExtractor {
 }
 
 /// PDF document extractor
-pub struct PdfExtractor;
+pub struct PdfExtractor {
+    page_range: Option<PageRange>,
+}
 
 impl PdfExtractor {
     pub fn new() -> Self {
-        Self
+        Self { page_range: None }
+    }
+
+    /// Only extract the pages in `page_range`; the other pages' content
+    /// streams are not parsed
+    pub fn with_page_range(mut self, page_range: PageRange) -> Self {
+        self.page_range = Some(page_range);
+        self
     }
 
     /// Extract pages from PDF text (legacy method, kept for compatibility)
@@ -281,6 +385,7 @@ impl PdfExtractor {
                     text: clean_text,
                     elements,
                     tables: vec![], // TODO: Implement table extraction for PDF
+                    links: vec![],
                 }
             })
             .collect()
@@ -295,7 +400,13 @@ impl DocumentExtractor for PdfExtractor {
         );
 
         // Use advanced PDF provider
-        let provider = PdfProvider::open_layout_critical(path)?;
+        let provider = PdfProvider::open_with_config(
+            path,
+            PdfExtractConfig {
+                page_range: self.page_range.clone(),
+                ..PdfExtractConfig::layout_critical()
+            },
+        )?;
 
         // Check if PDF is encrypted
         let encryption_info = provider.check_encryption()?;
@@ -315,27 +426,39 @@ impl DocumentExtractor for PdfExtractor {
             }
         };
 
-        // Extract advanced pages with tables
-        let pages = self.extract_advanced_pages(&provider, &full_text)?;
-
         // Extract comprehensive metadata
         let pdf_metadata = provider.get_metadata().unwrap_or_default();
-        let stats = provider.get_extraction_stats().ok();
+        // Statistics of a partial extraction only count the selected pages
+        let extraction_stats = self
+            .page_range
+            .is_none()
+            .then(|| provider.get_extraction_stats().ok())
+            .flatten();
+
+        // Extract advanced pages with tables
+        let page_numbers = self
+            .page_range
+            .as_ref()
+            .map(|range| range.select(pdf_metadata.page_count));
+        let pages = self.extract_advanced_pages(&provider, &full_text, page_numbers)?;
 
         let metadata = ExtractMetadata {
             title: pdf_metadata.title,
             author: pdf_metadata.author,
             subject: pdf_metadata.subject,
             creator: pdf_metadata.creator,
-            total_pages: if let Some(ref stats) = stats {
-                stats.total_pages
+            total_pages: if let Some(ref extraction_stats) = extraction_stats {
+                extraction_stats.total_pages
             } else {
                 pdf_metadata.page_count
             },
             created: pdf_metadata.created,
             modified: pdf_metadata.modified,
+            language: language::resolve(pdf_metadata.language.as_deref(), None, &full_text),
         };
 
+        let stats = ExtractStats::from_pages(&pages);
+
         Ok(ExtractResult {
             filename: path
                 .file_name()
@@ -345,11 +468,30 @@ impl DocumentExtractor for PdfExtractor {
             format: "PDF Document (.pdf)".to_string(),
             pages,
             metadata,
+            stats,
             success: true,
             error: None,
         })
     }
 
+    /// Read the Info dictionary and page tree without extracting any text
+    fn extract_metadata_only(&self, path: &Path) -> Result<ExtractMetadata, DocumentError> {
+        let pdf_metadata = PdfProvider::open(path)?.get_metadata()?;
+        Ok(ExtractMetadata {
+            title: pdf_metadata.title,
+            author: pdf_metadata.author,
+            subject: pdf_metadata.subject,
+            creator: pdf_metadata.creator,
+            total_pages: pdf_metadata.page_count,
+            created: pdf_metadata.created,
+            modified: pdf_metadata.modified,
+            language: pdf_metadata
+                .language
+                .as_deref()
+                .and_then(language::primary_subtag),
+        })
+    }
+
     fn supported_types(&self) -> &[DocumentType] {
         &[DocumentType::Pdf]
     }
@@ -357,15 +499,20 @@ impl DocumentExtractor for PdfExtractor {
 
 impl PdfExtractor {
     /// Extract pages with advanced features (tables, layout information)
+    ///
+    /// `page_numbers` holds the PDF page number of each text page when only
+    /// some pages were extracted.
     fn extract_advanced_pages(
         &self,
         provider: &PdfProvider,
         full_text: &str,
+        page_numbers: Option<Vec<usize>>,
     ) -> Result<Vec<ExtractedPage>, DocumentError> {
         debug!("Extracting pages with advanced features");
 
         // Try to extract tables first
         let pdf_tables = provider.extract_tables().unwrap_or_default();
+        let pdf_links = provider.extract_links().unwrap_or_default();
 
         // Split text into pages (simplified)
         let page_texts = if full_text.contains('\x0C') {
@@ -397,13 +544,30 @@ impl PdfExtractor {
             };
 
             pages.push(ExtractedPage {
-                number: page_num + 1,
+                number: page_numbers
+                    .as_ref()
+                    .and_then(|numbers| numbers.get(page_num).copied())
+                    .unwrap_or(page_num + 1),
                 text: page_text.clone(),
                 elements,
                 tables: page_tables,
+                links: vec![],
             });
         }
 
+        // Text split by form feeds may not line up with the PDF's pages;
+        // links past the last text page go on the last one
+        let last = pages.len().saturating_sub(1);
+        for (number, link) in pdf_links {
+            let index = pages.iter().position(|page| page.number == number);
+            if let Some(page) = pages.get_mut(index.unwrap_or(last)) {
+                page.links.push(super::ExtractedLink {
+                    text: link.text,
+                    url: link.url,
+                });
+            }
+        }
+
         Ok(pages)
     }
 
@@ -470,26 +634,29 @@ impl UniversalExtractor {
 
     /// Extract from any supported document format
     pub fn extract_from_path(path: &Path) -> Result<ExtractResult, DocumentError> {
-        let extractor = match path.extension().and_then(|s| s.to_str()) {
-            Some("docx") => Box::new(WordExtractor::new()) as Box<dyn DocumentExtractor>,
-            Some("pptx") => Box::new(PowerPointExtractor::new()) as Box<dyn DocumentExtractor>,
-            Some("pdf") => Box::new(PdfExtractor::new()) as Box<dyn DocumentExtractor>,
-            Some("xlsx") => Box::new(ExcelExtractor::new()) as Box<dyn DocumentExtractor>,
-            Some("txt") => Box::new(TextExtractor::new()) as Box<dyn DocumentExtractor>,
-            Some(ext) => {
-                return Err(DocumentError::UnsupportedFormat {
-                    format: ext.to_string(),
-                })
-            }
-            None => {
-                return Err(DocumentError::UnsupportedFormat {
-                    format: "none".to_string(),
-                })
-            }
-        };
-
+        let extractor = super::ExtractorFactory::create_extractor(path)?;
         extractor.extract(path)
     }
+
+    /// Extract only the pages or slides in `page_range` from any supported format
+    ///
+    /// Formats without pages are extracted whole, with a warning.
+    pub fn extract_pages(
+        path: &Path,
+        page_range: Option<&PageRange>,
+    ) -> Result<ExtractResult, DocumentError> {
+        let Some(doc_type) = DocumentType::detect(path) else {
+            return Err(crate::provider::unsupported_format(path));
+        };
+        if page_range.is_some() && !matches!(doc_type, DocumentType::Pdf | DocumentType::PowerPoint)
+        {
+            warn!(
+                "Page ranges only apply to PDF and PowerPoint; extracting all of {}",
+                path.display()
+            );
+        }
+        super::ExtractorFactory::extractor_with_pages(doc_type, page_range).extract(path)
+    }
 }
 
 impl Default for WordExtractor {
@@ -529,13 +696,9 @@ impl TextExtractor {
     pub fn new() -> Self {
         Self
     }
-}
-
-impl DocumentExtractor for TextExtractor {
-    fn extract(&self, path: &Path) -> Result<ExtractResult, DocumentError> {
-        debug!("Extracting text from plain text file: {}", path.display());
 
-        let provider = TextProvider::open(path)?;
+    /// Build the extraction result for an opened text file
+    fn extract_document(provider: &TextProvider) -> Result<ExtractResult, DocumentError> {
         let text = provider.get_text()?;
 
         // For text files, we treat the entire file as one page
@@ -549,10 +712,12 @@ impl DocumentExtractor for TextExtractor {
                 marker: None,
             }],
             tables: vec![], // Text files don't have tables
+            links: vec![],
         };
 
         let metadata = ExtractMetadata {
-            title: path
+            title: provider
+                .get_path()
                 .file_stem()
                 .and_then(|s| s.to_str())
                 .map(|s| s.to_string()),
@@ -562,21 +727,39 @@ impl DocumentExtractor for TextExtractor {
             total_pages: 1,
             created: None,
             modified: None,
+            language: language::detect(&page.text),
         };
 
+        let pages = vec![page];
+        let stats = ExtractStats::from_pages(&pages);
+
         Ok(ExtractResult {
-            filename: path
+            filename: provider
+                .get_path()
                 .file_name()
                 .and_then(|n| n.to_str())
                 .unwrap_or("unknown")
                 .to_string(),
             format: "text".to_string(),
-            pages: vec![page],
+            pages,
             metadata,
+            stats,
             success: true,
             error: None,
         })
     }
+}
+
+impl DocumentExtractor for TextExtractor {
+    fn extract(&self, path: &Path) -> Result<ExtractResult, DocumentError> {
+        debug!("Extracting text from plain text file: {}", path.display());
+        Self::extract_document(&TextProvider::open(path)?)
+    }
+
+    fn extract_bytes(&self, data: &[u8]) -> Result<ExtractResult, DocumentError> {
+        debug!("Extracting text from in-memory text");
+        Self::extract_document(&TextProvider::from_bytes(data)?)
+    }
 
     fn supported_types(&self) -> &[DocumentType] {
         &[DocumentType::Text]
@@ -588,3 +771,57 @@ impl Default for TextExtractor {
         Self::new()
     }
 }
+
+/// Convert Word document metadata to extraction metadata
+fn word_metadata(metadata: crate::word::WordMetadata) -> ExtractMetadata {
+    ExtractMetadata {
+        title: metadata.title,
+        author: metadata.author,
+        subject: metadata.subject,
+        creator: metadata.creator,
+        total_pages: metadata.total_pages.max(1), // At least 1 page
+        created: metadata.created,
+        modified: metadata.modified,
+        language: metadata
+            .language
+            .as_deref()
+            .and_then(language::primary_subtag),
+    }
+}
+
+/// Convert PowerPoint document metadata to extraction metadata
+fn powerpoint_metadata(metadata: crate::powerpoint::PowerPointMetadata) -> ExtractMetadata {
+    ExtractMetadata {
+        title: metadata.title,
+        author: metadata.author,
+        subject: metadata.subject,
+        creator: metadata.creator,
+        total_pages: metadata.total_slides.max(1), // At least 1 page
+        created: metadata.created,
+        modified: metadata.modified,
+        language: None,
+    }
+}
+
+/// Convert Excel document metadata to extraction metadata
+fn excel_metadata(metadata: crate::excel::ExcelMetadata) -> ExtractMetadata {
+    ExtractMetadata {
+        title: metadata.title,
+        author: metadata.author,
+        subject: metadata.subject,
+        creator: metadata.creator,
+        total_pages: metadata.total_sheets.max(1), // At least 1 page
+        created: metadata.created,
+        modified: metadata.modified,
+        language: None,
+    }
+}
+
+/// Text of every page, for language detection
+fn pages_text(pages: &[ExtractedPage]) -> String {
+    pages
+        .iter()
+        .map(|page| page.text.as_str())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
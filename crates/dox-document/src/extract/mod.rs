@@ -1,10 +1,15 @@
 //! Text extraction functionality for various document formats
 
+pub mod batch;
 pub mod extractors;
 
+use crate::page_range::PageRange;
 use crate::provider::{DocumentError, DocumentType};
 use serde::{Deserialize, Serialize};
 use std::path::Path;
+use unicode_width::UnicodeWidthStr;
+
+pub use batch::{BatchExtraction, BatchExtractor};
 
 /// Result of document text extraction
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,12 +22,190 @@ pub struct ExtractResult {
     pub pages: Vec<ExtractedPage>,
     /// Document metadata
     pub metadata: ExtractMetadata,
+    /// Length statistics of the extracted content
+    #[serde(default)]
+    pub stats: ExtractStats,
     /// Success status
     pub success: bool,
     /// Error message if extraction failed
     pub error: Option<String>,
 }
 
+impl ExtractResult {
+    /// Number of words in the extracted text
+    pub fn word_count(&self) -> usize {
+        self.stats.word_count
+    }
+
+    /// Combine the results of several files into one document titled `title`
+    ///
+    /// Pages keep their order and are renumbered from 1. Metadata fields
+    /// that differ between files are joined, creation takes the earliest
+    /// date and modification the latest. The result fails if any input did.
+    pub fn merge(results: Vec<ExtractResult>, title: &str) -> ExtractResult {
+        let filenames: Vec<&str> = results.iter().map(|r| r.filename.as_str()).collect();
+        let filename = filenames.join(", ");
+
+        let mut formats: Vec<&str> = Vec::new();
+        for result in &results {
+            if !formats.contains(&result.format.as_str()) {
+                formats.push(&result.format);
+            }
+        }
+        let format = match formats.as_slice() {
+            [format] => format.to_string(),
+            _ => format!("Combined ({} documents)", results.len()),
+        };
+
+        // Distinct values of a metadata field in input order
+        let joined = |field: fn(&ExtractMetadata) -> &Option<String>| {
+            let mut values: Vec<&str> = Vec::new();
+            for value in results.iter().filter_map(|r| field(&r.metadata).as_deref()) {
+                if !values.contains(&value) {
+                    values.push(value);
+                }
+            }
+            (!values.is_empty()).then(|| values.join(", "))
+        };
+        let author = joined(|m| &m.author);
+        let subject = joined(|m| &m.subject);
+        let creator = joined(|m| &m.creator);
+        let language = joined(|m| &m.language);
+        // Dates are ISO 8601, so they order as strings
+        let created = results
+            .iter()
+            .filter_map(|r| r.metadata.created.clone())
+            .min();
+        let modified = results
+            .iter()
+            .filter_map(|r| r.metadata.modified.clone())
+            .max();
+
+        let errors: Vec<String> = results
+            .iter()
+            .filter(|r| !r.success || r.error.is_some())
+            .map(|r| {
+                let error = r.error.as_deref().unwrap_or("extraction failed");
+                format!("{}: {}", r.filename, error)
+            })
+            .collect();
+        let success = results.iter().all(|r| r.success);
+
+        let pages: Vec<ExtractedPage> = results
+            .into_iter()
+            .flat_map(|r| r.pages)
+            .enumerate()
+            .map(|(index, page)| ExtractedPage {
+                number: index + 1,
+                ..page
+            })
+            .collect();
+
+        ExtractResult {
+            filename,
+            format,
+            metadata: ExtractMetadata {
+                title: Some(title.to_string()),
+                author,
+                subject,
+                creator,
+                total_pages: pages.len(),
+                created,
+                modified,
+                language,
+            },
+            stats: ExtractStats::from_pages(&pages),
+            pages,
+            success,
+            error: (!errors.is_empty()).then(|| errors.join("; ")),
+        }
+    }
+}
+
+/// Length statistics of extracted content
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct ExtractStats {
+    /// Number of words; each CJK character counts as one word
+    pub word_count: usize,
+    /// Number of characters, not counting whitespace
+    pub character_count: usize,
+    /// Number of text blocks (headings, paragraphs, list items, ...)
+    pub paragraph_count: usize,
+    /// Number of tables
+    pub table_count: usize,
+}
+
+impl ExtractStats {
+    /// Compute statistics over extracted pages
+    ///
+    /// Paragraphs are counted from the structured elements; pages without
+    /// elements count the blank-line separated blocks of their text.
+    pub fn from_pages(pages: &[ExtractedPage]) -> Self {
+        let mut stats = ExtractStats::default();
+
+        for page in pages {
+            stats.word_count += count_words(&page.text);
+            stats.character_count += page.text.chars().filter(|c| !c.is_whitespace()).count();
+            stats.paragraph_count += if page.elements.is_empty() {
+                page.text
+                    .split("\n\n")
+                    .filter(|block| !block.trim().is_empty())
+                    .count()
+            } else {
+                page.elements
+                    .iter()
+                    .filter(|element| {
+                        element.element_type != "table_marker" && !element.content.trim().is_empty()
+                    })
+                    .count()
+            };
+            stats.table_count += page.tables.len();
+        }
+
+        stats
+    }
+}
+
+/// Count words, treating every CJK character as a word of its own
+///
+/// Runs of other letters and digits are words; punctuation inside a run,
+/// as in "don't" or "e-mail", does not split it.
+fn count_words(text: &str) -> usize {
+    let mut count = 0;
+    let mut in_word = false;
+
+    for c in text.chars() {
+        if is_cjk(c) {
+            count += 1;
+            in_word = false;
+        } else if c.is_alphanumeric() {
+            if !in_word {
+                count += 1;
+                in_word = true;
+            }
+        } else if c.is_whitespace() {
+            in_word = false;
+        }
+    }
+
+    count
+}
+
+/// Check if a character is Hangul, kana or a CJK ideograph
+fn is_cjk(c: char) -> bool {
+    matches!(
+        c,
+        '\u{1100}'..='\u{11FF}'     // Hangul Jamo
+            | '\u{3040}'..='\u{30FF}' // Hiragana and Katakana
+            | '\u{3130}'..='\u{318F}' // Hangul Compatibility Jamo
+            | '\u{3400}'..='\u{4DBF}' // CJK Extension A
+            | '\u{4E00}'..='\u{9FFF}' // CJK Unified Ideographs
+            | '\u{AC00}'..='\u{D7A3}' // Hangul Syllables
+            | '\u{F900}'..='\u{FAFF}' // CJK Compatibility Ideographs
+            | '\u{20000}'..='\u{2A6DF}' // CJK Extension B
+    )
+}
+
 /// A single extracted page
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExtractedPage {
@@ -34,6 +217,9 @@ pub struct ExtractedPage {
     pub elements: Vec<ExtractedElement>,
     /// Tables found on this page
     pub tables: Vec<ExtractedTable>,
+    /// Hyperlinks found on this page
+    #[serde(default)]
+    pub links: Vec<ExtractedLink>,
 }
 
 /// A structured text element
@@ -45,10 +231,19 @@ pub struct ExtractedElement {
     pub content: String,
     /// Heading level (for headings)
     pub level: Option<u8>,
-    /// List marker (for list items)
+    /// List marker (for list items) or language (for code blocks)
     pub marker: Option<String>,
 }
 
+/// A hyperlink and the text it is attached to
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExtractedLink {
+    /// Linked text as displayed
+    pub text: String,
+    /// Link target; links within the document start with `#`
+    pub url: String,
+}
+
 /// An extracted table
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExtractedTable {
@@ -63,7 +258,7 @@ pub struct ExtractedTable {
 }
 
 /// Document metadata
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 pub struct ExtractMetadata {
     /// Document title
     pub title: Option<String>,
@@ -79,6 +274,10 @@ pub struct ExtractMetadata {
     pub created: Option<String>,
     /// Last modified date
     pub modified: Option<String>,
+    /// Primary language subtag (`ko`, `en`, ...), declared by the document
+    /// or detected from its text
+    #[serde(default)]
+    pub language: Option<String>,
 }
 
 /// Output format for extracted content
@@ -92,6 +291,10 @@ pub enum ExtractFormat {
     Markdown,
     /// HTML format with rich layout
     Html,
+    /// Rich Text Format for word processors without HTML import
+    Rtf,
+    /// CSV containing only the extracted tables
+    Csv,
 }
 
 impl ExtractFormat {
@@ -102,6 +305,8 @@ impl ExtractFormat {
             ExtractFormat::Json => "json",
             ExtractFormat::Markdown => "md",
             ExtractFormat::Html => "html",
+            ExtractFormat::Rtf => "rtf",
+            ExtractFormat::Csv => "csv",
         }
     }
 }
@@ -111,17 +316,35 @@ pub trait DocumentExtractor: Send + Sync {
     /// Extract content from a document
     fn extract(&self, path: &Path) -> Result<ExtractResult, DocumentError>;
 
+    /// Extract content from a document held in memory
+    ///
+    /// The result has an empty filename. The default writes the bytes to a
+    /// temporary file for extractors whose backend only reads from disk;
+    /// extractors that can parse memory override it.
+    fn extract_bytes(&self, data: &[u8]) -> Result<ExtractResult, DocumentError> {
+        let mut file = tempfile::NamedTempFile::new()?;
+        std::io::Write::write_all(&mut file, data)?;
+        let mut result = self.extract(file.path())?;
+        result.filename = String::new();
+        Ok(result)
+    }
+
+    /// Extract only the document metadata, skipping body text and tables
+    ///
+    /// The default runs a full extraction and discards the body; extractors
+    /// override it to read just the parts that hold the metadata, in which
+    /// case only a declared language is reported.
+    fn extract_metadata_only(&self, path: &Path) -> Result<ExtractMetadata, DocumentError> {
+        self.extract(path).map(|result| result.metadata)
+    }
+
     /// Get supported document types
     fn supported_types(&self) -> &[DocumentType];
 
     /// Check if this extractor supports the given file
     fn supports_file(&self, path: &Path) -> bool {
-        if let Some(ext) = path.extension().and_then(|s| s.to_str()) {
-            if let Some(doc_type) = DocumentType::from_extension(ext) {
-                return self.supported_types().contains(&doc_type);
-            }
-        }
-        false
+        DocumentType::detect(path)
+            .is_some_and(|doc_type| self.supported_types().contains(&doc_type))
     }
 }
 
@@ -130,31 +353,104 @@ pub struct ExtractorFactory;
 
 impl ExtractorFactory {
     /// Create an extractor for the given file
+    ///
+    /// The document type is sniffed from the file content, so misnamed files
+    /// are still handled; the extension is only used as a fallback.
     pub fn create_extractor(path: &Path) -> Result<Box<dyn DocumentExtractor>, DocumentError> {
-        let ext = path.extension().and_then(|s| s.to_str()).ok_or_else(|| {
-            DocumentError::UnsupportedFormat {
-                format: "none".to_string(),
-            }
-        })?;
+        match DocumentType::detect(path) {
+            Some(doc_type) => Ok(Self::extractor_for(doc_type)),
+            None => Err(crate::provider::unsupported_format(path)),
+        }
+    }
+
+    /// Create the extractor for a document type
+    pub fn extractor_for(doc_type: DocumentType) -> Box<dyn DocumentExtractor> {
+        Self::extractor_with_pages(doc_type, None)
+    }
 
-        match DocumentType::from_extension(ext) {
-            Some(DocumentType::Word) => Ok(Box::new(extractors::WordExtractor::new())),
-            Some(DocumentType::PowerPoint) => Ok(Box::new(extractors::PowerPointExtractor::new())),
-            Some(DocumentType::Pdf) => Ok(Box::new(extractors::PdfExtractor::new())),
-            Some(DocumentType::Excel) => Ok(Box::new(extractors::ExcelExtractor::new())),
-            Some(DocumentType::Text) => Ok(Box::new(extractors::TextExtractor::new())),
-            None => Err(DocumentError::UnsupportedFormat {
-                format: ext.to_string(),
-            }),
+    /// Create the extractor for a document type, limited to `page_range`
+    ///
+    /// Pages are PDF pages or PowerPoint slides; other formats have no
+    /// pages to select and are always extracted whole.
+    pub fn extractor_with_pages(
+        doc_type: DocumentType,
+        page_range: Option<&PageRange>,
+    ) -> Box<dyn DocumentExtractor> {
+        match (doc_type, page_range) {
+            (DocumentType::PowerPoint, Some(range)) => {
+                Box::new(extractors::PowerPointExtractor::new().with_page_range(range.clone()))
+            }
+            (DocumentType::Pdf, Some(range)) => {
+                Box::new(extractors::PdfExtractor::new().with_page_range(range.clone()))
+            }
+            (DocumentType::Word, _) => Box::new(extractors::WordExtractor::new()),
+            (DocumentType::PowerPoint, _) => Box::new(extractors::PowerPointExtractor::new()),
+            (DocumentType::Pdf, _) => Box::new(extractors::PdfExtractor::new()),
+            (DocumentType::Excel, _) => Box::new(extractors::ExcelExtractor::new()),
+            (DocumentType::Text, _) => Box::new(extractors::TextExtractor::new()),
         }
     }
 
+    /// Extract a document held in memory, e.g. one received over the network
+    ///
+    /// Word, PowerPoint, Excel and text documents are parsed from memory;
+    /// PDFs go through a temporary file.
+    pub fn extract_from_bytes(
+        data: &[u8],
+        doc_type: DocumentType,
+    ) -> Result<ExtractResult, DocumentError> {
+        Self::extractor_for(doc_type).extract_bytes(data)
+    }
+
     /// Get all supported file extensions
     pub fn supported_extensions() -> Vec<&'static str> {
         vec!["docx", "pptx", "pdf", "xlsx", "txt"]
     }
 }
 
+/// Stylesheet used by the HTML output unless custom CSS is given
+pub const DEFAULT_HTML_CSS: &str = "\
+body { font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif; line-height: 1.6; margin: 2rem; }\n\
+.document-header { border-bottom: 2px solid #e2e8f0; margin-bottom: 2rem; padding-bottom: 1rem; }\n\
+.document-title { font-size: 2rem; margin: 0 0 0.5rem 0; color: #1a202c; }\n\
+.document-meta { color: #718096; font-size: 0.875rem; }\n\
+.page { margin-bottom: 3rem; }\n\
+.page-header { font-size: 1.25rem; font-weight: 600; margin-bottom: 1rem; color: #2d3748; border-left: 4px solid #4299e1; padding-left: 1rem; }\n\
+.text-element { margin-bottom: 1rem; }\n\
+.heading-1 { font-size: 1.875rem; font-weight: 700; margin: 2rem 0 1rem 0; }\n\
+.heading-2 { font-size: 1.5rem; font-weight: 600; margin: 1.5rem 0 1rem 0; }\n\
+.heading-3 { font-size: 1.25rem; font-weight: 600; margin: 1.25rem 0 0.75rem 0; }\n\
+.paragraph { margin-bottom: 1rem; text-align: justify; }\n\
+.list-item { margin-left: 1.5rem; margin-bottom: 0.5rem; }\n\
+.extracted-table { margin: 2rem 0; overflow-x: auto; }\n\
+.extracted-table table { width: 100%; border-collapse: collapse; font-size: 0.875rem; background: white; box-shadow: 0 1px 3px rgba(0,0,0,0.1); }\n\
+.extracted-table th { background-color: #f7fafc; font-weight: 600; padding: 0.75rem; text-align: left; border: 1px solid #e2e8f0; }\n\
+.extracted-table td { padding: 0.75rem; border: 1px solid #e2e8f0; }\n\
+.extracted-table tr:nth-child(even) { background-color: #f9fafb; }\n\
+.extracted-table tr:hover { background-color: #edf2f7; }\n\
+.table-marker { color: #718096; font-style: italic; margin: 1rem 0; }\n";
+
+/// Options for HTML output
+#[derive(Debug, Clone)]
+pub struct HtmlFormatOptions {
+    /// Stylesheet replacing [`DEFAULT_HTML_CSS`]
+    pub css: Option<String>,
+    /// Add a linked table of contents built from the headings
+    pub include_toc: bool,
+    /// Emit a full document; when false only the body content is written
+    pub standalone: bool,
+}
+
+impl Default for HtmlFormatOptions {
+    fn default() -> Self {
+        HtmlFormatOptions {
+            css: None,
+            include_toc: false,
+            standalone: true,
+        }
+    }
+}
+
 /// Output formatter for different formats
 pub struct OutputFormatter;
 
@@ -166,25 +462,118 @@ impl OutputFormatter {
             ExtractFormat::Json => Self::format_json(result),
             ExtractFormat::Markdown => Self::format_markdown(result),
             ExtractFormat::Html => Self::format_html(result),
+            ExtractFormat::Rtf => Self::format_rtf(result),
+            ExtractFormat::Csv => Self::format_csv(result),
         }
     }
 
     /// Format as plain text
+    ///
+    /// Structured elements keep their shape: headings are underlined, list
+    /// items keep their marker and tables are laid out in aligned columns.
+    /// Pages without elements fall back to their raw text.
     fn format_text(result: &ExtractResult) -> Result<String, DocumentError> {
         let mut output = String::new();
 
         for page in &result.pages {
-            if !page.text.trim().is_empty() {
-                output.push_str(&page.text);
-                if !output.ends_with('\n') {
+            if page.elements.is_empty() {
+                if !page.text.trim().is_empty() {
+                    output.push_str(&page.text);
+                    if !output.ends_with('\n') {
+                        output.push('\n');
+                    }
+                }
+            } else {
+                // Rows between table markers are laid out from `page.tables`
+                let mut in_table = false;
+                for (element_idx, element) in page.elements.iter().enumerate() {
+                    if element.element_type == "table_marker" {
+                        in_table = element.content.trim() == "[TABLE]";
+                        continue;
+                    }
+                    if in_table {
+                        continue;
+                    }
+
+                    // A blank line closes a list before any other block
+                    if element.element_type != "list_item"
+                        && element_idx > 0
+                        && page.elements[element_idx - 1].element_type == "list_item"
+                    {
+                        output.push('\n');
+                    }
+
+                    match element.element_type.as_str() {
+                        "heading" => {
+                            let underline = match element.level.unwrap_or(1) {
+                                1 => "=",
+                                2 => "-",
+                                _ => "~",
+                            };
+                            output.push_str(&format!(
+                                "{}\n{}\n\n",
+                                element.content,
+                                underline.repeat(element.content.width().max(1))
+                            ));
+                        }
+                        "list_item" => {
+                            let marker = element.marker.as_deref().unwrap_or("-");
+                            output.push_str(&format!("{} {}\n", marker, element.content));
+                        }
+                        _ => {
+                            output.push_str(&format!("{}\n\n", element.content));
+                        }
+                    }
+                }
+            }
+
+            for table in &page.tables {
+                if !output.is_empty() && !output.ends_with("\n\n") {
                     output.push('\n');
                 }
+                output.push_str(&Self::text_table(table));
+                output.push('\n');
             }
         }
 
         Ok(output)
     }
 
+    /// Lay out a table in columns padded to their widest cell
+    ///
+    /// Widths are measured in terminal columns so wide characters such as
+    /// Hangul keep the columns aligned. The header row is underlined.
+    fn text_table(table: &ExtractedTable) -> String {
+        let cols = table.data.iter().map(Vec::len).max().unwrap_or(0);
+        let mut widths = vec![0; cols];
+        for row in &table.data {
+            for (col, cell) in row.iter().enumerate() {
+                widths[col] = widths[col].max(cell.width());
+            }
+        }
+
+        let mut output = String::new();
+        for (row_idx, row) in table.data.iter().enumerate() {
+            let cells: Vec<String> = widths
+                .iter()
+                .enumerate()
+                .map(|(col, &width)| {
+                    let cell = row.get(col).map(String::as_str).unwrap_or("");
+                    format!("{}{}", cell, " ".repeat(width - cell.width()))
+                })
+                .collect();
+            output.push_str(cells.join("  ").trim_end());
+            output.push('\n');
+
+            if row_idx == 0 && table.data.len() > 1 {
+                let rule: Vec<String> = widths.iter().map(|&width| "-".repeat(width)).collect();
+                output.push_str(&rule.join("  "));
+                output.push('\n');
+            }
+        }
+        output
+    }
+
     /// Format as JSON
     fn format_json(result: &ExtractResult) -> Result<String, DocumentError> {
         serde_json::to_string_pretty(result).map_err(|e| DocumentError::OperationFailed {
@@ -220,7 +609,15 @@ impl OutputFormatter {
 
             // Process structured elements if available
             if !page.elements.is_empty() {
-                for element in &page.elements {
+                for (element_idx, element) in page.elements.iter().enumerate() {
+                    // A blank line must close a list before any other block
+                    if element.element_type != "list_item"
+                        && element_idx > 0
+                        && page.elements[element_idx - 1].element_type == "list_item"
+                    {
+                        output.push('\n');
+                    }
+
                     match element.element_type.as_str() {
                         "heading" => {
                             let level = element.level.unwrap_or(1).min(6);
@@ -234,6 +631,19 @@ impl OutputFormatter {
                             let marker = element.marker.as_deref().unwrap_or("*");
                             output.push_str(&format!("{} {}\n", marker, element.content));
                         }
+                        "speaker_note" => {
+                            for line in element.content.lines() {
+                                output.push_str(&format!("> {}\n", line));
+                            }
+                            output.push('\n');
+                        }
+                        "code_block" => {
+                            output.push_str(&format!(
+                                "```{}\n{}\n```\n\n",
+                                element.marker.as_deref().unwrap_or(""),
+                                element.content
+                            ));
+                        }
                         _ => {
                             output.push_str(&format!("{}\n\n", element.content));
                         }
@@ -273,53 +683,37 @@ impl OutputFormatter {
 
     /// Format as HTML with table and layout preservation
     fn format_html(result: &ExtractResult) -> Result<String, DocumentError> {
-        let mut output = String::new();
-
-        // HTML document structure with metadata
-        output.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n");
-        output.push_str("    <meta charset=\"UTF-8\">\n");
-        output.push_str(
-            "    <meta name=\"viewport\" content=\"width=device-width, initial-scale=1.0\">\n",
-        );
+        Self::format_html_with(result, &HtmlFormatOptions::default())
+    }
 
-        // Document title from metadata
+    /// Format as HTML with a custom stylesheet, table of contents or as a fragment
+    pub fn format_html_with(
+        result: &ExtractResult,
+        options: &HtmlFormatOptions,
+    ) -> Result<String, DocumentError> {
+        let mut output = String::new();
         let title = result.metadata.title.as_deref().unwrap_or(&result.filename);
-        output.push_str(&format!(
-            "    <title>{}</title>\n",
-            Self::html_escape(title)
-        ));
 
-        // Embedded CSS for better table and layout rendering
-        output.push_str("    <style>\n");
-        output.push_str("        body { font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif; line-height: 1.6; margin: 2rem; }\n");
-        output.push_str("        .document-header { border-bottom: 2px solid #e2e8f0; margin-bottom: 2rem; padding-bottom: 1rem; }\n");
-        output.push_str(
-            "        .document-title { font-size: 2rem; margin: 0 0 0.5rem 0; color: #1a202c; }\n",
-        );
-        output.push_str("        .document-meta { color: #718096; font-size: 0.875rem; }\n");
-        output.push_str("        .page { margin-bottom: 3rem; }\n");
-        output.push_str("        .page-header { font-size: 1.25rem; font-weight: 600; margin-bottom: 1rem; color: #2d3748; border-left: 4px solid #4299e1; padding-left: 1rem; }\n");
-        output.push_str("        .text-element { margin-bottom: 1rem; }\n");
-        output.push_str("        .heading-1 { font-size: 1.875rem; font-weight: 700; margin: 2rem 0 1rem 0; }\n");
-        output.push_str("        .heading-2 { font-size: 1.5rem; font-weight: 600; margin: 1.5rem 0 1rem 0; }\n");
-        output.push_str("        .heading-3 { font-size: 1.25rem; font-weight: 600; margin: 1.25rem 0 0.75rem 0; }\n");
-        output.push_str("        .paragraph { margin-bottom: 1rem; text-align: justify; }\n");
-        output.push_str("        .list-item { margin-left: 1.5rem; margin-bottom: 0.5rem; }\n");
-        output.push_str("        .extracted-table { margin: 2rem 0; overflow-x: auto; }\n");
-        output.push_str("        .extracted-table table { width: 100%; border-collapse: collapse; font-size: 0.875rem; background: white; box-shadow: 0 1px 3px rgba(0,0,0,0.1); }\n");
-        output.push_str("        .extracted-table th { background-color: #f7fafc; font-weight: 600; padding: 0.75rem; text-align: left; border: 1px solid #e2e8f0; }\n");
-        output.push_str(
-            "        .extracted-table td { padding: 0.75rem; border: 1px solid #e2e8f0; }\n",
-        );
-        output.push_str(
-            "        .extracted-table tr:nth-child(even) { background-color: #f9fafb; }\n",
-        );
-        output.push_str("        .extracted-table tr:hover { background-color: #edf2f7; }\n");
-        output.push_str(
-            "        .table-marker { color: #718096; font-style: italic; margin: 1rem 0; }\n",
-        );
-        output.push_str("    </style>\n");
-        output.push_str("</head>\n<body>\n");
+        if options.standalone {
+            // HTML document structure with metadata
+            output.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n");
+            output.push_str("    <meta charset=\"UTF-8\">\n");
+            output.push_str(
+                "    <meta name=\"viewport\" content=\"width=device-width, initial-scale=1.0\">\n",
+            );
+            output.push_str(&format!(
+                "    <title>{}</title>\n",
+                Self::html_escape(title)
+            ));
+            Self::push_html_style(
+                &mut output,
+                options.css.as_deref().unwrap_or(DEFAULT_HTML_CSS),
+            );
+            output.push_str("</head>\n<body>\n");
+        } else if let Some(ref css) = options.css {
+            // Fragments leave styling to the host page unless CSS is given
+            Self::push_html_style(&mut output, css);
+        }
 
         // Document header with metadata
         output.push_str("    <div class=\"document-header\">\n");
@@ -363,6 +757,10 @@ impl OutputFormatter {
         }
         output.push_str("    </div>\n");
 
+        if options.include_toc {
+            Self::push_html_toc(&mut output, result);
+        }
+
         // Process each page
         for (page_idx, page) in result.pages.iter().enumerate() {
             output.push_str("    <div class=\"page\">\n");
@@ -376,20 +774,26 @@ impl OutputFormatter {
 
             // Process structured elements if available
             if !page.elements.is_empty() {
-                for element in &page.elements {
+                for (element_idx, element) in page.elements.iter().enumerate() {
                     output.push_str("        <div class=\"text-element\">\n");
 
                     match element.element_type.as_str() {
                         "heading" => {
-                            let level = element.level.unwrap_or(2).min(6).max(1);
+                            let level = element.level.unwrap_or(2).clamp(1, 6);
                             let class_name = match level {
                                 1 => "heading-1",
                                 2 => "heading-2",
                                 _ => "heading-3",
                             };
+                            let id = if options.include_toc {
+                                format!(" id=\"{}\"", Self::heading_id(page_idx, element_idx))
+                            } else {
+                                String::new()
+                            };
                             output.push_str(&format!(
-                                "            <h{} class=\"{}\">{}</h{}>\n",
+                                "            <h{}{} class=\"{}\">{}</h{}>\n",
                                 level,
+                                id,
                                 class_name,
                                 Self::html_escape(&element.content),
                                 level
@@ -500,12 +904,58 @@ impl OutputFormatter {
             output.push_str("    </div>\n");
         }
 
-        // Close HTML document
-        output.push_str("</body>\n</html>");
+        if options.standalone {
+            // Close HTML document
+            output.push_str("</body>\n</html>");
+        }
 
         Ok(output)
     }
 
+    /// Append a `<style>` element with each rule indented under `<head>`
+    fn push_html_style(output: &mut String, css: &str) {
+        output.push_str("    <style>\n");
+        for line in css.lines() {
+            output.push_str(&format!("        {}\n", line));
+        }
+        output.push_str("    </style>\n");
+    }
+
+    /// Append a table of contents linking to every heading
+    fn push_html_toc(output: &mut String, result: &ExtractResult) {
+        let headings: Vec<(usize, usize, &ExtractedElement)> = result
+            .pages
+            .iter()
+            .enumerate()
+            .flat_map(|(page_idx, page)| {
+                page.elements
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, element)| element.element_type == "heading")
+                    .map(move |(element_idx, element)| (page_idx, element_idx, element))
+            })
+            .collect();
+        if headings.is_empty() {
+            return;
+        }
+
+        output.push_str("    <nav class=\"toc\">\n        <ul>\n");
+        for (page_idx, element_idx, element) in headings {
+            output.push_str(&format!(
+                "            <li class=\"toc-level-{}\"><a href=\"#{}\">{}</a></li>\n",
+                element.level.unwrap_or(2).clamp(1, 6),
+                Self::heading_id(page_idx, element_idx),
+                Self::html_escape(&element.content)
+            ));
+        }
+        output.push_str("        </ul>\n    </nav>\n");
+    }
+
+    /// Anchor id of a heading, unique across pages
+    fn heading_id(page_idx: usize, element_idx: usize) -> String {
+        format!("heading-{}-{}", page_idx + 1, element_idx + 1)
+    }
+
     /// Escape HTML special characters
     fn html_escape(text: &str) -> String {
         text.replace('&', "&amp;")
@@ -514,4 +964,551 @@ impl OutputFormatter {
             .replace('"', "&quot;")
             .replace('\'', "&#39;")
     }
+
+    /// Format as RTF with bold, enlarged headings and bordered tables
+    ///
+    /// Pages are separated by page breaks. Font sizes are in half-points.
+    fn format_rtf(result: &ExtractResult) -> Result<String, DocumentError> {
+        let mut output = String::new();
+
+        output.push_str("{\\rtf1\\ansi\\ansicpg1252\\deff0\\uc1\n");
+        output.push_str("{\\fonttbl{\\f0\\fswiss Arial;}{\\f1\\fmodern Courier New;}}\n");
+
+        let mut info = String::new();
+        if let Some(ref title) = result.metadata.title {
+            info.push_str(&format!("{{\\title {}}}", Self::rtf_escape(title)));
+        }
+        if let Some(ref author) = result.metadata.author {
+            info.push_str(&format!("{{\\author {}}}", Self::rtf_escape(author)));
+        }
+        if !info.is_empty() {
+            output.push_str(&format!("{{\\info{}}}\n", info));
+        }
+
+        output.push_str("\\fs22\n");
+
+        if let Some(ref title) = result.metadata.title {
+            output.push_str(&format!(
+                "{{\\pard\\qc\\sa240\\b\\fs36 {}\\par}}\n",
+                Self::rtf_escape(title)
+            ));
+        }
+
+        for (page_idx, page) in result.pages.iter().enumerate() {
+            if page_idx > 0 {
+                output.push_str("\\page\n");
+            }
+
+            if !page.elements.is_empty() {
+                for element in &page.elements {
+                    let content = Self::rtf_escape(&element.content);
+                    match element.element_type.as_str() {
+                        "heading" => {
+                            let size = match element.level.unwrap_or(1) {
+                                1 => 32,
+                                2 => 28,
+                                _ => 24,
+                            };
+                            output.push_str(&format!(
+                                "{{\\pard\\keepn\\sb240\\sa120\\b\\fs{} {}\\par}}\n",
+                                size, content
+                            ));
+                        }
+                        "list_item" => {
+                            let marker = element.marker.as_deref().unwrap_or("\u{2022}");
+                            output.push_str(&format!(
+                                "{{\\pard\\li360\\fi-360\\sa60 {}\\tab {}\\par}}\n",
+                                Self::rtf_escape(marker),
+                                content
+                            ));
+                        }
+                        "code_block" => {
+                            output.push_str(&format!(
+                                "{{\\pard\\sa120\\f1\\fs20 {}\\par}}\n",
+                                content
+                            ));
+                        }
+                        _ => {
+                            output.push_str(&format!("{{\\pard\\sa120 {}\\par}}\n", content));
+                        }
+                    }
+                }
+            } else {
+                // Fallback to raw text if no structured elements
+                for paragraph in page.text.split("\n\n").filter(|p| !p.trim().is_empty()) {
+                    output.push_str(&format!(
+                        "{{\\pard\\sa120 {}\\par}}\n",
+                        Self::rtf_escape(&paragraph.trim().replace('\n', " "))
+                    ));
+                }
+            }
+
+            for table in &page.tables {
+                Self::push_rtf_table(&mut output, table);
+            }
+        }
+
+        output.push('}');
+        Ok(output)
+    }
+
+    /// Append a table as RTF rows, with the first row as a bold repeating header
+    fn push_rtf_table(output: &mut String, table: &ExtractedTable) {
+        const TABLE_WIDTH: usize = 9000;
+
+        let cols = table.data.iter().map(Vec::len).max().unwrap_or(0);
+        if cols == 0 {
+            return;
+        }
+        let cell_width = TABLE_WIDTH / cols;
+
+        for (row_idx, row) in table.data.iter().enumerate() {
+            output.push_str("\\trowd\\trgaph108");
+            if row_idx == 0 {
+                output.push_str("\\trhdr");
+            }
+            for col in 1..=cols {
+                output.push_str(&format!(
+                    "\\clbrdrt\\brdrs\\clbrdrl\\brdrs\\clbrdrb\\brdrs\\clbrdrr\\brdrs\\cellx{}",
+                    cell_width * col
+                ));
+            }
+            output.push_str("\n\\pard\\intbl");
+            for col in 0..cols {
+                let cell = Self::rtf_escape(row.get(col).map(String::as_str).unwrap_or(""));
+                if row_idx == 0 {
+                    output.push_str(&format!(" {{\\b {}}}\\cell", cell));
+                } else {
+                    output.push_str(&format!(" {}\\cell", cell));
+                }
+            }
+            output.push_str("\\row\n");
+        }
+        output.push_str("\\pard\\par\n");
+    }
+
+    /// Format the tables of all pages as CSV
+    ///
+    /// Each table is one block preceded by a `# Page N - Table M` line, and
+    /// blocks are separated by a blank line. Text outside tables is dropped.
+    fn format_csv(result: &ExtractResult) -> Result<String, DocumentError> {
+        let mut blocks = Vec::new();
+
+        for page in &result.pages {
+            for (table_idx, table) in page.tables.iter().enumerate() {
+                let mut block = format!("# Page {} - Table {}\n", page.number, table_idx + 1);
+                for row in &table.data {
+                    let fields: Vec<String> =
+                        row.iter().map(|cell| Self::csv_field(cell)).collect();
+                    block.push_str(&fields.join(","));
+                    block.push('\n');
+                }
+                blocks.push(block);
+            }
+        }
+
+        Ok(blocks.join("\n"))
+    }
+
+    /// Quote a CSV field when it contains a delimiter, quote or line break
+    fn csv_field(value: &str) -> String {
+        if value.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", value.replace('"', "\"\""))
+        } else {
+            value.to_string()
+        }
+    }
+
+    /// Escape RTF control characters and encode non-ASCII text as `\u` escapes
+    ///
+    /// Each `\uN` is followed by a `?` fallback for readers without Unicode
+    /// support; characters outside the BMP are written as surrogate pairs.
+    fn rtf_escape(text: &str) -> String {
+        let mut escaped = String::with_capacity(text.len());
+        for c in text.chars() {
+            match c {
+                '\\' => escaped.push_str("\\\\"),
+                '{' => escaped.push_str("\\{"),
+                '}' => escaped.push_str("\\}"),
+                '\n' => escaped.push_str("\\line "),
+                '\t' => escaped.push_str("\\tab "),
+                '\r' => {}
+                c if c.is_ascii() => escaped.push(c),
+                c => {
+                    let mut units = [0u16; 2];
+                    for unit in c.encode_utf16(&mut units) {
+                        // RTF takes signed 16-bit values
+                        escaped.push_str(&format!("\\u{}?", *unit as i16));
+                    }
+                }
+            }
+        }
+        escaped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn element(element_type: &str, content: &str, level: Option<u8>) -> ExtractedElement {
+        ExtractedElement {
+            element_type: element_type.to_string(),
+            content: content.to_string(),
+            level,
+            marker: None,
+        }
+    }
+
+    #[test]
+    fn test_merge_results() {
+        let document = |filename: &str, texts: &[&str], author: &str, created: &str| {
+            let pages: Vec<ExtractedPage> = texts
+                .iter()
+                .enumerate()
+                .map(|(index, text)| ExtractedPage {
+                    number: index + 1,
+                    text: text.to_string(),
+                    elements: vec![element("paragraph", text, None)],
+                    tables: vec![],
+                    links: vec![],
+                })
+                .collect();
+            ExtractResult {
+                filename: filename.to_string(),
+                format: "PDF Document (.pdf)".to_string(),
+                metadata: ExtractMetadata {
+                    title: Some(filename.to_string()),
+                    author: Some(author.to_string()),
+                    total_pages: pages.len(),
+                    created: Some(created.to_string()),
+                    modified: Some(created.to_string()),
+                    ..ExtractMetadata::default()
+                },
+                stats: ExtractStats::from_pages(&pages),
+                pages,
+                success: true,
+                error: None,
+            }
+        };
+
+        let merged = ExtractResult::merge(
+            vec![
+                document("a.pdf", &["first", "second"], "Kim", "2024-03-01T00:00:00Z"),
+                document("b.pdf", &["third"], "Lee", "2023-12-31T00:00:00Z"),
+            ],
+            "Quarterly pack",
+        );
+
+        let pages: Vec<(usize, &str)> = merged
+            .pages
+            .iter()
+            .map(|page| (page.number, page.text.as_str()))
+            .collect();
+        assert_eq!(pages, vec![(1, "first"), (2, "second"), (3, "third")]);
+        assert_eq!(merged.metadata.total_pages, 3);
+        assert_eq!(merged.metadata.title.as_deref(), Some("Quarterly pack"));
+        assert_eq!(merged.metadata.author.as_deref(), Some("Kim, Lee"));
+        assert_eq!(
+            merged.metadata.created.as_deref(),
+            Some("2023-12-31T00:00:00Z")
+        );
+        assert_eq!(
+            merged.metadata.modified.as_deref(),
+            Some("2024-03-01T00:00:00Z")
+        );
+        assert_eq!(merged.filename, "a.pdf, b.pdf");
+        assert_eq!(merged.format, "PDF Document (.pdf)");
+        assert_eq!(merged.stats.paragraph_count, 3);
+        assert!(merged.success && merged.error.is_none());
+
+        let markdown = OutputFormatter::format(&merged, ExtractFormat::Markdown).unwrap();
+        assert!(markdown.starts_with("# Quarterly pack\n"));
+        assert!(markdown.contains("## Page 3\n\nthird"));
+    }
+
+    #[test]
+    fn test_format_rtf() {
+        let result = ExtractResult {
+            filename: "report.docx".to_string(),
+            format: "docx".to_string(),
+            pages: vec![ExtractedPage {
+                number: 1,
+                text: String::new(),
+                elements: vec![
+                    element("heading", "Summary", Some(1)),
+                    element("paragraph", "Braces {x} and C:\\path", None),
+                    element("paragraph", "보고서 😀", None),
+                ],
+                tables: vec![ExtractedTable {
+                    index: 0,
+                    data: vec![
+                        vec!["Name".to_string(), "Qty".to_string()],
+                        vec!["Apple".to_string(), "3".to_string()],
+                    ],
+                    rows: 2,
+                    cols: 2,
+                }],
+                links: vec![],
+            }],
+            metadata: ExtractMetadata::default(),
+            stats: ExtractStats::default(),
+            success: true,
+            error: None,
+        };
+
+        let rtf = OutputFormatter::format(&result, ExtractFormat::Rtf).unwrap();
+        assert_eq!(ExtractFormat::Rtf.extension(), "rtf");
+
+        assert!(rtf.starts_with("{\\rtf1"));
+        assert!(rtf.ends_with('}'));
+        assert!(rtf.contains("\\b\\fs32 Summary\\par"));
+        assert!(rtf.contains("Braces \\{x\\} and C:\\\\path"));
+        // 보 is U+BCF4 and the emoji is a surrogate pair
+        assert!(rtf.contains("\\u-17164?"));
+        assert!(rtf.contains("\\u-10179?\\u-8704?"));
+        assert!(rtf.is_ascii());
+
+        assert!(rtf.contains("\\trowd"));
+        assert!(rtf.contains("\\trhdr"));
+        assert!(rtf.contains("\\cellx4500\\clbrdrt"));
+        assert!(rtf.contains("{\\b Name}\\cell {\\b Qty}\\cell\\row"));
+        assert!(rtf.contains("\\intbl Apple\\cell 3\\cell\\row"));
+    }
+
+    #[test]
+    fn test_format_text_keeps_structure() {
+        let mut bullet = element("list_item", "First point", None);
+        bullet.marker = Some("*".to_string());
+        let result = ExtractResult {
+            filename: "notes.docx".to_string(),
+            format: "docx".to_string(),
+            pages: vec![ExtractedPage {
+                number: 1,
+                text: "raw text that should not be used".to_string(),
+                elements: vec![
+                    element("heading", "개요", Some(1)),
+                    element("heading", "Details", Some(2)),
+                    bullet,
+                    element("paragraph", "Closing words.", None),
+                    // The table's rows as they appear in the page text
+                    element("table_marker", "[TABLE]", None),
+                    element("paragraph", "Name\t지역", None),
+                    element("paragraph", "Kim\tSeoul", None),
+                    element("table_marker", "[/TABLE]", None),
+                ],
+                tables: vec![table(0, &[&["Name", "지역"], &["Kim", "Seoul"]])],
+                links: vec![],
+            }],
+            metadata: ExtractMetadata::default(),
+            stats: ExtractStats::default(),
+            success: true,
+            error: None,
+        };
+
+        let text = OutputFormatter::format(&result, ExtractFormat::Text).unwrap();
+        assert_eq!(
+            text,
+            "개요\n====\n\n\
+             Details\n-------\n\n\
+             * First point\n\n\
+             Closing words.\n\n\
+             Name  지역\n\
+             ----  -----\n\
+             Kim   Seoul\n\n"
+        );
+    }
+
+    #[test]
+    fn test_format_text_falls_back_to_raw_text() {
+        let result = ExtractResult {
+            filename: "plain.txt".to_string(),
+            format: "txt".to_string(),
+            pages: vec![ExtractedPage {
+                number: 1,
+                text: "line one\nline two".to_string(),
+                elements: vec![],
+                tables: vec![],
+                links: vec![],
+            }],
+            metadata: ExtractMetadata::default(),
+            stats: ExtractStats::default(),
+            success: true,
+            error: None,
+        };
+
+        let text = OutputFormatter::format(&result, ExtractFormat::Text).unwrap();
+        assert_eq!(text, "line one\nline two\n");
+    }
+
+    fn table(index: usize, data: &[&[&str]]) -> ExtractedTable {
+        ExtractedTable {
+            index,
+            data: data
+                .iter()
+                .map(|row| row.iter().map(|cell| cell.to_string()).collect())
+                .collect(),
+            rows: data.len(),
+            cols: data.first().map_or(0, |row| row.len()),
+        }
+    }
+
+    #[test]
+    fn test_format_csv() {
+        let page = |number: usize, tables: Vec<ExtractedTable>| ExtractedPage {
+            number,
+            text: "Body text that is not part of a table".to_string(),
+            elements: vec![],
+            tables,
+            links: vec![],
+        };
+        let result = ExtractResult {
+            filename: "sales.pdf".to_string(),
+            format: "pdf".to_string(),
+            pages: vec![
+                page(
+                    1,
+                    vec![table(0, &[&["City", "Sales"], &["Seoul, KR", "1,200"]])],
+                ),
+                page(2, vec![]),
+                page(
+                    3,
+                    vec![table(0, &[&["Note"], &["Say \"hi\""], &["two\nlines"]])],
+                ),
+            ],
+            metadata: ExtractMetadata::default(),
+            stats: ExtractStats::default(),
+            success: true,
+            error: None,
+        };
+
+        let csv = OutputFormatter::format(&result, ExtractFormat::Csv).unwrap();
+        assert_eq!(ExtractFormat::Csv.extension(), "csv");
+        assert_eq!(
+            csv,
+            "# Page 1 - Table 1\n\
+             City,Sales\n\
+             \"Seoul, KR\",\"1,200\"\n\
+             \n\
+             # Page 3 - Table 1\n\
+             Note\n\
+             \"Say \"\"hi\"\"\"\n\
+             \"two\nlines\"\n"
+        );
+    }
+
+    #[test]
+    fn test_stats_for_english_text() {
+        let pages = vec![ExtractedPage {
+            number: 1,
+            text: "Annual Report\nWe don't ship e-mail clients.\nRevenue grew 12% in 2024."
+                .to_string(),
+            elements: vec![
+                element("heading", "Annual Report", Some(1)),
+                element("paragraph", "We don't ship e-mail clients.", None),
+                element("paragraph", "Revenue grew 12% in 2024.", None),
+            ],
+            tables: vec![table(0, &[&["A", "B"]])],
+            links: vec![],
+        }];
+
+        let stats = ExtractStats::from_pages(&pages);
+        assert_eq!(stats.word_count, 12);
+        assert_eq!(stats.character_count, 58);
+        assert_eq!(stats.paragraph_count, 3);
+        assert_eq!(stats.table_count, 1);
+    }
+
+    #[test]
+    fn test_stats_count_korean_characters_as_words() {
+        let pages = vec![ExtractedPage {
+            number: 1,
+            text: "분기 보고서\n\n매출이 증가했습니다. Rust로 작성\n".to_string(),
+            elements: vec![],
+            tables: vec![],
+            links: vec![],
+        }];
+
+        let stats = ExtractStats::from_pages(&pages);
+        // 17 Hangul syllables plus the word "Rust"
+        assert_eq!(stats.word_count, 18);
+        assert_eq!(stats.character_count, 22);
+        assert_eq!(stats.paragraph_count, 2);
+        assert_eq!(stats.table_count, 0);
+
+        let result = ExtractResult {
+            filename: "report.txt".to_string(),
+            format: "text".to_string(),
+            stats,
+            pages,
+            metadata: ExtractMetadata::default(),
+            success: true,
+            error: None,
+        };
+        assert_eq!(result.word_count(), 18);
+    }
+
+    fn html_result() -> ExtractResult {
+        ExtractResult {
+            filename: "guide.docx".to_string(),
+            format: "docx".to_string(),
+            pages: vec![ExtractedPage {
+                number: 1,
+                text: String::new(),
+                elements: vec![
+                    element("heading", "Setup", Some(1)),
+                    element("paragraph", "Install the tools.", None),
+                    element("heading", "Usage & Tips", Some(2)),
+                ],
+                tables: vec![],
+                links: vec![],
+            }],
+            metadata: ExtractMetadata::default(),
+            stats: ExtractStats::default(),
+            success: true,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_html_custom_css() {
+        let options = HtmlFormatOptions {
+            css: Some("body { color: #003366; }\nh1 { font-family: serif; }".to_string()),
+            ..HtmlFormatOptions::default()
+        };
+        let html = OutputFormatter::format_html_with(&html_result(), &options).unwrap();
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("    <style>\n        body { color: #003366; }\n        h1 { font-family: serif; }\n    </style>\n"));
+        assert!(!html.contains(".document-header {"));
+
+        let default_html = OutputFormatter::format(&html_result(), ExtractFormat::Html).unwrap();
+        assert!(default_html.contains(".document-header {"));
+        assert!(!default_html.contains("class=\"toc\""));
+        assert!(!default_html.contains(" id=\"heading-"));
+    }
+
+    #[test]
+    fn test_html_fragment_with_toc() {
+        let options = HtmlFormatOptions {
+            include_toc: true,
+            standalone: false,
+            ..HtmlFormatOptions::default()
+        };
+        let html = OutputFormatter::format_html_with(&html_result(), &options).unwrap();
+
+        assert!(!html.contains("<!DOCTYPE"));
+        assert!(!html.contains("<head>"));
+        assert!(!html.contains("<body>"));
+        assert!(!html.contains("</html>"));
+        assert!(!html.contains("<style>"));
+        assert!(html.starts_with("    <div class=\"document-header\">"));
+
+        assert!(html.contains("<li class=\"toc-level-1\"><a href=\"#heading-1-1\">Setup</a></li>"));
+        assert!(html.contains(
+            "<li class=\"toc-level-2\"><a href=\"#heading-1-3\">Usage &amp; Tips</a></li>"
+        ));
+        assert!(html.contains("<h1 id=\"heading-1-1\" class=\"heading-1\">Setup</h1>"));
+        assert!(html.contains("<h2 id=\"heading-1-3\" class=\"heading-2\">Usage &amp; Tips</h2>"));
+    }
 }
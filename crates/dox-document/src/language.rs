@@ -0,0 +1,121 @@
+//! Document language from declared tags or the text itself
+
+/// Share of CJK characters among all letters above which text counts as
+/// East Asian
+///
+/// A Hangul syllable is a whole word part while a Latin letter is not, so
+/// Korean text with English terms mixed in stays well above this.
+const CJK_RATIO: f64 = 0.2;
+
+/// Primary subtag of a language tag such as `ko-KR`, lowercased
+pub fn primary_subtag(tag: &str) -> Option<String> {
+    let primary = tag.trim().split(['-', '_']).next()?.to_ascii_lowercase();
+    (!primary.is_empty() && primary.chars().all(|c| c.is_ascii_alphabetic())).then_some(primary)
+}
+
+/// Guess the language of `text` from the scripts it is written in
+///
+/// Mostly Hangul text is `ko` and Latin text is `en`; anything else,
+/// including text without letters, gives `None`.
+pub fn detect(text: &str) -> Option<String> {
+    let mut letters = 0usize;
+    let mut cjk = 0usize;
+    let mut hangul = 0usize;
+    let mut latin = 0usize;
+
+    for c in text.chars().filter(|c| c.is_alphabetic()) {
+        letters += 1;
+        if is_hangul(c) {
+            hangul += 1;
+            cjk += 1;
+        } else if is_cjk(c) {
+            cjk += 1;
+        } else if c.is_ascii_alphabetic() || ('\u{00C0}'..='\u{024F}').contains(&c) {
+            latin += 1;
+        }
+    }
+
+    if letters == 0 {
+        return None;
+    }
+    if cjk as f64 / letters as f64 >= CJK_RATIO {
+        (hangul > 0).then(|| "ko".to_string())
+    } else if latin * 2 > letters {
+        Some("en".to_string())
+    } else {
+        None
+    }
+}
+
+/// Language of a document from its declared tags and text
+///
+/// `east_asian` is the tag declared for East Asian text (Word's
+/// `w:eastAsia`); it wins over `declared` when the text is mostly CJK.
+/// Without a usable tag the language is detected from the text.
+pub fn resolve(declared: Option<&str>, east_asian: Option<&str>, text: &str) -> Option<String> {
+    let detected = detect(text);
+    let east_asian = east_asian
+        .filter(|_| detected.as_deref() == Some("ko"))
+        .and_then(primary_subtag);
+
+    east_asian
+        .or_else(|| declared.and_then(primary_subtag))
+        .or(detected)
+}
+
+fn is_hangul(c: char) -> bool {
+    matches!(c,
+        '\u{AC00}'..='\u{D7A3}' // syllables
+        | '\u{1100}'..='\u{11FF}' // jamo
+        | '\u{3130}'..='\u{318F}') // compatibility jamo
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{4E00}'..='\u{9FFF}' // unified ideographs
+        | '\u{3400}'..='\u{4DBF}' // extension A
+        | '\u{3040}'..='\u{30FF}') // hiragana and katakana
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_language() {
+        assert_eq!(detect("분기별 매출 보고서입니다").as_deref(), Some("ko"));
+        // English product names do not outweigh the Korean text
+        assert_eq!(
+            detect("Excel 파일을 PowerPoint 발표 자료로 변환합니다").as_deref(),
+            Some("ko")
+        );
+        assert_eq!(
+            detect("Quarterly sales report for the board").as_deref(),
+            Some("en")
+        );
+        assert_eq!(detect("12,345 / 67.8%"), None);
+    }
+
+    #[test]
+    fn test_resolve_language() {
+        assert_eq!(primary_subtag("ko-KR").as_deref(), Some("ko"));
+        assert_eq!(primary_subtag("EN_us").as_deref(), Some("en"));
+        assert_eq!(primary_subtag(""), None);
+
+        // The declared tag wins over detection
+        assert_eq!(
+            resolve(Some("de-DE"), None, "Quarterly report").as_deref(),
+            Some("de")
+        );
+        // Word marks Korean runs with w:eastAsia while w:val stays en-US
+        assert_eq!(
+            resolve(Some("en-US"), Some("ko-KR"), "분기별 보고서").as_deref(),
+            Some("ko")
+        );
+        assert_eq!(
+            resolve(Some("en-US"), Some("ko-KR"), "Quarterly report").as_deref(),
+            Some("en")
+        );
+        assert_eq!(resolve(None, None, "분기별 보고서").as_deref(), Some("ko"));
+    }
+}
@@ -5,36 +5,49 @@
 //! Microsoft Office document formats and PDF documents.
 
 pub mod compat;
+pub mod diff;
 pub mod excel;
 pub mod extract;
+pub mod language;
 pub mod markdown;
+pub mod page_range;
 pub mod pdf;
 pub mod powerpoint;
+pub mod progress;
 pub mod provider;
 pub mod replace;
 pub mod template;
 pub mod text;
 pub mod utils;
 pub mod word;
+pub mod word_structure;
 
 // Re-export main types
+pub use diff::DocumentDiff;
 pub use excel::ExcelProvider;
+pub use page_range::{PageRange, ParsePageRangeError};
 pub use pdf::{PdfMetadata, PdfProvider};
-pub use powerpoint::PowerPointProvider;
-pub use provider::{create_provider, DocumentError, DocumentProvider, DocumentType};
+pub use powerpoint::{PowerPointProvider, SlideText};
+pub use progress::{CancellationToken, ProgressCallback};
+pub use provider::{
+    create_provider, create_provider_from_bytes, DocumentError, DocumentProvider, DocumentType,
+    PlaceholderMatch,
+};
 pub use text::TextProvider;
 pub use utils::{
     create_zip, extract_zip, is_file_locked, is_office_document, is_office_temp_file,
     validate_file_access,
 };
 pub use word::WordProvider;
+pub use word_structure::{RunFormat, WordDocumentModel, WordParagraph, WordRun};
 
 // Re-export compatibility layer
 pub use compat::{Document, DocumentOps};
 
 // Re-export document processing modules
 pub use extract::{
-    ExtractFormat, ExtractMetadata, ExtractResult, ExtractorFactory, OutputFormatter,
+    BatchExtraction, BatchExtractor, ExtractFormat, ExtractMetadata, ExtractResult, ExtractStats,
+    ExtractorFactory, HtmlFormatOptions, OutputFormatter,
 };
 pub use markdown::*;
 pub use replace::*;
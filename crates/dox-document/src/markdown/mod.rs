@@ -1,4 +1,16 @@
-// TODO: Implement markdown parsing and conversion
+//! Markdown parsing into the extraction data model
+//!
+//! Converts Markdown into an [`ExtractResult`] so that Markdown produced by
+//! `OutputFormatter` (or written by hand) can be read back into the same
+//! structure the document extractors produce.
+
+use crate::extract::{
+    ExtractMetadata, ExtractResult, ExtractStats, ExtractedElement, ExtractedPage, ExtractedTable,
+};
+use pulldown_cmark::{CodeBlockKind, Event, Options, Parser, Tag, TagEnd};
+use std::path::Path;
+
+/// Markdown parser producing structured extraction results
 pub struct MarkdownParser;
 
 impl Default for MarkdownParser {
@@ -12,7 +24,261 @@ impl MarkdownParser {
         MarkdownParser
     }
 
-    pub fn parse(&self, _content: &str) -> anyhow::Result<()> {
-        anyhow::bail!("Markdown parsing not yet implemented")
+    /// Parse a Markdown file, recording its name in the result
+    pub fn parse_file(&self, path: &Path) -> anyhow::Result<ExtractResult> {
+        let content = std::fs::read_to_string(path)?;
+        let mut result = self.parse(&content)?;
+        result.filename = path
+            .file_name()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default()
+            .to_string();
+        Ok(result)
+    }
+
+    /// Parse Markdown content into a single-page extraction result
+    ///
+    /// Headings, paragraphs and list items become elements with the
+    /// `heading`, `paragraph` and `list_item` types; fenced and indented code
+    /// becomes a `code_block` element whose marker holds the language. Tables
+    /// are collected into the page's `tables`.
+    pub fn parse(&self, content: &str) -> anyhow::Result<ExtractResult> {
+        let mut elements = Vec::new();
+        let mut tables = Vec::new();
+
+        let mut text = String::new();
+        // Next item number for each open list (`None` for bullet lists)
+        let mut lists: Vec<Option<u64>> = Vec::new();
+        let mut code_language: Option<String> = None;
+        let mut table_rows: Vec<Vec<String>> = Vec::new();
+        let mut row: Vec<String> = Vec::new();
+
+        let mut options = Options::empty();
+        options.insert(Options::ENABLE_TABLES);
+        options.insert(Options::ENABLE_STRIKETHROUGH);
+
+        for event in Parser::new_ext(content, options) {
+            match event {
+                Event::Start(Tag::Heading { .. }) | Event::Start(Tag::CodeBlock(_))
+                    if lists.is_empty() =>
+                {
+                    text.clear();
+                    if let Event::Start(Tag::CodeBlock(kind)) = event {
+                        code_language = match kind {
+                            CodeBlockKind::Fenced(lang) if !lang.is_empty() => {
+                                Some(lang.to_string())
+                            }
+                            _ => None,
+                        };
+                    }
+                }
+                Event::End(TagEnd::Heading(level)) => {
+                    elements.push(element("heading", &text, Some(level as u8), None));
+                    text.clear();
+                }
+                Event::Start(Tag::Paragraph) if lists.is_empty() => text.clear(),
+                Event::End(TagEnd::Paragraph) => {
+                    if lists.is_empty() {
+                        if !text.trim().is_empty() {
+                            elements.push(element("paragraph", &text, None, None));
+                        }
+                        text.clear();
+                    } else {
+                        // Separate paragraphs of a loose list item
+                        text.push(' ');
+                    }
+                }
+                Event::End(TagEnd::CodeBlock) if lists.is_empty() => {
+                    elements.push(ExtractedElement {
+                        element_type: "code_block".to_string(),
+                        content: text.trim_end_matches('\n').to_string(),
+                        level: None,
+                        marker: code_language.take(),
+                    });
+                    text.clear();
+                }
+                Event::Start(Tag::List(start)) => {
+                    // A nested list ends the text of its parent item
+                    flush_list_item(&mut elements, &mut lists, &mut text);
+                    lists.push(start);
+                }
+                Event::End(TagEnd::List(_)) => {
+                    lists.pop();
+                }
+                Event::Start(Tag::Item) => text.clear(),
+                Event::End(TagEnd::Item) => flush_list_item(&mut elements, &mut lists, &mut text),
+                Event::Start(Tag::Table(_)) => table_rows.clear(),
+                Event::Start(Tag::TableHead) | Event::Start(Tag::TableRow) => row.clear(),
+                Event::Start(Tag::TableCell) => text.clear(),
+                Event::End(TagEnd::TableCell) => {
+                    row.push(text.trim().to_string());
+                    text.clear();
+                }
+                Event::End(TagEnd::TableHead) | Event::End(TagEnd::TableRow) => {
+                    table_rows.push(std::mem::take(&mut row));
+                }
+                Event::End(TagEnd::Table) => {
+                    let data = std::mem::take(&mut table_rows);
+                    tables.push(ExtractedTable {
+                        index: tables.len(),
+                        rows: data.len(),
+                        cols: data.iter().map(Vec::len).max().unwrap_or(0),
+                        data,
+                    });
+                }
+                Event::Text(t) | Event::Code(t) => text.push_str(&t),
+                Event::SoftBreak => text.push(' '),
+                Event::HardBreak => text.push('\n'),
+                _ => {}
+            }
+        }
+
+        let mut page_text: Vec<String> = elements.iter().map(|e| e.content.clone()).collect();
+        for table in &tables {
+            page_text.extend(table.data.iter().map(|row| row.join("\t")));
+        }
+
+        let pages = vec![ExtractedPage {
+            number: 1,
+            text: page_text.join("\n"),
+            elements,
+            tables,
+            links: vec![],
+        }];
+        let stats = ExtractStats::from_pages(&pages);
+
+        Ok(ExtractResult {
+            filename: String::new(),
+            format: "markdown".to_string(),
+            pages,
+            metadata: ExtractMetadata {
+                total_pages: 1,
+                ..Default::default()
+            },
+            stats,
+            success: true,
+            error: None,
+        })
+    }
+}
+
+fn element(
+    element_type: &str,
+    content: &str,
+    level: Option<u8>,
+    marker: Option<String>,
+) -> ExtractedElement {
+    ExtractedElement {
+        element_type: element_type.to_string(),
+        content: content.trim().to_string(),
+        level,
+        marker,
+    }
+}
+
+/// Emit the collected item text as a `list_item`, numbering ordered lists
+fn flush_list_item(
+    elements: &mut Vec<ExtractedElement>,
+    lists: &mut [Option<u64>],
+    text: &mut String,
+) {
+    if text.trim().is_empty() {
+        text.clear();
+        return;
+    }
+
+    let marker = match lists.last_mut() {
+        Some(Some(number)) => {
+            let marker = format!("{}.", number);
+            *number += 1;
+            marker
+        }
+        _ => "-".to_string(),
+    };
+
+    elements.push(element("list_item", text, None, Some(marker)));
+    text.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extract::{ExtractFormat, OutputFormatter};
+
+    fn element_types(result: &ExtractResult) -> Vec<&str> {
+        result.pages[0]
+            .elements
+            .iter()
+            .map(|e| e.element_type.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_headings_and_paragraphs() {
+        let result = MarkdownParser::new()
+            .parse("# Title\n\nFirst *paragraph*\ncontinues.\n\n### Detail\n\nSecond.")
+            .unwrap();
+
+        assert!(result.success);
+        assert_eq!(result.format, "markdown");
+        assert_eq!(
+            element_types(&result),
+            vec!["heading", "paragraph", "heading", "paragraph"]
+        );
+
+        let elements = &result.pages[0].elements;
+        assert_eq!(elements[0].level, Some(1));
+        assert_eq!(elements[0].content, "Title");
+        assert_eq!(elements[1].content, "First paragraph continues.");
+        assert_eq!(elements[2].level, Some(3));
+    }
+
+    #[test]
+    fn test_parse_lists() {
+        let result = MarkdownParser::new()
+            .parse("- apple\n- banana\n  - nested\n\n3. third\n4. fourth\n")
+            .unwrap();
+
+        let elements = &result.pages[0].elements;
+        assert_eq!(element_types(&result), vec!["list_item"; 5]);
+        assert_eq!(elements[0].marker.as_deref(), Some("-"));
+        assert_eq!(elements[1].content, "banana");
+        assert_eq!(elements[2].content, "nested");
+        assert_eq!(elements[3].marker.as_deref(), Some("3."));
+        assert_eq!(elements[4].marker.as_deref(), Some("4."));
+    }
+
+    #[test]
+    fn test_parse_code_block_and_table() {
+        let markdown =
+            "```rust\nfn main() {}\n```\n\n| Name | Qty |\n|---|---|\n| a | 1 |\n| b | 2 |\n";
+        let result = MarkdownParser::new().parse(markdown).unwrap();
+
+        let page = &result.pages[0];
+        assert_eq!(element_types(&result), vec!["code_block"]);
+        assert_eq!(page.elements[0].content, "fn main() {}");
+        assert_eq!(page.elements[0].marker.as_deref(), Some("rust"));
+
+        assert_eq!(page.tables.len(), 1);
+        assert_eq!(page.tables[0].rows, 3);
+        assert_eq!(page.tables[0].cols, 2);
+        assert_eq!(page.tables[0].data[0], vec!["Name", "Qty"]);
+        assert_eq!(page.tables[0].data[2], vec!["b", "2"]);
+    }
+
+    #[test]
+    fn test_markdown_round_trip() {
+        let markdown = "# Report\n\nIntro text.\n\n- one\n- two\n\nClosing.\n\n```\ncode\n```\n";
+        let parser = MarkdownParser::new();
+        let first = parser.parse(markdown).unwrap();
+
+        let rendered = OutputFormatter::format(&first, ExtractFormat::Markdown).unwrap();
+        let second = parser.parse(&rendered).unwrap();
+
+        assert_eq!(element_types(&first), element_types(&second));
+        assert_eq!(
+            first.pages[0].elements[4].content,
+            second.pages[0].elements[4].content
+        );
     }
 }
@@ -0,0 +1,110 @@
+//! Page selections such as `2-5,8`
+
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+/// A set of one-based page (or slide) numbers written as `2-5,8`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageRange {
+    spans: Vec<RangeInclusive<usize>>,
+}
+
+/// Error returned when a page range cannot be parsed
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("Invalid page range '{input}': {reason}")]
+pub struct ParsePageRangeError {
+    input: String,
+    reason: &'static str,
+}
+
+impl PageRange {
+    /// Whether `page` (one-based) is selected
+    pub fn contains(&self, page: usize) -> bool {
+        self.spans.iter().any(|span| span.contains(&page))
+    }
+
+    /// Selected pages of a `total`-page document, in ascending order
+    pub fn select(&self, total: usize) -> Vec<usize> {
+        (1..=total).filter(|&page| self.contains(page)).collect()
+    }
+
+    /// Warning for selected pages past the end of a `total`-page document
+    pub fn out_of_range(&self, total: usize) -> Option<String> {
+        let last = self.spans.iter().map(|span| *span.end()).max()?;
+        (last > total).then(|| {
+            format!(
+                "Page range {} goes past the last page ({}); missing pages are ignored",
+                self, total
+            )
+        })
+    }
+}
+
+impl FromStr for PageRange {
+    type Err = ParsePageRangeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = |reason| ParsePageRangeError {
+            input: s.to_string(),
+            reason,
+        };
+        let page = |value: &str| match value.trim().parse::<usize>() {
+            Ok(0) => Err(error("pages are numbered from 1")),
+            Ok(page) => Ok(page),
+            Err(_) => Err(error("expected page numbers such as 2-5,8")),
+        };
+
+        let mut spans = Vec::new();
+        for part in s.split(',') {
+            let span = match part.split_once('-') {
+                Some((start, end)) => page(start)?..=page(end)?,
+                None => {
+                    let page = page(part)?;
+                    page..=page
+                }
+            };
+            if span.is_empty() {
+                return Err(error("a range must not end before it starts"));
+            }
+            spans.push(span);
+        }
+
+        Ok(Self { spans })
+    }
+}
+
+impl fmt::Display for PageRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, span) in self.spans.iter().enumerate() {
+            if i > 0 {
+                f.write_str(",")?;
+            }
+            if span.start() == span.end() {
+                write!(f, "{}", span.start())?;
+            } else {
+                write!(f, "{}-{}", span.start(), span.end())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_page_range() {
+        let range: PageRange = "2-5, 8".parse().unwrap();
+        assert_eq!(range.to_string(), "2-5,8");
+        assert_eq!(range.select(10), vec![2, 3, 4, 5, 8]);
+        assert_eq!(range.select(4), vec![2, 3, 4]);
+        assert!(range.out_of_range(8).is_none());
+        assert!(range.out_of_range(6).unwrap().contains("last page (6)"));
+
+        for invalid in ["", "0", "5-2", "a-3", "1,,2"] {
+            assert!(invalid.parse::<PageRange>().is_err(), "{:?}", invalid);
+        }
+    }
+}
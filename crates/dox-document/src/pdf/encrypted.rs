@@ -1,6 +1,7 @@
 //! Encrypted PDF handling and password management
 
 use crate::provider::DocumentError;
+use lopdf::encryption::DecryptionError;
 use lopdf::Document;
 use std::path::Path;
 use tracing::{debug, info, warn};
@@ -30,7 +31,7 @@ pub struct EncryptionInfo {
 }
 
 /// PDF encryption permissions
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct EncryptionPermissions {
     /// Print permission
     pub print: bool,
@@ -50,17 +51,18 @@ pub struct EncryptionPermissions {
     pub assemble: bool,
 }
 
-impl Default for EncryptionPermissions {
-    fn default() -> Self {
+impl EncryptionPermissions {
+    /// Permissions of a document without an encryption dictionary
+    pub fn unrestricted() -> Self {
         Self {
-            print: false,
-            print_high_quality: false,
-            modify: false,
-            copy: false,
-            annotate: false,
-            fill_forms: false,
-            extract_accessibility: false,
-            assemble: false,
+            print: true,
+            print_high_quality: true,
+            modify: true,
+            copy: true,
+            annotate: true,
+            fill_forms: true,
+            extract_accessibility: true,
+            assemble: true,
         }
     }
 }
@@ -78,6 +80,24 @@ pub enum PasswordResult {
     Error(String),
 }
 
+/// Convert a failure to load the PDF at `path`
+///
+/// I/O failures stay I/O errors, files lopdf cannot parse are reported as
+/// unreadable, and encryption it cannot undo as [`DocumentError::EncryptionRequired`].
+pub(crate) fn load_error(path: &Path, error: lopdf::Error) -> DocumentError {
+    match error {
+        lopdf::Error::IO(e) => DocumentError::Io(e),
+        lopdf::Error::Decryption(e) => {
+            warn!("Failed to decrypt PDF: {}", e);
+            DocumentError::EncryptionRequired
+        }
+        e => DocumentError::FileReadError {
+            path: path.to_string_lossy().to_string(),
+            source: e.into(),
+        },
+    }
+}
+
 impl EncryptedPdfHandler {
     /// Create a new encrypted PDF handler
     pub fn new(path: &Path) -> Result<Self, DocumentError> {
@@ -108,23 +128,20 @@ impl EncryptedPdfHandler {
                     security_handler: None,
                     algorithm: None,
                     key_length: None,
-                    permissions: EncryptionPermissions::default(),
+                    permissions: EncryptionPermissions::unrestricted(),
                 });
             }
 
             info!("PDF is encrypted, analyzing encryption details");
             // Clone the document for analysis to avoid borrow issues
-            let doc_clone =
-                Document::load(&self.path).map_err(|e| DocumentError::OperationFailed {
-                    reason: format!("Failed to load PDF for analysis: {}", e),
-                })?;
+            let doc_clone = Document::load(&self.path).map_err(|e| load_error(&self.path, e))?;
 
             self.analyze_encryption(&doc_clone)
         }
     }
 
     /// Attempt to authenticate with a password
-    pub fn authenticate(&mut self, _password: &str) -> Result<PasswordResult, DocumentError> {
+    pub fn authenticate(&mut self, password: &str) -> Result<PasswordResult, DocumentError> {
         debug!("Attempting to authenticate with provided password");
 
         let document = self.load_document()?;
@@ -133,19 +150,18 @@ impl EncryptedPdfHandler {
             return Ok(PasswordResult::NotNeeded);
         }
 
-        // For lopdf, we need to load the document with the password
-        // This is a simplified approach - in practice, we'd need to implement
-        // proper password authentication
-        // lopdf doesn't have load_from with password in this version
-        // For now, just try to load normally and check if it succeeds
-        match Document::load(&self.path) {
-            Ok(_authenticated_doc) => {
+        match document.clone().decrypt(password) {
+            Ok(()) => {
                 info!("Password authentication successful");
                 Ok(PasswordResult::Success)
             }
+            Err(lopdf::Error::Decryption(DecryptionError::IncorrectPassword)) => {
+                debug!("Password authentication failed");
+                Ok(PasswordResult::Incorrect)
+            }
             Err(e) => {
                 warn!("Password authentication failed: {}", e);
-                Ok(PasswordResult::Incorrect)
+                Ok(PasswordResult::Error(e.to_string()))
             }
         }
     }
@@ -183,14 +199,33 @@ impl EncryptedPdfHandler {
         Ok(None)
     }
 
-    /// Load the authenticated document
+    /// Load the document, decrypting it when it is encrypted
+    ///
+    /// Without a password the empty user password is tried, which opens
+    /// documents that only restrict permissions.
     pub fn load_authenticated_document(
         &mut self,
-        _password: Option<&str>,
+        password: Option<&str>,
     ) -> Result<Document, DocumentError> {
-        // Simplified implementation - just load the document normally
-        debug!("Loading document");
-        self.load_document().cloned()
+        let path = self.path.to_string_lossy().to_string();
+        let mut document = self.load_document()?.clone();
+
+        if !document.is_encrypted() {
+            return Ok(document);
+        }
+
+        debug!("Decrypting document");
+        match document.decrypt(password.unwrap_or_default()) {
+            Ok(()) => Ok(document),
+            Err(lopdf::Error::Decryption(DecryptionError::IncorrectPassword)) => match password {
+                Some(_) => Err(DocumentError::IncorrectPassword { path }),
+                None => Err(DocumentError::PasswordRequired { path }),
+            },
+            Err(e) => {
+                warn!("Failed to decrypt PDF: {}", e);
+                Err(DocumentError::EncryptionRequired)
+            }
+        }
     }
 
     /// Get extraction strategy for encrypted PDF
@@ -211,10 +246,7 @@ impl EncryptedPdfHandler {
     /// Load the PDF document
     fn load_document(&mut self) -> Result<&Document, DocumentError> {
         if self.document.is_none() {
-            let document =
-                Document::load(&self.path).map_err(|e| DocumentError::OperationFailed {
-                    reason: format!("Failed to load PDF: {}", e),
-                })?;
+            let document = Document::load(&self.path).map_err(|e| load_error(&self.path, e))?;
             self.document = Some(document);
         }
         Ok(self.document.as_ref().unwrap())
@@ -339,8 +371,6 @@ impl ExtractionStrategy {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::io::Write;
-    use tempfile::NamedTempFile;
 
     #[test]
     fn test_encryption_permissions_parsing() {
@@ -1,10 +1,16 @@
 //! Advanced PDF text extraction with layout analysis and streaming support
 
+use super::encrypted::{EncryptedPdfHandler, EncryptionInfo, EncryptionPermissions};
+use super::layout::{self, concat_matrix};
+use super::ocr::{OcrConfig, PdfOcrProcessor};
+use crate::page_range::PageRange;
 use crate::provider::DocumentError;
-use lopdf::Document;
+use lopdf::content::Content;
+use lopdf::{Dictionary, Document, Object, ObjectId, Stream};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::Path;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 /// Advanced PDF extractor with layout analysis and streaming capabilities
 #[derive(Debug)]
@@ -15,8 +21,15 @@ pub struct AdvancedPdfExtractor {
     config: PdfExtractConfig,
     /// Cached document (loaded on demand)
     document: Option<Document>,
+    /// Encryption details of the original file (set when the document is loaded)
+    encryption: Option<EncryptionInfo>,
+    /// OCR processor for scanned pages (created on first use)
+    ocr: Option<PdfOcrProcessor>,
 }
 
+/// Pages with fewer non-whitespace characters than this are sent to OCR
+const OCR_TEXT_THRESHOLD: usize = 16;
+
 /// Configuration for PDF extraction
 #[derive(Debug, Clone)]
 pub struct PdfExtractConfig {
@@ -34,8 +47,12 @@ pub struct PdfExtractConfig {
     pub extract_images: bool,
     /// Handle encrypted PDFs
     pub handle_encrypted: bool,
-    /// OCR for image-based PDFs (future feature)
+    /// Password for encrypted PDFs (the empty password is tried when unset)
+    pub password: Option<String>,
+    /// Run OCR on pages without extractable text
     pub enable_ocr: bool,
+    /// Only extract these pages; all pages when unset
+    pub page_range: Option<PageRange>,
 }
 
 impl Default for PdfExtractConfig {
@@ -48,7 +65,9 @@ impl Default for PdfExtractConfig {
             chunk_size: 1024 * 1024, // 1MB
             extract_images: false,
             handle_encrypted: true,
+            password: None,
             enable_ocr: false,
+            page_range: None,
         }
     }
 }
@@ -115,6 +134,9 @@ pub struct PdfPage {
     pub tables: Vec<PdfTable>,
     /// Image metadata
     pub images: Vec<ImageInfo>,
+    /// URI link annotations
+    #[serde(default)]
+    pub links: Vec<PdfLink>,
     /// Page dimensions
     pub dimensions: PageDimensions,
 }
@@ -194,6 +216,16 @@ pub struct ImageInfo {
     pub position: BlockPosition,
 }
 
+/// A link annotation pointing at a URI
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PdfLink {
+    /// Text drawn inside the link area, or the annotation's `Contents`
+    pub text: String,
+    pub url: String,
+    /// Link area on the page
+    pub position: BlockPosition,
+}
+
 /// Page dimensions
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PageDimensions {
@@ -228,6 +260,17 @@ pub struct PdfPermissions {
     pub annotate: bool,
 }
 
+impl From<&EncryptionPermissions> for PdfPermissions {
+    fn from(permissions: &EncryptionPermissions) -> Self {
+        Self {
+            print: permissions.print,
+            modify: permissions.modify,
+            copy: permissions.copy,
+            annotate: permissions.annotate,
+        }
+    }
+}
+
 /// Extraction statistics
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExtractionStats {
@@ -249,9 +292,11 @@ impl AdvancedPdfExtractor {
             });
         }
 
-        // Verify it's a PDF file
+        // Verify it's a PDF file; misnamed files are accepted by their header
         if let Some(extension) = path.extension() {
-            if extension.to_string_lossy().to_lowercase() != "pdf" {
+            if extension.to_string_lossy().to_lowercase() != "pdf"
+                && !crate::provider::has_pdf_header(path)
+            {
                 return Err(DocumentError::UnsupportedFormat {
                     format: extension.to_string_lossy().to_string(),
                 });
@@ -262,35 +307,42 @@ impl AdvancedPdfExtractor {
             path: path.to_path_buf(),
             config,
             document: None,
+            encryption: None,
+            ocr: None,
         })
     }
 
+    /// Use the given OCR processor for scanned pages instead of the default one
+    pub fn with_ocr_processor(mut self, processor: PdfOcrProcessor) -> Self {
+        self.ocr = Some(processor);
+        self
+    }
+
     /// Extract content with advanced features
     pub fn extract(&mut self) -> Result<AdvancedPdfResult, DocumentError> {
         let start_time = std::time::Instant::now();
         info!("Starting advanced PDF extraction: {}", self.path.display());
 
         // Check file size for streaming decision
-        let file_size = std::fs::metadata(&self.path)
-            .map_err(|e| DocumentError::FileReadError {
-                path: self.path.to_string_lossy().to_string(),
-                source: e.into(),
-            })?
-            .len();
+        let file_size = self.file_size()?;
 
         let should_stream = self.config.enable_streaming
             && file_size > (self.config.max_memory_mb * 1024 * 1024) as u64;
 
-        let result = if should_stream {
-            self.extract_streaming(file_size)?
+        let mut warnings = Vec::new();
+        let (pages, metadata) = if should_stream {
+            self.extract_streaming(file_size, &mut warnings)?
         } else {
-            self.extract_in_memory(file_size)?
+            let (mut pages, metadata) = self.extract_in_memory(file_size, &mut warnings)?;
+            if self.config.enable_ocr {
+                self.apply_ocr(&mut pages, &mut warnings);
+            }
+            (pages, metadata)
         };
 
         let extraction_time = start_time.elapsed().as_millis() as u64;
         info!("PDF extraction completed in {}ms", extraction_time);
 
-        let pages = result.0;
         let stats = ExtractionStats {
             total_pages: pages.len(),
             text_blocks: pages.iter().map(|p| p.text_blocks.len()).sum(),
@@ -303,16 +355,142 @@ impl AdvancedPdfExtractor {
 
         Ok(AdvancedPdfResult {
             pages,
-            metadata: result.1,
+            metadata,
             stats,
-            warnings: vec![],
+            warnings,
         })
     }
 
+    /// Extract pages one at a time, handing each to `on_page` as soon as it is ready
+    ///
+    /// Only the page being processed is held in memory. An error returned by
+    /// the callback stops extraction and is returned as-is. OCR warnings are
+    /// logged rather than collected.
+    pub fn extract_with_callback<F>(
+        &mut self,
+        mut on_page: F,
+    ) -> Result<ExtractionStats, DocumentError>
+    where
+        F: FnMut(PdfPage) -> Result<(), DocumentError>,
+    {
+        let start_time = std::time::Instant::now();
+        info!(
+            "Starting page-by-page PDF extraction: {}",
+            self.path.display()
+        );
+
+        let file_size = self.file_size()?;
+        let mut stats = ExtractionStats {
+            total_pages: 0,
+            text_blocks: 0,
+            tables_detected: 0,
+            images_detected: 0,
+            extraction_time_ms: 0,
+            memory_usage_mb: (file_size as f64) / (1024.0 * 1024.0),
+            streaming_used: true,
+        };
+
+        let mut warnings = Vec::new();
+        self.stream_pages(file_size, &mut warnings, |page| {
+            stats.total_pages += 1;
+            stats.text_blocks += page.text_blocks.len();
+            stats.tables_detected += page.tables.len();
+            stats.images_detected += page.images.len();
+            on_page(page)
+        })?;
+
+        for warning in warnings {
+            warn!("{}", warning);
+        }
+
+        stats.extraction_time_ms = start_time.elapsed().as_millis() as u64;
+        info!("PDF extraction completed in {}ms", stats.extraction_time_ms);
+        Ok(stats)
+    }
+
+    fn file_size(&self) -> Result<u64, DocumentError> {
+        std::fs::metadata(&self.path)
+            .map(|m| m.len())
+            .map_err(|e| DocumentError::FileReadError {
+                path: self.path.to_string_lossy().to_string(),
+                source: e.into(),
+            })
+    }
+
+    /// Run OCR on the images of pages with little or no extractable text
+    ///
+    /// Recognized text is appended to the page text and each recognized word
+    /// becomes a text block. Failures are reported as warnings.
+    fn apply_ocr(&mut self, pages: &mut [PdfPage], warnings: &mut Vec<String>) {
+        let needs_ocr = |page: &PdfPage| {
+            page.raw_text.chars().filter(|c| !c.is_whitespace()).count() < OCR_TEXT_THRESHOLD
+        };
+        if !pages.iter().any(needs_ocr) {
+            return;
+        }
+
+        let processor = self
+            .ocr
+            .get_or_insert_with(|| PdfOcrProcessor::new(OcrConfig::default()));
+        if !processor.has_engine() {
+            if let Err(e) = processor.initialize_engine() {
+                warnings.push(format!("OCR unavailable: {}", e));
+                return;
+            }
+        }
+
+        let (Some(processor), Some(document)) = (&self.ocr, &self.document) else {
+            return;
+        };
+        let page_ids = document.get_pages();
+
+        for page in pages.iter_mut().filter(|page| needs_ocr(page)) {
+            let Some(&page_id) = page_ids.get(&(page.number as u32)) else {
+                continue;
+            };
+
+            for (name, image) in self.page_images(document, page_id) {
+                match processor.process_page(&image.content, page.number) {
+                    Ok(result) => {
+                        if !page.raw_text.trim().is_empty() {
+                            page.raw_text.push('\n');
+                        }
+                        page.raw_text.push_str(&result.text);
+
+                        page.text_blocks
+                            .extend(result.words.into_iter().map(|word| TextBlock {
+                                text: word.text,
+                                block_type: TextBlockType::Unknown,
+                                font: FontInfo {
+                                    family: None,
+                                    size: None,
+                                    bold: false,
+                                    italic: false,
+                                },
+                                position: BlockPosition {
+                                    x: word.bbox.x,
+                                    y: word.bbox.y,
+                                    width: word.bbox.width,
+                                    height: word.bbox.height,
+                                },
+                            }));
+                    }
+                    Err(e) => warnings.push(format!(
+                        "OCR failed for image {} on page {}: {}",
+                        String::from_utf8_lossy(&name),
+                        page.number,
+                        e
+                    )),
+                }
+            }
+        }
+    }
+
     /// In-memory extraction for smaller files
     fn extract_in_memory(
         &mut self,
         file_size: u64,
+        warnings: &mut Vec<String>,
     ) -> Result<(Vec<PdfPage>, PdfDocumentMetadata), DocumentError> {
         debug!(
             "Using in-memory extraction for PDF ({}MB)",
@@ -326,11 +504,22 @@ impl AdvancedPdfExtractor {
         // Extract metadata
         let metadata = self.extract_metadata(document, file_size)?;
 
-        // Extract text content
-        let text_content = self.extract_text_content()?;
+        let pages = if self.config.page_range.is_some() {
+            // Only the selected pages' content streams are parsed
+            self.selected_pages(document, warnings)
+                .into_iter()
+                .map(|number| {
+                    let text = Self::page_text(document, number);
+                    self.process_single_page(document, number as usize, &text)
+                })
+                .collect::<Result<Vec<_>, _>>()?
+        } else {
+            // Extract text content
+            let text_content = self.extract_text_content()?;
 
-        // Process pages
-        let pages = self.process_pages(document, &text_content)?;
+            // Process pages
+            self.process_pages(document, &text_content)?
+        };
 
         Ok((pages, metadata))
     }
@@ -339,36 +528,106 @@ impl AdvancedPdfExtractor {
     fn extract_streaming(
         &mut self,
         file_size: u64,
+        warnings: &mut Vec<String>,
     ) -> Result<(Vec<PdfPage>, PdfDocumentMetadata), DocumentError> {
         debug!(
             "Using streaming extraction for PDF ({}MB)",
             file_size / 1024 / 1024
         );
 
-        // For streaming, we'll process pages one at a time
+        let mut pages = Vec::new();
+        let metadata = self.stream_pages(file_size, warnings, |page| {
+            pages.push(page);
+            Ok(())
+        })?;
+
+        Ok((pages, metadata))
+    }
+
+    /// Process pages in order, reading each page's own content stream
+    ///
+    /// Each page is handed to `on_page` before the next one is read.
+    fn stream_pages<F>(
+        &mut self,
+        file_size: u64,
+        warnings: &mut Vec<String>,
+        mut on_page: F,
+    ) -> Result<PdfDocumentMetadata, DocumentError>
+    where
+        F: FnMut(PdfPage) -> Result<(), DocumentError>,
+    {
         self.load_document()?;
         let document = self.document.as_ref().unwrap();
-
-        // Extract metadata first
         let metadata = self.extract_metadata(document, file_size)?;
+        let page_numbers = self.selected_pages(document, warnings);
+        debug!("Processing {} pages with streaming", page_numbers.len());
 
-        // Process pages in streaming fashion
-        let pages = self.process_pages_streaming(document)?;
+        for page_number in page_numbers {
+            let document = self.document.as_ref().unwrap();
+            let text = Self::page_text(document, page_number);
+            let mut page = self.process_single_page(document, page_number as usize, &text)?;
 
-        Ok((pages, metadata))
+            if self.config.enable_ocr {
+                self.apply_ocr(std::slice::from_mut(&mut page), warnings);
+            }
+
+            on_page(page)?;
+        }
+
+        Ok(metadata)
     }
 
-    /// Load the PDF document
+    /// Collect the URI links of the selected pages with their page number
+    ///
+    /// Only the pages' annotations are read; no text is extracted.
+    pub fn extract_page_links(&mut self) -> Result<Vec<(usize, PdfLink)>, DocumentError> {
+        self.load_document()?;
+        let document = self.document.as_ref().unwrap();
+
+        let mut links = Vec::new();
+        for number in self.selected_pages(document, &mut Vec::new()) {
+            let number = number as usize;
+            links.extend(
+                self.extract_links(document, number)
+                    .into_iter()
+                    .map(|link| (number, link)),
+            );
+        }
+        Ok(links)
+    }
+
+    /// Numbers of the pages to extract, in page order
+    ///
+    /// Requested pages past the end of the document are reported as a warning.
+    fn selected_pages(&self, document: &Document, warnings: &mut Vec<String>) -> Vec<u32> {
+        let page_numbers = document.get_pages().into_keys();
+        let Some(range) = &self.config.page_range else {
+            return page_numbers.collect();
+        };
+
+        if let Some(warning) = range.out_of_range(document.get_pages().len()) {
+            warnings.push(warning);
+        }
+        page_numbers
+            .filter(|&number| range.contains(number as usize))
+            .collect()
+    }
+
+    /// Load the PDF document, decrypting it if needed
     fn load_document(&mut self) -> Result<(), DocumentError> {
         if self.document.is_none() {
             debug!("Loading PDF document: {}", self.path.display());
 
-            let document =
-                Document::load(&self.path).map_err(|e| DocumentError::OperationFailed {
-                    reason: format!("Failed to load PDF: {}", e),
-                })?;
+            let mut handler = EncryptedPdfHandler::new(&self.path)?;
+            let encryption = handler.check_encryption()?;
+            if encryption.is_encrypted && !self.config.handle_encrypted {
+                return Err(DocumentError::EncryptionRequired);
+            }
+
+            let document = handler.load_authenticated_document(self.config.password.as_deref())?;
 
             self.document = Some(document);
+            self.encryption = Some(encryption);
         }
         Ok(())
     }
@@ -389,16 +648,18 @@ impl AdvancedPdfExtractor {
             modification_date: None,
             page_count: document.get_pages().len(),
             file_size,
-            pdf_version: "1.4".to_string(), // Default PDF version - API doesn't provide easy access
-            encrypted: document.is_encrypted(),
-            permissions: PdfPermissions {
-                print: true,
-                modify: true,
-                copy: true,
-                annotate: true,
-            },
+            // Version from the `%PDF-1.x` header
+            pdf_version: document.version.clone(),
+            encrypted: false,
+            permissions: PdfPermissions::from(&EncryptionPermissions::unrestricted()),
         };
 
+        // The loaded document is already decrypted, so use the original file's details
+        if let Some(encryption) = &self.encryption {
+            metadata.encrypted = encryption.is_encrypted;
+            metadata.permissions = PdfPermissions::from(&encryption.permissions);
+        }
+
         // Extract document info dictionary
         if let Ok(info_dict) = document.trailer.get(b"Info") {
             if let Ok(info_ref) = info_dict.as_reference() {
@@ -461,10 +722,22 @@ impl AdvancedPdfExtractor {
     fn extract_text_content(&self) -> Result<String, DocumentError> {
         debug!("Extracting text content from PDF");
 
-        let bytes = std::fs::read(&self.path).map_err(|e| DocumentError::FileReadError {
-            path: self.path.to_string_lossy().to_string(),
-            source: e.into(),
-        })?;
+        let bytes = match &self.document {
+            // pdf-extract cannot decrypt with our password, so hand it the decrypted copy
+            Some(document) if self.encryption.as_ref().is_some_and(|e| e.is_encrypted) => {
+                let mut bytes = Vec::new();
+                document.clone().save_to(&mut bytes).map_err(|e| {
+                    DocumentError::OperationFailed {
+                        reason: format!("Failed to prepare decrypted PDF: {}", e),
+                    }
+                })?;
+                bytes
+            }
+            _ => std::fs::read(&self.path).map_err(|e| DocumentError::FileReadError {
+                path: self.path.to_string_lossy().to_string(),
+                source: e.into(),
+            })?,
+        };
 
         pdf_extract::extract_text_from_mem(&bytes).map_err(|e| DocumentError::OperationFailed {
             reason: format!("Text extraction failed: {}", e),
@@ -480,8 +753,7 @@ impl AdvancedPdfExtractor {
         let page_count = document.get_pages().len();
         debug!("Processing {} pages", page_count);
 
-        // Split text into pages (simplified approach)
-        let page_texts = self.split_text_into_pages(text_content, page_count);
+        let page_texts = self.split_text_into_pages(document, text_content);
 
         let mut pages = Vec::new();
         for (page_num, page_text) in page_texts.into_iter().enumerate() {
@@ -492,51 +764,41 @@ impl AdvancedPdfExtractor {
         Ok(pages)
     }
 
-    /// Process pages for streaming extraction
-    fn process_pages_streaming(&self, document: &Document) -> Result<Vec<PdfPage>, DocumentError> {
-        let page_count = document.get_pages().len();
-        debug!("Processing {} pages with streaming", page_count);
-
-        let mut pages = Vec::new();
-
-        // For streaming, we'd implement page-by-page processing
-        // For now, fallback to simplified approach
-        let text_content = self.extract_text_content()?;
-        let page_texts = self.split_text_into_pages(&text_content, page_count);
-
-        for (page_num, page_text) in page_texts.into_iter().enumerate() {
-            let page = self.process_single_page(document, page_num + 1, &page_text)?;
-            pages.push(page);
-        }
-
-        Ok(pages)
-    }
-
     /// Split text content into pages
-    fn split_text_into_pages(&self, text: &str, page_count: usize) -> Vec<String> {
+    ///
+    /// Form feeds in the extracted text mark page breaks directly; otherwise
+    /// each page is read from its own content stream.
+    fn split_text_into_pages(&self, document: &Document, text: &str) -> Vec<String> {
         if text.contains('\x0C') {
-            // Split by form feed character
             text.split('\x0C').map(|s| s.to_string()).collect()
-        } else if page_count > 1 {
-            // Estimate page breaks by text length
-            let chars_per_page = text.len() / page_count;
-            let mut pages = Vec::new();
-            let mut start = 0;
-
-            for _ in 0..page_count {
-                let end = (start + chars_per_page).min(text.len());
-                pages.push(text[start..end].to_string());
-                start = end;
-                if start >= text.len() {
-                    break;
-                }
-            }
-            pages
+        } else if document.get_pages().len() > 1 {
+            self.extract_page_texts(document)
         } else {
             vec![text.to_string()]
         }
     }
 
+    /// Extract the text of every page separately, in page order
+    fn extract_page_texts(&self, document: &Document) -> Vec<String> {
+        document
+            .get_pages()
+            .keys()
+            .map(|&page_number| Self::page_text(document, page_number))
+            .collect()
+    }
+
+    /// Extract the text of a single page from its content stream
+    fn page_text(document: &Document, page_number: u32) -> String {
+        let mut text = String::new();
+        for chunk in document.extract_text_chunks(&[page_number]) {
+            match chunk {
+                Ok(chunk) => text.push_str(&chunk),
+                Err(e) => debug!("Skipping text on page {}: {}", page_number, e),
+            }
+        }
+        text
+    }
+
     /// Process a single page
     fn process_single_page(
         &self,
@@ -571,9 +833,15 @@ impl AdvancedPdfExtractor {
             }]
         };
 
-        // Extract tables (simplified implementation)
         let tables = if self.config.extract_tables {
-            self.extract_tables(text, &dimensions)
+            self.extract_tables(document, page_num)
+        } else {
+            vec![]
+        };
+
+        // Image XObjects are only collected on request
+        let images = if self.config.extract_images {
+            self.extract_images(document, page_num)
         } else {
             vec![]
         };
@@ -583,11 +851,158 @@ impl AdvancedPdfExtractor {
             raw_text: text.to_string(),
             text_blocks,
             tables,
-            images: vec![], // TODO: Implement image extraction
+            images,
+            links: self.extract_links(document, page_num),
             dimensions,
         })
     }
 
+    /// Extract metadata for the image XObjects of a page
+    fn extract_images(&self, document: &Document, page_num: usize) -> Vec<ImageInfo> {
+        let Some(&page_id) = document.get_pages().get(&(page_num as u32)) else {
+            return vec![];
+        };
+
+        let placements = self.image_placements(document, page_id);
+        let mut images = Vec::new();
+
+        for (name, stream) in self.page_images(document, page_id) {
+            let dict = &stream.dict;
+            let dimension = |key: &[u8]| {
+                dict.get(key)
+                    .and_then(Object::as_i64)
+                    .ok()
+                    .and_then(|v| u32::try_from(v).ok())
+                    .unwrap_or(0)
+            };
+            let width = dimension(b"Width");
+            let height = dimension(b"Height");
+
+            // Images that are never drawn keep their pixel size at the origin
+            let position = placements.get(&name).cloned().unwrap_or(BlockPosition {
+                x: 0.0,
+                y: 0.0,
+                width: width as f32,
+                height: height as f32,
+            });
+
+            images.push(ImageInfo {
+                index: images.len(),
+                format: image_format(dict).to_string(),
+                width,
+                height,
+                position,
+            });
+        }
+
+        debug!("Found {} images on page {}", images.len(), page_num);
+        images
+    }
+
+    /// Image XObjects available to a page
+    fn page_images<'a>(
+        &self,
+        document: &'a Document,
+        page_id: ObjectId,
+    ) -> Vec<(Vec<u8>, &'a Stream)> {
+        self.page_xobjects(document, page_id)
+            .into_iter()
+            .filter(|(_, stream)| {
+                stream.dict.get(b"Subtype").and_then(Object::as_name).ok()
+                    == Some(b"Image".as_slice())
+            })
+            .collect()
+    }
+
+    /// XObject streams available to a page, including inherited resources
+    fn page_xobjects<'a>(
+        &self,
+        document: &'a Document,
+        page_id: ObjectId,
+    ) -> Vec<(Vec<u8>, &'a Stream)> {
+        let Ok((own, inherited)) = document.get_page_resources(page_id) else {
+            return vec![];
+        };
+
+        let resources = own.into_iter().chain(
+            inherited
+                .into_iter()
+                .filter_map(|id| document.get_dictionary(id).ok()),
+        );
+
+        let mut xobjects: Vec<(Vec<u8>, &Stream)> = Vec::new();
+        for resource in resources {
+            let Ok(dict) = document.get_dict_in_dict(resource, b"XObject") else {
+                continue;
+            };
+            for (name, value) in dict.iter() {
+                if xobjects.iter().any(|(existing, _)| existing == name) {
+                    continue;
+                }
+                let stream = document
+                    .dereference(value)
+                    .and_then(|(_, object)| object.as_stream());
+                if let Ok(stream) = stream {
+                    xobjects.push((name.clone(), stream));
+                }
+            }
+        }
+
+        xobjects
+    }
+
+    /// Approximate where each XObject is first drawn, from the `cm` and `Do`
+    /// operators of the page content
+    fn image_placements(
+        &self,
+        document: &Document,
+        page_id: ObjectId,
+    ) -> HashMap<Vec<u8>, BlockPosition> {
+        let mut placements = HashMap::new();
+        let content = document
+            .get_page_content(page_id)
+            .and_then(|data| Content::decode(&data));
+        let Ok(content) = content else {
+            return placements;
+        };
+
+        let mut ctm = [1.0, 0.0, 0.0, 1.0, 0.0, 0.0];
+        let mut saved = Vec::new();
+
+        for operation in content.operations {
+            match operation.operator.as_str() {
+                "q" => saved.push(ctm),
+                "Q" => ctm = saved.pop().unwrap_or(ctm),
+                "cm" => {
+                    let values: Vec<f32> = operation
+                        .operands
+                        .iter()
+                        .filter_map(|o| o.as_float().ok())
+                        .collect();
+                    if let Ok(matrix) = <[f32; 6]>::try_from(values) {
+                        ctm = concat_matrix(&matrix, &ctm);
+                    }
+                }
+                "Do" => {
+                    if let Some(name) = operation.operands.first().and_then(|o| o.as_name().ok()) {
+                        // Images are drawn into the unit square mapped by the CTM
+                        placements
+                            .entry(name.to_vec())
+                            .or_insert_with(|| BlockPosition {
+                                x: ctm[4],
+                                y: ctm[5],
+                                width: ctm[0].hypot(ctm[1]),
+                                height: ctm[2].hypot(ctm[3]),
+                            });
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        placements
+    }
+
     /// Get page dimensions
     fn get_page_dimensions(
         &self,
@@ -653,84 +1068,114 @@ impl AdvancedPdfExtractor {
         }
     }
 
-    /// Extract tables from text (simplified implementation)
-    fn extract_tables(&self, text: &str, dimensions: &PageDimensions) -> Vec<PdfTable> {
-        let mut tables = Vec::new();
-        let lines: Vec<&str> = text.lines().collect();
+    /// Detect tables from the positions of the page's text runs
+    fn extract_tables(&self, document: &Document, page_num: usize) -> Vec<PdfTable> {
+        let Some(&page_id) = document.get_pages().get(&(page_num as u32)) else {
+            return vec![];
+        };
 
-        // Look for table patterns (lines with multiple columns separated by whitespace)
-        let mut table_start = None;
-        let mut table_lines = Vec::new();
+        layout::detect_tables(&layout::page_text_runs(document, page_id))
+    }
 
-        for (i, line) in lines.iter().enumerate() {
-            let cols: Vec<&str> = line.split_whitespace().collect();
+    /// Collect the page's link annotations with a URI action
+    ///
+    /// The linked text is taken from the text runs that start inside the
+    /// annotation rectangle.
+    fn extract_links(&self, document: &Document, page_num: usize) -> Vec<PdfLink> {
+        let Some(&page_id) = document.get_pages().get(&(page_num as u32)) else {
+            return vec![];
+        };
+        let annotations = document
+            .get_dictionary(page_id)
+            .and_then(|page| page.get(b"Annots"))
+            .and_then(|annots| document.dereference(annots))
+            .and_then(|(_, annots)| annots.as_array());
+        let Ok(annotations) = annotations else {
+            return vec![];
+        };
 
-            if cols.len() >= 3 && self.looks_like_table_row(line) {
-                if table_start.is_none() {
-                    table_start = Some(i);
-                }
-                table_lines.push(cols);
-            } else if !table_lines.is_empty() {
-                // End of table
-                if table_lines.len() >= 2 {
-                    tables.push(self.create_pdf_table(
-                        table_lines.clone(),
-                        table_start.unwrap(),
-                        dimensions,
-                    ));
-                }
-                table_lines.clear();
-                table_start = None;
+        let runs = layout::page_text_runs(document, page_id);
+        let mut links = Vec::new();
+
+        for annotation in annotations {
+            let Ok(dict) = document
+                .dereference(annotation)
+                .and_then(|(_, object)| object.as_dict())
+            else {
+                continue;
+            };
+            if dict.get(b"Subtype").and_then(Object::as_name).ok() != Some(b"Link".as_slice()) {
+                continue;
             }
-        }
 
-        // Handle table at end of text
-        if !table_lines.is_empty() && table_lines.len() >= 2 {
-            tables.push(self.create_pdf_table(
-                table_lines.clone(),
-                table_start.unwrap(),
-                dimensions,
-            ));
-        }
+            let uri = dict
+                .get(b"A")
+                .and_then(|action| document.dereference(action))
+                .and_then(|(_, action)| action.as_dict())
+                .and_then(|action| action.get(b"URI"))
+                .and_then(Object::as_str);
+            let Ok(uri) = uri else {
+                continue;
+            };
+
+            let rect: Vec<f32> = dict
+                .get(b"Rect")
+                .and_then(Object::as_array)
+                .map(|rect| rect.iter().filter_map(|v| v.as_float().ok()).collect())
+                .unwrap_or_default();
+            let Ok([x1, y1, x2, y2]) = <[f32; 4]>::try_from(rect) else {
+                continue;
+            };
+            let (left, right) = (x1.min(x2), x1.max(x2));
+            let (bottom, top) = (y1.min(y2), y1.max(y2));
+
+            let mut text = runs
+                .iter()
+                .filter(|run| {
+                    (left - 1.0..=right).contains(&run.x) && (bottom - 1.0..=top).contains(&run.y)
+                })
+                .map(|run| run.text.trim())
+                .collect::<Vec<_>>()
+                .join(" ");
+            if text.is_empty() {
+                text = dict
+                    .get(b"Contents")
+                    .and_then(Object::as_str)
+                    .map(|contents| String::from_utf8_lossy(contents).into_owned())
+                    .unwrap_or_default();
+            }
 
-        tables
-    }
+            links.push(PdfLink {
+                text,
+                url: String::from_utf8_lossy(uri).into_owned(),
+                position: BlockPosition {
+                    x: left,
+                    y: bottom,
+                    width: right - left,
+                    height: top - bottom,
+                },
+            });
+        }
 
-    /// Check if a line looks like a table row
-    fn looks_like_table_row(&self, line: &str) -> bool {
-        let cols: Vec<&str> = line.split_whitespace().collect();
-        cols.len() >= 3
-            && line.chars().filter(|c| c.is_whitespace()).count() >= 4
-            && !line.trim().is_empty()
+        debug!("Found {} links on page {}", links.len(), page_num);
+        links
     }
+}
 
-    /// Create a PDF table from extracted lines
-    fn create_pdf_table(
-        &self,
-        lines: Vec<Vec<&str>>,
-        start_line: usize,
-        dimensions: &PageDimensions,
-    ) -> PdfTable {
-        let rows = lines.len();
-        let cols = lines.iter().map(|row| row.len()).max().unwrap_or(0);
-
-        let data: Vec<Vec<String>> = lines
-            .into_iter()
-            .map(|row| row.into_iter().map(|cell| cell.to_string()).collect())
-            .collect();
-
-        PdfTable {
-            index: 0,
-            data,
-            rows,
-            cols,
-            position: BlockPosition {
-                x: 72.0,
-                y: 400.0 - (start_line as f32 * 14.0),
-                width: dimensions.width - 144.0,
-                height: (rows as f32 * 14.0),
-            },
-            confidence: 0.8, // Moderate confidence for pattern-based detection
-        }
+/// Image format named after the last filter of an image stream
+fn image_format(dict: &Dictionary) -> &'static str {
+    let filter = match dict.get(b"Filter") {
+        Ok(Object::Name(name)) => Some(name.as_slice()),
+        Ok(Object::Array(filters)) => filters.last().and_then(|f| f.as_name().ok()),
+        _ => None,
+    };
+
+    match filter {
+        Some(b"DCTDecode") => "JPEG",
+        Some(b"JPXDecode") => "JPEG2000",
+        Some(b"JBIG2Decode") => "JBIG2",
+        Some(b"CCITTFaxDecode") => "TIFF",
+        Some(b"FlateDecode") | Some(b"LZWDecode") => "PNG",
+        _ => "RAW",
     }
 }
@@ -0,0 +1,336 @@
+//! Positioned text and column-aligned table detection for PDF pages
+//!
+//! Text runs are read from the page content stream with their position in
+//! page space. Tables are inferred from consecutive lines whose runs start at
+//! the same x-positions, so they are found regardless of how many spaces the
+//! plain-text output puts between cells.
+
+use super::extractor::{BlockPosition, PdfTable};
+use lopdf::content::Content;
+use lopdf::{Document, Encoding, Object, ObjectId};
+use std::collections::{BTreeMap, BTreeSet};
+use tracing::debug;
+
+/// Runs whose baselines differ by less than this belong to the same line
+const LINE_TOLERANCE: f32 = 2.0;
+/// Runs whose x-positions differ by less than this belong to the same column
+const COLUMN_TOLERANCE: f32 = 4.0;
+/// Share of a block's lines that must have a run in a column for it to count
+const COLUMN_SUPPORT: f32 = 0.6;
+/// Blocks scoring below this are not reported as tables
+const MIN_TABLE_CONFIDENCE: f32 = 0.75;
+
+/// A piece of text drawn at one position
+#[derive(Debug, Clone)]
+pub(crate) struct TextRun {
+    pub x: f32,
+    pub y: f32,
+    pub size: f32,
+    pub text: String,
+}
+
+/// Multiply two PDF transformation matrices (`m × t`)
+pub(crate) fn concat_matrix(m: &[f32; 6], t: &[f32; 6]) -> [f32; 6] {
+    [
+        m[0] * t[0] + m[1] * t[2],
+        m[0] * t[1] + m[1] * t[3],
+        m[2] * t[0] + m[3] * t[2],
+        m[2] * t[1] + m[3] * t[3],
+        m[4] * t[0] + m[5] * t[2] + t[4],
+        m[4] * t[1] + m[5] * t[3] + t[5],
+    ]
+}
+
+const IDENTITY: [f32; 6] = [1.0, 0.0, 0.0, 1.0, 0.0, 0.0];
+
+/// Read the text runs of a page in content-stream order
+///
+/// Text shown without repositioning in between is merged into the previous
+/// run, since glyph widths are not tracked.
+pub(crate) fn page_text_runs(document: &Document, page_id: ObjectId) -> Vec<TextRun> {
+    let content = document
+        .get_page_content(page_id)
+        .and_then(|data| Content::decode(&data));
+    let content = match content {
+        Ok(content) => content,
+        Err(e) => {
+            debug!("Cannot read page content for layout: {}", e);
+            return vec![];
+        }
+    };
+
+    let encodings: BTreeMap<Vec<u8>, Encoding> = document
+        .get_page_fonts(page_id)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|(name, font)| Some((name, font.get_font_encoding(document).ok()?)))
+        .collect();
+
+    let mut runs: Vec<TextRun> = Vec::new();
+    let mut ctm = IDENTITY;
+    let mut saved = Vec::new();
+    let mut line_matrix = IDENTITY;
+    let mut font: Option<Vec<u8>> = None;
+    let mut font_size = 0.0;
+    let mut leading = 0.0;
+    // Set when the text position changed since the last run was emitted
+    let mut moved = true;
+
+    for operation in &content.operations {
+        let operands: Vec<f32> = operation
+            .operands
+            .iter()
+            .filter_map(|o| o.as_float().ok())
+            .collect();
+
+        match operation.operator.as_str() {
+            "q" => saved.push(ctm),
+            "Q" => ctm = saved.pop().unwrap_or(ctm),
+            "cm" => {
+                if let Ok(matrix) = <[f32; 6]>::try_from(operands) {
+                    ctm = concat_matrix(&matrix, &ctm);
+                }
+            }
+            "BT" => {
+                line_matrix = IDENTITY;
+                moved = true;
+            }
+            "Tf" => {
+                font = operation
+                    .operands
+                    .first()
+                    .and_then(|o| o.as_name().ok())
+                    .map(<[u8]>::to_vec);
+                font_size = operands.first().copied().unwrap_or(font_size);
+            }
+            "TL" => leading = operands.first().copied().unwrap_or(leading),
+            "Td" | "TD" => {
+                if let [tx, ty] = operands[..] {
+                    if operation.operator == "TD" {
+                        leading = -ty;
+                    }
+                    line_matrix = concat_matrix(&[1.0, 0.0, 0.0, 1.0, tx, ty], &line_matrix);
+                    moved = true;
+                }
+            }
+            "Tm" => {
+                if let Ok(matrix) = <[f32; 6]>::try_from(operands) {
+                    line_matrix = matrix;
+                    moved = true;
+                }
+            }
+            "T*" | "'" | "\"" => {
+                line_matrix = concat_matrix(&[1.0, 0.0, 0.0, 1.0, 0.0, -leading], &line_matrix);
+                moved = true;
+            }
+            _ => {}
+        }
+
+        if !matches!(operation.operator.as_str(), "Tj" | "TJ" | "'" | "\"") {
+            continue;
+        }
+
+        let encoding = font.as_ref().and_then(|name| encodings.get(name));
+        let text = decode_operands(&operation.operands, encoding);
+        if text.trim().is_empty() {
+            continue;
+        }
+
+        match runs.last_mut() {
+            Some(last) if !moved => last.text.push_str(&text),
+            _ => {
+                let matrix = concat_matrix(&line_matrix, &ctm);
+                runs.push(TextRun {
+                    x: matrix[4],
+                    y: matrix[5],
+                    size: font_size * matrix[2].hypot(matrix[3]),
+                    text,
+                });
+            }
+        }
+        moved = false;
+    }
+
+    runs
+}
+
+/// Decode the string operands of a text-showing operator
+fn decode_operands(operands: &[Object], encoding: Option<&Encoding>) -> String {
+    let mut text = String::new();
+    for operand in operands {
+        match operand {
+            Object::String(bytes, _) => {
+                match encoding.and_then(|e| e.bytes_to_string(bytes).ok()) {
+                    Some(decoded) => text.push_str(&decoded),
+                    None => text.extend(bytes.iter().map(|&b| b as char)),
+                }
+            }
+            Object::Array(items) => text.push_str(&decode_operands(items, encoding)),
+            // Large negative adjustments in `TJ` arrays separate words
+            Object::Integer(i) if *i < -200 => text.push(' '),
+            Object::Real(r) if *r < -200.0 => text.push(' '),
+            _ => {}
+        }
+    }
+    text
+}
+
+/// A line of runs sharing a baseline, ordered left to right
+struct Line<'a> {
+    y: f32,
+    runs: Vec<&'a TextRun>,
+}
+
+/// Group runs into lines from the top of the page down
+fn group_lines(runs: &[TextRun]) -> Vec<Line<'_>> {
+    let mut sorted: Vec<&TextRun> = runs.iter().collect();
+    sorted.sort_by(|a, b| b.y.total_cmp(&a.y).then(a.x.total_cmp(&b.x)));
+
+    let mut lines: Vec<Line> = Vec::new();
+    for run in sorted {
+        match lines.last_mut() {
+            Some(line) if (line.y - run.y).abs() < LINE_TOLERANCE => line.runs.push(run),
+            _ => lines.push(Line {
+                y: run.y,
+                runs: vec![run],
+            }),
+        }
+    }
+
+    for line in &mut lines {
+        line.runs.sort_by(|a, b| a.x.total_cmp(&b.x));
+    }
+    lines
+}
+
+/// Detect tables as blocks of consecutive multi-run lines with aligned columns
+pub(crate) fn detect_tables(runs: &[TextRun]) -> Vec<PdfTable> {
+    let lines = group_lines(runs);
+    let mut tables = Vec::new();
+    let mut block: Vec<&Line> = Vec::new();
+
+    for line in lines.iter().map(Some).chain(std::iter::once(None)) {
+        match line {
+            Some(line) if line.runs.len() >= 2 => block.push(line),
+            _ => {
+                if let Some(mut table) = table_from_block(&block) {
+                    table.index = tables.len();
+                    tables.push(table);
+                }
+                block.clear();
+            }
+        }
+    }
+
+    tables
+}
+
+/// Build a table from a block of lines if its runs line up in columns
+fn table_from_block(lines: &[&Line]) -> Option<PdfTable> {
+    if lines.len() < 2 {
+        return None;
+    }
+
+    // Cluster run start positions; each cluster is a candidate column
+    let mut starts: Vec<(f32, usize)> = lines
+        .iter()
+        .enumerate()
+        .flat_map(|(i, line)| line.runs.iter().map(move |run| (run.x, i)))
+        .collect();
+    starts.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut clusters: Vec<Vec<(f32, usize)>> = Vec::new();
+    for start in starts {
+        match clusters.last_mut() {
+            Some(cluster) if start.0 - cluster[cluster.len() - 1].0 <= COLUMN_TOLERANCE => {
+                cluster.push(start)
+            }
+            _ => clusters.push(vec![start]),
+        }
+    }
+
+    // Only columns present in most lines count; stray words in prose do not
+    let min_support = ((lines.len() as f32 * COLUMN_SUPPORT).ceil() as usize).max(2);
+    let columns: Vec<&Vec<(f32, usize)>> = clusters
+        .iter()
+        .filter(|cluster| {
+            let rows: BTreeSet<usize> = cluster.iter().map(|&(_, row)| row).collect();
+            rows.len() >= min_support
+        })
+        .collect();
+    if columns.len() < 2 {
+        return None;
+    }
+
+    let total_runs: usize = lines.iter().map(|line| line.runs.len()).sum();
+    let aligned_runs: usize = columns.iter().map(|cluster| cluster.len()).sum();
+    let filled_cells: usize = columns
+        .iter()
+        .map(|cluster| {
+            let rows: BTreeSet<usize> = cluster.iter().map(|&(_, row)| row).collect();
+            rows.len()
+        })
+        .sum();
+
+    let alignment = aligned_runs as f32 / total_runs as f32;
+    let fill = filled_cells as f32 / (columns.len() * lines.len()) as f32;
+    let confidence = alignment * fill;
+    if confidence < MIN_TABLE_CONFIDENCE {
+        debug!(
+            "Rejected table candidate with {} lines (confidence {:.2})",
+            lines.len(),
+            confidence
+        );
+        return None;
+    }
+
+    let column_starts: Vec<f32> = columns.iter().map(|cluster| cluster[0].0).collect();
+    let data: Vec<Vec<String>> = lines
+        .iter()
+        .map(|line| {
+            let mut cells = vec![String::new(); column_starts.len()];
+            for run in &line.runs {
+                // Unaligned runs continue the cell of the column to their left
+                let col = column_starts
+                    .iter()
+                    .rposition(|&x| x <= run.x + COLUMN_TOLERANCE)
+                    .unwrap_or(0);
+                if !cells[col].is_empty() {
+                    cells[col].push(' ');
+                }
+                cells[col].push_str(run.text.trim());
+            }
+            cells
+        })
+        .collect();
+
+    let font_size = lines
+        .iter()
+        .flat_map(|line| line.runs.iter().map(|run| run.size))
+        .fold(0.0, f32::max);
+    let top = lines[0].y;
+    let bottom = lines[lines.len() - 1].y;
+    let left = column_starts[0];
+    // Glyph widths are unknown, so estimate the last column from its longest cell
+    let last_width = data
+        .iter()
+        .map(|row| row[row.len() - 1].chars().count())
+        .max()
+        .unwrap_or(0) as f32
+        * font_size
+        * 0.5;
+
+    Some(PdfTable {
+        index: 0,
+        rows: data.len(),
+        cols: column_starts.len(),
+        data,
+        position: BlockPosition {
+            x: left,
+            y: bottom,
+            width: column_starts[column_starts.len() - 1] - left + last_width,
+            height: top - bottom + font_size,
+        },
+        confidence,
+    })
+}
@@ -2,6 +2,7 @@
 
 pub mod encrypted;
 pub mod extractor;
+mod layout;
 pub mod ocr;
 pub mod provider;
 
@@ -10,8 +11,8 @@ pub mod tests;
 
 pub use extractor::{
     AdvancedPdfExtractor, AdvancedPdfResult, BlockPosition, ExtractionStats, FontInfo, ImageInfo,
-    PageDimensions, PdfDocumentMetadata, PdfExtractConfig, PdfPage, PdfPermissions, PdfTable,
-    TextBlock, TextBlockType,
+    PageDimensions, PdfDocumentMetadata, PdfExtractConfig, PdfLink, PdfPage, PdfPermissions,
+    PdfTable, TextBlock, TextBlockType,
 };
 
 pub use encrypted::{
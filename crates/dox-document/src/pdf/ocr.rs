@@ -20,6 +20,14 @@ pub trait OcrEngine: Send + Sync {
         language: &str,
     ) -> Result<String, OcrError>;
 
+    /// Extract individual words with their positions
+    ///
+    /// Engines that only produce plain text can rely on the default, which
+    /// reports no words.
+    fn extract_words(&self, _image_data: &[u8], _language: &str) -> Result<Vec<OcrWord>, OcrError> {
+        Ok(vec![])
+    }
+
     /// Get supported languages
     fn supported_languages(&self) -> Vec<String>;
 
@@ -173,6 +181,19 @@ impl PdfOcrProcessor {
         }
     }
 
+    /// Create an OCR processor using the given engine
+    pub fn with_engine(config: OcrConfig, engine: Box<dyn OcrEngine>) -> Self {
+        Self {
+            config,
+            engine: Some(engine),
+        }
+    }
+
+    /// Check whether an engine is ready for processing
+    pub fn has_engine(&self) -> bool {
+        self.engine.is_some()
+    }
+
     /// Initialize OCR engine (would integrate with tesseract-rs or similar)
     pub fn initialize_engine(&mut self) -> Result<(), OcrError> {
         info!("Initializing OCR engine");
@@ -219,9 +240,8 @@ impl PdfOcrProcessor {
         let start_time = std::time::Instant::now();
 
         // Try primary language first
-        let mut result =
-            engine.extract_text_from_image(page_data, &self.config.primary_language)?;
-        let processing_time = start_time.elapsed().as_millis() as u64;
+        let mut language = &self.config.primary_language;
+        let mut result = engine.extract_text_from_image(page_data, language)?;
 
         // If confidence is low, try fallback languages
         if self.should_try_fallback(&result) {
@@ -231,6 +251,7 @@ impl PdfOcrProcessor {
                     Ok(fallback_result) => {
                         if self.is_better_result(&fallback_result, &result) {
                             result = fallback_result;
+                            language = lang;
                         }
                     }
                     Err(e) => {
@@ -240,12 +261,20 @@ impl PdfOcrProcessor {
             }
         }
 
+        let words = engine
+            .extract_words(page_data, language)
+            .unwrap_or_else(|e| {
+                warn!("Word positions unavailable for page {}: {}", page_number, e);
+                vec![]
+            });
+        let processing_time = start_time.elapsed().as_millis() as u64;
+
         Ok(OcrResult {
             text: result,
             confidence: 0.8, // Mock confidence
-            language: self.config.primary_language.clone(),
+            language: language.clone(),
             processing_time_ms: processing_time,
-            words: vec![], // Would be populated by real OCR engine
+            words,
         })
     }
 
@@ -343,7 +372,7 @@ impl OcrEngine for MockOcrEngine {
     }
 }
 
-/// Convenience functions for OCR processing
+// Convenience functions for OCR processing
 
 /// Quick OCR analysis of a PDF
 pub fn analyze_pdf_ocr_requirements(path: &Path) -> Result<OcrAnalysis, DocumentError> {
@@ -361,7 +390,7 @@ pub fn process_pdf_with_ocr(
 
     processor
         .initialize_engine()
-        .map_err(|e| DocumentError::OperationFailed {
+        .map_err(|e| DocumentError::OcrFailed {
             reason: format!("OCR initialization failed: {}", e),
         })?;
 
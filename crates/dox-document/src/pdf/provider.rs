@@ -35,17 +35,21 @@ impl PdfProvider {
             });
         }
 
-        // Verify it's a PDF file
-        if let Some(extension) = path.extension() {
-            if extension.to_string_lossy().to_lowercase() != "pdf" {
-                return Err(DocumentError::UnsupportedFormat {
-                    format: extension.to_string_lossy().to_string(),
-                });
+        // Verify it's a PDF file; misnamed files are accepted by their header
+        if !crate::provider::has_pdf_header(path) {
+            match path.extension() {
+                Some(extension) if extension.to_string_lossy().to_lowercase() == "pdf" => {}
+                Some(extension) => {
+                    return Err(DocumentError::UnsupportedFormat {
+                        format: extension.to_string_lossy().to_string(),
+                    });
+                }
+                None => {
+                    return Err(DocumentError::UnsupportedFormat {
+                        format: "unknown".to_string(),
+                    });
+                }
             }
-        } else {
-            return Err(DocumentError::UnsupportedFormat {
-                format: "unknown".to_string(),
-            });
         }
 
         Ok(PdfProvider {
@@ -76,15 +80,22 @@ impl PdfProvider {
     pub fn get_metadata(&self) -> Result<PdfMetadata, DocumentError> {
         debug!("Extracting metadata from PDF: {}", self.path.display());
 
-        let document =
-            lopdf::Document::load(&self.path).map_err(|e| DocumentError::OperationFailed {
-                reason: format!("Failed to load PDF for metadata: {}", e),
-            })?;
-
-        let mut metadata = PdfMetadata::default();
+        let document = lopdf::Document::load(&self.path)
+            .map_err(|e| super::encrypted::load_error(&self.path, e))?;
 
         // Get page count
-        metadata.page_count = document.get_pages().len();
+        let mut metadata = PdfMetadata {
+            page_count: document.get_pages().len(),
+            ..Default::default()
+        };
+
+        // The catalog's /Lang holds the document's natural language
+        metadata.language = document
+            .catalog()
+            .and_then(|catalog| catalog.get(b"Lang"))
+            .and_then(|lang| lang.as_str())
+            .ok()
+            .map(|lang| String::from_utf8_lossy(lang).to_string());
 
         // Try to get document info
         if let Ok(info_dict) = document.trailer.get(b"Info") {
@@ -208,13 +219,12 @@ impl PdfProvider {
                     warn!("PDF authentication failed - incorrect password");
                     Ok(false)
                 }
-                crate::pdf::PasswordResult::Error(err) => Err(DocumentError::OperationFailed {
-                    reason: format!("PDF authentication error: {}", err),
-                }),
+                crate::pdf::PasswordResult::Error(err) => {
+                    warn!("PDF authentication error: {}", err);
+                    Err(DocumentError::EncryptionRequired)
+                }
             },
-            Err(e) => Err(DocumentError::OperationFailed {
-                reason: format!("PDF authentication failed: {}", e),
-            }),
+            Err(e) => Err(e),
         }
     }
 
@@ -223,11 +233,7 @@ impl PdfProvider {
         info!("Trying common passwords for PDF");
         let mut handler = EncryptedPdfHandler::new(&self.path)?;
 
-        handler
-            .try_common_passwords()
-            .map_err(|e| DocumentError::OperationFailed {
-                reason: format!("Failed to try common passwords: {}", e),
-            })
+        handler.try_common_passwords()
     }
 
     /// Analyze PDF for OCR requirements
@@ -254,27 +260,26 @@ impl PdfProvider {
     pub fn get_advanced_text(&self) -> Result<String, DocumentError> {
         debug!("Extracting advanced text from PDF: {}", self.path.display());
 
-        // Check encryption status first
-        let encryption_info = self.check_encryption()?;
-        if encryption_info.is_encrypted {
+        // Without a configured password, fall back to common ones
+        let mut config = self.extract_config.clone();
+        if config.password.is_none() && self.check_encryption()?.is_encrypted {
             info!("PDF is encrypted, attempting common passwords");
-            if let Some(_password) = self.try_common_passwords()? {
-                info!("Found working password for encrypted PDF");
-            } else {
+            config.password = self.try_common_passwords()?;
+            if config.password.is_none() {
                 warn!("Could not authenticate encrypted PDF");
-                return Err(DocumentError::OperationFailed {
-                    reason: "PDF is encrypted and requires authentication".to_string(),
-                });
             }
         }
 
         // Use advanced extractor
-        let mut extractor = AdvancedPdfExtractor::new(&self.path, self.extract_config.clone())?;
+        let mut extractor = AdvancedPdfExtractor::new(&self.path, config)?;
         let result = extractor.extract()?;
 
-        // Combine all page text
+        // Combine all page text, separating pages with form feeds
         let mut combined_text = String::new();
-        for page in result.pages {
+        for (index, page) in result.pages.into_iter().enumerate() {
+            if index > 0 {
+                combined_text.push('\x0C');
+            }
             if self.extract_config.preserve_layout {
                 // Use structured text blocks
                 for block in page.text_blocks {
@@ -328,6 +333,13 @@ impl PdfProvider {
         Ok(all_tables)
     }
 
+    /// Extract URI links with the number of the page they are on
+    pub fn extract_links(&self) -> Result<Vec<(usize, crate::pdf::PdfLink)>, DocumentError> {
+        debug!("Extracting links from PDF: {}", self.path.display());
+
+        AdvancedPdfExtractor::new(&self.path, self.extract_config.clone())?.extract_page_links()
+    }
+
     /// Process PDF with OCR (for image-based PDFs)
     pub fn process_with_ocr(&self, config: Option<OcrConfig>) -> Result<String, DocumentError> {
         info!("Processing PDF with OCR: {}", self.path.display());
@@ -337,7 +349,7 @@ impl PdfProvider {
 
         processor
             .initialize_engine()
-            .map_err(|e| DocumentError::OperationFailed {
+            .map_err(|e| DocumentError::OcrFailed {
                 reason: format!("OCR initialization failed: {}", e),
             })?;
 
@@ -357,6 +369,8 @@ pub struct PdfMetadata {
     pub page_count: usize,
     pub created: Option<String>,
     pub modified: Option<String>,
+    /// Natural language declared in the catalog (`/Lang`), e.g. `en-US`
+    pub language: Option<String>,
 }
 
 impl DocumentProvider for PdfProvider {
@@ -1,9 +1,10 @@
 //! Comprehensive tests for PDF functionality
 
 #[cfg(test)]
+#[allow(clippy::module_inception)]
 mod tests {
     use super::super::*;
-    use crate::provider::DocumentProvider;
+    use crate::provider::{DocumentError, DocumentProvider};
     use std::io::Write;
     use tempfile::NamedTempFile;
 
@@ -143,20 +144,9 @@ mod tests {
         let list_item = TextBlockType::ListItem;
 
         // Test that they can be created and matched
-        match paragraph {
-            TextBlockType::Paragraph => assert!(true),
-            _ => assert!(false),
-        }
-
-        match heading {
-            TextBlockType::Heading(level) => assert_eq!(level, 1),
-            _ => assert!(false),
-        }
-
-        match list_item {
-            TextBlockType::ListItem => assert!(true),
-            _ => assert!(false),
-        }
+        assert!(matches!(paragraph, TextBlockType::Paragraph));
+        assert!(matches!(heading, TextBlockType::Heading(1)));
+        assert!(matches!(list_item, TextBlockType::ListItem));
     }
 
     #[test]
@@ -315,4 +305,696 @@ mod tests {
         assert!(analysis.recommended_ocr);
         assert_eq!(analysis.estimated_processing_time_minutes, 15);
     }
+
+    /// Build a PDF whose pages are `(content stream, resources)` pairs created by `pages`
+    fn build_pdf(
+        pages: impl FnOnce(&mut lopdf::Document) -> Vec<(String, lopdf::Dictionary)>,
+    ) -> NamedTempFile {
+        save_pdf(build_document(pages))
+    }
+
+    fn build_document(
+        pages: impl FnOnce(&mut lopdf::Document) -> Vec<(String, lopdf::Dictionary)>,
+    ) -> lopdf::Document {
+        use lopdf::{dictionary, Object, Stream};
+
+        let mut doc = lopdf::Document::with_version("1.5");
+        let pages_id = doc.new_object_id();
+
+        let mut kids = Vec::new();
+        for (content, resources) in pages(&mut doc) {
+            let content_id = doc.add_object(Stream::new(dictionary! {}, content.into()));
+            let page_id = doc.add_object(dictionary! {
+                "Type" => "Page",
+                "Parent" => pages_id,
+                "Contents" => content_id,
+                "Resources" => resources,
+                "MediaBox" => vec![0.into(), 0.into(), 612.into(), 792.into()],
+            });
+            kids.push(Object::from(page_id));
+        }
+
+        let count = kids.len() as i64;
+        doc.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Kids" => kids,
+                "Count" => count,
+            }),
+        );
+        let catalog_id = doc.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+        });
+        doc.trailer.set("Root", catalog_id);
+        doc
+    }
+
+    fn save_pdf(mut doc: lopdf::Document) -> NamedTempFile {
+        let mut temp_file = NamedTempFile::with_suffix(".pdf").unwrap();
+        doc.save_to(&mut temp_file).unwrap();
+        temp_file
+    }
+
+    /// Create a one-page PDF drawing a 64x48 JPEG at (50, 600), scaled to 200x100
+    fn create_pdf_with_jpeg() -> NamedTempFile {
+        use lopdf::{dictionary, Stream};
+
+        build_pdf(|doc| {
+            let image_id = doc.add_object(Stream::new(
+                dictionary! {
+                    "Type" => "XObject",
+                    "Subtype" => "Image",
+                    "Width" => 64,
+                    "Height" => 48,
+                    "ColorSpace" => "DeviceRGB",
+                    "BitsPerComponent" => 8,
+                    "Filter" => "DCTDecode",
+                },
+                vec![0xFF, 0xD8, 0xFF, 0xD9],
+            ));
+            let resources = dictionary! {
+                "XObject" => dictionary! { "Im1" => image_id },
+            };
+            vec![("q 200 0 0 100 50 600 cm /Im1 Do Q".to_string(), resources)]
+        })
+    }
+
+    /// Create a PDF with one line of Helvetica text per page
+    fn create_pdf_with_text_pages(contents: &[&str]) -> NamedTempFile {
+        save_pdf(text_document(contents))
+    }
+
+    fn text_document(contents: &[&str]) -> lopdf::Document {
+        use lopdf::dictionary;
+
+        build_document(|doc| {
+            let font_id = doc.add_object(dictionary! {
+                "Type" => "Font",
+                "Subtype" => "Type1",
+                "BaseFont" => "Helvetica",
+                "Encoding" => "WinAnsiEncoding",
+            });
+            contents
+                .iter()
+                .map(|&content| {
+                    let resources = dictionary! {
+                        "Font" => dictionary! { "F1" => font_id },
+                    };
+                    (content.to_string(), resources)
+                })
+                .collect()
+        })
+    }
+
+    #[test]
+    fn test_extract_jpeg_image() {
+        let temp_file = create_pdf_with_jpeg();
+
+        let config = PdfExtractConfig {
+            extract_images: true,
+            ..PdfExtractConfig::small_file()
+        };
+        let result = AdvancedPdfExtractor::new(temp_file.path(), config)
+            .unwrap()
+            .extract()
+            .unwrap();
+
+        assert_eq!(result.stats.images_detected, 1);
+        let images = &result.pages[0].images;
+        assert_eq!(images.len(), 1);
+        assert_eq!(images[0].format, "JPEG");
+        assert_eq!((images[0].width, images[0].height), (64, 48));
+        assert_eq!((images[0].position.x, images[0].position.y), (50.0, 600.0));
+        assert_eq!(images[0].position.width, 200.0);
+        assert_eq!(images[0].position.height, 100.0);
+
+        // Image extraction is off by default
+        let result = AdvancedPdfExtractor::new(temp_file.path(), PdfExtractConfig::default())
+            .unwrap()
+            .extract()
+            .unwrap();
+        assert_eq!(result.stats.images_detected, 0);
+        assert!(result.pages[0].images.is_empty());
+    }
+
+    #[test]
+    fn test_extract_uri_links() {
+        use crate::extract::ExtractorFactory;
+        use lopdf::{dictionary, Object};
+
+        let mut doc = text_document(&[
+            "BT /F1 12 Tf 72 700 Td (See the) Tj 60 0 Td (release notes) Tj ET \
+             BT /F1 12 Tf 72 650 Td (Unlinked text) Tj ET",
+        ]);
+        let page_id = *doc.get_pages().get(&1).unwrap();
+        let link = doc.add_object(dictionary! {
+            "Type" => "Annot",
+            "Subtype" => "Link",
+            "Rect" => vec![130.into(), 695.into(), 230.into(), 712.into()],
+            "A" => dictionary! {
+                "S" => "URI",
+                "URI" => Object::string_literal("https://example.com/releases"),
+            },
+        });
+        // Links without a URI action, such as jumps within the document, are skipped
+        let jump = doc.add_object(dictionary! {
+            "Type" => "Annot",
+            "Subtype" => "Link",
+            "Rect" => vec![72.into(), 645.into(), 150.into(), 662.into()],
+            "Dest" => vec![Object::from(page_id), "Fit".into()],
+        });
+        doc.get_object_mut(page_id)
+            .and_then(Object::as_dict_mut)
+            .unwrap()
+            .set("Annots", vec![Object::from(link), Object::from(jump)]);
+        let temp_file = save_pdf(doc);
+
+        let result = AdvancedPdfExtractor::new(temp_file.path(), PdfExtractConfig::default())
+            .unwrap()
+            .extract()
+            .unwrap();
+        let links = &result.pages[0].links;
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].text, "release notes");
+        assert_eq!(links[0].url, "https://example.com/releases");
+        assert_eq!((links[0].position.x, links[0].position.y), (130.0, 695.0));
+
+        // Links alone come from the page annotations, limited to the page range
+        let links = PdfProvider::open(temp_file.path())
+            .unwrap()
+            .extract_links()
+            .unwrap();
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].0, 1);
+        assert_eq!(links[0].1.url, "https://example.com/releases");
+        let config = PdfExtractConfig {
+            page_range: Some("2".parse().unwrap()),
+            ..PdfExtractConfig::default()
+        };
+        let links = AdvancedPdfExtractor::new(temp_file.path(), config)
+            .unwrap()
+            .extract_page_links()
+            .unwrap();
+        assert!(links.is_empty());
+
+        let extracted = ExtractorFactory::create_extractor(temp_file.path())
+            .unwrap()
+            .extract(temp_file.path())
+            .unwrap();
+        let links = &extracted.pages[0].links;
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].text, "release notes");
+        assert_eq!(links[0].url, "https://example.com/releases");
+    }
+
+    #[test]
+    fn test_text_extracted_per_page() {
+        let temp_file = create_pdf_with_text_pages(&[
+            "BT /F1 12 Tf 72 700 Td (FIRST alpha) Tj ET",
+            "BT /F1 12 Tf 72 700 Td (SECOND omega on a page with a much longer line) Tj ET",
+        ]);
+
+        for config in [
+            PdfExtractConfig::small_file(),
+            PdfExtractConfig::large_file(),
+        ] {
+            let config = PdfExtractConfig {
+                max_memory_mb: 0,
+                ..config
+            };
+            let result = AdvancedPdfExtractor::new(temp_file.path(), config)
+                .unwrap()
+                .extract()
+                .unwrap();
+
+            assert_eq!(result.pages.len(), 2);
+            assert!(result.pages[0].raw_text.contains("FIRST alpha"));
+            assert!(!result.pages[0].raw_text.contains("SECOND"));
+            assert!(result.pages[1].raw_text.contains("SECOND omega"));
+            assert!(!result.pages[1].raw_text.contains("FIRST"));
+        }
+    }
+
+    #[test]
+    fn test_callback_receives_pages_in_order() {
+        let temp_file = create_pdf_with_text_pages(&[
+            "BT /F1 12 Tf 72 700 Td (page one) Tj ET",
+            "BT /F1 12 Tf 72 700 Td (page two) Tj ET",
+            "BT /F1 12 Tf 72 700 Td (page three) Tj ET",
+        ]);
+
+        let mut extractor =
+            AdvancedPdfExtractor::new(temp_file.path(), PdfExtractConfig::default()).unwrap();
+        let mut seen = Vec::new();
+        let stats = extractor
+            .extract_with_callback(|page| {
+                seen.push((page.number, page.raw_text));
+                Ok(())
+            })
+            .unwrap();
+
+        assert_eq!(stats.total_pages, 3);
+        assert!(stats.streaming_used);
+        let numbers: Vec<usize> = seen.iter().map(|(number, _)| *number).collect();
+        assert_eq!(numbers, vec![1, 2, 3]);
+        assert!(seen[0].1.contains("page one"));
+        assert!(seen[2].1.contains("page three"));
+    }
+
+    #[test]
+    fn test_callback_error_aborts_extraction() {
+        let temp_file = create_pdf_with_text_pages(&[
+            "BT /F1 12 Tf 72 700 Td (page one) Tj ET",
+            "BT /F1 12 Tf 72 700 Td (page two) Tj ET",
+            "BT /F1 12 Tf 72 700 Td (page three) Tj ET",
+        ]);
+
+        let mut extractor =
+            AdvancedPdfExtractor::new(temp_file.path(), PdfExtractConfig::default()).unwrap();
+        let mut calls = 0;
+        let result = extractor.extract_with_callback(|page| {
+            calls += 1;
+            if page.number == 2 {
+                return Err(DocumentError::OperationFailed {
+                    reason: "stop".to_string(),
+                });
+            }
+            Ok(())
+        });
+
+        assert!(matches!(
+            result,
+            Err(DocumentError::OperationFailed { reason }) if reason == "stop"
+        ));
+        assert_eq!(calls, 2);
+    }
+
+    #[test]
+    fn test_extract_page_range() {
+        use crate::extract::ExtractorFactory;
+        use crate::page_range::PageRange;
+        use crate::provider::DocumentType;
+
+        let contents: Vec<String> = (1..=5)
+            .map(|n| format!("BT /F1 12 Tf 72 700 Td (PAGE{} text) Tj ET", n))
+            .collect();
+        let contents: Vec<&str> = contents.iter().map(String::as_str).collect();
+        let temp_file = create_pdf_with_text_pages(&contents);
+
+        let range: PageRange = "2-3".parse().unwrap();
+        let result = ExtractorFactory::extractor_with_pages(DocumentType::Pdf, Some(&range))
+            .extract(temp_file.path())
+            .unwrap();
+        let numbers: Vec<usize> = result.pages.iter().map(|page| page.number).collect();
+        assert_eq!(numbers, vec![2, 3]);
+        assert!(result.pages[0].text.contains("PAGE2"));
+        assert!(result.pages[1].text.contains("PAGE3"));
+        for other in ["PAGE1", "PAGE4", "PAGE5"] {
+            assert!(!result.pages.iter().any(|page| page.text.contains(other)));
+        }
+        assert_eq!(result.metadata.total_pages, 5);
+
+        // Pages past the end are dropped with a warning, in memory and streamed
+        for max_memory_mb in [512, 0] {
+            let config = PdfExtractConfig {
+                page_range: Some("4-9".parse().unwrap()),
+                max_memory_mb,
+                ..PdfExtractConfig::default()
+            };
+            let result = AdvancedPdfExtractor::new(temp_file.path(), config)
+                .unwrap()
+                .extract()
+                .unwrap();
+            let numbers: Vec<usize> = result.pages.iter().map(|page| page.number).collect();
+            assert_eq!(numbers, vec![4, 5]);
+            assert!(result.pages[1].raw_text.contains("PAGE5"));
+            assert_eq!(result.warnings.len(), 1);
+            assert!(result.warnings[0].contains("4-9"));
+        }
+    }
+
+    #[test]
+    fn test_declared_language() {
+        use crate::extract::ExtractorFactory;
+        use lopdf::Object;
+
+        let mut doc = text_document(&["BT /F1 12 Tf 72 700 Td (Quarterly report) Tj ET"]);
+        let extract = |doc: lopdf::Document| {
+            let temp_file = save_pdf(doc);
+            ExtractorFactory::create_extractor(temp_file.path())
+                .unwrap()
+                .extract(temp_file.path())
+                .unwrap()
+                .metadata
+                .language
+        };
+        assert_eq!(extract(doc.clone()).as_deref(), Some("en"));
+
+        doc.catalog_mut()
+            .unwrap()
+            .set("Lang", Object::string_literal("fr-FR"));
+        assert_eq!(extract(doc).as_deref(), Some("fr"));
+    }
+
+    /// OCR engine returning a fixed word, or failing when `fail` is set
+    struct FixedOcrEngine {
+        fail: bool,
+    }
+
+    impl OcrEngine for FixedOcrEngine {
+        fn extract_text_from_image(
+            &self,
+            _image_data: &[u8],
+            _language: &str,
+        ) -> Result<String, OcrError> {
+            if self.fail {
+                return Err(OcrError::RecognitionFailed {
+                    reason: "unreadable scan".to_string(),
+                });
+            }
+            Ok("Scanned invoice".to_string())
+        }
+
+        fn extract_words(
+            &self,
+            _image_data: &[u8],
+            _language: &str,
+        ) -> Result<Vec<OcrWord>, OcrError> {
+            Ok(vec![OcrWord {
+                text: "invoice".to_string(),
+                confidence: 0.9,
+                bbox: BoundingBox {
+                    x: 60.0,
+                    y: 620.0,
+                    width: 80.0,
+                    height: 20.0,
+                },
+            }])
+        }
+
+        fn supported_languages(&self) -> Vec<String> {
+            vec!["eng".to_string()]
+        }
+
+        fn engine_info(&self) -> OcrEngineInfo {
+            OcrEngineInfo {
+                name: "Fixed".to_string(),
+                version: "1.0".to_string(),
+                supported_formats: vec!["JPEG".to_string()],
+            }
+        }
+    }
+
+    fn extract_scanned(enable_ocr: bool, fail: bool) -> AdvancedPdfResult {
+        let temp_file = create_pdf_with_jpeg();
+        let config = PdfExtractConfig {
+            enable_ocr,
+            ..PdfExtractConfig::small_file()
+        };
+        let processor =
+            PdfOcrProcessor::with_engine(OcrConfig::english(), Box::new(FixedOcrEngine { fail }));
+
+        AdvancedPdfExtractor::new(temp_file.path(), config)
+            .unwrap()
+            .with_ocr_processor(processor)
+            .extract()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_ocr_for_image_only_page() {
+        let result = extract_scanned(true, false);
+        let page = &result.pages[0];
+        assert_eq!(page.raw_text.trim(), "Scanned invoice");
+
+        let word = page
+            .text_blocks
+            .iter()
+            .find(|block| block.text == "invoice")
+            .unwrap();
+        assert_eq!((word.position.x, word.position.y), (60.0, 620.0));
+        assert!(result.warnings.is_empty());
+
+        let result = extract_scanned(false, false);
+        assert!(result.pages[0].raw_text.trim().is_empty());
+        assert!(result.pages[0].text_blocks.is_empty());
+    }
+
+    #[test]
+    fn test_ocr_failure_becomes_warning() {
+        let result = extract_scanned(true, true);
+
+        assert!(result.pages[0].raw_text.trim().is_empty());
+        assert_eq!(result.warnings.len(), 1);
+        assert!(result.warnings[0].contains("unreadable scan"));
+        assert!(result.warnings[0].contains("page 1"));
+    }
+
+    /// RC4 stream cipher used by the PDF standard security handler
+    fn rc4(key: &[u8], data: &[u8]) -> Vec<u8> {
+        let mut state: Vec<u8> = (0..=255).collect();
+        let mut j = 0u8;
+        for i in 0..256 {
+            j = j.wrapping_add(state[i]).wrapping_add(key[i % key.len()]);
+            state.swap(i, j as usize);
+        }
+
+        let (mut i, mut j) = (0u8, 0u8);
+        data.iter()
+            .map(|byte| {
+                i = i.wrapping_add(1);
+                j = j.wrapping_add(state[i as usize]);
+                state.swap(i as usize, j as usize);
+                byte ^ state[state[i as usize].wrapping_add(state[j as usize]) as usize]
+            })
+            .collect()
+    }
+
+    /// Encrypt a document with 128-bit RC4 (revision 4) for `user_password`
+    fn encrypt_document(doc: &mut lopdf::Document, user_password: &str, permissions: i64) {
+        use lopdf::{dictionary, Object};
+        use md5::{Digest, Md5};
+
+        const PAD: [u8; 32] = [
+            0x28, 0xBF, 0x4E, 0x5E, 0x4E, 0x75, 0x8A, 0x41, 0x64, 0x00, 0x4E, 0x56, 0xFF, 0xFA,
+            0x01, 0x08, 0x2E, 0x2E, 0x00, 0xB6, 0xD0, 0x68, 0x3E, 0x80, 0x2F, 0x0C, 0xA9, 0xFE,
+            0x64, 0x53, 0x69, 0x7A,
+        ];
+        let file_id = b"dox-test-file-id".to_vec();
+        doc.trailer.set(
+            "ID",
+            vec![
+                Object::string_literal(file_id.clone()),
+                Object::string_literal(file_id.clone()),
+            ],
+        );
+
+        let encrypt_id = doc.add_object(dictionary! {
+            "Filter" => "Standard",
+            "V" => 4,
+            "R" => 4,
+            "Length" => 128,
+            "P" => permissions,
+            "O" => Object::string_literal(vec![0x42; 32]),
+            "CF" => dictionary! {
+                "StdCF" => dictionary! { "CFM" => "V2", "Length" => 16 },
+            },
+            "StmF" => "StdCF",
+            "StrF" => "StdCF",
+        });
+        doc.trailer.set("Encrypt", encrypt_id);
+
+        let key = lopdf::encryption::get_encryption_key(doc, user_password, false).unwrap();
+
+        // Algorithm 3.5: the /U entry lets readers verify the user password
+        let mut user_check = rc4(
+            &key,
+            &Md5::new()
+                .chain_update(PAD)
+                .chain_update(&file_id)
+                .finalize(),
+        );
+        for round in 1..=19u8 {
+            let round_key: Vec<u8> = key.iter().map(|b| b ^ round).collect();
+            user_check = rc4(&round_key, &user_check);
+        }
+        user_check.extend_from_slice(&PAD[..16]);
+        doc.get_dictionary_mut(encrypt_id)
+            .unwrap()
+            .set("U", Object::string_literal(user_check));
+
+        // RC4 is symmetric, so decrypting plain objects encrypts them
+        for (&id, object) in doc.objects.iter_mut() {
+            if id == encrypt_id {
+                continue;
+            }
+            if let Ok(data) = lopdf::encryption::decrypt_object(&key, id, object, false) {
+                match object {
+                    Object::Stream(stream) => stream.set_content(data),
+                    Object::String(content, _) => *content = data,
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    fn create_encrypted_pdf() -> NamedTempFile {
+        let mut doc = text_document(&["BT /F1 12 Tf 72 700 Td (Confidential figures) Tj ET"]);
+        // Printing allowed; modifying, copying and annotating not
+        encrypt_document(&mut doc, "secret", -3900);
+        save_pdf(doc)
+    }
+
+    fn extract_with_password(
+        path: &std::path::Path,
+        password: Option<&str>,
+    ) -> Result<AdvancedPdfResult, DocumentError> {
+        let config = PdfExtractConfig {
+            password: password.map(str::to_string),
+            ..PdfExtractConfig::small_file()
+        };
+        AdvancedPdfExtractor::new(path, config)?.extract()
+    }
+
+    #[test]
+    fn test_encrypted_pdf_with_correct_password() {
+        let temp_file = create_encrypted_pdf();
+
+        let result = extract_with_password(temp_file.path(), Some("secret")).unwrap();
+
+        assert!(result.pages[0].raw_text.contains("Confidential figures"));
+        assert!(result.metadata.encrypted);
+        assert!(result.metadata.permissions.print);
+        assert!(!result.metadata.permissions.modify);
+        assert!(!result.metadata.permissions.copy);
+        assert!(!result.metadata.permissions.annotate);
+    }
+
+    #[test]
+    fn test_encrypted_pdf_with_wrong_or_missing_password() {
+        let temp_file = create_encrypted_pdf();
+
+        let err = extract_with_password(temp_file.path(), Some("guess")).unwrap_err();
+        assert!(matches!(err, DocumentError::IncorrectPassword { .. }));
+
+        let err = extract_with_password(temp_file.path(), None).unwrap_err();
+        assert!(matches!(err, DocumentError::PasswordRequired { .. }));
+    }
+
+    #[test]
+    fn test_load_failures_are_not_structure_errors() {
+        let temp_file = create_encrypted_pdf();
+        let provider = PdfProvider::open(temp_file.path()).unwrap();
+        assert!(!provider.authenticate("guess").unwrap());
+        assert!(provider.authenticate("secret").unwrap());
+
+        let mut garbage = NamedTempFile::with_suffix(".pdf").unwrap();
+        garbage.write_all(b"not a pdf at all").unwrap();
+        let provider = PdfProvider::open(garbage.path()).unwrap();
+        let err = provider.get_metadata().unwrap_err();
+        assert!(
+            matches!(err, DocumentError::FileReadError { .. }),
+            "{:?}",
+            err
+        );
+        let err = provider.authenticate("secret").unwrap_err();
+        assert!(
+            matches!(err, DocumentError::FileReadError { .. }),
+            "{:?}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_metadata_version_and_permissions() {
+        let mut doc = text_document(&["BT /F1 12 Tf 72 700 Td (Version check) Tj ET"]);
+        doc.version = "1.7".to_string();
+        let temp_file = save_pdf(doc);
+
+        let result = extract_with_password(temp_file.path(), None).unwrap();
+        assert_eq!(result.metadata.pdf_version, "1.7");
+        assert!(!result.metadata.encrypted);
+        assert!(result.metadata.permissions.print);
+        assert!(result.metadata.permissions.copy);
+
+        // Opens with the empty user password, but printing is disabled
+        let mut doc = text_document(&["BT /F1 12 Tf 72 700 Td (No printing) Tj ET"]);
+        encrypt_document(&mut doc, "", -3904);
+        let temp_file = save_pdf(doc);
+
+        let result = extract_with_password(temp_file.path(), None).unwrap();
+        assert_eq!(result.metadata.pdf_version, "1.5");
+        assert!(result.metadata.encrypted);
+        assert!(!result.metadata.permissions.print);
+        assert!(result.pages[0].raw_text.contains("No printing"));
+    }
+
+    /// Content stream placing each word at an explicit position
+    fn positioned_words(words: &[(f32, f32, &str)]) -> String {
+        let shows: Vec<String> = words
+            .iter()
+            .map(|(x, y, word)| format!("1 0 0 1 {} {} Tm ({}) Tj", x, y, word))
+            .collect();
+        format!("BT /F1 10 Tf {} ET", shows.join(" "))
+    }
+
+    fn extract_tables_from(content: &str) -> Vec<PdfTable> {
+        let temp_file = save_pdf(text_document(&[content]));
+        let result = extract_with_password(temp_file.path(), None).unwrap();
+        assert_eq!(result.stats.tables_detected, result.pages[0].tables.len());
+        result.pages[0].tables.clone()
+    }
+
+    #[test]
+    fn test_aligned_table_detected() {
+        // Cells are a single space apart in plain text but share x-positions
+        let mut words = Vec::new();
+        let rows = [
+            ["Item", "Qty", "Price"],
+            ["Apple", "3", "1.50"],
+            ["Banana split", "12", "4.25"],
+            ["Cherry", "7", "0.80"],
+        ];
+        for (row, cells) in rows.iter().enumerate() {
+            let y = 700.0 - row as f32 * 14.0;
+            for (cell, x) in cells.iter().zip([72.0, 180.0, 240.0]) {
+                words.push((x, y, *cell));
+            }
+        }
+
+        let tables = extract_tables_from(&positioned_words(&words));
+
+        assert_eq!(tables.len(), 1);
+        let table = &tables[0];
+        assert_eq!((table.rows, table.cols), (4, 3));
+        assert_eq!(table.data[0], vec!["Item", "Qty", "Price"]);
+        assert_eq!(table.data[2], vec!["Banana split", "12", "4.25"]);
+        assert!(table.confidence > 0.9);
+        assert_eq!(table.position.x, 72.0);
+        assert_eq!(table.position.y, 658.0);
+    }
+
+    #[test]
+    fn test_prose_is_not_a_table() {
+        // Justified prose drawn word by word: lines start together, words drift
+        let lines = [
+            "The quarterly results were broadly in line with",
+            "expectations although several regional offices",
+            "reported weaker demand during the summer months",
+            "and the board asked for a revised outlook soon",
+        ];
+        let mut words = Vec::new();
+        for (row, line) in lines.iter().enumerate() {
+            let y = 700.0 - row as f32 * 14.0;
+            let mut x = 72.0;
+            for word in line.split(' ') {
+                words.push((x, y, word));
+                x += (word.len() as f32 + 1.0) * 5.3;
+            }
+        }
+
+        assert!(extract_tables_from(&positioned_words(&words)).is_empty());
+    }
 }
@@ -1,10 +1,15 @@
 //! PowerPoint document (.pptx) processing implementation
 
+use crate::extract::ExtractedLink;
 use crate::provider::{DocumentError, DocumentProvider, DocumentType};
 use crate::utils::{
-    copy_zip_with_replacements, extract_text_from_xml, read_zip_file, replace_text_in_xml,
+    copy_zip_with_replacements, extract_text_from_xml, extract_zip, open_zip_file,
+    read_relationships, read_zip_file, replace_pattern_in_xml, replace_text_in_xml,
+    resolve_part_target, Relationship,
 };
+use dox_core::utils::write_atomic;
 use std::collections::HashMap;
+use std::io::{Read, Seek};
 use std::path::{Path, PathBuf};
 use tracing::{debug, info};
 use xml::reader::{EventReader, XmlEvent};
@@ -22,6 +27,19 @@ pub struct PowerPointMetadata {
     pub modified: Option<String>,
 }
 
+/// Text of one slide, split by the role of the shape it came from
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SlideText {
+    /// Paragraphs of the title placeholder
+    pub title: Vec<String>,
+    /// Paragraphs of every other shape, in document order
+    pub body: Vec<String>,
+    /// Paragraphs of the speaker notes
+    pub notes: Vec<String>,
+    /// Hyperlinks on the slide's text
+    pub links: Vec<ExtractedLink>,
+}
+
 /// PowerPoint document provider for .pptx files
 #[derive(Debug)]
 pub struct PowerPointProvider {
@@ -44,7 +62,18 @@ impl PowerPointProvider {
 
         // Read entire file into memory
         let archive_data = std::fs::read(path)?;
+        Self::load(path.to_path_buf(), archive_data)
+    }
 
+    /// Open a PowerPoint document held in memory
+    ///
+    /// The document has no path, so changes are written with `save_as`.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, DocumentError> {
+        debug!("Opening PowerPoint document from {} bytes", data.len());
+        Self::load(PathBuf::new(), data.to_vec())
+    }
+
+    fn load(path: PathBuf, archive_data: Vec<u8>) -> Result<Self, DocumentError> {
         // Extract slide contents
         let slide_contents = Self::extract_slide_contents(&archive_data)?;
 
@@ -54,7 +83,7 @@ impl PowerPointProvider {
         );
 
         Ok(PowerPointProvider {
-            path: path.to_path_buf(),
+            path,
             archive_data,
             slide_contents,
             modified: false,
@@ -68,12 +97,24 @@ impl PowerPointProvider {
         })
     }
 
+    /// Slide number of a `ppt/slides/slideN.xml` part
+    fn slide_number(name: &str) -> Option<usize> {
+        name.strip_prefix("ppt/slides/slide")?
+            .strip_suffix(".xml")?
+            .parse()
+            .ok()
+    }
+
+    /// Check if a ZIP entry is a slide
+    fn is_slide_part(name: &str) -> bool {
+        Self::slide_number(name).is_some()
+    }
+
     /// Extract slide contents from the archive
     fn extract_slide_contents(
         archive_data: &[u8],
     ) -> Result<Vec<(String, Vec<u8>)>, DocumentError> {
-        let reader = std::io::Cursor::new(archive_data);
-        let mut archive = ZipArchive::new(reader)?;
+        let mut archive = extract_zip(archive_data)?;
 
         let mut slide_contents = Vec::new();
         let mut slide_names = Vec::new();
@@ -83,14 +124,13 @@ impl PowerPointProvider {
             let file = archive.by_index(i)?;
             let name = file.name().to_string();
 
-            // Check if this is a slide file
-            if name.starts_with("ppt/slides/slide") && name.ends_with(".xml") {
+            if Self::is_slide_part(&name) {
                 slide_names.push(name);
             }
         }
 
-        // Sort slide names to ensure consistent ordering
-        slide_names.sort();
+        // Numeric order, so slide10 comes after slide9
+        slide_names.sort_by_key(|name| Self::slide_number(name));
 
         // Then read the content of each slide
         for name in slide_names {
@@ -116,15 +156,196 @@ impl PowerPointProvider {
         if slide_index >= self.slide_contents.len() {
             return Err(DocumentError::InvalidStructure {
                 reason: format!(
-                    "Slide index {} out of range (0-{})",
+                    "Slide index {} out of range ({} slides)",
+                    slide_index,
+                    self.slide_contents.len()
+                ),
+            });
+        }
+
+        let (name, content) = &self.slide_contents[slide_index];
+        extract_text_from_xml(content, Self::text_tags()).map_err(|e| e.in_part(name))
+    }
+
+    /// Get the title, body and speaker notes of a specific slide
+    pub fn get_slide_structure(&self, slide_index: usize) -> Result<SlideText, DocumentError> {
+        let Some((name, content)) = self.slide_contents.get(slide_index) else {
+            return Err(DocumentError::InvalidStructure {
+                reason: format!(
+                    "Slide index {} out of range ({} slides)",
                     slide_index,
-                    self.slide_contents.len() - 1
+                    self.slide_contents.len()
                 ),
             });
+        };
+
+        let mut text = SlideText::default();
+        for (placeholder, paragraph) in
+            Self::shape_paragraphs(content).map_err(|e| e.in_part(name))?
+        {
+            match placeholder.as_deref() {
+                Some("title" | "ctrTitle") => text.title.push(paragraph),
+                _ => text.body.push(paragraph),
+            }
+        }
+
+        let mut archive = extract_zip(&self.archive_data)?;
+        let relationships = read_relationships(&mut archive, name)?;
+        text.links = Self::slide_links(content, &relationships).map_err(|e| e.in_part(name))?;
+
+        let notes_part = relationships
+            .iter()
+            .find(|rel| rel.rel_type.ends_with("/notesSlide"))
+            .map(|rel| resolve_part_target(name, &rel.target));
+        let notes =
+            notes_part.and_then(|part| Some((read_zip_file(&mut archive, &part).ok()?, part)));
+        if let Some((notes, part)) = notes {
+            // Notes pages also hold the slide image and number; the notes
+            // themselves are in the body placeholder
+            text.notes = Self::shape_paragraphs(&notes)
+                .map_err(|e| e.in_part(&part))?
+                .into_iter()
+                .filter(|(placeholder, _)| placeholder.as_deref() == Some("body"))
+                .map(|(_, paragraph)| paragraph)
+                .collect();
         }
 
-        let (_, content) = &self.slide_contents[slide_index];
-        extract_text_from_xml(content, Self::text_tags())
+        Ok(text)
+    }
+
+    /// Hyperlinks on text runs of a slide part, with adjacent runs
+    /// linking to the same target merged into one link
+    fn slide_links(
+        xml: &[u8],
+        relationships: &[Relationship],
+    ) -> Result<Vec<ExtractedLink>, DocumentError> {
+        use quick_xml::events::Event;
+
+        let mut reader = quick_xml::Reader::from_reader(xml);
+        let mut buf = Vec::new();
+        let mut links: Vec<ExtractedLink> = Vec::new();
+        // Relationship id of the current run's link and of the last link pushed
+        let mut run_link: Option<String> = None;
+        let mut last_link: Option<String> = None;
+        let mut in_text = false;
+
+        loop {
+            match reader
+                .read_event_into(&mut buf)
+                .map_err(DocumentError::Xml)?
+            {
+                Event::Eof => break,
+                Event::Start(e) | Event::Empty(e) => match e.name().as_ref() {
+                    b"a:r" => run_link = None,
+                    b"a:hlinkClick" => {
+                        run_link = e
+                            .attributes()
+                            .flatten()
+                            .find(|a| a.key.as_ref() == b"r:id")
+                            .map(|a| String::from_utf8_lossy(&a.value).into_owned())
+                            .filter(|id| !id.is_empty());
+                    }
+                    b"a:t" => in_text = true,
+                    _ => {}
+                },
+                Event::Text(e) if in_text && run_link.is_some() => {
+                    let id = run_link.as_ref().unwrap();
+                    let text = e.unescape().map_err(DocumentError::Xml)?;
+                    if last_link.as_ref() == Some(id) {
+                        if let Some(link) = links.last_mut() {
+                            link.text.push_str(&text);
+                        }
+                    } else if let Some(rel) = relationships.iter().find(|r| &r.id == id) {
+                        // Jumps to other slides point at their part, e.g. slide3.xml
+                        let url = if rel.external {
+                            rel.target.clone()
+                        } else {
+                            format!("#{}", rel.target.trim_end_matches(".xml"))
+                        };
+                        links.push(ExtractedLink {
+                            text: text.into_owned(),
+                            url,
+                        });
+                        last_link = Some(id.clone());
+                    }
+                }
+                Event::End(e) => match e.name().as_ref() {
+                    b"a:t" => in_text = false,
+                    // A paragraph break ends the link
+                    b"a:p" => last_link = None,
+                    _ => {}
+                },
+                _ => {}
+            }
+            buf.clear();
+        }
+
+        Ok(links)
+    }
+
+    /// Non-empty paragraphs of a slide part with the placeholder type of
+    /// the shape holding them (`None` for ordinary shapes and tables)
+    fn shape_paragraphs(xml: &[u8]) -> Result<Vec<(Option<String>, String)>, DocumentError> {
+        use quick_xml::events::Event;
+
+        let mut reader = quick_xml::Reader::from_reader(xml);
+        let mut buf = Vec::new();
+        let mut paragraphs = Vec::new();
+        // Placeholder type of the shape being read
+        let mut placeholder: Option<String> = None;
+        let mut in_shape = false;
+        let mut in_text = false;
+        let mut paragraph = String::new();
+
+        loop {
+            match reader
+                .read_event_into(&mut buf)
+                .map_err(DocumentError::Xml)?
+            {
+                Event::Eof => break,
+                Event::Start(e) => match e.name().as_ref() {
+                    b"p:sp" => {
+                        in_shape = true;
+                        placeholder = None;
+                    }
+                    b"a:p" => paragraph.clear(),
+                    b"a:t" => in_text = true,
+                    _ => {}
+                },
+                Event::Empty(e) if in_shape && e.name().as_ref() == b"p:ph" => {
+                    // A placeholder without a type is a body placeholder
+                    placeholder = Some(
+                        e.attributes()
+                            .flatten()
+                            .find(|a| a.key.as_ref() == b"type")
+                            .map(|a| String::from_utf8_lossy(&a.value).into_owned())
+                            .unwrap_or_else(|| "body".to_string()),
+                    );
+                }
+                Event::Empty(e) if e.name().as_ref() == b"a:br" => paragraph.push('\n'),
+                Event::Text(e) if in_text => {
+                    paragraph.push_str(&e.unescape().map_err(DocumentError::Xml)?);
+                }
+                Event::End(e) => match e.name().as_ref() {
+                    b"p:sp" => {
+                        in_shape = false;
+                        placeholder = None;
+                    }
+                    b"a:p" => {
+                        let text = paragraph.trim();
+                        if !text.is_empty() {
+                            paragraphs.push((placeholder.clone(), text.to_string()));
+                        }
+                    }
+                    b"a:t" => in_text = false,
+                    _ => {}
+                },
+                _ => {}
+            }
+            buf.clear();
+        }
+
+        Ok(paragraphs)
     }
 
     /// Replace text in a specific slide
@@ -137,9 +358,9 @@ impl PowerPointProvider {
         if slide_index >= self.slide_contents.len() {
             return Err(DocumentError::InvalidStructure {
                 reason: format!(
-                    "Slide index {} out of range (0-{})",
+                    "Slide index {} out of range ({} slides)",
                     slide_index,
-                    self.slide_contents.len() - 1
+                    self.slide_contents.len()
                 ),
             });
         }
@@ -160,26 +381,45 @@ impl PowerPointProvider {
     pub fn get_metadata(&self) -> Result<PowerPointMetadata, DocumentError> {
         use crate::utils::extract_zip;
         let mut archive = extract_zip(&self.archive_data)?;
+        Self::read_properties(&mut archive, self.slide_contents.len())
+    }
 
-        let mut metadata = PowerPointMetadata::default();
-        metadata.total_slides = self.slide_contents.len();
+    /// Read the metadata of a presentation on disk without loading its slides
+    pub fn read_metadata(path: &Path) -> Result<PowerPointMetadata, DocumentError> {
+        let mut archive = open_zip_file(path)?;
+        let slide_count = archive
+            .file_names()
+            .filter(|name| Self::is_slide_part(name))
+            .count();
+        Self::read_properties(&mut archive, slide_count)
+    }
+
+    /// Read metadata from the core and app property parts
+    fn read_properties<R: Read + Seek>(
+        archive: &mut ZipArchive<R>,
+        slide_count: usize,
+    ) -> Result<PowerPointMetadata, DocumentError> {
+        let mut metadata = PowerPointMetadata {
+            total_slides: slide_count,
+            ..Default::default()
+        };
 
         // Try to read core.xml for basic metadata
-        if let Ok(core_xml) = read_zip_file(&mut archive, "docProps/core.xml") {
-            metadata = self.parse_core_properties(&core_xml)?;
-            metadata.total_slides = self.slide_contents.len(); // Override with actual slide count
+        if let Ok(core_xml) = read_zip_file(archive, "docProps/core.xml") {
+            metadata = Self::parse_core_properties(&core_xml)?;
+            metadata.total_slides = slide_count; // Override with actual slide count
         }
 
         // Try to read app.xml for additional metadata
-        if let Ok(app_xml) = read_zip_file(&mut archive, "docProps/app.xml") {
-            self.parse_app_properties(&app_xml, &mut metadata)?;
+        if let Ok(app_xml) = read_zip_file(archive, "docProps/app.xml") {
+            Self::parse_app_properties(&app_xml, &mut metadata)?;
         }
 
         Ok(metadata)
     }
 
     /// Parse core properties XML
-    fn parse_core_properties(&self, xml_data: &[u8]) -> Result<PowerPointMetadata, DocumentError> {
+    fn parse_core_properties(xml_data: &[u8]) -> Result<PowerPointMetadata, DocumentError> {
         let mut metadata = PowerPointMetadata::default();
         let reader = EventReader::new(std::io::Cursor::new(xml_data));
 
@@ -217,7 +457,6 @@ impl PowerPointProvider {
 
     /// Parse app properties XML for additional metadata
     fn parse_app_properties(
-        &self,
         xml_data: &[u8],
         metadata: &mut PowerPointMetadata,
     ) -> Result<(), DocumentError> {
@@ -287,6 +526,36 @@ impl DocumentProvider for PowerPointProvider {
         Ok(total_count)
     }
 
+    fn replace_pattern(
+        &mut self,
+        pattern: &regex::Regex,
+        replacement: &str,
+    ) -> Result<usize, DocumentError> {
+        debug!(
+            "Replacing pattern '{}' with '{}' in PowerPoint document",
+            pattern, replacement
+        );
+
+        let mut total_count = 0;
+
+        for (slide_name, content) in &mut self.slide_contents {
+            let (new_content, count) =
+                replace_pattern_in_xml(content, Self::text_tags(), pattern, replacement)?;
+
+            if count > 0 {
+                *content = new_content;
+                total_count += count;
+                debug!("Replaced {} matches in {}", count, slide_name);
+            }
+        }
+
+        if total_count > 0 {
+            self.modified = true;
+        }
+
+        Ok(total_count)
+    }
+
     fn save(&self) -> Result<(), DocumentError> {
         if !self.modified {
             debug!("No changes to save in PowerPoint document");
@@ -300,8 +569,6 @@ impl DocumentProvider for PowerPointProvider {
     fn save_as(&self, path: &Path) -> Result<(), DocumentError> {
         debug!("Saving PowerPoint document as: {}", path.display());
 
-        let file = std::fs::File::create(path)?;
-
         // Prepare replacements map
         let mut replacements = HashMap::new();
         for (slide_name, content) in &self.slide_contents {
@@ -309,7 +576,9 @@ impl DocumentProvider for PowerPointProvider {
         }
 
         // Copy archive with replacements
-        copy_zip_with_replacements(&self.archive_data, file, &replacements)?;
+        write_atomic(path, |file| {
+            copy_zip_with_replacements(&self.archive_data, file, &replacements)
+        })?;
 
         debug!("PowerPoint document saved successfully");
         Ok(())
@@ -320,8 +589,9 @@ impl DocumentProvider for PowerPointProvider {
 
         let mut all_text = String::new();
 
-        for (slide_index, (_, content)) in self.slide_contents.iter().enumerate() {
-            let slide_text = extract_text_from_xml(content, Self::text_tags())?;
+        for (slide_index, (name, content)) in self.slide_contents.iter().enumerate() {
+            let slide_text =
+                extract_text_from_xml(content, Self::text_tags()).map_err(|e| e.in_part(name))?;
 
             if !slide_text.is_empty() {
                 if slide_index > 0 {
@@ -416,6 +686,88 @@ mod tests {
         zip_data
     }
 
+    /// Presentation with a titled slide with speaker notes and a link, and a
+    /// slide without relationships stored as slide10 to check numeric ordering
+    fn create_pptx_with_notes() -> Vec<u8> {
+        let slide = |title: &str, body: &str| {
+            format!(
+                r#"<?xml version="1.0" encoding="UTF-8"?>
+<p:sld xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"
+       xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main">
+    <p:cSld><p:spTree>
+        <p:sp><p:nvSpPr><p:nvPr><p:ph type="title"/></p:nvPr></p:nvSpPr>
+            <p:txBody><a:p><a:r><a:t>{}</a:t></a:r></a:p></p:txBody></p:sp>
+        <p:sp><p:nvSpPr><p:nvPr><p:ph idx="1"/></p:nvPr></p:nvSpPr>
+            <p:txBody><a:p><a:r><a:rPr><a:hlinkClick r:id="rId3"/></a:rPr><a:t>{}</a:t></a:r></a:p><a:p/></p:txBody></p:sp>
+    </p:spTree></p:cSld>
+</p:sld>"#,
+                title, body
+            )
+        };
+        let notes = r#"<?xml version="1.0" encoding="UTF-8"?>
+<p:notes xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"
+         xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main">
+    <p:cSld><p:spTree>
+        <p:sp><p:nvSpPr><p:nvPr><p:ph type="sldImg"/></p:nvPr></p:nvSpPr></p:sp>
+        <p:sp><p:nvSpPr><p:nvPr><p:ph type="body" idx="1"/></p:nvPr></p:nvSpPr>
+            <p:txBody><a:p><a:r><a:t>Mention the budget</a:t></a:r></a:p></p:txBody></p:sp>
+        <p:sp><p:nvSpPr><p:nvPr><p:ph type="sldNum" idx="5"/></p:nvPr></p:nvSpPr>
+            <p:txBody><a:p><a:r><a:t>1</a:t></a:r></a:p></p:txBody></p:sp>
+    </p:spTree></p:cSld>
+</p:notes>"#;
+        let rels = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+    <Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/notesSlide" Target="../notesSlides/notesSlide1.xml"/>
+    <Relationship Id="rId3" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/hyperlink" Target="https://example.com/revenue" TargetMode="External"/>
+</Relationships>"#;
+
+        let mut zip_data = Vec::new();
+        {
+            use zip::{write::SimpleFileOptions, ZipWriter};
+            let mut writer = ZipWriter::new(std::io::Cursor::new(&mut zip_data));
+            let parts = [
+                ("ppt/slides/slide10.xml", slide("Closing", "Thanks")),
+                ("ppt/slides/slide2.xml", slide("Overview", "Revenue grew")),
+                ("ppt/slides/_rels/slide2.xml.rels", rels.to_string()),
+                ("ppt/notesSlides/notesSlide1.xml", notes.to_string()),
+            ];
+            for (name, content) in parts {
+                writer
+                    .start_file(name, SimpleFileOptions::default())
+                    .unwrap();
+                writer.write_all(content.as_bytes()).unwrap();
+            }
+            writer.finish().unwrap();
+        }
+        zip_data
+    }
+
+    #[test]
+    fn test_slide_structure_with_notes() {
+        let doc = PowerPointProvider::from_bytes(&create_pptx_with_notes()).unwrap();
+        assert_eq!(doc.slide_count(), 2);
+
+        let first = doc.get_slide_structure(0).unwrap();
+        assert_eq!(first.title, vec!["Overview"]);
+        assert_eq!(first.body, vec!["Revenue grew"]);
+        assert_eq!(first.notes, vec!["Mention the budget"]);
+        assert_eq!(
+            first.links,
+            vec![ExtractedLink {
+                text: "Revenue grew".to_string(),
+                url: "https://example.com/revenue".to_string(),
+            }]
+        );
+
+        let second = doc.get_slide_structure(1).unwrap();
+        assert_eq!(second.title, vec!["Closing"]);
+        assert!(second.notes.is_empty());
+        // slide10 has no relationships, so its link cannot be resolved
+        assert!(second.links.is_empty());
+
+        assert!(doc.get_slide_structure(2).is_err());
+    }
+
     #[test]
     fn test_powerpoint_document_text_extraction() {
         let zip_data = create_minimal_pptx();
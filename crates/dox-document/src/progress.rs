@@ -0,0 +1,50 @@
+//! Progress reporting and cancellation for batch operations
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Flag used to stop a batch job between files
+///
+/// Clones share the same flag, so the caller keeps one copy and hands
+/// another to the job. Files that are already being processed are finished;
+/// no new file is started once the token is cancelled.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Request cancellation
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    /// Whether cancellation has been requested
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// Callback invoked once per file of a batch job with `(current, total)`
+#[derive(Clone)]
+pub struct ProgressCallback(Arc<dyn Fn(usize, usize) + Send + Sync>);
+
+impl ProgressCallback {
+    pub fn new(callback: impl Fn(usize, usize) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(callback))
+    }
+
+    /// Report that `current` of `total` files are done
+    pub fn report(&self, current: usize, total: usize) {
+        (self.0)(current, total)
+    }
+}
+
+impl fmt::Debug for ProgressCallback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ProgressCallback")
+    }
+}
@@ -1,7 +1,10 @@
 //! Document provider trait and error types
 
 use anyhow::Result;
-use std::path::Path;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 
 /// Errors that can occur during document operations
@@ -37,6 +40,44 @@ pub enum DocumentError {
 
     #[error("Operation failed: {reason}")]
     OperationFailed { reason: String },
+
+    #[error("Document is password protected: {path}")]
+    PasswordRequired { path: String },
+
+    #[error("Incorrect password for {path}")]
+    IncorrectPassword { path: String },
+
+    #[error("Document archive is corrupt: {reason}")]
+    CorruptArchive { reason: String },
+
+    #[error("Document is missing the {part} part")]
+    MissingPart { part: String },
+
+    #[error("XML parsing error in {part}: {source}")]
+    XmlParseError {
+        part: String,
+        #[source]
+        source: quick_xml::Error,
+    },
+
+    #[error("Document is encrypted and must be decrypted before it can be read")]
+    EncryptionRequired,
+
+    #[error("OCR failed: {reason}")]
+    OcrFailed { reason: String },
+}
+
+impl DocumentError {
+    /// Attribute an XML error to the package part that was being parsed
+    pub(crate) fn in_part(self, part: &str) -> Self {
+        match self {
+            DocumentError::Xml(source) => DocumentError::XmlParseError {
+                part: part.to_string(),
+                source,
+            },
+            other => other,
+        }
+    }
 }
 
 /// Trait for document operations
@@ -51,6 +92,39 @@ pub trait DocumentProvider: std::fmt::Debug {
     /// The number of replacements made
     fn replace_text(&mut self, old: &str, new: &str) -> Result<usize, DocumentError>;
 
+    /// Replace every match of a regular expression in the document
+    ///
+    /// `replacement` may refer to capture groups (`$1`, `${name}`).
+    /// Formats without text replacement support return an error.
+    fn replace_pattern(
+        &mut self,
+        _pattern: &regex::Regex,
+        _replacement: &str,
+    ) -> Result<usize, DocumentError> {
+        Err(DocumentError::OperationFailed {
+            reason: format!(
+                "Pattern replacement is not supported for {:?} documents",
+                self.document_type()
+            ),
+        })
+    }
+
+    /// List the distinct matches of `pattern` in the document with their
+    /// counts, in order of first appearance
+    ///
+    /// Meant as a cheap inventory of template placeholders such as
+    /// `{{name}}` before replacing them; the document is not modified.
+    fn find_placeholders(
+        &self,
+        pattern: &regex::Regex,
+    ) -> Result<Vec<PlaceholderMatch>, DocumentError> {
+        let text = self.get_text()?;
+        Ok(tally_placeholders(
+            self.get_path(),
+            pattern.find_iter(&text).map(|found| found.as_str()),
+        ))
+    }
+
     /// Save the document to its original location
     fn save(&self) -> Result<(), DocumentError>;
 
@@ -73,6 +147,42 @@ pub trait DocumentProvider: std::fmt::Debug {
     fn document_type(&self) -> DocumentType;
 }
 
+/// A distinct placeholder found in a document
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PlaceholderMatch {
+    /// Document the placeholder was found in
+    pub path: PathBuf,
+    /// The matched text, e.g. `{{name}}`
+    pub placeholder: String,
+    /// Number of occurrences in the document
+    pub count: usize,
+}
+
+/// Count each distinct placeholder in `found`, keeping first-seen order
+pub(crate) fn tally_placeholders<'a>(
+    path: &Path,
+    found: impl IntoIterator<Item = &'a str>,
+) -> Vec<PlaceholderMatch> {
+    let mut matches: Vec<PlaceholderMatch> = Vec::new();
+    let mut index: HashMap<&str, usize> = HashMap::new();
+
+    for placeholder in found {
+        match index.get(placeholder) {
+            Some(&i) => matches[i].count += 1,
+            None => {
+                index.insert(placeholder, matches.len());
+                matches.push(PlaceholderMatch {
+                    path: path.to_path_buf(),
+                    placeholder: placeholder.to_string(),
+                    count: 1,
+                });
+            }
+        }
+    }
+
+    matches
+}
+
 /// Document type enumeration
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum DocumentType {
@@ -111,24 +221,102 @@ impl DocumentType {
             _ => None,
         }
     }
+
+    /// Detect the document type of a file from its content
+    ///
+    /// PDFs are recognised by their `%PDF` header and Office documents by the
+    /// main part declared in `[Content_Types].xml`. Files that cannot be
+    /// sniffed fall back to their extension.
+    pub fn detect(path: &Path) -> Option<Self> {
+        Self::sniff(path).or_else(|| {
+            path.extension()
+                .and_then(|ext| ext.to_str())
+                .and_then(Self::from_extension)
+        })
+    }
+
+    /// Identify a document type from the file content alone
+    fn sniff(path: &Path) -> Option<Self> {
+        let mut header = [0u8; 4];
+        let mut file = std::fs::File::open(path).ok()?;
+        file.read_exact(&mut header).ok()?;
+
+        if &header == PDF_MAGIC {
+            return Some(DocumentType::Pdf);
+        }
+        if &header != ZIP_MAGIC {
+            return None;
+        }
+
+        let mut archive = zip::ZipArchive::new(file).ok()?;
+        let mut content_types = String::new();
+        archive
+            .by_name("[Content_Types].xml")
+            .ok()?
+            .read_to_string(&mut content_types)
+            .ok()?;
+
+        OFFICE_MAIN_PARTS
+            .iter()
+            .find(|(content_type, _)| content_types.contains(content_type))
+            .map(|(_, doc_type)| doc_type.clone())
+    }
+}
+
+/// Leading bytes of a PDF file
+const PDF_MAGIC: &[u8; 4] = b"%PDF";
+/// Leading bytes of a ZIP archive, which every Office document is
+const ZIP_MAGIC: &[u8; 4] = b"PK\x03\x04";
+
+/// Content types of the main part of each Office document type
+const OFFICE_MAIN_PARTS: &[(&str, DocumentType)] = &[
+    ("wordprocessingml.document.main", DocumentType::Word),
+    ("presentationml.presentation.main", DocumentType::PowerPoint),
+    ("spreadsheetml.sheet.main", DocumentType::Excel),
+];
+
+/// Check whether a file starts with the PDF header
+pub(crate) fn has_pdf_header(path: &Path) -> bool {
+    DocumentType::sniff(path) == Some(DocumentType::Pdf)
+}
+
+/// Error for a file whose document type could not be determined
+pub(crate) fn unsupported_format(path: &Path) -> DocumentError {
+    DocumentError::UnsupportedFormat {
+        format: path
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_string())
+            .unwrap_or_else(|| "none".to_string()),
+    }
+}
+
+/// Create a document provider for a document held in memory
+///
+/// PDF documents are read from disk by the PDF backend and cannot be opened
+/// this way.
+pub fn create_provider_from_bytes(
+    data: &[u8],
+    doc_type: DocumentType,
+) -> Result<Box<dyn DocumentProvider>, DocumentError> {
+    match doc_type {
+        DocumentType::Word => Ok(Box::new(crate::WordProvider::from_bytes(data)?)),
+        DocumentType::PowerPoint => Ok(Box::new(crate::PowerPointProvider::from_bytes(data)?)),
+        DocumentType::Excel => Ok(Box::new(crate::ExcelProvider::from_bytes(data)?)),
+        DocumentType::Text => Ok(Box::new(crate::text::TextProvider::from_bytes(data)?)),
+        DocumentType::Pdf => Err(DocumentError::OperationFailed {
+            reason: "PDF documents can only be opened from a file".to_string(),
+        }),
+    }
 }
 
 /// Factory function to create appropriate document provider
 pub fn create_provider(path: &Path) -> Result<Box<dyn DocumentProvider>, DocumentError> {
-    let ext = path.extension().and_then(|s| s.to_str()).ok_or_else(|| {
-        DocumentError::UnsupportedFormat {
-            format: "none".to_string(),
-        }
-    })?;
-
-    match DocumentType::from_extension(ext) {
+    match DocumentType::detect(path) {
         Some(DocumentType::Word) => Ok(Box::new(crate::WordProvider::open(path)?)),
         Some(DocumentType::PowerPoint) => Ok(Box::new(crate::PowerPointProvider::open(path)?)),
         Some(DocumentType::Pdf) => Ok(Box::new(crate::pdf::PdfProvider::open(path)?)),
         Some(DocumentType::Excel) => Ok(Box::new(crate::ExcelProvider::open(path)?)),
         Some(DocumentType::Text) => Ok(Box::new(crate::text::TextProvider::open(path)?)),
-        None => Err(DocumentError::UnsupportedFormat {
-            format: ext.to_string(),
-        }),
+        None => Err(unsupported_format(path)),
     }
 }
@@ -1,6 +1,10 @@
+use crate::progress::{CancellationToken, ProgressCallback};
 use anyhow::Result;
+use regex::{Regex, RegexBuilder};
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::path::{Path, PathBuf};
+use tracing::{error, info};
 use walkdir::WalkDir;
 
 mod replacer;
@@ -10,10 +14,26 @@ pub use replacer::Replacer;
 mod tests;
 
 /// A single replacement rule
+///
+/// Rules match `old` literally unless one of the matching options is set, in
+/// which case they are compiled into a regular expression.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Rule {
     pub old: String,
     pub new: String,
+    /// Treat `old` as a regular expression; `new` may use `$1` captures
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub regex: bool,
+    /// Match regardless of letter case
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub case_insensitive: bool,
+    /// Only match `old` as a whole word
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub whole_word: bool,
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
 }
 
 impl Rule {
@@ -22,6 +42,65 @@ impl Rule {
         Rule {
             old: old.into(),
             new: new.into(),
+            regex: false,
+            case_insensitive: false,
+            whole_word: false,
+        }
+    }
+
+    /// Create a rule whose `old` value is a regular expression
+    pub fn regex(pattern: impl Into<String>, new: impl Into<String>) -> Self {
+        Rule {
+            regex: true,
+            ..Rule::new(pattern, new)
+        }
+    }
+
+    /// Set case-insensitive matching
+    pub fn with_case_insensitive(mut self, case_insensitive: bool) -> Self {
+        self.case_insensitive = case_insensitive;
+        self
+    }
+
+    /// Set whole-word matching
+    pub fn with_whole_word(mut self, whole_word: bool) -> Self {
+        self.whole_word = whole_word;
+        self
+    }
+
+    /// Compile the rule into a regular expression
+    ///
+    /// Returns `None` for plain literal rules, which are applied with
+    /// ordinary text replacement.
+    pub fn pattern(&self) -> Result<Option<Regex>> {
+        if !self.regex && !self.case_insensitive && !self.whole_word {
+            return Ok(None);
+        }
+
+        let mut source = if self.regex {
+            self.old.clone()
+        } else {
+            regex::escape(&self.old)
+        };
+        if self.whole_word {
+            source = format!(r"\b(?:{})\b", source);
+        }
+
+        let pattern = RegexBuilder::new(&source)
+            .case_insensitive(self.case_insensitive)
+            .build()
+            .map_err(|e| anyhow::anyhow!("Invalid regex pattern '{}': {}", self.old, e))?;
+        Ok(Some(pattern))
+    }
+
+    /// Replacement text to use with [`Rule::pattern`]
+    ///
+    /// For literal rules `$` is escaped so the text is inserted as-is.
+    pub fn pattern_replacement<'a>(&self, new: &'a str) -> Cow<'a, str> {
+        if self.regex {
+            Cow::Borrowed(new)
+        } else {
+            Cow::Owned(new.replace('$', "$$"))
         }
     }
 
@@ -30,9 +109,10 @@ impl Rule {
         if self.old.is_empty() {
             anyhow::bail!("Replacement rule 'old' value cannot be empty");
         }
-        if self.old == self.new {
+        if self.old == self.new && !self.regex {
             anyhow::bail!("Replacement rule 'old' and 'new' values cannot be the same");
         }
+        self.pattern()?;
         Ok(())
     }
 }
@@ -47,6 +127,27 @@ pub struct ReplaceOptions {
     pub concurrent: bool,
     pub max_workers: usize,
     pub show_diff: bool,
+    /// Called after each file with the number of files done and the total
+    pub progress: Option<ProgressCallback>,
+    /// Stops the run before the next file once cancelled
+    pub cancel: Option<CancellationToken>,
+    /// Record the SHA-256 of every file before and after in
+    /// [`ReplaceResults::files`]
+    pub checksums: bool,
+}
+
+impl ReplaceOptions {
+    fn is_cancelled(&self) -> bool {
+        self.cancel
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled)
+    }
+
+    fn report_progress(&self, current: usize, total: usize) {
+        if let Some(progress) = &self.progress {
+            progress.report(current, total);
+        }
+    }
 }
 
 impl Default for ReplaceOptions {
@@ -59,17 +160,98 @@ impl Default for ReplaceOptions {
             concurrent: false,
             max_workers: 4,
             show_diff: false,
+            progress: None,
+            cancel: None,
+            checksums: false,
         }
     }
 }
 
 /// Results from a replacement operation
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize)]
 pub struct ReplaceResults {
     pub files_processed: usize,
     pub total_replacements: usize,
     pub errors: usize,
     pub skipped: usize,
+    /// Files that were found but not processed, with the reason
+    pub skipped_files: Vec<(PathBuf, String)>,
+    /// The run was stopped through [`ReplaceOptions::cancel`] before every
+    /// file was processed
+    pub cancelled: bool,
+    /// Outcome of every file that was processed or skipped, by path
+    pub files: Vec<FileResult>,
+}
+
+impl ReplaceResults {
+    /// Count a processed file and keep its record
+    fn record(&mut self, file: FileResult) {
+        if file.status == FileStatus::Failed {
+            error!(
+                "Error processing {}: {}",
+                file.path.display(),
+                file.error.as_deref().unwrap_or_default()
+            );
+            self.errors += 1;
+        } else {
+            self.files_processed += 1;
+            self.total_replacements += file.replacements;
+            if file.replacements > 0 {
+                info!(
+                    "Processed {}: {} replacements",
+                    file.path.display(),
+                    file.replacements
+                );
+            }
+        }
+        self.files.push(file);
+    }
+}
+
+/// What a replacement run did to one file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileStatus {
+    /// Replacements were made and saved
+    Modified,
+    /// Replacements were found but not saved because of
+    /// [`ReplaceOptions::dry_run`]
+    DryRun,
+    /// No rule matched
+    Unchanged,
+    /// The file cannot be modified in place; see [`skip_reason`]
+    Skipped,
+    /// Processing failed and the file was not saved
+    Failed,
+}
+
+/// Outcome of a replacement run for one file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileResult {
+    pub path: PathBuf,
+    pub status: FileStatus,
+    pub replacements: usize,
+    /// Hex SHA-256 of the file before the run, with
+    /// [`ReplaceOptions::checksums`]
+    pub hash_before: Option<String>,
+    /// Hex SHA-256 of the file after the run, with
+    /// [`ReplaceOptions::checksums`]
+    pub hash_after: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Hex-encoded SHA-256 of a file's content
+pub fn file_sha256(path: &Path) -> std::io::Result<String> {
+    use sha2::{Digest, Sha256};
+
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut std::fs::File::open(path)?, &mut hasher)?;
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
 }
 
 /// Container for replacement rules loaded from YAML
@@ -111,7 +293,7 @@ pub fn load_rules(path: &Path) -> Result<Vec<Rule>> {
     Ok(rules)
 }
 
-/// Find all document files in a directory
+/// Find all document files in a directory, in file name order
 pub fn find_document_files(
     path: &Path,
     recursive: bool,
@@ -126,11 +308,13 @@ pub fn find_document_files(
         return Ok(files);
     }
 
+    // Sorted so batch jobs visit files in a predictable order
     let walker = if recursive {
         WalkDir::new(path)
     } else {
         WalkDir::new(path).max_depth(1)
-    };
+    }
+    .sort_by_file_name();
 
     for entry in walker.into_iter().filter_map(|e| e.ok()) {
         let path = entry.path();
@@ -153,9 +337,28 @@ pub fn find_document_files(
 }
 
 /// Check if a file is a supported document type
+///
+/// This includes formats that are reported as skipped during replacement;
+/// see [`skip_reason`].
 pub fn is_supported_document(path: &Path) -> bool {
-    match path.extension().and_then(|s| s.to_str()) {
-        Some("docx") | Some("pptx") | Some("txt") => true,
-        _ => false,
+    path.extension()
+        .and_then(|s| s.to_str())
+        .and_then(crate::DocumentType::from_extension)
+        .is_some()
+}
+
+/// Path of the backup written before a document is modified (`<file>.bak`)
+pub fn backup_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(".bak");
+    PathBuf::from(name)
+}
+
+/// Reason a supported document cannot be modified in place, if any
+pub fn skip_reason(path: &Path) -> Option<&'static str> {
+    let ext = path.extension().and_then(|s| s.to_str())?;
+    match crate::DocumentType::from_extension(ext)? {
+        crate::DocumentType::Pdf => Some("in-place text replacement is not supported for PDF"),
+        _ => None,
     }
 }
@@ -1,16 +1,20 @@
-use super::{ReplaceOptions, ReplaceResults, Rule};
+use super::{FileResult, FileStatus, ReplaceOptions, ReplaceResults, Rule};
 use crate::compat::Document;
 use anyhow::Result;
 use colored::*;
 use dox_core::replace::SmartReplacer;
 use dox_core::utils::ui;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use tracing::{debug, error, info, warn};
 
 /// Handles text replacement in documents
+#[derive(Clone)]
 pub struct Replacer {
     rules: Vec<Rule>,
-    smart_replacer: Option<SmartReplacer>,
+    smart_replacer: Option<Arc<SmartReplacer>>,
+    #[cfg(test)]
+    probe: Option<Arc<OverlapProbe>>,
 }
 
 impl Replacer {
@@ -19,6 +23,8 @@ impl Replacer {
         Replacer {
             rules,
             smart_replacer: None,
+            #[cfg(test)]
+            probe: None,
         }
     }
 
@@ -32,10 +38,19 @@ impl Replacer {
         let smart_replacer = SmartReplacer::new(model, api_key, context)?;
         Ok(Replacer {
             rules,
-            smart_replacer: Some(smart_replacer),
+            smart_replacer: Some(Arc::new(smart_replacer)),
+            #[cfg(test)]
+            probe: None,
         })
     }
 
+    /// Report every file to `probe` while it is processed
+    #[cfg(test)]
+    pub(super) fn with_probe(mut self, probe: Arc<OverlapProbe>) -> Self {
+        self.probe = Some(probe);
+        self
+    }
+
     /// Process a file or directory with the replacement rules
     pub async fn process_path(
         &self,
@@ -62,7 +77,7 @@ impl Replacer {
     /// Process files sequentially
     async fn process_sequential(
         &self,
-        files: Vec<std::path::PathBuf>,
+        files: Vec<PathBuf>,
         options: ReplaceOptions,
     ) -> Result<ReplaceResults> {
         let mut results = ReplaceResults::default();
@@ -70,42 +85,73 @@ impl Replacer {
         let progress = ui::create_progress_bar(files.len() as u64, "문서 처리 중");
 
         for (i, file) in files.iter().enumerate() {
+            if options.is_cancelled() {
+                warn!("Cancelled with {} file(s) left", files.len() - i);
+                results.cancelled = true;
+                break;
+            }
+
             progress.set_message(format!("처리 중: {}", file.display()));
 
-            match self.process_file(file, &options).await {
-                Ok(count) => {
-                    results.files_processed += 1;
-                    results.total_replacements += count;
-                    if count > 0 {
-                        info!("Processed {}: {} replacements", file.display(), count);
-                    }
-                }
-                Err(e) => {
-                    error!("Error processing {}: {}", file.display(), e);
-                    results.errors += 1;
-                }
+            if let Some(reason) = super::skip_reason(file) {
+                warn!("Skipping {}: {}", file.display(), reason);
+                results.skipped += 1;
+                results
+                    .skipped_files
+                    .push((file.clone(), reason.to_string()));
+                results.files.push(Self::skipped(file, &options));
+                progress.set_position((i + 1) as u64);
+                options.report_progress(i + 1, files.len());
+                continue;
             }
 
+            let record = self.process_recorded(file, &options).await;
+            results.record(record);
+
             progress.set_position((i + 1) as u64);
+            options.report_progress(i + 1, files.len());
         }
 
         progress.finish_with_message("문서 처리 완료");
+        results.files.sort_by(|a, b| a.path.cmp(&b.path));
         Ok(results)
     }
 
     /// Process files concurrently
+    ///
+    /// Each file runs on Tokio's blocking pool, with at most `max_workers`
+    /// files in flight. A failing file is counted in `errors` and does not
+    /// stop the others. Files that have not started when the run is
+    /// cancelled are left alone.
     async fn process_concurrent(
         &self,
-        files: Vec<std::path::PathBuf>,
+        files: Vec<PathBuf>,
         options: ReplaceOptions,
     ) -> Result<ReplaceResults> {
         use futures::stream::{self, StreamExt};
-        use std::sync::{
-            atomic::{AtomicUsize, Ordering},
-            Arc,
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            warn!("No async runtime available, processing files sequentially");
+            return self.process_sequential(files, options).await;
         };
 
-        let max_workers = options.max_workers.min(files.len());
+        let total = files.len();
+        let mut skipped_files = Vec::new();
+        let files: Vec<_> = files
+            .into_iter()
+            .filter(|file| match super::skip_reason(file) {
+                Some(reason) => {
+                    warn!("Skipping {}: {}", file.display(), reason);
+                    skipped_files.push((file.clone(), reason.to_string()));
+                    options.report_progress(skipped_files.len(), total);
+                    false
+                }
+                None => true,
+            })
+            .collect();
+
+        let max_workers = options.max_workers.min(files.len()).max(1);
         info!(
             "Processing {} files with {} workers",
             files.len(),
@@ -116,57 +162,99 @@ impl Replacer {
             files.len() as u64,
             &format!("병렬 처리 중 ({}개 작업자)", max_workers),
         );
-        let completed = Arc::new(AtomicUsize::new(0));
+        let completed = AtomicUsize::new(skipped_files.len());
 
-        let results = stream::iter(files)
+        let mut results = stream::iter(files)
             .map(|file| {
-                let rules = self.rules.clone();
+                let replacer = self.clone();
                 let opts = options.clone();
-                let progress = progress.clone();
-                let completed = Arc::clone(&completed);
-                async move {
-                    let result = {
-                        let replacer = Replacer::new(rules);
-                        replacer
-                            .process_file(&file, &opts)
-                            .await
-                            .map(|count| (1, count, 0))
-                            .unwrap_or_else(|e| {
-                                error!("Error processing {}: {}", file.display(), e);
-                                (0, 0, 1)
-                            })
-                    };
+                let runtime = runtime.clone();
+                tokio::task::spawn_blocking(move || {
+                    // Checked when the file is picked up, so queued files are skipped
+                    if opts.is_cancelled() {
+                        return (file, None);
+                    }
+                    let record = runtime.block_on(replacer.process_recorded(&file, &opts));
+                    (file, Some(record))
+                })
+            })
+            .buffer_unordered(max_workers)
+            .fold(ReplaceResults::default(), |mut acc, joined| {
+                progress.inc(1);
 
+                if !matches!(joined, Ok((_, None))) {
                     let current = completed.fetch_add(1, Ordering::SeqCst) + 1;
-                    progress.set_position(current as u64);
+                    options.report_progress(current, total);
+                }
 
-                    result
+                match joined {
+                    Ok((_, None)) => acc.cancelled = true,
+                    Ok((_, Some(record))) => acc.record(record),
+                    Err(e) => {
+                        error!("Replacement task failed: {}", e);
+                        acc.errors += 1;
+                    }
                 }
+
+                futures::future::ready(acc)
             })
-            .buffer_unordered(max_workers)
-            .fold(
-                ReplaceResults::default(),
-                |mut acc, (processed, replacements, errors)| async move {
-                    acc.files_processed += processed;
-                    acc.total_replacements += replacements;
-                    acc.errors += errors;
-                    acc
-                },
-            )
             .await;
 
         progress.finish_with_message("병렬 처리 완료");
+
+        results.skipped = skipped_files.len();
+        for (file, _) in &skipped_files {
+            results.files.push(Self::skipped(file, &options));
+        }
+        results.files.sort_by(|a, b| a.path.cmp(&b.path));
+        results.skipped_files = skipped_files;
         Ok(results)
     }
 
+    /// Process a single file and describe the outcome
+    ///
+    /// With [`ReplaceOptions::checksums`], the file is hashed before and,
+    /// unless it was left alone, after processing.
+    async fn process_recorded(&self, path: &Path, options: &ReplaceOptions) -> FileResult {
+        let hash_before = checksum(path, options);
+        let (status, replacements, error) = match self.process_file(path, options).await {
+            Ok(0) => (FileStatus::Unchanged, 0, None),
+            Ok(count) if options.dry_run => (FileStatus::DryRun, count, None),
+            Ok(count) => (FileStatus::Modified, count, None),
+            Err(e) => (FileStatus::Failed, 0, Some(format!("{:#}", e))),
+        };
+        let hash_after = match status {
+            FileStatus::Modified | FileStatus::Failed => checksum(path, options),
+            _ => hash_before.clone(),
+        };
+
+        FileResult {
+            path: path.to_path_buf(),
+            status,
+            replacements,
+            hash_before,
+            hash_after,
+            error,
+        }
+    }
+
+    fn skipped(path: &Path, options: &ReplaceOptions) -> FileResult {
+        let hash = checksum(path, options);
+        FileResult {
+            path: path.to_path_buf(),
+            status: FileStatus::Skipped,
+            replacements: 0,
+            hash_before: hash.clone(),
+            hash_after: hash,
+            error: None,
+        }
+    }
+
     /// Process a single file
     async fn process_file(&self, path: &Path, options: &ReplaceOptions) -> Result<usize> {
         debug!("Processing file: {}", path.display());
-
-        // Create backup if requested
-        if options.backup && !options.dry_run {
-            self.create_backup(path)?;
-        }
+        #[cfg(test)]
+        let _probe = self.probe.as_ref().map(|probe| probe.enter());
 
         // Open the document
         let mut doc = Document::open(path)?;
@@ -183,6 +271,8 @@ impl Replacer {
         let mut applied_rules = Vec::new();
 
         for rule in &self.rules {
+            let pattern = rule.pattern()?;
+
             let replacement_text = if let Some(smart_replacer) = &self.smart_replacer {
                 // Use AI-enhanced replacement
                 match self
@@ -199,7 +289,12 @@ impl Replacer {
                 rule.new.clone()
             };
 
-            let count = doc.replace_text(&rule.old, &replacement_text)?;
+            let count = match &pattern {
+                Some(pattern) => {
+                    doc.replace_pattern(pattern, &rule.pattern_replacement(&replacement_text))?
+                }
+                None => doc.replace_text(&rule.old, &replacement_text)?,
+            };
             if count > 0 {
                 debug!(
                     "Replaced {} occurrences of '{}' with '{}'",
@@ -210,8 +305,9 @@ impl Replacer {
             }
         }
 
-        // Show diff if requested and changes were made
-        if options.show_diff && total_replacements > 0 {
+        // Show diff if requested and changes were made; JSON output keeps
+        // stdout for the results
+        if options.show_diff && total_replacements > 0 && !ui::json_output() {
             if let Some(original) = original_content {
                 let new_content = doc.get_text()?;
                 println!(
@@ -226,9 +322,13 @@ impl Replacer {
 
         // Save the document if not in dry-run mode
         if !options.dry_run && total_replacements > 0 {
+            // Keep the original next to the file before overwriting it
+            if options.backup {
+                Self::create_backup(path)?;
+            }
             doc.save()?;
             info!("Saved changes to {}", path.display());
-        } else if options.dry_run && total_replacements > 0 {
+        } else if options.dry_run && total_replacements > 0 && !ui::json_output() {
             println!(
                 "  {} {}: {} replacements would be made",
                 "→".cyan(),
@@ -249,32 +349,9 @@ impl Replacer {
         Ok(total_replacements)
     }
 
-    /// Create a backup of the file
-    fn create_backup(&self, path: &Path) -> Result<()> {
-        use std::time::SystemTime;
-
-        let timestamp = SystemTime::now()
-            .duration_since(SystemTime::UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
-
-        let backup_path = if let Some(parent) = path.parent() {
-            let file_stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("file");
-            let extension = path.extension().and_then(|s| s.to_str()).unwrap_or("");
-            let backup_name = if extension.is_empty() {
-                format!("{}.backup.{}", file_stem, timestamp)
-            } else {
-                format!("{}.backup.{}.{}", file_stem, timestamp, extension)
-            };
-            parent.join(backup_name)
-        } else {
-            path.with_extension(format!(
-                "{}.backup.{}",
-                path.extension().and_then(|s| s.to_str()).unwrap_or(""),
-                timestamp
-            ))
-        };
-
+    /// Copy the original file to `<file>.bak`
+    fn create_backup(path: &Path) -> Result<()> {
+        let backup_path = super::backup_path(path);
         std::fs::copy(path, &backup_path)?;
         info!("Created backup: {}", backup_path.display());
 
@@ -315,3 +392,54 @@ impl Replacer {
         }
     }
 }
+
+/// SHA-256 of `path` when checksums were requested and the file is readable
+fn checksum(path: &Path, options: &ReplaceOptions) -> Option<String> {
+    if !options.checksums {
+        return None;
+    }
+    super::file_sha256(path)
+        .map_err(|e| warn!("Failed to hash {}: {}", path.display(), e))
+        .ok()
+}
+
+#[cfg(test)]
+pub(super) use probe::OverlapProbe;
+
+#[cfg(test)]
+mod probe {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    /// Counts how many files are processed at the same time
+    ///
+    /// Each file is held open for a moment so that files running
+    /// concurrently overlap.
+    #[derive(Default)]
+    pub(in crate::replace) struct OverlapProbe {
+        active: AtomicUsize,
+        peak: AtomicUsize,
+    }
+
+    impl OverlapProbe {
+        /// Largest number of files that were processed at once
+        pub(in crate::replace) fn peak(&self) -> usize {
+            self.peak.load(Ordering::SeqCst)
+        }
+
+        pub(super) fn enter(&self) -> OverlapGuard<'_> {
+            let active = self.active.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(active, Ordering::SeqCst);
+            std::thread::sleep(Duration::from_millis(50));
+            OverlapGuard(self)
+        }
+    }
+
+    pub(super) struct OverlapGuard<'a>(&'a OverlapProbe);
+
+    impl Drop for OverlapGuard<'_> {
+        fn drop(&mut self) {
+            self.0.active.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+}
@@ -1,4 +1,5 @@
 #[cfg(test)]
+#[allow(clippy::module_inception)]
 mod tests {
 
     use rstest::*;
@@ -150,3 +151,201 @@ mod tests {
         }
     }
 }
+
+#[cfg(test)]
+mod rules {
+    use super::super::*;
+
+    fn replace_in_text_file(content: &str, rules: Vec<Rule>) -> (String, ReplaceResults) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sample.txt");
+        std::fs::write(&path, content).unwrap();
+
+        let results = run(&path, rules, ReplaceOptions::default());
+        (std::fs::read_to_string(&path).unwrap(), results)
+    }
+
+    fn run(path: &Path, rules: Vec<Rule>, options: ReplaceOptions) -> ReplaceResults {
+        tokio_test::block_on(Replacer::new(rules).process_path(path, options)).unwrap()
+    }
+
+    #[test]
+    fn test_regex_date_replacement() {
+        let rule = Rule::regex(r"(\d{4})-(\d{2})-(\d{2})", "$3/$2/$1");
+        rule.validate().unwrap();
+
+        let (content, results) =
+            replace_in_text_file("Start 2024-01-15, end 2024-02-01.", vec![rule]);
+
+        assert_eq!(content, "Start 15/01/2024, end 01/02/2024.");
+        assert_eq!(results.total_replacements, 2);
+    }
+
+    #[test]
+    fn test_case_insensitive_literal() {
+        let rule = Rule::new("hello", "$hi").with_case_insensitive(true);
+
+        let (content, results) = replace_in_text_file("Hello WORLD, HELLO world", vec![rule]);
+
+        // Literal rules insert `$` as-is even though a regex is used
+        assert_eq!(content, "$hi WORLD, $hi world");
+        assert_eq!(results.total_replacements, 2);
+    }
+
+    #[test]
+    fn test_whole_word_and_literal_default() {
+        let rules = vec![
+            Rule::new("cat", "dog").with_whole_word(true),
+            Rule::new("a.b", "x"),
+        ];
+
+        let (content, _) = replace_in_text_file("cat concat cat. a.b acb", rules);
+
+        assert_eq!(content, "dog concat dog. x acb");
+    }
+
+    #[test]
+    fn test_rule_yaml_options_and_validation() {
+        let yaml = r#"
+replacements:
+  - old: "v\\d+"
+    new: "vNext"
+    regex: true
+    condition: "ignored"
+  - old: "Plain"
+    new: "Text"
+"#;
+        let rules: RulesFile = serde_yaml::from_str(yaml).unwrap();
+        assert!(rules.replacements[0].regex);
+        assert!(!rules.replacements[1].regex);
+        assert!(!rules.replacements[1].case_insensitive);
+        assert!(rules.replacements[1].pattern().unwrap().is_none());
+
+        let invalid = Rule::regex("(unclosed", "x");
+        let err = invalid.validate().unwrap_err();
+        assert!(err.to_string().contains("Invalid regex pattern"));
+    }
+
+    #[test]
+    fn test_backup_only_when_requested() {
+        let dir = tempfile::tempdir().unwrap();
+        let with_backup = dir.path().join("with.txt");
+        let without_backup = dir.path().join("without.txt");
+        std::fs::write(&with_backup, "version 1").unwrap();
+        std::fs::write(&without_backup, "version 1").unwrap();
+
+        let rules = || vec![Rule::new("1", "2")];
+        let options = ReplaceOptions {
+            backup: true,
+            ..Default::default()
+        };
+        run(&with_backup, rules(), options);
+        run(&without_backup, rules(), ReplaceOptions::default());
+
+        let backup = backup_path(&with_backup);
+        assert!(backup.ends_with("with.txt.bak"));
+        assert_eq!(std::fs::read_to_string(&backup).unwrap(), "version 1");
+        assert_eq!(std::fs::read_to_string(&with_backup).unwrap(), "version 2");
+
+        assert!(!backup_path(&without_backup).exists());
+        assert_eq!(
+            std::fs::read_to_string(&without_backup).unwrap(),
+            "version 2"
+        );
+    }
+
+    #[test]
+    fn test_concurrent_files_overlap_up_to_max_workers() {
+        use super::super::replacer::OverlapProbe;
+        use std::sync::Arc;
+
+        let dir = tempfile::tempdir().unwrap();
+        for i in 0..8 {
+            std::fs::write(dir.path().join(format!("note{}.txt", i)), "Hi {{NAME}}").unwrap();
+        }
+
+        let count_overlap = |concurrent: bool| {
+            let probe = Arc::new(OverlapProbe::default());
+            let replacer =
+                Replacer::new(vec![Rule::new("{{NAME}}", "Kim")]).with_probe(probe.clone());
+            let options = ReplaceOptions {
+                concurrent,
+                max_workers: 4,
+                ..Default::default()
+            };
+            let results = tokio_test::block_on(replacer.process_path(dir.path(), options)).unwrap();
+            assert_eq!(results.files_processed, 8);
+            probe.peak()
+        };
+
+        assert_eq!(count_overlap(false), 1);
+        let peak = count_overlap(true);
+        assert!((2..=4).contains(&peak), "peak of {} files at once", peak);
+    }
+
+    #[test]
+    fn test_dry_run_leaves_file_untouched() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("draft.txt");
+        let original = "line one\nline two\n";
+        std::fs::write(&path, original).unwrap();
+
+        let options = ReplaceOptions {
+            dry_run: true,
+            backup: true,
+            show_diff: true,
+            ..Default::default()
+        };
+        let results = run(&path, vec![Rule::new("two", "2")], options);
+
+        assert_eq!(results.total_replacements, 1);
+        assert_eq!(std::fs::read(&path).unwrap(), original.as_bytes());
+        assert!(!backup_path(&path).exists());
+    }
+
+    #[test]
+    fn test_cancel_after_first_file() {
+        for concurrent in [false, true] {
+            let dir = tempfile::tempdir().unwrap();
+            for name in ["a.txt", "b.txt", "c.txt"] {
+                std::fs::write(dir.path().join(name), "draft").unwrap();
+            }
+
+            let token = CancellationToken::new();
+            let calls = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+            let progress = {
+                let token = token.clone();
+                let calls = calls.clone();
+                ProgressCallback::new(move |current, total| {
+                    calls.lock().unwrap().push((current, total));
+                    token.cancel();
+                })
+            };
+            let options = ReplaceOptions {
+                concurrent,
+                max_workers: 1,
+                progress: Some(progress),
+                cancel: Some(token),
+                ..Default::default()
+            };
+            let results = run(dir.path(), vec![Rule::new("draft", "final")], options);
+
+            assert!(results.cancelled);
+            assert_eq!(results.files_processed, 1);
+            assert_eq!(*calls.lock().unwrap(), vec![(1, 3)]);
+
+            let read = |name| std::fs::read_to_string(dir.path().join(name)).unwrap();
+            assert_eq!(read("a.txt"), "final");
+            assert_eq!(read("b.txt"), "draft");
+            assert_eq!(read("c.txt"), "draft");
+        }
+    }
+
+    #[test]
+    fn test_unified_diff_output() {
+        let diff = dox_core::utils::ui::unified_diff("a\nb\nc\n", "a\nB\nc\n", 1);
+
+        assert!(diff.contains("@@ -1,3 +1,3 @@"));
+        assert!(diff.contains("-b\n+B"));
+    }
+}
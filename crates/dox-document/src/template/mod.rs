@@ -1,5 +1,26 @@
-// TODO: Implement template processing
-pub struct TemplateProcessor;
+//! Placeholder substitution for document templates
+//!
+//! Templates use the same `{{key}}` convention as the generation prompts in
+//! dox-core. Keys are dotted paths into a JSON value (`{{user.name}}`,
+//! `{{items.0}}` or `{{items[0]}}`), and `\{{` writes a literal `{{`.
+
+use anyhow::anyhow;
+use serde_json::Value;
+
+/// What to do when a placeholder has no matching value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MissingKeyBehavior {
+    /// Fail with a list of the unresolved placeholders
+    #[default]
+    Error,
+    /// Leave the placeholder in the output unchanged
+    Keep,
+}
+
+/// Template processor substituting `{{key}}` placeholders
+pub struct TemplateProcessor {
+    missing_keys: MissingKeyBehavior,
+}
 
 impl Default for TemplateProcessor {
     fn default() -> Self {
@@ -9,10 +30,188 @@ impl Default for TemplateProcessor {
 
 impl TemplateProcessor {
     pub fn new() -> Self {
-        TemplateProcessor
+        TemplateProcessor {
+            missing_keys: MissingKeyBehavior::default(),
+        }
+    }
+
+    /// Set how placeholders without a value are handled
+    pub fn with_missing_keys(mut self, behavior: MissingKeyBehavior) -> Self {
+        self.missing_keys = behavior;
+        self
+    }
+
+    /// Replace every placeholder in `template` with its value from `values`
+    pub fn process(&self, template: &str, values: &Value) -> anyhow::Result<String> {
+        let mut output = String::with_capacity(template.len());
+        let mut missing = Vec::new();
+        let mut rest = template;
+
+        while let Some(pos) = rest.find("{{") {
+            // `\{{` is an escaped literal opening brace pair
+            if rest[..pos].ends_with('\\') {
+                output.push_str(&rest[..pos - 1]);
+                output.push_str("{{");
+                rest = &rest[pos + 2..];
+                continue;
+            }
+
+            output.push_str(&rest[..pos]);
+            let after_open = &rest[pos + 2..];
+            let Some(end) = after_open.find("}}") else {
+                // Unterminated placeholder: keep the remainder as-is
+                output.push_str(&rest[pos..]);
+                rest = "";
+                break;
+            };
+
+            let placeholder = &rest[pos..pos + 2 + end + 2];
+            let key = after_open[..end].trim();
+
+            match lookup(values, key) {
+                Some(value) => output.push_str(&render_value(value)),
+                None => {
+                    if self.missing_keys == MissingKeyBehavior::Error {
+                        missing.push(key.to_string());
+                    }
+                    output.push_str(placeholder);
+                }
+            }
+
+            rest = &after_open[end + 2..];
+        }
+        output.push_str(rest);
+
+        if !missing.is_empty() {
+            return Err(anyhow!(
+                "Template contains unresolved placeholders: {}",
+                missing.join(", ")
+            ));
+        }
+
+        Ok(output)
+    }
+}
+
+/// Resolve a dotted path such as `user.name`, `items.0` or `items[0].name`
+fn lookup<'a>(values: &'a Value, key: &str) -> Option<&'a Value> {
+    if key.is_empty() {
+        return None;
+    }
+
+    let path = key.replace('[', ".").replace(']', "");
+    let mut current = values;
+
+    for segment in path.split('.').filter(|s| !s.is_empty()) {
+        current = match current {
+            Value::Object(map) => map.get(segment)?,
+            Value::Array(items) => items.get(segment.parse::<usize>().ok()?)?,
+            _ => return None,
+        };
+    }
+
+    Some(current)
+}
+
+/// Render a JSON value as template text
+fn render_value(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        Value::Array(items) => items
+            .iter()
+            .map(render_value)
+            .collect::<Vec<_>>()
+            .join(", "),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_simple_and_nested_keys() {
+        let values = json!({
+            "title": "Quarterly Report",
+            "user": { "name": "Kim", "address": { "city": "Seoul" } },
+            "count": 3,
+            "active": true
+        });
+
+        let result = TemplateProcessor::new()
+            .process(
+                "{{title}} by {{ user.name }} ({{user.address.city}}): {{count}} items, active={{active}}",
+                &values,
+            )
+            .unwrap();
+
+        assert_eq!(
+            result,
+            "Quarterly Report by Kim (Seoul): 3 items, active=true"
+        );
     }
 
-    pub fn process(&self, _template: &str, _values: &serde_json::Value) -> anyhow::Result<String> {
-        anyhow::bail!("Template processing not yet implemented")
+    #[test]
+    fn test_array_access() {
+        let values = json!({
+            "tags": ["rust", "cli"],
+            "items": [{ "name": "first" }, { "name": "second" }]
+        });
+
+        let result = TemplateProcessor::new()
+            .process("{{items.0.name}}, {{items[1].name}} / {{tags}}", &values)
+            .unwrap();
+
+        assert_eq!(result, "first, second / rust, cli");
+    }
+
+    #[test]
+    fn test_missing_key_errors_by_default() {
+        let values = json!({ "name": "Kim" });
+
+        let err = TemplateProcessor::new()
+            .process("{{name}} {{missing}} {{user.email}}", &values)
+            .unwrap_err();
+
+        let message = err.to_string();
+        assert!(message.contains("missing"));
+        assert!(message.contains("user.email"));
+    }
+
+    #[test]
+    fn test_missing_key_kept() {
+        let values = json!({ "name": "Kim", "items": [] });
+
+        let result = TemplateProcessor::new()
+            .with_missing_keys(MissingKeyBehavior::Keep)
+            .process("Hello {{name}}, {{ unknown }} {{items.5}}", &values)
+            .unwrap();
+
+        assert_eq!(result, "Hello Kim, {{ unknown }} {{items.5}}");
+    }
+
+    #[test]
+    fn test_escaped_braces() {
+        let values = json!({ "name": "Kim" });
+
+        let result = TemplateProcessor::new()
+            .process(r"\{{name}} is written as {{name}}", &values)
+            .unwrap();
+
+        assert_eq!(result, "{{name}} is written as Kim");
+    }
+
+    #[test]
+    fn test_unterminated_placeholder_is_literal() {
+        let values = json!({ "name": "Kim" });
+
+        let result = TemplateProcessor::new()
+            .process("{{name}} and {{broken", &values)
+            .unwrap();
+
+        assert_eq!(result, "Kim and {{broken");
     }
 }
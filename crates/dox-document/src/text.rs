@@ -27,6 +27,23 @@ impl TextProvider {
             modified: false,
         })
     }
+
+    /// Open UTF-8 text held in memory
+    ///
+    /// The document has no path, so changes are written with `save_as`.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, DocumentError> {
+        let content =
+            String::from_utf8(data.to_vec()).map_err(|e| DocumentError::FileReadError {
+                path: String::new(),
+                source: e.into(),
+            })?;
+
+        Ok(TextProvider {
+            path: PathBuf::new(),
+            content,
+            modified: false,
+        })
+    }
 }
 
 impl DocumentProvider for TextProvider {
@@ -42,6 +59,20 @@ impl DocumentProvider for TextProvider {
         Ok(replacements)
     }
 
+    fn replace_pattern(
+        &mut self,
+        pattern: &regex::Regex,
+        replacement: &str,
+    ) -> Result<usize, DocumentError> {
+        let replacements = pattern.find_iter(&self.content).count();
+        if replacements > 0 {
+            self.content = pattern.replace_all(&self.content, replacement).into_owned();
+            self.modified = true;
+        }
+
+        Ok(replacements)
+    }
+
     fn save(&self) -> Result<(), DocumentError> {
         fs::write(&self.path, &self.content).map_err(DocumentError::Io)?;
         Ok(())
@@ -4,14 +4,43 @@ use crate::provider::{DocumentError, DocumentType};
 use std::fs::File;
 use std::io::{ErrorKind, Read, Seek, Write};
 use std::path::Path;
+use zip::result::ZipError;
 use zip::write::SimpleFileOptions;
 use zip::{ZipArchive, ZipWriter};
 
+/// Signature of the OLE compound file that password-protected Office
+/// documents are stored in instead of a zip package
+const COMPOUND_FILE_SIGNATURE: [u8; 8] = [0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1];
+
 /// Extract a ZIP archive to a temporary directory
 pub fn extract_zip(zip_data: &[u8]) -> Result<ZipArchive<std::io::Cursor<&[u8]>>, DocumentError> {
-    let reader = std::io::Cursor::new(zip_data);
-    let archive = ZipArchive::new(reader)?;
-    Ok(archive)
+    open_archive(std::io::Cursor::new(zip_data))
+}
+
+/// Open a package, telling encrypted documents apart from damaged ones
+fn open_archive<R: Read + Seek>(mut reader: R) -> Result<ZipArchive<R>, DocumentError> {
+    let mut signature = [0u8; 8];
+    let encrypted =
+        reader.read_exact(&mut signature).is_ok() && signature == COMPOUND_FILE_SIGNATURE;
+    reader.rewind()?;
+    if encrypted {
+        return Err(DocumentError::EncryptionRequired);
+    }
+
+    ZipArchive::new(reader).map_err(|e| match e {
+        ZipError::InvalidArchive(_) | ZipError::UnsupportedArchive(_) => {
+            DocumentError::CorruptArchive {
+                reason: e.to_string(),
+            }
+        }
+        // Truncated files run out of data while the directory is read
+        ZipError::Io(io) if io.kind() == ErrorKind::UnexpectedEof => {
+            DocumentError::CorruptArchive {
+                reason: "the file is truncated".to_string(),
+            }
+        }
+        other => DocumentError::Zip(other),
+    })
 }
 
 /// Create a new ZIP archive with the given files
@@ -39,24 +68,133 @@ pub fn is_office_document(path: &Path) -> bool {
 }
 
 /// Read a file from a ZIP archive by name
-pub fn read_zip_file(
-    archive: &mut ZipArchive<std::io::Cursor<&[u8]>>,
+pub fn read_zip_file<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
     file_name: &str,
 ) -> Result<Vec<u8>, DocumentError> {
-    let mut file = archive.by_name(file_name)?;
+    let mut file = archive.by_name(file_name).map_err(|e| match e {
+        ZipError::FileNotFound => DocumentError::MissingPart {
+            part: file_name.to_string(),
+        },
+        other => DocumentError::Zip(other),
+    })?;
     let mut content = Vec::new();
     file.read_to_end(&mut content)?;
     Ok(content)
 }
 
+/// A relationship from a package `.rels` part
+#[derive(Debug, Clone, PartialEq)]
+pub struct Relationship {
+    pub id: String,
+    /// Relationship type URI
+    pub rel_type: String,
+    pub target: String,
+    /// Whether the target is outside the package, such as a web URL
+    pub external: bool,
+}
+
+/// Name of the `.rels` part holding the relationships of `part_name`
+///
+/// For example `word/document.xml` has `word/_rels/document.xml.rels`.
+pub fn relationships_part_name(part_name: &str) -> String {
+    match part_name.rsplit_once('/') {
+        Some((dir, file)) => format!("{}/_rels/{}.rels", dir, file),
+        None => format!("_rels/{}.rels", part_name),
+    }
+}
+
+/// Read the relationships of a package part
+///
+/// A part without a `.rels` part has no relationships.
+pub fn read_relationships<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    part_name: &str,
+) -> Result<Vec<Relationship>, DocumentError> {
+    use quick_xml::events::Event;
+    use quick_xml::Reader;
+
+    let Ok(xml) = read_zip_file(archive, &relationships_part_name(part_name)) else {
+        return Ok(Vec::new());
+    };
+
+    let mut reader = Reader::from_reader(xml.as_slice());
+    let mut buf = Vec::new();
+    let mut relationships = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Eof => break,
+            Event::Start(e) | Event::Empty(e) if e.name().as_ref() == b"Relationship" => {
+                let attr = |key: &[u8]| {
+                    e.attributes()
+                        .flatten()
+                        .find(|a| a.key.as_ref() == key)
+                        .and_then(|a| a.unescape_value().ok())
+                        .map(|v| v.into_owned())
+                        .unwrap_or_default()
+                };
+                relationships.push(Relationship {
+                    id: attr(b"Id"),
+                    rel_type: attr(b"Type"),
+                    target: attr(b"Target"),
+                    external: attr(b"TargetMode") == "External",
+                });
+            }
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(relationships)
+}
+
+/// Resolve an internal relationship target against the part that owns it
+///
+/// `../notesSlides/notesSlide1.xml` from `ppt/slides/slide1.xml` resolves
+/// to `ppt/notesSlides/notesSlide1.xml`.
+pub fn resolve_part_target(part_name: &str, target: &str) -> String {
+    let mut parts: Vec<&str> = if target.starts_with('/') {
+        Vec::new()
+    } else {
+        part_name.split('/').collect()
+    };
+    // Drop the owning part's file name
+    parts.pop();
+
+    for segment in target.split('/') {
+        match segment {
+            ".." => {
+                parts.pop();
+            }
+            "." | "" => {}
+            segment => parts.push(segment),
+        }
+    }
+
+    parts.join("/")
+}
+
+/// Open a ZIP archive on disk without reading it into memory
+///
+/// Only the entries that are read are decompressed, which keeps lookups of
+/// small parts such as document properties cheap for large files.
+pub fn open_zip_file(path: &Path) -> Result<ZipArchive<File>, DocumentError> {
+    if !path.exists() {
+        return Err(DocumentError::DocumentNotFound {
+            path: path.to_string_lossy().to_string(),
+        });
+    }
+    open_archive(File::open(path)?)
+}
+
 /// Copy all files from source ZIP to destination ZIP, optionally replacing some files
 pub fn copy_zip_with_replacements<W: Write + Seek>(
     source_data: &[u8],
     destination: W,
     replacements: &std::collections::HashMap<String, Vec<u8>>,
 ) -> Result<(), DocumentError> {
-    let reader = std::io::Cursor::new(source_data);
-    let mut source_archive = ZipArchive::new(reader)?;
+    let mut source_archive = extract_zip(source_data)?;
     let mut dest_writer = ZipWriter::new(destination);
 
     // Copy all files from source, replacing when necessary
@@ -139,6 +277,34 @@ pub fn replace_text_in_xml(
     text_tags: &[&str],
     old: &str,
     new: &str,
+) -> Result<(Vec<u8>, usize), DocumentError> {
+    rewrite_xml_text(xml_content, text_tags, |text| {
+        let count = text.matches(old).count();
+        (text.replace(old, new), count)
+    })
+}
+
+/// Replace regex matches in XML text content while preserving structure
+///
+/// `replacement` may refer to capture groups (`$1`, `${name}`).
+pub fn replace_pattern_in_xml(
+    xml_content: &[u8],
+    text_tags: &[&str],
+    pattern: &regex::Regex,
+    replacement: &str,
+) -> Result<(Vec<u8>, usize), DocumentError> {
+    rewrite_xml_text(xml_content, text_tags, |text| {
+        let count = pattern.find_iter(text).count();
+        (pattern.replace_all(text, replacement).into_owned(), count)
+    })
+}
+
+/// Rewrite the text inside `text_tags`, returning the new XML and the
+/// total number of replacements reported by `rewrite`
+fn rewrite_xml_text(
+    xml_content: &[u8],
+    text_tags: &[&str],
+    mut rewrite: impl FnMut(&str) -> (String, usize),
 ) -> Result<(Vec<u8>, usize), DocumentError> {
     use quick_xml::events::{BytesText, Event};
     use quick_xml::{Reader, Writer};
@@ -177,10 +343,8 @@ pub fn replace_text_in_xml(
             }
             Ok(Event::Text(ref e)) if in_text => {
                 let text = e.unescape()?;
-                let replaced = text.replace(old, new);
-                if text != replaced {
-                    replacement_count += text.matches(old).count();
-                }
+                let (replaced, count) = rewrite(&text);
+                replacement_count += count;
                 writer.write_event(Event::Text(BytesText::new(&replaced)))?;
             }
             Ok(Event::Eof) => break,
@@ -193,6 +357,256 @@ pub fn replace_text_in_xml(
     Ok((output, replacement_count))
 }
 
+/// Replace text in XML content, matching across the runs of each paragraph
+///
+/// A match spanning several `text_tag` elements inside one `paragraph_tag`
+/// is written into the element where it starts, so it keeps that run's
+/// formatting; the matched text is removed from the other elements.
+pub fn replace_text_across_runs(
+    xml_content: &[u8],
+    paragraph_tag: &str,
+    text_tag: &str,
+    old: &str,
+    new: &str,
+) -> Result<(Vec<u8>, usize), DocumentError> {
+    rewrite_paragraph_text(xml_content, paragraph_tag, text_tag, |text| {
+        if old.is_empty() {
+            return vec![];
+        }
+        text.match_indices(old)
+            .map(|(start, matched)| (start..start + matched.len(), new.to_string()))
+            .collect()
+    })
+}
+
+/// Replace regex matches in XML content, matching across the runs of each paragraph
+///
+/// `replacement` may refer to capture groups (`$1`, `${name}`).
+pub fn replace_pattern_across_runs(
+    xml_content: &[u8],
+    paragraph_tag: &str,
+    text_tag: &str,
+    pattern: &regex::Regex,
+    replacement: &str,
+) -> Result<(Vec<u8>, usize), DocumentError> {
+    rewrite_paragraph_text(xml_content, paragraph_tag, text_tag, |text| {
+        pattern
+            .captures_iter(text)
+            .map(|caps| {
+                let matched = caps.get(0).unwrap();
+                let mut expanded = String::new();
+                caps.expand(replacement, &mut expanded);
+                (matched.range(), expanded)
+            })
+            .collect()
+    })
+}
+
+/// Find regex matches in XML content, matching across the runs of each paragraph
+///
+/// Returns the text of every match in document order.
+pub fn find_pattern_across_runs(
+    xml_content: &[u8],
+    paragraph_tag: &str,
+    text_tag: &str,
+    pattern: &regex::Regex,
+) -> Result<Vec<String>, DocumentError> {
+    let mut found = Vec::new();
+    rewrite_paragraph_text(xml_content, paragraph_tag, text_tag, |text| {
+        found.extend(pattern.find_iter(text).map(|m| m.as_str().to_string()));
+        vec![]
+    })?;
+    Ok(found)
+}
+
+/// Buffered content of an open paragraph
+#[derive(Default)]
+struct Paragraph {
+    items: Vec<ParagraphItem>,
+    /// Text of each text element, in document order
+    segments: Vec<String>,
+}
+
+enum ParagraphItem {
+    Event(quick_xml::events::Event<'static>),
+    /// Start of a text element whose content is `segments[index]`
+    Text {
+        start: quick_xml::events::BytesStart<'static>,
+        index: usize,
+    },
+}
+
+/// Rewrite the joined text of each paragraph using the edits from `find`
+///
+/// `find` returns non-overlapping byte ranges of the paragraph text, in
+/// order, each with its replacement.
+fn rewrite_paragraph_text(
+    xml_content: &[u8],
+    paragraph_tag: &str,
+    text_tag: &str,
+    mut find: impl FnMut(&str) -> Vec<(std::ops::Range<usize>, String)>,
+) -> Result<(Vec<u8>, usize), DocumentError> {
+    use quick_xml::events::Event;
+    use quick_xml::{Reader, Writer};
+
+    let mut reader = Reader::from_reader(std::io::Cursor::new(xml_content));
+    let mut output = Vec::new();
+    let mut writer = Writer::new(std::io::Cursor::new(&mut output));
+    let mut buf = Vec::new();
+    let mut replacement_count = 0;
+    // Paragraphs can nest, e.g. inside text boxes
+    let mut paragraphs: Vec<Paragraph> = Vec::new();
+    let mut in_text = false;
+
+    loop {
+        let event = match reader.read_event_into(&mut buf) {
+            Ok(Event::Eof) => break,
+            Ok(event) => event.into_owned(),
+            Err(e) => return Err(DocumentError::Xml(e)),
+        };
+        buf.clear();
+
+        let Some(paragraph) = paragraphs.last_mut() else {
+            if matches!(&event, Event::Start(e) if e.name().as_ref() == paragraph_tag.as_bytes()) {
+                paragraphs.push(Paragraph {
+                    items: vec![ParagraphItem::Event(event)],
+                    ..Default::default()
+                });
+            } else {
+                writer.write_event(event)?;
+            }
+            continue;
+        };
+
+        match event {
+            Event::Start(e) if e.name().as_ref() == paragraph_tag.as_bytes() => {
+                paragraphs.push(Paragraph {
+                    items: vec![ParagraphItem::Event(Event::Start(e))],
+                    ..Default::default()
+                });
+            }
+            Event::Start(e) if e.name().as_ref() == text_tag.as_bytes() => {
+                paragraph.items.push(ParagraphItem::Text {
+                    start: e,
+                    index: paragraph.segments.len(),
+                });
+                paragraph.segments.push(String::new());
+                in_text = true;
+            }
+            Event::Text(e) if in_text => {
+                let text = e.unescape()?;
+                paragraph.segments.last_mut().unwrap().push_str(&text);
+            }
+            Event::End(e) if e.name().as_ref() == text_tag.as_bytes() => {
+                in_text = false;
+                paragraph.items.push(ParagraphItem::Event(Event::End(e)));
+            }
+            Event::End(e) if e.name().as_ref() == paragraph_tag.as_bytes() => {
+                let mut paragraph = paragraphs.pop().unwrap();
+                paragraph.items.push(ParagraphItem::Event(Event::End(e)));
+
+                // Without a text element there is nowhere to put a replacement
+                let edits = if paragraph.segments.is_empty() {
+                    vec![]
+                } else {
+                    find(&paragraph.segments.concat())
+                };
+                replacement_count += edits.len();
+                let segments = splice_segments(&paragraph.segments, &edits);
+                let events = resolve_paragraph(paragraph.items, &paragraph.segments, &segments);
+
+                match paragraphs.last_mut() {
+                    Some(parent) => parent
+                        .items
+                        .extend(events.into_iter().map(ParagraphItem::Event)),
+                    None => {
+                        for event in events {
+                            writer.write_event(event)?;
+                        }
+                    }
+                }
+            }
+            event => paragraph.items.push(ParagraphItem::Event(event)),
+        }
+    }
+
+    Ok((output, replacement_count))
+}
+
+/// Apply edits to the joined text of `segments`, keeping segment boundaries
+///
+/// Each replacement goes into the segment where its match starts.
+fn splice_segments(segments: &[String], edits: &[(std::ops::Range<usize>, String)]) -> Vec<String> {
+    let joined = segments.concat();
+    let mut bounds = Vec::with_capacity(segments.len());
+    let mut offset = 0;
+    for segment in segments {
+        bounds.push(offset..offset + segment.len());
+        offset += segment.len();
+    }
+
+    let mut result = vec![String::new(); segments.len()];
+    // Copy unmatched text back into the segments it came from
+    let copy = |result: &mut [String], from: usize, to: usize| {
+        for (i, bound) in bounds.iter().enumerate() {
+            let (start, end) = (from.max(bound.start), to.min(bound.end));
+            if start < end {
+                result[i].push_str(&joined[start..end]);
+            }
+        }
+    };
+
+    let mut cursor = 0;
+    for (range, replacement) in edits {
+        copy(&mut result, cursor, range.start);
+        let owner = bounds
+            .iter()
+            .position(|bound| range.start < bound.end)
+            .unwrap_or(segments.len() - 1);
+        result[owner].push_str(replacement);
+        cursor = range.end;
+    }
+    copy(&mut result, cursor, joined.len());
+
+    result
+}
+
+/// Turn buffered paragraph items back into events with the new segment text
+fn resolve_paragraph(
+    items: Vec<ParagraphItem>,
+    original: &[String],
+    segments: &[String],
+) -> Vec<quick_xml::events::Event<'static>> {
+    use quick_xml::events::{BytesText, Event};
+
+    let mut events = Vec::with_capacity(items.len());
+    for item in items {
+        match item {
+            ParagraphItem::Event(event) => events.push(event),
+            ParagraphItem::Text { mut start, index } => {
+                let text = &segments[index];
+                // Word drops leading and trailing spaces unless told to keep them
+                let needs_preserve = *text != original[index]
+                    && (text.starts_with(char::is_whitespace)
+                        || text.ends_with(char::is_whitespace));
+                let has_space = start
+                    .attributes()
+                    .flatten()
+                    .any(|attr| attr.key.as_ref() == b"xml:space");
+                if needs_preserve && !has_space {
+                    start.push_attribute(("xml:space", "preserve"));
+                }
+
+                events.push(Event::Start(start));
+                if !text.is_empty() {
+                    events.push(Event::Text(BytesText::new(text).into_owned()));
+                }
+            }
+        }
+    }
+    events
+}
+
 /// Extract text content from XML
 pub fn extract_text_from_xml(
     xml_content: &[u8],
@@ -1,11 +1,18 @@
 //! Word document (.docx) processing implementation
 
-use crate::provider::{DocumentError, DocumentProvider, DocumentType};
+use crate::extract::ExtractedLink;
+use crate::provider::{
+    tally_placeholders, DocumentError, DocumentProvider, DocumentType, PlaceholderMatch,
+};
 use crate::utils::{
-    copy_zip_with_replacements, extract_text_from_xml, extract_zip, read_zip_file,
-    replace_text_in_xml,
+    copy_zip_with_replacements, extract_text_from_xml, extract_zip, find_pattern_across_runs,
+    open_zip_file, read_relationships, read_zip_file, replace_pattern_across_runs,
+    replace_text_across_runs,
 };
+use crate::word_structure::{self, WordDocumentModel};
+use dox_core::utils::write_atomic;
 use std::collections::HashMap;
+use std::io::{Read, Seek};
 use std::path::{Path, PathBuf};
 use tracing::debug;
 use xml::reader::{EventReader, XmlEvent};
@@ -20,6 +27,10 @@ pub struct WordMetadata {
     pub total_pages: usize,
     pub created: Option<String>,
     pub modified: Option<String>,
+    /// Declared language tag, e.g. `ko-KR`
+    pub language: Option<String>,
+    /// Language tag declared for East Asian text (`w:eastAsia`)
+    pub east_asian_language: Option<String>,
 }
 
 /// Word document provider for .docx files
@@ -44,17 +55,24 @@ impl WordProvider {
 
         // Read entire file into memory
         let archive_data = std::fs::read(path)?;
+        Self::load(path.to_path_buf(), archive_data)
+    }
+
+    /// Open a Word document held in memory
+    ///
+    /// The document has no path, so changes are written with `save_as`.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, DocumentError> {
+        debug!("Opening Word document from {} bytes", data.len());
+        Self::load(PathBuf::new(), data.to_vec())
+    }
 
+    fn load(path: PathBuf, archive_data: Vec<u8>) -> Result<Self, DocumentError> {
         // Extract document.xml content
         let mut archive = extract_zip(&archive_data)?;
-        let content = read_zip_file(&mut archive, "word/document.xml").map_err(|_| {
-            DocumentError::InvalidStructure {
-                reason: "Missing word/document.xml".to_string(),
-            }
-        })?;
+        let content = read_zip_file(&mut archive, Self::DOCUMENT_PART)?;
 
         Ok(WordProvider {
-            path: path.to_path_buf(),
+            path,
             archive_data,
             content,
             modified: false,
@@ -68,32 +86,185 @@ impl WordProvider {
         })
     }
 
-    /// Get the Word text tags used for text extraction and replacement
+    /// Part holding the document body
+    const DOCUMENT_PART: &'static str = "word/document.xml";
+    /// Paragraph tag; replacements match across the runs inside it
+    const PARAGRAPH_TAG: &'static str = "w:p";
+    /// Run text tag
+    const TEXT_TAG: &'static str = "w:t";
+
+    /// Get the Word text tags used for text extraction
     fn text_tags() -> &'static [&'static str] {
-        &["w:t"]
+        &[Self::TEXT_TAG]
+    }
+
+    /// Read the paragraphs and runs of a Word document
+    pub fn read_structure(path: &Path) -> Result<WordDocumentModel, DocumentError> {
+        Self::open(path)?.structure()
+    }
+
+    /// Paragraphs and runs of the document body, including pending edits
+    pub fn structure(&self) -> Result<WordDocumentModel, DocumentError> {
+        word_structure::read_structure(&self.content).map_err(|e| e.in_part(Self::DOCUMENT_PART))
+    }
+
+    /// Non-empty paragraphs outside tables and tables in the document body,
+    /// as `(paragraphs, tables)`
+    pub(crate) fn block_counts(&self) -> Result<(usize, usize), DocumentError> {
+        word_structure::count_blocks(&self.content).map_err(|e| e.in_part(Self::DOCUMENT_PART))
+    }
+
+    /// Write edited run text from `model` back into the document
+    ///
+    /// `model` must come from [`Self::structure`] with only run text
+    /// changed. Returns the number of runs whose text changed; call `save`
+    /// or `save_as` to write the file.
+    pub fn apply_structure(&mut self, model: &WordDocumentModel) -> Result<usize, DocumentError> {
+        let (new_content, count) = word_structure::write_structure(&self.content, model)
+            .map_err(|e| e.in_part(Self::DOCUMENT_PART))?;
+        if count > 0 {
+            self.content = new_content;
+            self.modified = true;
+            debug!("Rewrote {} runs in Word document", count);
+        }
+        Ok(count)
+    }
+
+    /// Hyperlinks in the document body (`w:hyperlink`) and their targets
+    ///
+    /// Links to bookmarks in the document get `#` and the bookmark name as
+    /// their URL.
+    pub fn hyperlinks(&self) -> Result<Vec<ExtractedLink>, DocumentError> {
+        use quick_xml::events::Event;
+
+        let mut archive = extract_zip(&self.archive_data)?;
+        let relationships = read_relationships(&mut archive, Self::DOCUMENT_PART)?;
+
+        let mut reader = quick_xml::Reader::from_reader(self.content.as_slice());
+        let mut buf = Vec::new();
+        let mut links = Vec::new();
+        // Link being read, until its w:hyperlink closes
+        let mut current: Option<ExtractedLink> = None;
+        let mut in_text = false;
+
+        loop {
+            match reader.read_event_into(&mut buf)? {
+                Event::Eof => break,
+                Event::Start(e) if e.name().as_ref() == b"w:hyperlink" => {
+                    let attr = |key: &[u8]| {
+                        e.attributes()
+                            .flatten()
+                            .find(|a| a.key.as_ref() == key)
+                            .and_then(|a| a.unescape_value().ok())
+                            .map(|v| v.into_owned())
+                    };
+                    let target = attr(b"r:id")
+                        .and_then(|id| relationships.iter().find(|rel| rel.id == id))
+                        .map(|rel| rel.target.clone());
+                    // An anchor alone links into the document; with a target it is a fragment
+                    let url = match (target, attr(b"w:anchor")) {
+                        (Some(target), Some(anchor)) => format!("{}#{}", target, anchor),
+                        (Some(target), None) => target,
+                        (None, Some(anchor)) => format!("#{}", anchor),
+                        (None, None) => String::new(),
+                    };
+                    current = Some(ExtractedLink {
+                        text: String::new(),
+                        url,
+                    });
+                }
+                Event::Start(e) if e.name().as_ref() == Self::TEXT_TAG.as_bytes() => {
+                    in_text = true;
+                }
+                Event::Text(e) if in_text => {
+                    if let Some(link) = current.as_mut() {
+                        link.text.push_str(&e.unescape()?);
+                    }
+                }
+                Event::End(e) => match e.name().as_ref() {
+                    b"w:hyperlink" => {
+                        if let Some(link) = current.take().filter(|link| !link.url.is_empty()) {
+                            links.push(link);
+                        }
+                    }
+                    name if name == Self::TEXT_TAG.as_bytes() => in_text = false,
+                    _ => {}
+                },
+                _ => {}
+            }
+            buf.clear();
+        }
+
+        Ok(links)
     }
 
     /// Extract metadata from core.xml properties
+    ///
+    /// Without a `dc:language` property the language comes from the first
+    /// `w:lang` in the document body. The style defaults are not used: they
+    /// belong to the template and say little about the text.
     pub fn get_metadata(&self) -> Result<WordMetadata, DocumentError> {
         let mut archive = extract_zip(&self.archive_data)?;
+        let mut metadata = Self::read_properties(&mut archive)?;
+        if metadata.language.is_none() {
+            (metadata.language, metadata.east_asian_language) = self.body_language()?;
+        }
+        Ok(metadata)
+    }
 
+    /// `w:val` and `w:eastAsia` of the first `w:lang` in the document body
+    fn body_language(&self) -> Result<(Option<String>, Option<String>), DocumentError> {
+        use quick_xml::events::Event;
+
+        let mut reader = quick_xml::Reader::from_reader(self.content.as_slice());
+        let mut buf = Vec::new();
+
+        loop {
+            match reader.read_event_into(&mut buf)? {
+                Event::Eof => return Ok((None, None)),
+                Event::Start(e) | Event::Empty(e) if e.name().as_ref() == b"w:lang" => {
+                    let attr = |key: &[u8]| {
+                        e.attributes()
+                            .flatten()
+                            .find(|a| a.key.as_ref() == key)
+                            .and_then(|a| a.unescape_value().ok())
+                            .map(|v| v.into_owned())
+                    };
+                    return Ok((attr(b"w:val"), attr(b"w:eastAsia")));
+                }
+                _ => {}
+            }
+            buf.clear();
+        }
+    }
+
+    /// Read the metadata of a document on disk without loading its body
+    pub fn read_metadata(path: &Path) -> Result<WordMetadata, DocumentError> {
+        let mut archive = open_zip_file(path)?;
+        Self::read_properties(&mut archive)
+    }
+
+    /// Read metadata from the core and app property parts
+    fn read_properties<R: Read + Seek>(
+        archive: &mut zip::ZipArchive<R>,
+    ) -> Result<WordMetadata, DocumentError> {
         let mut metadata = WordMetadata::default();
 
         // Try to read core.xml for basic metadata
-        if let Ok(core_xml) = read_zip_file(&mut archive, "docProps/core.xml") {
-            metadata = self.parse_core_properties(&core_xml)?;
+        if let Ok(core_xml) = read_zip_file(archive, "docProps/core.xml") {
+            metadata = Self::parse_core_properties(&core_xml)?;
         }
 
         // Try to read app.xml for additional metadata
-        if let Ok(app_xml) = read_zip_file(&mut archive, "docProps/app.xml") {
-            self.parse_app_properties(&app_xml, &mut metadata)?;
+        if let Ok(app_xml) = read_zip_file(archive, "docProps/app.xml") {
+            Self::parse_app_properties(&app_xml, &mut metadata)?;
         }
 
         Ok(metadata)
     }
 
     /// Parse core properties XML
-    fn parse_core_properties(&self, xml_data: &[u8]) -> Result<WordMetadata, DocumentError> {
+    fn parse_core_properties(xml_data: &[u8]) -> Result<WordMetadata, DocumentError> {
         let mut metadata = WordMetadata::default();
         let reader = EventReader::new(std::io::Cursor::new(xml_data));
 
@@ -116,6 +287,9 @@ impl WordProvider {
                         "subject" => metadata.subject = Some(text_content.clone()),
                         "created" => metadata.created = Some(text_content.clone()),
                         "modified" => metadata.modified = Some(text_content.clone()),
+                        "language" if !text_content.trim().is_empty() => {
+                            metadata.language = Some(text_content.trim().to_string())
+                        }
                         _ => {}
                     }
                     current_element.clear();
@@ -131,7 +305,6 @@ impl WordProvider {
 
     /// Parse app properties XML for additional metadata
     fn parse_app_properties(
-        &self,
         xml_data: &[u8],
         metadata: &mut WordMetadata,
     ) -> Result<(), DocumentError> {
@@ -175,7 +348,8 @@ impl DocumentProvider for WordProvider {
     fn replace_text(&mut self, old: &str, new: &str) -> Result<usize, DocumentError> {
         debug!("Replacing text '{}' with '{}' in Word document", old, new);
 
-        let (new_content, count) = replace_text_in_xml(&self.content, Self::text_tags(), old, new)?;
+        let (new_content, count) =
+            replace_text_across_runs(&self.content, Self::PARAGRAPH_TAG, Self::TEXT_TAG, old, new)?;
 
         if count > 0 {
             self.content = new_content;
@@ -186,6 +360,46 @@ impl DocumentProvider for WordProvider {
         Ok(count)
     }
 
+    fn replace_pattern(
+        &mut self,
+        pattern: &regex::Regex,
+        replacement: &str,
+    ) -> Result<usize, DocumentError> {
+        debug!(
+            "Replacing pattern '{}' with '{}' in Word document",
+            pattern, replacement
+        );
+
+        let (new_content, count) = replace_pattern_across_runs(
+            &self.content,
+            Self::PARAGRAPH_TAG,
+            Self::TEXT_TAG,
+            pattern,
+            replacement,
+        )?;
+
+        if count > 0 {
+            self.content = new_content;
+            self.modified = true;
+            debug!("Replaced {} matches in Word document", count);
+        }
+
+        Ok(count)
+    }
+
+    fn find_placeholders(
+        &self,
+        pattern: &regex::Regex,
+    ) -> Result<Vec<PlaceholderMatch>, DocumentError> {
+        // Placeholders are often split over runs, which get_text separates
+        let found =
+            find_pattern_across_runs(&self.content, Self::PARAGRAPH_TAG, Self::TEXT_TAG, pattern)?;
+        Ok(tally_placeholders(
+            &self.path,
+            found.iter().map(String::as_str),
+        ))
+    }
+
     fn save(&self) -> Result<(), DocumentError> {
         if !self.modified {
             debug!("No changes to save in Word document");
@@ -199,14 +413,14 @@ impl DocumentProvider for WordProvider {
     fn save_as(&self, path: &Path) -> Result<(), DocumentError> {
         debug!("Saving Word document as: {}", path.display());
 
-        let file = std::fs::File::create(path)?;
-
         // Prepare replacements map
         let mut replacements = HashMap::new();
-        replacements.insert("word/document.xml".to_string(), self.content.clone());
+        replacements.insert(Self::DOCUMENT_PART.to_string(), self.content.clone());
 
         // Copy archive with replacements
-        copy_zip_with_replacements(&self.archive_data, file, &replacements)?;
+        write_atomic(path, |file| {
+            copy_zip_with_replacements(&self.archive_data, file, &replacements)
+        })?;
 
         debug!("Word document saved successfully");
         Ok(())
@@ -214,7 +428,8 @@ impl DocumentProvider for WordProvider {
 
     fn get_text(&self) -> Result<String, DocumentError> {
         debug!("Extracting text from Word document");
-        let text = extract_text_from_xml(&self.content, Self::text_tags())?;
+        let text = extract_text_from_xml(&self.content, Self::text_tags())
+            .map_err(|e| e.in_part(Self::DOCUMENT_PART))?;
         Ok(text)
     }
 
@@ -238,6 +453,16 @@ mod tests {
     use tempfile::NamedTempFile;
 
     fn create_minimal_docx() -> Vec<u8> {
+        create_docx_with_body(
+            r#"<w:p>
+            <w:r>
+                <w:t>Hello World</w:t>
+            </w:r>
+        </w:p>"#,
+        )
+    }
+
+    fn create_docx_with_body(body: &str) -> Vec<u8> {
         // This is a minimal Word document structure for testing
         // In a real implementation, you'd want to use a proper minimal template
         let mut zip_data = Vec::new();
@@ -246,16 +471,15 @@ mod tests {
             let mut writer = ZipWriter::new(std::io::Cursor::new(&mut zip_data));
 
             // Add minimal document.xml
-            let doc_xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+            let doc_xml = format!(
+                r#"<?xml version="1.0" encoding="UTF-8"?>
 <w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">
     <w:body>
-        <w:p>
-            <w:r>
-                <w:t>Hello World</w:t>
-            </w:r>
-        </w:p>
+        {}
     </w:body>
-</w:document>"#;
+</w:document>"#,
+                body
+            );
 
             writer
                 .start_file("word/document.xml", SimpleFileOptions::default())
@@ -266,6 +490,18 @@ mod tests {
         zip_data
     }
 
+    /// `Dear {{name}},` with the placeholder split over three bold runs
+    fn create_split_placeholder_docx() -> Vec<u8> {
+        create_docx_with_body(
+            r#"<w:p>
+            <w:r><w:t xml:space="preserve">Dear </w:t></w:r>
+            <w:r><w:rPr><w:b/></w:rPr><w:t>{{</w:t></w:r>
+            <w:r><w:rPr><w:i/></w:rPr><w:t>name</w:t></w:r>
+            <w:r><w:rPr><w:u w:val="single"/></w:rPr><w:t>}},</w:t></w:r>
+        </w:p>"#,
+        )
+    }
+
     #[test]
     fn test_word_document_text_extraction() {
         let zip_data = create_minimal_docx();
@@ -291,4 +527,149 @@ mod tests {
         let text = doc.get_text().unwrap();
         assert_eq!(text, "Hi World");
     }
+
+    #[test]
+    fn test_replacement_across_runs() {
+        let temp_file = NamedTempFile::new().unwrap();
+        std::fs::write(temp_file.path(), create_split_placeholder_docx()).unwrap();
+
+        for name in ["Kim", "Alexandria Ocasio"] {
+            let mut doc = WordProvider::open(temp_file.path()).unwrap();
+            let count = doc.replace_text("{{name}}", name).unwrap();
+            assert_eq!(count, 1);
+
+            let xml = String::from_utf8(doc.content.clone()).unwrap();
+            // The replacement keeps the formatting of the run where the match started
+            assert!(xml.contains(&format!("<w:b/></w:rPr><w:t>{}</w:t>", name)));
+            assert!(xml.contains("<w:i/></w:rPr><w:t></w:t>"));
+            assert!(xml.contains("<w:u w:val=\"single\"/></w:rPr><w:t>,</w:t>"));
+
+            let output = NamedTempFile::new().unwrap();
+            doc.save_as(output.path()).unwrap();
+            let saved = WordProvider::open(output.path()).unwrap();
+            let text = saved.get_text().unwrap();
+            assert!(text.contains(name));
+            assert!(!text.contains("{{") && !text.contains("}}"));
+        }
+    }
+
+    #[test]
+    fn test_pattern_replacement_across_runs() {
+        let temp_file = NamedTempFile::new().unwrap();
+        std::fs::write(temp_file.path(), create_split_placeholder_docx()).unwrap();
+
+        let mut doc = WordProvider::open(temp_file.path()).unwrap();
+        let pattern = regex::Regex::new(r"\{\{(\w+)\}\}").unwrap();
+        let count = doc.replace_pattern(&pattern, "<$1>").unwrap();
+        assert_eq!(count, 1);
+
+        let xml = String::from_utf8(doc.content.clone()).unwrap();
+        assert!(xml.contains("<w:b/></w:rPr><w:t>&lt;name&gt;</w:t>"));
+        assert!(!xml.contains("{{"));
+    }
+
+    #[test]
+    fn test_find_placeholders() {
+        let temp_file = NamedTempFile::new().unwrap();
+        std::fs::write(
+            temp_file.path(),
+            create_docx_with_body(
+                r#"<w:p><w:r><w:t>Dear {{name}}, as of {{date}}</w:t></w:r></w:p>
+            <w:p>
+                <w:r><w:t xml:space="preserve">Signed: </w:t></w:r>
+                <w:r><w:rPr><w:b/></w:rPr><w:t>{{na</w:t></w:r>
+                <w:r><w:t>me}}</w:t></w:r>
+            </w:p>
+            <w:p><w:r><w:t>{{name}} ({{date}}), {{name}}</w:t></w:r></w:p>"#,
+            ),
+        )
+        .unwrap();
+
+        let doc = WordProvider::open(temp_file.path()).unwrap();
+        let pattern = regex::Regex::new(r"\{\{\w+\}\}").unwrap();
+        let found = doc.find_placeholders(&pattern).unwrap();
+
+        let counts: Vec<(&str, usize)> = found
+            .iter()
+            .map(|m| (m.placeholder.as_str(), m.count))
+            .collect();
+        assert_eq!(counts, vec![("{{name}}", 4), ("{{date}}", 2)]);
+        assert!(found.iter().all(|m| m.path == temp_file.path()));
+        assert!(!doc.is_modified());
+    }
+
+    fn create_formatted_docx() -> Vec<u8> {
+        create_docx_with_body(
+            r#"<w:p>
+            <w:pPr><w:pStyle w:val="Heading1"/><w:jc w:val="center"/><w:rPr><w:i/></w:rPr></w:pPr>
+            <w:r><w:rPr><w:b/><w:sz w:val="32"/><w:color w:val="FF0000"/></w:rPr><w:t>Quarterly</w:t></w:r>
+            <w:r><w:t xml:space="preserve"> Report</w:t></w:r>
+        </w:p>
+        <w:p>
+            <w:r><w:rPr><w:i/><w:b w:val="0"/><w:rFonts w:ascii="Arial"/></w:rPr><w:t>Sales </w:t><w:t>grew</w:t></w:r>
+        </w:p>
+        <w:p/>"#,
+        )
+    }
+
+    #[test]
+    fn test_read_structure() {
+        let temp_file = NamedTempFile::new().unwrap();
+        std::fs::write(temp_file.path(), create_formatted_docx()).unwrap();
+
+        let model = WordProvider::read_structure(temp_file.path()).unwrap();
+        assert_eq!(model.paragraphs.len(), 3);
+
+        let heading = &model.paragraphs[0];
+        assert_eq!(heading.style.as_deref(), Some("Heading1"));
+        assert_eq!(heading.alignment.as_deref(), Some("center"));
+        assert_eq!(heading.runs[0].text, "Quarterly");
+        assert!(heading.runs[0].format.bold);
+        // The paragraph mark's italic is not run formatting
+        assert!(!heading.runs[0].format.italic);
+        assert_eq!(heading.runs[0].format.font_size, Some(16.0));
+        assert_eq!(heading.runs[0].format.color.as_deref(), Some("FF0000"));
+        assert_eq!(heading.text(), "Quarterly Report");
+
+        let body = &model.paragraphs[1].runs[0];
+        assert_eq!(body.text, "Sales grew");
+        assert!(body.format.italic);
+        assert!(!body.format.bold);
+        assert_eq!(body.format.font.as_deref(), Some("Arial"));
+
+        assert!(model.paragraphs[2].runs.is_empty());
+    }
+
+    #[test]
+    fn test_apply_structure() {
+        let mut doc = WordProvider::from_bytes(&create_formatted_docx()).unwrap();
+        let mut model = doc.structure().unwrap();
+
+        // Unchanged models leave the document alone
+        assert_eq!(doc.apply_structure(&model).unwrap(), 0);
+        assert!(!doc.is_modified());
+
+        model.paragraphs[0].runs[1].text = " Summary ".to_string();
+        model.paragraphs[1].runs[0].text = "Revenue rose".to_string();
+        assert_eq!(doc.apply_structure(&model).unwrap(), 2);
+        assert!(doc.is_modified());
+
+        let reread = doc.structure().unwrap();
+        assert_eq!(reread.paragraphs[0].text(), "Quarterly Summary ");
+        assert_eq!(reread.paragraphs[1].runs[0].text, "Revenue rose");
+        // Formatting is kept
+        assert_eq!(
+            reread.paragraphs[1].runs[0].format,
+            model.paragraphs[1].runs[0].format
+        );
+
+        let temp_file = NamedTempFile::with_suffix(".docx").unwrap();
+        doc.save_as(temp_file.path()).unwrap();
+        let saved = WordProvider::open(temp_file.path()).unwrap();
+        assert_eq!(saved.get_text().unwrap().matches("Revenue rose").count(), 1);
+
+        // Adding or removing runs is rejected
+        model.paragraphs[1].runs.pop();
+        assert!(doc.apply_structure(&model).is_err());
+    }
 }
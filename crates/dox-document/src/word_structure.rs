@@ -0,0 +1,354 @@
+//! Structured paragraph and run model of a Word document body
+//!
+//! [`WordDocumentModel`] is read from `word/document.xml` by walking its
+//! paragraphs (`w:p`) and runs (`w:r`). Run text can be edited and written
+//! back with [`crate::WordProvider::apply_structure`]; everything else in the
+//! XML, including run formatting, is left as it was.
+
+use crate::provider::DocumentError;
+use quick_xml::events::{BytesStart, BytesText, Event};
+use quick_xml::{Reader, Writer};
+use serde::{Deserialize, Serialize};
+
+/// Paragraphs of a Word document in reading order
+///
+/// Paragraphs nested in text boxes or table cells are listed in the order
+/// they start.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WordDocumentModel {
+    pub paragraphs: Vec<WordParagraph>,
+}
+
+impl WordDocumentModel {
+    /// Text of all paragraphs, one per line
+    pub fn text(&self) -> String {
+        self.paragraphs
+            .iter()
+            .map(WordParagraph::text)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// A paragraph (`w:p`) and its runs
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WordParagraph {
+    /// Paragraph style id, e.g. `Heading1`
+    pub style: Option<String>,
+    /// Justification (`left`, `center`, `right`, `both`)
+    pub alignment: Option<String>,
+    pub runs: Vec<WordRun>,
+}
+
+impl WordParagraph {
+    /// Text of the paragraph's runs joined together
+    pub fn text(&self) -> String {
+        self.runs.iter().map(|run| run.text.as_str()).collect()
+    }
+}
+
+/// A run (`w:r`): text sharing one set of formatting
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WordRun {
+    /// Text of the run's `w:t` elements
+    pub text: String,
+    pub format: RunFormat,
+}
+
+/// Direct formatting of a run from its `w:rPr`
+///
+/// Formatting inherited from styles is not resolved.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RunFormat {
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+    pub strike: bool,
+    /// Font size in points
+    pub font_size: Option<f32>,
+    /// Hex color such as `FF0000`
+    pub color: Option<String>,
+    pub font: Option<String>,
+}
+
+const PARAGRAPH_TAG: &[u8] = b"w:p";
+const RUN_TAG: &[u8] = b"w:r";
+const TEXT_TAG: &[u8] = b"w:t";
+const TABLE_TAG: &[u8] = b"w:tbl";
+
+/// Value of the `w:val` attribute
+fn val(e: &BytesStart) -> Option<String> {
+    attribute(e, b"w:val")
+}
+
+fn attribute(e: &BytesStart, key: &[u8]) -> Option<String> {
+    e.attributes()
+        .flatten()
+        .find(|attr| attr.key.as_ref() == key)
+        .and_then(|attr| attr.unescape_value().ok())
+        .map(|value| value.into_owned())
+}
+
+/// Toggle properties like `<w:b/>` are on unless `w:val` turns them off
+fn toggle(e: &BytesStart) -> bool {
+    !matches!(val(e).as_deref(), Some("0" | "false" | "none"))
+}
+
+/// Read the paragraph and run model from `word/document.xml`
+pub(crate) fn read_structure(xml_content: &[u8]) -> Result<WordDocumentModel, DocumentError> {
+    let mut reader = Reader::from_reader(xml_content);
+    let mut buf = Vec::new();
+    let mut model = WordDocumentModel::default();
+    // Indices of the open paragraphs, innermost last
+    let mut open: Vec<usize> = Vec::new();
+    let mut in_run = false;
+    let mut in_run_props = false;
+    let mut in_paragraph_props = false;
+    let mut in_text = false;
+
+    loop {
+        let event = reader
+            .read_event_into(&mut buf)
+            .map_err(DocumentError::Xml)?;
+        let empty = matches!(event, Event::Empty(_));
+
+        match &event {
+            Event::Eof => break,
+            Event::Start(e) | Event::Empty(e) => match e.name().as_ref() {
+                PARAGRAPH_TAG => {
+                    model.paragraphs.push(WordParagraph::default());
+                    if !empty {
+                        open.push(model.paragraphs.len() - 1);
+                    }
+                }
+                RUN_TAG => {
+                    if let Some(&index) = open.last() {
+                        model.paragraphs[index].runs.push(WordRun::default());
+                        in_run = !empty;
+                    }
+                }
+                b"w:pPr" if !in_run => in_paragraph_props = !empty,
+                b"w:rPr" if in_run => in_run_props = !empty,
+                TEXT_TAG if in_run => in_text = !empty,
+                name => {
+                    let Some(&index) = open.last() else {
+                        buf.clear();
+                        continue;
+                    };
+                    let paragraph = &mut model.paragraphs[index];
+                    if in_run_props {
+                        let format = &mut paragraph.runs.last_mut().unwrap().format;
+                        match name {
+                            b"w:b" => format.bold = toggle(e),
+                            b"w:i" => format.italic = toggle(e),
+                            b"w:u" => format.underline = toggle(e),
+                            b"w:strike" => format.strike = toggle(e),
+                            b"w:sz" => {
+                                // Stored in half-points
+                                format.font_size = val(e)
+                                    .and_then(|v| v.parse::<f32>().ok())
+                                    .map(|half| half / 2.0);
+                            }
+                            b"w:color" => format.color = val(e),
+                            b"w:rFonts" => format.font = attribute(e, b"w:ascii"),
+                            _ => {}
+                        }
+                    } else if in_paragraph_props {
+                        match name {
+                            b"w:pStyle" => paragraph.style = val(e),
+                            b"w:jc" => paragraph.alignment = val(e),
+                            _ => {}
+                        }
+                    }
+                }
+            },
+            Event::Text(e) if in_text => {
+                if let Some(&index) = open.last() {
+                    let text = e.unescape().map_err(DocumentError::Xml)?;
+                    let run = model.paragraphs[index].runs.last_mut().unwrap();
+                    run.text.push_str(&text);
+                }
+            }
+            Event::End(e) => match e.name().as_ref() {
+                PARAGRAPH_TAG => {
+                    open.pop();
+                }
+                RUN_TAG => in_run = false,
+                b"w:pPr" => in_paragraph_props = false,
+                b"w:rPr" => in_run_props = false,
+                TEXT_TAG => in_text = false,
+                _ => {}
+            },
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(model)
+}
+
+/// Count the non-empty paragraphs outside tables and the tables of `word/document.xml`
+///
+/// Paragraphs in table cells belong to their table, and nested tables are
+/// counted with the table holding them. Returns `(paragraphs, tables)`.
+pub(crate) fn count_blocks(xml_content: &[u8]) -> Result<(usize, usize), DocumentError> {
+    let mut reader = Reader::from_reader(xml_content);
+    let mut buf = Vec::new();
+    let (mut paragraphs, mut tables) = (0, 0);
+    let mut table_depth = 0;
+    // Whether each open paragraph outside tables has text yet, innermost last
+    let mut open: Vec<bool> = Vec::new();
+    let mut in_text = false;
+
+    loop {
+        match reader
+            .read_event_into(&mut buf)
+            .map_err(DocumentError::Xml)?
+        {
+            Event::Eof => break,
+            Event::Start(e) => match e.name().as_ref() {
+                TABLE_TAG => {
+                    tables += usize::from(table_depth == 0);
+                    table_depth += 1;
+                }
+                PARAGRAPH_TAG if table_depth == 0 => open.push(false),
+                TEXT_TAG => in_text = true,
+                _ => {}
+            },
+            Event::Text(e) if in_text => {
+                if let Some(has_text) = open.last_mut() {
+                    let text = e.unescape().map_err(DocumentError::Xml)?;
+                    *has_text |= !text.trim().is_empty();
+                }
+            }
+            Event::End(e) => match e.name().as_ref() {
+                TABLE_TAG => table_depth -= 1,
+                PARAGRAPH_TAG if table_depth == 0 => {
+                    paragraphs += usize::from(open.pop() == Some(true));
+                }
+                TEXT_TAG => in_text = false,
+                _ => {}
+            },
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok((paragraphs, tables))
+}
+
+/// Write the run text of `model` back into `word/document.xml`
+///
+/// The model must have the document's paragraph and run layout. Changed runs
+/// get their new text in their first `w:t`; later `w:t` elements of the run
+/// are emptied. Returns the new XML and the number of runs changed.
+pub(crate) fn write_structure(
+    xml_content: &[u8],
+    model: &WordDocumentModel,
+) -> Result<(Vec<u8>, usize), DocumentError> {
+    let current = read_structure(xml_content)?;
+    let same_layout = current.paragraphs.len() == model.paragraphs.len()
+        && current
+            .paragraphs
+            .iter()
+            .zip(&model.paragraphs)
+            .all(|(a, b)| a.runs.len() == b.runs.len());
+    if !same_layout {
+        return Err(DocumentError::OperationFailed {
+            reason: "Paragraph and run layout does not match the document".to_string(),
+        });
+    }
+
+    // New text for each changed run, by (paragraph, run) position
+    let changed = |paragraph: usize, run: usize| -> Option<&str> {
+        let new = &model.paragraphs[paragraph].runs[run].text;
+        (*new != current.paragraphs[paragraph].runs[run].text).then_some(new.as_str())
+    };
+
+    let mut reader = Reader::from_reader(xml_content);
+    let mut output = Vec::new();
+    let mut writer = Writer::new(std::io::Cursor::new(&mut output));
+    let mut buf = Vec::new();
+    let mut paragraph_count = 0;
+    // Open paragraphs as (index, runs seen so far), innermost last
+    let mut open: Vec<(usize, usize)> = Vec::new();
+    // Replacement text of the current run, taken by its first w:t
+    let mut pending: Option<&str> = None;
+    let mut rewriting = false;
+    let mut in_text = false;
+    let mut changed_runs = 0;
+
+    loop {
+        let event = reader
+            .read_event_into(&mut buf)
+            .map_err(DocumentError::Xml)?;
+
+        match event {
+            Event::Eof => break,
+            Event::Start(ref e) if e.name().as_ref() == PARAGRAPH_TAG => {
+                open.push((paragraph_count, 0));
+                paragraph_count += 1;
+            }
+            Event::Empty(ref e) if e.name().as_ref() == PARAGRAPH_TAG => paragraph_count += 1,
+            Event::End(ref e) if e.name().as_ref() == PARAGRAPH_TAG => {
+                open.pop();
+            }
+            Event::Start(ref e) | Event::Empty(ref e) if e.name().as_ref() == RUN_TAG => {
+                if let Some((paragraph, runs)) = open.last_mut() {
+                    pending = changed(*paragraph, *runs);
+                    rewriting = pending.is_some();
+                    changed_runs += usize::from(rewriting);
+                    *runs += 1;
+                }
+                if matches!(event, Event::Empty(_)) {
+                    check_written(pending.take())?;
+                    rewriting = false;
+                }
+            }
+            Event::End(ref e) if e.name().as_ref() == RUN_TAG => {
+                check_written(pending.take())?;
+                rewriting = false;
+            }
+            Event::Start(e) if rewriting && e.name().as_ref() == TEXT_TAG => {
+                let mut start = e.into_owned();
+                in_text = true;
+                let text = pending.take().unwrap_or_default();
+                // Word drops leading and trailing spaces unless told to keep them
+                let has_space = start
+                    .attributes()
+                    .flatten()
+                    .any(|attr| attr.key.as_ref() == b"xml:space");
+                if !has_space && text.trim() != text {
+                    start.push_attribute(("xml:space", "preserve"));
+                }
+                writer.write_event(Event::Start(start))?;
+                if !text.is_empty() {
+                    writer.write_event(Event::Text(BytesText::new(text)))?;
+                }
+                buf.clear();
+                continue;
+            }
+            Event::Text(_) if rewriting && in_text => {
+                buf.clear();
+                continue;
+            }
+            Event::End(ref e) if e.name().as_ref() == TEXT_TAG => in_text = false,
+            _ => {}
+        }
+
+        writer.write_event(event)?;
+        buf.clear();
+    }
+
+    Ok((output, changed_runs))
+}
+
+/// Fail if a changed run had no `w:t` to take its new text
+fn check_written(pending: Option<&str>) -> Result<(), DocumentError> {
+    match pending {
+        Some(text) if !text.is_empty() => Err(DocumentError::OperationFailed {
+            reason: format!("Run has no text element to hold '{}'", text),
+        }),
+        _ => Ok(()),
+    }
+}
@@ -1,11 +1,36 @@
 //! Integration tests for dox-document crate
 
 use dox_document::{
-    create_provider, DocumentProvider, DocumentType, PowerPointProvider, WordProvider,
+    create_provider, create_provider_from_bytes, DocumentProvider, DocumentType, ExcelProvider,
+    ExtractorFactory, PowerPointProvider, ReplaceOptions, Replacer, Rule, WordProvider,
 };
 use std::io::Write;
 use tempfile::NamedTempFile;
 
+/// Add a `[Content_Types].xml` declaring the package's main part
+fn write_content_types<W: Write + std::io::Seek>(
+    writer: &mut zip::ZipWriter<W>,
+    part_name: &str,
+    content_type: &str,
+) {
+    writer
+        .start_file(
+            "[Content_Types].xml",
+            zip::write::SimpleFileOptions::default(),
+        )
+        .unwrap();
+    write!(
+        writer,
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">
+    <Default Extension="xml" ContentType="application/xml"/>
+    <Override PartName="{}" ContentType="application/vnd.openxmlformats-officedocument.{}+xml"/>
+</Types>"#,
+        part_name, content_type
+    )
+    .unwrap();
+}
+
 fn create_test_docx() -> Vec<u8> {
     let mut zip_data = Vec::new();
     {
@@ -40,6 +65,12 @@ fn create_test_docx() -> Vec<u8> {
             .unwrap();
         writer.write_all(b"<Relationships/>").unwrap();
 
+        write_content_types(
+            &mut writer,
+            "/word/document.xml",
+            "wordprocessingml.document.main",
+        );
+
         writer.finish().unwrap();
     }
     zip_data
@@ -99,6 +130,86 @@ fn create_test_pptx() -> Vec<u8> {
             .unwrap();
         writer.write_all(slide2_xml.as_bytes()).unwrap();
 
+        write_content_types(
+            &mut writer,
+            "/ppt/presentation.xml",
+            "presentationml.presentation.main",
+        );
+
+        writer.finish().unwrap();
+    }
+    zip_data
+}
+
+fn create_test_xlsx() -> Vec<u8> {
+    let mut zip_data = Vec::new();
+    {
+        use zip::{write::SimpleFileOptions, ZipWriter};
+        let mut writer = ZipWriter::new(std::io::Cursor::new(&mut zip_data));
+
+        let parts = [
+            (
+                "[Content_Types].xml",
+                r#"<?xml version="1.0" encoding="UTF-8"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">
+    <Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>
+    <Default Extension="xml" ContentType="application/xml"/>
+    <Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/>
+    <Override PartName="/xl/worksheets/sheet1.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/>
+    <Override PartName="/xl/sharedStrings.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sharedStrings+xml"/>
+</Types>"#,
+            ),
+            (
+                "_rels/.rels",
+                r#"<?xml version="1.0" encoding="UTF-8"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+    <Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/>
+</Relationships>"#,
+            ),
+            (
+                "xl/workbook.xml",
+                r#"<?xml version="1.0" encoding="UTF-8"?>
+<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships">
+    <sheets><sheet name="Sheet1" sheetId="1" r:id="rId1"/></sheets>
+</workbook>"#,
+            ),
+            (
+                "xl/_rels/workbook.xml.rels",
+                r#"<?xml version="1.0" encoding="UTF-8"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+    <Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/>
+    <Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/sharedStrings" Target="sharedStrings.xml"/>
+</Relationships>"#,
+            ),
+            (
+                "xl/sharedStrings.xml",
+                r#"<?xml version="1.0" encoding="UTF-8"?>
+<sst xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" count="1" uniqueCount="1">
+    <si><t>Customer: {{NAME}}</t></si>
+</sst>"#,
+            ),
+            (
+                "xl/worksheets/sheet1.xml",
+                r#"<?xml version="1.0" encoding="UTF-8"?>
+<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">
+    <sheetData>
+        <row r="1">
+            <c r="A1" t="s"><v>0</v></c>
+            <c r="B1" t="inlineStr"><is><t>Owner {{NAME}}</t></is></c>
+            <c r="C1"><v>42</v></c>
+        </row>
+    </sheetData>
+</worksheet>"#,
+            ),
+        ];
+
+        for (name, content) in parts {
+            writer
+                .start_file(name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+
         writer.finish().unwrap();
     }
     zip_data
@@ -127,6 +238,416 @@ fn test_document_factory() {
     assert!(text.contains("{{TITLE}}"));
 }
 
+#[test]
+fn test_detect_document_type_from_content() {
+    let cases = [
+        (create_test_docx(), ".dat", DocumentType::Word),
+        (create_test_pptx(), "", DocumentType::PowerPoint),
+        (create_test_xlsx(), ".zip", DocumentType::Excel),
+        // A Word document misnamed as a presentation
+        (create_test_docx(), ".pptx", DocumentType::Word),
+        (
+            b"%PDF-1.4\n%%EOF\n".to_vec(),
+            ".download",
+            DocumentType::Pdf,
+        ),
+    ];
+
+    for (data, suffix, expected) in cases {
+        let temp_file = NamedTempFile::with_suffix(suffix).unwrap();
+        std::fs::write(temp_file.path(), &data).unwrap();
+
+        assert_eq!(
+            DocumentType::detect(temp_file.path()),
+            Some(expected.clone()),
+            "detecting {:?} saved with suffix {:?}",
+            expected,
+            suffix
+        );
+        let extractor = ExtractorFactory::create_extractor(temp_file.path()).unwrap();
+        assert_eq!(extractor.supported_types(), &[expected]);
+    }
+}
+
+#[test]
+fn test_detect_falls_back_to_extension() {
+    let text_file = NamedTempFile::with_suffix(".txt").unwrap();
+    std::fs::write(text_file.path(), "plain text").unwrap();
+    assert_eq!(
+        DocumentType::detect(text_file.path()),
+        Some(DocumentType::Text)
+    );
+
+    // A ZIP archive that is not an Office package is judged by its extension
+    let docx_without_types = NamedTempFile::with_suffix(".docx").unwrap();
+    let mut zip_data = Vec::new();
+    {
+        let mut writer = zip::ZipWriter::new(std::io::Cursor::new(&mut zip_data));
+        writer
+            .start_file(
+                "word/document.xml",
+                zip::write::SimpleFileOptions::default(),
+            )
+            .unwrap();
+        writer.write_all(b"<w:document/>").unwrap();
+        writer.finish().unwrap();
+    }
+    std::fs::write(docx_without_types.path(), &zip_data).unwrap();
+    assert_eq!(
+        DocumentType::detect(docx_without_types.path()),
+        Some(DocumentType::Word)
+    );
+
+    let unknown = NamedTempFile::with_suffix(".bin").unwrap();
+    std::fs::write(unknown.path(), &zip_data).unwrap();
+    assert_eq!(DocumentType::detect(unknown.path()), None);
+}
+
+#[test]
+fn test_misnamed_documents_open_by_content() {
+    let temp_docx = NamedTempFile::with_suffix(".dat").unwrap();
+    std::fs::write(temp_docx.path(), create_test_docx()).unwrap();
+    let doc = create_provider(temp_docx.path()).unwrap();
+    assert_eq!(doc.document_type(), DocumentType::Word);
+    assert!(doc.get_text().unwrap().contains("{{NAME}}"));
+
+    let temp_pptx = NamedTempFile::new().unwrap();
+    std::fs::write(temp_pptx.path(), create_test_pptx()).unwrap();
+    let result = ExtractorFactory::create_extractor(temp_pptx.path())
+        .unwrap()
+        .extract(temp_pptx.path())
+        .unwrap();
+    assert!(result.pages[0].text.contains("{{TITLE}}"));
+}
+
+#[test]
+fn test_extract_pptx_slides_with_notes() {
+    let slide = |title: &str, body: &str| {
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<p:sld xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"
+       xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main">
+    <p:cSld><p:spTree>
+        <p:sp><p:nvSpPr><p:nvPr><p:ph type="title"/></p:nvPr></p:nvSpPr>
+            <p:txBody><a:p><a:r><a:t>{}</a:t></a:r></a:p></p:txBody></p:sp>
+        <p:sp><p:txBody><a:p><a:r><a:t>{}</a:t></a:r></a:p></p:txBody></p:sp>
+    </p:spTree></p:cSld>
+</p:sld>"#,
+            title, body
+        )
+    };
+    let notes = |text: &str| {
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<p:notes xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"
+         xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main">
+    <p:cSld><p:spTree>
+        <p:sp><p:nvSpPr><p:nvPr><p:ph type="body" idx="1"/></p:nvPr></p:nvSpPr>
+            <p:txBody><a:p><a:r><a:t>{}</a:t></a:r></a:p></p:txBody></p:sp>
+    </p:spTree></p:cSld>
+</p:notes>"#,
+            text
+        )
+    };
+    let rels = |target: &str| {
+        format!(
+            r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+    <Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/notesSlide" Target="{}"/>
+</Relationships>"#,
+            target
+        )
+    };
+
+    let mut pptx_data = Vec::new();
+    {
+        use zip::{write::SimpleFileOptions, ZipWriter};
+        let mut writer = ZipWriter::new(std::io::Cursor::new(&mut pptx_data));
+        let parts = [
+            ("ppt/slides/slide1.xml", slide("분기 실적", "매출 12% 증가")),
+            ("ppt/slides/slide2.xml", slide("다음 단계", "채용 확대")),
+            (
+                "ppt/slides/_rels/slide1.xml.rels",
+                rels("../notesSlides/notesSlide1.xml"),
+            ),
+            (
+                "ppt/slides/_rels/slide2.xml.rels",
+                rels("../notesSlides/notesSlide2.xml"),
+            ),
+            (
+                "ppt/notesSlides/notesSlide1.xml",
+                notes("전년 대비 수치 강조"),
+            ),
+            ("ppt/notesSlides/notesSlide2.xml", notes("질문 받기")),
+        ];
+        for (name, content) in parts {
+            writer
+                .start_file(name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        write_content_types(
+            &mut writer,
+            "/ppt/presentation.xml",
+            "presentationml.presentation.main",
+        );
+        writer.finish().unwrap();
+    }
+
+    let temp_pptx = NamedTempFile::with_suffix(".pptx").unwrap();
+    std::fs::write(temp_pptx.path(), &pptx_data).unwrap();
+    let result = ExtractorFactory::create_extractor(temp_pptx.path())
+        .unwrap()
+        .extract(temp_pptx.path())
+        .unwrap();
+
+    let elements = |page: usize| -> Vec<(String, String)> {
+        result.pages[page]
+            .elements
+            .iter()
+            .map(|e| (e.element_type.clone(), e.content.clone()))
+            .collect()
+    };
+    let pair = |t: &str, c: &str| (t.to_string(), c.to_string());
+
+    assert_eq!(result.pages.len(), 2);
+    assert_eq!(
+        elements(0),
+        vec![
+            pair("heading", "분기 실적"),
+            pair("paragraph", "매출 12% 증가"),
+            pair("speaker_note", "전년 대비 수치 강조"),
+        ]
+    );
+    assert_eq!(
+        elements(1),
+        vec![
+            pair("heading", "다음 단계"),
+            pair("paragraph", "채용 확대"),
+            pair("speaker_note", "질문 받기"),
+        ]
+    );
+    // Notes are kept out of the slide text
+    assert_eq!(result.pages[1].number, 2);
+    assert_eq!(result.pages[1].text, "다음 단계\n채용 확대");
+}
+
+#[test]
+fn test_extract_docx_hyperlinks() {
+    let doc_xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main"
+            xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships">
+    <w:body>
+        <w:p>
+            <w:r><w:t xml:space="preserve">자세한 내용은 </w:t></w:r>
+            <w:hyperlink r:id="rId7">
+                <w:r><w:t>공식 </w:t></w:r>
+                <w:r><w:t>문서</w:t></w:r>
+            </w:hyperlink>
+            <w:r><w:t>를 참고하세요.</w:t></w:r>
+        </w:p>
+        <w:p>
+            <w:hyperlink w:anchor="appendix"><w:r><w:t>부록</w:t></w:r></w:hyperlink>
+        </w:p>
+    </w:body>
+</w:document>"#;
+    let rels = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+    <Relationship Id="rId7" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/hyperlink" Target="https://docs.example.com/guide?a=1&amp;b=2" TargetMode="External"/>
+</Relationships>"#;
+
+    let mut docx_data = Vec::new();
+    {
+        use zip::{write::SimpleFileOptions, ZipWriter};
+        let mut writer = ZipWriter::new(std::io::Cursor::new(&mut docx_data));
+        for (name, content) in [
+            ("word/document.xml", doc_xml),
+            ("word/_rels/document.xml.rels", rels),
+        ] {
+            writer
+                .start_file(name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        write_content_types(
+            &mut writer,
+            "/word/document.xml",
+            "wordprocessingml.document.main",
+        );
+        writer.finish().unwrap();
+    }
+
+    let temp_docx = NamedTempFile::with_suffix(".docx").unwrap();
+    std::fs::write(temp_docx.path(), &docx_data).unwrap();
+    let result = ExtractorFactory::create_extractor(temp_docx.path())
+        .unwrap()
+        .extract(temp_docx.path())
+        .unwrap();
+
+    let links: Vec<(&str, &str)> = result.pages[0]
+        .links
+        .iter()
+        .map(|link| (link.text.as_str(), link.url.as_str()))
+        .collect();
+    assert_eq!(
+        links,
+        vec![
+            ("공식 문서", "https://docs.example.com/guide?a=1&b=2"),
+            ("부록", "#appendix"),
+        ]
+    );
+}
+
+#[test]
+fn test_extract_pptx_without_slides() {
+    let mut pptx_data = Vec::new();
+    {
+        let mut writer = zip::ZipWriter::new(std::io::Cursor::new(&mut pptx_data));
+        write_content_types(
+            &mut writer,
+            "/ppt/presentation.xml",
+            "presentationml.presentation.main",
+        );
+        writer.finish().unwrap();
+    }
+
+    let temp_pptx = NamedTempFile::with_suffix(".pptx").unwrap();
+    std::fs::write(temp_pptx.path(), &pptx_data).unwrap();
+    let result = ExtractorFactory::create_extractor(temp_pptx.path())
+        .unwrap()
+        .extract(temp_pptx.path())
+        .unwrap();
+
+    assert_eq!(result.pages.len(), 1);
+    assert!(result.pages[0].elements.is_empty());
+}
+
+/// Rebuild a package with document properties added and some parts removed
+fn with_properties(package: &[u8], title: &str, remove: &[&str]) -> Vec<u8> {
+    let mut archive = dox_document::extract_zip(package).unwrap();
+    let mut files = Vec::new();
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i).unwrap();
+        let name = file.name().to_string();
+        if remove.contains(&name.as_str()) {
+            continue;
+        }
+        let mut content = Vec::new();
+        std::io::Read::read_to_end(&mut file, &mut content).unwrap();
+        files.push((name, content));
+    }
+
+    let core = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<cp:coreProperties xmlns:cp="http://schemas.openxmlformats.org/package/2006/metadata/core-properties" xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:dcterms="http://purl.org/dc/terms/">
+    <dc:title>{}</dc:title>
+    <dc:creator>Kim</dc:creator>
+    <dc:subject>Quarterly numbers</dc:subject>
+    <dcterms:created>2025-01-02T03:04:05Z</dcterms:created>
+    <dcterms:modified>2025-02-03T04:05:06Z</dcterms:modified>
+</cp:coreProperties>"#,
+        title
+    );
+    files.push(("docProps/core.xml".to_string(), core.into_bytes()));
+    files.push((
+        "docProps/app.xml".to_string(),
+        b"<Properties><Application>Microsoft Office Word</Application><Pages>3</Pages></Properties>"
+            .to_vec(),
+    ));
+
+    let mut output = std::io::Cursor::new(Vec::new());
+    dox_document::create_zip(&mut output, files.into_iter()).unwrap();
+    output.into_inner()
+}
+
+#[test]
+fn test_metadata_only_matches_full_extraction() {
+    let cases = [
+        (with_properties(&create_test_docx(), "Report", &[]), ".docx"),
+        (with_properties(&create_test_pptx(), "Deck", &[]), ".pptx"),
+        (create_test_xlsx(), ".xlsx"),
+    ];
+
+    for (data, suffix) in cases {
+        let temp_file = NamedTempFile::with_suffix(suffix).unwrap();
+        std::fs::write(temp_file.path(), &data).unwrap();
+        let extractor = ExtractorFactory::create_extractor(temp_file.path()).unwrap();
+
+        let full = extractor.extract(temp_file.path()).unwrap();
+        let metadata = extractor.extract_metadata_only(temp_file.path()).unwrap();
+        // Nothing is declared, and detecting the language needs the body
+        assert_eq!(metadata.language, None);
+        let full_metadata = dox_document::ExtractMetadata {
+            language: None,
+            ..full.metadata
+        };
+        assert_eq!(metadata, full_metadata, "metadata for {}", suffix);
+    }
+}
+
+#[test]
+fn test_extract_from_bytes_matches_path() {
+    let cases = [
+        (
+            with_properties(&create_test_docx(), "Report", &[]),
+            DocumentType::Word,
+        ),
+        (create_test_pptx(), DocumentType::PowerPoint),
+        (create_test_xlsx(), DocumentType::Excel),
+    ];
+
+    for (data, doc_type) in cases {
+        let temp_file = NamedTempFile::with_suffix(".bin").unwrap();
+        std::fs::write(temp_file.path(), &data).unwrap();
+        let from_path = ExtractorFactory::create_extractor(temp_file.path())
+            .unwrap()
+            .extract(temp_file.path())
+            .unwrap();
+
+        let from_bytes = ExtractorFactory::extract_from_bytes(&data, doc_type.clone()).unwrap();
+        assert!(from_bytes.filename.is_empty());
+        let mut expected = serde_json::to_value(&from_path).unwrap();
+        expected["filename"] = "".into();
+        if doc_type == DocumentType::Excel {
+            // Workbook titles fall back to the file name, which bytes lack
+            expected["metadata"]["title"] = serde_json::Value::Null;
+        }
+        assert_eq!(
+            serde_json::to_value(&from_bytes).unwrap(),
+            expected,
+            "{:?}",
+            doc_type
+        );
+
+        let provider = create_provider_from_bytes(&data, doc_type).unwrap();
+        assert_eq!(
+            provider.get_text().unwrap(),
+            create_provider(temp_file.path())
+                .unwrap()
+                .get_text()
+                .unwrap()
+        );
+    }
+
+    assert!(create_provider_from_bytes(b"%PDF-1.4", DocumentType::Pdf).is_err());
+}
+
+#[test]
+fn test_metadata_only_skips_document_body() {
+    // Without its body the document cannot be extracted, but its metadata can
+    let data = with_properties(&create_test_docx(), "Report", &["word/document.xml"]);
+    let temp_file = NamedTempFile::with_suffix(".docx").unwrap();
+    std::fs::write(temp_file.path(), &data).unwrap();
+    let extractor = ExtractorFactory::create_extractor(temp_file.path()).unwrap();
+
+    assert!(extractor.extract(temp_file.path()).is_err());
+    let metadata = extractor.extract_metadata_only(temp_file.path()).unwrap();
+    assert_eq!(metadata.title.as_deref(), Some("Report"));
+    assert_eq!(metadata.author.as_deref(), Some("Kim"));
+    assert_eq!(metadata.created.as_deref(), Some("2025-01-02T03:04:05Z"));
+    assert_eq!(metadata.creator.as_deref(), Some("Microsoft Office Word"));
+    assert_eq!(metadata.total_pages, 3);
+}
+
 #[test]
 fn test_word_document_operations() {
     let docx_data = create_test_docx();
@@ -239,3 +760,370 @@ fn test_error_handling() {
         DocumentError::UnsupportedFormat { .. }
     ));
 }
+
+#[test]
+fn test_damaged_and_encrypted_documents() {
+    use dox_document::DocumentError;
+
+    let result = WordProvider::from_bytes(b"not a zip archive");
+    assert!(matches!(
+        result.unwrap_err(),
+        DocumentError::CorruptArchive { .. }
+    ));
+
+    // A download that stopped halfway
+    let docx = create_test_docx();
+    let temp_file = NamedTempFile::with_suffix(".docx").unwrap();
+    std::fs::write(temp_file.path(), &docx[..docx.len() / 2]).unwrap();
+    let result = WordProvider::open(temp_file.path());
+    assert!(matches!(
+        result.unwrap_err(),
+        DocumentError::CorruptArchive { .. }
+    ));
+
+    let result = ExcelProvider::from_bytes(b"PK\x03\x04 truncated");
+    assert!(matches!(
+        result.unwrap_err(),
+        DocumentError::CorruptArchive { .. }
+    ));
+
+    // Password-protected Office files are OLE compound files, not zips
+    let mut encrypted = vec![0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1];
+    encrypted.resize(512, 0);
+    let result = PowerPointProvider::from_bytes(&encrypted);
+    assert!(matches!(
+        result.unwrap_err(),
+        DocumentError::EncryptionRequired
+    ));
+
+    // A valid zip that lacks the document body
+    let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    write_content_types(
+        &mut zip,
+        "/word/document.xml",
+        "wordprocessingml.document.main",
+    );
+    let package = zip.finish().unwrap().into_inner();
+    match WordProvider::from_bytes(&package).unwrap_err() {
+        DocumentError::MissingPart { part } => assert_eq!(part, "word/document.xml"),
+        other => panic!("expected a missing part, got {:?}", other),
+    }
+
+    // Parse errors name the part they come from
+    let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    zip.start_file(
+        "word/document.xml",
+        zip::write::SimpleFileOptions::default(),
+    )
+    .unwrap();
+    zip.write_all(b"<w:document><w:body></w:p></w:document>")
+        .unwrap();
+    let package = zip.finish().unwrap().into_inner();
+    let provider = WordProvider::from_bytes(&package).unwrap();
+    match provider.get_text().unwrap_err() {
+        DocumentError::XmlParseError { part, .. } => assert_eq!(part, "word/document.xml"),
+        other => panic!("expected an XML error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_excel_document_operations() {
+    let temp_file = NamedTempFile::with_suffix(".xlsx").unwrap();
+    std::fs::write(temp_file.path(), create_test_xlsx()).unwrap();
+
+    let mut doc = ExcelProvider::open(temp_file.path()).unwrap();
+    assert!(!doc.is_modified());
+
+    // Shared string and inline string cells are both replaced
+    let count = doc.replace_text("{{NAME}}", "Kim").unwrap();
+    assert_eq!(count, 2);
+    assert!(doc.is_modified());
+
+    let text = doc.get_text().unwrap();
+    assert!(text.contains("Customer: Kim"));
+    assert!(text.contains("Owner Kim"));
+    assert!(text.contains("42"));
+
+    doc.save().unwrap();
+    let reopened = ExcelProvider::open(temp_file.path()).unwrap();
+    let text = reopened.get_text().unwrap();
+    assert!(text.contains("Customer: Kim"));
+    assert!(!text.contains("{{NAME}}"));
+}
+
+#[test]
+fn test_replace_mixed_directory() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("letter.docx"), create_test_docx()).unwrap();
+    std::fs::write(dir.path().join("orders.xlsx"), create_test_xlsx()).unwrap();
+    std::fs::write(dir.path().join("scan.pdf"), b"%PDF-1.4\n%%EOF\n").unwrap();
+
+    for concurrent in [false, true] {
+        let options = ReplaceOptions {
+            concurrent,
+            dry_run: true,
+            ..Default::default()
+        };
+        let results = tokio_test::block_on(
+            Replacer::new(vec![Rule::new("{{NAME}}", "Kim")]).process_path(dir.path(), options),
+        )
+        .unwrap();
+
+        assert_eq!(results.files_processed, 2);
+        assert_eq!(results.total_replacements, 3);
+        assert_eq!(results.errors, 0);
+        assert_eq!(results.skipped, 1);
+        assert!(results.skipped_files[0].0.ends_with("scan.pdf"));
+        assert!(results.skipped_files[0].1.contains("PDF"));
+    }
+}
+
+#[test]
+fn test_concurrent_replace_matches_sequential() {
+    let sequential_dir = tempfile::tempdir().unwrap();
+    let concurrent_dir = tempfile::tempdir().unwrap();
+    for dir in [&sequential_dir, &concurrent_dir] {
+        for i in 0..24 {
+            std::fs::write(
+                dir.path().join(format!("doc{:02}.docx", i)),
+                create_test_docx(),
+            )
+            .unwrap();
+        }
+        std::fs::write(dir.path().join("broken.docx"), b"not a zip archive").unwrap();
+        std::fs::write(dir.path().join("scan.pdf"), b"%PDF-1.4\n%%EOF\n").unwrap();
+    }
+
+    let run = |dir: &std::path::Path, concurrent: bool| {
+        let options = ReplaceOptions {
+            concurrent,
+            max_workers: 4,
+            ..Default::default()
+        };
+        let rules = vec![Rule::new("{{NAME}}", "Kim"), Rule::new("{{VALUE}}", "42")];
+        tokio_test::block_on(Replacer::new(rules).process_path(dir, options)).unwrap()
+    };
+    let sequential = run(sequential_dir.path(), false);
+    let concurrent = run(concurrent_dir.path(), true);
+
+    for results in [&sequential, &concurrent] {
+        assert_eq!(results.files_processed, 24);
+        assert_eq!(results.total_replacements, 48);
+        assert_eq!(results.errors, 1);
+        assert_eq!(results.skipped, 1);
+    }
+
+    for i in 0..24 {
+        let name = format!("doc{:02}.docx", i);
+        let expected = std::fs::read(sequential_dir.path().join(&name)).unwrap();
+        let actual = std::fs::read(concurrent_dir.path().join(&name)).unwrap();
+        assert_eq!(expected, actual, "{} differs", name);
+    }
+}
+
+/// A Word document whose single run carries the given `w:lang` attributes
+fn docx_with_text(text: &str, lang: &str) -> Vec<u8> {
+    docx_with_body(&format!(
+        "<w:p><w:r><w:rPr>{}</w:rPr><w:t>{}</w:t></w:r></w:p>",
+        lang, text
+    ))
+}
+
+/// A Word document with `body` as the content of its `w:body`
+fn docx_with_body(body: &str) -> Vec<u8> {
+    use zip::{write::SimpleFileOptions, ZipWriter};
+
+    let mut writer = ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    let doc_xml = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">
+    <w:body>{}</w:body>
+</w:document>"#,
+        body
+    );
+    writer
+        .start_file("word/document.xml", SimpleFileOptions::default())
+        .unwrap();
+    writer.write_all(doc_xml.as_bytes()).unwrap();
+    write_content_types(
+        &mut writer,
+        "/word/document.xml",
+        "wordprocessingml.document.main",
+    );
+    writer.finish().unwrap().into_inner()
+}
+
+#[test]
+fn test_document_language() {
+    let language = |data: &[u8], doc_type| {
+        ExtractorFactory::extract_from_bytes(data, doc_type)
+            .unwrap()
+            .metadata
+            .language
+    };
+
+    // Detected from the text when nothing is declared
+    let korean = docx_with_text("분기별 매출 보고서입니다", "");
+    assert_eq!(language(&korean, DocumentType::Word).as_deref(), Some("ko"));
+    assert_eq!(
+        language(&create_test_docx(), DocumentType::Word).as_deref(),
+        Some("en")
+    );
+    assert_eq!(
+        language("회의록: 모두 동의함".as_bytes(), DocumentType::Text).as_deref(),
+        Some("ko")
+    );
+
+    // Word tags Korean runs through w:eastAsia, leaving w:val at en-US
+    let tagged = docx_with_text(
+        "분기별 매출 보고서입니다",
+        r#"<w:lang w:val="en-US" w:eastAsia="ko-KR"/>"#,
+    );
+    assert_eq!(language(&tagged, DocumentType::Word).as_deref(), Some("ko"));
+
+    // A declared language wins over the text
+    let german = docx_with_text("Quarterly report", r#"<w:lang w:val="de-DE"/>"#);
+    assert_eq!(language(&german, DocumentType::Word).as_deref(), Some("de"));
+}
+
+#[test]
+fn test_batch_extraction_collects_results_and_errors() {
+    use dox_document::{BatchExtractor, DocumentError};
+
+    let dir = tempfile::tempdir().unwrap();
+    let mut files = Vec::new();
+    for i in 0..30 {
+        let (ext, content) = match i % 4 {
+            0 => ("docx", create_test_docx()),
+            1 => ("pptx", create_test_pptx()),
+            2 => ("xlsx", create_test_xlsx()),
+            _ => ("txt", format!("note {}", i).into_bytes()),
+        };
+        let path = dir.path().join(format!("doc{:02}.{}", i, ext));
+        std::fs::write(&path, content).unwrap();
+        files.push(path);
+    }
+    // Broken files in the middle of the list must not stop the others
+    let broken = dir.path().join("broken.docx");
+    std::fs::write(&broken, b"not a zip archive").unwrap();
+    files.insert(7, broken.clone());
+    let missing = dir.path().join("missing.pptx");
+    files.insert(20, missing.clone());
+
+    let sequential: Vec<_> = files
+        .iter()
+        .filter_map(|file| {
+            dox_document::extract::extractors::UniversalExtractor::extract_from_path(file).ok()
+        })
+        .collect();
+
+    let batch = tokio_test::block_on(BatchExtractor::new(8).extract(files.clone()));
+
+    assert!(!batch.cancelled);
+    assert_eq!(batch.files_processed(), files.len());
+    assert_eq!(batch.results.len(), 30);
+
+    // Results come back in input order, matching a sequential run
+    let expected: Vec<_> = files
+        .iter()
+        .filter(|f| **f != broken && **f != missing)
+        .collect();
+    let actual: Vec<_> = batch.results.iter().map(|(path, _)| path).collect();
+    assert_eq!(actual, expected);
+    for ((_, result), expected) in batch.results.iter().zip(&sequential) {
+        assert_eq!(result.filename, expected.filename);
+        assert_eq!(result.stats.character_count, expected.stats.character_count);
+    }
+
+    let failed: Vec<_> = batch.errors.iter().map(|(path, _)| path).collect();
+    assert_eq!(failed, vec![&broken, &missing]);
+    assert!(matches!(
+        batch.errors[0].1,
+        DocumentError::CorruptArchive { .. }
+    ));
+}
+
+#[test]
+fn test_markdown_table_and_blockquote_survive_docx_round_trip() {
+    use dox_core::create::{
+        CreateOptions, CreateRequest, DocumentCreator, MarkdownParser, OutputFormat,
+        WordDocumentCreator,
+    };
+
+    let markdown = "# Inventory\n\n\
+        | Item | Qty |\n|------|-----|\n| Apples | 12 |\n| Pears | 7 |\n\n\
+        > Stock is counted weekly.\n>\n> Last count: Monday\n";
+
+    let output = NamedTempFile::with_suffix(".docx").unwrap();
+    let request = CreateRequest {
+        content: markdown.to_string(),
+        format: OutputFormat::Word,
+        template_path: None,
+        output_path: output.path().to_string_lossy().to_string(),
+        options: CreateOptions::default(),
+    };
+    let document = MarkdownParser::new(CreateOptions::default())
+        .parse(markdown)
+        .unwrap();
+    WordDocumentCreator::new()
+        .create_document(&document, &request)
+        .unwrap();
+
+    let extractor = ExtractorFactory::create_extractor(output.path()).unwrap();
+    let text = extractor.extract(output.path()).unwrap().pages[0]
+        .text
+        .clone();
+    for expected in [
+        "Item",
+        "Qty",
+        "Apples",
+        "12",
+        "Pears",
+        "7",
+        "Stock is counted weekly.",
+        "Last count: Monday",
+    ] {
+        assert!(
+            text.contains(expected),
+            "missing {:?} in {:?}",
+            expected,
+            text
+        );
+    }
+
+    let docx_data = std::fs::read(output.path()).unwrap();
+    let mut archive = dox_document::extract_zip(&docx_data).unwrap();
+    let document_xml = String::from_utf8(
+        dox_document::utils::read_zip_file(&mut archive, "word/document.xml").unwrap(),
+    )
+    .unwrap();
+    assert_eq!(document_xml.matches("<w:tbl>").count(), 1);
+    assert_eq!(document_xml.matches("<w:tr>").count(), 3);
+    assert!(document_xml.contains("<w:tblBorders>"));
+    assert_eq!(
+        document_xml.matches(r#"<w:pStyle w:val="Quote"/>"#).count(),
+        2
+    );
+}
+
+#[test]
+fn test_docx_stats_count_paragraphs_and_tables() {
+    let cell = |text: &str| format!("<w:tc><w:p><w:r><w:t>{}</w:t></w:r></w:p></w:tc>", text);
+    let body = format!(
+        "<w:p><w:pPr><w:pStyle w:val=\"Heading1\"/></w:pPr><w:r><w:t>Report</w:t></w:r></w:p>\
+         <w:p><w:r><w:t>Revenue grew.</w:t></w:r></w:p>\
+         <w:p/>\
+         <w:tbl><w:tr>{}{}</w:tr><w:tr>{}{}</w:tr></w:tbl>\
+         <w:p><w:r><w:t>Costs fell.</w:t></w:r></w:p>",
+        cell("Q1"),
+        cell("Q2"),
+        cell("10"),
+        cell("12")
+    );
+
+    let result =
+        ExtractorFactory::extract_from_bytes(&docx_with_body(&body), DocumentType::Word).unwrap();
+    // Empty paragraphs and table cells are not counted as paragraphs
+    assert_eq!(result.stats.paragraph_count, 3);
+    assert_eq!(result.stats.table_count, 1);
+}
@@ -0,0 +1,301 @@
+//! Formula text of worksheet cells
+//!
+//! calamine returns the cached result of a formula cell, so data that is read
+//! and written back would turn every formula into a constant. The formulas
+//! are read from the `<f>` elements of the worksheet part instead.
+
+use crate::package::{attr, read_part, worksheet_parts};
+use crate::validation::parse_cell;
+use anyhow::{anyhow, Result};
+use dox_core::{column_letter_to_index, index_to_column_letter, Cell, MAX_COLUMNS, MAX_ROWS};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use regex::Regex;
+use std::collections::HashMap;
+use std::io::{Read, Seek};
+use std::sync::OnceLock;
+use zip::ZipArchive;
+
+/// Formulas of a sheet by zero-based `(row, col)`, without the leading `=`
+pub(crate) type SheetFormulas = HashMap<(u32, u32), String>;
+
+/// Read the formulas of `sheet_name`
+pub(crate) fn sheet_formulas<R: Read + Seek>(
+    zip: &mut ZipArchive<R>,
+    sheet_name: &str,
+) -> Result<SheetFormulas> {
+    let part = worksheet_parts(zip)?
+        .into_iter()
+        .find(|(name, _)| name == sheet_name)
+        .map(|(_, part)| part)
+        .ok_or_else(|| anyhow!("Sheet '{}' not found", sheet_name))?;
+
+    match read_part(zip, &part)? {
+        Some(xml) => parse_formulas(&xml),
+        None => Ok(SheetFormulas::new()),
+    }
+}
+
+/// Replace the cells of `data` that hold a formula with `=` and its text
+///
+/// `data` starts at the zero-based sheet position `origin`. With `grow`, the
+/// data is extended down and right to formulas past its end, which happens
+/// when a formula has no cached result.
+pub(crate) fn place_formulas(
+    formulas: &SheetFormulas,
+    data: &mut Vec<Vec<Cell>>,
+    origin: (u32, u32),
+    grow: bool,
+) {
+    for (&(row, col), formula) in formulas {
+        if row < origin.0 || col < origin.1 {
+            continue;
+        }
+        let (row_idx, col_idx) = ((row - origin.0) as usize, (col - origin.1) as usize);
+        if grow {
+            if data.len() <= row_idx {
+                data.resize(row_idx + 1, Vec::new());
+            }
+            let cells = &mut data[row_idx];
+            if cells.len() <= col_idx {
+                cells.resize(col_idx + 1, Cell::new(""));
+            }
+        }
+        if let Some(cell) = data
+            .get_mut(row_idx)
+            .and_then(|cells| cells.get_mut(col_idx))
+        {
+            *cell = Cell::new(format!("={}", formula));
+        }
+    }
+
+    if grow {
+        // Keep the data rectangular
+        let width = data.iter().map(Vec::len).max().unwrap_or(0);
+        for cells in data.iter_mut() {
+            cells.resize(width, Cell::new(""));
+        }
+    }
+}
+
+/// Collect the formulas of a worksheet part
+///
+/// Cells of a shared formula only name the formula's index, so they get the
+/// text of the cell that defines it with relative references moved by the
+/// distance between the two cells.
+fn parse_formulas(xml: &str) -> Result<SheetFormulas> {
+    let mut formulas = SheetFormulas::new();
+    // Cell and text defining each shared formula, by its `si` index
+    let mut shared: HashMap<String, ((u32, u32), String)> = HashMap::new();
+    // Cells using a shared formula defined elsewhere
+    let mut shared_uses: Vec<((u32, u32), String)> = Vec::new();
+
+    // `r` is optional on rows and cells, in which case they follow the
+    // previous one
+    let mut next_row = 0;
+    let mut row = 0;
+    let mut next_col = 0;
+    let mut cell: Option<(u32, u32)> = None;
+    // Shared formula index and text of the `<f>` element being read
+    let mut formula: Option<(Option<String>, String)> = None;
+
+    let mut reader = Reader::from_str(xml);
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) | Event::Empty(e) if e.local_name().as_ref() == b"row" => {
+                row = attr(&e, "r")
+                    .and_then(|r| r.parse::<u32>().ok())
+                    .map_or(next_row, |r| r.saturating_sub(1));
+                next_row = row + 1;
+                next_col = 0;
+            }
+            Event::Start(e) if e.local_name().as_ref() == b"c" => {
+                let position = attr(&e, "r")
+                    .and_then(|r| parse_cell(&r))
+                    .map_or((row, next_col), |(row, col)| (row, col as u32));
+                next_col = position.1 + 1;
+                cell = Some(position);
+            }
+            Event::Empty(e) if e.local_name().as_ref() == b"c" => {
+                next_col = attr(&e, "r")
+                    .and_then(|r| parse_cell(&r))
+                    .map_or(next_col, |(_, col)| col as u32)
+                    + 1;
+            }
+            Event::Start(e) if e.local_name().as_ref() == b"f" && cell.is_some() => {
+                let index = (attr(&e, "t").as_deref() == Some("shared"))
+                    .then(|| attr(&e, "si"))
+                    .flatten();
+                formula = Some((index, String::new()));
+            }
+            Event::Empty(e) if e.local_name().as_ref() == b"f" => {
+                if let (Some(position), Some(index)) = (cell, attr(&e, "si")) {
+                    shared_uses.push((position, index));
+                }
+            }
+            Event::Text(t) => {
+                if let Some((_, text)) = formula.as_mut() {
+                    text.push_str(&t.unescape()?);
+                }
+            }
+            Event::End(e) => match e.local_name().as_ref() {
+                b"f" => {
+                    if let (Some(position), Some((index, text))) = (cell, formula.take()) {
+                        match index {
+                            Some(index) if text.is_empty() => shared_uses.push((position, index)),
+                            Some(index) => {
+                                shared.insert(index, (position, text.clone()));
+                                formulas.insert(position, text);
+                            }
+                            None if !text.is_empty() => {
+                                formulas.insert(position, text);
+                            }
+                            None => {}
+                        }
+                    }
+                }
+                b"c" => cell = None,
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    for (position, index) in shared_uses {
+        if let Some((origin, text)) = shared.get(&index) {
+            let rows = position.0.saturating_sub(origin.0);
+            let cols = position.1.saturating_sub(origin.1);
+            formulas.insert(position, shift_references(text, rows, cols));
+        }
+    }
+
+    Ok(formulas)
+}
+
+/// Move the relative references of `formula` down by `rows` and right by
+/// `cols`, as Excel does when copying it
+///
+/// `$`-anchored parts stay put, and string literals and quoted sheet names
+/// are left alone. A reference moved past the last row or column becomes
+/// `#REF!`.
+fn shift_references(formula: &str, rows: u32, cols: u32) -> String {
+    if rows == 0 && cols == 0 {
+        return formula.to_string();
+    }
+
+    let mut shifted = String::with_capacity(formula.len());
+    let mut start = 0;
+    let mut quote: Option<char> = None;
+    for (index, c) in formula.char_indices() {
+        match quote {
+            Some(open) if c == open => {
+                shifted.push_str(&formula[start..=index]);
+                start = index + 1;
+                quote = None;
+            }
+            Some(_) => {}
+            None if c == '"' || c == '\'' => {
+                shifted.push_str(&shift_unquoted(&formula[start..index], rows, cols));
+                start = index;
+                quote = Some(c);
+            }
+            None => {}
+        }
+    }
+    match quote {
+        Some(_) => shifted.push_str(&formula[start..]),
+        None => shifted.push_str(&shift_unquoted(&formula[start..], rows, cols)),
+    }
+    shifted
+}
+
+/// Shift the references in a part of a formula outside any quotes
+fn shift_unquoted(text: &str, rows: u32, cols: u32) -> String {
+    static REFERENCE: OnceLock<Regex> = OnceLock::new();
+    let reference = REFERENCE
+        .get_or_init(|| Regex::new(r"(\$?)([A-Za-z]{1,3})(\$?)([0-9]+)").expect("valid regex"));
+    let is_name_char = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '.';
+
+    let mut shifted = String::with_capacity(text.len());
+    let mut last = 0;
+    for captures in reference.captures_iter(text) {
+        let whole = captures.get(0).expect("whole match");
+        // Skip parts of longer names and function calls such as `LOG10(`
+        let before = text[..whole.start()].chars().next_back();
+        let after = text[whole.end()..].chars().next();
+        if before.is_some_and(is_name_char) || after.is_some_and(|c| is_name_char(c) || c == '(') {
+            continue;
+        }
+
+        let col_anchor = &captures[1];
+        let row_anchor = &captures[3];
+        let (Some(col), Ok(row)) = (
+            column_letter_to_index(&captures[2]),
+            captures[4].parse::<u32>(),
+        ) else {
+            continue;
+        };
+
+        let col = if col_anchor.is_empty() {
+            col + cols
+        } else {
+            col
+        };
+        let row = if row_anchor.is_empty() {
+            row + rows
+        } else {
+            row
+        };
+        shifted.push_str(&text[last..whole.start()]);
+        if col < MAX_COLUMNS && row <= MAX_ROWS {
+            shifted.push_str(&format!(
+                "{}{}{}{}",
+                col_anchor,
+                index_to_column_letter(col),
+                row_anchor,
+                row
+            ));
+        } else {
+            shifted.push_str("#REF!");
+        }
+        last = whole.end();
+    }
+    shifted.push_str(&text[last..]);
+    shifted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_formulas() {
+        let xml = r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">
+<sheetData>
+<row r="1"><c r="A1"><v>2</v></c><c r="B1"><f>A1*2</f><v>4</v></c></row>
+<row r="2"><c r="A2"><v>3</v></c><c r="B2"><f t="shared" ref="B2:B3" si="0">A2*$A$1</f><v>6</v></c></row>
+<row r="3"><c r="A3"><v>5</v></c><c r="B3"><f t="shared" si="0"/><v>10</v></c></row>
+<row><c/><c><f>IF(A1&gt;1,"A1","B2")</f></c></row>
+</sheetData></worksheet>"#;
+
+        let formulas = parse_formulas(xml).unwrap();
+        assert_eq!(formulas.len(), 4);
+        assert_eq!(formulas[&(0, 1)], "A1*2");
+        assert_eq!(formulas[&(1, 1)], "A2*$A$1");
+        assert_eq!(formulas[&(2, 1)], "A3*$A$1");
+        assert_eq!(formulas[&(3, 1)], r#"IF(A1>1,"A1","B2")"#);
+    }
+
+    #[test]
+    fn test_shift_references() {
+        assert_eq!(shift_references("SUM(A1:B2)", 1, 1), "SUM(B2:C3)");
+        assert_eq!(shift_references("$A1+A$1+$A$1", 2, 2), "$A3+C$1+$A$1");
+        assert_eq!(shift_references("LOG10(A1)", 1, 0), "LOG10(A2)");
+        assert_eq!(
+            shift_references("'Q1 2024'!A1&\"A1\"", 1, 0),
+            "'Q1 2024'!A2&\"A1\""
+        );
+        assert_eq!(shift_references("XFD1", 0, 1), "#REF!");
+    }
+}
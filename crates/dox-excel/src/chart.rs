@@ -5,13 +5,14 @@
 //! - Add data series to charts with proper range references
 //! - Position and style charts within worksheets
 //! - Update chart data dynamically
+//! - Draw sparklines inside cells
 
 use anyhow::{anyhow, Result};
-use rust_xlsxwriter::{Chart, ChartType, Worksheet};
+use rust_xlsxwriter::{Chart, ChartType, SparklineType as XlsxSparklineType, Worksheet};
 use serde::{Deserialize, Serialize};
 use tracing::{debug, info};
 
-use dox_core::RangeRef;
+use dox_core::{RangeRef, Sparkline, SparklineType};
 
 /// Represents a chart data series
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -96,7 +97,6 @@ pub struct ExcelChartBuilder {
 /// Chart manager for Excel worksheets
 pub struct ChartManager<'a> {
     worksheet: &'a mut Worksheet,
-    charts: Vec<Chart>,
 }
 
 impl ExcelChartType {
@@ -292,10 +292,7 @@ impl ChartSeries {
 impl<'a> ChartManager<'a> {
     /// Create a new chart manager for a worksheet
     pub fn new(worksheet: &'a mut Worksheet) -> Self {
-        ChartManager {
-            worksheet,
-            charts: Vec::new(),
-        }
+        ChartManager { worksheet }
     }
 
     /// Create a simple column chart with data
@@ -508,6 +505,52 @@ pub mod helpers {
     }
 }
 
+/// Draw a sparkline on the worksheet named `sheet_name`
+///
+/// Data ranges without a sheet name are read from that sheet.
+pub fn add_sparkline(
+    worksheet: &mut Worksheet,
+    sheet_name: &str,
+    sparkline: &Sparkline,
+) -> Result<()> {
+    let (row, col, last_row, last_col) = crate::validation::parse_cell_range(&sparkline.location)?;
+    if (row, col) != (last_row, last_col) {
+        return Err(anyhow!(
+            "Sparkline location {} must be a single cell",
+            sparkline.location
+        ));
+    }
+
+    let (data_sheet, _) = sparkline.data_range.parse();
+    let data_sheet = data_sheet.unwrap_or_else(|| sheet_name.to_string());
+    let (first_row, first_col, last_row, last_col) =
+        crate::validation::parse_cell_range(&sparkline.data_range)?;
+    let sparkline_type = match sparkline.sparkline_type {
+        SparklineType::Line => XlsxSparklineType::Line,
+        SparklineType::Column => XlsxSparklineType::Column,
+        SparklineType::WinLoss => XlsxSparklineType::WinLose,
+    };
+    let xlsx_sparkline = rust_xlsxwriter::Sparkline::new()
+        .set_range((
+            data_sheet.as_str(),
+            first_row,
+            first_col,
+            last_row,
+            last_col,
+        ))
+        .set_type(sparkline_type);
+
+    worksheet
+        .add_sparkline(row, col, &xlsx_sparkline)
+        .map_err(|e| anyhow!("Failed to add sparkline to {}: {}", sparkline.location, e))?;
+
+    debug!(
+        "Added sparkline for {} to {}",
+        sparkline.data_range, sparkline.location
+    );
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -549,4 +592,52 @@ mod tests {
         let _pie_type = ExcelChartType::Pie.to_rust_xlsxwriter_type();
         // Just ensure conversion methods work without errors
     }
+
+    #[tokio::test]
+    async fn test_write_range_sparklines() {
+        use dox_core::{Cell, SheetId, SpreadsheetProvider, WriteOptions};
+        use std::io::Read;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("trend.xlsx");
+        let sheet_id = SheetId(path.to_string_lossy().into_owned());
+        let data = ["Sales", "12", "15", "9", "21", "18"]
+            .iter()
+            .map(|value| vec![Cell::new(*value)])
+            .collect();
+        let options = WriteOptions {
+            sparklines: vec![
+                Sparkline::new("A2:A6", "B2"),
+                Sparkline::new("A2:A6", "Summary!B2").with_type(SparklineType::Column),
+            ],
+            ..Default::default()
+        };
+
+        crate::ExcelProvider::new()
+            .write_range(&sheet_id, &RangeRef::new("Trend!A1"), data, Some(options))
+            .await
+            .unwrap();
+
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(&path).unwrap()).unwrap();
+        let mut sheet = String::new();
+        archive
+            .by_name("xl/worksheets/sheet1.xml")
+            .unwrap()
+            .read_to_string(&mut sheet)
+            .unwrap();
+
+        // Only the sparkline for this sheet is drawn, as a line
+        assert_eq!(sheet.matches("<x14:sparklineGroup ").count(), 1);
+        assert!(!sheet.contains(r#"type="column""#));
+        assert!(sheet.contains("<xm:f>Trend!A2:A6</xm:f><xm:sqref>B2</xm:sqref>"));
+    }
+
+    #[test]
+    fn test_sparkline_location_must_be_one_cell() {
+        let mut workbook = rust_xlsxwriter::Workbook::new();
+        let worksheet = workbook.add_worksheet();
+        let error =
+            add_sparkline(worksheet, "Sheet1", &Sparkline::new("A1:A5", "B1:B2")).unwrap_err();
+        assert!(error.to_string().contains("single cell"), "{}", error);
+    }
 }
@@ -0,0 +1,287 @@
+//! Cell comments and notes
+//!
+//! calamine does not expose comments, so they are read straight from the
+//! package. Legacy notes live in `xl/comments*.xml`; modern threaded comments
+//! live in `xl/threadedComments/*.xml` with their authors in
+//! `xl/persons/person.xml`. Excel also writes a legacy placeholder for every
+//! threaded comment, which is skipped in favour of the thread.
+
+use crate::package::{attr, read_part, relationships, worksheet_parts};
+use anyhow::Result;
+use dox_core::CellComment;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use std::collections::{HashMap, HashSet};
+use std::io::{Read, Seek};
+use tracing::debug;
+use zip::ZipArchive;
+
+/// Read every comment in the workbook, sheet by sheet
+pub(crate) fn read_comments<R: Read + Seek>(zip: &mut ZipArchive<R>) -> Result<Vec<CellComment>> {
+    let persons = read_persons(zip)?;
+    let mut comments = Vec::new();
+
+    for (sheet, part) in worksheet_parts(zip)? {
+        let rels = relationships(zip, &part)?;
+
+        let mut threaded = Vec::new();
+        for rel in rels
+            .iter()
+            .filter(|r| r.rel_type.ends_with("/threadedComment"))
+        {
+            if let Some(xml) = read_part(zip, &rel.target)? {
+                threaded.extend(parse_threaded_comments(&xml, &sheet, &persons)?);
+            }
+        }
+        let threaded_cells: HashSet<String> = threaded.iter().map(|c| c.cell.clone()).collect();
+
+        for rel in rels.iter().filter(|r| r.rel_type.ends_with("/comments")) {
+            if let Some(xml) = read_part(zip, &rel.target)? {
+                comments.extend(
+                    parse_legacy_comments(&xml, &sheet)?
+                        .into_iter()
+                        .filter(|c| !threaded_cells.contains(&c.cell)),
+                );
+            }
+        }
+        comments.extend(threaded);
+    }
+
+    debug!("Read {} cell comment(s)", comments.len());
+    Ok(comments)
+}
+
+/// Map of person id to display name for threaded comment authors
+fn read_persons<R: Read + Seek>(zip: &mut ZipArchive<R>) -> Result<HashMap<String, String>> {
+    let mut persons = HashMap::new();
+    let rels = relationships(zip, "xl/workbook.xml")?;
+
+    for rel in rels.iter().filter(|r| r.rel_type.ends_with("/person")) {
+        let Some(xml) = read_part(zip, &rel.target)? else {
+            continue;
+        };
+        let mut reader = Reader::from_str(&xml);
+        loop {
+            match reader.read_event()? {
+                Event::Start(e) | Event::Empty(e) if e.local_name().as_ref() == b"person" => {
+                    if let (Some(id), Some(name)) = (attr(&e, "id"), attr(&e, "displayName")) {
+                        persons.insert(id, name);
+                    }
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+    }
+
+    Ok(persons)
+}
+
+/// Parse a legacy `comments*.xml` part
+fn parse_legacy_comments(xml: &str, sheet: &str) -> Result<Vec<CellComment>> {
+    let mut authors = Vec::new();
+    let mut comments = Vec::new();
+
+    let mut reader = Reader::from_str(xml);
+    let mut in_author = false;
+    let mut in_text = false;
+    let mut author = String::new();
+    // Cell and author index of the comment being read
+    let mut current: Option<(String, Option<usize>)> = None;
+    let mut text = String::new();
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) => match e.local_name().as_ref() {
+                b"author" => {
+                    in_author = true;
+                    author.clear();
+                }
+                b"comment" => {
+                    current = attr(&e, "ref")
+                        .map(|cell| (cell, attr(&e, "authorId").and_then(|id| id.parse().ok())));
+                    text.clear();
+                }
+                b"t" => in_text = current.is_some(),
+                _ => {}
+            },
+            Event::Text(t) => {
+                if in_author {
+                    author.push_str(&t.unescape()?);
+                } else if in_text {
+                    text.push_str(&t.unescape()?);
+                }
+            }
+            Event::End(e) => match e.local_name().as_ref() {
+                b"author" => {
+                    in_author = false;
+                    authors.push(author.clone());
+                }
+                b"t" => in_text = false,
+                b"comment" => {
+                    if let Some((cell, author_id)) = current.take() {
+                        let author = author_id
+                            .and_then(|id| authors.get(id))
+                            .filter(|name| !name.is_empty())
+                            .cloned();
+                        comments.push(CellComment {
+                            sheet: sheet.to_string(),
+                            cell,
+                            text: strip_author_prefix(&text, author.as_deref()),
+                            author,
+                            reply: false,
+                        });
+                    }
+                }
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(comments)
+}
+
+/// Parse a `threadedComments/*.xml` part
+fn parse_threaded_comments(
+    xml: &str,
+    sheet: &str,
+    persons: &HashMap<String, String>,
+) -> Result<Vec<CellComment>> {
+    let mut comments = Vec::new();
+
+    let mut reader = Reader::from_str(xml);
+    let mut current: Option<CellComment> = None;
+    let mut in_text = false;
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) => match e.local_name().as_ref() {
+                b"threadedComment" => {
+                    current = attr(&e, "ref").map(|cell| CellComment {
+                        sheet: sheet.to_string(),
+                        cell,
+                        author: attr(&e, "personId").and_then(|id| persons.get(&id).cloned()),
+                        text: String::new(),
+                        reply: attr(&e, "parentId").is_some(),
+                    });
+                }
+                b"text" => in_text = current.is_some(),
+                _ => {}
+            },
+            Event::Text(t) if in_text => {
+                if let Some(comment) = current.as_mut() {
+                    comment.text.push_str(&t.unescape()?);
+                }
+            }
+            Event::End(e) => match e.local_name().as_ref() {
+                b"text" => in_text = false,
+                b"threadedComment" => comments.extend(current.take()),
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(comments)
+}
+
+/// Excel and most writers start a note with a bold `Author:` line
+fn strip_author_prefix(text: &str, author: Option<&str>) -> String {
+    author
+        .and_then(|name| text.strip_prefix(name))
+        .and_then(|rest| rest.strip_prefix(':'))
+        .map(|rest| rest.trim_start_matches(['\r', '\n']))
+        .unwrap_or(text)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Cursor, Write};
+    use zip::write::SimpleFileOptions;
+    use zip::ZipWriter;
+
+    const WORKBOOK: &str = r#"<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships">
+<sheets><sheet name="Budget" sheetId="1" r:id="rId1"/></sheets></workbook>"#;
+
+    const WORKBOOK_RELS: &str = r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/>
+<Relationship Id="rId2" Type="http://schemas.microsoft.com/office/2017/10/relationships/person" Target="persons/person.xml"/>
+</Relationships>"#;
+
+    const SHEET_RELS: &str = r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/comments" Target="../comments1.xml"/>
+<Relationship Id="rId2" Type="http://schemas.microsoft.com/office/2017/10/relationships/threadedComment" Target="../threadedComments/threadedComment1.xml"/>
+</Relationships>"#;
+
+    const COMMENTS: &str = r#"<comments xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">
+<authors><author>Kim</author><author>tc={1}</author></authors>
+<commentList>
+<comment ref="B2" authorId="0"><text><r><rPr><b/></rPr><t>Kim:</t></r><r><t xml:space="preserve">
+Check the Q3 total</t></r></text></comment>
+<comment ref="C5" authorId="1"><text><t>[Threaded comment] Placeholder</t></text></comment>
+</commentList></comments>"#;
+
+    const THREADED: &str = r#"<ThreadedComments xmlns="http://schemas.microsoft.com/office/spreadsheetml/2018/threadedcomments">
+<threadedComment ref="C5" personId="{P1}" id="{T1}"><text>Is this &amp; that final?</text></threadedComment>
+<threadedComment ref="C5" personId="{P2}" id="{T2}" parentId="{T1}"><text>Yes</text></threadedComment>
+</ThreadedComments>"#;
+
+    const PERSONS: &str = r#"<personList xmlns="http://schemas.microsoft.com/office/spreadsheetml/2018/threadedcomments">
+<person displayName="Lee" id="{P1}" userId="lee" providerId="None"/>
+<person displayName="Park" id="{P2}" userId="park" providerId="None"/>
+</personList>"#;
+
+    fn comment_fixture() -> ZipArchive<Cursor<Vec<u8>>> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        let options = SimpleFileOptions::default();
+        for (name, content) in [
+            ("xl/workbook.xml", WORKBOOK),
+            ("xl/_rels/workbook.xml.rels", WORKBOOK_RELS),
+            ("xl/worksheets/sheet1.xml", "<worksheet/>"),
+            ("xl/worksheets/_rels/sheet1.xml.rels", SHEET_RELS),
+            ("xl/comments1.xml", COMMENTS),
+            ("xl/threadedComments/threadedComment1.xml", THREADED),
+            ("xl/persons/person.xml", PERSONS),
+        ] {
+            writer.start_file(name, options).unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        ZipArchive::new(writer.finish().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_read_legacy_and_threaded_comments() {
+        let mut zip = comment_fixture();
+        let comments = read_comments(&mut zip).unwrap();
+
+        assert_eq!(comments.len(), 3);
+
+        assert_eq!(comments[0].sheet, "Budget");
+        assert_eq!(comments[0].cell, "B2");
+        assert_eq!(comments[0].author.as_deref(), Some("Kim"));
+        assert_eq!(comments[0].text, "Check the Q3 total");
+        assert!(!comments[0].reply);
+
+        // The legacy placeholder on C5 gives way to the thread
+        assert_eq!(comments[1].cell, "C5");
+        assert_eq!(comments[1].author.as_deref(), Some("Lee"));
+        assert_eq!(comments[1].text, "Is this & that final?");
+        assert!(!comments[1].reply);
+
+        assert_eq!(comments[2].author.as_deref(), Some("Park"));
+        assert_eq!(comments[2].text, "Yes");
+        assert!(comments[2].reply);
+    }
+
+    #[test]
+    fn test_strip_author_prefix() {
+        assert_eq!(strip_author_prefix("Kim:\nHello", Some("Kim")), "Hello");
+        assert_eq!(strip_author_prefix("Hello", Some("Kim")), "Hello");
+        assert_eq!(strip_author_prefix("Kim: Hello", None), "Kim: Hello");
+    }
+}
@@ -0,0 +1,371 @@
+//! CSV import and export for Excel workbooks
+//!
+//! Converts between CSV files and XLSX sheets using the same cell model as
+//! the rest of the provider:
+//! - Import types fields the same way as sheet writes, keeping headers as text
+//! - Export quotes fields containing the delimiter, quotes or line breaks
+//! - The delimiter is configurable through [`CsvOptions`]
+
+use anyhow::{anyhow, Result};
+use calamine::{open_workbook, Reader, Xlsx};
+use rust_xlsxwriter::Workbook;
+use std::path::Path;
+use tracing::{debug, info};
+
+use dox_core::utils::write_atomic;
+use dox_core::{Cell, SheetId, WriteOptions, INVALID_SHEET_CHARS, MAX_SHEET_NAME_CHARS};
+
+use crate::ExcelProvider;
+
+/// Options for CSV conversion
+#[derive(Debug, Clone)]
+pub struct CsvOptions {
+    /// Field delimiter (`,` by default)
+    pub delimiter: u8,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        CsvOptions { delimiter: b',' }
+    }
+}
+
+impl CsvOptions {
+    /// Options for semicolon-separated files (common in European locales)
+    pub fn semicolon() -> Self {
+        CsvOptions { delimiter: b';' }
+    }
+
+    /// Options for tab-separated files
+    pub fn tab() -> Self {
+        CsvOptions { delimiter: b'\t' }
+    }
+}
+
+impl ExcelProvider {
+    /// Import a comma-separated file as a new workbook
+    ///
+    /// The sheet is named after the CSV file stem, with characters Excel
+    /// rejects replaced by `_` and cut to 31 characters. When `has_headers`
+    /// is set the first record is always written as text.
+    pub async fn import_csv(
+        &self,
+        sheet_id: &SheetId,
+        csv_path: &Path,
+        has_headers: bool,
+    ) -> Result<()> {
+        self.import_csv_with_options(sheet_id, csv_path, has_headers, &CsvOptions::default())
+            .await
+    }
+
+    /// Import a delimited file as a new workbook using the given options
+    pub async fn import_csv_with_options(
+        &self,
+        sheet_id: &SheetId,
+        csv_path: &Path,
+        has_headers: bool,
+        options: &CsvOptions,
+    ) -> Result<()> {
+        let path = self.resolve_path(sheet_id);
+
+        debug!("Importing CSV {:?} into Excel file {:?}", csv_path, path);
+
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(options.delimiter)
+            .has_headers(false)
+            .flexible(true)
+            .from_path(csv_path)
+            .map_err(|e| anyhow!("Failed to open CSV file {:?}: {}", csv_path, e))?;
+
+        let sheet_name = sheet_name_from_stem(csv_path);
+
+        let mut data = Vec::new();
+        for record in reader.records() {
+            let record = record.map_err(|e| anyhow!("Failed to parse CSV record: {}", e))?;
+            data.push(record.iter().map(Cell::new).collect::<Vec<_>>());
+        }
+
+        let mut workbook = Workbook::new();
+        Self::write_sheet(&mut workbook, &sheet_name, &data, &WriteOptions::default())?;
+
+        // Header names that look like numbers or dates stay as written
+        if has_headers {
+            if let Some(header) = data.first() {
+                let worksheet = workbook.worksheet_from_name(&sheet_name)?;
+                for (col, cell) in header.iter().enumerate() {
+                    if !cell.value.is_empty() {
+                        worksheet
+                            .write_string(0, col as u16, &cell.value)
+                            .map_err(|e| anyhow!("Failed to write string: {}", e))?;
+                    }
+                }
+            }
+        }
+
+        write_atomic(&path, |file| {
+            workbook
+                .save_to_writer(file)
+                .map_err(|e| anyhow!("Failed to save Excel file: {}", e))
+        })?;
+
+        info!("Imported {} CSV rows into {:?}", data.len(), path);
+        Ok(())
+    }
+
+    /// Export a sheet of the workbook to a comma-separated file
+    pub async fn export_csv(
+        &self,
+        sheet_id: &SheetId,
+        sheet_name: &str,
+        csv_path: &Path,
+    ) -> Result<()> {
+        self.export_csv_with_options(sheet_id, sheet_name, csv_path, &CsvOptions::default())
+            .await
+    }
+
+    /// Export a sheet of the workbook to a delimited file using the given options
+    pub async fn export_csv_with_options(
+        &self,
+        sheet_id: &SheetId,
+        sheet_name: &str,
+        csv_path: &Path,
+        options: &CsvOptions,
+    ) -> Result<()> {
+        let path = self.resolve_path(sheet_id);
+
+        debug!(
+            "Exporting sheet '{}' of {:?} to {:?}",
+            sheet_name, path, csv_path
+        );
+
+        let mut workbook: Xlsx<_> =
+            open_workbook(&path).map_err(|e| anyhow!("Failed to open Excel file: {}", e))?;
+        let range = workbook
+            .worksheet_range(sheet_name)
+            .map_err(|e| anyhow!("Failed to read sheet '{}': {}", sheet_name, e))?;
+
+        let mut writer = csv::WriterBuilder::new()
+            .delimiter(options.delimiter)
+            .from_path(csv_path)
+            .map_err(|e| anyhow!("Failed to create CSV file {:?}: {}", csv_path, e))?;
+
+        for row in range.rows() {
+            let values = row
+                .iter()
+                .map(|data| Self::convert_calamine_cell(data, false).value);
+            writer
+                .write_record(values)
+                .map_err(|e| anyhow!("Failed to write CSV record: {}", e))?;
+        }
+        writer
+            .flush()
+            .map_err(|e| anyhow!("Failed to write CSV file {:?}: {}", csv_path, e))?;
+
+        info!("Exported {} rows to {:?}", range.height(), csv_path);
+        Ok(())
+    }
+}
+
+/// Sheet name for an imported CSV file, taken from its file stem
+///
+/// Characters Excel does not allow in sheet names become `_` and the name is
+/// cut to Excel's length limit. A missing or empty stem gives `Sheet1`.
+fn sheet_name_from_stem(csv_path: &Path) -> String {
+    let name: String = csv_path
+        .file_stem()
+        .map(|s| s.to_string_lossy())
+        .unwrap_or_default()
+        .chars()
+        .map(|c| {
+            if INVALID_SHEET_CHARS.contains(&c) {
+                '_'
+            } else {
+                c
+            }
+        })
+        .take(MAX_SHEET_NAME_CHARS)
+        .collect();
+
+    if name.is_empty() {
+        "Sheet1".to_string()
+    } else {
+        name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dox_core::{RangeRef, SpreadsheetProvider};
+
+    fn sheet_id(path: &Path) -> SheetId {
+        SheetId(path.to_string_lossy().to_string())
+    }
+
+    #[tokio::test]
+    async fn test_csv_round_trip_with_quoted_fields() {
+        let dir = tempfile::tempdir().unwrap();
+        let csv_path = dir.path().join("people.csv");
+        let xlsx_path = dir.path().join("people.xlsx");
+        let out_path = dir.path().join("out.csv");
+
+        let original = "name,note,amount\n\
+                        \"Kim, Minsu\",\"line one\nline two\",1500\n\
+                        Lee,\"says \"\"hi\"\"\",2.5\n";
+        std::fs::write(&csv_path, original).unwrap();
+
+        let provider = ExcelProvider::new();
+        provider
+            .import_csv(&sheet_id(&xlsx_path), &csv_path, true)
+            .await
+            .unwrap();
+        provider
+            .export_csv(&sheet_id(&xlsx_path), "people", &out_path)
+            .await
+            .unwrap();
+
+        assert_eq!(std::fs::read_to_string(&out_path).unwrap(), original);
+    }
+
+    #[tokio::test]
+    async fn test_csv_import_types_numbers() {
+        let dir = tempfile::tempdir().unwrap();
+        let csv_path = dir.path().join("numbers.csv");
+        let xlsx_path = dir.path().join("numbers.xlsx");
+        std::fs::write(&csv_path, "10,20\n3.5,text\n").unwrap();
+
+        let provider = ExcelProvider::new();
+        provider
+            .import_csv(&sheet_id(&xlsx_path), &csv_path, false)
+            .await
+            .unwrap();
+
+        let mut workbook: Xlsx<_> = open_workbook(&xlsx_path).unwrap();
+        let range = workbook.worksheet_range("numbers").unwrap();
+        assert_eq!(range.get((0, 0)), Some(&calamine::Data::Float(10.0)));
+        assert_eq!(range.get((1, 0)), Some(&calamine::Data::Float(3.5)));
+        assert_eq!(
+            range.get((1, 1)),
+            Some(&calamine::Data::String("text".to_string()))
+        );
+
+        let cells = provider
+            .read_range(&sheet_id(&xlsx_path), &RangeRef::new("numbers!A1:B2"), None)
+            .await
+            .unwrap();
+        assert_eq!(cells[0][1].value, "20");
+    }
+
+    #[tokio::test]
+    async fn test_csv_import_types_like_sheet_writes() {
+        let dir = tempfile::tempdir().unwrap();
+        let csv_path = dir.path().join("typed.csv");
+        let xlsx_path = dir.path().join("typed.xlsx");
+        std::fs::write(
+            &csv_path,
+            "2024,flag,day
+1,true,2024-03-15
+",
+        )
+        .unwrap();
+
+        let provider = ExcelProvider::new();
+        provider
+            .import_csv(&sheet_id(&xlsx_path), &csv_path, true)
+            .await
+            .unwrap();
+
+        let mut workbook: Xlsx<_> = open_workbook(&xlsx_path).unwrap();
+        let range = workbook.worksheet_range("typed").unwrap();
+        assert_eq!(
+            range.get((0, 0)),
+            Some(&calamine::Data::String("2024".to_string()))
+        );
+        assert_eq!(range.get((1, 0)), Some(&calamine::Data::Float(1.0)));
+        assert_eq!(range.get((1, 1)), Some(&calamine::Data::Bool(true)));
+        assert!(matches!(
+            range.get((1, 2)),
+            Some(calamine::Data::DateTime(_))
+        ));
+    }
+
+    #[test]
+    fn test_sheet_name_from_stem() {
+        assert_eq!(sheet_name_from_stem(Path::new("dir/sales.csv")), "sales");
+        assert_eq!(
+            sheet_name_from_stem(Path::new("Q1 [draft]: a*b?.csv")),
+            "Q1 _draft__ a_b_"
+        );
+        assert_eq!(
+            sheet_name_from_stem(Path::new(
+                "quarterly revenue by region and product line.csv"
+            )),
+            "quarterly revenue by region and"
+        );
+        assert_eq!(sheet_name_from_stem(Path::new(".csv")), ".csv");
+        assert_eq!(sheet_name_from_stem(Path::new("")), "Sheet1");
+    }
+
+    #[tokio::test]
+    async fn test_csv_import_sanitizes_sheet_name() {
+        let dir = tempfile::tempdir().unwrap();
+        let csv_path = dir
+            .path()
+            .join("report [final] for the second quarter of 2024.csv");
+        let xlsx_path = dir.path().join("report.xlsx");
+        std::fs::write(&csv_path, "a\n1\n").unwrap();
+
+        let provider = ExcelProvider::new();
+        provider
+            .import_csv(&sheet_id(&xlsx_path), &csv_path, true)
+            .await
+            .unwrap();
+
+        let sheets = provider.list_sheets(&sheet_id(&xlsx_path)).await.unwrap();
+        assert_eq!(sheets[0].name, "report _final_ for the second q");
+    }
+
+    #[tokio::test]
+    async fn test_csv_custom_delimiter() {
+        let dir = tempfile::tempdir().unwrap();
+        let csv_path = dir.path().join("semi.csv");
+        let xlsx_path = dir.path().join("semi.xlsx");
+        let out_path = dir.path().join("semi_out.csv");
+        std::fs::write(&csv_path, "a;b\n\"x;y\";2\n").unwrap();
+
+        let provider = ExcelProvider::new();
+        let options = CsvOptions::semicolon();
+        provider
+            .import_csv_with_options(&sheet_id(&xlsx_path), &csv_path, true, &options)
+            .await
+            .unwrap();
+        provider
+            .export_csv_with_options(&sheet_id(&xlsx_path), "semi", &out_path, &options)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(&out_path).unwrap(),
+            "a;b\n\"x;y\";2\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_csv_export_missing_sheet() {
+        let dir = tempfile::tempdir().unwrap();
+        let csv_path = dir.path().join("data.csv");
+        let xlsx_path = dir.path().join("data.xlsx");
+        std::fs::write(&csv_path, "a\n").unwrap();
+
+        let provider = ExcelProvider::new();
+        provider
+            .import_csv(&sheet_id(&xlsx_path), &csv_path, false)
+            .await
+            .unwrap();
+
+        let result = provider
+            .export_csv(&sheet_id(&xlsx_path), "Missing", &dir.path().join("x.csv"))
+            .await;
+        assert!(result.is_err());
+    }
+}
@@ -1,37 +1,23 @@
-//! Excel formatting using rust_xlsxwriter (simplified implementation)
+//! Excel formatting using rust_xlsxwriter
 //!
-//! This module provides basic functionality to:
-//! - Apply basic cell formatting (fonts, colors, borders)
-//! - Document more advanced formatting for future implementation
-//! - Create format templates for consistent styling
-
-use anyhow::Result;
-use rust_xlsxwriter::Worksheet;
-use serde::{Deserialize, Serialize};
-use tracing::{info, warn};
-
-use dox_core::RangeRef;
-
-/// Basic format options that work with current rust_xlsxwriter
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct BasicCellFormat {
-    /// Bold text
-    pub bold: Option<bool>,
-    /// Italic text
-    pub italic: Option<bool>,
-    /// Font size
-    pub font_size: Option<f64>,
-    /// Font name
-    pub font_name: Option<String>,
-}
+//! This module provides functionality to:
+//! - Convert basic cell formats (fonts, colors, number formats) to rust_xlsxwriter
+//! - Apply formats to worksheet ranges
+//! - Resolve theme and column templates for written tables
 
-/// Format template for reusable styling
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct FormatTemplate {
-    pub name: String,
-    pub description: String,
-    pub format: BasicCellFormat,
-}
+use anyhow::{anyhow, Result};
+use rust_xlsxwriter::{
+    ConditionalFormat2ColorScale, ConditionalFormatCell, ConditionalFormatCellRule,
+    ConditionalFormatDataBar, ConditionalFormatTop, ConditionalFormatTopRule, Format,
+    FormatPattern, Worksheet,
+};
+use tracing::debug;
+use unicode_width::UnicodeWidthStr;
+
+pub use dox_core::{BasicCellFormat, FormatTemplate, StyleTheme};
+use dox_core::{
+    Cell, CellValue, CellValueRule, ConditionalFormat, ConditionalRule, RangeRef, WriteOptions,
+};
 
 /// Basic formatting manager
 pub struct BasicFormattingManager<'a> {
@@ -43,134 +29,449 @@ impl<'a> BasicFormattingManager<'a> {
         Self { worksheet }
     }
 
-    /// Apply basic formatting to a range (documented for future implementation)
+    /// Apply basic formatting to a range, keeping the cell values
     pub fn apply_format(&mut self, range: &RangeRef, format: &BasicCellFormat) -> Result<()> {
-        // Log what formatting would be applied
-        info!("Format would be applied to range: {}", range.0);
-
-        if let Some(bold) = format.bold {
-            info!("  Bold: {}", bold);
-        }
-
-        if let Some(italic) = format.italic {
-            info!("  Italic: {}", italic);
-        }
-
-        if let Some(size) = format.font_size {
-            info!("  Font size: {}", size);
-        }
+        let (first_row, first_col, last_row, last_col) =
+            crate::validation::parse_cell_range(range)?;
 
-        if let Some(font) = &format.font_name {
-            info!("  Font name: {}", font);
-        }
-
-        // Note: Full implementation would create Format and apply to range
-        warn!("Cell formatting documented but not applied - API limitations");
+        self.worksheet
+            .set_range_format(
+                first_row,
+                first_col,
+                last_row,
+                last_col,
+                &to_xlsx_format(format),
+            )
+            .map_err(|e| anyhow!("Failed to format range {}: {}", range.0, e))?;
 
+        debug!("Applied format to range: {}", range.0);
         Ok(())
     }
 
     /// Create a format for headers
     pub fn apply_header_format(&mut self, range: &RangeRef) -> Result<()> {
-        let header_format = BasicCellFormat {
-            bold: Some(true),
-            italic: None,
-            font_size: Some(12.0),
-            font_name: Some("Arial".to_string()),
-        };
-
-        self.apply_format(range, &header_format)
+        self.apply_format(range, &FormatTemplate::header().format)
     }
 
     /// Create a format for data cells
     pub fn apply_data_format(&mut self, range: &RangeRef) -> Result<()> {
-        let data_format = BasicCellFormat {
-            bold: Some(false),
-            italic: None,
-            font_size: Some(10.0),
-            font_name: Some("Arial".to_string()),
-        };
-
-        self.apply_format(range, &data_format)
+        self.apply_format(range, &FormatTemplate::data().format)
     }
 }
 
-/// Predefined format templates
-impl FormatTemplate {
-    /// Header format template
-    pub fn header() -> Self {
-        Self {
-            name: "Header".to_string(),
-            description: "Bold header formatting".to_string(),
-            format: BasicCellFormat {
-                bold: Some(true),
-                italic: None,
-                font_size: Some(12.0),
-                font_name: Some("Arial".to_string()),
-            },
-        }
-    }
+/// Convert a basic cell format to a rust_xlsxwriter format
+pub fn to_xlsx_format(format: &BasicCellFormat) -> Format {
+    let mut xlsx_format = Format::new();
 
-    /// Normal data format template
-    pub fn data() -> Self {
-        Self {
-            name: "Data".to_string(),
-            description: "Standard data cell formatting".to_string(),
-            format: BasicCellFormat {
-                bold: Some(false),
-                italic: None,
-                font_size: Some(10.0),
-                font_name: Some("Arial".to_string()),
-            },
-        }
+    if format.bold == Some(true) {
+        xlsx_format = xlsx_format.set_bold();
+    }
+    if format.italic == Some(true) {
+        xlsx_format = xlsx_format.set_italic();
+    }
+    if let Some(size) = format.font_size {
+        xlsx_format = xlsx_format.set_font_size(size);
+    }
+    if let Some(name) = &format.font_name {
+        xlsx_format = xlsx_format.set_font_name(name);
+    }
+    if let Some(color) = &format.font_color {
+        xlsx_format = xlsx_format.set_font_color(color.as_str());
+    }
+    if let Some(color) = &format.background_color {
+        xlsx_format = xlsx_format
+            .set_pattern(FormatPattern::Solid)
+            .set_background_color(color.as_str());
+    }
+    if let Some(num_format) = &format.num_format {
+        xlsx_format = xlsx_format.set_num_format(num_format);
     }
 
-    /// Emphasis format template
-    pub fn emphasis() -> Self {
-        Self {
-            name: "Emphasis".to_string(),
-            description: "Italic emphasis formatting".to_string(),
-            format: BasicCellFormat {
-                bold: Some(false),
-                italic: Some(true),
-                font_size: Some(10.0),
-                font_name: Some("Arial".to_string()),
-            },
+    xlsx_format
+}
+
+/// Add a conditional format to the worksheet
+pub fn apply_conditional_format(
+    worksheet: &mut Worksheet,
+    conditional_format: &ConditionalFormat,
+) -> Result<()> {
+    let (first_row, first_col, last_row, last_col) =
+        crate::validation::parse_cell_range(&conditional_format.range)?;
+    let format = to_xlsx_format(&conditional_format.format.clone().unwrap_or_default());
+
+    let added = match &conditional_format.rule {
+        ConditionalRule::CellValue(rule) => {
+            let rule = match *rule {
+                CellValueRule::EqualTo(value) => ConditionalFormatCellRule::EqualTo(value),
+                CellValueRule::NotEqualTo(value) => ConditionalFormatCellRule::NotEqualTo(value),
+                CellValueRule::GreaterThan(value) => ConditionalFormatCellRule::GreaterThan(value),
+                CellValueRule::GreaterThanOrEqualTo(value) => {
+                    ConditionalFormatCellRule::GreaterThanOrEqualTo(value)
+                }
+                CellValueRule::LessThan(value) => ConditionalFormatCellRule::LessThan(value),
+                CellValueRule::LessThanOrEqualTo(value) => {
+                    ConditionalFormatCellRule::LessThanOrEqualTo(value)
+                }
+                CellValueRule::Between(min, max) => ConditionalFormatCellRule::Between(min, max),
+                CellValueRule::NotBetween(min, max) => {
+                    ConditionalFormatCellRule::NotBetween(min, max)
+                }
+            };
+            let cell_format = ConditionalFormatCell::new()
+                .set_rule(rule)
+                .set_format(format);
+            worksheet.add_conditional_format(first_row, first_col, last_row, last_col, &cell_format)
         }
-    }
+        ConditionalRule::ColorScale {
+            min_color,
+            max_color,
+        } => {
+            let color_scale = ConditionalFormat2ColorScale::new()
+                .set_minimum_color(min_color.as_str())
+                .set_maximum_color(max_color.as_str());
+            worksheet.add_conditional_format(first_row, first_col, last_row, last_col, &color_scale)
+        }
+        ConditionalRule::DataBar { color } => {
+            let data_bar = match color {
+                Some(color) => ConditionalFormatDataBar::new().set_fill_color(color.as_str()),
+                None => ConditionalFormatDataBar::new(),
+            };
+            worksheet.add_conditional_format(first_row, first_col, last_row, last_col, &data_bar)
+        }
+        ConditionalRule::Top(count) => {
+            let top = ConditionalFormatTop::new()
+                .set_rule(ConditionalFormatTopRule::Top(*count))
+                .set_format(format);
+            worksheet.add_conditional_format(first_row, first_col, last_row, last_col, &top)
+        }
+        ConditionalRule::Bottom(count) => {
+            let bottom = ConditionalFormatTop::new()
+                .set_rule(ConditionalFormatTopRule::Bottom(*count))
+                .set_format(format);
+            worksheet.add_conditional_format(first_row, first_col, last_row, last_col, &bottom)
+        }
+    };
+
+    added.map_err(|e| {
+        anyhow!(
+            "Failed to add conditional format to {}: {}",
+            conditional_format.range.0,
+            e
+        )
+    })?;
+
+    debug!("Added conditional format to {}", conditional_format.range.0);
+    Ok(())
 }
 
-/// Style theme containing multiple format templates
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct StyleTheme {
-    pub name: String,
-    pub description: String,
-    pub templates: Vec<FormatTemplate>,
+/// Format for a cell of a table written from A1, if the options ask for any
+///
+/// The theme's `Header` template covers the first row and its `Data` template
+/// the rest; column templates only apply below the header.
+pub fn table_cell_format(
+    options: &WriteOptions,
+    row: usize,
+    col: usize,
+) -> Option<BasicCellFormat> {
+    let theme_template = options
+        .theme
+        .as_ref()
+        .and_then(|theme| theme.get_template(if row == 0 { "Header" } else { "Data" }));
+    let column_template = (row > 0)
+        .then(|| options.column_formats.get(&col))
+        .flatten();
+
+    match (theme_template, column_template) {
+        (None, None) => None,
+        (Some(base), None) => Some(base.format.clone()),
+        (None, Some(column)) => Some(column.format.clone()),
+        (Some(base), Some(column)) => Some(base.format.merged(&column.format)),
+    }
 }
 
-impl StyleTheme {
-    /// Professional business theme
-    pub fn professional() -> Self {
-        Self {
-            name: "Professional".to_string(),
-            description: "Clean professional formatting".to_string(),
-            templates: vec![
-                FormatTemplate::header(),
-                FormatTemplate::data(),
-                FormatTemplate::emphasis(),
-            ],
+/// Widest column, in characters, that auto-fit will produce
+pub const MAX_AUTO_FIT_WIDTH: f64 = 60.0;
+
+/// Column widths that fit the longest value in each column of `data`
+///
+/// Widths count display columns, so Korean and other wide characters take
+/// two. Formulas are skipped because their displayed result is unknown here.
+pub fn auto_fit_widths(data: &[Vec<Cell>]) -> Vec<f64> {
+    let mut widths: Vec<usize> = Vec::new();
+
+    for row in data {
+        if widths.len() < row.len() {
+            widths.resize(row.len(), 0);
+        }
+        for (col, cell) in row.iter().enumerate() {
+            if cell.value.starts_with('=') {
+                continue;
+            }
+            let width = cell
+                .value
+                .lines()
+                .map(UnicodeWidthStr::width)
+                .max()
+                .unwrap_or(0);
+            widths[col] = widths[col].max(width);
         }
     }
 
-    /// Get a template by name
-    pub fn get_template(&self, name: &str) -> Option<&FormatTemplate> {
-        self.templates.iter().find(|t| t.name == name)
+    // Two extra characters of padding keep text clear of the cell border
+    widths
+        .into_iter()
+        .map(|width| (width as f64 + 2.0).min(MAX_AUTO_FIT_WIDTH))
+        .collect()
+}
+
+/// Number format for dates that the write options leave unformatted
+pub const DATE_NUM_FORMAT: &str = "yyyy-mm-dd";
+
+/// Number format for date-times that the write options leave unformatted
+pub const DATETIME_NUM_FORMAT: &str = "yyyy-mm-dd hh:mm:ss";
+
+/// Default number format for a value, if it is a date
+pub fn date_num_format(value: &CellValue) -> Option<&'static str> {
+    match value {
+        CellValue::Date(_) => Some(DATE_NUM_FORMAT),
+        CellValue::DateTime(_) => Some(DATETIME_NUM_FORMAT),
+        _ => None,
     }
 }
 
+/// Format for a written cell: [`table_cell_format`] plus a date number
+/// format for dates, since Excel shows them as serial numbers otherwise
+pub fn written_cell_format(
+    options: &WriteOptions,
+    row: usize,
+    col: usize,
+    value: &CellValue,
+) -> Option<BasicCellFormat> {
+    let format = table_cell_format(options, row, col);
+    let Some(date_format) = date_num_format(value) else {
+        return format;
+    };
+
+    let mut format = format.unwrap_or_default();
+    format
+        .num_format
+        .get_or_insert_with(|| date_format.to_string());
+    Some(format)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use dox_core::{SheetId, SpreadsheetProvider};
+    use std::io::Read;
+
+    fn read_part(archive: &mut zip::ZipArchive<std::fs::File>, name: &str) -> String {
+        let mut xml = String::new();
+        archive
+            .by_name(name)
+            .unwrap()
+            .read_to_string(&mut xml)
+            .unwrap();
+        xml
+    }
+
+    /// The `<xf>` element and font used by a cell in the first worksheet
+    fn cell_style(path: &std::path::Path, cell: &str) -> (String, String) {
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(path).unwrap()).unwrap();
+        let sheet = read_part(&mut archive, "xl/worksheets/sheet1.xml");
+        let styles = read_part(&mut archive, "xl/styles.xml");
+
+        let style_index = regex::Regex::new(&format!(r#"<c r="{}" s="(\d+)""#, cell))
+            .unwrap()
+            .captures(&sheet)
+            .map_or(0, |c| c[1].parse::<usize>().unwrap());
+
+        let section = |tag: &str| {
+            let start = styles.find(&format!("<{}", tag)).unwrap();
+            let end = styles.find(&format!("</{}>", tag)).unwrap();
+            styles[start..end].to_string()
+        };
+        let xf = regex::Regex::new(r"<xf [^>]*>")
+            .unwrap()
+            .find_iter(&section("cellXfs"))
+            .nth(style_index)
+            .unwrap()
+            .as_str()
+            .to_string();
+        let font_id: usize = regex::Regex::new(r#"fontId="(\d+)""#)
+            .unwrap()
+            .captures(&xf)
+            .unwrap()[1]
+            .parse()
+            .unwrap();
+        let font = section("fonts")
+            .split("<font>")
+            .nth(font_id + 1)
+            .unwrap()
+            .to_string();
+
+        (xf, font)
+    }
+
+    #[tokio::test]
+    async fn test_write_range_with_theme() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("report.xlsx");
+        let sheet_id = SheetId(path.to_string_lossy().into_owned());
+        let data = vec![
+            vec![Cell::new("Item"), Cell::new("Qty"), Cell::new("Price")],
+            vec![Cell::new("Pen"), Cell::new("3"), Cell::new("1.5")],
+            vec![Cell::new("Book"), Cell::new("1"), Cell::new("12")],
+        ];
+        let options = WriteOptions {
+            theme: Some(StyleTheme::professional()),
+            column_formats: [(2, FormatTemplate::currency())].into(),
+            ..Default::default()
+        };
+
+        crate::ExcelProvider::new()
+            .write_range(&sheet_id, &RangeRef::new("A1"), data, Some(options))
+            .await
+            .unwrap();
+
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(&path).unwrap()).unwrap();
+        let styles = read_part(&mut archive, "xl/styles.xml");
+        let currency_id =
+            regex::Regex::new(r#"<numFmt numFmtId="(\d+)" formatCode="\$#,##0\.00"/>"#)
+                .unwrap()
+                .captures(&styles)
+                .expect("currency number format")[1]
+                .to_string();
+
+        let (header_xf, header_font) = cell_style(&path, "A1");
+        assert!(header_font.contains("<b/>"));
+        assert!(header_font.contains(r#"<color rgb="FFFFFFFF"/>"#));
+        assert!(header_xf.contains(r#"applyFill="1""#));
+
+        let (price_xf, price_font) = cell_style(&path, "C2");
+        assert!(price_xf.contains(&format!(r#"numFmtId="{}""#, currency_id)));
+        assert!(!price_font.contains("<b/>"));
+
+        let (qty_xf, _) = cell_style(&path, "B3");
+        assert!(qty_xf.contains(r#"numFmtId="0""#));
+    }
+
+    #[tokio::test]
+    async fn test_write_range_auto_fit_columns() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("widths.xlsx");
+        let sheet_id = SheetId(path.to_string_lossy().into_owned());
+        let data = vec![
+            vec![
+                Cell::new("Id"),
+                Cell::new("Description"),
+                Cell::new("담당자"),
+            ],
+            vec![
+                Cell::new("1"),
+                Cell::new("A fairly long description that would be clipped"),
+                Cell::new("김"),
+            ],
+            vec![Cell::new("2"), Cell::new("x".repeat(200)), Cell::new("=A2")],
+        ];
+        let options = WriteOptions {
+            auto_fit_columns: true,
+            ..Default::default()
+        };
+
+        crate::ExcelProvider::new()
+            .write_range(&sheet_id, &RangeRef::new("A1"), data, Some(options))
+            .await
+            .unwrap();
+
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(&path).unwrap()).unwrap();
+        let sheet = read_part(&mut archive, "xl/worksheets/sheet1.xml");
+        let widths: Vec<f64> = regex::Regex::new(r#"<col min="\d+" max="\d+" width="([\d.]+)""#)
+            .unwrap()
+            .captures_iter(&sheet)
+            .map(|c| c[1].parse().unwrap())
+            .collect();
+
+        assert_eq!(widths.len(), 3);
+        // The id column is sized by its header, the description is capped
+        assert!(widths[1] > widths[0] + 40.0);
+        assert!(widths[1] <= MAX_AUTO_FIT_WIDTH + 1.0);
+        // "담당자" is three wide characters
+        assert!(widths[2] > widths[0] + 3.0);
+    }
+
+    #[tokio::test]
+    async fn test_write_range_conditional_formats() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("balance.xlsx");
+        let sheet_id = SheetId(path.to_string_lossy().into_owned());
+        let data = vec![
+            vec![Cell::new("Month"), Cell::new("Balance")],
+            vec![Cell::new("Jan"), Cell::new("120")],
+            vec![Cell::new("Feb"), Cell::new("-40")],
+            vec![Cell::new("Mar"), Cell::new("15")],
+        ];
+        let negative_red = ConditionalFormat::new(
+            "B2:B4",
+            ConditionalRule::CellValue(CellValueRule::LessThan(0.0)),
+        )
+        .with_format(BasicCellFormat {
+            font_color: Some("#9C0006".to_string()),
+            background_color: Some("#FFC7CE".to_string()),
+            ..Default::default()
+        });
+        let color_scale = ConditionalFormat::new(
+            "Sheet1!B2:B4",
+            ConditionalRule::ColorScale {
+                min_color: "#F8696B".to_string(),
+                max_color: "#63BE7B".to_string(),
+            },
+        );
+        let other_sheet =
+            ConditionalFormat::new("Summary!A1:A4", ConditionalRule::DataBar { color: None });
+        let options = WriteOptions {
+            conditional_formats: vec![negative_red, color_scale, other_sheet],
+            ..Default::default()
+        };
+
+        crate::ExcelProvider::new()
+            .write_range(&sheet_id, &RangeRef::new("A1"), data, Some(options))
+            .await
+            .unwrap();
+
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(&path).unwrap()).unwrap();
+        let sheet = read_part(&mut archive, "xl/worksheets/sheet1.xml");
+        let styles = read_part(&mut archive, "xl/styles.xml");
+
+        // Both rules target the same cells and share one element
+        assert!(sheet.contains(r#"<conditionalFormatting sqref="B2:B4">"#));
+        assert!(sheet.contains(r#"<cfRule type="cellIs" dxfId="0" priority="1" operator="lessThan"><formula>0</formula></cfRule>"#));
+        assert!(sheet.contains(r#"<cfRule type="colorScale" priority="2">"#));
+        assert!(sheet.contains(r#"<color rgb="FFF8696B"/><color rgb="FF63BE7B"/>"#));
+        assert!(!sheet.contains("dataBar"));
+
+        let dxfs = &styles[styles.find("<dxfs").unwrap()..styles.find("</dxfs>").unwrap()];
+        assert!(dxfs.contains(r#"<color rgb="FF9C0006"/>"#));
+    }
+
+    #[test]
+    fn test_column_format_layers_over_theme() {
+        let options = WriteOptions {
+            theme: Some(StyleTheme::professional()),
+            column_formats: [(1, FormatTemplate::currency())].into(),
+            ..Default::default()
+        };
+
+        let header = table_cell_format(&options, 0, 1).unwrap();
+        assert_eq!(header.num_format, None);
+        assert_eq!(header.bold, Some(true));
+
+        let price = table_cell_format(&options, 3, 1).unwrap();
+        assert_eq!(price.num_format.as_deref(), Some("$#,##0.00"));
+        assert_eq!(price.font_name.as_deref(), Some("Arial"));
+
+        assert_eq!(table_cell_format(&WriteOptions::default(), 0, 0), None);
+    }
 
     #[test]
     fn test_format_template_creation() {
@@ -3,15 +3,17 @@
 //! This module provides functionality to:
 //! - Parse Excel formulas into an abstract syntax tree (AST)
 //! - Evaluate formulas with cell reference resolution
-//! - Support common Excel functions (SUM, AVERAGE, COUNT, IF, etc.)
+//! - Support common Excel functions (SUM, AVERAGE, COUNT, IF, VLOOKUP,
+//!   CONCATENATE, etc.) and the `&` and comparison operators
 //! - Handle cell references (A1, B2:D10, etc.)
-//! - Manage formula dependencies for calculation order
+//! - Order formulas by their dependencies and detect circular references
 
 use anyhow::{anyhow, Result};
-use evalexpr::{eval_with_context, ContextWithMutableVariables, HashMapContext, Value};
+use dox_core::{column_letter_to_index, index_to_column_letter};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
 use tracing::debug;
 
@@ -27,6 +29,11 @@ pub struct Formula {
 }
 
 /// Represents a cell reference (e.g., A1, B2:D10, Sheet1!A1)
+///
+/// The `*_absolute` flags record the `$` anchors of `$A$1`-style
+/// references, which stay put when a formula is copied or shifted. They
+/// take part in equality, so look cells up by
+/// [`CellReference::without_anchors`].
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct CellReference {
     /// Sheet name (if specified)
@@ -39,16 +46,33 @@ pub struct CellReference {
     pub end_col: Option<u32>,
     /// End row for range references (None for single cell)
     pub end_row: Option<u32>,
+    /// Whether the column is absolute (`$A1`)
+    #[serde(default)]
+    pub col_absolute: bool,
+    /// Whether the row is absolute (`A$1`)
+    #[serde(default)]
+    pub row_absolute: bool,
+    /// Whether the end column of a range is absolute (`A1:$B2`)
+    #[serde(default)]
+    pub end_col_absolute: bool,
+    /// Whether the end row of a range is absolute (`A1:B$2`)
+    #[serde(default)]
+    pub end_row_absolute: bool,
 }
 
+/// Built-in function implementation used by the evaluator
+type FormulaFunction = Box<dyn Fn(&[Value]) -> Value + Send + Sync>;
+
 /// Excel formula evaluation context containing cell values
 pub struct FormulaContext {
     /// Cell values by reference
     cell_values: HashMap<CellReference, f64>,
     /// Text values by reference (for non-numeric cells)
     text_values: HashMap<CellReference, String>,
+    /// Formulas by the cell that holds them, for dependency ordering
+    formulas: HashMap<CellReference, Formula>,
     /// Function implementations
-    functions: HashMap<String, Box<dyn Fn(&[Value]) -> Result<Value> + Send + Sync>>,
+    functions: HashMap<String, FormulaFunction>,
 }
 
 /// Formula evaluation result
@@ -60,14 +84,35 @@ pub enum FormulaResult {
     Error(String),
 }
 
+/// Formulas that depend on themselves through the listed cells
+///
+/// `cells` runs along the cycle, so each cell is referenced by the one
+/// before it and the first by the last.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CircularReference {
+    pub cells: Vec<CellReference>,
+}
+
+impl fmt::Display for CircularReference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cells: Vec<String> = self.cells.iter().map(ToString::to_string).collect();
+        write!(f, "Circular reference: {}", cells.join(" -> "))?;
+        if let Some(first) = self.cells.first() {
+            write!(f, " -> {}", first)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for CircularReference {}
+
 impl Formula {
     /// Parse a formula string into a Formula structure
     pub fn parse(formula_str: &str) -> Result<Self> {
-        let expression = if formula_str.starts_with('=') {
-            formula_str[1..].to_string()
-        } else {
-            formula_str.to_string()
-        };
+        let expression = formula_str
+            .strip_prefix('=')
+            .unwrap_or(formula_str)
+            .to_string();
 
         let cell_refs = Self::extract_cell_references(&expression)?;
 
@@ -82,21 +127,25 @@ impl Formula {
     fn extract_cell_references(expression: &str) -> Result<Vec<CellReference>> {
         let mut refs = Vec::new();
 
-        // Pattern for cell references: [Sheet!]A1[:B2]
-        let cell_ref_pattern =
-            Regex::new(r"(?:([A-Za-z0-9_]+)!)?([A-Z]+)(\d+)(?::([A-Z]+)(\d+))?")?;
+        // Pattern for cell references: [Sheet!][$]A[$]1[:[$]B[$]2]
+        let cell_ref_pattern = Regex::new(
+            r"(?:([A-Za-z0-9_]+)!)?(\$?)([A-Z]+)(\$?)(\d+)(?::(\$?)([A-Z]+)(\$?)(\d+))?",
+        )?;
+        let anchored = |captures: &regex::Captures, index| {
+            captures.get(index).is_some_and(|m| m.as_str() == "$")
+        };
 
         for captures in cell_ref_pattern.captures_iter(expression) {
             let sheet = captures.get(1).map(|m| m.as_str().to_string());
-            let start_col = Self::column_to_index(captures.get(2).unwrap().as_str())?;
-            let start_row = captures.get(3).unwrap().as_str().parse::<u32>()? - 1; // Convert to 0-based
+            let start_col = Self::column_to_index(captures.get(3).unwrap().as_str())?;
+            let start_row = Self::row_to_index(captures.get(5).unwrap().as_str())?;
 
             let (end_col, end_row) = if let (Some(end_col_match), Some(end_row_match)) =
-                (captures.get(4), captures.get(5))
+                (captures.get(7), captures.get(9))
             {
                 (
                     Some(Self::column_to_index(end_col_match.as_str())?),
-                    Some(end_row_match.as_str().parse::<u32>()? - 1),
+                    Some(Self::row_to_index(end_row_match.as_str())?),
                 )
             } else {
                 (None, None)
@@ -108,6 +157,10 @@ impl Formula {
                 row: start_row,
                 end_col,
                 end_row,
+                col_absolute: anchored(&captures, 2),
+                row_absolute: anchored(&captures, 4),
+                end_col_absolute: anchored(&captures, 6),
+                end_row_absolute: anchored(&captures, 8),
             });
         }
 
@@ -116,172 +169,52 @@ impl Formula {
 
     /// Convert column letters (A, B, AA, etc.) to zero-based index
     fn column_to_index(column: &str) -> Result<u32> {
-        let mut index = 0u32;
-        for (i, c) in column.chars().rev().enumerate() {
-            if !c.is_ascii_uppercase() {
-                return Err(anyhow!("Invalid column letter: {}", c));
-            }
-            index += (c as u32 - 'A' as u32 + 1) * 26_u32.pow(i as u32);
-        }
-        Ok(index - 1) // Convert to 0-based
+        column_letter_to_index(column).ok_or_else(|| anyhow!("Invalid column: {}", column))
+    }
+
+    /// Convert a one-based row number to a zero-based index
+    fn row_to_index(row: &str) -> Result<u32> {
+        row.parse::<u32>()
+            .ok()
+            .and_then(|row| row.checked_sub(1))
+            .ok_or_else(|| anyhow!("Invalid row: {}", row))
     }
 
     /// Evaluate the formula using the provided context
+    ///
+    /// Problems with the formula itself, such as an unknown function or a
+    /// lookup that finds nothing, are returned as Excel error values
+    /// (`#N/A`, `#VALUE!`, ...) in [`FormulaResult::Error`].
     pub fn evaluate(&self, context: &FormulaContext) -> Result<FormulaResult> {
         debug!("Evaluating formula: {}", self.expression);
 
-        // Create evaluation context with cell values and functions
-        let mut eval_context = HashMapContext::new();
-
-        // Add cell values to context
-        for cell_ref in &self.cell_refs {
-            let var_name = format!(
-                "{}_{}",
-                Self::index_to_column(cell_ref.col)?,
-                cell_ref.row + 1
-            );
-
-            if let Some(value) = context.cell_values.get(cell_ref) {
-                eval_context.set_value(var_name, Value::Float(*value))?;
-            } else if let Some(text) = context.text_values.get(cell_ref) {
-                eval_context.set_value(var_name, Value::String(text.clone()))?;
-            } else {
-                eval_context.set_value(var_name, Value::Float(0.0))?; // Default to 0 for empty cells
-            }
-        }
-
-        // Replace Excel-style cell references with variable names in expression
-        let mut eval_expression = self.expression.clone();
-        for cell_ref in &self.cell_refs {
-            let original = if let Some(ref sheet) = cell_ref.sheet {
-                format!(
-                    "{}!{}{}",
-                    sheet,
-                    Self::index_to_column(cell_ref.col)?,
-                    cell_ref.row + 1
-                )
-            } else {
-                format!(
-                    "{}{}",
-                    Self::index_to_column(cell_ref.col)?,
-                    cell_ref.row + 1
-                )
-            };
-
-            let var_name = format!(
-                "{}_{}",
-                Self::index_to_column(cell_ref.col)?,
-                cell_ref.row + 1
-            );
-
-            eval_expression = eval_expression.replace(&original, &var_name);
-        }
-
-        // Handle Excel functions by converting to evalexpr-compatible functions
-        eval_expression = Self::convert_excel_functions(eval_expression, context)?;
-
-        // Evaluate the expression
-        match eval_with_context(&eval_expression, &eval_context) {
-            Ok(Value::Float(f)) => Ok(FormulaResult::Number(f)),
-            Ok(Value::Int(i)) => Ok(FormulaResult::Number(i as f64)),
-            Ok(Value::String(s)) => Ok(FormulaResult::Text(s)),
-            Ok(Value::Boolean(b)) => Ok(FormulaResult::Boolean(b)),
-            Ok(_) => Ok(FormulaResult::Error("Unsupported result type".to_string())),
-            Err(e) => Ok(FormulaResult::Error(format!(
-                "Formula evaluation error: {}",
-                e
-            ))),
-        }
-    }
-
-    /// Convert Excel functions to evalexpr-compatible expressions
-    fn convert_excel_functions(
-        mut expression: String,
-        _context: &FormulaContext,
-    ) -> Result<String> {
-        // Handle SUM function: SUM(A1:A10) -> (A1_1 + A1_2 + ... + A1_10)
-        let sum_pattern = Regex::new(r"SUM\(([A-Z]+)(\d+):([A-Z]+)(\d+)\)")?;
-        while let Some(captures) = sum_pattern.captures(&expression) {
-            let start_col = Self::column_to_index(captures.get(1).unwrap().as_str())?;
-            let start_row: u32 = captures.get(2).unwrap().as_str().parse::<u32>()? - 1;
-            let end_col = Self::column_to_index(captures.get(3).unwrap().as_str())?;
-            let end_row: u32 = captures.get(4).unwrap().as_str().parse::<u32>()? - 1;
-
-            let mut sum_terms = Vec::new();
-            for row in start_row..=end_row {
-                for col in start_col..=end_col {
-                    sum_terms.push(format!("{}_{}", Self::index_to_column(col)?, row + 1));
-                }
-            }
-
-            let replacement = if sum_terms.is_empty() {
-                "0".to_string()
-            } else {
-                format!("({})", sum_terms.join(" + "))
-            };
-
-            expression = sum_pattern
-                .replace(&expression, replacement.as_str())
-                .to_string();
-        }
-
-        // Handle AVERAGE function: AVERAGE(A1:A10) -> (A1_1 + ... + A1_10) / count
-        let avg_pattern = Regex::new(r"AVERAGE\(([A-Z]+)(\d+):([A-Z]+)(\d+)\)")?;
-        while let Some(captures) = avg_pattern.captures(&expression) {
-            let start_col = Self::column_to_index(captures.get(1).unwrap().as_str())?;
-            let start_row: u32 = captures.get(2).unwrap().as_str().parse::<u32>()? - 1;
-            let end_col = Self::column_to_index(captures.get(3).unwrap().as_str())?;
-            let end_row: u32 = captures.get(4).unwrap().as_str().parse::<u32>()? - 1;
-
-            let mut sum_terms = Vec::new();
-            for row in start_row..=end_row {
-                for col in start_col..=end_col {
-                    sum_terms.push(format!("{}_{}", Self::index_to_column(col)?, row + 1));
-                }
+        let value = match Parser::parse(&self.expression) {
+            Ok(expr) => context.eval(&expr),
+            Err(e) => {
+                debug!("Formula parse error: {}", e);
+                Value::Error(NAME_ERROR)
             }
+        };
 
-            let replacement = if sum_terms.is_empty() {
-                "0".to_string()
-            } else {
-                format!("({}) / {}", sum_terms.join(" + "), sum_terms.len())
-            };
-
-            expression = avg_pattern
-                .replace(&expression, replacement.as_str())
-                .to_string();
-        }
-
-        // Handle COUNT function: COUNT(A1:A10) -> count of non-empty cells
-        let count_pattern = Regex::new(r"COUNT\(([A-Z]+)(\d+):([A-Z]+)(\d+)\)")?;
-        while let Some(captures) = count_pattern.captures(&expression) {
-            let start_col = Self::column_to_index(captures.get(1).unwrap().as_str())?;
-            let start_row: u32 = captures.get(2).unwrap().as_str().parse::<u32>()? - 1;
-            let end_col = Self::column_to_index(captures.get(3).unwrap().as_str())?;
-            let end_row: u32 = captures.get(4).unwrap().as_str().parse::<u32>()? - 1;
-
-            let cell_count = (end_row - start_row + 1) * (end_col - start_col + 1);
-            let replacement = cell_count.to_string();
-
-            expression = count_pattern
-                .replace(&expression, replacement.as_str())
-                .to_string();
-        }
-
-        Ok(expression)
+        Ok(match value {
+            Value::Number(n) => FormulaResult::Number(n),
+            Value::Text(t) => FormulaResult::Text(t),
+            Value::Bool(b) => FormulaResult::Boolean(b),
+            Value::Empty => FormulaResult::Number(0.0),
+            Value::Range(_) => FormulaResult::Error(VALUE_ERROR.to_string()),
+            Value::Error(code) => FormulaResult::Error(code.to_string()),
+        })
     }
 
     /// Convert zero-based column index to Excel column letters
-    pub fn index_to_column(mut index: u32) -> Result<String> {
-        let mut column = String::new();
-        index += 1; // Convert to 1-based for Excel
-
-        while index > 0 {
-            index -= 1;
-            column = char::from((index % 26) as u8 + b'A').to_string() + &column;
-            index /= 26;
-        }
+    pub fn index_to_column(index: u32) -> Result<String> {
+        Ok(index_to_column_letter(index))
+    }
+}
 
-        Ok(column)
+impl Default for FormulaContext {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -291,6 +224,7 @@ impl FormulaContext {
         let mut context = FormulaContext {
             cell_values: HashMap::new(),
             text_values: HashMap::new(),
+            formulas: HashMap::new(),
             functions: HashMap::new(),
         };
 
@@ -308,56 +242,277 @@ impl FormulaContext {
         self.text_values.insert(cell_ref, text);
     }
 
+    /// Add the formula held by a cell, to be ordered by
+    /// [`FormulaContext::build_dependency_order`]
+    pub fn set_cell_formula(&mut self, cell_ref: CellReference, formula: Formula) {
+        self.formulas.insert(cell_ref, formula);
+    }
+
+    /// Formula registered for a cell
+    pub fn cell_formula(&self, cell_ref: &CellReference) -> Option<&Formula> {
+        self.formulas.get(cell_ref)
+    }
+
+    /// Forget the formula of a cell, e.g. one that cannot be evaluated
+    pub fn remove_cell_formula(&mut self, cell_ref: &CellReference) -> Option<Formula> {
+        self.formulas.remove(cell_ref)
+    }
+
+    /// Record an evaluated formula so formulas that reference its cell
+    /// read the result
+    pub fn set_cell_result(&mut self, cell_ref: CellReference, result: &FormulaResult) {
+        match result {
+            FormulaResult::Number(n) => self.set_cell_value(cell_ref, *n),
+            FormulaResult::Boolean(b) => self.set_cell_value(cell_ref, if *b { 1.0 } else { 0.0 }),
+            FormulaResult::Text(text) | FormulaResult::Error(text) => {
+                self.set_cell_text(cell_ref, text.clone())
+            }
+        }
+    }
+
+    /// Formula cells ordered so every formula comes after the formulas it
+    /// references
+    ///
+    /// Cells that do not depend on each other keep row-major order. Fails
+    /// with the first cycle found when formulas reference themselves,
+    /// directly or through other formulas.
+    pub fn build_dependency_order(
+        &self,
+    ) -> std::result::Result<Vec<CellReference>, CircularReference> {
+        let mut cells: Vec<&CellReference> = self.formulas.keys().collect();
+        cells.sort_by_key(|cell| (cell.row, cell.col, cell.sheet.clone()));
+
+        let mut order = Vec::with_capacity(cells.len());
+        let mut done: HashSet<&CellReference> = HashSet::new();
+        // Cells on the current path, to tell a cycle from a shared dependency
+        let mut visiting: HashSet<&CellReference> = HashSet::new();
+
+        for start in cells {
+            if done.contains(start) {
+                continue;
+            }
+            // Depth-first without recursion, so long chains of formulas
+            // cannot overflow the stack
+            let mut stack = vec![(start, self.dependencies(start), 0)];
+            visiting.insert(start);
+
+            while let Some((cell, dependencies, next)) = stack.last_mut() {
+                let Some(&dependency) = dependencies.get(*next) else {
+                    let cell = *cell;
+                    visiting.remove(cell);
+                    done.insert(cell);
+                    order.push(cell.clone());
+                    stack.pop();
+                    continue;
+                };
+                *next += 1;
+
+                if visiting.contains(dependency) {
+                    let from = stack
+                        .iter()
+                        .position(|(cell, _, _)| *cell == dependency)
+                        .unwrap_or(0);
+                    return Err(CircularReference {
+                        cells: stack[from..]
+                            .iter()
+                            .map(|(cell, _, _)| (*cell).clone())
+                            .collect(),
+                    });
+                }
+                if !done.contains(dependency) {
+                    visiting.insert(dependency);
+                    stack.push((dependency, self.dependencies(dependency), 0));
+                }
+            }
+        }
+
+        Ok(order)
+    }
+
+    /// Formula cells that the formula in `cell_ref` reads, in row-major order
+    fn dependencies(&self, cell_ref: &CellReference) -> Vec<&CellReference> {
+        let mut dependencies: Vec<&CellReference> = self.formulas[cell_ref]
+            .cell_refs
+            .iter()
+            .flat_map(CellReference::expand_range)
+            .filter_map(|referenced| {
+                // Like `cell_value`, a qualified reference falls back to the
+                // unqualified cell
+                let referenced = referenced.without_anchors();
+                let unqualified = CellReference::new_single(None, referenced.col, referenced.row);
+                self.formulas
+                    .get_key_value(&referenced)
+                    .or_else(|| self.formulas.get_key_value(&unqualified))
+                    .map(|(key, _)| key)
+            })
+            .collect();
+        dependencies.sort_by_key(|cell| (cell.row, cell.col));
+        dependencies.dedup();
+        dependencies
+    }
+
     /// Register standard Excel functions
+    ///
+    /// `IF` is not registered here because only the chosen branch may be
+    /// evaluated; see [`FormulaContext::eval`].
     fn register_standard_functions(&mut self) {
-        // IF function
         self.functions.insert(
-            "IF".to_string(),
+            "SUM".to_string(),
             Box::new(|args| {
-                if args.len() != 3 {
-                    return Err(anyhow!("IF function requires exactly 3 arguments"));
-                }
+                numbers(args).map_or_else(Value::Error, |n| Value::Number(n.iter().sum()))
+            }),
+        );
 
-                let condition = args[0].as_boolean().unwrap_or(false);
-                if condition {
-                    Ok(args[1].clone())
-                } else {
-                    Ok(args[2].clone())
-                }
+        self.functions.insert(
+            "AVERAGE".to_string(),
+            Box::new(|args| match numbers(args) {
+                Ok(n) if n.is_empty() => Value::Error(DIV_ZERO_ERROR),
+                Ok(n) => Value::Number(n.iter().sum::<f64>() / n.len() as f64),
+                Err(code) => Value::Error(code),
             }),
         );
 
-        // MAX function
+        // COUNT only counts numbers, like Excel
         self.functions.insert(
-            "MAX".to_string(),
+            "COUNT".to_string(),
             Box::new(|args| {
-                let mut max_val = f64::NEG_INFINITY;
-                for arg in args {
-                    if let Ok(val) = arg.as_float() {
-                        if val > max_val {
-                            max_val = val;
-                        }
-                    }
-                }
-                Ok(Value::Float(max_val))
+                let count = args
+                    .iter()
+                    .flat_map(Value::flatten)
+                    .filter(|value| matches!(value, Value::Number(_)))
+                    .count();
+                Value::Number(count as f64)
+            }),
+        );
+
+        self.functions.insert(
+            "MAX".to_string(),
+            Box::new(|args| match numbers(args) {
+                Ok(n) => Value::Number(n.into_iter().reduce(f64::max).unwrap_or(0.0)),
+                Err(code) => Value::Error(code),
             }),
         );
 
-        // MIN function
         self.functions.insert(
             "MIN".to_string(),
+            Box::new(|args| match numbers(args) {
+                Ok(n) => Value::Number(n.into_iter().reduce(f64::min).unwrap_or(0.0)),
+                Err(code) => Value::Error(code),
+            }),
+        );
+
+        self.functions.insert(
+            "AND".to_string(),
+            Box::new(|args| {
+                booleans(args).map_or_else(Value::Error, |b| Value::Bool(b.iter().all(|b| *b)))
+            }),
+        );
+
+        self.functions.insert(
+            "OR".to_string(),
             Box::new(|args| {
-                let mut min_val = f64::INFINITY;
+                booleans(args).map_or_else(Value::Error, |b| Value::Bool(b.iter().any(|b| *b)))
+            }),
+        );
+
+        self.functions.insert(
+            "NOT".to_string(),
+            Box::new(|args| match args {
+                [value] => value
+                    .to_bool()
+                    .map_or_else(Value::Error, |b| Value::Bool(!b)),
+                _ => Value::Error(VALUE_ERROR),
+            }),
+        );
+
+        self.functions.insert(
+            "CONCATENATE".to_string(),
+            Box::new(|args| {
+                let mut text = String::new();
                 for arg in args {
-                    if let Ok(val) = arg.as_float() {
-                        if val < min_val {
-                            min_val = val;
-                        }
+                    match arg.to_text() {
+                        Ok(part) => text.push_str(&part),
+                        Err(code) => return Value::Error(code),
                     }
                 }
-                Ok(Value::Float(min_val))
+                Value::Text(text)
             }),
         );
+
+        self.functions
+            .insert("VLOOKUP".to_string(), Box::new(vlookup));
+    }
+
+    /// Value of a single cell; cells that were never set are empty
+    fn cell_value(&self, cell_ref: &CellReference) -> Value {
+        // Fall back to the unqualified cell so `Sheet1!A1` finds values
+        // registered without a sheet
+        let cell_ref = cell_ref.without_anchors();
+        let unqualified = CellReference::new_single(None, cell_ref.col, cell_ref.row);
+        for key in [&cell_ref, &unqualified] {
+            if let Some(value) = self.cell_values.get(key) {
+                return Value::Number(*value);
+            }
+            if let Some(text) = self.text_values.get(key) {
+                return Value::Text(text.clone());
+            }
+        }
+        Value::Empty
+    }
+
+    /// Evaluate a parsed expression
+    fn eval(&self, expr: &Expr) -> Value {
+        match expr {
+            Expr::Number(n) => Value::Number(*n),
+            Expr::Text(t) => Value::Text(t.clone()),
+            Expr::Bool(b) => Value::Bool(*b),
+            Expr::Ref(cell_ref) if cell_ref.is_single_cell() => self.cell_value(cell_ref),
+            Expr::Ref(cell_ref) => {
+                let end_row = cell_ref.end_row.unwrap_or(cell_ref.row);
+                let end_col = cell_ref.end_col.unwrap_or(cell_ref.col);
+                Value::Range(
+                    (cell_ref.row..=end_row)
+                        .map(|row| {
+                            (cell_ref.col..=end_col)
+                                .map(|col| {
+                                    self.cell_value(&CellReference::new_single(
+                                        cell_ref.sheet.clone(),
+                                        col,
+                                        row,
+                                    ))
+                                })
+                                .collect()
+                        })
+                        .collect(),
+                )
+            }
+            Expr::Negate(inner) => match self.eval(inner).to_number() {
+                Ok(n) => Value::Number(-n),
+                Err(code) => Value::Error(code),
+            },
+            Expr::Binary(op, left, right) => binary(*op, self.eval(left), self.eval(right)),
+            Expr::Call(name, args) if name == "IF" => {
+                if !(2..=3).contains(&args.len()) {
+                    return Value::Error(VALUE_ERROR);
+                }
+                match self.eval(&args[0]).to_bool() {
+                    Ok(true) => self.eval(&args[1]),
+                    Ok(false) => args.get(2).map_or(Value::Bool(false), |e| self.eval(e)),
+                    Err(code) => Value::Error(code),
+                }
+            }
+            Expr::Call(name, args) => match self.functions.get(name) {
+                Some(function) => {
+                    let args: Vec<Value> = args.iter().map(|arg| self.eval(arg)).collect();
+                    // Errors in arguments propagate, as in Excel
+                    match args.iter().find_map(Value::error) {
+                        Some(code) => Value::Error(code),
+                        None => function(&args),
+                    }
+                }
+                None => Value::Error(NAME_ERROR),
+            },
+        }
     }
 }
 
@@ -370,6 +525,10 @@ impl CellReference {
             row,
             end_col: None,
             end_row: None,
+            col_absolute: false,
+            row_absolute: false,
+            end_col_absolute: false,
+            end_row_absolute: false,
         }
     }
 
@@ -387,6 +546,29 @@ impl CellReference {
             row: start_row,
             end_col: Some(end_col),
             end_row: Some(end_row),
+            col_absolute: false,
+            row_absolute: false,
+            end_col_absolute: false,
+            end_row_absolute: false,
+        }
+    }
+
+    /// Whether every column and row of the reference is absolute, as in
+    /// `$A$1` or `$A$1:$B$2`
+    pub fn is_absolute(&self) -> bool {
+        self.col_absolute
+            && self.row_absolute
+            && (self.is_single_cell() || (self.end_col_absolute && self.end_row_absolute))
+    }
+
+    /// The same cells with every `$` anchor dropped
+    pub fn without_anchors(&self) -> CellReference {
+        CellReference {
+            col_absolute: false,
+            row_absolute: false,
+            end_col_absolute: false,
+            end_row_absolute: false,
+            ..self.clone()
         }
     }
 
@@ -421,22 +603,28 @@ impl CellReference {
 
 impl fmt::Display for CellReference {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let anchor = |absolute| if absolute { "$" } else { "" };
+
         if let Some(ref sheet) = self.sheet {
             write!(f, "{}!", sheet)?;
         }
 
         write!(
             f,
-            "{}{}",
-            Formula::index_to_column(self.col).unwrap_or_default(),
+            "{}{}{}{}",
+            anchor(self.col_absolute),
+            index_to_column_letter(self.col),
+            anchor(self.row_absolute),
             self.row + 1
         )?;
 
         if let (Some(end_col), Some(end_row)) = (self.end_col, self.end_row) {
             write!(
                 f,
-                ":{}{}",
-                Formula::index_to_column(end_col).unwrap_or_default(),
+                ":{}{}{}{}",
+                anchor(self.end_col_absolute),
+                index_to_column_letter(end_col),
+                anchor(self.end_row_absolute),
                 end_row + 1
             )?;
         }
@@ -451,11 +639,534 @@ impl fmt::Display for FormulaResult {
             FormulaResult::Number(n) => write!(f, "{}", n),
             FormulaResult::Text(t) => write!(f, "{}", t),
             FormulaResult::Boolean(b) => write!(f, "{}", if *b { "TRUE" } else { "FALSE" }),
-            FormulaResult::Error(e) => write!(f, "#ERROR: {}", e),
+            FormulaResult::Error(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+const NA_ERROR: &str = "#N/A";
+pub(crate) const VALUE_ERROR: &str = "#VALUE!";
+pub(crate) const REF_ERROR: &str = "#REF!";
+const NAME_ERROR: &str = "#NAME?";
+/// Value of a cell whose formula depends on itself. Excel has no error
+/// value for this, so it gets a code of its own.
+pub(crate) const CIRCULAR_ERROR: &str = "#CIRCULAR!";
+const DIV_ZERO_ERROR: &str = "#DIV/0!";
+
+/// Value produced while evaluating a formula
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Number(f64),
+    Text(String),
+    Bool(bool),
+    Empty,
+    /// Cells of a range reference, by row
+    Range(Vec<Vec<Value>>),
+    /// Excel error value such as `#N/A`
+    Error(&'static str),
+}
+
+impl Value {
+    fn error(&self) -> Option<&'static str> {
+        match self {
+            Value::Error(code) => Some(code),
+            _ => None,
+        }
+    }
+
+    /// Cells of a range, or the value itself
+    fn flatten(&self) -> Vec<Value> {
+        match self {
+            Value::Range(rows) => rows.iter().flatten().cloned().collect(),
+            value => vec![value.clone()],
+        }
+    }
+
+    fn to_number(&self) -> std::result::Result<f64, &'static str> {
+        match self {
+            Value::Number(n) => Ok(*n),
+            Value::Bool(b) => Ok(if *b { 1.0 } else { 0.0 }),
+            Value::Empty => Ok(0.0),
+            Value::Text(t) => t.trim().parse().map_err(|_| VALUE_ERROR),
+            Value::Range(_) => Err(VALUE_ERROR),
+            Value::Error(code) => Err(code),
+        }
+    }
+
+    fn to_text(&self) -> std::result::Result<String, &'static str> {
+        match self {
+            Value::Number(n) => Ok(n.to_string()),
+            Value::Text(t) => Ok(t.clone()),
+            Value::Bool(b) => Ok(if *b { "TRUE" } else { "FALSE" }.to_string()),
+            Value::Empty => Ok(String::new()),
+            Value::Range(_) => Err(VALUE_ERROR),
+            Value::Error(code) => Err(code),
+        }
+    }
+
+    fn to_bool(&self) -> std::result::Result<bool, &'static str> {
+        match self {
+            Value::Bool(b) => Ok(*b),
+            Value::Number(n) => Ok(*n != 0.0),
+            Value::Empty => Ok(false),
+            Value::Text(t) if t.eq_ignore_ascii_case("TRUE") => Ok(true),
+            Value::Text(t) if t.eq_ignore_ascii_case("FALSE") => Ok(false),
+            Value::Text(_) | Value::Range(_) => Err(VALUE_ERROR),
+            Value::Error(code) => Err(code),
+        }
+    }
+
+    /// Excel ordering: numbers before text before booleans, text compared
+    /// case-insensitively. Empty cells compare like zero or empty text.
+    fn compare(&self, other: &Value) -> Ordering {
+        fn rank(value: &Value) -> u8 {
+            match value {
+                Value::Number(_) | Value::Empty => 0,
+                Value::Text(_) => 1,
+                _ => 2,
+            }
+        }
+
+        match (self, other) {
+            (Value::Empty, Value::Text(t)) => "".cmp(t.as_str()),
+            (Value::Text(t), Value::Empty) => t.as_str().cmp(""),
+            (Value::Text(a), Value::Text(b)) => a.to_lowercase().cmp(&b.to_lowercase()),
+            (Value::Bool(a), Value::Bool(b)) => a.cmp(b),
+            (a, b) if rank(a) == 0 && rank(b) == 0 => {
+                let (a, b) = (a.to_number().unwrap_or(0.0), b.to_number().unwrap_or(0.0));
+                a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+            }
+            (a, b) => rank(a).cmp(&rank(b)),
+        }
+    }
+}
+
+/// All numbers among the arguments; ranges skip text and empty cells
+fn numbers(args: &[Value]) -> std::result::Result<Vec<f64>, &'static str> {
+    let mut result = Vec::new();
+    for arg in args {
+        match arg {
+            Value::Range(_) => result.extend(arg.flatten().iter().filter_map(|v| match v {
+                Value::Number(n) => Some(*n),
+                _ => None,
+            })),
+            value => result.push(value.to_number()?),
+        }
+    }
+    Ok(result)
+}
+
+/// All arguments as booleans; ranges skip text and empty cells
+fn booleans(args: &[Value]) -> std::result::Result<Vec<bool>, &'static str> {
+    let mut result = Vec::new();
+    for arg in args {
+        match arg {
+            Value::Range(_) => {
+                for value in arg.flatten() {
+                    if matches!(value, Value::Number(_) | Value::Bool(_)) {
+                        result.push(value.to_bool()?);
+                    }
+                }
+            }
+            value => result.push(value.to_bool()?),
+        }
+    }
+    Ok(result)
+}
+
+/// `VLOOKUP(lookup_value, table, column, [approximate])`
+///
+/// Exact matching compares text case-insensitively. Approximate matching,
+/// the default, expects the first column to be sorted ascending and picks the
+/// last row not greater than the lookup value.
+fn vlookup(args: &[Value]) -> Value {
+    let (lookup, table, column, approximate) = match args {
+        [lookup, table, column] => (lookup, table, column, Ok(true)),
+        [lookup, table, column, approximate] => (lookup, table, column, approximate.to_bool()),
+        _ => return Value::Error(VALUE_ERROR),
+    };
+    let Value::Range(rows) = table else {
+        return Value::Error(VALUE_ERROR);
+    };
+    let (column, approximate) = match (column.to_number(), approximate) {
+        (Ok(column), Ok(approximate)) => (column.trunc(), approximate),
+        (Err(code), _) | (_, Err(code)) => return Value::Error(code),
+    };
+    if column < 1.0 {
+        return Value::Error(VALUE_ERROR);
+    }
+    let column = column as usize - 1;
+    if rows.first().map_or(0, Vec::len) <= column {
+        return Value::Error(REF_ERROR);
+    }
+
+    let row = if approximate {
+        rows.iter()
+            .take_while(|row| row[0].compare(lookup) != Ordering::Greater)
+            .last()
+    } else {
+        rows.iter()
+            .find(|row| row[0].compare(lookup) == Ordering::Equal)
+    };
+
+    match row {
+        Some(row) => row[column].clone(),
+        None => Value::Error(NA_ERROR),
+    }
+}
+
+/// Apply a binary operator
+fn binary(op: BinaryOp, left: Value, right: Value) -> Value {
+    if let Some(code) = left.error().or(right.error()) {
+        return Value::Error(code);
+    }
+
+    match op {
+        BinaryOp::Concat => match (left.to_text(), right.to_text()) {
+            (Ok(left), Ok(right)) => Value::Text(left + &right),
+            (Err(code), _) | (_, Err(code)) => Value::Error(code),
+        },
+        BinaryOp::Eq => Value::Bool(left.compare(&right) == Ordering::Equal),
+        BinaryOp::Ne => Value::Bool(left.compare(&right) != Ordering::Equal),
+        BinaryOp::Lt => Value::Bool(left.compare(&right) == Ordering::Less),
+        BinaryOp::Le => Value::Bool(left.compare(&right) != Ordering::Greater),
+        BinaryOp::Gt => Value::Bool(left.compare(&right) == Ordering::Greater),
+        BinaryOp::Ge => Value::Bool(left.compare(&right) != Ordering::Less),
+        BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div | BinaryOp::Pow => {
+            let (left, right) = match (left.to_number(), right.to_number()) {
+                (Ok(left), Ok(right)) => (left, right),
+                (Err(code), _) | (_, Err(code)) => return Value::Error(code),
+            };
+            match op {
+                BinaryOp::Add => Value::Number(left + right),
+                BinaryOp::Sub => Value::Number(left - right),
+                BinaryOp::Mul => Value::Number(left * right),
+                BinaryOp::Div if right == 0.0 => Value::Error(DIV_ZERO_ERROR),
+                BinaryOp::Div => Value::Number(left / right),
+                _ => Value::Number(left.powf(right)),
+            }
         }
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Pow,
+    Concat,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl BinaryOp {
+    /// Binding strength, following Excel's operator precedence
+    fn precedence(self) -> u8 {
+        match self {
+            BinaryOp::Eq
+            | BinaryOp::Ne
+            | BinaryOp::Lt
+            | BinaryOp::Le
+            | BinaryOp::Gt
+            | BinaryOp::Ge => 1,
+            BinaryOp::Concat => 2,
+            BinaryOp::Add | BinaryOp::Sub => 3,
+            BinaryOp::Mul | BinaryOp::Div => 4,
+            BinaryOp::Pow => 5,
+        }
+    }
+}
+
+/// Parsed formula expression
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Number(f64),
+    Text(String),
+    Bool(bool),
+    Ref(CellReference),
+    Negate(Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+    /// Function call with the name in upper case
+    Call(String, Vec<Expr>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Text(String),
+    /// Function name, boolean or cell reference
+    Name(String),
+    Op(BinaryOp),
+    Minus,
+    Plus,
+    LParen,
+    RParen,
+    Comma,
+    Colon,
+}
+
+/// Recursive descent parser for formula expressions
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn parse(expression: &str) -> Result<Expr> {
+        let mut parser = Parser {
+            tokens: Self::tokenize(expression)?,
+            pos: 0,
+        };
+        let expr = parser.expression(0)?;
+        if parser.pos != parser.tokens.len() {
+            return Err(anyhow!("Unexpected token {:?}", parser.tokens[parser.pos]));
+        }
+        Ok(expr)
+    }
+
+    fn tokenize(expression: &str) -> Result<Vec<Token>> {
+        let chars: Vec<char> = expression.chars().collect();
+        let mut tokens = Vec::new();
+        let mut i = 0;
+
+        while i < chars.len() {
+            let c = chars[i];
+            let next = chars.get(i + 1).copied();
+            i += 1;
+
+            let token = match c {
+                c if c.is_whitespace() => continue,
+                '"' => {
+                    // Doubled quotes stand for a literal quote
+                    let mut text = String::new();
+                    loop {
+                        match chars.get(i) {
+                            Some('"') if chars.get(i + 1) == Some(&'"') => {
+                                text.push('"');
+                                i += 2;
+                            }
+                            Some('"') => {
+                                i += 1;
+                                break;
+                            }
+                            Some(c) => {
+                                text.push(*c);
+                                i += 1;
+                            }
+                            None => return Err(anyhow!("Unterminated string")),
+                        }
+                    }
+                    Token::Text(text)
+                }
+                c if c.is_ascii_digit() || c == '.' => {
+                    let start = i - 1;
+                    while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                        i += 1;
+                    }
+                    let number: String = chars[start..i].iter().collect();
+                    Token::Number(number.parse()?)
+                }
+                '\'' => {
+                    // Quoted sheet name, kept with its quotes until the '!'
+                    let start = i - 1;
+                    while i < chars.len() && chars[i] != '\'' {
+                        i += 1;
+                    }
+                    i += 1;
+                    let mut name: String = chars[start..i.min(chars.len())].iter().collect();
+                    while i < chars.len()
+                        && (chars[i].is_alphanumeric() || "!$_.".contains(chars[i]))
+                    {
+                        name.push(chars[i]);
+                        i += 1;
+                    }
+                    Token::Name(name)
+                }
+                c if c.is_alphabetic() || c == '_' || c == '$' => {
+                    let start = i - 1;
+                    while i < chars.len()
+                        && (chars[i].is_alphanumeric() || "!$_.".contains(chars[i]))
+                    {
+                        i += 1;
+                    }
+                    Token::Name(chars[start..i].iter().collect())
+                }
+                '+' => Token::Plus,
+                '-' => Token::Minus,
+                '*' => Token::Op(BinaryOp::Mul),
+                '/' => Token::Op(BinaryOp::Div),
+                '^' => Token::Op(BinaryOp::Pow),
+                '&' => Token::Op(BinaryOp::Concat),
+                '=' => Token::Op(BinaryOp::Eq),
+                '<' if next == Some('>') => {
+                    i += 1;
+                    Token::Op(BinaryOp::Ne)
+                }
+                '<' if next == Some('=') => {
+                    i += 1;
+                    Token::Op(BinaryOp::Le)
+                }
+                '>' if next == Some('=') => {
+                    i += 1;
+                    Token::Op(BinaryOp::Ge)
+                }
+                '<' => Token::Op(BinaryOp::Lt),
+                '>' => Token::Op(BinaryOp::Gt),
+                '(' => Token::LParen,
+                ')' => Token::RParen,
+                ',' => Token::Comma,
+                ':' => Token::Colon,
+                c => return Err(anyhow!("Unexpected character '{}'", c)),
+            };
+            tokens.push(token);
+        }
+
+        Ok(tokens)
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<()> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            other => Err(anyhow!("Expected {:?}, found {:?}", expected, other)),
+        }
+    }
+
+    /// Parse operators binding at least as tightly as `min_precedence`
+    fn expression(&mut self, min_precedence: u8) -> Result<Expr> {
+        let mut left = self.unary()?;
+
+        loop {
+            let op = match self.peek() {
+                Some(Token::Op(op)) => *op,
+                Some(Token::Plus) => BinaryOp::Add,
+                Some(Token::Minus) => BinaryOp::Sub,
+                _ => break,
+            };
+            if op.precedence() < min_precedence {
+                break;
+            }
+            self.pos += 1;
+            let right = self.expression(op.precedence() + 1)?;
+            left = Expr::Binary(op, Box::new(left), Box::new(right));
+        }
+
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Expr> {
+        match self.peek() {
+            Some(Token::Minus) => {
+                self.pos += 1;
+                Ok(Expr::Negate(Box::new(self.unary()?)))
+            }
+            Some(Token::Plus) => {
+                self.pos += 1;
+                self.unary()
+            }
+            _ => self.primary(),
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expr> {
+        match self.next() {
+            Some(Token::Number(n)) => Ok(Expr::Number(n)),
+            Some(Token::Text(t)) => Ok(Expr::Text(t)),
+            Some(Token::LParen) => {
+                let expr = self.expression(0)?;
+                self.expect(Token::RParen)?;
+                Ok(expr)
+            }
+            Some(Token::Name(name)) if self.peek() == Some(&Token::LParen) => {
+                self.pos += 1;
+                let mut args = Vec::new();
+                if self.peek() == Some(&Token::RParen) {
+                    self.pos += 1;
+                } else {
+                    loop {
+                        args.push(self.expression(0)?);
+                        match self.next() {
+                            Some(Token::Comma) => continue,
+                            Some(Token::RParen) => break,
+                            other => return Err(anyhow!("Expected ',' or ')', found {:?}", other)),
+                        }
+                    }
+                }
+                Ok(Expr::Call(name.to_uppercase(), args))
+            }
+            Some(Token::Name(name)) if name.eq_ignore_ascii_case("TRUE") => Ok(Expr::Bool(true)),
+            Some(Token::Name(name)) if name.eq_ignore_ascii_case("FALSE") => Ok(Expr::Bool(false)),
+            Some(Token::Name(name)) => {
+                let start = Self::cell_reference(&name)?;
+                if self.peek() != Some(&Token::Colon) {
+                    return Ok(Expr::Ref(start));
+                }
+                self.pos += 1;
+                let end = match self.next() {
+                    Some(Token::Name(end)) => Self::cell_reference(&end)?,
+                    other => return Err(anyhow!("Expected range end, found {:?}", other)),
+                };
+                Ok(Expr::Ref(CellReference::new_range(
+                    start.sheet,
+                    start.col.min(end.col),
+                    start.row.min(end.row),
+                    start.col.max(end.col),
+                    start.row.max(end.row),
+                )))
+            }
+            other => Err(anyhow!("Unexpected token {:?}", other)),
+        }
+    }
+
+    /// Parse `A1`, `$B$2` or `Sheet1!C3` into a single cell reference
+    fn cell_reference(name: &str) -> Result<CellReference> {
+        let (sheet, cell) = match name.rsplit_once('!') {
+            Some((sheet, cell)) => (Some(sheet.trim_matches('\'').to_string()), cell),
+            None => (None, name),
+        };
+        let cell = cell.to_uppercase();
+        let (col_absolute, cell) = match cell.strip_prefix('$') {
+            Some(rest) => (true, rest),
+            None => (false, cell.as_str()),
+        };
+        let split = cell
+            .find(|c: char| c.is_ascii_digit() || c == '$')
+            .ok_or_else(|| anyhow!("Unknown name: {}", name))?;
+        let (letters, digits) = cell.split_at(split);
+        let (row_absolute, digits) = match digits.strip_prefix('$') {
+            Some(rest) => (true, rest),
+            None => (false, digits),
+        };
+        let row: u32 = digits
+            .parse()
+            .map_err(|_| anyhow!("Unknown name: {}", name))?;
+        if letters.is_empty() || row == 0 {
+            return Err(anyhow!("Unknown name: {}", name));
+        }
+
+        Ok(CellReference {
+            col_absolute,
+            row_absolute,
+            ..CellReference::new_single(sheet, Formula::column_to_index(letters)?, row - 1)
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -554,4 +1265,194 @@ mod tests {
         let result = simple_formula.evaluate(&context).unwrap();
         assert_eq!(result, FormulaResult::Number(15.0));
     }
+
+    #[test]
+    fn test_vlookup() {
+        let mut context = FormulaContext::new();
+        for (row, (code, price)) in [("apple", 1.5), ("banana", 0.5), ("cherry", 4.0)]
+            .into_iter()
+            .enumerate()
+        {
+            context.set_cell_text(
+                CellReference::new_single(None, 0, row as u32),
+                code.to_string(),
+            );
+            context.set_cell_value(CellReference::new_single(None, 1, row as u32), price);
+        }
+
+        let evaluate = |formula: &str| Formula::parse(formula).unwrap().evaluate(&context).unwrap();
+
+        assert_eq!(
+            evaluate("=VLOOKUP(\"Banana\", A1:B3, 2, FALSE)"),
+            FormulaResult::Number(0.5)
+        );
+        assert_eq!(
+            evaluate("=VLOOKUP(\"cherry\", $A$1:$B$3, 1, FALSE)"),
+            FormulaResult::Text("cherry".to_string())
+        );
+        assert_eq!(
+            evaluate("=VLOOKUP(\"durian\", A1:B3, 2, FALSE)"),
+            FormulaResult::Error("#N/A".to_string())
+        );
+        assert_eq!(
+            evaluate("=VLOOKUP(\"apple\", A1:B3, 3, FALSE)"),
+            FormulaResult::Error("#REF!".to_string())
+        );
+        assert_eq!(
+            evaluate("=VLOOKUP(\"apple\", A1, 1, FALSE)"),
+            FormulaResult::Error("#VALUE!".to_string())
+        );
+    }
+
+    #[test]
+    fn test_concatenation() {
+        let mut context = FormulaContext::new();
+        context.set_cell_text(CellReference::new_single(None, 0, 0), "Hello".to_string());
+        context.set_cell_value(CellReference::new_single(None, 1, 0), 42.0);
+
+        let evaluate = |formula: &str| Formula::parse(formula).unwrap().evaluate(&context).unwrap();
+
+        assert_eq!(
+            evaluate("=A1 & \", \" & \"world\""),
+            FormulaResult::Text("Hello, world".to_string())
+        );
+        assert_eq!(
+            evaluate("=CONCATENATE(A1, \" #\", B1 + 1)"),
+            FormulaResult::Text("Hello #43".to_string())
+        );
+        assert_eq!(
+            evaluate("=\"Say \"\"hi\"\"\""),
+            FormulaResult::Text("Say \"hi\"".to_string())
+        );
+        assert_eq!(
+            evaluate("=A1 + 1"),
+            FormulaResult::Error("#VALUE!".to_string())
+        );
+    }
+
+    #[test]
+    fn test_nested_if() {
+        let formula = Formula::parse(
+            "=IF(A1 >= 90, \"A\", IF(A1 >= 80, \"B\", IF(A1 <> 0, \"C\", \"none\")))",
+        )
+        .unwrap();
+
+        for (score, grade) in [(95.0, "A"), (80.0, "B"), (42.0, "C"), (0.0, "none")] {
+            let mut context = FormulaContext::new();
+            context.set_cell_value(CellReference::new_single(None, 0, 0), score);
+
+            assert_eq!(
+                formula.evaluate(&context).unwrap(),
+                FormulaResult::Text(grade.to_string()),
+                "score {}",
+                score
+            );
+        }
+
+        // Only the chosen branch is evaluated
+        let context = FormulaContext::new();
+        let formula = Formula::parse("=IF(1 < 2, 2 * 3 + 1, 1 / 0)").unwrap();
+        assert_eq!(
+            formula.evaluate(&context).unwrap(),
+            FormulaResult::Number(7.0)
+        );
+
+        let formula = Formula::parse("=NOSUCHFUNCTION(1)").unwrap();
+        assert_eq!(
+            formula.evaluate(&context).unwrap(),
+            FormulaResult::Error("#NAME?".to_string())
+        );
+    }
+
+    fn formula_context(formulas: &[(&str, &str)]) -> FormulaContext {
+        let mut context = FormulaContext::new();
+        for (cell, formula) in formulas {
+            context.set_cell_formula(
+                Parser::cell_reference(cell).unwrap(),
+                Formula::parse(formula).unwrap(),
+            );
+        }
+        context
+    }
+
+    #[test]
+    fn test_dependency_order_follows_chain() {
+        // C1 needs B1, which needs A2; A1 stands alone
+        let context = formula_context(&[
+            ("C1", "=B1 * 2"),
+            ("A1", "=1 + 1"),
+            ("B1", "=SUM(A2:A3) + 1"),
+            ("A2", "=10"),
+        ]);
+
+        let order: Vec<String> = context
+            .build_dependency_order()
+            .unwrap()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(order, vec!["A1", "A2", "B1", "C1"]);
+    }
+
+    #[test]
+    fn test_dependency_order_detects_cycle() {
+        let context = formula_context(&[
+            ("A1", "=C1 + 1"),
+            ("B1", "=A1 + 1"),
+            ("C1", "=B1 + 1"),
+            ("D1", "=A1"),
+        ]);
+
+        let cycle = context.build_dependency_order().unwrap_err();
+        let cells: Vec<String> = cycle.cells.iter().map(ToString::to_string).collect();
+        assert_eq!(cells, vec!["A1", "C1", "B1"]);
+        assert_eq!(
+            cycle.to_string(),
+            "Circular reference: A1 -> C1 -> B1 -> A1"
+        );
+
+        // A formula that reads its own cell is a cycle of one
+        let context = formula_context(&[("A1", "=A1 + 1")]);
+        assert_eq!(context.build_dependency_order().unwrap_err().cells.len(), 1);
+    }
+
+    #[test]
+    fn test_absolute_references() {
+        let cases = [
+            ("$A$1", true, true),
+            ("A$1", false, true),
+            ("$A1", true, false),
+            ("A1", false, false),
+        ];
+        for (text, col_absolute, row_absolute) in cases {
+            let formula = Formula::parse(&format!("={} * 2", text)).unwrap();
+            let cell_ref = &formula.cell_refs[0];
+            assert_eq!(
+                (cell_ref.col, cell_ref.row),
+                (0, 0),
+                "{} points at the wrong cell",
+                text
+            );
+            assert_eq!(cell_ref.col_absolute, col_absolute, "{}", text);
+            assert_eq!(cell_ref.row_absolute, row_absolute, "{}", text);
+            assert_eq!(cell_ref.to_string(), text);
+            assert_eq!(Parser::cell_reference(text).unwrap(), *cell_ref);
+        }
+
+        let formula = Formula::parse("=SUM(Data!$B2:C$10)").unwrap();
+        let range = &formula.cell_refs[0];
+        assert!(range.col_absolute && !range.row_absolute);
+        assert!(!range.end_col_absolute && range.end_row_absolute);
+        assert!(!range.is_absolute());
+        assert_eq!(range.to_string(), "Data!$B2:C$10");
+
+        // The anchors survive serialization and do not change the value
+        let json = serde_json::to_string(&formula).unwrap();
+        assert_eq!(serde_json::from_str::<Formula>(&json).unwrap(), formula);
+
+        let mut context = FormulaContext::new();
+        context.set_cell_value(CellReference::new_single(None, 0, 0), 21.0);
+        let result = Formula::parse("=$A$1 * 2").unwrap().evaluate(&context);
+        assert_eq!(result.unwrap(), FormulaResult::Number(42.0));
+    }
 }
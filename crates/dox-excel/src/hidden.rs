@@ -0,0 +1,149 @@
+//! Hidden rows and columns
+//!
+//! calamine reports sheet visibility but not the `hidden` flags of the
+//! worksheet's `<row>` and `<col>` elements, so those are read from the
+//! worksheet part itself.
+
+use crate::package::{attr, read_part, worksheet_parts};
+use anyhow::{anyhow, Result};
+use dox_core::{Cell, MAX_COLUMNS};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use std::collections::BTreeSet;
+use std::io::{Read, Seek};
+use zip::ZipArchive;
+
+/// Zero-based indexes of a sheet's hidden rows and columns, in ascending order
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct HiddenLines {
+    pub rows: Vec<u32>,
+    pub columns: Vec<u32>,
+}
+
+impl HiddenLines {
+    /// Remove hidden rows and columns from `data`, which starts at the
+    /// zero-based sheet position `origin`
+    pub fn remove_from(&self, data: &mut Vec<Vec<Cell>>, origin: (u32, u32)) {
+        let mut row = origin.0;
+        data.retain(|_| {
+            let keep = self.rows.binary_search(&row).is_err();
+            row += 1;
+            keep
+        });
+
+        if self.columns.is_empty() {
+            return;
+        }
+        for cells in data.iter_mut() {
+            let mut col = origin.1;
+            cells.retain(|_| {
+                let keep = self.columns.binary_search(&col).is_err();
+                col += 1;
+                keep
+            });
+        }
+    }
+}
+
+/// Read the hidden rows and columns of `sheet_name`
+pub(crate) fn hidden_lines<R: Read + Seek>(
+    zip: &mut ZipArchive<R>,
+    sheet_name: &str,
+) -> Result<HiddenLines> {
+    let part = worksheet_parts(zip)?
+        .into_iter()
+        .find(|(name, _)| name == sheet_name)
+        .map(|(_, part)| part)
+        .ok_or_else(|| anyhow!("Sheet '{}' not found", sheet_name))?;
+
+    match read_part(zip, &part)? {
+        Some(xml) => parse_hidden_lines(&xml),
+        None => Ok(HiddenLines::default()),
+    }
+}
+
+/// Collect hidden `<row>` and `<col>` elements of a worksheet part
+fn parse_hidden_lines(xml: &str) -> Result<HiddenLines> {
+    let is_hidden = |value: Option<String>| matches!(value.as_deref(), Some("1" | "true"));
+
+    let mut rows = Vec::new();
+    let mut columns = BTreeSet::new();
+    // `r` is optional on rows, in which case a row follows the previous one
+    let mut next_row = 0;
+
+    let mut reader = Reader::from_str(xml);
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) | Event::Empty(e) => match e.local_name().as_ref() {
+                b"row" => {
+                    let row = attr(&e, "r")
+                        .and_then(|r| r.parse::<u32>().ok())
+                        .map_or(next_row, |r| r.saturating_sub(1));
+                    next_row = row + 1;
+                    if is_hidden(attr(&e, "hidden")) {
+                        rows.push(row);
+                    }
+                }
+                b"col" if is_hidden(attr(&e, "hidden")) => {
+                    let bound = |name| attr(&e, name).and_then(|v| v.parse::<u32>().ok());
+                    if let (Some(min), Some(max)) = (bound("min"), bound("max")) {
+                        // Columns are one-based in the file
+                        columns.extend(min.max(1) - 1..max.min(MAX_COLUMNS));
+                    }
+                }
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    rows.sort_unstable();
+    Ok(HiddenLines {
+        rows,
+        columns: columns.into_iter().collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_hidden_lines() {
+        let xml = r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">
+<cols><col min="1" max="1" width="9"/><col min="3" max="4" hidden="1"/></cols>
+<sheetData>
+<row r="1"><c r="A1"/></row>
+<row r="3" hidden="1"><c r="A3"/></row>
+<row hidden="true"/>
+</sheetData></worksheet>"#;
+
+        let hidden = parse_hidden_lines(xml).unwrap();
+        assert_eq!(hidden.rows, vec![2, 3]);
+        assert_eq!(hidden.columns, vec![2, 3]);
+    }
+
+    #[test]
+    fn test_remove_hidden_lines() {
+        let hidden = HiddenLines {
+            rows: vec![0, 5],
+            columns: vec![2],
+        };
+        let mut data: Vec<Vec<Cell>> = (4..7)
+            .map(|row| {
+                (1..4)
+                    .map(|col| Cell::new(format!("{row},{col}")))
+                    .collect()
+            })
+            .collect();
+
+        // The data starts at row 4, column 1 of the sheet
+        hidden.remove_from(&mut data, (4, 1));
+        let values: Vec<Vec<&str>> = data
+            .iter()
+            .map(|row| row.iter().map(|cell| cell.value.as_str()).collect())
+            .collect();
+        assert_eq!(values, vec![vec!["4,1", "4,3"], vec!["6,1", "6,3"]]);
+    }
+}
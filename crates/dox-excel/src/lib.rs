@@ -1,9 +1,14 @@
 //! Excel spreadsheet provider implementation using calamine and rust_xlsxwriter.
 
+mod cell_formulas;
 pub mod chart;
+mod comments;
+pub mod csv_io;
 pub mod formatting;
 pub mod formula;
+mod hidden;
 pub mod macro_handling;
+mod package;
 pub mod pivot;
 pub mod streaming;
 pub mod validation;
@@ -13,11 +18,13 @@ pub mod tests;
 
 use anyhow::{anyhow, Result};
 use calamine::{open_workbook, Reader, Xlsx};
+use dox_core::utils::write_atomic;
 use dox_core::{
-    Cell, RangeRef, ReadOptions, Ruleset, Sheet, SheetId, SpreadsheetMetadata, SpreadsheetProvider,
-    WriteOptions,
+    index_to_column_letter, Cell, CellComment, CellValue, MergedRange, ProtectedAction, RangeRef,
+    ReadOptions, Ruleset, Sheet, SheetId, SheetProtection, SpreadsheetMetadata,
+    SpreadsheetProvider, WriteOptions,
 };
-use rust_xlsxwriter::Workbook;
+use rust_xlsxwriter::{ExcelDateTime, Format, ProtectionOptions, Workbook, Worksheet};
 use std::collections::HashMap;
 use std::future::Future;
 use std::path::PathBuf;
@@ -27,8 +34,9 @@ use tracing::{debug, info, warn};
 pub use chart::{
     ChartManager, ChartPosition, ChartSeries, ChartStyle, ExcelChartBuilder, ExcelChartType,
 };
+pub use csv_io::CsvOptions;
 pub use formatting::{BasicCellFormat, BasicFormattingManager, FormatTemplate, StyleTheme};
-pub use formula::{CellReference, Formula, FormulaContext, FormulaResult};
+pub use formula::{CellReference, CircularReference, Formula, FormulaContext, FormulaResult};
 pub use macro_handling::{
     MacroAnalysisResult, MacroAnalyzer, MacroConfig, MacroHandlingOption, MacroSecurityLevel,
     SecurityRisk, VbaModule, VbaProject,
@@ -36,30 +44,238 @@ pub use macro_handling::{
 pub use pivot::{
     DataField, PivotField, PivotLocation, PivotTable, PivotTableManager, PivotTableMetadata,
 };
-pub use streaming::{
-    DataChunk, StreamProgress, StreamingConfig, StreamingExcelReader, StreamingProcessor,
-};
+pub use streaming::{DataChunk, StreamProgress, StreamingConfig, StreamingExcelReader};
 pub use validation::{
-    SimpleValidationConfig, SimpleValidationManager, SimpleValidationType, ValidationTemplate,
+    SimpleValidationConfig, SimpleValidationManager, SimpleValidationType, ValidationMode,
+    ValidationTemplate, ValidationViolation,
 };
 
+/// Render an Excel date cell as ISO 8601
+///
+/// Dates without a time of day become `2024-03-15`, others
+/// `2024-03-15T09:30:00`, matching what [`dox_core::CellValue`] parses on
+/// write. Durations keep their serial number.
+pub(crate) fn datetime_to_iso(dt: &calamine::ExcelDateTime) -> String {
+    match dt.as_datetime() {
+        Some(datetime) if dt.is_datetime() => {
+            if datetime.time() == chrono::NaiveTime::MIN {
+                datetime.date().format("%Y-%m-%d").to_string()
+            } else {
+                datetime.format("%Y-%m-%dT%H:%M:%S").to_string()
+            }
+        }
+        _ => dt.to_string(),
+    }
+}
+
+/// Convert a date and time to rust_xlsxwriter's representation
+fn to_excel_datetime(datetime: &chrono::NaiveDateTime) -> Result<ExcelDateTime> {
+    use chrono::{Datelike, Timelike};
+
+    let seconds = datetime.second() as f64 + datetime.nanosecond() as f64 / 1e9;
+    ExcelDateTime::from_ymd(
+        datetime.year() as u16,
+        datetime.month() as u8,
+        datetime.day() as u8,
+    )
+    .and_then(|date| date.and_hms(datetime.hour() as u16, datetime.minute() as u8, seconds))
+    .map_err(|e| anyhow!("Date {} cannot be written to Excel: {}", datetime, e))
+}
+
+/// Cells read from a sheet, with the zero-based `(row, col)` of the first one
+type PlacedCells = ((u32, u32), Vec<Vec<Cell>>);
+
 /// Excel provider for reading and writing XLSX files
 pub struct ExcelProvider {
     /// Base directory for Excel files
     base_dir: Option<PathBuf>,
+    /// Number of workbooks opened, so tests can check how often files are read
+    #[cfg(test)]
+    opened: std::sync::atomic::AtomicUsize,
 }
 
 impl ExcelProvider {
     /// Creates a new Excel provider
     pub fn new() -> Self {
-        Self { base_dir: None }
+        Self {
+            base_dir: None,
+            #[cfg(test)]
+            opened: Default::default(),
+        }
     }
 
     /// Creates a new Excel provider with a base directory
     pub fn with_base_dir(base_dir: impl Into<PathBuf>) -> Self {
         Self {
             base_dir: Some(base_dir.into()),
+            #[cfg(test)]
+            opened: Default::default(),
+        }
+    }
+
+    /// Opens the workbook at `path` for reading
+    fn open(&self, path: &std::path::Path) -> Result<Xlsx<std::io::BufReader<std::fs::File>>> {
+        #[cfg(test)]
+        self.opened
+            .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        open_workbook(path).map_err(|e| anyhow!("Failed to open Excel file: {}", e))
+    }
+
+    /// Opens the workbook at `path` as a zip archive, for the parts calamine
+    /// does not read
+    fn open_archive(path: &std::path::Path) -> Result<zip::ZipArchive<std::fs::File>> {
+        let file = std::fs::File::open(path)
+            .map_err(|e| anyhow!("Failed to open Excel file {:?}: {}", path, e))?;
+        zip::ZipArchive::new(file)
+            .map_err(|e| anyhow!("Failed to read {:?} as an xlsx archive: {}", path, e))
+    }
+
+    /// Lists the sheets of an open workbook with their used size, hidden
+    /// state and hidden rows and columns
+    fn sheets(
+        workbook: &mut Xlsx<std::io::BufReader<std::fs::File>>,
+        path: &std::path::Path,
+    ) -> Result<Vec<Sheet>> {
+        let mut archive = Self::open_archive(path)?;
+
+        workbook
+            .sheets_metadata()
+            .to_vec()
+            .into_iter()
+            .map(|sheet| {
+                let lines = hidden::hidden_lines(&mut archive, &sheet.name)?;
+                let (row_count, column_count) = Self::used_size(workbook, &sheet.name)?;
+                Ok(Sheet {
+                    name: sheet.name,
+                    id: None,
+                    row_count,
+                    column_count,
+                    hidden: sheet.visible != calamine::SheetVisible::Visible,
+                    hidden_rows: lines.rows,
+                    hidden_columns: lines.columns,
+                })
+            })
+            .collect()
+    }
+
+    /// Counts the rows and columns from A1 to the sheet's last non-empty cell
+    ///
+    /// Cells are streamed rather than trusting `<dimension>`, which may be
+    /// missing or cover formatted but empty cells. An empty sheet is `(0, 0)`.
+    fn used_size<R: std::io::Read + std::io::Seek>(
+        workbook: &mut Xlsx<R>,
+        sheet_name: &str,
+    ) -> Result<(usize, usize)> {
+        let mut cells = workbook
+            .worksheet_cells_reader(sheet_name)
+            .map_err(|e| anyhow!("Failed to read sheet '{}': {}", sheet_name, e))?;
+
+        let (mut rows, mut columns) = (0, 0);
+        while let Some(cell) = cells
+            .next_cell()
+            .map_err(|e| anyhow!("Failed to read cell: {}", e))?
+        {
+            if matches!(cell.get_value(), calamine::DataRef::Empty) {
+                continue;
+            }
+            let (row, col) = cell.get_position();
+            rows = rows.max(row as usize + 1);
+            columns = columns.max(col as usize + 1);
+        }
+        Ok((rows, columns))
+    }
+
+    /// Reads a whole sheet from an open workbook
+    ///
+    /// The data covers the sheet's used area, whose zero-based top-left
+    /// `(row, col)` is returned alongside it.
+    fn read_sheet(
+        workbook: &mut Xlsx<std::io::BufReader<std::fs::File>>,
+        sheet_name: &str,
+        evaluate_formulas: bool,
+    ) -> Result<PlacedCells> {
+        let range = workbook
+            .worksheet_range(sheet_name)
+            .map_err(|e| anyhow!("Failed to read sheet '{}': {}", sheet_name, e))?;
+
+        let data = range
+            .rows()
+            .map(|row| {
+                row.iter()
+                    .map(|data| Self::convert_calamine_cell(data, evaluate_formulas))
+                    .collect()
+            })
+            .collect();
+        Ok((range.start().unwrap_or((0, 0)), data))
+    }
+
+    /// Merged regions of a sheet, from its `<mergeCells>` element
+    fn merged_ranges<R: std::io::Read + std::io::Seek>(
+        workbook: &mut Xlsx<R>,
+        sheet_name: &str,
+    ) -> Result<Vec<MergedRange>> {
+        let regions = workbook
+            .worksheet_merge_cells(sheet_name)
+            .ok_or_else(|| anyhow!("Sheet '{}' not found", sheet_name))?
+            .map_err(|e| anyhow!("Failed to read merged cells of '{}': {}", sheet_name, e))?;
+
+        Ok(regions
+            .into_iter()
+            .map(|region| MergedRange {
+                first_row: region.start.0,
+                first_col: region.start.1,
+                last_row: region.end.0,
+                last_col: region.end.1,
+            })
+            .collect())
+    }
+
+    /// Copy a merged region's anchor value into the region's other cells
+    ///
+    /// `data` starts at the zero-based sheet position `origin`; cells of the
+    /// region outside it are left alone. When the data starts inside the
+    /// region, the anchor is read from the sheet.
+    fn fill_merge<R: std::io::Read + std::io::Seek>(
+        workbook: &mut Xlsx<R>,
+        sheet_name: &str,
+        data: &mut [Vec<Cell>],
+        origin: (u32, u32),
+        merge: &MergedRange,
+        evaluate_formulas: bool,
+    ) -> Result<()> {
+        let (first_row, first_col) = (merge.first_row, merge.first_col);
+        let anchor = if first_row >= origin.0 && first_col >= origin.1 {
+            data.get((first_row - origin.0) as usize)
+                .and_then(|row| row.get((first_col - origin.1) as usize))
+                .cloned()
+        } else {
+            None
+        };
+        let anchor = match anchor {
+            Some(cell) => cell,
+            None => {
+                let bounds = (first_row, first_col as u16, first_row, first_col as u16);
+                Self::read_cells(workbook, sheet_name, bounds, evaluate_formulas)?
+                    .first()
+                    .and_then(|row| row.first())
+                    .cloned()
+                    .unwrap_or_else(|| Cell::new(""))
+            }
+        };
+
+        for (row_idx, row) in data.iter_mut().enumerate() {
+            let sheet_row = origin.0 + row_idx as u32;
+            if !(first_row..=merge.last_row).contains(&sheet_row) {
+                continue;
+            }
+            for (col_idx, cell) in row.iter_mut().enumerate() {
+                if merge.contains(sheet_row, origin.1 + col_idx as u32) {
+                    *cell = anchor.clone();
+                }
+            }
         }
+
+        Ok(())
     }
 
     /// Resolves a sheet ID to a file path
@@ -97,7 +313,7 @@ impl ExcelProvider {
                 }
             }
             calamine::Data::Bool(b) => b.to_string(),
-            calamine::Data::DateTime(dt) => dt.to_string(),
+            calamine::Data::DateTime(dt) => datetime_to_iso(dt),
             calamine::Data::DateTimeIso(s) => s.clone(),
             calamine::Data::DurationIso(s) => s.clone(),
             calamine::Data::Error(e) => format!("#ERR: {:?}", e),
@@ -106,10 +322,412 @@ impl ExcelProvider {
         Cell::new(value)
     }
 
+    /// Read the cells inside zero-based `(first_row, first_col, last_row,
+    /// last_col)` bounds
+    ///
+    /// Cells are streamed in row order, so reading stops after the last
+    /// requested row instead of loading the whole sheet. The result starts
+    /// at the first requested cell and ends where the bounds or the sheet's
+    /// used area end, whichever comes first, so `A1:XFD1048576` only costs
+    /// what the sheet holds. Cells inside it that the sheet does not have
+    /// are empty; bounds past the used area give no rows.
+    fn read_cells<R: std::io::Read + std::io::Seek>(
+        workbook: &mut Xlsx<R>,
+        sheet_name: &str,
+        (first_row, first_col, last_row, last_col): (u32, u16, u32, u16),
+        evaluate_formulas: bool,
+    ) -> Result<Vec<Vec<Cell>>> {
+        let (first_col, last_col) = (first_col as u32, last_col as u32);
+
+        let mut cells = workbook
+            .worksheet_cells_reader(sheet_name)
+            .map_err(|e| anyhow!("Failed to read sheet '{}': {}", sheet_name, e))?;
+
+        // The sheet's `<dimension>` may understate its cells, so the end
+        // also grows to every cell found
+        let used_end = cells.dimensions().end;
+        let (mut end_row, mut end_col) = (used_end.0.min(last_row), used_end.1.min(last_col));
+        let mut found = Vec::new();
+        while let Some(cell) = cells
+            .next_cell()
+            .map_err(|e| anyhow!("Failed to read cell: {}", e))?
+        {
+            let (row, col) = cell.get_position();
+            if row > last_row {
+                break;
+            }
+            if row < first_row || !(first_col..=last_col).contains(&col) {
+                continue;
+            }
+
+            end_row = end_row.max(row);
+            end_col = end_col.max(col);
+            let data = calamine::Data::from(cell.get_value().clone());
+            found.push((
+                row,
+                col,
+                Self::convert_calamine_cell(&data, evaluate_formulas),
+            ));
+        }
+
+        if end_row < first_row || end_col < first_col {
+            return Ok(Vec::new());
+        }
+        let width = (end_col - first_col + 1) as usize;
+        let height = (end_row - first_row + 1) as usize;
+        let mut result = vec![vec![Cell::new(""); width]; height];
+        for (row, col, cell) in found {
+            result[(row - first_row) as usize][(col - first_col) as usize] = cell;
+        }
+
+        Ok(result)
+    }
+
+    /// Look up `range` among the workbook's defined names
+    ///
+    /// Names are matched case-insensitively, as Excel does. Ranges that carry
+    /// a sheet prefix are never treated as names.
+    fn resolve_defined_name<R: std::io::Read + std::io::Seek>(
+        workbook: &Xlsx<R>,
+        range: &RangeRef,
+    ) -> Result<Option<RangeRef>> {
+        if range.0.contains('!') {
+            return Ok(None);
+        }
+
+        let Some((name, formula)) = workbook
+            .defined_names()
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(range.0.trim()))
+        else {
+            return Ok(None);
+        };
+
+        let target = formula.trim_start_matches('=');
+        if target.contains(',') {
+            return Err(anyhow!(
+                "Defined name '{}' spans several areas, which is not supported: {}",
+                name,
+                target
+            ));
+        }
+
+        // Sheet names with spaces are quoted: 'My Sheet'!$A$1:$B$2
+        let Some((sheet, cells)) = target.rsplit_once('!') else {
+            return Err(anyhow!(
+                "Defined name '{}' does not refer to a cell range: {}",
+                name,
+                target
+            ));
+        };
+        let sheet = sheet
+            .strip_prefix('\'')
+            .and_then(|s| s.strip_suffix('\''))
+            .map_or_else(|| sheet.to_string(), |s| s.replace("''", "'"));
+
+        Ok(Some(RangeRef::new(format!("{}!{}", sheet, cells))))
+    }
+
+    /// Reads `range` like [`SpreadsheetProvider::read_range`] and also
+    /// returns the merged regions that overlap it
+    ///
+    /// Merged cells come back empty except for the top-left anchor unless
+    /// [`ReadOptions::fill_merged`] is set.
+    pub async fn read_range_with_merges(
+        &self,
+        sheet_id: &SheetId,
+        range: &RangeRef,
+        options: Option<ReadOptions>,
+    ) -> Result<(Vec<Vec<Cell>>, Vec<MergedRange>)> {
+        let path = self.resolve_path(sheet_id);
+        self.read_range_and_merges(path, range.clone(), options.unwrap_or_default(), true)
+            .await
+    }
+
+    /// Shared implementation of [`SpreadsheetProvider::read_range`] and
+    /// [`Self::read_range_with_merges`]
+    ///
+    /// Merges are only looked up when `with_merges` or `fill_merged` is set,
+    /// as that parses the sheet a second time.
+    async fn read_range_and_merges(
+        &self,
+        path: PathBuf,
+        range: RangeRef,
+        options: ReadOptions,
+        with_merges: bool,
+    ) -> Result<(Vec<Vec<Cell>>, Vec<MergedRange>)> {
+        debug!("Reading Excel file: {:?}", path);
+
+        // Open the workbook
+        let mut workbook = self.open(&path)?;
+
+        // Defined names stand for a concrete sheet and range
+        let range = match Self::resolve_defined_name(&workbook, &range)? {
+            Some(resolved) => {
+                debug!("Resolved defined name '{}' to {}", range.0, resolved.0);
+                resolved
+            }
+            None => range,
+        };
+        let (sheet_name, range_str) = self.parse_range(&range);
+        // An empty range reads the whole sheet; anything else must be A1
+        if !range_str.is_empty() {
+            range.validate()?;
+        }
+
+        // Get the sheet
+        let sheet_name = sheet_name.unwrap_or_else(|| {
+            workbook
+                .sheet_names()
+                .first()
+                .cloned()
+                .unwrap_or_else(|| "Sheet1".to_string())
+        });
+
+        debug!("Reading sheet: {}", sheet_name);
+
+        let evaluate_formulas = options.evaluate_formulas;
+
+        // An A1 range reads just that rectangle; anything else reads the
+        // whole sheet
+        let bounds = validation::parse_cell_range(&RangeRef::new(range_str)).ok();
+        let (origin, mut result) = match bounds {
+            Some(bounds) => (
+                (bounds.0, bounds.1 as u32),
+                Self::read_cells(&mut workbook, &sheet_name, bounds, evaluate_formulas)?,
+            ),
+            None => Self::read_sheet(&mut workbook, &sheet_name, evaluate_formulas)?,
+        };
+
+        if options.preserve_formulas {
+            // A whole sheet also takes in formulas without a cached result
+            let mut archive = Self::open_archive(&path)?;
+            let formulas = cell_formulas::sheet_formulas(&mut archive, &sheet_name)?;
+            cell_formulas::place_formulas(&formulas, &mut result, origin, bounds.is_none());
+        } else if evaluate_formulas {
+            if let Err(e) = self
+                .evaluate_formulas_in_data(&mut result, &mut workbook)
+                .await
+            {
+                warn!("Formula evaluation failed: {}", e);
+            }
+        }
+
+        let mut merges = Vec::new();
+        let height = result.len() as u32;
+        let width = result.iter().map(Vec::len).max().unwrap_or(0) as u32;
+        if (with_merges || options.fill_merged) && height > 0 && width > 0 {
+            let bounds = (
+                origin.0,
+                origin.1,
+                origin.0 + height - 1,
+                origin.1 + width - 1,
+            );
+            merges = Self::merged_ranges(&mut workbook, &sheet_name)?
+                .into_iter()
+                .filter(|merge| merge.intersects(bounds))
+                .collect();
+        }
+
+        if options.fill_merged {
+            for merge in &merges {
+                Self::fill_merge(
+                    &mut workbook,
+                    &sheet_name,
+                    &mut result,
+                    origin,
+                    merge,
+                    evaluate_formulas,
+                )?;
+            }
+        }
+
+        if options.skip_hidden {
+            let mut archive = Self::open_archive(&path)?;
+            hidden::hidden_lines(&mut archive, &sheet_name)?.remove_from(&mut result, origin);
+        }
+
+        info!("Read {} rows from Excel file", result.len());
+        Ok((result, merges))
+    }
+
+    /// Map sheet protection settings to rust_xlsxwriter options
+    fn protection_options(protection: &SheetProtection) -> ProtectionOptions {
+        use ProtectedAction::*;
+
+        ProtectionOptions {
+            select_locked_cells: protection.allows(SelectLockedCells),
+            select_unlocked_cells: protection.allows(SelectUnlockedCells),
+            format_cells: protection.allows(FormatCells),
+            format_columns: protection.allows(FormatColumns),
+            format_rows: protection.allows(FormatRows),
+            insert_columns: protection.allows(InsertColumns),
+            insert_rows: protection.allows(InsertRows),
+            insert_links: protection.allows(InsertLinks),
+            delete_columns: protection.allows(DeleteColumns),
+            delete_rows: protection.allows(DeleteRows),
+            sort: protection.allows(Sort),
+            use_autofilter: protection.allows(UseAutofilter),
+            use_pivot_tables: protection.allows(UsePivotTables),
+            edit_scenarios: protection.allows(EditScenarios),
+            edit_objects: protection.allows(EditObjects),
+            ..ProtectionOptions::new()
+        }
+    }
+
+    /// Add `sheet_name` to `workbook` and write `data` to it from `A1`
+    ///
+    /// The data is checked against the validation rules before anything is
+    /// written.
+    fn write_sheet(
+        workbook: &mut Workbook,
+        sheet_name: &str,
+        data: &[Vec<Cell>],
+        options: &WriteOptions,
+    ) -> Result<()> {
+        // Check the data before anything is written
+        let violations = validation::find_violations(&options.validations, sheet_name, data)?;
+        if !violations.is_empty() {
+            match options.validation_mode {
+                ValidationMode::Reject => {
+                    let details: Vec<String> = violations.iter().map(ToString::to_string).collect();
+                    return Err(anyhow!(
+                        "Data validation failed for {} cell(s): {}",
+                        violations.len(),
+                        details.join("; ")
+                    ));
+                }
+                ValidationMode::Warn => {
+                    for violation in &violations {
+                        warn!("Data validation: {}", violation);
+                    }
+                }
+            }
+        }
+
+        let worksheet = workbook.add_worksheet().set_name(sheet_name)?;
+
+        // Formats only depend on whether a cell is in the header, on its
+        // column and on whether it holds a date, so they are built once
+        // per combination
+        type FormatKey = (bool, usize, Option<&'static str>);
+        let mut formats: HashMap<FormatKey, Option<Format>> = HashMap::new();
+
+        // Write the data
+        for (row_idx, row_data) in data.iter().enumerate() {
+            for (col_idx, cell) in row_data.iter().enumerate() {
+                let row = row_idx as u32;
+                let col = col_idx as u16;
+                let value = cell.typed_value();
+                let date_format = formatting::date_num_format(&value);
+                let format = formats
+                    .entry((row_idx == 0, col_idx, date_format))
+                    .or_insert_with(|| {
+                        formatting::written_cell_format(options, row_idx, col_idx, &value)
+                            .map(|format| formatting::to_xlsx_format(&format))
+                    })
+                    .as_ref();
+
+                Self::write_cell(worksheet, row, col, &value, format)?;
+            }
+        }
+
+        if let Some((row, col)) = options.freeze_panes {
+            worksheet
+                .set_freeze_panes(row, col)
+                .map_err(|e| anyhow!("Failed to freeze panes: {}", e))?;
+        }
+
+        if let Some(protection) = &options.protect {
+            if let Some(password) = &protection.password {
+                worksheet.protect_with_password(password);
+            }
+            worksheet.protect_with_options(&Self::protection_options(protection));
+        }
+
+        if options.auto_fit_columns {
+            for (col, width) in formatting::auto_fit_widths(data).into_iter().enumerate() {
+                worksheet
+                    .set_column_width(col as u16, width)
+                    .map_err(|e| anyhow!("Failed to set column width: {}", e))?;
+            }
+        }
+
+        // Store the rules so Excel shows dropdowns and checks later edits
+        let mut validation_manager = SimpleValidationManager::new(worksheet);
+        for config in &options.validations {
+            if validation::applies_to_sheet(&config.range, sheet_name) {
+                validation_manager.apply_validation(config)?;
+            }
+        }
+
+        for conditional_format in &options.conditional_formats {
+            if validation::applies_to_sheet(&conditional_format.range, sheet_name) {
+                formatting::apply_conditional_format(worksheet, conditional_format)?;
+            }
+        }
+
+        for sparkline in &options.sparklines {
+            if validation::applies_to_sheet(&sparkline.location, sheet_name) {
+                chart::add_sparkline(worksheet, sheet_name, sparkline)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Write a cell value with the Excel type matching its detected type
+    fn write_cell(
+        worksheet: &mut Worksheet,
+        row: u32,
+        col: u16,
+        value: &CellValue,
+        format: Option<&Format>,
+    ) -> Result<()> {
+        let default_format = Format::new();
+        let format = format.unwrap_or(&default_format);
+
+        match value {
+            CellValue::Formula(formula) => {
+                worksheet
+                    .write_formula_with_format(row, col, formula.as_str(), format)
+                    .map_err(|e| anyhow!("Failed to write formula: {}", e))?;
+            }
+            CellValue::Number(number) => {
+                worksheet
+                    .write_number_with_format(row, col, *number, format)
+                    .map_err(|e| anyhow!("Failed to write number: {}", e))?;
+            }
+            CellValue::Bool(bool_val) => {
+                worksheet
+                    .write_boolean_with_format(row, col, *bool_val, format)
+                    .map_err(|e| anyhow!("Failed to write boolean: {}", e))?;
+            }
+            CellValue::Date(date) => {
+                let datetime = to_excel_datetime(&date.and_time(chrono::NaiveTime::MIN))?;
+                worksheet
+                    .write_datetime_with_format(row, col, datetime, format)
+                    .map_err(|e| anyhow!("Failed to write date: {}", e))?;
+            }
+            CellValue::DateTime(datetime) => {
+                worksheet
+                    .write_datetime_with_format(row, col, to_excel_datetime(datetime)?, format)
+                    .map_err(|e| anyhow!("Failed to write date: {}", e))?;
+            }
+            CellValue::Empty | CellValue::String(_) => {
+                worksheet
+                    .write_string_with_format(row, col, value.to_string(), format)
+                    .map_err(|e| anyhow!("Failed to write string: {}", e))?;
+            }
+        }
+
+        Ok(())
+    }
+
     /// Evaluate formulas in the provided data using context from the workbook
     async fn evaluate_formulas_in_data(
         &self,
-        data: &mut Vec<Vec<Cell>>,
+        data: &mut [Vec<Cell>],
         _workbook: &mut Xlsx<std::io::BufReader<std::fs::File>>,
     ) -> Result<()> {
         // Create formula context from the workbook data
@@ -128,34 +746,65 @@ impl ExcelProvider {
             }
         }
 
-        // Evaluate formulas
+        // Register formulas so they can be evaluated in dependency order
         for (row_idx, row) in data.iter_mut().enumerate() {
             for (col_idx, cell) in row.iter_mut().enumerate() {
                 if cell.value.starts_with('=') {
                     match Formula::parse(&cell.value) {
-                        Ok(formula) => match formula.evaluate(&formula_context) {
-                            Ok(result) => {
-                                cell.value = result.to_string();
-                            }
-                            Err(e) => {
-                                warn!(
-                                    "Formula evaluation error at {}{}): {}",
-                                    Formula::index_to_column(col_idx as u32).unwrap_or_default(),
-                                    row_idx + 1,
-                                    e
-                                );
-                                cell.value = format!("#ERROR: {}", e);
-                            }
-                        },
+                        Ok(formula) => formula_context.set_cell_formula(
+                            CellReference::new_single(None, col_idx as u32, row_idx as u32),
+                            formula,
+                        ),
+                        // Parsing only fails on a reference outside the sheet
                         Err(e) => {
                             warn!("Formula parsing error: {}", e);
-                            cell.value = format!("#ERROR: {}", e);
+                            cell.value = formula::REF_ERROR.to_string();
                         }
                     }
                 }
             }
         }
 
+        // Cells on a cycle cannot be evaluated; report them and order the rest
+        let order = loop {
+            match formula_context.build_dependency_order() {
+                Ok(order) => break order,
+                Err(cycle) => {
+                    warn!("{}", cycle);
+                    for cell_ref in cycle.cells {
+                        let error = formula::CIRCULAR_ERROR.to_string();
+                        formula_context.remove_cell_formula(&cell_ref);
+                        formula_context.set_cell_text(cell_ref.clone(), error.clone());
+                        data[cell_ref.row as usize][cell_ref.col as usize].value = error;
+                    }
+                }
+            }
+        };
+
+        // Evaluate each formula after the formulas it reads, so their
+        // results are in the context
+        for cell_ref in order {
+            let Some(formula) = formula_context.cell_formula(&cell_ref) else {
+                continue;
+            };
+            let cell = &mut data[cell_ref.row as usize][cell_ref.col as usize];
+            match formula.evaluate(&formula_context) {
+                Ok(result) => {
+                    cell.value = result.to_string();
+                    formula_context.set_cell_result(cell_ref, &result);
+                }
+                Err(e) => {
+                    warn!(
+                        "Formula evaluation error at {}{}: {}",
+                        index_to_column_letter(cell_ref.col),
+                        cell_ref.row + 1,
+                        e
+                    );
+                    cell.value = formula::VALUE_ERROR.to_string();
+                }
+            }
+        }
+
         Ok(())
     }
 
@@ -210,9 +859,11 @@ impl ExcelProvider {
         }
 
         // Save the workbook
-        workbook
-            .save(&path)
-            .map_err(|e| anyhow!("Failed to save Excel file with chart: {}", e))?;
+        write_atomic(&path, |file| {
+            workbook
+                .save_to_writer(file)
+                .map_err(|e| anyhow!("Failed to save Excel file with chart: {}", e))
+        })?;
 
         info!("Chart '{}' created successfully in Excel file", title);
         Ok(())
@@ -246,6 +897,10 @@ impl ExcelProvider {
     }
 }
 
+/// Chart specification for [`ExcelProvider::create_excel_report`]:
+/// chart type, title and named data series
+pub type ReportChart<'a> = (ExcelChartType, String, Vec<(&'a str, RangeRef)>);
+
 /// Extended Excel provider with advanced functionality
 impl ExcelProvider {
     /// Create a comprehensive Excel report with data and charts
@@ -253,7 +908,7 @@ impl ExcelProvider {
         &self,
         sheet_id: &SheetId,
         data: Vec<Vec<Cell>>,
-        charts: Vec<(ExcelChartType, String, Vec<(&str, RangeRef)>)>,
+        charts: Vec<ReportChart<'_>>,
     ) -> Result<()> {
         let path = self.resolve_path(sheet_id);
 
@@ -324,9 +979,11 @@ impl ExcelProvider {
             chart_row += 20; // Space charts vertically
         }
 
-        workbook
-            .save(&path)
-            .map_err(|e| anyhow!("Failed to save Excel report: {}", e))?;
+        write_atomic(&path, |file| {
+            workbook
+                .save_to_writer(file)
+                .map_err(|e| anyhow!("Failed to save Excel report: {}", e))
+        })?;
 
         info!("Excel report created with {} charts", chart_count);
         Ok(())
@@ -347,55 +1004,13 @@ impl SpreadsheetProvider for ExcelProvider {
         options: Option<ReadOptions>,
     ) -> Pin<Box<dyn Future<Output = Result<Vec<Vec<Cell>>>> + Send + '_>> {
         let path = self.resolve_path(sheet_id);
-        let (sheet_name, _range_str) = self.parse_range(range);
-        let _options = options.unwrap_or_default();
+        let range = range.clone();
+        let options = options.unwrap_or_default();
 
         Box::pin(async move {
-            debug!("Reading Excel file: {:?}", path);
-
-            // Open the workbook
-            let mut workbook: Xlsx<_> =
-                open_workbook(&path).map_err(|e| anyhow!("Failed to open Excel file: {}", e))?;
-
-            // Get the sheet
-            let sheet_name = sheet_name.unwrap_or_else(|| {
-                workbook
-                    .sheet_names()
-                    .first()
-                    .cloned()
-                    .unwrap_or_else(|| "Sheet1".to_string())
-            });
-
-            debug!("Reading sheet: {}", sheet_name);
-
-            // Read the range
-            let range = workbook
-                .worksheet_range(&sheet_name)
-                .map_err(|e| anyhow!("Failed to read sheet '{}': {}", sheet_name, e))?;
-
-            let evaluate_formulas = _options.evaluate_formulas;
-
-            // Convert to our Cell type
-            let mut result = Vec::new();
-            for row in range.rows() {
-                let cells: Vec<Cell> = row
-                    .iter()
-                    .map(|data| Self::convert_calamine_cell(data, evaluate_formulas))
-                    .collect();
-                result.push(cells);
-            }
-
-            // Evaluate formulas if requested
-            if evaluate_formulas {
-                if let Err(e) = self
-                    .evaluate_formulas_in_data(&mut result, &mut workbook)
-                    .await
-                {
-                    warn!("Formula evaluation failed: {}", e);
-                }
-            }
-
-            info!("Read {} rows from Excel file", result.len());
+            let (result, _) = self
+                .read_range_and_merges(path, range, options, false)
+                .await?;
             Ok(result)
         })
     }
@@ -408,58 +1023,79 @@ impl SpreadsheetProvider for ExcelProvider {
         options: Option<WriteOptions>,
     ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + '_>> {
         let path = self.resolve_path(sheet_id);
-        let (sheet_name, _range_str) = self.parse_range(range);
-        let _options = options.unwrap_or_default();
+        let (sheet_name, range_str) = self.parse_range(range);
+        let checked = if range_str.is_empty() {
+            Ok(())
+        } else {
+            range.validate()
+        };
+        let options = options.unwrap_or_default();
 
         Box::pin(async move {
             debug!("Writing to Excel file: {:?}", path);
+            checked?;
 
-            // Create a new workbook
-            let mut workbook = Workbook::new();
             let sheet_name = sheet_name.unwrap_or_else(|| "Sheet1".to_string());
-            let worksheet = workbook.add_worksheet().set_name(&sheet_name)?;
-
-            // Write the data
-            for (row_idx, row_data) in data.iter().enumerate() {
-                for (col_idx, cell) in row_data.iter().enumerate() {
-                    let row = row_idx as u32;
-                    let col = col_idx as u16;
-
-                    if cell.value.starts_with('=') {
-                        // Write as formula
-                        worksheet
-                            .write_formula(row, col, cell.value.as_str())
-                            .map_err(|e| anyhow!("Failed to write formula: {}", e))?;
-                    } else if let Ok(number) = cell.value.parse::<f64>() {
-                        // Write as number
-                        worksheet
-                            .write_number(row, col, number)
-                            .map_err(|e| anyhow!("Failed to write number: {}", e))?;
-                    } else if cell.value.parse::<bool>().is_ok() {
-                        // Write as boolean
-                        let bool_val = cell.value.to_lowercase() == "true";
-                        worksheet
-                            .write_boolean(row, col, bool_val)
-                            .map_err(|e| anyhow!("Failed to write boolean: {}", e))?;
-                    } else {
-                        // Write as string
-                        worksheet
-                            .write_string(row, col, &cell.value)
-                            .map_err(|e| anyhow!("Failed to write string: {}", e))?;
-                    }
-                }
-            }
+            let mut workbook = Workbook::new();
+            Self::write_sheet(&mut workbook, &sheet_name, &data, &options)?;
 
             // Save the workbook
-            workbook
-                .save(&path)
-                .map_err(|e| anyhow!("Failed to save Excel file: {}", e))?;
+            write_atomic(&path, |file| {
+                workbook
+                    .save_to_writer(file)
+                    .map_err(|e| anyhow!("Failed to save Excel file: {}", e))
+            })?;
 
             info!("Wrote {} rows to Excel file", data.len());
             Ok(())
         })
     }
 
+    fn write_ranges(
+        &self,
+        sheet_id: &SheetId,
+        writes: Vec<(String, Vec<Vec<Cell>>)>,
+        options: Option<WriteOptions>,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + '_>> {
+        let path = self.resolve_path(sheet_id);
+        let options = options.unwrap_or_default();
+
+        Box::pin(async move {
+            debug!("Writing {} sheets to Excel file: {:?}", writes.len(), path);
+            if writes.is_empty() {
+                return Err(anyhow!("No sheets to write"));
+            }
+
+            let mut names = std::collections::HashSet::new();
+            for (sheet_name, _) in &writes {
+                RangeRef::cell(Some(sheet_name), 1, 1)?.validate()?;
+                if !names.insert(sheet_name.to_lowercase()) {
+                    return Err(anyhow!("Sheet '{}' is written more than once", sheet_name));
+                }
+            }
+
+            // Every sheet goes into one workbook so none replaces another
+            let mut workbook = Workbook::new();
+            for (sheet_name, data) in &writes {
+                Self::write_sheet(&mut workbook, sheet_name, data, &options)?;
+            }
+
+            write_atomic(&path, |file| {
+                workbook
+                    .save_to_writer(file)
+                    .map_err(|e| anyhow!("Failed to save Excel file: {}", e))
+            })?;
+
+            let rows: usize = writes.iter().map(|(_, data)| data.len()).sum();
+            info!(
+                "Wrote {} rows in {} sheets to Excel file",
+                rows,
+                writes.len()
+            );
+            Ok(())
+        })
+    }
+
     fn list_sheets(
         &self,
         sheet_id: &SheetId,
@@ -470,26 +1106,68 @@ impl SpreadsheetProvider for ExcelProvider {
             debug!("Listing sheets in Excel file: {:?}", path);
 
             // Open the workbook
-            let workbook: Xlsx<_> =
-                open_workbook(&path).map_err(|e| anyhow!("Failed to open Excel file: {}", e))?;
+            let mut workbook = self.open(&path)?;
 
-            // Get sheet names
-            let sheets: Vec<Sheet> = workbook
-                .sheet_names()
-                .into_iter()
-                .map(|name| Sheet {
-                    name,
-                    id: None,
-                    row_count: 0, // Would need to read each sheet to get actual counts
-                    column_count: 0, // Would need to read each sheet to get actual counts
-                })
-                .collect();
+            let sheets = Self::sheets(&mut workbook, &path)?;
 
             info!("Found {} sheets in Excel file", sheets.len());
             Ok(sheets)
         })
     }
 
+    fn read_workbook(
+        &self,
+        sheet_id: &SheetId,
+        options: Option<ReadOptions>,
+    ) -> Pin<Box<dyn Future<Output = Result<HashMap<String, Vec<Vec<Cell>>>>> + Send + '_>> {
+        let path = self.resolve_path(sheet_id);
+        let options = options.unwrap_or_default();
+        let evaluate_formulas = options.evaluate_formulas;
+
+        Box::pin(async move {
+            debug!("Reading all sheets in Excel file: {:?}", path);
+
+            // One open for the whole workbook instead of one per sheet
+            let mut workbook = self.open(&path)?;
+            let mut archive = if options.preserve_formulas {
+                Some(Self::open_archive(&path)?)
+            } else {
+                None
+            };
+            let mut sheets = HashMap::new();
+            for name in workbook.sheet_names() {
+                let (origin, mut data) = Self::read_sheet(&mut workbook, &name, evaluate_formulas)?;
+                if let Some(archive) = archive.as_mut() {
+                    let formulas = cell_formulas::sheet_formulas(archive, &name)?;
+                    cell_formulas::place_formulas(&formulas, &mut data, origin, true);
+                } else if evaluate_formulas {
+                    if let Err(e) = self
+                        .evaluate_formulas_in_data(&mut data, &mut workbook)
+                        .await
+                    {
+                        warn!("Formula evaluation failed in '{}': {}", name, e);
+                    }
+                }
+                sheets.insert(name, data);
+            }
+
+            info!("Read {} sheets from Excel file", sheets.len());
+            Ok(sheets)
+        })
+    }
+
+    fn read_comments(
+        &self,
+        sheet_id: &SheetId,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<CellComment>>> + Send + '_>> {
+        let path = self.resolve_path(sheet_id);
+
+        Box::pin(async move {
+            let mut archive = Self::open_archive(&path)?;
+            comments::read_comments(&mut archive)
+        })
+    }
+
     fn apply_rules(
         &self,
         sheet_id: &SheetId,
@@ -502,8 +1180,7 @@ impl SpreadsheetProvider for ExcelProvider {
             info!("Applying ruleset '{}' to Excel file", ruleset_name);
 
             // Read the entire workbook
-            let mut workbook: Xlsx<_> =
-                open_workbook(&path).map_err(|e| anyhow!("Failed to open Excel file: {}", e))?;
+            let mut workbook = self.open(&path)?;
 
             let mut total_replacements = 0;
 
@@ -548,6 +1225,9 @@ impl SpreadsheetProvider for ExcelProvider {
                 id: None,
                 row_count: 0,
                 column_count: 0,
+                hidden: false,
+                hidden_rows: Vec::new(),
+                hidden_columns: Vec::new(),
             })
         })
     }
@@ -582,20 +1262,10 @@ impl SpreadsheetProvider for ExcelProvider {
             debug!("Getting metadata for Excel file: {:?}", path);
 
             // Open the workbook
-            let workbook: Xlsx<_> =
-                open_workbook(&path).map_err(|e| anyhow!("Failed to open Excel file: {}", e))?;
+            let mut workbook = self.open(&path)?;
 
             // Get sheet information
-            let sheets: Vec<Sheet> = workbook
-                .sheet_names()
-                .into_iter()
-                .map(|name| Sheet {
-                    name,
-                    id: None,
-                    row_count: 0,
-                    column_count: 0,
-                })
-                .collect();
+            let sheets = Self::sheets(&mut workbook, &path)?;
 
             // Get file metadata
             let file_metadata = std::fs::metadata(&path)?;
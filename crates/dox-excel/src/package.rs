@@ -0,0 +1,128 @@
+//! Reading parts of the xlsx package that calamine does not expose
+
+use anyhow::{anyhow, Result};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use std::io::{Read, Seek};
+use zip::ZipArchive;
+
+/// A relationship entry from a `_rels/*.rels` part
+pub(crate) struct Relationship {
+    pub id: String,
+    pub rel_type: String,
+    /// Archive path of the target part
+    pub target: String,
+}
+
+/// Sheet names with the archive path of their worksheet part, in workbook order
+pub(crate) fn worksheet_parts<R: Read + Seek>(
+    zip: &mut ZipArchive<R>,
+) -> Result<Vec<(String, String)>> {
+    let Some(workbook) = read_part(zip, "xl/workbook.xml")? else {
+        return Ok(Vec::new());
+    };
+
+    let mut sheets = Vec::new();
+    let mut reader = Reader::from_str(&workbook);
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) | Event::Empty(e) if e.local_name().as_ref() == b"sheet" => {
+                if let (Some(name), Some(id)) = (attr(&e, "name"), attr(&e, "id")) {
+                    sheets.push((name, id));
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    let workbook_rels = relationships(zip, "xl/workbook.xml")?;
+    Ok(sheets
+        .into_iter()
+        .filter_map(|(name, rel_id)| {
+            let rel = workbook_rels.iter().find(|rel| rel.id == rel_id)?;
+            Some((name, rel.target.clone()))
+        })
+        .collect())
+}
+
+/// Read the relationships of `part` with targets resolved to archive paths
+pub(crate) fn relationships<R: Read + Seek>(
+    zip: &mut ZipArchive<R>,
+    part: &str,
+) -> Result<Vec<Relationship>> {
+    let (dir, file) = part.rsplit_once('/').unwrap_or(("", part));
+    let rels_path = format!("{}/_rels/{}.rels", dir, file);
+
+    let Some(xml) = read_part(zip, &rels_path)? else {
+        return Ok(Vec::new());
+    };
+
+    let mut rels = Vec::new();
+    let mut reader = Reader::from_str(&xml);
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) | Event::Empty(e) if e.local_name().as_ref() == b"Relationship" => {
+                if let (Some(id), Some(rel_type), Some(target)) =
+                    (attr(&e, "Id"), attr(&e, "Type"), attr(&e, "Target"))
+                {
+                    rels.push(Relationship {
+                        id,
+                        rel_type,
+                        target: resolve_target(dir, &target),
+                    });
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(rels)
+}
+
+/// Resolve a relationship target relative to the directory of its source part
+fn resolve_target(base_dir: &str, target: &str) -> String {
+    if let Some(absolute) = target.strip_prefix('/') {
+        return absolute.to_string();
+    }
+
+    let mut segments: Vec<&str> = base_dir.split('/').filter(|s| !s.is_empty()).collect();
+    for segment in target.split('/') {
+        match segment {
+            ".." => {
+                segments.pop();
+            }
+            "." | "" => {}
+            _ => segments.push(segment),
+        }
+    }
+    segments.join("/")
+}
+
+/// Read an archive entry as UTF-8, or `None` when the entry does not exist
+pub(crate) fn read_part<R: Read + Seek>(
+    zip: &mut ZipArchive<R>,
+    name: &str,
+) -> Result<Option<String>> {
+    let mut entry = match zip.by_name(name) {
+        Ok(entry) => entry,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(anyhow!("Failed to read {}: {}", name, e)),
+    };
+
+    let mut content = String::new();
+    entry
+        .read_to_string(&mut content)
+        .map_err(|e| anyhow!("Failed to read {}: {}", name, e))?;
+    Ok(Some(content))
+}
+
+/// Unescaped value of the attribute with the given local name
+pub(crate) fn attr(e: &BytesStart, name: &str) -> Option<String> {
+    e.attributes()
+        .flatten()
+        .find(|a| a.key.local_name().as_ref() == name.as_bytes())
+        .and_then(|a| a.unescape_value().ok())
+        .map(|v| v.into_owned())
+}
@@ -171,6 +171,25 @@ pub enum AggregationFunction {
     VarP,
 }
 
+impl AggregationFunction {
+    /// Name Excel gives the function in default data field captions such
+    /// as "Average of Sales"
+    pub fn caption(&self) -> &'static str {
+        match self {
+            AggregationFunction::Sum => "Sum",
+            AggregationFunction::Count | AggregationFunction::CountNumbers => "Count",
+            AggregationFunction::Average => "Average",
+            AggregationFunction::Max => "Max",
+            AggregationFunction::Min => "Min",
+            AggregationFunction::Product => "Product",
+            AggregationFunction::StdDev => "StdDev",
+            AggregationFunction::StdDevP => "StdDevp",
+            AggregationFunction::Var => "Var",
+            AggregationFunction::VarP => "Varp",
+        }
+    }
+}
+
 /// Show values as options for data fields
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ShowValuesAs {
@@ -249,6 +268,12 @@ impl Default for PivotStyle {
     }
 }
 
+impl Default for PivotTableManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl PivotTableManager {
     /// Create a new pivot table manager
     pub fn new() -> Self {
@@ -259,24 +284,26 @@ impl PivotTableManager {
 
     /// Discover pivot tables in an Excel workbook (read-only)
     ///
-    /// Note: This is a placeholder implementation. The actual discovery would require
-    /// parsing Excel's internal pivot table XML structures, which is complex and
-    /// not fully supported by calamine.
-    pub fn discover_pivot_tables(&mut self, _file_path: &std::path::Path) -> Result<usize> {
-        warn!("Pivot table discovery is not fully implemented");
-        warn!("This is a limitation of the current Excel reading libraries");
+    /// calamine does not expose pivot tables, so the definitions are read
+    /// directly from the xlsx archive: `xl/pivotTables/pivotTable*.xml` for the
+    /// layout and `xl/pivotCache/pivotCacheDefinition*.xml` for the source
+    /// range and field names. Discovered tables are appended to the manager
+    /// and the number found in this workbook is returned.
+    pub fn discover_pivot_tables(&mut self, file_path: &std::path::Path) -> Result<usize> {
+        debug!("Discovering pivot tables in {:?}", file_path);
+
+        let file = std::fs::File::open(file_path)
+            .map_err(|e| anyhow!("Failed to open Excel file {:?}: {}", file_path, e))?;
+        let mut zip = zip::ZipArchive::new(file)
+            .map_err(|e| anyhow!("Failed to read {:?} as an xlsx archive: {}", file_path, e))?;
 
-        // In a full implementation, this would:
-        // 1. Open the Excel file as a ZIP archive
-        // 2. Parse the pivot table XML files (xl/pivotTables/pivotTable*.xml)
-        // 3. Parse the pivot cache XML files (xl/pivotCache/pivotCacheDefinition*.xml)
-        // 4. Extract field configurations and relationships
-        // 5. Build PivotTable structs from the parsed data
+        let discovered = archive::read_pivot_tables(&mut zip)?;
+        let count = discovered.len();
 
-        debug!("Attempting to discover pivot tables (placeholder implementation)");
+        info!("Discovered {} pivot table(s) in {:?}", count, file_path);
+        self.pivot_tables.extend(discovered);
 
-        // For now, return 0 discovered tables
-        Ok(0)
+        Ok(count)
     }
 
     /// Get all discovered pivot tables
@@ -306,7 +333,7 @@ impl PivotTableManager {
         for pivot_table in &self.pivot_tables {
             let sheet_pivots = by_sheet
                 .entry(pivot_table.location.sheet_name.clone())
-                .or_insert_with(Vec::new);
+                .or_default();
             sheet_pivots.push(pivot_table.name.clone());
 
             source_ranges.push(pivot_table.source_range.clone());
@@ -332,13 +359,11 @@ impl PivotTableManager {
 
     /// Get warnings about pivot table limitations
     pub fn get_pivot_table_warnings(&self) -> Vec<String> {
-        let mut warnings = Vec::new();
-
-        warnings.push("⚠️  Pivot Table Support Limitations:".to_string());
+        let mut warnings = vec!["⚠️  Pivot Table Support Limitations:".to_string()];
         warnings
             .push("   • Cannot create new pivot tables (rust_xlsxwriter limitation)".to_string());
         warnings.push("   • Cannot modify existing pivot tables".to_string());
-        warnings.push("   • Pivot table discovery is limited (calamine limitation)".to_string());
+        warnings.push("   • Discovery reads pivot definitions only, not cached values".to_string());
         warnings.push("   • Can preserve pivot tables when copying workbook structure".to_string());
 
         if !self.pivot_tables.is_empty() {
@@ -388,6 +413,395 @@ impl PivotTableManager {
     }
 }
 
+/// Parsing of the pivot table parts stored inside an xlsx archive
+mod archive {
+    use super::*;
+    use crate::package::{attr, read_part, relationships, worksheet_parts};
+    use quick_xml::events::{BytesStart, Event};
+    use quick_xml::Reader;
+    use std::io::{Read, Seek};
+    use zip::ZipArchive;
+
+    /// Source information recorded in a pivot cache definition
+    #[derive(Debug, Default)]
+    struct PivotCache {
+        source_ref: Option<String>,
+        source_sheet: Option<String>,
+        source_name: Option<String>,
+        field_names: Vec<String>,
+    }
+
+    /// Which area of the pivot table definition the reader is currently in
+    #[derive(Clone, Copy, PartialEq)]
+    enum Section {
+        None,
+        PivotFields,
+        RowFields,
+        ColumnFields,
+    }
+
+    /// Read every pivot table definition in the archive
+    pub(super) fn read_pivot_tables<R: Read + Seek>(
+        zip: &mut ZipArchive<R>,
+    ) -> Result<Vec<PivotTable>> {
+        let mut parts: Vec<String> = zip
+            .file_names()
+            .filter(|name| {
+                name.starts_with("xl/pivotTables/")
+                    && !name.contains("/_rels/")
+                    && name.ends_with(".xml")
+            })
+            .map(str::to_string)
+            .collect();
+
+        if parts.is_empty() {
+            debug!("Workbook contains no pivot table parts");
+            return Ok(Vec::new());
+        }
+
+        // pivotTable2.xml must come before pivotTable10.xml
+        parts.sort_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)));
+
+        let owners = pivot_table_sheets(zip)?;
+        let mut tables = Vec::with_capacity(parts.len());
+
+        for part in parts {
+            let cache = match relationships(zip, &part)?
+                .into_iter()
+                .find(|rel| rel.rel_type.ends_with("/pivotCacheDefinition"))
+            {
+                Some(rel) => match read_part(zip, &rel.target)? {
+                    Some(xml) => parse_cache_definition(&xml)?,
+                    None => {
+                        warn!(
+                            "Pivot cache {} referenced by {} is missing",
+                            rel.target, part
+                        );
+                        PivotCache::default()
+                    }
+                },
+                None => {
+                    warn!("No pivot cache relationship found for {}", part);
+                    PivotCache::default()
+                }
+            };
+
+            let Some(xml) = read_part(zip, &part)? else {
+                continue;
+            };
+            let sheet_name = owners.get(&part).cloned().unwrap_or_default();
+            let table = parse_table_definition(&xml, &cache, sheet_name)?;
+
+            debug!(
+                "Found pivot table '{}' on sheet '{}' (source: {})",
+                table.name, table.location.sheet_name, table.source_range.0
+            );
+            tables.push(table);
+        }
+
+        Ok(tables)
+    }
+
+    /// Map each pivot table part to the name of the sheet that hosts it
+    fn pivot_table_sheets<R: Read + Seek>(
+        zip: &mut ZipArchive<R>,
+    ) -> Result<HashMap<String, String>> {
+        let mut owners = HashMap::new();
+
+        for (sheet_name, part) in worksheet_parts(zip)? {
+            for rel in relationships(zip, &part)? {
+                if rel.rel_type.ends_with("/pivotTable") {
+                    owners.insert(rel.target, sheet_name.clone());
+                }
+            }
+        }
+
+        Ok(owners)
+    }
+
+    /// Parse `xl/pivotCache/pivotCacheDefinition*.xml`
+    fn parse_cache_definition(xml: &str) -> Result<PivotCache> {
+        let mut cache = PivotCache::default();
+        let mut reader = Reader::from_str(xml);
+
+        loop {
+            match reader.read_event()? {
+                Event::Start(e) | Event::Empty(e) => match e.local_name().as_ref() {
+                    b"worksheetSource" => {
+                        cache.source_ref = attr(&e, "ref");
+                        cache.source_sheet = attr(&e, "sheet");
+                        cache.source_name = attr(&e, "name");
+                    }
+                    b"cacheField" => {
+                        let index = cache.field_names.len();
+                        cache.field_names.push(
+                            attr(&e, "name").unwrap_or_else(|| format!("Field{}", index + 1)),
+                        );
+                    }
+                    _ => {}
+                },
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+
+        Ok(cache)
+    }
+
+    /// Parse `xl/pivotTables/pivotTable*.xml` using the field names from its cache
+    fn parse_table_definition(
+        xml: &str,
+        cache: &PivotCache,
+        sheet_name: String,
+    ) -> Result<PivotTable> {
+        let field_name = |index: usize| {
+            cache
+                .field_names
+                .get(index)
+                .cloned()
+                .unwrap_or_else(|| format!("Field{}", index + 1))
+        };
+
+        let source_range = match (&cache.source_sheet, &cache.source_ref, &cache.source_name) {
+            (Some(sheet), Some(range), _) => RangeRef::new(format!("{}!{}", sheet, range)),
+            (None, Some(range), _) => RangeRef::new(range.clone()),
+            (_, None, Some(name)) => RangeRef::new(name.clone()),
+            _ => RangeRef::new(""),
+        };
+
+        let mut table = PivotTable {
+            name: String::new(),
+            source_range,
+            location: PivotLocation {
+                sheet_name,
+                start_cell: String::new(),
+                end_cell: None,
+            },
+            row_fields: Vec::new(),
+            column_fields: Vec::new(),
+            data_fields: Vec::new(),
+            page_fields: Vec::new(),
+            style: None,
+        };
+
+        // Per-field settings from <pivotFields>, indexed like the cache fields
+        let mut field_settings: Vec<(Option<String>, SubtotalConfig, SortOrder)> = Vec::new();
+        let mut row_indices = Vec::new();
+        let mut column_indices = Vec::new();
+        let mut page_indices = Vec::new();
+        let mut grand_total_rows = true;
+        let mut grand_total_columns = true;
+        let mut section = Section::None;
+
+        let mut reader = Reader::from_str(xml);
+        loop {
+            let event = reader.read_event()?;
+            let is_empty = matches!(event, Event::Empty(_));
+
+            match event {
+                Event::Start(e) | Event::Empty(e) => match e.local_name().as_ref() {
+                    b"pivotTableDefinition" => {
+                        table.name = attr(&e, "name").unwrap_or_default();
+                        grand_total_rows = bool_attr(&e, "rowGrandTotals", true);
+                        grand_total_columns = bool_attr(&e, "colGrandTotals", true);
+                    }
+                    b"location" => {
+                        if let Some(range) = attr(&e, "ref") {
+                            let mut cells = range.splitn(2, ':');
+                            table.location.start_cell = cells.next().unwrap_or("").to_string();
+                            table.location.end_cell = cells.next().map(str::to_string);
+                        }
+                    }
+                    b"pivotFields" if !is_empty => section = Section::PivotFields,
+                    b"rowFields" if !is_empty => section = Section::RowFields,
+                    b"colFields" if !is_empty => section = Section::ColumnFields,
+                    b"pivotField" if section == Section::PivotFields => {
+                        field_settings.push((attr(&e, "name"), subtotals(&e), sort_order(&e)));
+                    }
+                    b"field" if section != Section::None => {
+                        // x="-2" is the placeholder for the values column
+                        if let Some(index) = attr(&e, "x").and_then(|x| x.parse::<usize>().ok()) {
+                            match section {
+                                Section::RowFields => row_indices.push(index),
+                                Section::ColumnFields => column_indices.push(index),
+                                _ => {}
+                            }
+                        }
+                    }
+                    b"pageField" => {
+                        if let Some(index) = attr(&e, "fld").and_then(|f| f.parse::<usize>().ok()) {
+                            page_indices.push(index);
+                        }
+                    }
+                    b"dataField" => {
+                        let source_field = attr(&e, "fld")
+                            .and_then(|f| f.parse::<usize>().ok())
+                            .map(field_name)
+                            .unwrap_or_default();
+                        let function = attr(&e, "subtotal")
+                            .map(|s| aggregation_function(&s))
+                            .unwrap_or(AggregationFunction::Sum);
+
+                        table.data_fields.push(DataField {
+                            display_name: attr(&e, "name").unwrap_or_else(|| {
+                                format!("{} of {}", function.caption(), source_field)
+                            }),
+                            source_field,
+                            function,
+                            number_format: attr(&e, "numFmtId")
+                                .and_then(|id| id.parse::<u32>().ok())
+                                .and_then(builtin_number_format),
+                            show_values_as: attr(&e, "showDataAs")
+                                .map(|s| show_values_as(&s))
+                                .unwrap_or(ShowValuesAs::Normal),
+                        });
+                    }
+                    b"pivotTableStyleInfo" => {
+                        table.style = Some(PivotStyle {
+                            style_name: attr(&e, "name"),
+                            show_row_headers: bool_attr(&e, "showRowHeaders", true),
+                            show_column_headers: bool_attr(&e, "showColHeaders", true),
+                            row_stripes: bool_attr(&e, "showRowStripes", false),
+                            column_stripes: bool_attr(&e, "showColStripes", false),
+                            grand_total_rows,
+                            grand_total_columns,
+                        });
+                    }
+                    _ => {}
+                },
+                Event::End(e) => match e.local_name().as_ref() {
+                    b"pivotFields" | b"rowFields" | b"colFields" => section = Section::None,
+                    _ => {}
+                },
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+
+        let build_fields = |indices: &[usize]| -> Vec<PivotField> {
+            indices
+                .iter()
+                .enumerate()
+                .map(|(position, &index)| {
+                    let (custom_label, subtotals, sort_order) = field_settings
+                        .get(index)
+                        .cloned()
+                        .unwrap_or((None, SubtotalConfig::default(), SortOrder::Manual));
+                    PivotField {
+                        name: field_name(index),
+                        position,
+                        custom_label,
+                        subtotals,
+                        sort_order,
+                        item_filter: None,
+                    }
+                })
+                .collect()
+        };
+
+        table.row_fields = build_fields(&row_indices);
+        table.column_fields = build_fields(&column_indices);
+        table.page_fields = build_fields(&page_indices);
+
+        Ok(table)
+    }
+
+    /// Subtotal settings of a `<pivotField>` element
+    fn subtotals(e: &BytesStart) -> SubtotalConfig {
+        const FLAGS: [(&str, AggregationFunction); 11] = [
+            ("sumSubtotal", AggregationFunction::Sum),
+            ("countASubtotal", AggregationFunction::Count),
+            ("avgSubtotal", AggregationFunction::Average),
+            ("maxSubtotal", AggregationFunction::Max),
+            ("minSubtotal", AggregationFunction::Min),
+            ("productSubtotal", AggregationFunction::Product),
+            ("countSubtotal", AggregationFunction::CountNumbers),
+            ("stdDevSubtotal", AggregationFunction::StdDev),
+            ("stdDevPSubtotal", AggregationFunction::StdDevP),
+            ("varSubtotal", AggregationFunction::Var),
+            ("varPSubtotal", AggregationFunction::VarP),
+        ];
+
+        let mut functions: Vec<AggregationFunction> = FLAGS
+            .iter()
+            .filter(|(name, _)| bool_attr(e, name, false))
+            .map(|(_, function)| function.clone())
+            .collect();
+
+        let enabled = bool_attr(e, "defaultSubtotal", true) || !functions.is_empty();
+        if enabled && functions.is_empty() {
+            functions.push(AggregationFunction::Sum);
+        }
+
+        SubtotalConfig {
+            enabled,
+            functions,
+            at_top: bool_attr(e, "subtotalTop", true),
+        }
+    }
+
+    fn sort_order(e: &BytesStart) -> SortOrder {
+        match attr(e, "sortType").as_deref() {
+            Some("ascending") => SortOrder::Ascending,
+            Some("descending") => SortOrder::Descending,
+            _ => SortOrder::Manual,
+        }
+    }
+
+    /// Map the `subtotal` attribute of a `<dataField>` (ST_DataConsolidateFunction)
+    fn aggregation_function(value: &str) -> AggregationFunction {
+        match value {
+            "count" => AggregationFunction::Count,
+            "average" => AggregationFunction::Average,
+            "max" => AggregationFunction::Max,
+            "min" => AggregationFunction::Min,
+            "product" => AggregationFunction::Product,
+            "countNums" => AggregationFunction::CountNumbers,
+            "stdDev" => AggregationFunction::StdDev,
+            "stdDevp" => AggregationFunction::StdDevP,
+            "var" => AggregationFunction::Var,
+            "varp" => AggregationFunction::VarP,
+            _ => AggregationFunction::Sum,
+        }
+    }
+
+    /// Map the `showDataAs` attribute of a `<dataField>` (ST_ShowDataAs)
+    fn show_values_as(value: &str) -> ShowValuesAs {
+        match value {
+            "difference" => ShowValuesAs::DifferenceFrom,
+            "percent" => ShowValuesAs::PercentageOf,
+            "percentDiff" => ShowValuesAs::PercentageDifferenceFrom,
+            "runTotal" => ShowValuesAs::RunningTotalIn,
+            "percentOfRow" => ShowValuesAs::PercentageOfRowTotal,
+            "percentOfCol" => ShowValuesAs::PercentageOfColumnTotal,
+            "percentOfTotal" => ShowValuesAs::PercentageOfGrandTotal,
+            "index" => ShowValuesAs::Index,
+            _ => ShowValuesAs::Normal,
+        }
+    }
+
+    /// Format codes for the common built-in number format ids
+    fn builtin_number_format(id: u32) -> Option<String> {
+        let code = match id {
+            1 => "0",
+            2 => "0.00",
+            3 => "#,##0",
+            4 => "#,##0.00",
+            9 => "0%",
+            10 => "0.00%",
+            _ => return None,
+        };
+        Some(code.to_string())
+    }
+
+    /// Boolean attribute (`1`/`true`), falling back to the schema default
+    fn bool_attr(e: &BytesStart, name: &str, default: bool) -> bool {
+        attr(e, name)
+            .map(|v| v == "1" || v == "true")
+            .unwrap_or(default)
+    }
+}
+
 /// Helper functions for working with pivot tables
 pub mod helpers {
     use super::*;
@@ -510,11 +924,11 @@ pub mod documentation {
             "• Cannot create new pivot tables (rust_xlsxwriter doesn't support pivot tables)"
                 .to_string(),
             "• Cannot modify existing pivot table structure".to_string(),
-            "• Limited ability to read pivot table definitions (calamine limitation)".to_string(),
+            "• Pivot table definitions are only read from .xlsx files".to_string(),
             "• Cannot refresh pivot table data programmatically".to_string(),
             "".to_string(),
             "✅ WHAT IS SUPPORTED:".to_string(),
-            "• Reading pivot table metadata (limited)".to_string(),
+            "• Reading pivot table definitions: location, source range, row, column and page fields, data fields and style".to_string(),
             "• Preserving pivot tables when copying workbook structure".to_string(),
             "• Documenting existing pivot table configurations".to_string(),
             "• Identifying source ranges used by pivot tables".to_string(),
@@ -526,7 +940,7 @@ pub mod documentation {
             "• Use formulas (SUMIF, COUNTIF, etc.) to create pivot-like summaries".to_string(),
             "".to_string(),
             "🔮 FUTURE IMPROVEMENTS:".to_string(),
-            "• Better pivot table reading when calamine adds support".to_string(),
+            "• Reading pivot tables from .xls and .xlsb files".to_string(),
             "• Pivot table creation if rust_xlsxwriter adds support".to_string(),
             "• Integration with external pivot table libraries".to_string(),
         ]
@@ -638,6 +1052,167 @@ mod tests {
         assert_eq!(deserialized.source_range.0, pivot.source_range.0);
     }
 
+    /// Write a minimal xlsx with a Data sheet and `pivot_count` pivot tables
+    /// on a Pivot sheet over Sales by Region from Data!A1:C4. The first one
+    /// sums into a named "Sum of Sales" field; the others average into a
+    /// field left for the reader to name.
+    fn write_pivot_fixture(path: &std::path::Path, pivot_count: usize) {
+        use std::io::Write;
+        use zip::{write::SimpleFileOptions, ZipWriter};
+
+        let mut writer = ZipWriter::new(std::fs::File::create(path).unwrap());
+        let options = SimpleFileOptions::default();
+        let mut add = |name: &str, content: &str| {
+            writer.start_file(name, options).unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        };
+
+        add(
+            "xl/workbook.xml",
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships">
+    <sheets>
+        <sheet name="Data" sheetId="1" r:id="rId1"/>
+        <sheet name="Pivot" sheetId="2" r:id="rId2"/>
+    </sheets>
+</workbook>"#,
+        );
+        add(
+            "xl/_rels/workbook.xml.rels",
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+    <Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/>
+    <Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet2.xml"/>
+</Relationships>"#,
+        );
+        add(
+            "xl/pivotCache/pivotCacheDefinition1.xml",
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<pivotCacheDefinition xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" recordCount="3">
+    <cacheSource type="worksheet">
+        <worksheetSource ref="A1:C4" sheet="Data"/>
+    </cacheSource>
+    <cacheFields count="3">
+        <cacheField name="Region" numFmtId="0"><sharedItems/></cacheField>
+        <cacheField name="Product" numFmtId="0"><sharedItems/></cacheField>
+        <cacheField name="Sales" numFmtId="0"><sharedItems containsNumber="1"/></cacheField>
+    </cacheFields>
+</pivotCacheDefinition>"#,
+        );
+
+        let mut sheet_rels = String::from(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+        );
+        for n in 1..=pivot_count {
+            sheet_rels.push_str(&format!(
+                r#"<Relationship Id="rId{n}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/pivotTable" Target="../pivotTables/pivotTable{n}.xml"/>"#
+            ));
+            add(
+                &format!("xl/pivotTables/pivotTable{n}.xml"),
+                &format!(
+                    r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<pivotTableDefinition xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" name="PivotTable{n}" cacheId="1" dataCaption="Values">
+    <location ref="A{row}:B{end}" firstHeaderRow="1" firstDataRow="1" firstDataCol="1"/>
+    <pivotFields count="3">
+        <pivotField axis="axisRow" showAll="0" sortType="ascending"><items count="1"><item t="default"/></items></pivotField>
+        <pivotField showAll="0"/>
+        <pivotField dataField="1" showAll="0"/>
+    </pivotFields>
+    <rowFields count="1"><field x="0"/></rowFields>
+    <dataFields count="1">{data_field}</dataFields>
+    <pivotTableStyleInfo name="PivotStyleLight16" showRowHeaders="1" showColHeaders="1" showRowStripes="0" showColStripes="0"/>
+</pivotTableDefinition>"#,
+                    row = (n - 1) * 10 + 3,
+                    end = (n - 1) * 10 + 7,
+                    data_field = if n == 1 {
+                        r#"<dataField name="Sum of Sales" fld="2" baseField="0" baseItem="0"/>"#
+                    } else {
+                        r#"<dataField fld="2" subtotal="average" baseField="0" baseItem="0"/>"#
+                    },
+                ),
+            );
+            add(
+                &format!("xl/pivotTables/_rels/pivotTable{n}.xml.rels"),
+                r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+    <Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/pivotCacheDefinition" Target="../pivotCache/pivotCacheDefinition1.xml"/>
+</Relationships>"#,
+            );
+        }
+        sheet_rels.push_str("</Relationships>");
+        add("xl/worksheets/_rels/sheet2.xml.rels", &sheet_rels);
+
+        writer.finish().unwrap();
+    }
+
+    #[test]
+    fn test_discover_single_pivot_table() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pivot.xlsx");
+        write_pivot_fixture(&path, 1);
+
+        let mut manager = PivotTableManager::new();
+        let count = manager.discover_pivot_tables(&path).unwrap();
+        assert_eq!(count, 1);
+
+        let pivot = manager.get_pivot_table_by_name("PivotTable1").unwrap();
+        assert_eq!(pivot.source_range.0, "Data!A1:C4");
+        assert_eq!(pivot.location.sheet_name, "Pivot");
+        assert_eq!(pivot.location.start_cell, "A3");
+        assert_eq!(pivot.location.end_cell.as_deref(), Some("B7"));
+
+        assert_eq!(pivot.row_fields.len(), 1);
+        assert_eq!(pivot.row_fields[0].name, "Region");
+        assert_eq!(pivot.row_fields[0].sort_order, SortOrder::Ascending);
+        assert!(pivot.column_fields.is_empty());
+
+        assert_eq!(pivot.data_fields.len(), 1);
+        assert_eq!(pivot.data_fields[0].source_field, "Sales");
+        assert_eq!(pivot.data_fields[0].display_name, "Sum of Sales");
+        assert_eq!(pivot.data_fields[0].function, AggregationFunction::Sum);
+
+        let style = pivot.style.as_ref().unwrap();
+        assert_eq!(style.style_name.as_deref(), Some("PivotStyleLight16"));
+    }
+
+    #[test]
+    fn test_discover_multiple_pivot_tables() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pivots.xlsx");
+        write_pivot_fixture(&path, 2);
+
+        let mut manager = PivotTableManager::new();
+        assert_eq!(manager.discover_pivot_tables(&path).unwrap(), 2);
+        assert_eq!(manager.get_pivot_tables_on_sheet("Pivot").len(), 2);
+        let second = manager.get_pivot_table_by_name("PivotTable2").unwrap();
+        assert_eq!(second.location.start_cell, "A13");
+        // Unnamed data fields get Excel's caption for their function
+        assert_eq!(second.data_fields[0].function, AggregationFunction::Average);
+        assert_eq!(second.data_fields[0].display_name, "Average of Sales");
+    }
+
+    #[test]
+    fn test_discover_without_pivot_tables() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("plain.xlsx");
+        write_pivot_fixture(&path, 0);
+
+        let mut manager = PivotTableManager::new();
+        assert_eq!(manager.discover_pivot_tables(&path).unwrap(), 0);
+        assert!(manager.get_pivot_tables().is_empty());
+    }
+
+    #[test]
+    fn test_discover_rejects_non_xlsx() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("not-excel.xlsx");
+        std::fs::write(&path, b"plain text").unwrap();
+
+        let mut manager = PivotTableManager::new();
+        assert!(manager.discover_pivot_tables(&path).is_err());
+    }
+
     #[test]
     fn test_pivot_table_export_import() {
         let mut manager = PivotTableManager::new();
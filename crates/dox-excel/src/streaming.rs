@@ -6,15 +6,15 @@
 //! - Lazy loading of sheets and ranges
 //! - Memory-mapped file support for read operations
 //! - Progress reporting for large file operations
+//! - Row-by-row iteration with a bounded read-ahead buffer
 
 use anyhow::{anyhow, Result};
 use calamine::{open_workbook, Reader, Xlsx};
-use memmap2::Mmap;
 use rayon::prelude::*;
+use std::collections::VecDeque;
 use std::fs::File;
 use std::io::BufReader;
 use std::path::Path;
-use tokio::sync::mpsc;
 use tracing::{debug, info, warn};
 
 use dox_core::Cell;
@@ -30,8 +30,6 @@ pub struct StreamingConfig {
     pub parallel_processing: bool,
     /// Number of worker threads for parallel processing
     pub worker_threads: usize,
-    /// Enable memory mapping for large files
-    pub enable_mmap: bool,
 }
 
 /// Progress information for streaming operations
@@ -81,13 +79,8 @@ pub struct ChunkResult {
 pub struct StreamingExcelReader {
     config: StreamingConfig,
     file_path: std::path::PathBuf,
-    memory_map: Option<Mmap>,
-}
-
-/// Streaming Excel processor for chunk-based operations
-pub struct StreamingProcessor {
-    config: StreamingConfig,
-    progress_tx: Option<mpsc::UnboundedSender<StreamProgress>>,
+    /// Workbook borrowed by the row iterator returned from [`StreamingExcelReader::rows`]
+    workbook: Option<Xlsx<BufReader<File>>>,
 }
 
 /// Memory-efficient Excel iterator
@@ -106,7 +99,6 @@ impl Default for StreamingConfig {
             max_memory_mb: 512,
             parallel_processing: true,
             worker_threads: num_cpus::get(),
-            enable_mmap: true,
         }
     }
 }
@@ -119,7 +111,6 @@ impl StreamingConfig {
             max_memory_mb: 256,
             parallel_processing: true,
             worker_threads: num_cpus::get().min(4), // Limit to avoid overwhelming system
-            enable_mmap: true,
         }
     }
 
@@ -130,7 +121,6 @@ impl StreamingConfig {
             max_memory_mb: 128,
             parallel_processing: false,
             worker_threads: 1,
-            enable_mmap: false,
         }
     }
 
@@ -141,7 +131,6 @@ impl StreamingConfig {
             max_memory_mb: 1024,
             parallel_processing: true,
             worker_threads: num_cpus::get(),
-            enable_mmap: true,
         }
     }
 }
@@ -155,34 +144,10 @@ impl StreamingExcelReader {
             return Err(anyhow!("File does not exist: {}", file_path.display()));
         }
 
-        let memory_map = if config.enable_mmap {
-            match File::open(&file_path) {
-                Ok(file) => match unsafe { Mmap::map(&file) } {
-                    Ok(mmap) => {
-                        info!("Memory-mapped file: {}", file_path.display());
-                        Some(mmap)
-                    }
-                    Err(e) => {
-                        warn!(
-                            "Failed to memory-map file, falling back to regular I/O: {}",
-                            e
-                        );
-                        None
-                    }
-                },
-                Err(e) => {
-                    warn!("Failed to open file for memory mapping: {}", e);
-                    None
-                }
-            }
-        } else {
-            None
-        };
-
         Ok(StreamingExcelReader {
             config,
             file_path,
-            memory_map,
+            workbook: None,
         })
     }
 
@@ -227,6 +192,93 @@ impl StreamingExcelReader {
         })
     }
 
+    /// Iterate the rows of the first sheet one at a time
+    ///
+    /// Cells are read straight from the worksheet XML, so at most
+    /// `chunk_size` rows are held in memory regardless of the sheet size.
+    pub fn rows(&mut self) -> impl Iterator<Item = Result<Vec<Cell>>> + '_ {
+        self.rows_with_progress(None, None)
+    }
+
+    /// Iterate the rows of a sheet (the first one if `None`) one at a time
+    ///
+    /// Rows are anchored at A1: leading empty rows are yielded as empty rows
+    /// and every row is padded to the width declared by the sheet. The
+    /// callback receives a [`StreamProgress`] each time a chunk of rows has
+    /// been buffered.
+    pub fn rows_with_progress(
+        &mut self,
+        sheet_name: Option<&str>,
+        progress_callback: Option<Box<dyn Fn(StreamProgress) + Send + Sync>>,
+    ) -> impl Iterator<Item = Result<Vec<Cell>>> + '_ {
+        let chunk_size = self.config.chunk_size.max(1);
+
+        debug!(
+            "Creating row iterator for file: {} (chunk size {})",
+            self.file_path.display(),
+            chunk_size
+        );
+
+        let opened = open_workbook::<Xlsx<_>, _>(&self.file_path)
+            .map_err(|e| anyhow!("Failed to open Excel file: {}", e))
+            .and_then(|workbook| {
+                let workbook = self.workbook.insert(workbook);
+                let sheet_name = match sheet_name {
+                    Some(name) => name.to_string(),
+                    None => workbook
+                        .sheet_names()
+                        .first()
+                        .cloned()
+                        .ok_or_else(|| anyhow!("Workbook contains no sheets"))?,
+                };
+                let cells = workbook
+                    .worksheet_cells_reader(&sheet_name)
+                    .map_err(|e| anyhow!("Failed to read sheet '{}': {}", sheet_name, e))?;
+                Ok((sheet_name, cells))
+            });
+
+        let (mut cells, mut buffer, mut error) = match opened {
+            Ok((sheet_name, cells)) => {
+                let dimensions = cells.dimensions();
+                let buffer = RowBuffer::new(
+                    sheet_name,
+                    chunk_size,
+                    dimensions.end.0 as usize + 1,
+                    dimensions.end.1 as usize + 1,
+                    progress_callback,
+                );
+                (Some(cells), Some(buffer), None)
+            }
+            Err(e) => (None, None, Some(e)),
+        };
+
+        std::iter::from_fn(move || {
+            if let Some(e) = error.take() {
+                return Some(Err(e));
+            }
+
+            let (reader, buffer) = (cells.as_mut()?, buffer.as_mut()?);
+            if buffer.rows.is_empty() && !buffer.finished {
+                let fill = buffer.fill(|| {
+                    reader
+                        .next_cell()
+                        .map(|cell| {
+                            cell.map(|cell| {
+                                (cell.get_position(), data_ref_to_string(cell.get_value()))
+                            })
+                        })
+                        .map_err(|e| anyhow!("Failed to read cell: {}", e))
+                });
+                if let Err(e) = fill {
+                    buffer.finished = true;
+                    return Some(Err(e));
+                }
+            }
+
+            buffer.rows.pop_front().map(Ok)
+        })
+    }
+
     /// Process the entire file using streaming with progress reporting
     pub async fn process_with_progress<F, R>(
         &self,
@@ -259,7 +311,7 @@ impl StreamingExcelReader {
 
         let start_time = std::time::Instant::now();
 
-        for (_sheet_index, sheet_name) in sheet_names.iter().enumerate() {
+        for sheet_name in sheet_names.iter() {
             debug!("Processing sheet: {}", sheet_name);
 
             let range = workbook
@@ -267,7 +319,7 @@ impl StreamingExcelReader {
                 .map_err(|e| anyhow!("Failed to read sheet '{}': {}", sheet_name, e))?;
 
             let sheet_rows = range.height();
-            let chunks = (sheet_rows + self.config.chunk_size - 1) / self.config.chunk_size;
+            let chunks = sheet_rows.div_ceil(self.config.chunk_size);
 
             for chunk_index in 0..chunks {
                 let start_row = chunk_index * self.config.chunk_size;
@@ -339,7 +391,7 @@ impl StreamingExcelReader {
             calamine::Data::Float(f) => f.to_string(),
             calamine::Data::String(s) => s.clone(),
             calamine::Data::Bool(b) => b.to_string(),
-            calamine::Data::DateTime(dt) => dt.to_string(),
+            calamine::Data::DateTime(dt) => crate::datetime_to_iso(dt),
             calamine::Data::DateTimeIso(s) => s.clone(),
             calamine::Data::DurationIso(s) => s.clone(),
             calamine::Data::Error(e) => format!("#ERR: {:?}", e),
@@ -375,7 +427,7 @@ impl StreamingExcelReader {
                 .map_err(|e| anyhow!("Failed to read sheet '{}': {}", sheet_name, e))?;
 
             let sheet_rows = range.height();
-            let chunks = (sheet_rows + self.config.chunk_size - 1) / self.config.chunk_size;
+            let chunks = sheet_rows.div_ceil(self.config.chunk_size);
 
             for chunk_index in 0..chunks {
                 let start_row = chunk_index * self.config.chunk_size;
@@ -419,6 +471,140 @@ impl StreamingExcelReader {
     }
 }
 
+/// Read-ahead buffer that assembles streamed cells into at most `chunk_size` rows
+struct RowBuffer {
+    sheet_name: String,
+    chunk_size: usize,
+    total_rows: usize,
+    width: usize,
+    rows: VecDeque<Vec<Cell>>,
+    current: Vec<Cell>,
+    current_row: usize,
+    pending: Option<((u32, u32), String)>,
+    loaded_rows: usize,
+    finished: bool,
+    start_time: std::time::Instant,
+    progress_callback: Option<Box<dyn Fn(StreamProgress) + Send + Sync>>,
+}
+
+impl RowBuffer {
+    fn new(
+        sheet_name: String,
+        chunk_size: usize,
+        total_rows: usize,
+        width: usize,
+        progress_callback: Option<Box<dyn Fn(StreamProgress) + Send + Sync>>,
+    ) -> Self {
+        RowBuffer {
+            sheet_name,
+            chunk_size,
+            total_rows,
+            width,
+            rows: VecDeque::with_capacity(chunk_size),
+            current: Vec::new(),
+            current_row: 0,
+            pending: None,
+            loaded_rows: 0,
+            finished: false,
+            start_time: std::time::Instant::now(),
+            progress_callback,
+        }
+    }
+
+    /// Pull cells until `chunk_size` rows are complete or the sheet ends
+    fn fill<F>(&mut self, mut next_cell: F) -> Result<()>
+    where
+        F: FnMut() -> Result<Option<((u32, u32), String)>>,
+    {
+        while self.rows.len() < self.chunk_size {
+            let cell = match self.pending.take() {
+                Some(cell) => Some(cell),
+                None => next_cell()?,
+            };
+
+            let Some(((row, col), value)) = cell else {
+                if !self.current.is_empty() {
+                    self.finish_row();
+                }
+                self.finished = true;
+                break;
+            };
+
+            let row = row as usize;
+            if row > self.current_row {
+                self.finish_row();
+                if row > self.current_row || self.rows.len() >= self.chunk_size {
+                    // Either an empty row precedes this cell or the chunk is full
+                    self.pending = Some(((row as u32, col), value));
+                    continue;
+                }
+            }
+
+            let col = col as usize;
+            if self.current.len() <= col {
+                self.current.resize(col + 1, Cell::new(""));
+            }
+            self.current[col] = Cell::new(value);
+        }
+
+        self.report_progress();
+        Ok(())
+    }
+
+    fn finish_row(&mut self) {
+        let mut row = std::mem::take(&mut self.current);
+        if row.len() < self.width {
+            row.resize(self.width, Cell::new(""));
+        }
+        self.rows.push_back(row);
+        self.current_row += 1;
+        self.loaded_rows += 1;
+    }
+
+    fn report_progress(&self) {
+        let Some(ref callback) = self.progress_callback else {
+            return;
+        };
+
+        let total_rows = self.total_rows.max(self.loaded_rows);
+        let elapsed = self.start_time.elapsed().as_secs();
+        let percentage = if self.finished || total_rows == 0 {
+            100.0
+        } else {
+            (self.loaded_rows as f64 / total_rows as f64) * 100.0
+        };
+        let eta = if percentage > 0.0 && elapsed > 0 {
+            Some(((100.0 - percentage) * elapsed as f64 / percentage) as u64)
+        } else {
+            None
+        };
+
+        callback(StreamProgress {
+            total_rows,
+            processed_rows: self.loaded_rows,
+            current_sheet: self.sheet_name.clone(),
+            percentage,
+            eta_seconds: eta,
+        });
+    }
+}
+
+/// Convert a streamed calamine cell value to string
+fn data_ref_to_string(data: &calamine::DataRef<'_>) -> String {
+    match data {
+        calamine::DataRef::Int(i) => i.to_string(),
+        calamine::DataRef::Float(f) => f.to_string(),
+        calamine::DataRef::String(s) => s.clone(),
+        calamine::DataRef::SharedString(s) => s.to_string(),
+        calamine::DataRef::Bool(b) => b.to_string(),
+        calamine::DataRef::DateTime(dt) => crate::datetime_to_iso(dt),
+        calamine::DataRef::DateTimeIso(s) => s.clone(),
+        calamine::DataRef::DurationIso(s) => s.clone(),
+        calamine::DataRef::Error(e) => format!("#ERR: {:?}", e),
+        calamine::DataRef::Empty => String::new(),
+    }
+}
+
 impl Iterator for ExcelIterator {
     type Item = Result<DataChunk>;
 
@@ -457,7 +643,7 @@ impl Iterator for ExcelIterator {
                             calamine::Data::Float(f) => f.to_string(),
                             calamine::Data::String(s) => s.clone(),
                             calamine::Data::Bool(b) => b.to_string(),
-                            calamine::Data::DateTime(dt) => dt.to_string(),
+                            calamine::Data::DateTime(dt) => crate::datetime_to_iso(dt),
                             calamine::Data::DateTimeIso(s) => s.clone(),
                             calamine::Data::DurationIso(s) => s.clone(),
                             calamine::Data::Error(e) => format!("#ERR: {:?}", e),
@@ -602,7 +788,6 @@ mod tests {
         assert_eq!(config.chunk_size, 1000);
         assert_eq!(config.max_memory_mb, 512);
         assert!(config.parallel_processing);
-        assert!(config.enable_mmap);
     }
 
     #[test]
@@ -635,6 +820,95 @@ mod tests {
         assert_eq!(progress.eta_seconds, Some(30));
     }
 
+    /// Write a single-sheet workbook with `rows` rows of three columns
+    fn write_large_sheet(path: &Path, rows: u32) {
+        let mut workbook = rust_xlsxwriter::Workbook::new();
+        let worksheet = workbook.add_worksheet().set_name("Data").unwrap();
+        for row in 0..rows {
+            worksheet.write_number(row, 0, row as f64).unwrap();
+            worksheet
+                .write_string(row, 1, format!("item-{}", row))
+                .unwrap();
+            worksheet.write_boolean(row, 2, row % 2 == 0).unwrap();
+        }
+        workbook.save(path).unwrap();
+    }
+
+    #[test]
+    fn test_rows_respects_chunk_size() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("large.xlsx");
+        write_large_sheet(&path, 20_000);
+
+        let config = StreamingConfig {
+            chunk_size: 512,
+            ..StreamingConfig::default()
+        };
+        let mut reader = StreamingExcelReader::new(&path, config).unwrap();
+
+        let reports = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = reports.clone();
+        let callback = Box::new(move |progress: StreamProgress| {
+            sink.lock().unwrap().push(progress.processed_rows);
+        });
+
+        let mut total = 0usize;
+        for (index, row) in reader.rows_with_progress(None, Some(callback)).enumerate() {
+            let row = row.unwrap();
+            assert_eq!(row.len(), 3);
+            assert_eq!(row[0].value, index.to_string());
+            assert_eq!(row[1].value, format!("item-{}", index));
+            total += 1;
+        }
+        assert_eq!(total, 20_000);
+
+        let reports = reports.lock().unwrap();
+        assert_eq!(*reports.last().unwrap(), 20_000);
+        let mut previous = 0;
+        for &processed in reports.iter() {
+            assert!(processed - previous <= 512, "chunk exceeded bound");
+            previous = processed;
+        }
+    }
+
+    #[test]
+    fn test_rows_pads_sparse_sheet() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sparse.xlsx");
+
+        let mut workbook = rust_xlsxwriter::Workbook::new();
+        let worksheet = workbook.add_worksheet();
+        worksheet.write_string(1, 0, "a").unwrap();
+        worksheet.write_string(3, 2, "c").unwrap();
+        workbook.save(&path).unwrap();
+
+        let config = StreamingConfig {
+            chunk_size: 1,
+            ..StreamingConfig::for_low_memory()
+        };
+        let mut reader = StreamingExcelReader::new(&path, config).unwrap();
+        let rows: Vec<Vec<Cell>> = reader.rows().map(|r| r.unwrap()).collect();
+
+        assert_eq!(rows.len(), 4);
+        assert!(rows[0].iter().all(|c| c.value.is_empty()));
+        assert_eq!(rows[1][0].value, "a");
+        assert!(rows[2].iter().all(|c| c.value.is_empty()));
+        assert_eq!(rows[3][2].value, "c");
+    }
+
+    #[test]
+    fn test_rows_unknown_sheet() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("small.xlsx");
+        write_large_sheet(&path, 3);
+
+        let mut reader =
+            StreamingExcelReader::new(&path, StreamingConfig::for_low_memory()).unwrap();
+        let mut rows = reader.rows_with_progress(Some("Missing"), None);
+        assert!(rows.next().unwrap().is_err());
+        assert!(rows.next().is_none());
+    }
+
     #[test]
     fn test_data_chunk_creation() {
         let chunk = DataChunk {
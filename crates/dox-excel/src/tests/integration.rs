@@ -4,7 +4,7 @@
 //! - Basic Excel provider functionality
 //! - Chart creation and management
 //! - Formatting with simplified API
-//! - Data validation (documented for future implementation)
+//! - Data validation rules (dropdowns and number ranges)
 //! - Streaming support for large files
 //! - Macro analysis and security handling
 //! - Pivot table metadata (read-only)
@@ -238,4 +238,617 @@ mod tests {
         assert_eq!(range_ref.end_col, Some(4));
         assert_eq!(range_ref.end_row, Some(9));
     }
+
+    /// Workbook with a sales table and defined names pointing into it
+    fn named_range_fixture(path: &std::path::Path) {
+        let mut workbook = rust_xlsxwriter::Workbook::new();
+        let worksheet = workbook.add_worksheet().set_name("Q1 Sales").unwrap();
+        let rows = [
+            ["Region", "Amount"],
+            ["North", "120"],
+            ["South", "80"],
+            ["East", "95"],
+        ];
+        for (row, values) in rows.iter().enumerate() {
+            for (col, value) in values.iter().enumerate() {
+                worksheet.write(row as u32, col as u16, *value).unwrap();
+            }
+        }
+        workbook.add_worksheet().set_name("Other").unwrap();
+        workbook
+            .define_name("SalesData", "='Q1 Sales'!$A$2:$B$4")
+            .unwrap();
+        workbook
+            .define_name("Regions", "='Q1 Sales'!$A$2:$A$4")
+            .unwrap();
+        workbook.save(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_read_range_through_defined_name() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sales.xlsx");
+        named_range_fixture(&path);
+        let provider = ExcelProvider::new();
+        let sheet_id = dox_core::SheetId(path.to_string_lossy().into_owned());
+
+        let read = |range: &'static str| {
+            let provider = &provider;
+            let sheet_id = &sheet_id;
+            async move {
+                provider
+                    .read_range(sheet_id, &RangeRef::new(range), None)
+                    .await
+                    .unwrap()
+                    .into_iter()
+                    .map(|row| row.into_iter().map(|c| c.value).collect::<Vec<_>>())
+                    .collect::<Vec<_>>()
+            }
+        };
+
+        assert_eq!(
+            read("SalesData").await,
+            vec![
+                vec!["North", "120"],
+                vec!["South", "80"],
+                vec!["East", "95"]
+            ]
+        );
+        assert_eq!(
+            read("regions").await,
+            vec![vec!["North"], vec!["South"], vec!["East"]]
+        );
+
+        // Plain A1 ranges still work alongside names
+        assert_eq!(
+            read("Q1 Sales!B1:B2").await,
+            vec![vec!["Amount"], vec!["120"]]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_read_range_subset() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("grid.xlsx");
+        let mut workbook = rust_xlsxwriter::Workbook::new();
+        let worksheet = workbook.add_worksheet().set_name("Grid").unwrap();
+        for row in 0..200u32 {
+            for col in 0..6u16 {
+                let value = format!("{}{}", (b'A' + col as u8) as char, row + 1);
+                worksheet.write(row, col, value).unwrap();
+            }
+        }
+        worksheet.write(2, 2, 7.5).unwrap();
+        workbook.save(&path).unwrap();
+
+        let provider = ExcelProvider::new();
+        let sheet_id = dox_core::SheetId(path.to_string_lossy().into_owned());
+        let cells = provider
+            .read_range(&sheet_id, &RangeRef::new("Grid!B2:C3"), None)
+            .await
+            .unwrap();
+
+        let values: Vec<Vec<String>> = cells
+            .into_iter()
+            .map(|row| row.into_iter().map(|cell| cell.value).collect())
+            .collect();
+        assert_eq!(values, vec![vec!["B2", "C2"], vec!["B3", "7.5"]]);
+
+        // Ranges are cut off where the used area ends
+        let cells = provider
+            .read_range(&sheet_id, &RangeRef::new("F200:G201"), None)
+            .await
+            .unwrap();
+        assert_eq!(cells.len(), 1);
+        assert_eq!(cells[0].len(), 1);
+        assert_eq!(cells[0][0].value, "F200");
+
+        let cells = provider
+            .read_range(&sheet_id, &RangeRef::new("Grid!A1:XFD1048576"), None)
+            .await
+            .unwrap();
+        assert_eq!(cells.len(), 200);
+        assert!(cells.iter().all(|row| row.len() == 6));
+
+        let cells = provider
+            .read_range(&sheet_id, &RangeRef::new("H300:H400"), None)
+            .await
+            .unwrap();
+        assert!(cells.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_read_range_rejects_malformed_reference() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("grid.xlsx");
+        let mut workbook = rust_xlsxwriter::Workbook::new();
+        workbook.add_worksheet().set_name("Grid").unwrap();
+        workbook.save(&path).unwrap();
+
+        let provider = ExcelProvider::new();
+        let sheet_id = dox_core::SheetId(path.to_string_lossy().into_owned());
+        let error = provider
+            .read_range(&sheet_id, &RangeRef::new("Grid!C5:A1"), None)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("inverted"), "{}", error);
+
+        let error = provider
+            .write_range(
+                &sheet_id,
+                &RangeRef::new("Grid!XFE1"),
+                vec![vec![dox_core::Cell::new("x")]],
+                None,
+            )
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("XFD"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn test_date_round_trip() {
+        use std::io::Read;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("schedule.xlsx");
+        let sheet_id = dox_core::SheetId(path.to_string_lossy().into_owned());
+        let rows = vec![
+            vec!["Task", "Due", "Started"],
+            vec!["Draft", "2024-03-15", "2024-03-01T09:30:00"],
+            vec!["Review", "2023-12-31", "2024-02-29T17:05:30"],
+        ];
+        let data: Vec<Vec<dox_core::Cell>> = rows
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|value| dox_core::Cell::new(*value))
+                    .collect()
+            })
+            .collect();
+
+        let provider = ExcelProvider::new();
+        provider
+            .write_range(&sheet_id, &RangeRef::new("A1"), data, None)
+            .await
+            .unwrap();
+
+        let cells = provider
+            .read_range(&sheet_id, &RangeRef::new("A1:C3"), None)
+            .await
+            .unwrap();
+        let values: Vec<Vec<String>> = cells
+            .into_iter()
+            .map(|row| row.into_iter().map(|cell| cell.value).collect())
+            .collect();
+        assert_eq!(values, rows);
+
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(&path).unwrap()).unwrap();
+        let mut read_part = |name: &str| {
+            let mut xml = String::new();
+            archive
+                .by_name(name)
+                .unwrap()
+                .read_to_string(&mut xml)
+                .unwrap();
+            xml
+        };
+        let sheet = read_part("xl/worksheets/sheet1.xml");
+        let styles = read_part("xl/styles.xml");
+
+        // Stored as Excel serial dates, not text
+        assert!(regex::Regex::new(r#"<c r="B2" s="\d+"><v>45366</v></c>"#)
+            .unwrap()
+            .is_match(&sheet));
+        assert!(styles.contains(r#"formatCode="yyyy-mm-dd""#));
+        assert!(styles.contains(r#"formatCode="yyyy-mm-dd hh:mm:ss""#));
+    }
+
+    /// Write a small table with `options` and return the worksheet XML
+    async fn write_and_read_sheet_xml(options: dox_core::WriteOptions) -> String {
+        use std::io::Read;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("report.xlsx");
+        let sheet_id = dox_core::SheetId(path.to_string_lossy().into_owned());
+        let data = vec![
+            vec![dox_core::Cell::new("Name"), dox_core::Cell::new("Score")],
+            vec![dox_core::Cell::new("Kim"), dox_core::Cell::new("90")],
+        ];
+        ExcelProvider::new()
+            .write_range(&sheet_id, &RangeRef::new("A1"), data, Some(options))
+            .await
+            .unwrap();
+
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(&path).unwrap()).unwrap();
+        let mut xml = String::new();
+        archive
+            .by_name("xl/worksheets/sheet1.xml")
+            .unwrap()
+            .read_to_string(&mut xml)
+            .unwrap();
+        xml
+    }
+
+    #[tokio::test]
+    async fn test_write_range_freeze_panes() {
+        let xml = write_and_read_sheet_xml(dox_core::WriteOptions {
+            freeze_panes: Some((1, 1)),
+            ..Default::default()
+        })
+        .await;
+
+        assert!(xml.contains(
+            r#"<pane xSplit="1" ySplit="1" topLeftCell="B2" activePane="bottomRight" state="frozen"/>"#
+        ));
+        assert!(!xml.contains("<sheetProtection"));
+    }
+
+    #[tokio::test]
+    async fn test_write_range_sheet_protection() {
+        let protection = dox_core::SheetProtection {
+            allowed: vec![
+                dox_core::ProtectedAction::SelectUnlockedCells,
+                dox_core::ProtectedAction::Sort,
+            ],
+            ..dox_core::SheetProtection::with_password("secret")
+        };
+        let xml = write_and_read_sheet_xml(dox_core::WriteOptions {
+            protect: Some(protection),
+            ..Default::default()
+        })
+        .await;
+
+        let protection = &xml[xml.find("<sheetProtection").expect("protected sheet")..];
+        let protection = &protection[..protection.find("/>").unwrap()];
+        assert!(protection.contains(r#"sheet="1""#));
+        assert!(protection.contains(r#"password=""#));
+        assert!(protection.contains(r#"sort="0""#));
+        assert!(protection.contains(r#"selectLockedCells="1""#));
+        assert!(!xml.contains("<pane"));
+    }
+
+    #[tokio::test]
+    async fn test_read_workbook_opens_file_once() {
+        use std::sync::atomic::Ordering;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("workbook.xlsx");
+        let mut workbook = rust_xlsxwriter::Workbook::new();
+        for (name, value) in [("Sales", "100"), ("Costs", "40"), ("Notes", "memo")] {
+            let sheet = workbook.add_worksheet().set_name(name).unwrap();
+            sheet.write_string(0, 0, name).unwrap();
+            sheet.write_string(1, 0, value).unwrap();
+        }
+        workbook.save(&path).unwrap();
+
+        let provider = ExcelProvider::new();
+        let sheet_id = dox_core::SheetId(path.display().to_string());
+        let sheets = provider.read_workbook(&sheet_id, None).await.unwrap();
+
+        assert_eq!(sheets.len(), 3);
+        assert_eq!(sheets["Sales"][1][0].value, "100");
+        assert_eq!(sheets["Costs"][0][0].value, "Costs");
+        assert_eq!(sheets["Notes"][1][0].value, "memo");
+        assert_eq!(provider.opened.load(Ordering::SeqCst), 1);
+
+        // Listing and reading sheet by sheet opens the file once per call
+        for sheet in provider.list_sheets(&sheet_id).await.unwrap() {
+            let range = RangeRef::new(format!("{}!", sheet.name));
+            let data = provider.read_range(&sheet_id, &range, None).await.unwrap();
+            assert_eq!(data, sheets[&sheet.name]);
+        }
+        assert_eq!(provider.opened.load(Ordering::SeqCst), 5);
+    }
+
+    #[tokio::test]
+    async fn test_read_range_with_merges() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("merged.xlsx");
+        let mut workbook = rust_xlsxwriter::Workbook::new();
+        let worksheet = workbook.add_worksheet().set_name("Report").unwrap();
+        let format = rust_xlsxwriter::Format::new();
+        worksheet
+            .merge_range(0, 0, 0, 2, "Quarterly sales", &format)
+            .unwrap();
+        for (col, value) in ["Jan", "Feb", "Mar"].into_iter().enumerate() {
+            worksheet.write_string(1, col as u16, value).unwrap();
+            worksheet.write_number(2, col as u16, 10.0).unwrap();
+        }
+        workbook.save(&path).unwrap();
+
+        let provider = ExcelProvider::new();
+        let sheet_id = dox_core::SheetId(path.display().to_string());
+        let header = MergedRange {
+            first_row: 0,
+            first_col: 0,
+            last_row: 0,
+            last_col: 2,
+        };
+
+        let (data, merges) = provider
+            .read_range_with_merges(&sheet_id, &RangeRef::new("Report!A1:C3"), None)
+            .await
+            .unwrap();
+        assert_eq!(merges, vec![header]);
+        assert_eq!(data[0][0].value, "Quarterly sales");
+        assert!(data[0][1].value.is_empty() && data[0][2].value.is_empty());
+        assert_eq!(data[1][1].value, "Feb");
+
+        // Ranges that miss the merge report none
+        let (_, merges) = provider
+            .read_range_with_merges(&sheet_id, &RangeRef::new("Report!A2:C3"), None)
+            .await
+            .unwrap();
+        assert!(merges.is_empty());
+
+        // Filling copies the anchor, even when the range starts inside the merge
+        let options = ReadOptions {
+            fill_merged: true,
+            ..ReadOptions::default()
+        };
+        let data = provider
+            .read_range(&sheet_id, &RangeRef::new("Report!B1:C2"), Some(options))
+            .await
+            .unwrap();
+        assert_eq!(data[0][0].value, "Quarterly sales");
+        assert_eq!(data[0][1].value, "Quarterly sales");
+        assert_eq!(data[1][0].value, "Feb");
+    }
+
+    #[tokio::test]
+    async fn test_read_comments() {
+        use rust_xlsxwriter::Note;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("comments.xlsx");
+        let mut workbook = rust_xlsxwriter::Workbook::new();
+        let summary = workbook.add_worksheet().set_name("Summary").unwrap();
+        summary.write_number(1, 1, 1200.0).unwrap();
+        summary
+            .insert_note(
+                1,
+                1,
+                &Note::new("Includes the March refund").set_author("Kim"),
+            )
+            .unwrap();
+        let detail = workbook.add_worksheet().set_name("Detail").unwrap();
+        detail
+            .insert_note(4, 2, &Note::new("Source: ERP export").set_author("Lee"))
+            .unwrap();
+        workbook.save(&path).unwrap();
+
+        let provider = ExcelProvider::new();
+        let sheet_id = dox_core::SheetId(path.display().to_string());
+        let comments = provider.read_comments(&sheet_id).await.unwrap();
+
+        assert_eq!(comments.len(), 2);
+        assert_eq!(comments[0].sheet, "Summary");
+        assert_eq!(comments[0].cell, "B2");
+        assert_eq!(comments[0].author.as_deref(), Some("Kim"));
+        assert_eq!(comments[0].text, "Includes the March refund");
+        assert_eq!(comments[1].sheet, "Detail");
+        assert_eq!(comments[1].cell, "C5");
+        assert_eq!(comments[1].author.as_deref(), Some("Lee"));
+        assert_eq!(comments[1].text, "Source: ERP export");
+    }
+
+    #[tokio::test]
+    async fn test_hidden_rows_columns_and_sheets() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("hidden.xlsx");
+        let mut workbook = rust_xlsxwriter::Workbook::new();
+        let totals = workbook.add_worksheet().set_name("Totals").unwrap();
+        for (row, values) in [
+            ["Item", "Cost", "Price"],
+            ["Pen", "1", "3"],
+            ["Ink", "2", "5"],
+        ]
+        .iter()
+        .enumerate()
+        {
+            for (col, value) in values.iter().enumerate() {
+                totals.write_string(row as u32, col as u16, *value).unwrap();
+            }
+        }
+        totals.set_column_hidden(1).unwrap();
+        totals.set_row_hidden(2).unwrap();
+        workbook
+            .add_worksheet()
+            .set_name("Lookup")
+            .unwrap()
+            .set_hidden(true);
+        workbook.save(&path).unwrap();
+
+        let provider = ExcelProvider::new();
+        let sheet_id = dox_core::SheetId(path.display().to_string());
+
+        let sheets = provider.list_sheets(&sheet_id).await.unwrap();
+        assert_eq!(sheets.len(), 2);
+        assert!(!sheets[0].hidden);
+        assert_eq!(sheets[0].hidden_rows, vec![2]);
+        assert_eq!(sheets[0].hidden_columns, vec![1]);
+        assert!(sheets[1].hidden);
+
+        let metadata = provider.get_metadata(&sheet_id).await.unwrap();
+        assert!(metadata.sheets[1].hidden);
+
+        // Hidden lines are read unless asked otherwise
+        let range = RangeRef::new("Totals!A1:C3");
+        let data = provider.read_range(&sheet_id, &range, None).await.unwrap();
+        assert_eq!(data.len(), 3);
+        assert_eq!(data[0][1].value, "Cost");
+
+        let options = ReadOptions {
+            skip_hidden: true,
+            ..ReadOptions::default()
+        };
+        let data = provider
+            .read_range(&sheet_id, &range, Some(options))
+            .await
+            .unwrap();
+        let values: Vec<Vec<&str>> = data
+            .iter()
+            .map(|row| row.iter().map(|cell| cell.value.as_str()).collect())
+            .collect();
+        assert_eq!(values, vec![vec!["Item", "Price"], vec!["Pen", "3"]]);
+    }
+
+    #[tokio::test]
+    async fn test_write_ranges_keeps_every_sheet() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("report.xlsx");
+        let sheet_id = dox_core::SheetId(path.display().to_string());
+        let rows = |values: &[&[&str]]| -> Vec<Vec<dox_core::Cell>> {
+            values
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .map(|value| dox_core::Cell::new(*value))
+                        .collect()
+                })
+                .collect()
+        };
+
+        let provider = ExcelProvider::new();
+        provider
+            .write_ranges(
+                &sheet_id,
+                vec![
+                    (
+                        "Sales".to_string(),
+                        rows(&[&["Region", "Total"], &["East", "120"]]),
+                    ),
+                    (
+                        "Q1 Costs".to_string(),
+                        rows(&[&["Item"], &["Rent"], &["Power"]]),
+                    ),
+                ],
+                None,
+            )
+            .await
+            .unwrap();
+
+        let names: Vec<String> = provider
+            .list_sheets(&sheet_id)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|sheet| sheet.name)
+            .collect();
+        assert_eq!(names, vec!["Sales", "Q1 Costs"]);
+
+        let sheets = provider.read_workbook(&sheet_id, None).await.unwrap();
+        assert_eq!(sheets["Sales"][1][0].value, "East");
+        assert_eq!(sheets["Sales"][1][1].value, "120");
+        assert_eq!(sheets["Q1 Costs"].len(), 3);
+        assert_eq!(sheets["Q1 Costs"][2][0].value, "Power");
+
+        // A batch that cannot be written leaves the file as it was
+        let error = provider
+            .write_ranges(
+                &sheet_id,
+                vec![
+                    ("Data".to_string(), rows(&[&["a"]])),
+                    ("data".to_string(), rows(&[&["b"]])),
+                ],
+                None,
+            )
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("more than once"), "{}", error);
+        assert_eq!(
+            provider.read_workbook(&sheet_id, None).await.unwrap(),
+            sheets
+        );
+    }
+
+    #[tokio::test]
+    async fn test_preserve_formulas_through_rewrite() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("budget.xlsx");
+        let mut workbook = rust_xlsxwriter::Workbook::new();
+        let budget = workbook.add_worksheet().set_name("Budget").unwrap();
+        budget.write_string(0, 0, "Cost").unwrap();
+        budget.write_string(0, 1, "With tax").unwrap();
+        for (row, cost) in [(1, 10.0), (2, 20.0)] {
+            budget.write_number(row, 0, cost).unwrap();
+            let formula = rust_xlsxwriter::Formula::new(format!("=A{}*1.1", row + 1))
+                .set_result((cost * 1.1).to_string());
+            budget.write_formula(row, 1, formula).unwrap();
+        }
+        let total = rust_xlsxwriter::Formula::new("=SUM(A2:A3)").set_result("30");
+        budget.write_formula(3, 0, total).unwrap();
+        workbook.save(&source).unwrap();
+
+        let provider = ExcelProvider::new();
+        let source_id = dox_core::SheetId(source.display().to_string());
+
+        // Without the flag a formula reads as its cached result
+        let range = RangeRef::new("Budget!A4");
+        let data = provider.read_range(&source_id, &range, None).await.unwrap();
+        assert_eq!(data[0][0].value, "30");
+
+        let options = ReadOptions {
+            preserve_formulas: true,
+            ..ReadOptions::default()
+        };
+        let data = provider
+            .read_range(&source_id, &range, Some(options.clone()))
+            .await
+            .unwrap();
+        assert_eq!(data[0][0].value, "=SUM(A2:A3)");
+
+        // Recreate the workbook from what was read
+        let sheets = provider
+            .read_workbook(&source_id, Some(options))
+            .await
+            .unwrap();
+        let copy = dir.path().join("copy.xlsx");
+        let copy_id = dox_core::SheetId(copy.display().to_string());
+        provider
+            .write_ranges(&copy_id, sheets.into_iter().collect(), None)
+            .await
+            .unwrap();
+
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(&copy).unwrap()).unwrap();
+        let xml = crate::package::read_part(&mut archive, "xl/worksheets/sheet1.xml")
+            .unwrap()
+            .unwrap();
+        for formula in ["<f>A2*1.1</f>", "<f>A3*1.1</f>", "<f>SUM(A2:A3)</f>"] {
+            assert!(xml.contains(formula), "{} missing from {}", formula, xml);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_evaluate_formulas_error_codes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("errors.xlsx");
+        let mut workbook = rust_xlsxwriter::Workbook::new();
+        let sheet = workbook.add_worksheet();
+        // Formula text stored as strings, as the evaluator reads it
+        for (col, text) in ["2", "=A1*3", "=D1+1", "=C1+1", "=XFDA1", "=A0+1"]
+            .into_iter()
+            .enumerate()
+        {
+            sheet.write_string(0, col as u16, text).unwrap();
+        }
+        workbook.save(&path).unwrap();
+
+        let provider = ExcelProvider::new();
+        let sheet_id = dox_core::SheetId(path.display().to_string());
+        let options = ReadOptions {
+            evaluate_formulas: true,
+            ..ReadOptions::default()
+        };
+        let data = provider
+            .read_range(&sheet_id, &RangeRef::new("A1:F1"), Some(options))
+            .await
+            .unwrap();
+
+        let values: Vec<_> = data[0].iter().map(|cell| cell.value.as_str()).collect();
+        assert_eq!(
+            values,
+            ["2", "6", "#CIRCULAR!", "#CIRCULAR!", "#REF!", "#REF!"]
+        );
+    }
 }
@@ -1,42 +1,17 @@
 //! Excel data validation using rust_xlsxwriter
 //!
-//! This module provides simplified functionality to:
-//! - Create basic data validation rules (currently limited by rust_xlsxwriter API)
-//! - Document validation requirements for future implementation
-//! - Handle dropdown lists and basic input validation
+//! This module provides functionality to:
+//! - Write data validation rules (dropdown lists, number ranges) to a worksheet
+//! - Check cell values against those rules before they are written
+//! - Build validation templates for common patterns
 
-use anyhow::Result;
-use rust_xlsxwriter::Worksheet;
+use anyhow::{anyhow, Result};
+use rust_xlsxwriter::{DataValidation, DataValidationRule, Formula, Worksheet};
 use serde::{Deserialize, Serialize};
-use tracing::{info, warn};
+use tracing::debug;
 
-use dox_core::RangeRef;
-
-/// Basic validation types supported
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum SimpleValidationType {
-    /// List of allowed values
-    List(Vec<String>),
-    /// Any integer number
-    WholeNumber,
-    /// Any decimal number  
-    Decimal,
-    /// Custom formula (documented but not implemented)
-    Custom(String),
-}
-
-/// Simplified validation configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct SimpleValidationConfig {
-    /// Target range for validation
-    pub range: RangeRef,
-    /// Type of validation
-    pub validation_type: SimpleValidationType,
-    /// Input message for user
-    pub input_message: Option<String>,
-    /// Allow blank cells
-    pub allow_blank: bool,
-}
+use dox_core::{column_letter_to_index, RangeRef};
+pub use dox_core::{SimpleValidationConfig, SimpleValidationType, ValidationMode};
 
 /// Simple validation manager
 pub struct SimpleValidationManager<'a> {
@@ -48,52 +23,45 @@ impl<'a> SimpleValidationManager<'a> {
         Self { worksheet }
     }
 
-    /// Apply basic validation - simplified to avoid API compatibility issues
+    /// Add the validation to the worksheet so Excel enforces it on input
     pub fn apply_validation(&mut self, config: &SimpleValidationConfig) -> Result<()> {
-        // For now, just log what validation would be applied
-        // Future implementations can use more sophisticated API calls when available
+        let (first_row, first_col, last_row, last_col) = parse_cell_range(&config.range)?;
 
-        match &config.validation_type {
-            SimpleValidationType::List(values) => {
-                info!(
-                    "List validation would be applied to {} with {} options",
-                    config.range.0,
-                    values.len()
-                );
-
-                // Note: Would implement with DataValidation::new().set_list() when API available
-                warn!("List validation documented but not applied - API limitations");
-            }
-            SimpleValidationType::WholeNumber => {
-                info!(
-                    "Whole number validation would be applied to {}",
-                    config.range.0
-                );
-                warn!("Number validation documented but not applied - API limitations");
+        let validation =
+            match &config.validation_type {
+                SimpleValidationType::List(values) => DataValidation::new()
+                    .allow_list_strings(values)
+                    .map_err(|e| anyhow!("Invalid list validation: {}", e))?,
+                SimpleValidationType::WholeNumber => DataValidation::new()
+                    .allow_whole_number(DataValidationRule::Between(i32::MIN, i32::MAX)),
+                SimpleValidationType::Decimal => DataValidation::new()
+                    .allow_decimal_number(DataValidationRule::Between(f64::MIN, f64::MAX)),
+                SimpleValidationType::WholeNumberBetween(min, max) => DataValidation::new()
+                    .allow_whole_number(DataValidationRule::Between(*min, *max)),
+                SimpleValidationType::DecimalBetween(min, max) => DataValidation::new()
+                    .allow_decimal_number(DataValidationRule::Between(*min, *max)),
+                SimpleValidationType::Custom(formula) => {
+                    DataValidation::new().allow_custom(Formula::new(formula))
+                }
             }
-            SimpleValidationType::Decimal => {
-                info!("Decimal validation would be applied to {}", config.range.0);
-                warn!("Decimal validation documented but not applied - API limitations");
-            }
-            SimpleValidationType::Custom(formula) => {
-                info!(
-                    "Custom validation with formula '{}' would be applied to {}",
-                    formula, config.range.0
-                );
-                warn!("Custom validation documented but not applied - API limitations");
-            }
-        }
+            .ignore_blank(config.allow_blank);
 
-        if let Some(message) = &config.input_message {
-            info!("Input message would be: {}", message);
-        }
+        let validation = match &config.input_message {
+            Some(message) => validation
+                .set_input_message(message)
+                .map_err(|e| anyhow!("Invalid input message: {}", e))?,
+            None => validation,
+        };
 
-        info!("Allow blank: {}", config.allow_blank);
+        self.worksheet
+            .add_data_validation(first_row, first_col, last_row, last_col, &validation)
+            .map_err(|e| anyhow!("Failed to add data validation: {}", e))?;
 
+        debug!("Added data validation to {}", config.range.0);
         Ok(())
     }
 
-    /// Create a simple dropdown (documented for future implementation)
+    /// Create a dropdown list
     pub fn create_dropdown(
         &mut self,
         range: &RangeRef,
@@ -110,7 +78,7 @@ impl<'a> SimpleValidationManager<'a> {
         self.apply_validation(&config)
     }
 
-    /// Create number validation (documented for future implementation)
+    /// Create number validation
     pub fn create_number_validation(
         &mut self,
         range: &RangeRef,
@@ -132,6 +100,101 @@ impl<'a> SimpleValidationManager<'a> {
     }
 }
 
+/// A cell value that breaks a validation rule
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationViolation {
+    /// Cell address in A1 notation
+    pub cell: String,
+    pub value: String,
+    /// Range of the rule that was broken
+    pub range: String,
+}
+
+impl std::fmt::Display for ValidationViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: '{}' does not satisfy the validation for {}",
+            self.cell, self.value, self.range
+        )
+    }
+}
+
+/// Check data written from A1 of `sheet_name` against the validations
+///
+/// Rules whose range names a different sheet are ignored. Only cells present
+/// in `data` are checked.
+pub fn find_violations(
+    validations: &[SimpleValidationConfig],
+    sheet_name: &str,
+    data: &[Vec<dox_core::Cell>],
+) -> Result<Vec<ValidationViolation>> {
+    let mut violations = Vec::new();
+
+    for config in validations {
+        if !applies_to_sheet(&config.range, sheet_name) {
+            continue;
+        }
+        let (first_row, first_col, last_row, last_col) = parse_cell_range(&config.range)?;
+
+        for (row_idx, row) in data.iter().enumerate() {
+            let row_num = row_idx as u32;
+            if !(first_row..=last_row).contains(&row_num) {
+                continue;
+            }
+            for (col_idx, cell) in row.iter().enumerate() {
+                let col_num = col_idx as u16;
+                if (first_col..=last_col).contains(&col_num) && !config.accepts(&cell.value) {
+                    violations.push(ValidationViolation {
+                        cell: rust_xlsxwriter::utility::row_col_to_cell(row_num, col_num),
+                        value: cell.value.clone(),
+                        range: config.range.0.clone(),
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(violations)
+}
+
+/// Whether a range targets `sheet_name`; ranges without a sheet apply to any
+pub(crate) fn applies_to_sheet(range: &RangeRef, sheet_name: &str) -> bool {
+    match range.parse() {
+        (Some(sheet), _) => sheet.trim_matches('\'') == sheet_name,
+        (None, _) => true,
+    }
+}
+
+/// Parse an A1 range such as `B2:B10` or `Sheet1!C3` into zero-based
+/// `(first_row, first_col, last_row, last_col)`
+pub fn parse_cell_range(range: &RangeRef) -> Result<(u32, u16, u32, u16)> {
+    let (_, cells) = range.parse();
+    let (first, last) = cells.split_once(':').unwrap_or((&cells, &cells));
+    let (first_row, first_col) = parse_cell(first)
+        .ok_or_else(|| anyhow!("Invalid cell range for validation: {}", range.0))?;
+    let (last_row, last_col) = parse_cell(last)
+        .ok_or_else(|| anyhow!("Invalid cell range for validation: {}", range.0))?;
+
+    Ok((
+        first_row.min(last_row),
+        first_col.min(last_col),
+        first_row.max(last_row),
+        first_col.max(last_col),
+    ))
+}
+
+/// Parse a single A1 cell reference, ignoring `$` markers
+pub(crate) fn parse_cell(cell: &str) -> Option<(u32, u16)> {
+    let cell = cell.trim().replace('$', "");
+    let split = cell.find(|c: char| c.is_ascii_digit())?;
+    let (letters, digits) = cell.split_at(split);
+    let col = column_letter_to_index(letters)?;
+    let row = digits.parse::<u32>().ok().filter(|&row| row > 0)?;
+
+    Some((row - 1, col as u16))
+}
+
 /// Template for common validation patterns
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ValidationTemplate {
@@ -183,6 +246,138 @@ impl ValidationTemplate {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use dox_core::{Cell, SheetId, SpreadsheetProvider, WriteOptions};
+    use std::io::Read;
+
+    fn cells(rows: &[&[&str]]) -> Vec<Vec<Cell>> {
+        rows.iter()
+            .map(|row| row.iter().map(|value| Cell::new(*value)).collect())
+            .collect()
+    }
+
+    fn write_options(config: SimpleValidationConfig) -> WriteOptions {
+        WriteOptions {
+            validations: vec![config],
+            ..Default::default()
+        }
+    }
+
+    fn sheet_xml(path: &std::path::Path) -> String {
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(path).unwrap()).unwrap();
+        let mut xml = String::new();
+        archive
+            .by_name("xl/worksheets/sheet1.xml")
+            .unwrap()
+            .read_to_string(&mut xml)
+            .unwrap();
+        xml
+    }
+
+    #[tokio::test]
+    async fn test_list_validation_on_write() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("colors.xlsx");
+        let sheet_id = SheetId(path.to_string_lossy().into_owned());
+        let provider = crate::ExcelProvider::new();
+        let config = SimpleValidationConfig {
+            range: RangeRef::new("Colors!B2:B10"),
+            validation_type: SimpleValidationType::List(vec![
+                "Red".to_string(),
+                "Green".to_string(),
+            ]),
+            input_message: None,
+            allow_blank: true,
+        };
+        let range = RangeRef::new("Colors!A1");
+
+        let err = provider
+            .write_range(
+                &sheet_id,
+                &range,
+                cells(&[&["Item", "Color"], &["Cap", "Red"], &["Hat", "Blue"]]),
+                Some(write_options(config.clone())),
+            )
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("B3: 'Blue'"));
+        assert!(!path.exists());
+
+        provider
+            .write_range(
+                &sheet_id,
+                &range,
+                cells(&[&["Item", "Color"], &["Cap", "Red"], &["Hat", ""]]),
+                Some(write_options(config)),
+            )
+            .await
+            .unwrap();
+
+        let xml = sheet_xml(&path);
+        assert!(xml.contains(r#"<dataValidation type="list" allowBlank="1""#));
+        assert!(xml.contains(r#"sqref="B2:B10""#));
+        assert!(xml.contains("<formula1>\"Red,Green\"</formula1>"));
+    }
+
+    #[tokio::test]
+    async fn test_number_range_validation_on_write() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ages.xlsx");
+        let sheet_id = SheetId(path.to_string_lossy().into_owned());
+        let provider = crate::ExcelProvider::new();
+        let config = SimpleValidationConfig {
+            range: RangeRef::new("A1:A5"),
+            validation_type: SimpleValidationType::WholeNumberBetween(0, 120),
+            input_message: Some("Enter an age".to_string()),
+            allow_blank: false,
+        };
+        let range = RangeRef::new("A1");
+
+        let err = provider
+            .write_range(
+                &sheet_id,
+                &range,
+                cells(&[&["42"], &["130"], &["4.5"]]),
+                Some(write_options(config.clone())),
+            )
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("2 cell(s)"));
+        assert!(!path.exists());
+
+        // Warn mode writes the data anyway
+        let options = WriteOptions {
+            validation_mode: ValidationMode::Warn,
+            ..write_options(config)
+        };
+        provider
+            .write_range(
+                &sheet_id,
+                &range,
+                cells(&[&["42"], &["130"]]),
+                Some(options),
+            )
+            .await
+            .unwrap();
+
+        let xml = sheet_xml(&path);
+        assert!(xml.contains(r#"<dataValidation type="whole""#));
+        assert!(xml.contains(r#"sqref="A1:A5""#));
+        assert!(xml.contains("<formula1>0</formula1><formula2>120</formula2>"));
+        assert!(xml.contains("<v>130</v>"));
+    }
+
+    #[test]
+    fn test_parse_cell_range() {
+        assert_eq!(
+            parse_cell_range(&RangeRef::new("Sheet1!$B$2:AA10")).unwrap(),
+            (1, 1, 9, 26)
+        );
+        assert_eq!(
+            parse_cell_range(&RangeRef::new("C3")).unwrap(),
+            (2, 2, 2, 2)
+        );
+        assert!(parse_cell_range(&RangeRef::new("A0:B2")).is_err());
+    }
 
     #[test]
     fn test_validation_template_creation() {
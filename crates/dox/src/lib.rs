@@ -0,0 +1,85 @@
+//! # dox
+//!
+//! High-level entry points for using dox from another Rust application.
+//! Each function takes a path and plain options and picks the right provider
+//! for the document itself, so callers never deal with extractors, creators
+//! or format detection.
+//!
+//! ```no_run
+//! use dox::{ExtractFormat, OutputFormat, ReplaceOptions, Rule};
+//! use std::path::Path;
+//!
+//! # async fn run() -> anyhow::Result<()> {
+//! let path = Path::new("report.docx");
+//! dox::create_document("# Report\n\nDraft v1", OutputFormat::Word, path)?;
+//!
+//! let rules = vec![Rule::new("Draft v1", "Final")];
+//! let results = dox::replace_in_document(path, rules, ReplaceOptions::default()).await?;
+//! assert_eq!(results.total_replacements, 1);
+//!
+//! let text = dox::extract_document(path, ExtractFormat::Text)?;
+//! assert!(text.contains("Final"));
+//! # Ok(())
+//! # }
+//! ```
+
+use anyhow::{Context, Result};
+use dox_core::create::{CreateOptions, CreateRequest, DocumentCreatorFactory, MarkdownParser};
+use dox_document::extract::extractors::UniversalExtractor;
+use dox_document::{OutputFormatter, Replacer};
+use std::path::Path;
+
+pub use dox_core::create::OutputFormat;
+pub use dox_document::{ExtractFormat, ReplaceOptions, ReplaceResults, Rule};
+
+/// Extract the content of a Word, PowerPoint, Excel, PDF or text document
+///
+/// The document type is taken from the file extension and the result is
+/// rendered the same way `dox extract --format` would.
+pub fn extract_document(path: &Path, format: ExtractFormat) -> Result<String> {
+    let result = UniversalExtractor::extract_from_path(path)
+        .with_context(|| format!("Failed to extract {}", path.display()))?;
+    Ok(OutputFormatter::format(&result, format)?)
+}
+
+/// Apply replacement rules to a document, or to every document under a
+/// directory
+///
+/// Files are modified in place; set [`ReplaceOptions::dry_run`] to only count
+/// the matches.
+pub async fn replace_in_document(
+    path: &Path,
+    rules: Vec<Rule>,
+    options: ReplaceOptions,
+) -> Result<ReplaceResults> {
+    for (i, rule) in rules.iter().enumerate() {
+        rule.validate()
+            .with_context(|| format!("Invalid rule at index {}", i))?;
+    }
+    Replacer::new(rules).process_path(path, options).await
+}
+
+/// Create a Word or PowerPoint document from Markdown
+///
+/// An existing file at `path` is replaced. The document title defaults to
+/// the file name, as with `dox create`.
+pub fn create_document(markdown: &str, format: OutputFormat, path: &Path) -> Result<()> {
+    let options = CreateOptions {
+        title: path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .map(|stem| stem.to_string()),
+        image_base_dir: path.parent().map(|dir| dir.to_path_buf()),
+        ..Default::default()
+    };
+
+    let document = MarkdownParser::new(options.clone()).parse(markdown)?;
+    let request = CreateRequest {
+        content: markdown.to_string(),
+        format,
+        template_path: None,
+        output_path: path.display().to_string(),
+        options,
+    };
+    DocumentCreatorFactory::create_creator(format)?.create_document(&document, &request)
+}
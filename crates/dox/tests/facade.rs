@@ -0,0 +1,60 @@
+use dox::{ExtractFormat, OutputFormat, ReplaceOptions, Rule};
+
+const REPORT: &str =
+    "# Quarterly Report\n\nStatus: {{status}}\n\n- Sales rose\n- Costs were flat\n";
+
+#[tokio::test]
+async fn test_create_replace_extract_word() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("report.docx");
+
+    dox::create_document(REPORT, OutputFormat::Word, &path).unwrap();
+    assert!(dox_core::create::validate_package(&path).is_ok());
+
+    let text = dox::extract_document(&path, ExtractFormat::Text).unwrap();
+    assert!(text.contains("Quarterly Report"));
+    assert!(text.contains("{{status}}"));
+
+    let rules = vec![Rule::new("{{status}}", "On track")];
+    let results = dox::replace_in_document(&path, rules, ReplaceOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(results.files_processed, 1);
+    assert_eq!(results.total_replacements, 1);
+
+    let text = dox::extract_document(&path, ExtractFormat::Text).unwrap();
+    assert!(text.contains("Status: On track"));
+    assert!(!text.contains("{{status}}"));
+}
+
+#[test]
+fn test_create_and_extract_powerpoint() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("deck.pptx");
+
+    dox::create_document(REPORT, OutputFormat::PowerPoint, &path).unwrap();
+
+    let json = dox::extract_document(&path, ExtractFormat::Json).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert!(value.is_object());
+    assert!(json.contains("Quarterly Report"));
+}
+
+#[tokio::test]
+async fn test_replace_rejects_invalid_rules() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("report.docx");
+    dox::create_document(REPORT, OutputFormat::Word, &path).unwrap();
+
+    let rules = vec![Rule::new("", "anything")];
+    let error = dox::replace_in_document(&path, rules, ReplaceOptions::default())
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("index 0"));
+}
+
+#[test]
+fn test_extract_missing_file_fails() {
+    let dir = tempfile::tempdir().unwrap();
+    assert!(dox::extract_document(&dir.path().join("missing.docx"), ExtractFormat::Text).is_err());
+}